@@ -0,0 +1,170 @@
+//! benchmarks of the sync layer against a generated repository.
+//!
+//! the size of the repository scales with `ASYNCGIT_BENCH_SCALE`
+//! (default `1`), e.g.:
+//! `ASYNCGIT_BENCH_SCALE=10 cargo bench -p asyncgit`
+
+use asyncgit::sync::{
+    diff::get_diff,
+    get_head,
+    status::{get_status, StatusType},
+    tree_files, LogWalker, RepoPath,
+};
+use criterion::{criterion_group, criterion_main, Criterion};
+use git2::{Repository, Signature};
+use std::{env, fs, path::Path};
+use tempfile::TempDir;
+
+const BIG_FILE: &str = "big.txt";
+
+struct BenchRepo {
+    _td: TempDir,
+    repo: Repository,
+    path: RepoPath,
+}
+
+fn scale() -> usize {
+    env::var("ASYNCGIT_BENCH_SCALE")
+        .ok()
+        .and_then(|scale| scale.parse().ok())
+        .unwrap_or(1)
+}
+
+/// `dir/dir/.../file` paths spread over a tree of `depth` levels
+fn file_path(idx: usize, depth: usize) -> String {
+    let dirs: Vec<String> = (0..depth)
+        .map(|level| format!("dir{}", (idx >> level) % 4))
+        .collect();
+    format!("{}/file{}.txt", dirs.join("/"), idx)
+}
+
+fn write(root: &Path, file: &str, content: &str) {
+    let path = root.join(file);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+fn commit(repo: &Repository, files: &[String], msg: &str) {
+    let mut index = repo.index().unwrap();
+    for file in files {
+        index.add_path(Path::new(file)).unwrap();
+    }
+    index.write().unwrap();
+
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let sig = Signature::now("bench", "bench@example.com").unwrap();
+    let parent =
+        repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+    let parents: Vec<_> = parent.iter().collect();
+
+    repo.commit(Some("HEAD"), &sig, &sig, msg, &tree, &parents)
+        .unwrap();
+}
+
+/// repository with `1000 * scale` files in a deep tree, `500 *
+/// scale` commits, a big file and a dirty workdir
+fn bench_repo() -> BenchRepo {
+    let scale = scale();
+    let files = 1000 * scale;
+    let commits = 500 * scale;
+    let depth = 8;
+
+    let td = TempDir::new().unwrap();
+    let repo = Repository::init(td.path()).unwrap();
+    let root = td.path();
+
+    let all_files: Vec<String> =
+        (0..files).map(|idx| file_path(idx, depth)).collect();
+    for (idx, file) in all_files.iter().enumerate() {
+        write(root, file, &format!("{}\n", idx));
+    }
+
+    let big: Vec<String> = (0..20_000 * scale)
+        .map(|line| format!("line {}", line))
+        .collect();
+    write(root, BIG_FILE, &big.join("\n"));
+
+    let mut initial = all_files.clone();
+    initial.push(BIG_FILE.to_string());
+    commit(&repo, &initial, "initial");
+
+    for idx in 0..commits {
+        let file = &all_files[idx % files];
+        write(root, file, &format!("{}\ncommit {}\n", idx, idx));
+        commit(&repo, &[file.clone()], &format!("commit {}", idx));
+    }
+
+    // dirty workdir: every 10th file and line changed, some new files
+    for file in all_files.iter().step_by(10) {
+        write(root, file, "changed\n");
+    }
+    for idx in 0..files / 10 {
+        write(root, &format!("new/file{}.txt", idx), "new\n");
+    }
+    let changed: Vec<String> = big
+        .iter()
+        .enumerate()
+        .map(|(idx, line)| {
+            if idx % 10 == 0 {
+                format!("{} changed", line)
+            } else {
+                line.clone()
+            }
+        })
+        .collect();
+    write(root, BIG_FILE, &changed.join("\n"));
+
+    let path = root.to_str().unwrap().into();
+
+    BenchRepo {
+        _td: td,
+        repo,
+        path,
+    }
+}
+
+fn sync_benches(c: &mut Criterion) {
+    let bench = bench_repo();
+
+    let mut group = c.benchmark_group("sync");
+    group.sample_size(10);
+
+    group.bench_function("status_workdir", |b| {
+        b.iter(|| {
+            get_status(&bench.path, StatusType::WorkingDir, true)
+                .unwrap()
+        })
+    });
+
+    group.bench_function("status_stage", |b| {
+        b.iter(|| {
+            get_status(&bench.path, StatusType::Stage, true).unwrap()
+        })
+    });
+
+    group.bench_function("log_walk", |b| {
+        b.iter(|| {
+            // chunks like the async log fetches them
+            let mut walker = LogWalker::new(&bench.repo);
+            let mut ids = Vec::new();
+            while walker.read(&mut ids, 3000).unwrap() > 0 {}
+            ids
+        })
+    });
+
+    group.bench_function("diff_big_file", |b| {
+        b.iter(|| {
+            get_diff(&bench.path, BIG_FILE, false, None).unwrap()
+        })
+    });
+
+    let head = get_head(&bench.path).unwrap();
+    group.bench_function("tree_files", |b| {
+        b.iter(|| tree_files(&bench.path, head).unwrap())
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, sync_benches);
+criterion_main!(benches);
@@ -1,8 +1,8 @@
 use crate::{
     error::Result,
     hash,
-    sync::{self, FileBlame},
-    AsyncNotification, CWD,
+    sync::{self, BlameCopyDetection, FileBlame, RepoPath},
+    AsyncNotification,
 };
 use crossbeam_channel::Sender;
 use std::{
@@ -18,8 +18,13 @@ use std::{
 pub struct BlameParams {
     /// path to the file to blame
     pub file_path: String,
+    /// how hard to look for moved/copied lines
+    pub copy_detection: BlameCopyDetection,
 }
 
+/// amount of lines blamed before the first partial result is shown
+const FIRST_CHUNK_LINES: usize = 200;
+
 struct Request<R, A>(R, Option<A>);
 
 #[derive(Default, Clone)]
@@ -31,6 +36,7 @@ struct LastResult<P, R> {
 
 ///
 pub struct AsyncBlame {
+    repo: RepoPath,
     current: Arc<Mutex<Request<u64, FileBlame>>>,
     last: Arc<Mutex<Option<LastResult<BlameParams, FileBlame>>>>,
     sender: Sender<AsyncNotification>,
@@ -39,8 +45,12 @@ pub struct AsyncBlame {
 
 impl AsyncBlame {
     ///
-    pub fn new(sender: &Sender<AsyncNotification>) -> Self {
+    pub fn new(
+        repo: RepoPath,
+        sender: &Sender<AsyncNotification>,
+    ) -> Self {
         Self {
+            repo,
             current: Arc::new(Mutex::new(Request(0, None))),
             last: Arc::new(Mutex::new(None)),
             sender: sender.clone(),
@@ -97,15 +107,18 @@ impl AsyncBlame {
         let arc_last = Arc::clone(&self.last);
         let sender = self.sender.clone();
         let arc_pending = Arc::clone(&self.pending);
+        let repo = self.repo.clone();
 
         self.pending.fetch_add(1, Ordering::Relaxed);
 
         rayon_core::spawn(move || {
             let notify = Self::get_blame_helper(
+                &repo,
                 params,
                 &arc_last,
                 &arc_current,
                 hash,
+                &sender,
             );
 
             let notify = match notify {
@@ -131,15 +144,44 @@ impl AsyncBlame {
     }
 
     fn get_blame_helper(
+        repo_path: &RepoPath,
         params: BlameParams,
         arc_last: &Arc<
             Mutex<Option<LastResult<BlameParams, FileBlame>>>,
         >,
         arc_current: &Arc<Mutex<Request<u64, FileBlame>>>,
         hash: u64,
+        sender: &Sender<AsyncNotification>,
     ) -> Result<bool> {
-        let file_blame =
-            sync::blame::blame_file(CWD, &params.file_path)?;
+        let file_blame = sync::blame::blame_file_progressive(
+            repo_path,
+            &params.file_path,
+            params.copy_detection,
+            FIRST_CHUNK_LINES,
+            |partial| {
+                // publish partial result, stop if the request was
+                // cancelled or replaced in the meantime
+                if let Ok(mut current) = arc_current.lock() {
+                    if current.0 == hash {
+                        current.1 = Some(partial.clone());
+                        drop(current);
+
+                        sender
+                            .send(AsyncNotification::Blame)
+                            .expect("error sending blame");
+
+                        return true;
+                    }
+                }
+
+                false
+            },
+        )?;
+
+        if file_blame.is_partial() {
+            // cancelled
+            return Ok(false);
+        }
 
         let mut notify = false;
         {
@@ -162,6 +204,11 @@ impl AsyncBlame {
         Ok(notify)
     }
 
+    /// cancels a running blame, partial results are dropped
+    pub fn cancel(&mut self) -> Result<()> {
+        self.clear_current()
+    }
+
     fn get_last_param(&self) -> Result<Option<BlameParams>> {
         Ok(self
             .last
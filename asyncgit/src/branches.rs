@@ -0,0 +1,122 @@
+use crate::{
+    error::Result,
+    sync::{self, BranchInfo, RepoPath},
+    AsyncNotification,
+};
+use crossbeam_channel::Sender;
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc, Mutex,
+};
+
+/// loads `BranchInfo` (including the ahead/behind counts, which need
+/// a graph walk per branch) in the background. results are collected
+/// until `take`n, results of requests older than the last
+/// `invalidate` are dropped
+pub struct AsyncBranches {
+    repo: RepoPath,
+    generation: Arc<AtomicUsize>,
+    results: Arc<Mutex<Vec<BranchInfo>>>,
+    sender: Sender<AsyncNotification>,
+    pending: Arc<AtomicUsize>,
+}
+
+impl AsyncBranches {
+    ///
+    pub fn new(
+        repo: RepoPath,
+        sender: &Sender<AsyncNotification>,
+    ) -> Self {
+        Self {
+            repo,
+            generation: Arc::new(AtomicUsize::new(0)),
+            results: Arc::new(Mutex::new(Vec::new())),
+            sender: sender.clone(),
+            pending: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    ///
+    pub fn is_pending(&self) -> bool {
+        self.pending.load(Ordering::Relaxed) > 0
+    }
+
+    /// results that arrived since the last call
+    pub fn take(&mut self) -> Result<Vec<BranchInfo>> {
+        let mut results = self.results.lock()?;
+
+        Ok(std::mem::take(&mut *results))
+    }
+
+    /// drops all results not taken yet and all still running
+    /// requests, to be called whenever the branches changed
+    pub fn invalidate(&mut self) -> Result<()> {
+        let mut results = self.results.lock()?;
+
+        self.generation.fetch_add(1, Ordering::Relaxed);
+        results.clear();
+
+        Ok(())
+    }
+
+    /// loads the details of the local (or remote) branches `names`
+    pub fn request(&mut self, local: bool, names: Vec<String>) {
+        log::trace!("request");
+
+        let generation = self.generation.load(Ordering::Relaxed);
+        let arc_generation = Arc::clone(&self.generation);
+        let arc_results = Arc::clone(&self.results);
+        let sender = self.sender.clone();
+        let arc_pending = Arc::clone(&self.pending);
+        let repo = self.repo.clone();
+
+        self.pending.fetch_add(1, Ordering::Relaxed);
+
+        rayon_core::spawn(move || {
+            let notify = Self::getter(
+                &repo,
+                local,
+                &names,
+                generation,
+                &arc_generation,
+                &arc_results,
+            )
+            .unwrap_or_else(|e| {
+                log::error!("branches error: {}", e);
+                false
+            });
+
+            arc_pending.fetch_sub(1, Ordering::Relaxed);
+
+            sender
+                .send(if notify {
+                    AsyncNotification::Branches
+                } else {
+                    AsyncNotification::FinishUnchanged
+                })
+                .expect("error sending notify");
+        });
+    }
+
+    fn getter(
+        repo_path: &RepoPath,
+        local: bool,
+        names: &[String],
+        generation: usize,
+        arc_generation: &Arc<AtomicUsize>,
+        arc_results: &Arc<Mutex<Vec<BranchInfo>>>,
+    ) -> Result<bool> {
+        let branches =
+            sync::get_branches_info_for(repo_path, local, names)?;
+
+        let mut results = arc_results.lock()?;
+
+        if arc_generation.load(Ordering::Relaxed) != generation {
+            return Ok(false);
+        }
+
+        results.extend(branches);
+
+        Ok(true)
+    }
+}
@@ -1,7 +1,7 @@
 use crate::{
     error::Result,
-    sync::{self, CommitId},
-    AsyncNotification, StatusItem, CWD,
+    sync::{self, CommitId, RepoPath},
+    AsyncNotification, StatusItem,
 };
 use crossbeam_channel::Sender;
 use std::sync::{
@@ -14,6 +14,7 @@ struct Request<R, A>(R, A);
 
 ///
 pub struct AsyncCommitFiles {
+    repo: RepoPath,
     current: Arc<Mutex<Option<Request<CommitId, ResultType>>>>,
     sender: Sender<AsyncNotification>,
     pending: Arc<AtomicUsize>,
@@ -21,8 +22,12 @@ pub struct AsyncCommitFiles {
 
 impl AsyncCommitFiles {
     ///
-    pub fn new(sender: &Sender<AsyncNotification>) -> Self {
+    pub fn new(
+        repo: RepoPath,
+        sender: &Sender<AsyncNotification>,
+    ) -> Self {
         Self {
+            repo,
             current: Arc::new(Mutex::new(None)),
             sender: sender.clone(),
             pending: Arc::new(AtomicUsize::new(0)),
@@ -64,11 +69,12 @@ impl AsyncCommitFiles {
         let arc_current = Arc::clone(&self.current);
         let sender = self.sender.clone();
         let arc_pending = Arc::clone(&self.pending);
+        let repo = self.repo.clone();
 
         self.pending.fetch_add(1, Ordering::Relaxed);
 
         rayon_core::spawn(move || {
-            Self::fetch_helper(id, &arc_current)
+            Self::fetch_helper(&repo, id, &arc_current)
                 .expect("failed to fetch");
 
             arc_pending.fetch_sub(1, Ordering::Relaxed);
@@ -82,12 +88,13 @@ impl AsyncCommitFiles {
     }
 
     fn fetch_helper(
+        repo_path: &RepoPath,
         id: CommitId,
         arc_current: &Arc<
             Mutex<Option<Request<CommitId, ResultType>>>,
         >,
     ) -> Result<()> {
-        let res = sync::get_commit_files(CWD, id)?;
+        let res = sync::get_commit_files(repo_path, id)?;
 
         log::trace!(
             "get_commit_files: {} ({})",
@@ -1,8 +1,8 @@
 use crate::{
     error::Result,
     hash,
-    sync::{self, CommitId},
-    AsyncNotification, FileDiff, CWD,
+    sync::{self, CommitId, DiffOptions, RepoPath},
+    AsyncNotification, FileDiff,
 };
 use crossbeam_channel::Sender;
 use std::{
@@ -22,6 +22,11 @@ pub enum DiffType {
     Stage,
     /// diff against file in workdir
     WorkDir,
+    /// diff of the workdir (including staged changes) against the
+    /// tree of a commit
+    Base(CommitId),
+    /// diff between the trees of two commits (from, to)
+    Commits(CommitId, CommitId),
 }
 
 ///
@@ -31,6 +36,8 @@ pub struct DiffParams {
     pub path: String,
     /// what kind of diff
     pub diff_type: DiffType,
+    /// diff options
+    pub options: DiffOptions,
 }
 
 struct Request<R, A>(R, Option<A>);
@@ -44,6 +51,7 @@ struct LastResult<P, R> {
 
 ///
 pub struct AsyncDiff {
+    repo: RepoPath,
     current: Arc<Mutex<Request<u64, FileDiff>>>,
     last: Arc<Mutex<Option<LastResult<DiffParams, FileDiff>>>>,
     sender: Sender<AsyncNotification>,
@@ -52,8 +60,12 @@ pub struct AsyncDiff {
 
 impl AsyncDiff {
     ///
-    pub fn new(sender: &Sender<AsyncNotification>) -> Self {
+    pub fn new(
+        repo: RepoPath,
+        sender: &Sender<AsyncNotification>,
+    ) -> Self {
         Self {
+            repo,
             current: Arc::new(Mutex::new(Request(0, None))),
             last: Arc::new(Mutex::new(None)),
             sender: sender.clone(),
@@ -106,11 +118,13 @@ impl AsyncDiff {
         let arc_last = Arc::clone(&self.last);
         let sender = self.sender.clone();
         let arc_pending = Arc::clone(&self.pending);
+        let repo = self.repo.clone();
 
         self.pending.fetch_add(1, Ordering::Relaxed);
 
         rayon_core::spawn(move || {
             let notify = Self::get_diff_helper(
+                &repo,
                 params,
                 &arc_last,
                 &arc_current,
@@ -140,6 +154,7 @@ impl AsyncDiff {
     }
 
     fn get_diff_helper(
+        repo_path: &RepoPath,
         params: DiffParams,
         arc_last: &Arc<
             Mutex<Option<LastResult<DiffParams, FileDiff>>>,
@@ -148,16 +163,38 @@ impl AsyncDiff {
         hash: u64,
     ) -> Result<bool> {
         let res = match params.diff_type {
-            DiffType::Stage => {
-                sync::diff::get_diff(CWD, &params.path, true)?
-            }
-            DiffType::WorkDir => {
-                sync::diff::get_diff(CWD, &params.path, false)?
+            DiffType::Stage => sync::diff::get_diff(
+                repo_path,
+                &params.path,
+                true,
+                Some(params.options),
+            )?,
+            DiffType::WorkDir => sync::diff::get_diff(
+                repo_path,
+                &params.path,
+                false,
+                Some(params.options),
+            )?,
+            DiffType::Base(id) => sync::diff::get_diff_base(
+                repo_path,
+                id,
+                &params.path,
+                Some(params.options),
+            )?,
+            DiffType::Commits(from, to) => {
+                sync::diff::get_diff_commits(
+                    repo_path,
+                    from,
+                    to,
+                    params.path.clone(),
+                    Some(params.options),
+                )?
             }
             DiffType::Commit(id) => sync::diff::get_diff_commit(
-                CWD,
+                repo_path,
                 id,
                 params.path.clone(),
+                Some(params.options),
             )?,
         };
 
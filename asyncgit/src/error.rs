@@ -24,6 +24,9 @@ pub enum Error {
     #[error("git: can\u{2019}t run blame on a binary file")]
     NoBlameOnBinaryFile,
 
+    #[error("another gitui instance is changing the repository, try again")]
+    InstanceLocked,
+
     #[error("io error:{0}")]
     Io(#[from] std::io::Error),
 
@@ -38,6 +41,9 @@ pub enum Error {
 
     #[error("EasyCast error:{0}")]
     EasyCast(#[from] easy_cast::Error),
+
+    #[error("zip error:{0}")]
+    Zip(#[from] zip::result::ZipError),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -1,10 +1,14 @@
 use crate::{
     error::{Error, Result},
+    network,
     sync::{
         cred::BasicAuthCredential,
-        remotes::{fetch, push::ProgressNotification},
+        remotes::{
+            fetch, fetch_all, push::ProgressNotification, FetchDepth,
+        },
+        RepoPath,
     },
-    AsyncNotification, RemoteProgress, CWD,
+    AsyncNotification, RemoteProgress,
 };
 use crossbeam_channel::{unbounded, Sender};
 use std::{
@@ -19,6 +23,10 @@ pub struct FetchRequest {
     pub remote: String,
     ///
     pub branch: String,
+    /// fetch all branches of `remote` instead of only `branch`
+    pub all_branches: bool,
+    /// how much history of `branch` to get
+    pub depth: FetchDepth,
     ///
     pub basic_credential: Option<BasicAuthCredential>,
 }
@@ -30,6 +38,7 @@ struct FetchState {
 
 ///
 pub struct AsyncFetch {
+    repo: RepoPath,
     state: Arc<Mutex<Option<FetchState>>>,
     last_result: Arc<Mutex<Option<(usize, String)>>>,
     progress: Arc<Mutex<Option<ProgressNotification>>>,
@@ -38,8 +47,12 @@ pub struct AsyncFetch {
 
 impl AsyncFetch {
     ///
-    pub fn new(sender: &Sender<AsyncNotification>) -> Self {
+    pub fn new(
+        repo: RepoPath,
+        sender: &Sender<AsyncNotification>,
+    ) -> Self {
         Self {
+            repo,
             state: Arc::new(Mutex::new(None)),
             last_result: Arc::new(Mutex::new(None)),
             progress: Arc::new(Mutex::new(None)),
@@ -80,6 +93,7 @@ impl AsyncFetch {
         let arc_res = Arc::clone(&self.last_result);
         let arc_progress = Arc::clone(&self.progress);
         let sender = self.sender.clone();
+        let repo = self.repo.clone();
 
         thread::spawn(move || {
             let (progress_sender, receiver) = unbounded();
@@ -91,12 +105,27 @@ impl AsyncFetch {
                 arc_progress,
             );
 
-            let res = fetch(
-                CWD,
-                &params.branch,
-                params.basic_credential,
-                Some(progress_sender.clone()),
-            );
+            let slot =
+                network::wait_for_turn("fetch", &params.remote);
+
+            let res = if params.all_branches {
+                fetch_all(
+                    &repo,
+                    &params.remote,
+                    params.basic_credential,
+                    Some(progress_sender.clone()),
+                )
+            } else {
+                fetch(
+                    &repo,
+                    &params.branch,
+                    params.depth,
+                    params.basic_credential,
+                    Some(progress_sender.clone()),
+                )
+            };
+
+            drop(slot);
 
             progress_sender
                 .send(ProgressNotification::Done)
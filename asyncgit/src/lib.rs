@@ -19,11 +19,13 @@
 // #![deny(clippy::expect_used)]
 
 mod blame;
+mod branches;
 pub mod cached;
 mod commit_files;
 mod diff;
 mod error;
 mod fetch;
+mod network;
 mod progress;
 mod push;
 mod push_tags;
@@ -35,12 +37,19 @@ mod tags;
 
 pub use crate::{
     blame::{AsyncBlame, BlameParams},
+    branches::AsyncBranches,
     commit_files::AsyncCommitFiles,
     diff::{AsyncDiff, DiffParams, DiffType},
     fetch::{AsyncFetch, FetchRequest},
+    network::{
+        network_operations, set_network_limits, NetworkLimits,
+        NetworkOperation,
+    },
     push::{AsyncPush, PushRequest},
     push_tags::{AsyncPushTags, PushTagsRequest},
-    remote_progress::{RemoteProgress, RemoteProgressState},
+    remote_progress::{
+        RemoteProgress, RemoteProgressState, TransferStats,
+    },
     revlog::{AsyncLog, FetchStatus},
     status::{AsyncStatus, StatusParams},
     sync::{
@@ -77,6 +86,10 @@ pub enum AsyncNotification {
     Fetch,
     ///
     Blame,
+    ///
+    Branches,
+    /// syntax highlighting done by the ui in the background
+    SyntaxHighlighting,
 }
 
 /// current working directory `./`
@@ -0,0 +1,274 @@
+//! limits how many network operations (fetch, push, push tags) run
+//! at the same time, in total and per remote. the others wait in a
+//! queue that can be shown to the user
+
+use once_cell::sync::Lazy;
+use std::{
+    collections::HashMap,
+    sync::{Condvar, Mutex, MutexGuard, PoisonError},
+    time::{Duration, Instant},
+};
+
+/// how many network operations may run at once
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct NetworkLimits {
+    /// in total, `0` means unlimited
+    pub max_concurrent: usize,
+    /// on the same remote, `0` means unlimited
+    pub max_per_remote: usize,
+    /// least time between the starts of two operations on the same
+    /// remote
+    pub remote_interval: Duration,
+}
+
+impl Default for NetworkLimits {
+    fn default() -> Self {
+        Self {
+            max_concurrent: 4,
+            max_per_remote: 1,
+            remote_interval: Duration::from_secs(0),
+        }
+    }
+}
+
+/// a network operation waiting for its turn or running
+#[derive(Clone, Debug, PartialEq)]
+pub struct NetworkOperation {
+    /// like `fetch` or `push`
+    pub kind: &'static str,
+    ///
+    pub remote: String,
+    /// `false` while it waits in the queue
+    pub running: bool,
+    /// when it was queued or started
+    pub since: Instant,
+}
+
+#[derive(Debug, PartialEq)]
+enum Turn {
+    Now,
+    /// until another operation finishes
+    Wait,
+    /// for the interval of the remote to pass
+    WaitFor(Duration),
+}
+
+#[derive(Default)]
+struct Limiter {
+    limits: NetworkLimits,
+    next_id: u64,
+    /// in the order they were requested
+    operations: Vec<(u64, NetworkOperation)>,
+    /// when the last operation on each remote started
+    last_start: HashMap<String, Instant>,
+}
+
+impl Limiter {
+    fn queue(&mut self, kind: &'static str, remote: &str) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        self.operations.push((
+            id,
+            NetworkOperation {
+                kind,
+                remote: remote.to_string(),
+                running: false,
+                since: Instant::now(),
+            },
+        ));
+
+        id
+    }
+
+    /// earlier requests on the same remote go first
+    fn turn(&self, id: u64) -> Turn {
+        let op = match self.operations.iter().find(|(i, _)| *i == id)
+        {
+            Some((_, op)) => op,
+            None => return Turn::Now,
+        };
+
+        let running = self
+            .operations
+            .iter()
+            .filter(|(_, other)| other.running)
+            .count();
+        let running_on_remote = self
+            .operations
+            .iter()
+            .filter(|(_, other)| {
+                other.running && other.remote == op.remote
+            })
+            .count();
+        let earlier_waiting =
+            self.operations.iter().take_while(|(i, _)| *i != id).any(
+                |(_, other)| {
+                    !other.running && other.remote == op.remote
+                },
+            );
+
+        if earlier_waiting
+            || (self.limits.max_concurrent > 0
+                && running >= self.limits.max_concurrent)
+            || (self.limits.max_per_remote > 0
+                && running_on_remote >= self.limits.max_per_remote)
+        {
+            return Turn::Wait;
+        }
+
+        if let Some(last) = self.last_start.get(&op.remote) {
+            let elapsed = last.elapsed();
+            if elapsed < self.limits.remote_interval {
+                return Turn::WaitFor(
+                    self.limits.remote_interval - elapsed,
+                );
+            }
+        }
+
+        Turn::Now
+    }
+
+    fn start(&mut self, id: u64) {
+        let now = Instant::now();
+
+        if let Some((_, op)) =
+            self.operations.iter_mut().find(|(i, _)| *i == id)
+        {
+            op.running = true;
+            op.since = now;
+            self.last_start.insert(op.remote.clone(), now);
+        }
+    }
+
+    fn finish(&mut self, id: u64) {
+        self.operations.retain(|(i, _)| *i != id);
+    }
+}
+
+static LIMITER: Lazy<(Mutex<Limiter>, Condvar)> =
+    Lazy::new(Default::default);
+
+fn limiter() -> MutexGuard<'static, Limiter> {
+    LIMITER.0.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// applies to all repositories of this process, waiting operations
+/// are checked against the new limits right away
+pub fn set_network_limits(limits: NetworkLimits) {
+    limiter().limits = limits;
+    LIMITER.1.notify_all();
+}
+
+/// the queued and running network operations in the order they were
+/// requested
+pub fn network_operations() -> Vec<NetworkOperation> {
+    limiter()
+        .operations
+        .iter()
+        .map(|(_, op)| op.clone())
+        .collect()
+}
+
+/// the turn of a running operation, the next one may start once
+/// this is dropped
+pub struct NetworkSlot {
+    id: u64,
+}
+
+impl Drop for NetworkSlot {
+    fn drop(&mut self) {
+        limiter().finish(self.id);
+        LIMITER.1.notify_all();
+    }
+}
+
+/// blocks until the limits allow `kind` to run on `remote`
+pub fn wait_for_turn(
+    kind: &'static str,
+    remote: &str,
+) -> NetworkSlot {
+    let (_, condvar) = &*LIMITER;
+    let mut state = limiter();
+    let id = state.queue(kind, remote);
+
+    loop {
+        state = match state.turn(id) {
+            Turn::Now => break,
+            Turn::Wait => condvar
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner),
+            Turn::WaitFor(time) => {
+                condvar.wait_timeout(state, time).map_or_else(
+                    |e| e.into_inner().0,
+                    |(guard, _)| guard,
+                )
+            }
+        };
+    }
+
+    state.start(id);
+    drop(state);
+    condvar.notify_all();
+
+    NetworkSlot { id }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_turns() {
+        let mut limiter = Limiter {
+            limits: NetworkLimits {
+                max_concurrent: 2,
+                max_per_remote: 1,
+                remote_interval: Duration::from_secs(0),
+            },
+            ..Limiter::default()
+        };
+
+        let a = limiter.queue("fetch", "origin");
+        let b = limiter.queue("push", "origin");
+        let c = limiter.queue("fetch", "upstream");
+        let d = limiter.queue("fetch", "fork");
+
+        // `b` waits for the earlier `a` on the same remote
+        assert_eq!(limiter.turn(a), Turn::Now);
+        assert_eq!(limiter.turn(b), Turn::Wait);
+
+        limiter.start(a);
+        assert_eq!(limiter.turn(b), Turn::Wait);
+        assert_eq!(limiter.turn(c), Turn::Now);
+
+        limiter.start(c);
+        assert_eq!(limiter.turn(d), Turn::Wait);
+
+        limiter.finish(a);
+        assert_eq!(limiter.turn(b), Turn::Now);
+        assert_eq!(limiter.operations.len(), 3);
+    }
+
+    #[test]
+    fn test_remote_interval() {
+        let mut limiter = Limiter {
+            limits: NetworkLimits {
+                max_concurrent: 0,
+                max_per_remote: 0,
+                remote_interval: Duration::from_secs(60),
+            },
+            ..Limiter::default()
+        };
+
+        let a = limiter.queue("fetch", "origin");
+        limiter.start(a);
+        limiter.finish(a);
+
+        let b = limiter.queue("fetch", "origin");
+        assert!(matches!(limiter.turn(b), Turn::WaitFor(_)));
+
+        let c = limiter.queue("fetch", "upstream");
+        assert_eq!(limiter.turn(c), Turn::Now);
+    }
+}
@@ -1,10 +1,11 @@
 use crate::{
     error::{Error, Result},
+    network,
     sync::{
         cred::BasicAuthCredential, remotes::push::push,
-        remotes::push::ProgressNotification,
+        remotes::push::ProgressNotification, RepoPath,
     },
-    AsyncNotification, RemoteProgress, CWD,
+    AsyncNotification, RemoteProgress,
 };
 use crossbeam_channel::{unbounded, Sender};
 use std::{
@@ -32,6 +33,7 @@ struct PushState {
 
 ///
 pub struct AsyncPush {
+    repo: RepoPath,
     state: Arc<Mutex<Option<PushState>>>,
     last_result: Arc<Mutex<Option<String>>>,
     progress: Arc<Mutex<Option<ProgressNotification>>>,
@@ -40,8 +42,12 @@ pub struct AsyncPush {
 
 impl AsyncPush {
     ///
-    pub fn new(sender: &Sender<AsyncNotification>) -> Self {
+    pub fn new(
+        repo: RepoPath,
+        sender: &Sender<AsyncNotification>,
+    ) -> Self {
         Self {
+            repo,
             state: Arc::new(Mutex::new(None)),
             last_result: Arc::new(Mutex::new(None)),
             progress: Arc::new(Mutex::new(None)),
@@ -82,6 +88,7 @@ impl AsyncPush {
         let arc_res = Arc::clone(&self.last_result);
         let arc_progress = Arc::clone(&self.progress);
         let sender = self.sender.clone();
+        let repo = self.repo.clone();
 
         thread::spawn(move || {
             let (progress_sender, receiver) = unbounded();
@@ -93,8 +100,10 @@ impl AsyncPush {
                 arc_progress,
             );
 
+            let slot = network::wait_for_turn("push", &params.remote);
+
             let res = push(
-                CWD,
+                &repo,
                 params.remote.as_str(),
                 params.branch.as_str(),
                 params.force,
@@ -102,6 +111,8 @@ impl AsyncPush {
                 Some(progress_sender.clone()),
             );
 
+            drop(slot);
+
             progress_sender
                 .send(ProgressNotification::Done)
                 .expect("closing send failed");
@@ -1,10 +1,12 @@
 use crate::{
     error::{Error, Result},
+    network,
     sync::{
         cred::BasicAuthCredential,
         remotes::tags::{push_tags, PushTagsProgress},
+        RepoPath,
     },
-    AsyncNotification, RemoteProgress, CWD,
+    AsyncNotification, RemoteProgress,
 };
 use crossbeam_channel::{unbounded, Sender};
 use std::{
@@ -17,6 +19,10 @@ use std::{
 pub struct PushTagsRequest {
     ///
     pub remote: String,
+    /// names of the tags to push, all missing on the remote if empty
+    pub tags: Vec<String>,
+    /// replaces the remote's tags of the same name
+    pub force: bool,
     ///
     pub basic_credential: Option<BasicAuthCredential>,
 }
@@ -28,6 +34,7 @@ struct PushState {
 
 ///
 pub struct AsyncPushTags {
+    repo: RepoPath,
     state: Arc<Mutex<Option<PushState>>>,
     last_result: Arc<Mutex<Option<String>>>,
     progress: Arc<Mutex<Option<PushTagsProgress>>>,
@@ -36,8 +43,12 @@ pub struct AsyncPushTags {
 
 impl AsyncPushTags {
     ///
-    pub fn new(sender: &Sender<AsyncNotification>) -> Self {
+    pub fn new(
+        repo: RepoPath,
+        sender: &Sender<AsyncNotification>,
+    ) -> Self {
         Self {
+            repo,
             state: Arc::new(Mutex::new(None)),
             last_result: Arc::new(Mutex::new(None)),
             progress: Arc::new(Mutex::new(None)),
@@ -78,6 +89,7 @@ impl AsyncPushTags {
         let arc_res = Arc::clone(&self.last_result);
         let arc_progress = Arc::clone(&self.progress);
         let sender = self.sender.clone();
+        let repo = self.repo.clone();
 
         thread::spawn(move || {
             let (progress_sender, receiver) = unbounded();
@@ -89,13 +101,20 @@ impl AsyncPushTags {
                 arc_progress,
             );
 
+            let slot =
+                network::wait_for_turn("push tags", &params.remote);
+
             let res = push_tags(
-                CWD,
+                &repo,
                 params.remote.as_str(),
+                &params.tags,
+                params.force,
                 params.basic_credential.clone(),
                 Some(progress_sender),
             );
 
+            drop(slot);
+
             handle.join().expect("joining thread failed");
 
             Self::set_result(&arc_res, res).expect("result error");
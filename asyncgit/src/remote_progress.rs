@@ -25,10 +25,21 @@ pub enum RemoteProgressState {
     Pushing,
     /// fetch progress
     Transfer,
+    /// fetch received all objects
+    ResolvingDeltas,
     /// remote progress done
     Done,
 }
 
+/// amount of data sent or received so far
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TransferStats {
+    ///
+    pub bytes: usize,
+    /// average since the transfer started
+    pub bytes_per_sec: usize,
+}
+
 ///
 #[derive(Clone, Debug)]
 pub struct RemoteProgress {
@@ -36,6 +47,8 @@ pub struct RemoteProgress {
     pub state: RemoteProgressState,
     ///
     pub progress: ProgressPercent,
+    /// only known while pushing or fetching objects
+    pub transfer: Option<TransferStats>,
 }
 
 impl RemoteProgress {
@@ -48,9 +61,23 @@ impl RemoteProgress {
         Self {
             state,
             progress: ProgressPercent::new(current, total),
+            transfer: None,
         }
     }
 
+    ///
+    pub const fn with_transfer(
+        mut self,
+        bytes: usize,
+        bytes_per_sec: usize,
+    ) -> Self {
+        self.transfer = Some(TransferStats {
+            bytes,
+            bytes_per_sec,
+        });
+        self
+    }
+
     ///
     pub const fn get_progress_percent(&self) -> u8 {
         self.progress.progress
@@ -130,22 +157,76 @@ impl From<ProgressNotification> for RemoteProgress {
             ProgressNotification::PushTransfer {
                 current,
                 total,
-                ..
+                bytes,
+                bytes_per_sec,
             } => Self::new(
                 RemoteProgressState::Pushing,
                 current,
                 total,
-            ),
+            )
+            .with_transfer(bytes, bytes_per_sec),
             ProgressNotification::Transfer {
                 objects,
                 total_objects,
-                ..
-            } => Self::new(
-                RemoteProgressState::Transfer,
-                objects,
-                total_objects,
-            ),
+                indexed_deltas,
+                total_deltas,
+                bytes,
+                bytes_per_sec,
+            } => if objects == total_objects && total_deltas > 0 {
+                Self::new(
+                    RemoteProgressState::ResolvingDeltas,
+                    indexed_deltas,
+                    total_deltas,
+                )
+            } else {
+                Self::new(
+                    RemoteProgressState::Transfer,
+                    objects,
+                    total_objects,
+                )
+            }
+            .with_transfer(bytes, bytes_per_sec),
             _ => Self::new(RemoteProgressState::Done, 1, 1),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transfer_resolving_deltas() {
+        let transfer = |objects, indexed_deltas| {
+            RemoteProgress::from(ProgressNotification::Transfer {
+                objects,
+                total_objects: 10,
+                indexed_deltas,
+                total_deltas: 4,
+                bytes: 2048,
+                bytes_per_sec: 1024,
+            })
+        };
+
+        let progress = transfer(5, 0);
+        assert!(matches!(
+            progress.state,
+            RemoteProgressState::Transfer
+        ));
+        assert_eq!(progress.get_progress_percent(), 50);
+        assert_eq!(
+            progress.transfer,
+            Some(TransferStats {
+                bytes: 2048,
+                bytes_per_sec: 1024
+            })
+        );
+
+        let progress = transfer(10, 1);
+        assert!(matches!(
+            progress.state,
+            RemoteProgressState::ResolvingDeltas
+        ));
+        assert_eq!(progress.get_progress_percent(), 25);
+    }
+}
@@ -1,10 +1,9 @@
 use crate::{
     error::Result,
-    sync::{utils::repo, CommitId, LogWalker},
-    AsyncNotification, CWD,
+    sync::{utils::repo, CommitId, LogFilter, LogWalker, RepoPath},
+    AsyncNotification,
 };
 use crossbeam_channel::Sender;
-use git2::Oid;
 use scopetime::scope_time;
 use std::{
     sync::{
@@ -28,7 +27,11 @@ pub enum FetchStatus {
 
 ///
 pub struct AsyncLog {
+    repo: RepoPath,
     current: Arc<Mutex<Vec<CommitId>>>,
+    /// `HEAD` when `current` was walked
+    walked_head: Option<CommitId>,
+    filter: LogFilter,
     sender: Sender<AsyncNotification>,
     pending: Arc<AtomicBool>,
     background: Arc<AtomicBool>,
@@ -40,9 +43,15 @@ static SLEEP_BACKGROUND: Duration = Duration::from_millis(1000);
 
 impl AsyncLog {
     ///
-    pub fn new(sender: &Sender<AsyncNotification>) -> Self {
+    pub fn new(
+        repo: RepoPath,
+        sender: &Sender<AsyncNotification>,
+    ) -> Self {
         Self {
+            repo,
             current: Arc::new(Mutex::new(Vec::new())),
+            walked_head: None,
+            filter: LogFilter::default(),
             sender: sender.clone(),
             pending: Arc::new(AtomicBool::new(false)),
             background: Arc::new(AtomicBool::new(false)),
@@ -68,6 +77,11 @@ impl AsyncLog {
         Ok(list[min..max].to_vec())
     }
 
+    /// index of the commit among the ones walked so far
+    pub fn position(&self, id: CommitId) -> Result<Option<usize>> {
+        Ok(self.current.lock()?.iter().position(|c| *c == id))
+    }
+
     ///
     pub fn is_pending(&self) -> bool {
         self.pending.load(Ordering::Relaxed)
@@ -78,23 +92,28 @@ impl AsyncLog {
         self.background.store(true, Ordering::Relaxed)
     }
 
-    ///
-    fn current_head(&self) -> Result<CommitId> {
-        Ok(self
-            .current
-            .lock()?
-            .first()
-            .map_or(Oid::zero().into(), |f| *f))
+    /// walks the log again, keeping only the commits matching
+    /// `filter`
+    pub fn set_filter(&mut self, filter: LogFilter) {
+        if filter != self.filter {
+            self.filter = filter;
+            self.clear();
+        }
     }
 
-    ///
-    fn head_changed(&self) -> Result<bool> {
-        if let Ok(head) = repo(CWD)?.head() {
-            if let Some(head) = head.target() {
-                return Ok(head != self.current_head()?.into());
-            }
+    /// `HEAD` or the revision the filter walks instead
+    fn head(&self) -> Result<Option<CommitId>> {
+        let repo = repo(&self.repo)?;
+
+        if self.filter.rev.is_some() {
+            return Ok(self.filter.start(&repo)?.map(CommitId::new));
         }
-        Ok(false)
+
+        Ok(repo
+            .head()
+            .ok()
+            .and_then(|head| head.target())
+            .map(CommitId::new))
     }
 
     ///
@@ -105,16 +124,20 @@ impl AsyncLog {
             return Ok(FetchStatus::Pending);
         }
 
-        if !self.head_changed()? {
+        let head = self.head()?;
+        if head.is_none() || head == self.walked_head {
             return Ok(FetchStatus::NoChange);
         }
 
-        self.clear()?;
+        self.clear();
+        self.walked_head = head;
 
         let arc_current = Arc::clone(&self.current);
+        let filter = self.filter.clone();
         let sender = self.sender.clone();
         let arc_pending = Arc::clone(&self.pending);
         let arc_background = Arc::clone(&self.background);
+        let repo = self.repo.clone();
 
         self.pending.store(true, Ordering::Relaxed);
 
@@ -122,6 +145,8 @@ impl AsyncLog {
             scope_time!("async::revlog");
 
             Self::fetch_helper(
+                &repo,
+                filter,
                 &arc_current,
                 &arc_background,
                 &sender,
@@ -137,24 +162,31 @@ impl AsyncLog {
     }
 
     fn fetch_helper(
+        repo_path: &RepoPath,
+        filter: LogFilter,
         arc_current: &Arc<Mutex<Vec<CommitId>>>,
         arc_background: &Arc<AtomicBool>,
         sender: &Sender<AsyncNotification>,
     ) -> Result<()> {
         let mut entries = Vec::with_capacity(LIMIT_COUNT);
-        let r = repo(CWD)?;
-        let mut walker = LogWalker::new(&r);
+        let r = repo(repo_path)?;
+        let mut walker = LogWalker::new(&r).with_filter(filter);
         loop {
+            // `clear` replaced the list, nobody waits for this walk
+            if Arc::strong_count(arc_current) == 1 {
+                break;
+            }
+
             entries.clear();
-            let res_is_err =
-                walker.read(&mut entries, LIMIT_COUNT).is_err();
+            let walked = walker.read(&mut entries, LIMIT_COUNT).ok();
 
-            if !res_is_err {
+            if walked.is_some() {
                 let mut current = arc_current.lock()?;
                 current.extend(entries.iter());
             }
 
-            if res_is_err || entries.len() <= 1 {
+            // a filtered batch may have no matches before the end
+            if walked.unwrap_or_default() <= 1 {
                 break;
             }
             Self::notify(sender);
@@ -171,9 +203,16 @@ impl AsyncLog {
         Ok(())
     }
 
-    fn clear(&mut self) -> Result<()> {
-        self.current.lock()?.clear();
-        Ok(())
+    /// the next `fetch` walks the log again, even if `HEAD` did not
+    /// change
+    pub fn invalidate(&mut self) {
+        self.clear();
+    }
+
+    fn clear(&mut self) {
+        // a walk still running keeps filling the old list
+        self.current = Arc::new(Mutex::new(Vec::new()));
+        self.walked_head = None;
     }
 
     fn notify(sender: &Sender<AsyncNotification>) {
@@ -1,8 +1,8 @@
 use crate::{
     error::Result,
     hash,
-    sync::{self, status::StatusType},
-    AsyncNotification, StatusItem, CWD,
+    sync::{self, status::StatusType, RepoPath},
+    AsyncNotification, StatusItem,
 };
 use crossbeam_channel::Sender;
 use std::{
@@ -27,11 +27,13 @@ pub struct Status {
 }
 
 ///
-#[derive(Default, Hash, Copy, Clone, PartialEq)]
+#[derive(Default, Hash, Clone, PartialEq)]
 pub struct StatusParams {
     tick: u128,
     status_type: StatusType,
     include_untracked: bool,
+    scope: Option<String>,
+    shallow: bool,
 }
 
 impl StatusParams {
@@ -44,14 +46,30 @@ impl StatusParams {
             tick: current_tick(),
             status_type,
             include_untracked,
+            scope: None,
+            shallow: false,
         }
     }
+
+    /// only the files inside of this folder (relative to the workdir)
+    pub fn with_scope(mut self, scope: Option<String>) -> Self {
+        self.scope = scope;
+        self
+    }
+
+    /// only tells which top-level folders have changes (see
+    /// `get_status_shallow`), unless a scope or a base is set
+    pub fn with_shallow(mut self, shallow: bool) -> Self {
+        self.shallow = shallow;
+        self
+    }
 }
 
 struct Request<R, A>(R, Option<A>);
 
 ///
 pub struct AsyncStatus {
+    repo: RepoPath,
     current: Arc<Mutex<Request<u64, Status>>>,
     last: Arc<Mutex<Status>>,
     sender: Sender<AsyncNotification>,
@@ -60,8 +78,12 @@ pub struct AsyncStatus {
 
 impl AsyncStatus {
     ///
-    pub fn new(sender: Sender<AsyncNotification>) -> Self {
+    pub fn new(
+        repo: RepoPath,
+        sender: Sender<AsyncNotification>,
+    ) -> Self {
         Self {
+            repo,
             current: Arc::new(Mutex::new(Request(0, None))),
             last: Arc::new(Mutex::new(Status::default())),
             sender,
@@ -114,15 +136,15 @@ impl AsyncStatus {
         let arc_last = Arc::clone(&self.last);
         let sender = self.sender.clone();
         let arc_pending = Arc::clone(&self.pending);
-        let status_type = params.status_type;
-        let include_untracked = params.include_untracked;
+        let params = params.clone();
+        let repo = self.repo.clone();
 
         self.pending.fetch_add(1, Ordering::Relaxed);
 
         rayon_core::spawn(move || {
             let ok = Self::fetch_helper(
-                status_type,
-                include_untracked,
+                &repo,
+                &params,
                 hash_request,
                 &arc_current,
                 &arc_last,
@@ -142,18 +164,18 @@ impl AsyncStatus {
     }
 
     fn fetch_helper(
-        status_type: StatusType,
-        include_untracked: bool,
+        repo_path: &RepoPath,
+        params: &StatusParams,
         hash_request: u64,
         arc_current: &Arc<Mutex<Request<u64, Status>>>,
         arc_last: &Arc<Mutex<Status>>,
     ) -> Result<()> {
-        let res = Self::get_status(status_type, include_untracked)?;
+        let res = Self::get_status(repo_path, params)?;
         log::trace!(
             "status fetched: {} (type: {:?}, untracked: {})",
             hash_request,
-            status_type,
-            include_untracked
+            params.status_type,
+            params.include_untracked
         );
 
         {
@@ -172,14 +194,24 @@ impl AsyncStatus {
     }
 
     fn get_status(
-        status_type: StatusType,
-        include_untracked: bool,
+        repo_path: &RepoPath,
+        params: &StatusParams,
     ) -> Result<Status> {
+        if params.shallow
+            && params.scope.is_none()
+            && params.status_type == StatusType::WorkingDir
+        {
+            return Ok(Status {
+                items: sync::status::get_status_shallow(repo_path)?,
+            });
+        }
+
         Ok(Status {
-            items: sync::status::get_status(
-                CWD,
-                status_type,
-                include_untracked,
+            items: sync::status::get_status_scoped(
+                repo_path,
+                params.status_type,
+                params.include_untracked,
+                params.scope.as_deref(),
             )?,
         })
     }
@@ -0,0 +1,281 @@
+//! applying patches like `git apply -3`
+
+use super::{
+    utils::{repo, work_dir},
+    InstanceLock, RepoPath,
+};
+use crate::error::{Error, Result};
+use git2::{
+    build::CheckoutBuilder, ApplyLocation, Diff, FileMode, Index,
+    IndexEntry, IndexTime, Oid, Repository,
+};
+use scopetime::scope_time;
+use std::path::{Path, PathBuf};
+
+/// applies `patch` (a diff or a `git format-patch` mail) to the
+/// working tree, and to the index as well if `to_index`.
+/// if it does not apply cleanly the blobs it was made from are merged
+/// with the working tree instead, the index is only updated if that
+/// merge has no conflicts. returns the paths left with conflict
+/// markers
+pub fn apply_patch(
+    repo_path: &RepoPath,
+    patch: &str,
+    to_index: bool,
+) -> Result<Vec<String>> {
+    scope_time!("apply_patch");
+
+    let _lock = InstanceLock::acquire(repo_path)?;
+
+    let repo = repo(repo_path)?;
+    let diff = Diff::from_buffer(patch.as_bytes())?;
+
+    if diff.deltas().next().is_none() {
+        return Err(Error::Generic(
+            "no changes in patch".to_string(),
+        ));
+    }
+
+    let location = if to_index {
+        ApplyLocation::Both
+    } else {
+        ApplyLocation::WorkDir
+    };
+
+    if let Err(e) = repo.apply(&diff, location, None) {
+        log::info!("patch does not apply, trying 3-way merge: {}", e);
+
+        return apply_three_way(&repo, &diff, to_index);
+    }
+
+    Ok(Vec::new())
+}
+
+fn apply_three_way(
+    repo: &Repository,
+    diff: &Diff,
+    to_index: bool,
+) -> Result<Vec<String>> {
+    let work_dir = work_dir(repo)?;
+
+    let mut base = Index::new()?;
+    let mut ours = Index::new()?;
+    let mut paths: Vec<PathBuf> = Vec::new();
+
+    for delta in diff.deltas() {
+        let old = delta.old_file();
+        let mode = entry_mode(old.mode());
+
+        if let Some(path) = old.path() {
+            if !old.id().is_zero() {
+                let id = find_blob_id(repo, old.id(), path)?;
+                base.add(&blob_entry(path, id, mode)?)?;
+            }
+
+            let file = work_dir.join(path);
+            if file.is_file() {
+                let id = repo.blob_path(&file)?;
+                ours.add(&blob_entry(path, id, mode)?)?;
+            }
+
+            paths.push(path.to_path_buf());
+        }
+
+        if let Some(path) = delta.new_file().path() {
+            paths.push(path.to_path_buf());
+        }
+    }
+
+    let base = repo.find_tree(base.write_tree_to(repo)?)?;
+    let ours = repo.find_tree(ours.write_tree_to(repo)?)?;
+    let theirs = repo.find_tree(
+        repo.apply_to_tree(&base, diff, None)?.write_tree_to(repo)?,
+    )?;
+
+    let mut merged = repo.merge_trees(&base, &ours, &theirs, None)?;
+
+    let mut checkout = CheckoutBuilder::new();
+    checkout
+        .force()
+        .allow_conflicts(true)
+        .conflict_style_merge(true)
+        .update_index(false);
+    for path in &paths {
+        checkout.path(path.as_path());
+    }
+    repo.checkout_index(Some(&mut merged), Some(&mut checkout))?;
+
+    let mut conflicts = Vec::new();
+    for conflict in merged.conflicts()? {
+        let conflict = conflict?;
+        let entry = conflict
+            .our
+            .or(conflict.their)
+            .or(conflict.ancestor)
+            .ok_or_else(|| {
+                Error::Generic("conflict without entry".to_string())
+            })?;
+
+        conflicts
+            .push(String::from_utf8_lossy(&entry.path).to_string());
+    }
+
+    if to_index && conflicts.is_empty() {
+        let mut index = repo.index()?;
+        for path in &paths {
+            if work_dir.join(path).is_file() {
+                index.add_path(path)?;
+            } else {
+                index.remove_path(path)?;
+            }
+        }
+        index.write()?;
+    }
+
+    Ok(conflicts)
+}
+
+/// the blob a patch line like `index 1a2b3c4..5d6e7f8` refers to,
+/// patches only name an abbreviated id (padded with zeros here)
+fn find_blob_id(
+    repo: &Repository,
+    id: Oid,
+    path: &Path,
+) -> Result<Oid> {
+    let hex = id.to_string();
+    let prefix = hex.trim_end_matches('0');
+    let prefix = &hex[..prefix.len().max(7)];
+
+    repo.revparse_single(prefix)
+        .and_then(|object| object.peel_to_blob())
+        .map(|blob| blob.id())
+        .map_err(|_| {
+            Error::Generic(format!(
+                "patch does not apply and the original of '{}' is unknown",
+                path.display()
+            ))
+        })
+}
+
+fn entry_mode(mode: FileMode) -> u32 {
+    if mode == FileMode::BlobExecutable {
+        0o100_755
+    } else {
+        0o100_644
+    }
+}
+
+fn blob_entry(path: &Path, id: Oid, mode: u32) -> Result<IndexEntry> {
+    let path = path.to_str().ok_or_else(|| {
+        Error::Generic(format!("invalid path: {}", path.display()))
+    })?;
+
+    Ok(IndexEntry {
+        ctime: IndexTime::new(0, 0),
+        mtime: IndexTime::new(0, 0),
+        dev: 0,
+        ino: 0,
+        mode,
+        uid: 0,
+        gid: 0,
+        file_size: 0,
+        id,
+        flags: 0,
+        flags_extended: 0,
+        path: path.as_bytes().to_vec(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::{
+        format_patch, stage_add_file,
+        tests::{repo_init, write_commit_file},
+    };
+    use std::fs;
+
+    const LINES: &str = "1\n2\n3\n4\n5\n6\n7\n8\n9\n10\n11\n12\n";
+
+    /// the patch of changing line 10 of `LINES`, with the file back
+    /// at `LINES`
+    fn setup_patch(
+        repo: &Repository,
+        repo_path: &RepoPath,
+    ) -> String {
+        write_commit_file(repo, "a.txt", LINES, "lines");
+        let id = write_commit_file(
+            repo,
+            "a.txt",
+            &LINES.replace("10\n", "ten\n"),
+            "ten",
+        );
+
+        let patch = format_patch(repo_path, id).unwrap();
+
+        fs::write(work_dir(repo).unwrap().join("a.txt"), LINES)
+            .unwrap();
+
+        patch
+    }
+
+    #[test]
+    fn test_apply_patch() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        let patch = setup_patch(&repo, repo_path);
+
+        assert!(apply_patch(repo_path, &patch, false)
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            fs::read_to_string(root.join("a.txt")).unwrap(),
+            LINES.replace("10\n", "ten\n")
+        );
+
+        assert!(apply_patch(repo_path, "no patch", false).is_err());
+    }
+
+    #[test]
+    fn test_apply_patch_three_way() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        let patch = setup_patch(&repo, repo_path);
+
+        // changes the context of the patch
+        fs::write(
+            root.join("a.txt"),
+            LINES.replace("8\n", "eight\n"),
+        )
+        .unwrap();
+
+        assert!(apply_patch(repo_path, &patch, true)
+            .unwrap()
+            .is_empty());
+        let merged =
+            LINES.replace("8\n", "eight\n").replace("10\n", "ten\n");
+        assert_eq!(
+            fs::read_to_string(root.join("a.txt")).unwrap(),
+            merged
+        );
+
+        // changes the line of the patch
+        fs::write(root.join("a.txt"), LINES.replace("10\n", "X\n"))
+            .unwrap();
+        stage_add_file(repo_path, Path::new("a.txt")).unwrap();
+
+        assert_eq!(
+            apply_patch(repo_path, &patch, true).unwrap(),
+            vec![String::from("a.txt")]
+        );
+        assert!(fs::read_to_string(root.join("a.txt"))
+            .unwrap()
+            .contains("<<<<<<<"));
+    }
+}
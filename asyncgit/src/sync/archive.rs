@@ -0,0 +1,293 @@
+//! archives of a commit's tree like `git archive` writes them
+
+use super::{
+    tree::tree_file_blob,
+    tree_files,
+    utils::{bytes2string, repo},
+    CommitId, RepoPath, TreeFile,
+};
+use crate::error::Result;
+use flate2::{write::GzEncoder, Compression};
+use git2::Repository;
+use scopetime::scope_time;
+use std::{
+    convert::TryFrom,
+    fs::File,
+    io::{self, Seek, Write},
+    path::Path,
+};
+use zip::{write::FileOptions, CompressionMethod, ZipWriter};
+
+const MODE_EXECUTABLE: i32 = 0o100_755;
+const MODE_SYMLINK: i32 = 0o120_000;
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// file formats `archive_commit` writes
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ArchiveFormat {
+    ///
+    Tar,
+    /// gzip compressed tar
+    TarGz,
+    ///
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// the format a file name asks for by its extension
+    pub fn from_file_name(name: &str) -> Option<Self> {
+        let name = name.to_lowercase();
+
+        if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(Self::TarGz)
+        } else if name.ends_with(".tar") {
+            Some(Self::Tar)
+        } else if name.ends_with(".zip") {
+            Some(Self::Zip)
+        } else {
+            None
+        }
+    }
+}
+
+/// writes all files of the commit's tree to `out_path`, every entry
+/// carries the commit time like in `git archive`
+pub fn archive_commit(
+    repo_path: &RepoPath,
+    commit: CommitId,
+    format: ArchiveFormat,
+    out_path: &Path,
+) -> Result<()> {
+    scope_time!("archive_commit");
+
+    let files = tree_files(repo_path, commit)?;
+
+    let repo = repo(repo_path)?;
+    let time = repo.find_commit(commit.into())?.time().seconds();
+
+    let out = File::create(out_path)?;
+
+    match format {
+        ArchiveFormat::Tar => {
+            write_tar(&repo, &files, time, out)?;
+        }
+        ArchiveFormat::TarGz => {
+            let out = GzEncoder::new(out, Compression::default());
+            write_tar(&repo, &files, time, out)?.finish()?;
+        }
+        ArchiveFormat::Zip => write_zip(&repo, &files, time, out)?,
+    }
+
+    Ok(())
+}
+
+/// `tree_files` starts all paths with `./`
+fn archive_path(file: &TreeFile) -> &Path {
+    file.path.strip_prefix("./").unwrap_or(&file.path)
+}
+
+fn write_tar<W: Write>(
+    repo: &Repository,
+    files: &[TreeFile],
+    time: i64,
+    out: W,
+) -> Result<W> {
+    let mut builder = tar::Builder::new(out);
+
+    for file in files {
+        let blob = tree_file_blob(repo, file)?;
+        let path = archive_path(file);
+
+        let mut header = tar::Header::new_gnu();
+        header.set_mtime(u64::try_from(time)?);
+
+        if file.filemode == MODE_SYMLINK {
+            header.set_entry_type(tar::EntryType::Symlink);
+            header.set_mode(0o777);
+            header.set_size(0);
+            header.set_link_name(bytes2string(blob.content())?)?;
+            builder.append_data(&mut header, path, io::empty())?;
+        } else {
+            header.set_entry_type(tar::EntryType::Regular);
+            header.set_mode(if file.filemode == MODE_EXECUTABLE {
+                0o755
+            } else {
+                0o644
+            });
+            header.set_size(u64::try_from(blob.content().len())?);
+            builder.append_data(&mut header, path, blob.content())?;
+        }
+    }
+
+    Ok(builder.into_inner()?)
+}
+
+fn write_zip<W: Write + Seek>(
+    repo: &Repository,
+    files: &[TreeFile],
+    time: i64,
+    out: W,
+) -> Result<()> {
+    let mut zip = ZipWriter::new(out);
+    let options = FileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .last_modified_time(zip_time(time));
+
+    for file in files {
+        let blob = tree_file_blob(repo, file)?;
+        let path = archive_path(file).to_string_lossy().to_string();
+
+        if file.filemode == MODE_SYMLINK {
+            zip.add_symlink(
+                path,
+                bytes2string(blob.content())?,
+                options,
+            )?;
+        } else {
+            zip.start_file(
+                path,
+                options.unix_permissions(
+                    if file.filemode == MODE_EXECUTABLE {
+                        0o755
+                    } else {
+                        0o644
+                    },
+                ),
+            )?;
+            zip.write_all(blob.content())?;
+        }
+    }
+
+    zip.finish()?;
+
+    Ok(())
+}
+
+/// zip entries store the (utc) date and time instead of a timestamp,
+/// zip can not store times before 1980
+fn zip_time(time: i64) -> zip::DateTime {
+    let days = time.div_euclid(SECONDS_PER_DAY);
+    let seconds = time.rem_euclid(SECONDS_PER_DAY);
+
+    let (year, month, day) = civil_from_days(days);
+
+    u16::try_from(year)
+        .ok()
+        .and_then(|year| {
+            zip::DateTime::from_date_and_time(
+                year,
+                u8::try_from(month).ok()?,
+                u8::try_from(day).ok()?,
+                u8::try_from(seconds / 3600).ok()?,
+                u8::try_from(seconds % 3600 / 60).ok()?,
+                u8::try_from(seconds % 60).ok()?,
+            )
+            .ok()
+        })
+        .unwrap_or_default()
+}
+
+/// year, month and day of the days since 1970-01-01, see
+/// <http://howardhinnant.github.io/date_algorithms.html>
+const fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719_468;
+    let era = (if z >= 0 { z } else { z - 146_096 }) / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::tests::{repo_init, write_commit_file};
+    use std::{collections::HashMap, io::Read};
+
+    #[test]
+    fn test_archive_commit() {
+        let (td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        write_commit_file(&repo, "a.txt", "a", "c1");
+        std::fs::create_dir(root.join("dir")).unwrap();
+        let id = write_commit_file(&repo, "dir/b.txt", "b", "c2");
+
+        let expected: HashMap<String, String> = vec![
+            (String::from("a.txt"), String::from("a")),
+            (String::from("dir/b.txt"), String::from("b")),
+        ]
+        .into_iter()
+        .collect();
+
+        let tar_gz = td.path().join("out.tar.gz");
+        archive_commit(repo_path, id, ArchiveFormat::TarGz, &tar_gz)
+            .unwrap();
+
+        let mut archive = tar::Archive::new(
+            flate2::read::GzDecoder::new(File::open(tar_gz).unwrap()),
+        );
+        let mut files = HashMap::new();
+        for entry in archive.entries().unwrap() {
+            let mut entry = entry.unwrap();
+            let path =
+                entry.path().unwrap().to_string_lossy().to_string();
+            let mut content = String::new();
+            entry.read_to_string(&mut content).unwrap();
+            files.insert(path, content);
+        }
+        assert_eq!(files, expected);
+
+        let zip_path = td.path().join("out.zip");
+        archive_commit(repo_path, id, ArchiveFormat::Zip, &zip_path)
+            .unwrap();
+
+        let mut archive =
+            zip::ZipArchive::new(File::open(zip_path).unwrap())
+                .unwrap();
+        let mut files = HashMap::new();
+        for idx in 0..archive.len() {
+            let mut file = archive.by_index(idx).unwrap();
+            let mut content = String::new();
+            file.read_to_string(&mut content).unwrap();
+            files.insert(file.name().to_string(), content);
+        }
+        assert_eq!(files, expected);
+    }
+
+    #[test]
+    fn test_format_from_file_name() {
+        assert_eq!(
+            ArchiveFormat::from_file_name("a.tar.gz"),
+            Some(ArchiveFormat::TarGz)
+        );
+        assert_eq!(
+            ArchiveFormat::from_file_name("a.TGZ"),
+            Some(ArchiveFormat::TarGz)
+        );
+        assert_eq!(
+            ArchiveFormat::from_file_name("a.tar"),
+            Some(ArchiveFormat::Tar)
+        );
+        assert_eq!(
+            ArchiveFormat::from_file_name("a.zip"),
+            Some(ArchiveFormat::Zip)
+        );
+        assert_eq!(ArchiveFormat::from_file_name("a.rar"), None);
+    }
+
+    #[test]
+    fn test_civil_from_days() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(11_016), (2000, 2, 29));
+        assert_eq!(civil_from_days(18_628), (2021, 1, 1));
+    }
+}
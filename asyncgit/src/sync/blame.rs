@@ -1,10 +1,11 @@
 //! Sync git API for fetching a file blame
 
-use super::{utils, CommitId};
+use super::{utils, CommitId, RepoPath};
 use crate::{
     error::{Error, Result},
     sync::get_commits_info,
 };
+use git2::{BlameOptions, Repository};
 use scopetime::scope_time;
 use std::collections::{HashMap, HashSet};
 use std::io::{BufRead, BufReader};
@@ -25,6 +26,56 @@ pub struct BlameHunk {
     pub start_line: usize,
     ///
     pub end_line: usize,
+    /// path of the file in the commit the hunk originates from,
+    /// only set if it differs from the blamed path (rename/copy)
+    pub orig_path: Option<String>,
+}
+
+/// how hard blame looks for lines that were moved or copied,
+/// mirrors the `-M`/`-C` flags of `git blame`
+#[derive(Copy, Clone, Hash, Debug, PartialEq, Eq)]
+pub enum BlameCopyDetection {
+    /// only follow whole file renames
+    Off,
+    /// detect lines moved within the same file (`-M`)
+    SameFile,
+    /// detect lines moved or copied from files modified in the
+    /// same commit (`-C`)
+    SameCommit,
+    /// detect lines copied from any file in any commit (`-C -C -C`)
+    AnyCommit,
+}
+
+impl Default for BlameCopyDetection {
+    fn default() -> Self {
+        Self::Off
+    }
+}
+
+impl BlameCopyDetection {
+    /// cycles through the sensitivity levels
+    pub const fn next(self) -> Self {
+        match self {
+            Self::Off => Self::SameFile,
+            Self::SameFile => Self::SameCommit,
+            Self::SameCommit => Self::AnyCommit,
+            Self::AnyCommit => Self::Off,
+        }
+    }
+
+    fn apply(self, opts: &mut BlameOptions) {
+        let level = match self {
+            Self::Off => 0,
+            Self::SameFile => 1,
+            Self::SameCommit => 2,
+            Self::AnyCommit => 3,
+        };
+
+        opts.track_copies_same_file(level >= 1)
+            .track_copies_same_commit_moves(level >= 2)
+            .track_copies_same_commit_copies(level >= 2)
+            .track_copies_any_commit_copies(level >= 3);
+    }
 }
 
 /// A `BlameFile` represents a collection of lines. This is targeted at how the
@@ -37,13 +88,48 @@ pub struct FileBlame {
     pub path: String,
     ///
     pub lines: Vec<(Option<BlameHunk>, String)>,
+    /// number of lines (from the top) that are already blamed,
+    /// equals `lines.len()` once the blame is complete
+    pub blamed_lines: usize,
 }
 
-///
+impl FileBlame {
+    /// true until all lines are blamed
+    pub fn is_partial(&self) -> bool {
+        self.blamed_lines < self.lines.len()
+    }
+}
+
+/// blames `file_path` at `HEAD`, following renames of the file
+/// through history
 pub fn blame_file(
-    repo_path: &str,
+    repo_path: &RepoPath,
     file_path: &str,
+    copy_detection: BlameCopyDetection,
 ) -> Result<FileBlame> {
+    blame_file_progressive(
+        repo_path,
+        file_path,
+        copy_detection,
+        usize::MAX,
+        |_| true,
+    )
+}
+
+/// same as `blame_file` but blames the file in chunks of lines
+/// (starting with `chunk_lines` and doubling), calling `progress`
+/// with the partial result after every chunk.
+/// `progress` returning `false` cancels the blame.
+pub fn blame_file_progressive<F>(
+    repo_path: &RepoPath,
+    file_path: &str,
+    copy_detection: BlameCopyDetection,
+    chunk_lines: usize,
+    mut progress: F,
+) -> Result<FileBlame>
+where
+    F: FnMut(&FileBlame) -> bool,
+{
     scope_time!("blame_file");
 
     let repo = utils::repo(repo_path)?;
@@ -59,67 +145,115 @@ pub fn blame_file(
         return Err(Error::NoBlameOnBinaryFile);
     }
 
-    let blame = repo.blame_file(Path::new(file_path), None)?;
-
     let reader = BufReader::new(blob.content());
 
-    let unique_commit_ids: HashSet<_> = blame
-        .iter()
-        .map(|hunk| CommitId::new(hunk.final_commit_id()))
-        .collect();
-    let mut commit_ids = Vec::with_capacity(unique_commit_ids.len());
-    commit_ids.extend(unique_commit_ids);
+    let mut file_blame = FileBlame {
+        commit_id,
+        path: file_path.into(),
+        lines: reader
+            .lines()
+            .map(|line| (None, line.unwrap_or_else(|_| "".into())))
+            .collect(),
+        blamed_lines: 0,
+    };
 
-    let commit_infos = get_commits_info(repo_path, &commit_ids, 0)?;
-    let unique_commit_infos: HashMap<_, _> = commit_infos
+    let total_lines = file_blame.lines.len();
+    let mut commit_infos: HashMap<CommitId, (String, i64)> =
+        HashMap::new();
+    let mut chunk_lines = chunk_lines.max(1);
+
+    while file_blame.blamed_lines < total_lines {
+        let first_line = file_blame.blamed_lines;
+        let last_line =
+            first_line.saturating_add(chunk_lines).min(total_lines);
+
+        blame_lines(
+            &repo,
+            repo_path,
+            (first_line, last_line),
+            copy_detection,
+            &mut commit_infos,
+            &mut file_blame,
+        )?;
+
+        file_blame.blamed_lines = last_line;
+        chunk_lines = chunk_lines.saturating_mul(2);
+
+        if file_blame.blamed_lines < total_lines
+            && !progress(&file_blame)
+        {
+            break;
+        }
+    }
+
+    Ok(file_blame)
+}
+
+/// blames the 0-based line range `[first, last)` into `file_blame`
+fn blame_lines(
+    repo: &Repository,
+    repo_path: &RepoPath,
+    (first, last): (usize, usize),
+    copy_detection: BlameCopyDetection,
+    commit_infos: &mut HashMap<CommitId, (String, i64)>,
+    file_blame: &mut FileBlame,
+) -> Result<()> {
+    let mut opts = BlameOptions::new();
+    opts.newest_commit(file_blame.commit_id.into());
+    // libgit2 line numbers are 1-based and inclusive
+    opts.min_line(first + 1);
+    opts.max_line(last);
+    copy_detection.apply(&mut opts);
+
+    let blame = repo
+        .blame_file(Path::new(&file_blame.path), Some(&mut opts))?;
+
+    let new_commit_ids: Vec<_> = blame
         .iter()
-        .map(|commit_info| (commit_info.id, commit_info))
+        .map(|hunk| CommitId::new(hunk.final_commit_id()))
+        .filter(|id| !commit_infos.contains_key(id))
+        .collect::<HashSet<_>>()
+        .into_iter()
         .collect();
 
-    let lines: Vec<(Option<BlameHunk>, String)> = reader
-        .lines()
-        .enumerate()
-        .map(|(i, line)| {
-            // Line indices in a `FileBlame` are 1-based.
-            let corresponding_hunk = blame.get_line(i + 1);
-
-            if let Some(hunk) = corresponding_hunk {
-                let commit_id = CommitId::new(hunk.final_commit_id());
-                // Line indices in a `BlameHunk` are 1-based.
-                let start_line =
-                    hunk.final_start_line().saturating_sub(1);
-                let end_line =
-                    start_line.saturating_add(hunk.lines_in_hunk());
-
-                if let Some(commit_info) =
-                    unique_commit_infos.get(&commit_id)
-                {
-                    let hunk = BlameHunk {
-                        commit_id,
-                        author: commit_info.author.clone(),
-                        time: commit_info.time,
-                        start_line,
-                        end_line,
-                    };
-
-                    return (
-                        Some(hunk),
-                        line.unwrap_or_else(|_| "".into()),
-                    );
-                }
-            }
-
-            (None, line.unwrap_or_else(|_| "".into()))
-        })
-        .collect();
+    for info in get_commits_info(repo_path, &new_commit_ids, 0)? {
+        commit_infos.insert(info.id, (info.author, info.time));
+    }
 
-    let file_blame = FileBlame {
-        commit_id,
-        path: file_path.into(),
-        lines,
-    };
+    for i in first..last {
+        // Line indices in a `FileBlame` are 1-based.
+        let hunk = match blame.get_line(i + 1) {
+            Some(hunk) => hunk,
+            None => continue,
+        };
+
+        let commit_id = CommitId::new(hunk.final_commit_id());
+
+        if let Some((author, time)) = commit_infos.get(&commit_id) {
+            // Line indices in a `BlameHunk` are 1-based.
+            let start_line =
+                hunk.final_start_line().saturating_sub(1);
+            let end_line =
+                start_line.saturating_add(hunk.lines_in_hunk());
+
+            let orig_path = hunk
+                .path()
+                .and_then(Path::to_str)
+                .filter(|path| *path != file_blame.path)
+                .map(String::from);
+
+            file_blame.lines[i].0 = Some(BlameHunk {
+                commit_id,
+                author: author.clone(),
+                time: *time,
+                start_line,
+                end_line,
+                orig_path,
+            });
+        }
+    }
 
-    Ok(file_blame)
+    Ok(())
 }
 
 #[cfg(test)]
@@ -127,10 +261,11 @@ mod tests {
     use super::*;
     use crate::error::Result;
     use crate::sync::{
-        commit, stage_add_file, tests::repo_init_empty,
+        commit, stage_add_file, stage_addremoved,
+        tests::repo_init_empty,
     };
     use std::{
-        fs::{File, OpenOptions},
+        fs::{self, File, OpenOptions},
         io::Write,
         path::Path,
     };
@@ -140,9 +275,13 @@ mod tests {
         let file_path = Path::new("foo");
         let (_td, repo) = repo_init_empty()?;
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
-        assert!(matches!(blame_file(&repo_path, "foo"), Err(_)));
+        assert!(matches!(
+            blame_file(&repo_path, "foo", BlameCopyDetection::Off),
+            Err(_)
+        ));
 
         File::create(&root.join(file_path))?
             .write_all(b"line 1\n")?;
@@ -150,7 +289,8 @@ mod tests {
         stage_add_file(repo_path, file_path)?;
         commit(repo_path, "first commit")?;
 
-        let blame = blame_file(&repo_path, "foo")?;
+        let blame =
+            blame_file(&repo_path, "foo", BlameCopyDetection::Off)?;
 
         assert!(matches!(
             blame.lines.as_slice(),
@@ -174,7 +314,8 @@ mod tests {
         stage_add_file(repo_path, file_path)?;
         commit(repo_path, "second commit")?;
 
-        let blame = blame_file(&repo_path, "foo")?;
+        let blame =
+            blame_file(&repo_path, "foo", BlameCopyDetection::Off)?;
 
         assert!(matches!(
             blame.lines.as_slice(),
@@ -201,17 +342,93 @@ mod tests {
 
         file.write(b"line 3\n")?;
 
-        let blame = blame_file(&repo_path, "foo")?;
+        let blame =
+            blame_file(&repo_path, "foo", BlameCopyDetection::Off)?;
 
         assert_eq!(blame.lines.len(), 2);
 
         stage_add_file(repo_path, file_path)?;
         commit(repo_path, "third commit")?;
 
-        let blame = blame_file(&repo_path, "foo")?;
+        let blame =
+            blame_file(&repo_path, "foo", BlameCopyDetection::Off)?;
 
         assert_eq!(blame.lines.len(), 3);
 
         Ok(())
     }
+
+    #[test]
+    fn test_blame_follows_rename() -> Result<()> {
+        let (_td, repo) = repo_init_empty()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        File::create(&root.join("foo"))?
+            .write_all(b"line 1\nline 2\n")?;
+        stage_add_file(repo_path, Path::new("foo"))?;
+        let first = commit(repo_path, "first commit")?;
+
+        fs::rename(root.join("foo"), root.join("bar"))?;
+        stage_add_file(repo_path, Path::new("bar"))?;
+        stage_addremoved(repo_path, Path::new("foo"))?;
+        commit(repo_path, "rename")?;
+
+        let blame =
+            blame_file(&repo_path, "bar", BlameCopyDetection::Off)?;
+
+        assert_eq!(blame.lines.len(), 2);
+        for (hunk, _) in &blame.lines {
+            let hunk = hunk.as_ref().unwrap();
+            assert_eq!(hunk.commit_id, first);
+            assert_eq!(hunk.orig_path.as_deref(), Some("foo"));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_blame_progressive() -> Result<()> {
+        let (_td, repo) = repo_init_empty()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        File::create(&root.join("foo"))?
+            .write_all(b"1\n2\n3\n4\n5\n6\n7\n")?;
+        stage_add_file(repo_path, Path::new("foo"))?;
+        commit(repo_path, "first commit")?;
+
+        let mut steps = Vec::new();
+        let blame = blame_file_progressive(
+            &repo_path,
+            "foo",
+            BlameCopyDetection::Off,
+            2,
+            |partial| {
+                steps.push(partial.blamed_lines);
+                true
+            },
+        )?;
+
+        // chunks of 2, 4 and the remaining line
+        assert_eq!(steps, vec![2, 6]);
+        assert!(!blame.is_partial());
+        assert!(blame.lines.iter().all(|(hunk, _)| hunk.is_some()));
+
+        let blame = blame_file_progressive(
+            &repo_path,
+            "foo",
+            BlameCopyDetection::Off,
+            2,
+            |_| false,
+        )?;
+
+        assert!(blame.is_partial());
+        assert_eq!(blame.blamed_lines, 2);
+        assert!(blame.lines[2].0.is_none());
+
+        Ok(())
+    }
 }
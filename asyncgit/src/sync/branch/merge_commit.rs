@@ -3,7 +3,10 @@
 use super::BranchType;
 use crate::{
     error::{Error, Result},
-    sync::{merge_msg, utils, CommitId},
+    sync::{
+        branch::merge_ff::merge_upstream_ff, merge_msg, utils,
+        CommitId, InstanceLock, RepoPath,
+    },
 };
 use git2::Commit;
 use scopetime::scope_time;
@@ -12,11 +15,13 @@ use scopetime::scope_time;
 /// if we did not create conflicts we create a merge commit and return the commit id.
 /// Otherwise we return `None`
 pub fn merge_upstream_commit(
-    repo_path: &str,
+    repo_path: &RepoPath,
     branch_name: &str,
 ) -> Result<Option<CommitId>> {
     scope_time!("merge_upstream_commit");
 
+    let _lock = InstanceLock::acquire(repo_path)?;
+
     let repo = utils::repo(repo_path)?;
 
     let branch = repo.find_branch(branch_name, BranchType::Local)?;
@@ -30,15 +35,14 @@ pub fn merge_upstream_commit(
     let (analysis, pref) =
         repo.merge_analysis(&[&annotated_upstream])?;
 
-    if !analysis.is_normal() {
-        return Err(Error::Generic(
-            "normal merge not possible".into(),
-        ));
+    // `merge.ff=only` asks for no merge commit
+    if analysis.is_fast_forward() && pref.is_fastforward_only() {
+        return merge_upstream_ff(repo_path, branch_name).map(Some);
     }
 
-    if analysis.is_fast_forward() && pref.is_fastforward_only() {
+    if !analysis.is_normal() {
         return Err(Error::Generic(
-            "ff merge would be possible".into(),
+            "normal merge not possible".into(),
         ));
     }
 
@@ -97,7 +101,7 @@ mod test {
     use super::*;
     use crate::sync::{
         branch_compare_upstream,
-        remotes::{fetch, push::push},
+        remotes::{fetch, push::push, FetchDepth},
         tests::{
             debug_cmd_print, get_commit_ids, repo_clone,
             repo_init_bare, write_commit_file,
@@ -115,7 +119,8 @@ mod test {
         let (clone2_dir, clone2) =
             repo_clone(r1_dir.path().to_str().unwrap()).unwrap();
 
-        let clone2_dir = clone2_dir.path().to_str().unwrap();
+        let clone2_dir: &RepoPath =
+            &clone2_dir.path().to_str().unwrap().into();
 
         // clone1
 
@@ -123,7 +128,7 @@ mod test {
             write_commit_file(&clone1, "test.txt", "test", "commit1");
 
         push(
-            clone1_dir.path().to_str().unwrap(),
+            &clone1_dir.path().to_str().unwrap().into(),
             "origin",
             "master",
             false,
@@ -148,7 +153,9 @@ mod test {
         .is_err());
 
         //lets fetch from origin
-        let bytes = fetch(clone2_dir, "master", None, None).unwrap();
+        let bytes =
+            fetch(clone2_dir, "master", FetchDepth::Full, None, None)
+                .unwrap();
         assert!(bytes > 0);
 
         //we should be one commit behind
@@ -205,12 +212,12 @@ mod test {
         );
 
         debug_cmd_print(
-            clone2_dir.path().to_str().unwrap(),
+            &clone2_dir.path().to_str().unwrap().into(),
             "git status",
         );
 
         push(
-            clone1_dir.path().to_str().unwrap(),
+            &clone1_dir.path().to_str().unwrap().into(),
             "origin",
             "master",
             false,
@@ -229,8 +236,9 @@ mod test {
         );
 
         let bytes = fetch(
-            clone2_dir.path().to_str().unwrap(),
+            &clone2_dir.path().to_str().unwrap().into(),
             "master",
+            FetchDepth::Full,
             None,
             None,
         )
@@ -238,7 +246,7 @@ mod test {
         assert!(bytes > 0);
 
         let res = merge_upstream_commit(
-            clone2_dir.path().to_str().unwrap(),
+            &clone2_dir.path().to_str().unwrap().into(),
             "master",
         )
         .unwrap();
@@ -247,7 +255,7 @@ mod test {
         assert_eq!(res, None);
 
         let state = crate::sync::repo_state(
-            clone2_dir.path().to_str().unwrap(),
+            &clone2_dir.path().to_str().unwrap().into(),
         )
         .unwrap();
 
@@ -258,4 +266,51 @@ mod test {
         let commits = get_commit_ids(&clone1, 10);
         assert_eq!(commits.len(), 1);
     }
+
+    #[test]
+    fn test_merge_ff_only() {
+        let (r1_dir, _repo) = repo_init_bare().unwrap();
+
+        let (clone1_dir, clone1) =
+            repo_clone(r1_dir.path().to_str().unwrap()).unwrap();
+        let clone1_dir: &RepoPath =
+            &clone1_dir.path().to_str().unwrap().into();
+
+        write_commit_file(&clone1, "test.txt", "test", "commit1");
+        push(clone1_dir, "origin", "master", false, None, None)
+            .unwrap();
+
+        let (clone2_dir, clone2) =
+            repo_clone(r1_dir.path().to_str().unwrap()).unwrap();
+        let clone2_dir: &RepoPath =
+            &clone2_dir.path().to_str().unwrap().into();
+        clone2
+            .config()
+            .unwrap()
+            .set_str("merge.ff", "only")
+            .unwrap();
+
+        let commit2 = write_commit_file(
+            &clone1,
+            "test2.txt",
+            "test",
+            "commit2",
+        );
+        push(clone1_dir, "origin", "master", false, None, None)
+            .unwrap();
+
+        fetch(clone2_dir, "master", FetchDepth::Full, None, None)
+            .unwrap();
+
+        let res =
+            merge_upstream_commit(clone2_dir, "master").unwrap();
+
+        // fast forwarded instead of failing, no merge commit
+        assert_eq!(res, Some(commit2));
+        assert_eq!(get_commit_ids(&clone2, 10)[0], commit2);
+        assert_eq!(
+            crate::sync::repo_state(clone2_dir).unwrap(),
+            RepoState::Clean
+        );
+    }
 }
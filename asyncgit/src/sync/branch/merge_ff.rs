@@ -3,19 +3,57 @@
 use super::BranchType;
 use crate::{
     error::{Error, Result},
-    sync::utils,
+    sync::{utils, CommitId, InstanceLock, RepoPath},
 };
+use git2::{Commit, MergeAnalysis, MergePreference, Repository};
 use scopetime::scope_time;
 
-///
+/// fast forwards unless `merge.ff` is set to `false`
 pub fn branch_merge_upstream_fastforward(
-    repo_path: &str,
+    repo_path: &RepoPath,
     branch: &str,
 ) -> Result<()> {
     scope_time!("branch_merge_upstream");
 
+    let _lock = InstanceLock::acquire(repo_path)?;
+
+    let repo = utils::repo(repo_path)?;
+
+    let (upstream_commit, analysis, pref) =
+        upstream_analysis(&repo, branch)?;
+
+    if pref.is_no_fast_forward() && analysis.is_fast_forward() {
+        return Err(Error::Generic("fast forward not wanted".into()));
+    }
+
+    fast_forward(&repo, &upstream_commit, analysis)?;
+
+    Ok(())
+}
+
+/// moves the checked out `branch` to its upstream and checks that
+/// out, fails if this is no fast forward or local changes are in
+/// the way
+pub fn merge_upstream_ff(
+    repo_path: &RepoPath,
+    branch: &str,
+) -> Result<CommitId> {
+    scope_time!("merge_upstream_ff");
+
+    let _lock = InstanceLock::acquire(repo_path)?;
+
     let repo = utils::repo(repo_path)?;
 
+    let (upstream_commit, analysis, _) =
+        upstream_analysis(&repo, branch)?;
+
+    fast_forward(&repo, &upstream_commit, analysis)
+}
+
+fn upstream_analysis<'a>(
+    repo: &'a Repository,
+    branch: &str,
+) -> Result<(Commit<'a>, MergeAnalysis, MergePreference)> {
     let branch = repo.find_branch(branch, BranchType::Local)?;
     let upstream = branch.upstream()?;
 
@@ -27,16 +65,20 @@ pub fn branch_merge_upstream_fastforward(
 
     let (analysis, pref) = repo.merge_analysis(&[&annotated])?;
 
+    Ok((upstream_commit, analysis, pref))
+}
+
+fn fast_forward(
+    repo: &Repository,
+    upstream_commit: &Commit,
+    analysis: MergeAnalysis,
+) -> Result<CommitId> {
     if !analysis.is_fast_forward() {
         return Err(Error::Generic(
             "fast forward merge not possible".into(),
         ));
     }
 
-    if pref.is_no_fast_forward() {
-        return Err(Error::Generic("fast forward not wanted".into()));
-    }
-
     //TODO: support merge on unborn
     if analysis.is_unborn() {
         return Err(Error::Generic("head is unborn".into()));
@@ -44,16 +86,16 @@ pub fn branch_merge_upstream_fastforward(
 
     repo.checkout_tree(upstream_commit.as_object(), None)?;
 
-    repo.head()?.set_target(annotated.id(), "")?;
+    repo.head()?.set_target(upstream_commit.id(), "")?;
 
-    Ok(())
+    Ok(upstream_commit.id().into())
 }
 
 #[cfg(test)]
 pub mod test {
     use super::*;
     use crate::sync::{
-        remotes::{fetch, push::push},
+        remotes::{fetch, push::push, FetchDepth},
         tests::{
             debug_cmd_print, get_commit_ids, repo_clone,
             repo_init_bare, write_commit_file,
@@ -76,7 +118,7 @@ pub mod test {
             write_commit_file(&clone1, "test.txt", "test", "commit1");
 
         push(
-            clone1_dir.path().to_str().unwrap(),
+            &clone1_dir.path().to_str().unwrap().into(),
             "origin",
             "master",
             false,
@@ -87,7 +129,7 @@ pub mod test {
 
         // clone2
         debug_cmd_print(
-            clone2_dir.path().to_str().unwrap(),
+            &clone2_dir.path().to_str().unwrap().into(),
             "git pull --ff",
         );
 
@@ -99,7 +141,7 @@ pub mod test {
         );
 
         push(
-            clone2_dir.path().to_str().unwrap(),
+            &clone2_dir.path().to_str().unwrap().into(),
             "origin",
             "master",
             false,
@@ -111,8 +153,9 @@ pub mod test {
         // clone1 again
 
         let bytes = fetch(
-            clone1_dir.path().to_str().unwrap(),
+            &clone1_dir.path().to_str().unwrap().into(),
             "master",
+            FetchDepth::Full,
             None,
             None,
         )
@@ -120,8 +163,9 @@ pub mod test {
         assert!(bytes > 0);
 
         let bytes = fetch(
-            clone1_dir.path().to_str().unwrap(),
+            &clone1_dir.path().to_str().unwrap().into(),
             "master",
+            FetchDepth::Full,
             None,
             None,
         )
@@ -129,7 +173,7 @@ pub mod test {
         assert_eq!(bytes, 0);
 
         branch_merge_upstream_fastforward(
-            clone1_dir.path().to_str().unwrap(),
+            &clone1_dir.path().to_str().unwrap().into(),
             "master",
         )
         .unwrap();
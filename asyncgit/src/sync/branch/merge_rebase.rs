@@ -2,18 +2,20 @@
 
 use crate::{
     error::{Error, Result},
-    sync::utils,
+    sync::{utils, InstanceLock, RepoPath},
 };
 use git2::BranchType;
 use scopetime::scope_time;
 
 /// trys merging current branch with its upstrema using rebase
 pub fn merge_upstream_rebase(
-    repo_path: &str,
+    repo_path: &RepoPath,
     branch_name: &str,
 ) -> Result<()> {
     scope_time!("merge_upstream_rebase");
 
+    let _lock = InstanceLock::acquire(repo_path)?;
+
     let repo = utils::repo(repo_path)?;
     if super::get_branch_name_repo(&repo)? != branch_name {
         return Err(Error::Generic(String::from(
@@ -57,7 +59,7 @@ mod test {
     use super::*;
     use crate::sync::{
         branch_compare_upstream, get_commits_info,
-        remotes::{fetch, push::push},
+        remotes::{fetch, push::push, FetchDepth},
         tests::{
             debug_cmd_print, get_commit_ids, repo_clone,
             repo_init_bare, write_commit_file,
@@ -69,7 +71,7 @@ mod test {
     fn get_commit_msgs(r: &Repository) -> Vec<String> {
         let commits = get_commit_ids(r, 10);
         get_commits_info(
-            r.workdir().unwrap().to_str().unwrap(),
+            &r.workdir().unwrap().to_str().unwrap().into(),
             &commits,
             10,
         )
@@ -86,7 +88,8 @@ mod test {
         let (clone1_dir, clone1) =
             repo_clone(r1_dir.path().to_str().unwrap()).unwrap();
 
-        let clone1_dir = clone1_dir.path().to_str().unwrap();
+        let clone1_dir: &RepoPath =
+            &clone1_dir.path().to_str().unwrap().into();
 
         // clone1
 
@@ -105,7 +108,8 @@ mod test {
         let (clone2_dir, clone2) =
             repo_clone(r1_dir.path().to_str().unwrap()).unwrap();
 
-        let clone2_dir = clone2_dir.path().to_str().unwrap();
+        let clone2_dir: &RepoPath =
+            &clone2_dir.path().to_str().unwrap().into();
 
         let _commit2 = write_commit_file(
             &clone2,
@@ -133,7 +137,9 @@ mod test {
         assert_eq!(clone1.head_detached().unwrap(), false);
 
         //lets fetch from origin
-        let bytes = fetch(clone1_dir, "master", None, None).unwrap();
+        let bytes =
+            fetch(clone1_dir, "master", FetchDepth::Full, None, None)
+                .unwrap();
         assert!(bytes > 0);
 
         //we should be one commit behind
@@ -175,7 +181,8 @@ mod test {
         let (clone1_dir, clone1) =
             repo_clone(r1_dir.path().to_str().unwrap()).unwrap();
 
-        let clone1_dir = clone1_dir.path().to_str().unwrap();
+        let clone1_dir: &RepoPath =
+            &clone1_dir.path().to_str().unwrap().into();
 
         // clone1
 
@@ -189,7 +196,8 @@ mod test {
         let (clone2_dir, clone2) =
             repo_clone(r1_dir.path().to_str().unwrap()).unwrap();
 
-        let clone2_dir = clone2_dir.path().to_str().unwrap();
+        let clone2_dir: &RepoPath =
+            &clone2_dir.path().to_str().unwrap().into();
 
         write_commit_file(&clone2, "test2.txt", "test", "commit2");
 
@@ -203,7 +211,8 @@ mod test {
 
         //lets fetch from origin
 
-        fetch(clone1_dir, "master", None, None).unwrap();
+        fetch(clone1_dir, "master", FetchDepth::Full, None, None)
+            .unwrap();
 
         merge_upstream_rebase(clone1_dir, "master").unwrap();
 
@@ -233,7 +242,8 @@ mod test {
         let (clone1_dir, clone1) =
             repo_clone(r1_dir.path().to_str().unwrap()).unwrap();
 
-        let clone1_dir = clone1_dir.path().to_str().unwrap();
+        let clone1_dir: &RepoPath =
+            &clone1_dir.path().to_str().unwrap().into();
 
         // clone1
 
@@ -248,7 +258,8 @@ mod test {
         let (clone2_dir, clone2) =
             repo_clone(r1_dir.path().to_str().unwrap()).unwrap();
 
-        let clone2_dir = clone2_dir.path().to_str().unwrap();
+        let clone2_dir: &RepoPath =
+            &clone2_dir.path().to_str().unwrap().into();
 
         let _commit2 = write_commit_file(
             &clone2,
@@ -265,7 +276,9 @@ mod test {
         let _commit3 =
             write_commit_file(&clone1, "test2.txt", "foo", "commit3");
 
-        let bytes = fetch(clone1_dir, "master", None, None).unwrap();
+        let bytes =
+            fetch(clone1_dir, "master", FetchDepth::Full, None, None)
+                .unwrap();
         assert!(bytes > 0);
 
         assert_eq!(
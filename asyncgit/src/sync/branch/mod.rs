@@ -5,12 +5,13 @@ pub mod merge_ff;
 pub mod merge_rebase;
 pub mod rename;
 
-use super::{
-    remotes::get_default_remote_in_repo, utils::bytes2string,
-};
+use super::utils::bytes2string;
 use crate::{
     error::{Error, Result},
-    sync::{utils, CommitId},
+    sync::{
+        autostash, stash_save, utils, CommitId, InstanceLock,
+        RepoPath,
+    },
 };
 use git2::{BranchType, Repository};
 use scopetime::scope_time;
@@ -18,7 +19,9 @@ use utils::get_head_repo;
 
 /// returns the branch-name head is currently pointing to
 /// this might be expensive, see `cached::BranchName`
-pub(crate) fn get_branch_name(repo_path: &str) -> Result<String> {
+pub(crate) fn get_branch_name(
+    repo_path: &RepoPath,
+) -> Result<String> {
     let repo = utils::repo(repo_path)?;
 
     get_branch_name_repo(&repo)
@@ -30,15 +33,18 @@ pub(crate) fn get_branch_name_repo(
 ) -> Result<String> {
     scope_time!("get_branch_name_repo");
 
-    let iter = repo.branches(None)?;
-
-    for b in iter {
-        let b = b?;
-
-        if b.0.is_head() {
-            let name = b.0.name()?.unwrap_or("");
-            return Ok(name.into());
+    // looking at `HEAD` directly instead of asking every branch
+    // whether it is the head, there can be plenty of branches
+    let head = match repo.head() {
+        Ok(head) => head,
+        Err(e) if e.code() == git2::ErrorCode::UnbornBranch => {
+            return Err(Error::NoHead)
         }
+        Err(e) => return Err(e.into()),
+    };
+
+    if head.is_branch() {
+        return bytes2string(head.shorthand_bytes());
     }
 
     Err(Error::NoHead)
@@ -53,6 +59,8 @@ pub struct LocalBranch {
     pub has_upstream: bool,
     ///
     pub remote: Option<String>,
+    /// ahead/behind the upstream, `None` without upstream
+    pub upstream_compare: Option<BranchCompare>,
 }
 
 ///
@@ -75,6 +83,8 @@ pub struct BranchInfo {
     pub top_commit_message: String,
     ///
     pub top_commit: CommitId,
+    /// commit time of `top_commit` in seconds since epoch
+    pub top_commit_time: i64,
     ///
     pub details: BranchDetails,
 }
@@ -90,66 +100,115 @@ impl BranchInfo {
     }
 }
 
+const fn branch_type(local: bool) -> BranchType {
+    if local {
+        BranchType::Local
+    } else {
+        BranchType::Remote
+    }
+}
+
 /// returns a list of `BranchInfo` with a simple summary on each branch
 /// `local` filters for local branches otherwise remote branches will be returned
 pub fn get_branches_info(
-    repo_path: &str,
+    repo_path: &RepoPath,
     local: bool,
 ) -> Result<Vec<BranchInfo>> {
     scope_time!("get_branches_info");
 
-    let filter = if local {
-        BranchType::Local
-    } else {
-        BranchType::Remote
-    };
+    let names = get_branch_names(repo_path, local)?;
+
+    get_branches_info_for(repo_path, local, &names)
+}
+
+/// sorted names of all local (or remote) branches, unlike
+/// `get_branches_info` this does not look at the commits, which
+/// makes it cheap even for repositories with huge numbers of refs
+pub fn get_branch_names(
+    repo_path: &RepoPath,
+    local: bool,
+) -> Result<Vec<String>> {
+    scope_time!("get_branch_names");
 
     let repo = utils::repo(repo_path)?;
-    let mut branches_for_display: Vec<BranchInfo> = repo
-        .branches(Some(filter))?
-        .map(|b| {
-            let branch = b?.0;
-            let top_commit = branch.get().peel_to_commit()?;
-            let reference = bytes2string(branch.get().name_bytes())?;
-            let upstream = branch.upstream();
-
-            let remote = repo
-                .branch_upstream_remote(&reference)
-                .ok()
-                .as_ref()
-                .and_then(git2::Buf::as_str)
-                .map(String::from);
-
-            let details = if local {
-                BranchDetails::Local(LocalBranch {
-                    is_head: branch.is_head(),
-                    has_upstream: upstream.is_ok(),
-                    remote,
-                })
-            } else {
-                BranchDetails::Remote
-            };
-
-            Ok(BranchInfo {
-                name: bytes2string(branch.name_bytes()?)?,
-                reference,
-                top_commit_message: bytes2string(
-                    top_commit.summary_bytes().unwrap_or_default(),
-                )?,
-                top_commit: top_commit.id().into(),
-                details,
-            })
+    let mut names: Vec<String> = repo
+        .branches(Some(branch_type(local)))?
+        .filter_map(|b| {
+            let branch = b.ok()?.0;
+            bytes2string(branch.name_bytes().ok()?).ok()
         })
-        .filter_map(Result::ok)
         .collect();
 
-    branches_for_display.sort_by(|a, b| a.name.cmp(&b.name));
+    names.sort();
 
-    Ok(branches_for_display)
+    Ok(names)
+}
+
+/// `BranchInfo` of just the branches named (in that order), used to
+/// load the details of the visible part of a long list lazily.
+/// branches that do not exist (anymore) are skipped
+pub fn get_branches_info_for(
+    repo_path: &RepoPath,
+    local: bool,
+    names: &[String],
+) -> Result<Vec<BranchInfo>> {
+    scope_time!("get_branches_info_for");
+
+    let repo = utils::repo(repo_path)?;
+
+    Ok(names
+        .iter()
+        .filter_map(|name| {
+            let branch =
+                repo.find_branch(name, branch_type(local)).ok()?;
+            branch_info(&repo, &branch, local).ok()
+        })
+        .collect())
+}
+
+fn branch_info(
+    repo: &Repository,
+    branch: &git2::Branch,
+    local: bool,
+) -> Result<BranchInfo> {
+    let top_commit = branch.get().peel_to_commit()?;
+    let reference = bytes2string(branch.get().name_bytes())?;
+
+    let details = if local {
+        let remote = repo
+            .branch_upstream_remote(&reference)
+            .ok()
+            .as_ref()
+            .and_then(git2::Buf::as_str)
+            .map(String::from);
+
+        let upstream_compare =
+            branch_compare_upstream_repo(repo, branch).ok();
+
+        BranchDetails::Local(LocalBranch {
+            is_head: branch.is_head(),
+            has_upstream: branch.upstream().is_ok(),
+            remote,
+            upstream_compare,
+        })
+    } else {
+        BranchDetails::Remote
+    };
+
+    Ok(BranchInfo {
+        name: bytes2string(branch.name_bytes()?)?,
+        reference,
+        top_commit_message: bytes2string(
+            top_commit.summary_bytes().unwrap_or_default(),
+        )?,
+        top_commit: top_commit.id().into(),
+        top_commit_time: top_commit.time().seconds(),
+        details,
+    })
 }
 
 ///
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
 pub struct BranchCompare {
     ///
     pub ahead: usize,
@@ -157,10 +216,12 @@ pub struct BranchCompare {
     pub behind: usize,
 }
 
-///
+/// tracks the branch of the same name on `remote` unless the local
+/// branch has an upstream already (`branch.<name>.remote`/`merge`)
 pub(crate) fn branch_set_upstream(
     repo: &Repository,
     branch_name: &str,
+    remote: &str,
 ) -> Result<()> {
     scope_time!("branch_set_upstream");
 
@@ -168,7 +229,6 @@ pub(crate) fn branch_set_upstream(
         repo.find_branch(branch_name, BranchType::Local)?;
 
     if branch.upstream().is_err() {
-        let remote = get_default_remote_in_repo(repo)?;
         let upstream_name = format!("{}/{}", remote, branch_name);
         branch.set_upstream(Some(upstream_name.as_str()))?;
     }
@@ -176,9 +236,60 @@ pub(crate) fn branch_set_upstream(
     Ok(())
 }
 
+/// name of the upstream tracking branch of the local `branch` (e.g.
+/// `origin/master`)
+pub fn get_upstream(
+    repo_path: &RepoPath,
+    branch: &str,
+) -> Result<Option<String>> {
+    scope_time!("get_upstream");
+
+    let repo = utils::repo(repo_path)?;
+    let branch = repo.find_branch(branch, BranchType::Local)?;
+
+    let upstream = match branch.upstream() {
+        Ok(upstream) => Some(bytes2string(upstream.name_bytes()?)?),
+        Err(e) if e.code() == git2::ErrorCode::NotFound => None,
+        Err(e) => return Err(e.into()),
+    };
+
+    Ok(upstream)
+}
+
+/// makes the local `branch` track the remote branch `upstream` (e.g.
+/// `origin/master`), `None` removes the tracking
+pub fn set_upstream(
+    repo_path: &RepoPath,
+    branch: &str,
+    upstream: Option<&str>,
+) -> Result<()> {
+    scope_time!("set_upstream");
+
+    let _lock = InstanceLock::acquire(repo_path)?;
+
+    let repo = utils::repo(repo_path)?;
+    let mut branch = repo.find_branch(branch, BranchType::Local)?;
+
+    if let Some(upstream) = upstream {
+        if repo.find_branch(upstream, BranchType::Remote).is_err() {
+            return Err(Error::Generic(format!(
+                "remote branch '{}' not found (fetch first?)",
+                upstream
+            )));
+        }
+    } else if branch.upstream().is_err() {
+        // libgit2 fails to remove a tracking that is not there
+        return Ok(());
+    }
+
+    branch.set_upstream(upstream)?;
+
+    Ok(())
+}
+
 /// returns remote of the upstream tracking branch for `branch`
 pub fn get_branch_remote(
-    repo_path: &str,
+    repo_path: &RepoPath,
     branch: &str,
 ) -> Result<Option<String>> {
     let repo = utils::repo(repo_path)?;
@@ -192,8 +303,92 @@ pub fn get_branch_remote(
     }
 }
 
+/// returns the remote `branch` gets pushed to:
+/// `branch.<name>.pushRemote`, then `remote.pushDefault`, then the
+/// remote of its upstream. in triangular workflows this is not the
+/// remote the branch is fetched from
+pub fn get_push_remote(
+    repo_path: &RepoPath,
+    branch: &str,
+) -> Result<Option<String>> {
+    scope_time!("get_push_remote");
+
+    let repo = utils::repo(repo_path)?;
+    let config = repo.config()?;
+
+    let keys = [
+        format!("branch.{}.pushRemote", branch),
+        String::from("remote.pushDefault"),
+    ];
+    for key in &keys {
+        if let Ok(remote) = config.get_string(key) {
+            if !remote.is_empty() {
+                return Ok(Some(remote));
+            }
+        }
+    }
+
+    get_branch_remote(repo_path, branch)
+}
+
+/// `branch` compared to the branch of the same name on its push
+/// remote (see `get_push_remote`)
+#[derive(Debug)]
+pub struct PushCompare {
+    ///
+    pub remote: String,
+    /// `None` if the branch is not on the remote (or not fetched)
+    pub compare: Option<BranchCompare>,
+}
+
+/// `None` if the push remote is the one of the upstream anyway, see
+/// `branch_compare_upstream` for that
+pub fn branch_compare_push(
+    repo_path: &RepoPath,
+    branch: &str,
+) -> Result<Option<PushCompare>> {
+    scope_time!("branch_compare_push");
+
+    let remote = match get_push_remote(repo_path, branch)? {
+        Some(remote) => remote,
+        None => return Ok(None),
+    };
+
+    if get_branch_remote(repo_path, branch)?.as_deref()
+        == Some(remote.as_str())
+    {
+        return Ok(None);
+    }
+
+    let repo = utils::repo(repo_path)?;
+    let local = repo.find_branch(branch, BranchType::Local)?;
+    let pushed = match repo.find_branch(
+        &format!("{}/{}", remote, branch),
+        BranchType::Remote,
+    ) {
+        Ok(pushed) => pushed,
+        Err(e) if e.code() == git2::ErrorCode::NotFound => {
+            return Ok(Some(PushCompare {
+                remote,
+                compare: None,
+            }))
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    let (ahead, behind) = repo.graph_ahead_behind(
+        local.into_reference().peel_to_commit()?.id(),
+        pushed.into_reference().peel_to_commit()?.id(),
+    )?;
+
+    Ok(Some(PushCompare {
+        remote,
+        compare: Some(BranchCompare { ahead, behind }),
+    }))
+}
+
 /// returns whether the pull merge strategy is set to rebase
-pub fn config_is_pull_rebase(repo_path: &str) -> Result<bool> {
+pub fn config_is_pull_rebase(repo_path: &RepoPath) -> Result<bool> {
     let repo = utils::repo(repo_path)?;
     let config = repo.config()?;
 
@@ -208,7 +403,7 @@ pub fn config_is_pull_rebase(repo_path: &str) -> Result<bool> {
 
 ///
 pub fn branch_compare_upstream(
-    repo_path: &str,
+    repo_path: &RepoPath,
     branch: &str,
 ) -> Result<BranchCompare> {
     scope_time!("branch_compare_upstream");
@@ -217,10 +412,16 @@ pub fn branch_compare_upstream(
 
     let branch = repo.find_branch(branch, BranchType::Local)?;
 
+    branch_compare_upstream_repo(&repo, &branch)
+}
+
+fn branch_compare_upstream_repo(
+    repo: &Repository,
+    branch: &git2::Branch,
+) -> Result<BranchCompare> {
     let upstream = branch.upstream()?;
 
-    let branch_commit =
-        branch.into_reference().peel_to_commit()?.id();
+    let branch_commit = branch.get().peel_to_commit()?.id();
 
     let upstream_commit =
         upstream.into_reference().peel_to_commit()?.id();
@@ -233,11 +434,13 @@ pub fn branch_compare_upstream(
 
 /// Modify HEAD to point to a branch then checkout head, does not work if there are uncommitted changes
 pub fn checkout_branch(
-    repo_path: &str,
+    repo_path: &RepoPath,
     branch_ref: &str,
 ) -> Result<()> {
     scope_time!("checkout_branch");
 
+    let _lock = InstanceLock::acquire(repo_path)?;
+
     // This defaults to a safe checkout, so don't delete anything that
     // hasn't been committed or stashed, in this case it will Err
     let repo = utils::repo(repo_path)?;
@@ -264,13 +467,134 @@ pub fn checkout_branch(
     }
 }
 
-///
+/// what a checkout does with uncommitted changes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CheckoutStrategy {
+    /// fails on uncommitted changes, like `checkout_branch`
+    Safe,
+    /// stashes them (untracked files included) and leaves them in
+    /// the stash
+    Stash,
+    /// carries them over to the checked out branch, like
+    /// `git checkout --merge` without conflict markers: if they do
+    /// not apply there they stay in the stash
+    Keep,
+    /// discards the changes to tracked files
+    Force,
+}
+
+/// there are uncommitted changes (untracked files included) a
+/// checkout would fail on
+pub fn has_uncommitted_changes(repo_path: &RepoPath) -> Result<bool> {
+    let repo = utils::repo(repo_path)?;
+    let statuses = repo.statuses(Some(
+        git2::StatusOptions::new().include_ignored(false),
+    ))?;
+
+    Ok(!statuses.is_empty())
+}
+
+/// checks out the local branch `branch_ref` dealing with uncommitted
+/// changes according to `strategy`
+pub fn checkout_with_strategy(
+    repo_path: &RepoPath,
+    branch_ref: &str,
+    strategy: CheckoutStrategy,
+) -> Result<()> {
+    scope_time!("checkout_with_strategy");
+
+    let _lock = InstanceLock::acquire(repo_path)?;
+
+    match strategy {
+        CheckoutStrategy::Safe => {
+            checkout_branch(repo_path, branch_ref)
+        }
+        CheckoutStrategy::Stash => {
+            if has_uncommitted_changes(repo_path)? {
+                stash_save(
+                    repo_path,
+                    Some(&format!("checkout of {}", branch_ref)),
+                    true,
+                    false,
+                    false,
+                )?;
+            }
+            checkout_branch(repo_path, branch_ref)
+        }
+        CheckoutStrategy::Keep => autostash(repo_path, true, || {
+            checkout_branch(repo_path, branch_ref)
+        }),
+        CheckoutStrategy::Force => {
+            let repo = utils::repo(repo_path)?;
+            let cur_ref = repo.head()?;
+
+            repo.set_head(branch_ref)?;
+
+            if let Err(e) = repo.checkout_head(Some(
+                git2::build::CheckoutBuilder::new().force(),
+            )) {
+                // This is safe beacuse cur_ref was just found
+                repo.set_head(
+                    bytes2string(cur_ref.name_bytes())?.as_str(),
+                )?;
+                return Err(Error::Git(e));
+            }
+            Ok(())
+        }
+    }
+}
+
+/// checks out `commit` leaving `HEAD` detached, fails on
+/// uncommitted changes like `checkout_branch`
+pub fn checkout_commit(
+    repo_path: &RepoPath,
+    commit: CommitId,
+) -> Result<()> {
+    scope_time!("checkout_commit");
+
+    let _lock = InstanceLock::acquire(repo_path)?;
+
+    let repo = utils::repo(repo_path)?;
+    let cur_ref = repo.head()?;
+    let statuses = repo.statuses(Some(
+        git2::StatusOptions::new().include_ignored(false),
+    ))?;
+
+    if statuses.is_empty() {
+        repo.set_head_detached(commit.into())?;
+
+        if let Err(e) = repo.checkout_head(Some(
+            git2::build::CheckoutBuilder::new().force(),
+        )) {
+            // This is safe beacuse cur_ref was just found
+            repo.set_head(
+                bytes2string(cur_ref.name_bytes())?.as_str(),
+            )?;
+            return Err(Error::Git(e));
+        }
+        Ok(())
+    } else {
+        Err(Error::UncommittedChanges)
+    }
+}
+
+/// `HEAD` points to a commit instead of a branch
+pub fn is_head_detached(repo_path: &RepoPath) -> Result<bool> {
+    let repo = utils::repo(repo_path)?;
+
+    Ok(repo.head_detached()?)
+}
+
+/// creates a local branch tracking the remote `branch` and checks it
+/// out, a local branch already tracking it is checked out instead
 pub fn checkout_remote_branch(
-    repo_path: &str,
+    repo_path: &RepoPath,
     branch: &BranchInfo,
 ) -> Result<()> {
     scope_time!("checkout_remote_branch");
 
+    let _lock = InstanceLock::acquire(repo_path)?;
+
     let repo = utils::repo(repo_path)?;
     let cur_ref = repo.head()?;
 
@@ -283,17 +607,46 @@ pub fn checkout_remote_branch(
         return Err(Error::UncommittedChanges);
     }
 
-    let name = branch.name.rfind('/').map_or_else(
-        || branch.name.clone(),
-        |pos| branch.name[pos..].to_string(),
-    );
-
-    let commit = repo.find_commit(branch.top_commit.into())?;
-    let mut new_branch = repo.branch(&name, &commit, false)?;
-    new_branch.set_upstream(Some(&branch.name))?;
+    // remote names may contain slashes as well
+    let remote =
+        bytes2string(&repo.branch_remote_name(&branch.reference)?)?;
+    let name = branch
+        .name
+        .strip_prefix(&format!("{}/", remote))
+        .unwrap_or(&branch.name)
+        .to_string();
+
+    let local_branch = match repo
+        .find_branch(&name, BranchType::Local)
+    {
+        Ok(local_branch) => {
+            let upstream =
+                local_branch.upstream().ok().and_then(|upstream| {
+                    upstream.get().name().map(String::from)
+                });
+
+            if upstream.as_deref() != Some(branch.reference.as_str())
+            {
+                return Err(Error::Generic(format!(
+                    "local branch '{}' exists but does not track '{}'",
+                    name, branch.name
+                )));
+            }
+
+            local_branch
+        }
+        Err(_) => {
+            let commit =
+                repo.find_commit(branch.top_commit.into())?;
+            let mut new_branch =
+                repo.branch(&name, &commit, false)?;
+            new_branch.set_upstream(Some(&branch.name))?;
+            new_branch
+        }
+    };
 
     repo.set_head(
-        bytes2string(new_branch.into_reference().name_bytes())?
+        bytes2string(local_branch.into_reference().name_bytes())?
             .as_str(),
     )?;
 
@@ -309,11 +662,13 @@ pub fn checkout_remote_branch(
 
 /// The user must not be on the branch for the branch to be deleted
 pub fn delete_branch(
-    repo_path: &str,
+    repo_path: &RepoPath,
     branch_ref: &str,
 ) -> Result<()> {
     scope_time!("delete_branch");
 
+    let _lock = InstanceLock::acquire(repo_path)?;
+
     let repo = utils::repo(repo_path)?;
     let branch_as_ref = repo.find_reference(branch_ref)?;
     let mut branch = git2::Branch::wrap(branch_as_ref);
@@ -325,9 +680,11 @@ pub fn delete_branch(
 }
 
 /// creates a new branch pointing to current HEAD commit and updating HEAD to new branch
-pub fn create_branch(repo_path: &str, name: &str) -> Result<()> {
+pub fn create_branch(repo_path: &RepoPath, name: &str) -> Result<()> {
     scope_time!("create_branch");
 
+    let _lock = InstanceLock::acquire(repo_path)?;
+
     let repo = utils::repo(repo_path)?;
 
     let head_id = get_head_repo(&repo)?;
@@ -341,6 +698,24 @@ pub fn create_branch(repo_path: &str, name: &str) -> Result<()> {
     Ok(())
 }
 
+/// creates a new branch pointing to `commit` without checking it out
+pub fn create_branch_at(
+    repo_path: &RepoPath,
+    name: &str,
+    commit: CommitId,
+) -> Result<()> {
+    scope_time!("create_branch_at");
+
+    let _lock = InstanceLock::acquire(repo_path)?;
+
+    let repo = utils::repo(repo_path)?;
+
+    let commit = repo.find_commit(commit.into())?;
+    repo.branch(name, &commit, false)?;
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests_branch_name {
     use super::*;
@@ -350,7 +725,8 @@ mod tests_branch_name {
     fn test_smoke() {
         let (_td, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         assert_eq!(
             get_branch_name(repo_path).unwrap().as_str(),
@@ -362,7 +738,8 @@ mod tests_branch_name {
     fn test_empty_repo() {
         let (_td, repo) = repo_init_empty().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         assert!(matches!(
             get_branch_name(repo_path),
@@ -374,13 +751,17 @@ mod tests_branch_name {
 #[cfg(test)]
 mod tests_create_branch {
     use super::*;
-    use crate::sync::tests::repo_init;
+    use crate::sync::{
+        tests::{repo_init, write_commit_file},
+        utils::get_head,
+    };
 
     #[test]
     fn test_smoke() {
         let (_td, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         create_branch(repo_path, "branch1").unwrap();
 
@@ -389,6 +770,36 @@ mod tests_create_branch {
             "branch1"
         );
     }
+
+    #[test]
+    fn test_create_at() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        let first = write_commit_file(&repo, "a.txt", "a", "first");
+        let second = write_commit_file(&repo, "a.txt", "b", "second");
+
+        create_branch_at(repo_path, "at_first", first).unwrap();
+
+        assert_eq!(
+            get_branch_name(repo_path).unwrap().as_str(),
+            "master"
+        );
+        assert_eq!(get_head(repo_path).unwrap(), second);
+
+        let branch =
+            repo.find_branch("at_first", BranchType::Local).unwrap();
+        assert_eq!(
+            CommitId::new(branch.get().target().unwrap()),
+            first
+        );
+
+        assert!(
+            create_branch_at(repo_path, "at_first", second).is_err()
+        );
+    }
 }
 
 #[cfg(test)]
@@ -400,7 +811,8 @@ mod tests_branch_compare {
     fn test_smoke() {
         let (_td, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         create_branch(repo_path, "test").unwrap();
 
@@ -426,7 +838,8 @@ mod tests_branches {
     fn test_smoke() {
         let (_td, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         assert_eq!(
             get_branches_info(repo_path, true)
@@ -442,7 +855,8 @@ mod tests_branches {
     fn test_multiple() {
         let (_td, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         create_branch(repo_path, "test").unwrap();
 
@@ -456,9 +870,37 @@ mod tests_branches {
         );
     }
 
+    #[test]
+    fn test_info_for_names() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        create_branch(repo_path, "b").unwrap();
+        create_branch(repo_path, "a").unwrap();
+
+        let names = get_branch_names(repo_path, true).unwrap();
+        assert_eq!(names, vec!["a", "b", "master"]);
+
+        let names = vec![
+            String::from("master"),
+            String::from("gone"),
+            String::from("a"),
+        ];
+        assert_eq!(
+            get_branches_info_for(repo_path, true, &names)
+                .unwrap()
+                .iter()
+                .map(|b| b.name.clone())
+                .collect::<Vec<_>>(),
+            vec!["master", "a"]
+        );
+    }
+
     fn clone_branch_commit_push(target: &str, branch_name: &str) {
         let (dir, repo) = repo_clone(target).unwrap();
-        let dir = dir.path().to_str().unwrap();
+        let dir: &RepoPath = &dir.path().to_str().unwrap().into();
 
         write_commit_file(&repo, "f1.txt", "foo", "c1");
         rename_branch(dir, "refs/heads/master", branch_name).unwrap();
@@ -479,7 +921,8 @@ mod tests_branches {
         clone_branch_commit_push(r2_path, "r2branch");
 
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         //add the remotes
         repo.remote("r1", r1_path).unwrap();
@@ -551,7 +994,8 @@ mod tests_branches {
     fn test_branch_remote_no_upstream() {
         let (_r, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         assert_eq!(
             get_branch_remote(repo_path, "master").unwrap(),
@@ -563,7 +1007,8 @@ mod tests_branches {
     fn test_branch_remote_no_branch() {
         let (_r, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         assert!(get_branch_remote(repo_path, "foo").is_err());
     }
@@ -572,13 +1017,17 @@ mod tests_branches {
 #[cfg(test)]
 mod tests_checkout {
     use super::*;
-    use crate::sync::tests::repo_init;
+    use crate::sync::{
+        tests::{repo_init, write_commit_file},
+        utils::get_head,
+    };
 
     #[test]
     fn test_smoke() {
         let (_td, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         assert!(
             checkout_branch(repo_path, "refs/heads/master").is_ok()
@@ -592,7 +1041,8 @@ mod tests_checkout {
     fn test_multiple() {
         let (_td, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         create_branch(repo_path, "test").unwrap();
 
@@ -602,6 +1052,113 @@ mod tests_checkout {
         );
         assert!(checkout_branch(repo_path, "refs/heads/test").is_ok());
     }
+
+    #[test]
+    fn test_checkout_commit() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        let first = write_commit_file(&repo, "a.txt", "a", "first");
+        write_commit_file(&repo, "a.txt", "b", "second");
+
+        assert!(!is_head_detached(repo_path).unwrap());
+
+        checkout_commit(repo_path, first).unwrap();
+
+        assert!(is_head_detached(repo_path).unwrap());
+        assert_eq!(get_head(repo_path).unwrap(), first);
+        assert_eq!(
+            std::fs::read_to_string(root.join("a.txt")).unwrap(),
+            "a"
+        );
+
+        create_branch(repo_path, "from_detached").unwrap();
+
+        assert!(!is_head_detached(repo_path).unwrap());
+        assert_eq!(
+            get_branch_name(repo_path).unwrap().as_str(),
+            "from_detached"
+        );
+    }
+
+    #[test]
+    fn test_checkout_commit_uncommitted_changes() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        let first = write_commit_file(&repo, "a.txt", "a", "first");
+        write_commit_file(&repo, "a.txt", "b", "second");
+        std::fs::write(root.join("a.txt"), "c").unwrap();
+
+        assert!(matches!(
+            checkout_commit(repo_path, first),
+            Err(Error::UncommittedChanges)
+        ));
+        assert!(!is_head_detached(repo_path).unwrap());
+    }
+
+    #[test]
+    fn test_checkout_with_strategy() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        write_commit_file(&repo, "a.txt", "a", "first");
+        create_branch(repo_path, "other").unwrap();
+        write_commit_file(&repo, "b.txt", "b", "second");
+
+        let dirty =
+            || std::fs::write(root.join("a.txt"), "c").unwrap();
+        let content =
+            || std::fs::read_to_string(root.join("a.txt")).unwrap();
+        let stashes = || crate::sync::get_stashes(repo_path).unwrap();
+
+        dirty();
+        assert!(has_uncommitted_changes(repo_path).unwrap());
+        assert!(checkout_with_strategy(
+            repo_path,
+            "refs/heads/master",
+            CheckoutStrategy::Safe
+        )
+        .is_err());
+
+        checkout_with_strategy(
+            repo_path,
+            "refs/heads/master",
+            CheckoutStrategy::Keep,
+        )
+        .unwrap();
+        assert_eq!(content(), "c");
+        assert!(stashes().is_empty());
+
+        checkout_with_strategy(
+            repo_path,
+            "refs/heads/other",
+            CheckoutStrategy::Stash,
+        )
+        .unwrap();
+        assert_eq!(content(), "a");
+        assert_eq!(stashes().len(), 1);
+
+        dirty();
+        checkout_with_strategy(
+            repo_path,
+            "refs/heads/master",
+            CheckoutStrategy::Force,
+        )
+        .unwrap();
+        assert_eq!(content(), "a");
+        assert!(!has_uncommitted_changes(repo_path).unwrap());
+        assert_eq!(
+            get_branch_name(repo_path).unwrap().as_str(),
+            "master"
+        );
+    }
 }
 
 #[cfg(test)]
@@ -613,7 +1170,8 @@ mod test_delete_branch {
     fn test_delete_branch() {
         let (_td, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         create_branch(repo_path, "branch1").unwrap();
         create_branch(repo_path, "branch2").unwrap();
@@ -650,6 +1208,147 @@ mod test_delete_branch {
     }
 }
 
+#[cfg(test)]
+mod test_upstream {
+    use super::*;
+    use crate::sync::remotes::push::push;
+    use crate::sync::tests::{
+        repo_clone, repo_init_bare, write_commit_file,
+    };
+
+    #[test]
+    fn test_set_upstream() {
+        let (r1_dir, _repo) = repo_init_bare().unwrap();
+        let (clone_dir, clone) =
+            repo_clone(r1_dir.path().to_str().unwrap()).unwrap();
+        let clone_dir: &RepoPath =
+            &clone_dir.path().to_str().unwrap().into();
+
+        write_commit_file(&clone, "test.txt", "test", "commit1");
+        push(clone_dir, "origin", "master", false, None, None)
+            .unwrap();
+        create_branch(clone_dir, "foo").unwrap();
+
+        assert_eq!(get_upstream(clone_dir, "foo").unwrap(), None);
+
+        set_upstream(clone_dir, "foo", Some("origin/master"))
+            .unwrap();
+        assert_eq!(
+            get_upstream(clone_dir, "foo").unwrap().as_deref(),
+            Some("origin/master")
+        );
+        assert_eq!(
+            get_branch_remote(clone_dir, "foo").unwrap().as_deref(),
+            Some("origin")
+        );
+
+        assert!(set_upstream(clone_dir, "foo", Some("origin/none"))
+            .is_err());
+
+        set_upstream(clone_dir, "foo", None).unwrap();
+        assert_eq!(get_upstream(clone_dir, "foo").unwrap(), None);
+        set_upstream(clone_dir, "foo", None).unwrap();
+    }
+
+    #[test]
+    fn test_info_upstream_compare() {
+        let (r1_dir, _repo) = repo_init_bare().unwrap();
+        let (clone_dir, clone) =
+            repo_clone(r1_dir.path().to_str().unwrap()).unwrap();
+        let clone_dir: &RepoPath =
+            &clone_dir.path().to_str().unwrap().into();
+
+        write_commit_file(&clone, "test.txt", "test", "commit1");
+        push(clone_dir, "origin", "master", false, None, None)
+            .unwrap();
+        write_commit_file(&clone, "test2.txt", "test", "commit2");
+        create_branch(clone_dir, "foo").unwrap();
+
+        let infos = get_branches_info(clone_dir, true).unwrap();
+
+        let foo = infos[0].local_details().unwrap();
+        assert_eq!(infos[0].name, "foo");
+        assert_eq!(foo.upstream_compare, None);
+
+        let master = infos[1].local_details().unwrap();
+        assert_eq!(infos[1].top_commit_message, "commit2");
+        assert!(infos[1].top_commit_time > 0);
+        assert_eq!(
+            master.upstream_compare,
+            Some(BranchCompare {
+                ahead: 1,
+                behind: 0
+            })
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_push_remote {
+    use super::*;
+    use crate::sync::remotes::push::push;
+    use crate::sync::tests::{
+        repo_clone, repo_init_bare, write_commit_file,
+    };
+
+    #[test]
+    fn test_triangular() {
+        let (origin_dir, _origin) = repo_init_bare().unwrap();
+        let (fork_dir, _fork) = repo_init_bare().unwrap();
+        let (clone_dir, clone) =
+            repo_clone(origin_dir.path().to_str().unwrap()).unwrap();
+        let clone_dir: &RepoPath =
+            &clone_dir.path().to_str().unwrap().into();
+
+        write_commit_file(&clone, "test.txt", "test", "commit1");
+        push(clone_dir, "origin", "master", false, None, None)
+            .unwrap();
+
+        assert_eq!(
+            get_push_remote(clone_dir, "master").unwrap().as_deref(),
+            Some("origin")
+        );
+        assert!(branch_compare_push(clone_dir, "master")
+            .unwrap()
+            .is_none());
+
+        clone
+            .remote("fork", fork_dir.path().to_str().unwrap())
+            .unwrap();
+        clone
+            .config()
+            .unwrap()
+            .set_str("remote.pushDefault", "fork")
+            .unwrap();
+
+        let res = branch_compare_push(clone_dir, "master")
+            .unwrap()
+            .unwrap();
+        assert_eq!(res.remote, "fork");
+        assert!(res.compare.is_none());
+
+        push(clone_dir, "fork", "master", false, None, None).unwrap();
+        write_commit_file(&clone, "test.txt", "test2", "commit2");
+
+        let res = branch_compare_push(clone_dir, "master")
+            .unwrap()
+            .unwrap();
+        let compare = res.compare.unwrap();
+        assert_eq!(compare.ahead, 1);
+        assert_eq!(compare.behind, 0);
+
+        clone
+            .config()
+            .unwrap()
+            .set_str("branch.master.pushRemote", "origin")
+            .unwrap();
+        assert_eq!(
+            get_push_remote(clone_dir, "master").unwrap().as_deref(),
+            Some("origin")
+        );
+    }
+}
+
 #[cfg(test)]
 mod test_remote_branches {
     use super::*;
@@ -665,7 +1364,8 @@ mod test_remote_branches {
         let (clone1_dir, clone1) =
             repo_clone(r1_dir.path().to_str().unwrap()).unwrap();
 
-        let clone1_dir = clone1_dir.path().to_str().unwrap();
+        let clone1_dir: &RepoPath =
+            &clone1_dir.path().to_str().unwrap().into();
 
         // clone1
 
@@ -685,7 +1385,8 @@ mod test_remote_branches {
         let (clone2_dir, _clone2) =
             repo_clone(r1_dir.path().to_str().unwrap()).unwrap();
 
-        let clone2_dir = clone2_dir.path().to_str().unwrap();
+        let clone2_dir: &RepoPath =
+            &clone2_dir.path().to_str().unwrap().into();
 
         let local_branches =
             get_branches_info(clone2_dir, true).unwrap();
@@ -705,7 +1406,8 @@ mod test_remote_branches {
 
         let (clone1_dir, clone1) =
             repo_clone(r1_dir.path().to_str().unwrap()).unwrap();
-        let clone1_dir = clone1_dir.path().to_str().unwrap();
+        let clone1_dir: &RepoPath =
+            &clone1_dir.path().to_str().unwrap().into();
 
         // clone1
 
@@ -721,7 +1423,8 @@ mod test_remote_branches {
         let (clone2_dir, _clone2) =
             repo_clone(r1_dir.path().to_str().unwrap()).unwrap();
 
-        let clone2_dir = clone2_dir.path().to_str().unwrap();
+        let clone2_dir: &RepoPath =
+            &clone2_dir.path().to_str().unwrap().into();
 
         let local_branches =
             get_branches_info(clone2_dir, true).unwrap();
@@ -740,4 +1443,51 @@ mod test_remote_branches {
 
         assert_eq!(&get_branch_name(clone2_dir).unwrap(), "foo");
     }
+
+    #[test]
+    fn test_checkout_remote_branch_existing() {
+        let (r1_dir, _repo) = repo_init_bare().unwrap();
+
+        let (clone1_dir, clone1) =
+            repo_clone(r1_dir.path().to_str().unwrap()).unwrap();
+        let clone1_dir: &RepoPath =
+            &clone1_dir.path().to_str().unwrap().into();
+
+        write_commit_file(&clone1, "test.txt", "test", "commit1");
+        push(clone1_dir, "origin", "master", false, None, None)
+            .unwrap();
+        create_branch(clone1_dir, "foo").unwrap();
+        write_commit_file(&clone1, "test.txt", "test2", "commit2");
+        push(clone1_dir, "origin", "foo", false, None, None).unwrap();
+
+        let (clone2_dir, _clone2) =
+            repo_clone(r1_dir.path().to_str().unwrap()).unwrap();
+        let clone2_dir: &RepoPath =
+            &clone2_dir.path().to_str().unwrap().into();
+
+        let branches = get_branches_info(clone2_dir, false).unwrap();
+        assert_eq!(&branches[1].name, "origin/foo");
+
+        checkout_remote_branch(clone2_dir, &branches[1]).unwrap();
+        checkout_branch(clone2_dir, "refs/heads/master").unwrap();
+
+        // the tracking branch exists now and is reused
+        checkout_remote_branch(clone2_dir, &branches[1]).unwrap();
+
+        assert_eq!(
+            get_branches_info(clone2_dir, true).unwrap().len(),
+            2
+        );
+        assert_eq!(&get_branch_name(clone2_dir).unwrap(), "foo");
+
+        // a local branch of that name not tracking it is no match
+        checkout_branch(clone2_dir, "refs/heads/master").unwrap();
+        delete_branch(clone2_dir, "refs/heads/foo").unwrap();
+        create_branch(clone2_dir, "foo").unwrap();
+        checkout_branch(clone2_dir, "refs/heads/master").unwrap();
+
+        assert!(
+            checkout_remote_branch(clone2_dir, &branches[1]).is_err()
+        );
+    }
 }
@@ -1,16 +1,21 @@
 //! renaming of branches
 
-use crate::{error::Result, sync::utils};
+use crate::{
+    error::Result,
+    sync::{utils, InstanceLock, RepoPath},
+};
 use scopetime::scope_time;
 
 /// Rename the branch reference
 pub fn rename_branch(
-    repo_path: &str,
+    repo_path: &RepoPath,
     branch_ref: &str,
     new_name: &str,
 ) -> Result<()> {
     scope_time!("delete_branch");
 
+    let _lock = InstanceLock::acquire(repo_path)?;
+
     let repo = utils::repo(repo_path)?;
     let branch_as_ref = repo.find_reference(branch_ref)?;
     let mut branch = git2::Branch::wrap(branch_as_ref);
@@ -24,12 +29,14 @@ mod test {
     use super::super::*;
     use super::rename_branch;
     use crate::sync::tests::repo_init;
+    use crate::sync::RepoPath;
 
     #[test]
     fn test_rename_branch() {
         let (_td, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         create_branch(repo_path, "branch1").unwrap();
 
@@ -1,16 +1,24 @@
-use super::{get_head, utils::repo, CommitId};
-use crate::error::Result;
-use git2::{ErrorCode, ObjectType, Repository, Signature};
+use super::{
+    squash::is_pushed,
+    utils::{get_head_repo, repo},
+    CommitId, InstanceLock, RepoPath,
+};
+use crate::error::{Error, Result};
+use git2::{
+    ErrorCode, ObjectType, Repository, RepositoryState, Signature,
+};
 use scopetime::scope_time;
 
 ///
 pub fn amend(
-    repo_path: &str,
+    repo_path: &RepoPath,
     id: CommitId,
     msg: &str,
 ) -> Result<CommitId> {
     scope_time!("amend");
 
+    let _lock = InstanceLock::acquire(repo_path)?;
+
     let repo = repo(repo_path)?;
     let commit = repo.find_commit(id.into())?;
 
@@ -30,6 +38,50 @@ pub fn amend(
     Ok(CommitId::new(new_id))
 }
 
+/// amends the staged changes into `HEAD` keeping its message, refuses
+/// to rewrite a commit that is already pushed upstream
+pub fn amend_staged(repo_path: &RepoPath) -> Result<CommitId> {
+    scope_time!("amend_staged");
+
+    let _lock = InstanceLock::acquire(repo_path)?;
+
+    let repo = repo(repo_path)?;
+
+    if repo.state() != RepositoryState::Clean {
+        return Err(Error::Generic(
+            "cannot amend during a merge, rebase or revert"
+                .to_string(),
+        ));
+    }
+
+    let head = repo.head()?.peel_to_commit()?;
+
+    if is_pushed(&repo, head.id())? {
+        return Err(Error::Generic(
+            "HEAD is already pushed upstream".to_string(),
+        ));
+    }
+
+    let tree_id = repo.index()?.write_tree()?;
+    if tree_id == head.tree_id() {
+        return Err(Error::Generic(
+            "nothing staged to amend".to_string(),
+        ));
+    }
+
+    let tree = repo.find_tree(tree_id)?;
+    let new_id = head.amend(
+        Some("HEAD"),
+        None,
+        None,
+        None,
+        None,
+        Some(&tree),
+    )?;
+
+    Ok(CommitId::new(new_id))
+}
+
 /// Wrap `Repository::signature` to allow unknown user.name.
 ///
 /// See <https://github.com/extrawurst/gitui/issues/79>.
@@ -57,18 +109,103 @@ pub(crate) fn signature_allow_undefined_name(
     signature
 }
 
-/// this does not run any git hooks
-pub fn commit(repo_path: &str, msg: &str) -> Result<CommitId> {
+/// what `commit_with_options` records besides staged changes with a
+/// message
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct CommitOptions {
+    /// commit even if nothing is staged (`--allow-empty`)
+    pub allow_empty: bool,
+    /// commit without a message (`--allow-empty-message`)
+    pub allow_empty_message: bool,
+}
+
+/// commits without checking for staged changes or a message, this
+/// does not run any git hooks
+pub fn commit(repo_path: &RepoPath, msg: &str) -> Result<CommitId> {
+    commit_with_options(
+        repo_path,
+        msg,
+        CommitOptions {
+            allow_empty: true,
+            allow_empty_message: true,
+        },
+    )
+}
+
+/// commits the staged changes like `git commit`, refusing to record
+/// nothing or to use an empty message unless `options` allow it, this
+/// does not run any git hooks
+pub fn commit_with_options(
+    repo_path: &RepoPath,
+    msg: &str,
+    options: CommitOptions,
+) -> Result<CommitId> {
     scope_time!("commit");
 
+    let _lock = InstanceLock::acquire(repo_path)?;
+
     let repo = repo(repo_path)?;
 
+    if !options.allow_empty_message && msg.trim().is_empty() {
+        return Err(Error::Generic(
+            "empty commit message".to_string(),
+        ));
+    }
+
+    if !options.allow_empty && !has_staged_changes(&repo)? {
+        return Err(Error::Generic(
+            "nothing staged to commit".to_string(),
+        ));
+    }
+
     let signature = signature_allow_undefined_name(&repo)?;
+
+    commit_index(&repo, &signature, msg)
+}
+
+/// the index differs from `HEAD` (or has entries without a `HEAD`)
+fn has_staged_changes(repo: &Repository) -> Result<bool> {
+    let mut index = repo.index()?;
+
+    Ok(match get_head_repo(repo) {
+        Ok(head) => {
+            let head = repo.find_commit(head.into())?;
+            index.write_tree()? != head.tree_id()
+        }
+        Err(_) => !index.is_empty(),
+    })
+}
+
+/// commits the staged changes with message and author of `id`
+/// (`git commit --reuse-message`), this does not run any git hooks
+pub fn commit_with_msg_from(
+    repo_path: &RepoPath,
+    id: CommitId,
+) -> Result<CommitId> {
+    scope_time!("commit_with_msg_from");
+
+    let _lock = InstanceLock::acquire(repo_path)?;
+
+    let repo = repo(repo_path)?;
+
+    let source = repo.find_commit(id.into())?;
+    let msg = String::from_utf8_lossy(source.message_bytes());
+
+    commit_index(&repo, &source.author(), &msg)
+}
+
+/// commits the index on top of `HEAD` (if any)
+fn commit_index(
+    repo: &Repository,
+    author: &Signature,
+    msg: &str,
+) -> Result<CommitId> {
+    let committer = signature_allow_undefined_name(repo)?;
     let mut index = repo.index()?;
     let tree_id = index.write_tree()?;
     let tree = repo.find_tree(tree_id)?;
 
-    let parents = if let Ok(id) = get_head(repo_path) {
+    let parents = if let Ok(id) = get_head_repo(repo) {
         vec![repo.find_commit(id.into())?]
     } else {
         Vec::new()
@@ -79,8 +216,8 @@ pub fn commit(repo_path: &str, msg: &str) -> Result<CommitId> {
     Ok(repo
         .commit(
             Some("HEAD"),
-            &signature,
-            &signature,
+            author,
+            &committer,
             msg,
             &tree,
             parents.as_slice(),
@@ -93,12 +230,14 @@ pub fn commit(repo_path: &str, msg: &str) -> Result<CommitId> {
 /// This function will return an `Err(…)` variant if the tag’s name is refused
 /// by git or if the tag already exists.
 pub fn tag(
-    repo_path: &str,
+    repo_path: &RepoPath,
     commit_id: &CommitId,
     tag: &str,
 ) -> Result<CommitId> {
     scope_time!("tag");
 
+    let _lock = InstanceLock::acquire(repo_path)?;
+
     let repo = repo(repo_path)?;
 
     let signature = signature_allow_undefined_name(&repo)?;
@@ -111,6 +250,7 @@ pub fn tag(
 
 #[cfg(test)]
 mod tests {
+    use crate::sync::RepoPath;
 
     use crate::error::Result;
     use crate::sync::{
@@ -120,7 +260,10 @@ mod tests {
         utils::get_head,
         LogWalker,
     };
-    use commit::{amend, tag};
+    use commit::{
+        amend, amend_staged, commit_with_msg_from,
+        commit_with_options, tag, CommitOptions,
+    };
     use git2::Repository;
     use std::{fs::File, io::Write, path::Path};
 
@@ -136,7 +279,8 @@ mod tests {
         let file_path = Path::new("foo");
         let (_td, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         File::create(&root.join(file_path))
             .unwrap()
@@ -159,7 +303,8 @@ mod tests {
         let file_path = Path::new("foo");
         let (_td, repo) = repo_init_empty().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         assert_eq!(get_statuses(repo_path), (0, 0));
 
@@ -185,7 +330,8 @@ mod tests {
         let file_path2 = Path::new("foo2");
         let (_td, repo) = repo_init_empty()?;
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         File::create(&root.join(file_path1))?.write_all(b"test1")?;
 
@@ -216,12 +362,114 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_amend_staged() -> Result<()> {
+        let (_td, repo) = repo_init_empty()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        File::create(&root.join("foo"))?.write_all(b"test1")?;
+        stage_add_file(repo_path, Path::new("foo"))?;
+        let id = commit(repo_path, "commit msg\n\nbody")?;
+        let msg = get_commit_details(repo_path, id)?.message.unwrap();
+
+        assert!(amend_staged(repo_path).is_err());
+
+        File::create(&root.join("foo2"))?.write_all(b"test2")?;
+        stage_add_file(repo_path, Path::new("foo2"))?;
+
+        let new_id = amend_staged(repo_path)?;
+
+        assert_eq!(count_commits(&repo, 10), 1);
+        assert_eq!(get_head(repo_path)?, new_id);
+        assert_eq!(get_commit_files(repo_path, new_id)?.len(), 2);
+
+        let new_msg =
+            get_commit_details(repo_path, new_id)?.message.unwrap();
+        assert_eq!(new_msg.combine(), msg.combine());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_commit_with_options() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        let strict = CommitOptions::default();
+
+        assert!(
+            commit_with_options(repo_path, "empty", strict).is_err()
+        );
+
+        let empty = commit_with_options(
+            repo_path,
+            "empty",
+            CommitOptions {
+                allow_empty: true,
+                ..strict
+            },
+        )?;
+        assert_eq!(get_head(repo_path)?, empty);
+        assert_eq!(get_commit_files(repo_path, empty)?.len(), 0);
+
+        File::create(&root.join("foo"))?.write_all(b"a")?;
+        stage_add_file(repo_path, Path::new("foo"))?;
+
+        assert!(
+            commit_with_options(repo_path, " \n", strict).is_err()
+        );
+
+        let no_msg = commit_with_options(
+            repo_path,
+            "",
+            CommitOptions {
+                allow_empty_message: true,
+                ..strict
+            },
+        )?;
+        assert_eq!(get_head(repo_path)?, no_msg);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_commit_with_msg_from() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        File::create(&root.join("foo"))?.write_all(b"a")?;
+        stage_add_file(repo_path, Path::new("foo"))?;
+        let first = commit(repo_path, "reused msg\n\nbody")?;
+
+        File::create(&root.join("foo"))?.write_all(b"b")?;
+        stage_add_file(repo_path, Path::new("foo"))?;
+        let second = commit_with_msg_from(repo_path, first)?;
+
+        assert_eq!(get_head(repo_path)?, second);
+        assert_eq!(get_statuses(repo_path), (0, 0));
+
+        let details = get_commit_details(repo_path, second)?;
+        assert_eq!(
+            details.message.unwrap().combine(),
+            "reused msg\n\nbody"
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_tag() -> Result<()> {
         let file_path = Path::new("foo");
         let (_td, repo) = repo_init_empty().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         File::create(&root.join(file_path))?
             .write_all(b"test\nfoo")?;
@@ -265,7 +513,8 @@ mod tests {
         let file_path = Path::new("foo");
         let (_td, repo) = repo_init_empty().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         File::create(&root.join(file_path))?
             .write_all(b"test\nfoo")?;
@@ -300,7 +549,8 @@ mod tests {
         let file_path = Path::new("foo");
         let (_td, repo) = repo_init_empty().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         File::create(&root.join(file_path))?
             .write_all(b"test\nfoo")?;
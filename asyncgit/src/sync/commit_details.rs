@@ -1,4 +1,10 @@
-use super::{commits_info::get_message, utils::repo, CommitId};
+use super::{
+    commits_info::get_message,
+    replace::Replacements,
+    signature::{verify_signature, SignatureInfo},
+    utils::repo,
+    CommitId, RepoPath,
+};
 use crate::error::Result;
 use git2::Signature;
 use scopetime::scope_time;
@@ -78,18 +84,24 @@ pub struct CommitDetails {
     pub message: Option<CommitMessage>,
     ///
     pub hash: String,
+    /// shown with the content or parents of a replacement (see
+    /// `use_replace_refs`)
+    pub replaced: bool,
+    /// checked signature, `None` if the commit is unsigned
+    pub signature: Option<SignatureInfo>,
 }
 
 ///
 pub fn get_commit_details(
-    repo_path: &str,
+    repo_path: &RepoPath,
     id: CommitId,
 ) -> Result<CommitDetails> {
     scope_time!("get_commit_details");
 
     let repo = repo(repo_path)?;
 
-    let commit = repo.find_commit(id.into())?;
+    let replacements = Replacements::new(&repo)?;
+    let commit = replacements.commit(&repo, id.into())?;
 
     let author = CommitSignature::from(&commit.author());
     let committer = CommitSignature::from(&commit.committer());
@@ -107,6 +119,8 @@ pub fn get_commit_details(
         committer,
         message: Some(msg),
         hash: id.to_string(),
+        replaced: replacements.is_replaced(id.into()),
+        signature: verify_signature(&repo, commit.id())?,
     };
 
     Ok(details)
@@ -114,6 +128,7 @@ pub fn get_commit_details(
 
 #[cfg(test)]
 mod tests {
+    use crate::sync::RepoPath;
 
     use super::{get_commit_details, CommitMessage};
     use crate::error::Result;
@@ -127,7 +142,8 @@ mod tests {
         let file_path = Path::new("foo");
         let (_td, repo) = repo_init_empty().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         File::create(&root.join(file_path))?.write_all(b"a")?;
         stage_add_file(repo_path, file_path).unwrap();
@@ -1,33 +1,29 @@
-use super::{stash::is_stash_commit, utils::repo, CommitId};
-use crate::{
-    error::Error, error::Result, StatusItem, StatusItemType,
+use super::{
+    diff::DiffOptions, stash::is_stash_commit, utils::repo, CommitId,
+    RepoPath,
+};
+use crate::{error::Result, StatusItem, StatusItemType};
+use git2::{
+    Diff, DiffDelta, DiffOptions as GitDiffOptions, Repository,
 };
-use git2::{Diff, DiffDelta, DiffOptions, Repository};
 use scopetime::scope_time;
 
 /// get all files that are part of a commit
 pub fn get_commit_files(
-    repo_path: &str,
+    repo_path: &RepoPath,
     id: CommitId,
 ) -> Result<Vec<StatusItem>> {
     scope_time!("get_commit_files");
 
     let repo = repo(repo_path)?;
 
-    let diff = get_commit_diff(&repo, id, None)?;
+    let diff = get_commit_diff(&repo, id, None, None)?;
 
     let mut res = Vec::new();
 
     diff.foreach(
         &mut |delta: DiffDelta<'_>, _progress| {
-            res.push(StatusItem {
-                path: delta
-                    .new_file()
-                    .path()
-                    .map(|p| p.to_str().unwrap_or("").to_string())
-                    .unwrap_or_default(),
-                status: StatusItemType::from(delta.status()),
-            });
+            res.push(delta_to_item(&delta));
             true
         },
         None,
@@ -38,11 +34,71 @@ pub fn get_commit_files(
     Ok(res)
 }
 
+/// get all files changed between the commits `from` and `to`
+pub fn get_compare_files(
+    repo_path: &RepoPath,
+    from: CommitId,
+    to: CommitId,
+) -> Result<Vec<StatusItem>> {
+    scope_time!("get_compare_files");
+
+    let repo = repo(repo_path)?;
+
+    let diff = get_compare_diff(&repo, from, to, None, None)?;
+
+    Ok(diff.deltas().map(|delta| delta_to_item(&delta)).collect())
+}
+
+fn delta_to_item(delta: &DiffDelta<'_>) -> StatusItem {
+    StatusItem {
+        path: delta
+            .new_file()
+            .path()
+            .map(|p| p.to_str().unwrap_or("").to_string())
+            .unwrap_or_default(),
+        status: StatusItemType::from(delta.status()),
+        submodule: None,
+        nested_repo: false,
+        summarized: false,
+    }
+}
+
+/// diff of the tree of `from` to the tree of `to`
+#[allow(clippy::redundant_pub_crate)]
+pub(crate) fn get_compare_diff(
+    repo: &Repository,
+    from: CommitId,
+    to: CommitId,
+    pathspec: Option<String>,
+    options: Option<DiffOptions>,
+) -> Result<Diff<'_>> {
+    let from_tree = repo.find_commit(from.into())?.tree()?;
+    let to_tree = repo.find_commit(to.into())?.tree()?;
+
+    let mut opts = GitDiffOptions::new();
+    if let Some(options) = options {
+        options.apply(&mut opts);
+    }
+    if let Some(p) = &pathspec {
+        opts.pathspec(p.clone());
+    }
+    opts.show_binary(true);
+
+    let diff = repo.diff_tree_to_tree(
+        Some(&from_tree),
+        Some(&to_tree),
+        Some(&mut opts),
+    )?;
+
+    Ok(diff)
+}
+
 #[allow(clippy::redundant_pub_crate)]
 pub(crate) fn get_commit_diff(
     repo: &Repository,
     id: CommitId,
     pathspec: Option<String>,
+    options: Option<DiffOptions>,
 ) -> Result<Diff<'_>> {
     // scope_time!("get_commit_diff");
 
@@ -54,7 +110,10 @@ pub(crate) fn get_commit_diff(
         None
     };
 
-    let mut opts = DiffOptions::new();
+    let mut opts = GitDiffOptions::new();
+    if let Some(options) = options {
+        options.apply(&mut opts);
+    }
     if let Some(p) = &pathspec {
         opts.pathspec(p.clone());
     }
@@ -66,18 +125,13 @@ pub(crate) fn get_commit_diff(
         Some(&mut opts),
     )?;
 
-    if is_stash_commit(
-        repo.path().to_str().map_or_else(
-            || Err(Error::Generic("repo path utf8 err".to_owned())),
-            Ok,
-        )?,
-        &id,
-    )? {
+    if is_stash_commit(repo, &id)? {
         if let Ok(untracked_commit) = commit.parent_id(2) {
             let untracked_diff = get_commit_diff(
                 repo,
                 CommitId::new(untracked_commit),
                 pathspec,
+                options,
             )?;
 
             diff.merge(&untracked_diff)?;
@@ -89,12 +143,13 @@ pub(crate) fn get_commit_diff(
 
 #[cfg(test)]
 mod tests {
-    use super::get_commit_files;
+    use super::{get_commit_files, get_compare_files};
+    use crate::sync::RepoPath;
     use crate::{
         error::Result,
         sync::{
-            commit, stage_add_file, stash_save,
-            tests::{get_statuses, repo_init},
+            commit, merge_base, stage_add_file, stash_save,
+            tests::{get_statuses, repo_init, write_commit_file},
         },
         StatusItemType,
     };
@@ -105,7 +160,8 @@ mod tests {
         let file_path = Path::new("file1.txt");
         let (_td, repo) = repo_init()?;
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         File::create(&root.join(file_path))?
             .write_all(b"test file1 content")?;
@@ -127,12 +183,13 @@ mod tests {
         let file_path = Path::new("file1.txt");
         let (_td, repo) = repo_init()?;
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         File::create(&root.join(file_path))?
             .write_all(b"test file1 content")?;
 
-        let id = stash_save(repo_path, None, true, false)?;
+        let id = stash_save(repo_path, None, true, false, false)?;
 
         let diff = get_commit_files(repo_path, id)?;
 
@@ -148,7 +205,8 @@ mod tests {
         let file_path2 = Path::new("file2.txt");
         let (_td, repo) = repo_init()?;
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         File::create(&root.join(file_path1))?.write_all(b"test")?;
         stage_add_file(repo_path, file_path1)?;
@@ -160,7 +218,7 @@ mod tests {
 
         assert_eq!(get_statuses(repo_path), (2, 0));
 
-        let id = stash_save(repo_path, None, true, false)?;
+        let id = stash_save(repo_path, None, true, false, false)?;
 
         let diff = get_commit_files(repo_path, id)?;
 
@@ -170,4 +228,28 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_compare_files() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        let base = write_commit_file(&repo, "a.txt", "a", "base");
+        write_commit_file(&repo, "a.txt", "b", "change");
+        let head = write_commit_file(&repo, "b.txt", "b", "add");
+
+        assert_eq!(merge_base(repo_path, base, head)?, base);
+
+        let files = get_compare_files(repo_path, base, head)?;
+
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].path, "a.txt");
+        assert_eq!(files[0].status, StatusItemType::Modified);
+        assert_eq!(files[1].path, "b.txt");
+        assert_eq!(files[1].status, StatusItemType::New);
+
+        Ok(())
+    }
 }
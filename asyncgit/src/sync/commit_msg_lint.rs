@@ -0,0 +1,162 @@
+//! sanity checks of commit messages before committing
+
+/// words that look like past tense, gerund or third person but are
+/// fine in imperative mood
+const IMPERATIVE_EXCEPTIONS: &[&str] = &[
+    "bring", "embed", "feed", "focus", "need", "proceed", "process",
+    "seed", "shed", "speed", "string", "succeed",
+];
+
+/// which checks `lint_commit_msg` runs
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CommitMsgLintOptions {
+    /// warn about subject lines longer than this, `0` disables
+    pub subject_max_len: usize,
+    /// warn if the line after the subject is not empty
+    pub blank_line_after_subject: bool,
+    /// warn if the subject does not start with a verb in
+    /// imperative mood (heuristic)
+    pub imperative_mood: bool,
+}
+
+impl Default for CommitMsgLintOptions {
+    fn default() -> Self {
+        Self {
+            subject_max_len: 50,
+            blank_line_after_subject: true,
+            imperative_mood: true,
+        }
+    }
+}
+
+/// single issue found in a commit message
+#[derive(Debug, Clone, PartialEq)]
+pub enum CommitMsgLint {
+    /// subject is longer than allowed, contains the length
+    SubjectTooLong(usize),
+    /// subject is directly followed by the body
+    MissingBlankLine,
+    /// first word of the subject does not look imperative
+    NotImperative(String),
+}
+
+/// checks `msg` according to `options`, returns all found issues
+pub fn lint_commit_msg(
+    msg: &str,
+    options: &CommitMsgLintOptions,
+) -> Vec<CommitMsgLint> {
+    let mut lines = msg.lines();
+    let subject = lines.next().unwrap_or_default();
+    let mut res = Vec::new();
+
+    let subject_len = subject.chars().count();
+    if options.subject_max_len > 0
+        && subject_len > options.subject_max_len
+    {
+        res.push(CommitMsgLint::SubjectTooLong(subject_len));
+    }
+
+    if options.blank_line_after_subject {
+        if let Some(line) = lines.next() {
+            if !line.trim().is_empty() {
+                res.push(CommitMsgLint::MissingBlankLine);
+            }
+        }
+    }
+
+    if options.imperative_mood {
+        if let Some(word) = first_word(subject) {
+            if !looks_imperative(word) {
+                res.push(CommitMsgLint::NotImperative(
+                    word.to_string(),
+                ));
+            }
+        }
+    }
+
+    res
+}
+
+/// first word of the subject, skipping prefixes like `fix:`,
+/// `ui(diff):` or `[#123]`
+fn first_word(subject: &str) -> Option<&str> {
+    subject.split_whitespace().find(|word| {
+        !(word.ends_with(':')
+            || (word.starts_with('[') && word.ends_with(']')))
+    })
+}
+
+fn looks_imperative(word: &str) -> bool {
+    let word = word.to_lowercase();
+
+    if !word.chars().all(char::is_alphabetic)
+        || IMPERATIVE_EXCEPTIONS.contains(&word.as_str())
+    {
+        return true;
+    }
+
+    let third_person = word.ends_with('s')
+        && !word.ends_with("ss")
+        && !word.ends_with("us")
+        && !word.ends_with("is");
+
+    !(word.ends_with("ed") || word.ends_with("ing") || third_person)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        lint_commit_msg, CommitMsgLint, CommitMsgLintOptions,
+    };
+
+    fn lint(msg: &str) -> Vec<CommitMsgLint> {
+        lint_commit_msg(msg, &CommitMsgLintOptions::default())
+    }
+
+    #[test]
+    fn test_clean_msg() {
+        assert!(lint("Add foo\n\nbecause of bar").is_empty());
+        assert!(lint("fix: process [#12] entries").is_empty());
+        assert!(lint("[#12] Embed fonts").is_empty());
+        assert!(lint("").is_empty());
+    }
+
+    #[test]
+    fn test_subject_too_long() {
+        let subject = "a".repeat(51);
+        assert_eq!(
+            lint(&subject),
+            vec![CommitMsgLint::SubjectTooLong(51)]
+        );
+
+        let options = CommitMsgLintOptions {
+            subject_max_len: 0,
+            ..CommitMsgLintOptions::default()
+        };
+        assert!(lint_commit_msg(&subject, &options).is_empty());
+    }
+
+    #[test]
+    fn test_missing_blank_line() {
+        assert_eq!(
+            lint("Add foo\nbody"),
+            vec![CommitMsgLint::MissingBlankLine]
+        );
+    }
+
+    #[test]
+    fn test_not_imperative() {
+        assert_eq!(
+            lint("Added foo"),
+            vec![CommitMsgLint::NotImperative("Added".into())]
+        );
+        assert_eq!(
+            lint("ui: fixes crash"),
+            vec![CommitMsgLint::NotImperative("fixes".into())]
+        );
+        assert_eq!(
+            lint("Adding foo"),
+            vec![CommitMsgLint::NotImperative("Adding".into())]
+        );
+    }
+}
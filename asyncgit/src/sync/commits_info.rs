@@ -1,6 +1,6 @@
-use super::utils::repo;
+use super::{replace::Replacements, utils::repo, RepoPath};
 use crate::error::Result;
-use git2::{Commit, Error, Oid};
+use git2::{Commit, Oid};
 use scopetime::scope_time;
 use unicode_truncate::UnicodeTruncateStr;
 
@@ -58,26 +58,32 @@ pub struct CommitInfo {
     pub author: String,
     ///
     pub id: CommitId,
+    /// shown with the content or parents of a replacement (see
+    /// `use_replace_refs`)
+    pub replaced: bool,
 }
 
 ///
 pub fn get_commits_info(
-    repo_path: &str,
+    repo_path: &RepoPath,
     ids: &[CommitId],
     message_length_limit: usize,
 ) -> Result<Vec<CommitInfo>> {
     scope_time!("get_commits_info");
 
     let repo = repo(repo_path)?;
+    let replacements = Replacements::new(&repo)?;
 
     let commits = ids
         .iter()
-        .map(|id| repo.find_commit((*id).into()))
-        .collect::<std::result::Result<Vec<Commit>, Error>>()?
+        .map(|id| {
+            replacements.commit(&repo, (*id).into()).map(|c| (*id, c))
+        })
+        .collect::<Result<Vec<_>>>()?
         .into_iter();
 
     let res = commits
-        .map(|c: Commit| {
+        .map(|(id, c)| {
             let message = get_message(&c, Some(message_length_limit));
             let author = c.author().name().map_or_else(
                 || String::from("<unknown>"),
@@ -87,7 +93,8 @@ pub fn get_commits_info(
                 message,
                 author,
                 time: c.time().seconds(),
-                id: CommitId(c.id()),
+                id,
+                replaced: replacements.is_replaced(id.into()),
             }
         })
         .collect::<Vec<_>>();
@@ -97,21 +104,23 @@ pub fn get_commits_info(
 
 ///
 pub fn get_commit_info(
-    repo_path: &str,
+    repo_path: &RepoPath,
     commit_id: &CommitId,
 ) -> Result<CommitInfo> {
     scope_time!("get_commit_info");
 
     let repo = repo(repo_path)?;
+    let replacements = Replacements::new(&repo)?;
 
-    let commit = repo.find_commit((*commit_id).into())?;
+    let commit = replacements.commit(&repo, (*commit_id).into())?;
     let author = commit.author();
 
     Ok(CommitInfo {
         message: commit.message().unwrap_or("").into(),
         author: author.name().unwrap_or("<unknown>").into(),
         time: commit.time().seconds(),
-        id: CommitId(commit.id()),
+        id: *commit_id,
+        replaced: replacements.is_replaced((*commit_id).into()),
     })
 }
 
@@ -137,6 +146,7 @@ pub fn get_message(
 mod tests {
     use super::get_commits_info;
     use crate::error::Result;
+    use crate::sync::RepoPath;
     use crate::sync::{
         commit, stage_add_file, tests::repo_init_empty,
         utils::get_head_repo,
@@ -148,7 +158,8 @@ mod tests {
         let file_path = Path::new("foo");
         let (_td, repo) = repo_init_empty().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         File::create(&root.join(file_path))?.write_all(b"a")?;
         stage_add_file(repo_path, file_path).unwrap();
@@ -173,7 +184,8 @@ mod tests {
         let file_path = Path::new("foo");
         let (_td, repo) = repo_init_empty().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         File::create(&root.join(file_path))?.write_all(b"a")?;
         stage_add_file(repo_path, file_path).unwrap();
@@ -192,7 +204,8 @@ mod tests {
         let file_path = Path::new("foo");
         let (_td, repo) = repo_init_empty().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         File::create(&root.join(file_path))?.write_all(b"a")?;
         stage_add_file(repo_path, file_path).unwrap();
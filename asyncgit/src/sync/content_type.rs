@@ -0,0 +1,116 @@
+//! rough classification of file content (text, image, binary)
+
+/// git looks at the same amount of bytes to decide if a file is binary
+const BINARY_CHECK_SIZE: usize = 8000;
+
+/// well known image formats we can detect by their magic bytes
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum ImageType {
+    ///
+    Png,
+    ///
+    Jpeg,
+    ///
+    Gif,
+    ///
+    Bmp,
+    ///
+    Ico,
+    ///
+    Webp,
+    ///
+    Tiff,
+}
+
+impl ImageType {
+    /// short lowercase name of the format
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Jpeg => "jpeg",
+            Self::Gif => "gif",
+            Self::Bmp => "bmp",
+            Self::Ico => "ico",
+            Self::Webp => "webp",
+            Self::Tiff => "tiff",
+        }
+    }
+
+    fn detect(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+            Some(Self::Png)
+        } else if bytes.starts_with(b"\xff\xd8\xff") {
+            Some(Self::Jpeg)
+        } else if bytes.starts_with(b"GIF87a")
+            || bytes.starts_with(b"GIF89a")
+        {
+            Some(Self::Gif)
+        } else if bytes.starts_with(b"BM") && bytes.len() > 14 {
+            Some(Self::Bmp)
+        } else if bytes.starts_with(b"\x00\x00\x01\x00") {
+            Some(Self::Ico)
+        } else if bytes.starts_with(b"RIFF")
+            && bytes.get(8..12) == Some(b"WEBP")
+        {
+            Some(Self::Webp)
+        } else if bytes.starts_with(b"II*\x00")
+            || bytes.starts_with(b"MM\x00*")
+        {
+            Some(Self::Tiff)
+        } else {
+            None
+        }
+    }
+}
+
+/// content type of a file
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub enum ContentType {
+    ///
+    Text,
+    ///
+    Image(ImageType),
+    /// any other non text content
+    Binary,
+}
+
+impl Default for ContentType {
+    fn default() -> Self {
+        Self::Text
+    }
+}
+
+/// detects the content type by looking at magic bytes and for `NUL`
+/// bytes the way git does it
+pub fn content_type(bytes: &[u8]) -> ContentType {
+    if let Some(image) = ImageType::detect(bytes) {
+        return ContentType::Image(image);
+    }
+
+    let check = &bytes[..bytes.len().min(BINARY_CHECK_SIZE)];
+    if check.contains(&0) {
+        ContentType::Binary
+    } else {
+        ContentType::Text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{content_type, ContentType, ImageType};
+
+    #[test]
+    fn test_content_type() {
+        assert_eq!(content_type(b"foo\nbar"), ContentType::Text);
+        assert_eq!(content_type(b""), ContentType::Text);
+        assert_eq!(content_type(b"\x00\x02"), ContentType::Binary);
+        assert_eq!(
+            content_type(b"\x89PNG\r\n\x1a\n\x00\x00"),
+            ContentType::Image(ImageType::Png)
+        );
+        assert_eq!(
+            content_type(b"RIFF\x00\x00\x00\x00WEBPVP8 "),
+            ContentType::Image(ImageType::Webp)
+        );
+    }
+}
@@ -0,0 +1,178 @@
+//! refs shown next to the commits they point to in the log, like
+//! `git log --decorate`
+
+use super::{
+    tags::tag_target, utils::repo, CommitId, RepoPath, Tags,
+};
+use crate::error::Result;
+use git2::Oid;
+use scopetime::scope_time;
+use std::collections::BTreeMap;
+
+/// kind of ref a decoration stands for
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DecorationKind {
+    ///
+    LocalBranch,
+    /// remote-tracking branch like `origin/master`
+    RemoteBranch,
+    /// tag object with a message (and maybe a signature)
+    AnnotatedTag,
+    /// tag ref pointing at the commit itself
+    LightweightTag,
+}
+
+impl DecorationKind {
+    ///
+    pub const fn is_tag(self) -> bool {
+        matches!(self, Self::AnnotatedTag | Self::LightweightTag)
+    }
+}
+
+/// a ref pointing to a commit
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Decoration {
+    /// short name, `origin/master` for remote branches
+    pub name: String,
+    ///
+    pub kind: DecorationKind,
+}
+
+/// decorations of each commit: local branches, then remote branches,
+/// then tags
+pub type Decorations = BTreeMap<CommitId, Vec<Decoration>>;
+
+/// branches, remote-tracking branches and tags by the commit they
+/// point to
+pub fn get_decorations(repo_path: &RepoPath) -> Result<Decorations> {
+    scope_time!("get_decorations");
+
+    let repo = repo(repo_path)?;
+
+    let mut res = Decorations::new();
+    let mut add = |id: Oid, name: String, kind| {
+        res.entry(CommitId::new(id))
+            .or_insert_with(Vec::new)
+            .push(Decoration { name, kind });
+    };
+
+    for (glob, kind) in &[
+        ("refs/heads/*", DecorationKind::LocalBranch),
+        ("refs/remotes/*", DecorationKind::RemoteBranch),
+    ] {
+        for reference in repo.references_glob(glob)? {
+            let reference = reference?;
+
+            // symbolic refs like `origin/HEAD` have no direct target
+            if let (Some(name), Some(target)) =
+                (reference.shorthand(), reference.target())
+            {
+                add(target, name.to_string(), *kind);
+            }
+        }
+    }
+
+    for reference in repo.references_glob("refs/tags/*")? {
+        let reference = reference?;
+
+        if let (Some(name), Some((target, annotated))) =
+            (reference.shorthand(), tag_target(&repo, &reference))
+        {
+            add(
+                target,
+                name.to_string(),
+                if annotated {
+                    DecorationKind::AnnotatedTag
+                } else {
+                    DecorationKind::LightweightTag
+                },
+            );
+        }
+    }
+
+    Ok(res)
+}
+
+/// the tag names among `decorations`
+pub fn decoration_tags(decorations: &Decorations) -> Tags {
+    decorations
+        .iter()
+        .filter_map(|(id, decorations)| {
+            let tags: Vec<String> = decorations
+                .iter()
+                .filter(|decoration| decoration.kind.is_tag())
+                .map(|decoration| decoration.name.clone())
+                .collect();
+
+            if tags.is_empty() {
+                None
+            } else {
+                Some((*id, tags))
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::tests::{debug_cmd_print, repo_init};
+    use git2::ObjectType;
+
+    #[test]
+    fn test_decorations() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        let sig = repo.signature().unwrap();
+        let head_id = repo.head().unwrap().target().unwrap();
+        let target = repo
+            .find_object(head_id, Some(ObjectType::Commit))
+            .unwrap();
+
+        repo.tag("annotated", &target, &sig, "", false).unwrap();
+        repo.tag_lightweight("lightweight", &target, false).unwrap();
+        repo.reference(
+            "refs/remotes/origin/master",
+            head_id,
+            false,
+            "",
+        )
+        .unwrap();
+        repo.reference_symbolic(
+            "refs/remotes/origin/HEAD",
+            "refs/remotes/origin/master",
+            false,
+            "",
+        )
+        .unwrap();
+
+        debug_cmd_print(repo_path, "git pack-refs --all");
+
+        let decorations = get_decorations(repo_path).unwrap();
+        let decorations: Vec<(&str, DecorationKind)> = decorations
+            [&CommitId::new(head_id)]
+            .iter()
+            .map(|d| (d.name.as_str(), d.kind))
+            .collect();
+
+        assert_eq!(
+            decorations,
+            vec![
+                ("master", DecorationKind::LocalBranch),
+                ("origin/master", DecorationKind::RemoteBranch),
+                ("annotated", DecorationKind::AnnotatedTag),
+                ("lightweight", DecorationKind::LightweightTag),
+            ]
+        );
+
+        let tags =
+            decoration_tags(&get_decorations(repo_path).unwrap());
+        assert_eq!(
+            tags[&CommitId::new(head_id)],
+            vec!["annotated", "lightweight"]
+        );
+    }
+}
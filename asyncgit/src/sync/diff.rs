@@ -1,15 +1,21 @@
 //! sync git api for fetching a diff
 
 use super::{
-    commit_files::get_commit_diff,
+    commit_files::{get_commit_diff, get_compare_diff},
+    content_type::{content_type, ContentType},
+    funcname::{
+        header_function, header_with_function, FuncnameMatcher,
+    },
+    submodules::{submodule_log, SubmoduleLog},
     utils::{self, get_head_repo, work_dir},
-    CommitId,
+    word_diff::highlight_changes,
+    CommitId, RepoPath,
 };
 use crate::{error::Error, error::Result, hash};
 use easy_cast::Conv;
 use git2::{
-    Delta, Diff, DiffDelta, DiffFormat, DiffHunk, DiffOptions, Patch,
-    Repository,
+    Delta, Diff, DiffDelta, DiffFormat, DiffHunk,
+    DiffOptions as GitDiffOptions, Patch, Repository,
 };
 use scopetime::scope_time;
 use std::{cell::RefCell, fs, path::Path, rc::Rc};
@@ -55,6 +61,17 @@ pub struct DiffLine {
     pub line_type: DiffLineType,
     ///
     pub position: DiffLinePosition,
+    /// intra-line ranges that differ from the paired line
+    pub changes: Vec<LineChange>,
+}
+
+/// byte range inside of `DiffLine::content` that changed
+#[derive(Clone, Copy, Default, Hash, Debug, PartialEq, Eq)]
+pub struct LineChange {
+    ///
+    pub start: usize,
+    ///
+    pub end: usize,
 }
 
 ///
@@ -90,6 +107,32 @@ pub(crate) struct HunkHeader {
     pub new_lines: u32,
 }
 
+impl HunkHeader {
+    /// `true` if `line` is an addition or deletion within the lines
+    /// this hunk spans
+    pub fn spans_change(&self, line: &git2::DiffLine) -> bool {
+        let within = |lineno: Option<u32>, start: u32, lines: u32| {
+            lineno.map_or(false, |lineno| {
+                lineno >= start && lineno < start + lines
+            })
+        };
+
+        match line.origin_value() {
+            git2::DiffLineType::Addition => within(
+                line.new_lineno(),
+                self.new_start,
+                self.new_lines,
+            ),
+            git2::DiffLineType::Deletion => within(
+                line.old_lineno(),
+                self.old_start,
+                self.old_lines,
+            ),
+            _ => false,
+        }
+    }
+}
+
 impl From<DiffHunk<'_>> for HunkHeader {
     fn from(h: DiffHunk) -> Self {
         Self {
@@ -106,6 +149,8 @@ impl From<DiffHunk<'_>> for HunkHeader {
 pub struct Hunk {
     /// hash of the hunk header
     pub header_hash: u64,
+    /// enclosing function/symbol of the hunk (if found)
+    pub function: Option<String>,
     /// list of `DiffLine`s
     pub lines: Vec<DiffLine>,
 }
@@ -123,6 +168,45 @@ pub struct FileDiff {
     pub sizes: (u64, u64),
     /// size delta in bytes
     pub size_delta: i64,
+    /// content type of the changed file, only detected for diffs
+    /// without hunks (binary)
+    pub content_type: ContentType,
+    /// raw content of the new (or deleted) file, set for images only
+    pub preview: Option<Vec<u8>>,
+    /// log of the submodule if the diff changes a submodule pointer
+    pub submodule: Option<SubmoduleLog>,
+}
+
+/// images larger than this are not loaded for previews
+const MAX_PREVIEW_SIZE: usize = 16 * 1024 * 1024;
+
+/// user facing options used to calculate a diff
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+pub struct DiffOptions {
+    /// ignore all whitespace changes
+    pub ignore_whitespace: bool,
+    /// ignore whitespace changes at the end of lines
+    pub ignore_whitespace_eol: bool,
+    /// number of unchanged lines shown around a change
+    pub context: u32,
+}
+
+impl Default for DiffOptions {
+    fn default() -> Self {
+        Self {
+            ignore_whitespace: false,
+            ignore_whitespace_eol: false,
+            context: 3,
+        }
+    }
+}
+
+impl DiffOptions {
+    pub(crate) fn apply(self, opt: &mut GitDiffOptions) {
+        opt.ignore_whitespace(self.ignore_whitespace);
+        opt.ignore_whitespace_eol(self.ignore_whitespace_eol);
+        opt.context_lines(self.context);
+    }
 }
 
 pub(crate) fn get_diff_raw<'a>(
@@ -130,13 +214,13 @@ pub(crate) fn get_diff_raw<'a>(
     p: &str,
     stage: bool,
     reverse: bool,
-    context: Option<u32>,
+    options: Option<DiffOptions>,
 ) -> Result<Diff<'a>> {
     // scope_time!("get_diff_raw");
 
-    let mut opt = DiffOptions::new();
-    if let Some(context) = context {
-        opt.context_lines(context);
+    let mut opt = GitDiffOptions::new();
+    if let Some(options) = options {
+        options.apply(&mut opt);
     }
     opt.pathspec(p);
     opt.reverse(reverse);
@@ -170,42 +254,97 @@ pub(crate) fn get_diff_raw<'a>(
 
 /// returns diff of a specific file either in `stage` or workdir
 pub fn get_diff(
-    repo_path: &str,
+    repo_path: &RepoPath,
     p: &str,
     stage: bool,
+    options: Option<DiffOptions>,
 ) -> Result<FileDiff> {
     scope_time!("get_diff");
 
     let repo = utils::repo(repo_path)?;
-    let work_dir = work_dir(&repo)?;
-    let diff = get_diff_raw(&repo, p, stage, false, None)?;
+    let diff = get_diff_raw(&repo, p, stage, false, options)?;
 
-    raw_diff_to_file_diff(&diff, work_dir)
+    raw_diff_to_file_diff(&repo, &diff)
+}
+
+/// returns diff of a specific file in the workdir (including staged
+/// changes) against the tree of `base`
+pub fn get_diff_base(
+    repo_path: &RepoPath,
+    base: CommitId,
+    p: &str,
+    options: Option<DiffOptions>,
+) -> Result<FileDiff> {
+    scope_time!("get_diff_base");
+
+    let repo = utils::repo(repo_path)?;
+    let tree = repo.find_commit(base.into())?.tree()?;
+
+    let mut opt = GitDiffOptions::new();
+    if let Some(options) = options {
+        options.apply(&mut opt);
+    }
+    opt.pathspec(p);
+    opt.include_untracked(true);
+    opt.recurse_untracked_dirs(true);
+
+    let diff = repo.diff_tree_to_workdir_with_index(
+        Some(&tree),
+        Some(&mut opt),
+    )?;
+
+    raw_diff_to_file_diff(&repo, &diff)
 }
 
 /// returns diff of a specific file inside a commit
 /// see `get_commit_diff`
 pub fn get_diff_commit(
-    repo_path: &str,
+    repo_path: &RepoPath,
     id: CommitId,
     p: String,
+    options: Option<DiffOptions>,
 ) -> Result<FileDiff> {
     scope_time!("get_diff_commit");
 
     let repo = utils::repo(repo_path)?;
-    let work_dir = work_dir(&repo)?;
-    let diff = get_commit_diff(&repo, id, Some(p))?;
+    let diff = get_commit_diff(&repo, id, Some(p), options)?;
+
+    raw_diff_to_file_diff(&repo, &diff)
+}
+
+/// returns diff of a specific file between the commits `from` and
+/// `to`, see `get_compare_diff`
+pub fn get_diff_commits(
+    repo_path: &RepoPath,
+    from: CommitId,
+    to: CommitId,
+    p: String,
+    options: Option<DiffOptions>,
+) -> Result<FileDiff> {
+    scope_time!("get_diff_commits");
+
+    let repo = utils::repo(repo_path)?;
+    let diff = get_compare_diff(&repo, from, to, Some(p), options)?;
 
-    raw_diff_to_file_diff(&diff, work_dir)
+    raw_diff_to_file_diff(&repo, &diff)
 }
 
 ///
 //TODO: refactor into helper type with the inline closures as dedicated functions
 #[allow(clippy::too_many_lines)]
 fn raw_diff_to_file_diff<'a>(
+    repo: &Repository,
     diff: &'a Diff,
-    work_dir: &Path,
 ) -> Result<FileDiff> {
+    let work_dir = work_dir(repo)?;
+    let funcname = diff.deltas().next().and_then(|delta| {
+        delta
+            .new_file()
+            .path()
+            .and_then(|path| FuncnameMatcher::new(repo, path))
+    });
+    let old_lines = old_file_lines(repo, diff, funcname.is_some());
+
     let res = Rc::new(RefCell::new(FileDiff::default()));
     {
         let mut current_lines = Vec::new();
@@ -214,10 +353,19 @@ fn raw_diff_to_file_diff<'a>(
         let res_cell = Rc::clone(&res);
         let adder = move |header: &HunkHeader,
                           lines: &Vec<DiffLine>| {
+            let mut lines = lines.clone();
+            highlight_changes(&mut lines);
+
+            let function = lines
+                .first()
+                .filter(|line| line.line_type == DiffLineType::Header)
+                .and_then(|line| header_function(&line.content));
+
             let mut res = res_cell.borrow_mut();
             res.hunks.push(Hunk {
                 header_hash: hash(header),
-                lines: lines.clone(),
+                function,
+                lines,
             });
             res.lines += lines.len();
         };
@@ -250,11 +398,31 @@ fn raw_diff_to_file_diff<'a>(
                     }
                 }
 
+                let mut content =
+                    String::from_utf8_lossy(line.content())
+                        .to_string();
+
+                if line.origin_value()
+                    == git2::DiffLineType::HunkHeader
+                {
+                    if let Some(function) =
+                        funcname.as_ref().and_then(|funcname| {
+                            funcname.find(
+                                &old_lines,
+                                hunk_header.old_start,
+                            )
+                        })
+                    {
+                        content =
+                            header_with_function(&content, &function);
+                    }
+                }
+
                 let diff_line = DiffLine {
                     position: DiffLinePosition::from(&line),
-                    content: String::from_utf8_lossy(line.content())
-                        .to_string(),
+                    content,
                     line_type: line.origin_value().into(),
+                    changes: Vec::new(),
                 };
 
                 current_lines.push(diff_line);
@@ -329,9 +497,78 @@ fn raw_diff_to_file_diff<'a>(
             res.borrow_mut().untracked = true;
         }
     }
-    let res = Rc::try_unwrap(res)
-        .map_err(|_| Error::Generic("rc unwrap error".to_owned()))?;
-    Ok(res.into_inner())
+    let mut res = Rc::try_unwrap(res)
+        .map_err(|_| Error::Generic("rc unwrap error".to_owned()))?
+        .into_inner();
+
+    res.submodule = diff
+        .deltas()
+        .next()
+        .and_then(|delta| submodule_log(work_dir, &delta));
+
+    if res.hunks.is_empty() {
+        if let Some(content) =
+            diff.deltas().next().and_then(|delta| {
+                changed_file_content(repo, work_dir, &delta)
+            })
+        {
+            res.content_type = content_type(&content);
+
+            if matches!(res.content_type, ContentType::Image(_))
+                && content.len() <= MAX_PREVIEW_SIZE
+            {
+                res.preview = Some(content);
+            }
+        }
+    }
+
+    Ok(res)
+}
+
+/// content of the new side of `delta` or the old one if the file was
+/// deleted, falls back to the workdir for objects not in the odb
+fn changed_file_content(
+    repo: &Repository,
+    work_dir: &Path,
+    delta: &DiffDelta,
+) -> Option<Vec<u8>> {
+    let file = if delta.status() == Delta::Deleted {
+        delta.old_file()
+    } else {
+        delta.new_file()
+    };
+
+    repo.find_blob(file.id())
+        .ok()
+        .map(|blob| blob.content().to_vec())
+        .or_else(|| {
+            file.path().and_then(|path| {
+                new_file_content(&work_dir.join(path))
+            })
+        })
+}
+
+/// lines of the old side of the (single file) diff, only loaded if
+/// we need to search them for function names
+fn old_file_lines(
+    repo: &Repository,
+    diff: &Diff,
+    needed: bool,
+) -> Vec<String> {
+    if !needed {
+        return Vec::new();
+    }
+
+    diff.deltas()
+        .next()
+        .and_then(|delta| repo.find_blob(delta.old_file().id()).ok())
+        .map(|blob| {
+            String::from_utf8_lossy(blob.content())
+                .lines()
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
 }
 
 fn new_file_content(path: &Path) -> Option<Vec<u8>> {
@@ -354,13 +591,20 @@ fn new_file_content(path: &Path) -> Option<Vec<u8>> {
 
 #[cfg(test)]
 mod tests {
-    use super::{get_diff, get_diff_commit};
+    use super::{
+        get_diff, get_diff_base, get_diff_commit, DiffOptions,
+    };
     use crate::error::Result;
+    use crate::sync::RepoPath;
     use crate::sync::{
         commit, stage_add_file,
         status::{get_status, StatusType},
-        tests::{get_statuses, repo_init, repo_init_empty},
+        tests::{
+            get_statuses, repo_init, repo_init_empty,
+            write_commit_file,
+        },
     };
+    use crate::sync::{ContentType, ImageType};
     use std::{
         fs::{self, File},
         io::Write,
@@ -371,7 +615,8 @@ mod tests {
     fn test_untracked_subfolder() {
         let (_td, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         assert_eq!(get_statuses(repo_path), (0, 0));
 
@@ -383,7 +628,8 @@ mod tests {
 
         assert_eq!(get_statuses(repo_path), (1, 0));
 
-        let diff = get_diff(repo_path, "foo/bar.txt", false).unwrap();
+        let diff =
+            get_diff(repo_path, "foo/bar.txt", false, None).unwrap();
 
         assert_eq!(diff.hunks.len(), 1);
         assert_eq!(diff.hunks[0].lines[1].content, "test\n");
@@ -394,7 +640,8 @@ mod tests {
         let file_path = Path::new("foo.txt");
         let (_td, repo) = repo_init_empty().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         assert_eq!(get_statuses(repo_path), (0, 0));
 
@@ -409,9 +656,13 @@ mod tests {
 
         assert_eq!(get_statuses(repo_path), (0, 1));
 
-        let diff =
-            get_diff(repo_path, file_path.to_str().unwrap(), true)
-                .unwrap();
+        let diff = get_diff(
+            repo_path,
+            file_path.to_str().unwrap(),
+            true,
+            None,
+        )
+        .unwrap();
 
         assert_eq!(diff.hunks.len(), 1);
     }
@@ -446,7 +697,8 @@ mod tests {
     fn test_hunks() {
         let (_td, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         assert_eq!(get_statuses(repo_path), (0, 0));
 
@@ -477,7 +729,8 @@ mod tests {
 
         assert_eq!(get_statuses(repo_path), (1, 1));
 
-        let res = get_diff(repo_path, "bar.txt", false).unwrap();
+        let res =
+            get_diff(repo_path, "bar.txt", false, None).unwrap();
 
         assert_eq!(res.hunks.len(), 2)
     }
@@ -497,9 +750,10 @@ mod tests {
             .unwrap();
 
         let diff = get_diff(
-            sub_path.to_str().unwrap(),
+            &sub_path.to_str().unwrap().into(),
             file_path.to_str().unwrap(),
             false,
+            None,
         )
         .unwrap();
 
@@ -511,7 +765,8 @@ mod tests {
         let file_path = Path::new("bar");
         let (_td, repo) = repo_init_empty().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         File::create(&root.join(file_path))?.write_all(b"\x00")?;
 
@@ -522,9 +777,13 @@ mod tests {
         File::create(&root.join(file_path))?
             .write_all(b"\x00\x02")?;
 
-        let diff =
-            get_diff(repo_path, file_path.to_str().unwrap(), false)
-                .unwrap();
+        let diff = get_diff(
+            repo_path,
+            file_path.to_str().unwrap(),
+            false,
+            None,
+        )
+        .unwrap();
 
         dbg!(&diff);
         assert_eq!(diff.sizes, (1, 2));
@@ -538,14 +797,19 @@ mod tests {
         let file_path = Path::new("bar");
         let (_td, repo) = repo_init_empty().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         File::create(&root.join(file_path))?
             .write_all(b"\x00\xc7")?;
 
-        let diff =
-            get_diff(repo_path, file_path.to_str().unwrap(), false)
-                .unwrap();
+        let diff = get_diff(
+            repo_path,
+            file_path.to_str().unwrap(),
+            false,
+            None,
+        )
+        .unwrap();
 
         dbg!(&diff);
         assert_eq!(diff.sizes, (0, 2));
@@ -559,7 +823,8 @@ mod tests {
         let file_path = Path::new("bar");
         let (_td, repo) = repo_init_empty().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         File::create(&root.join(file_path))?.write_all(b"\x00")?;
 
@@ -575,7 +840,8 @@ mod tests {
         let id = commit(repo_path, "").unwrap();
 
         let diff =
-            get_diff_commit(repo_path, id, String::new()).unwrap();
+            get_diff_commit(repo_path, id, String::new(), None)
+                .unwrap();
 
         dbg!(&diff);
         assert_eq!(diff.sizes, (1, 2));
@@ -583,4 +849,151 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_diff_options() -> Result<()> {
+        let file_path = Path::new("bar.txt");
+        let (_td, repo) = repo_init_empty().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        File::create(&root.join(file_path))?
+            .write_all(b"a\nb\nc\nd\ne\nf\ng\n")?;
+
+        stage_add_file(repo_path, file_path).unwrap();
+        commit(repo_path, "commit").unwrap();
+
+        File::create(&root.join(file_path))?
+            .write_all(b"a\nb \nc\nd\nE\nf\ng\n")?;
+
+        let path = file_path.to_str().unwrap();
+
+        let diff = get_diff(repo_path, path, false, None)?;
+        assert_eq!(diff.hunks.len(), 1);
+        assert_eq!(diff.lines, 10);
+
+        let diff = get_diff(
+            repo_path,
+            path,
+            false,
+            Some(DiffOptions {
+                ignore_whitespace_eol: true,
+                context: 0,
+                ..DiffOptions::default()
+            }),
+        )?;
+        assert_eq!(diff.hunks.len(), 1);
+        assert_eq!(diff.hunks[0].lines[1].content, "e\n");
+        assert_eq!(diff.lines, 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_hunk_function_context() -> Result<()> {
+        let file_path = Path::new("foo.rs");
+        let (_td, repo) = repo_init_empty().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        let content = "impl Foo {\n    fn bar() {\n        a();\n        b();\n        c();\n        d();\n        e();\n    }\n}\n";
+
+        File::create(&root.join(file_path))?
+            .write_all(content.as_bytes())?;
+
+        stage_add_file(repo_path, file_path).unwrap();
+        commit(repo_path, "commit").unwrap();
+
+        File::create(&root.join(file_path))?
+            .write_all(content.replace("e()", "f()").as_bytes())?;
+
+        let diff = get_diff(
+            repo_path,
+            file_path.to_str().unwrap(),
+            false,
+            Some(DiffOptions {
+                context: 1,
+                ..DiffOptions::default()
+            }),
+        )?;
+
+        assert_eq!(diff.hunks.len(), 1);
+        assert_eq!(
+            diff.hunks[0].function,
+            Some(String::from("fn bar() {"))
+        );
+        assert!(diff.hunks[0].lines[0]
+            .content
+            .ends_with("fn bar() {\n"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_binary_diff_image_preview() -> Result<()> {
+        let file_path = Path::new("foo.png");
+        let (_td, repo) = repo_init_empty().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        let content = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR";
+        File::create(&root.join(file_path))?.write_all(content)?;
+
+        let diff = get_diff(
+            repo_path,
+            file_path.to_str().unwrap(),
+            false,
+            None,
+        )?;
+
+        assert!(diff.hunks.is_empty());
+        assert_eq!(
+            diff.content_type,
+            ContentType::Image(ImageType::Png)
+        );
+        assert_eq!(diff.preview.as_deref(), Some(&content[..]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_diff_base() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        let base = write_commit_file(&repo, "a.txt", "a\n", "base");
+        write_commit_file(&repo, "a.txt", "b\n", "change");
+
+        File::create(&root.join("b.txt"))?.write_all(b"staged\n")?;
+        stage_add_file(repo_path, Path::new("b.txt"))?;
+        File::create(&root.join("c.txt"))?
+            .write_all(b"untracked\n")?;
+
+        assert_eq!(
+            get_status(repo_path, StatusType::WorkingDir, true)?
+                .len(),
+            1
+        );
+
+        let res =
+            get_status(repo_path, StatusType::Base(base), true)?;
+        assert_eq!(
+            res.iter()
+                .map(|item| item.path.as_str())
+                .collect::<Vec<_>>(),
+            vec!["a.txt", "b.txt", "c.txt"]
+        );
+
+        let diff = get_diff_base(repo_path, base, "a.txt", None)?;
+        assert_eq!(diff.hunks.len(), 1);
+        assert_eq!(diff.hunks[0].lines[1].content, "a\n");
+        assert_eq!(diff.hunks[0].lines[2].content, "b\n");
+
+        Ok(())
+    }
 }
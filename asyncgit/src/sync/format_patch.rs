@@ -0,0 +1,105 @@
+//! commits as mails like `git format-patch` writes them, to send
+//! them around or apply them with `git am`
+
+use super::{utils::repo, CommitId, RepoPath};
+use crate::error::{Error, Result};
+use git2::DiffOptions;
+use scopetime::scope_time;
+
+/// longest subject part of a patch file name, like git
+const FILE_NAME_MAX_SUBJECT: usize = 52;
+
+/// the commit as a mail: headers, message and the diff against its
+/// parent, merge commits have no patch
+pub fn format_patch(
+    repo_path: &RepoPath,
+    id: CommitId,
+) -> Result<String> {
+    scope_time!("format_patch");
+
+    let repo = repo(repo_path)?;
+    let commit = repo.find_commit(id.into())?;
+
+    if commit.parent_count() > 1 {
+        return Err(Error::Generic(
+            "merge commits have no patch".to_string(),
+        ));
+    }
+
+    let parent_tree = if commit.parent_count() == 1 {
+        Some(commit.parent(0)?.tree()?)
+    } else {
+        None
+    };
+
+    let mut opts = DiffOptions::new();
+    opts.show_binary(true);
+
+    let mut diff = repo.diff_tree_to_tree(
+        parent_tree.as_ref(),
+        Some(&commit.tree()?),
+        Some(&mut opts),
+    )?;
+
+    let mail = diff.format_email(1, 1, &commit, None)?;
+
+    Ok(String::from_utf8_lossy(&mail).to_string())
+}
+
+/// `0001-fix-the-thing.patch` for the subject "fix the thing", like
+/// `git format-patch` names the files
+pub fn patch_file_name(subject: &str) -> String {
+    let mut slug = String::new();
+
+    for c in subject.chars() {
+        if c.is_ascii_alphanumeric() || c == '_' || c == '.' {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+
+        if slug.len() >= FILE_NAME_MAX_SUBJECT {
+            break;
+        }
+    }
+
+    format!("0001-{}.patch", slug.trim_end_matches(&['-', '.'][..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::tests::{repo_init, write_commit_file};
+
+    #[test]
+    fn test_format_patch() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        let id =
+            write_commit_file(&repo, "foo.txt", "hello\n", "add foo");
+
+        let patch = format_patch(repo_path, id).unwrap();
+
+        assert!(
+            patch.starts_with(&format!("From {} ", id.to_string()))
+        );
+        assert!(patch.contains("Subject: [PATCH] add foo"));
+        assert!(patch.contains("+++ b/foo.txt"));
+        assert!(patch.contains("+hello"));
+    }
+
+    #[test]
+    fn test_patch_file_name() {
+        assert_eq!(
+            patch_file_name("Fix: the (small) thing."),
+            "0001-Fix-the-small-thing.patch"
+        );
+        assert_eq!(
+            patch_file_name(&"a".repeat(80)),
+            format!("0001-{}.patch", "a".repeat(52))
+        );
+    }
+}
@@ -0,0 +1,123 @@
+//! finds the symbol (function, struct, ..) enclosing a diff hunk
+
+use git2::{AttrCheckFlags, AttrValue, Repository};
+use regex::Regex;
+use std::path::Path;
+
+/// built-in function name patterns by file extension, only used if
+/// the file has no `diff` driver assigned via `.gitattributes`
+/// (libgit2 honors `diff.<driver>.xfuncname` in that case already)
+const BUILTIN_PATTERNS: &[(&[&str], &str)] = &[
+    (
+        &["rs"],
+        r"^\s*((pub(\([^)]*\))?\s+)?((async|const|unsafe|extern)\s+)*(fn|struct|enum|union|trait|impl|mod|macro_rules!)\b.*)$",
+    ),
+    (&["go"], r"^\s*((func|type)\s.*)$"),
+    (&["py"], r"^\s*((async\s+)?(class|def)\s.*)$"),
+    (&["rb"], r"^\s*((class|module|def)\s.*)$"),
+    (
+        &["js", "jsx", "mjs", "ts", "tsx"],
+        r"^\s*((export\s+)?(default\s+)?(async\s+)?(function\*?|class|interface)\b.*)$",
+    ),
+    (
+        &["c", "h", "cc", "cpp", "cxx", "hpp", "hh"],
+        r"^((struct|class|enum|union|namespace)\b[^;]*|[A-Za-z_][A-Za-z_0-9]*[\s*&]+[A-Za-z_][A-Za-z_0-9:~]*\s*\([^;]*)$",
+    ),
+    (
+        &["java", "kt", "cs", "scala"],
+        r"^\s*(((public|protected|private|internal|static|final|abstract|sealed|override|async|suspend)\s+)*(class|interface|enum|record|object|fun|def|[A-Za-z_][A-Za-z_0-9<>\[\],]*\s+[A-Za-z_][A-Za-z_0-9]*\s*\()[^;]*)$",
+    ),
+];
+
+pub(crate) struct FuncnameMatcher {
+    regex: Regex,
+}
+
+impl FuncnameMatcher {
+    /// returns `None` if the file has a diff driver set (or is
+    /// binary) or we have no built-in pattern for its extension
+    pub fn new(repo: &Repository, path: &Path) -> Option<Self> {
+        let attr = repo
+            .get_attr(path, "diff", AttrCheckFlags::default())
+            .ok()?;
+        if !matches!(
+            AttrValue::from_string(attr),
+            AttrValue::Unspecified
+        ) {
+            return None;
+        }
+
+        let extension = path.extension()?.to_str()?;
+
+        BUILTIN_PATTERNS
+            .iter()
+            .find(|(extensions, _)| extensions.contains(&extension))
+            .and_then(|(_, pattern)| Regex::new(pattern).ok())
+            .map(|regex| Self { regex })
+    }
+
+    /// searches upwards from the line before `line_no` (1-based)
+    pub fn find(
+        &self,
+        lines: &[String],
+        line_no: u32,
+    ) -> Option<String> {
+        let end =
+            (line_no as usize).saturating_sub(1).min(lines.len());
+
+        lines[..end].iter().rev().find_map(|line| {
+            self.regex.captures(line).and_then(|captures| {
+                captures
+                    .get(1)
+                    .or_else(|| captures.get(0))
+                    .map(|m| m.as_str().trim().to_string())
+            })
+        })
+    }
+}
+
+/// extracts the function context libgit2 appends to a hunk header
+/// (`@@ -1,2 +1,2 @@ fn foo()`)
+pub(crate) fn header_function(header: &str) -> Option<String> {
+    header
+        .strip_prefix("@@")
+        .and_then(|rest| rest.find("@@").map(|idx| &rest[idx + 2..]))
+        .map(str::trim)
+        .filter(|function| !function.is_empty())
+        .map(String::from)
+}
+
+/// replaces the function context of a hunk header
+pub(crate) fn header_with_function(
+    header: &str,
+    function: &str,
+) -> String {
+    header
+        .strip_prefix("@@")
+        .and_then(|rest| rest.find("@@"))
+        .map_or_else(
+            || header.to_string(),
+            |idx| format!("{} {}\n", &header[..idx + 4], function),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{header_function, header_with_function};
+
+    #[test]
+    fn test_header_function() {
+        assert_eq!(
+            header_function("@@ -1,3 +1,3 @@ fn foo() {\n"),
+            Some(String::from("fn foo() {"))
+        );
+        assert_eq!(header_function("@@ -1,3 +1,3 @@\n"), None);
+        assert_eq!(
+            header_with_function(
+                "@@ -1 +1 @@ impl Foo {\n",
+                "fn bar()"
+            ),
+            String::from("@@ -1 +1 @@ fn bar()\n")
+        );
+    }
+}
@@ -0,0 +1,156 @@
+//! stepping through the history one commit at a time, along the
+//! first parents like `git log --first-parent`
+
+use super::{
+    utils::{get_head_repo, repo},
+    CommitId, RepoPath,
+};
+use crate::error::Result;
+use git2::{Commit, Oid};
+use scopetime::scope_time;
+use std::path::Path;
+
+/// the commit before `id`, with a `path` the one that introduced the
+/// previous version of that file (or directory)
+pub fn older_commit(
+    repo_path: &RepoPath,
+    id: CommitId,
+    path: Option<&Path>,
+) -> Result<Option<CommitId>> {
+    scope_time!("older_commit");
+
+    let repo = repo(repo_path)?;
+    let commit = repo.find_commit(id.into())?;
+
+    let path = match path {
+        Some(path) => path,
+        None => {
+            return Ok(first_parent(&commit).map(|c| c.id().into()))
+        }
+    };
+
+    let current = path_entry(&commit, path);
+
+    // the newest ancestor with another version
+    let mut ancestor = first_parent(&commit);
+    while let Some(c) = ancestor.take() {
+        if path_entry(&c, path) != current {
+            ancestor = Some(c);
+            break;
+        }
+        ancestor = first_parent(&c);
+    }
+
+    let mut introducing = match ancestor {
+        Some(c) => c,
+        None => return Ok(None),
+    };
+
+    // the oldest one still having it introduced it
+    let version = path_entry(&introducing, path);
+    while let Some(parent) = first_parent(&introducing) {
+        if path_entry(&parent, path) != version {
+            break;
+        }
+        introducing = parent;
+    }
+
+    Ok(Some(introducing.id().into()))
+}
+
+/// the commit after `id` on the way to `HEAD`, with a `path` the one
+/// that introduced the next version of that file (or directory).
+/// `None` if there is none or `id` is no first parent ancestor of
+/// `HEAD`
+pub fn newer_commit(
+    repo_path: &RepoPath,
+    id: CommitId,
+    path: Option<&Path>,
+) -> Result<Option<CommitId>> {
+    scope_time!("newer_commit");
+
+    let repo = repo(repo_path)?;
+    let id = Oid::from(id);
+
+    // newest first
+    let mut newer: Vec<Commit> = Vec::new();
+    let mut next =
+        Some(repo.find_commit(get_head_repo(&repo)?.into())?);
+    loop {
+        let commit = match next {
+            Some(commit) => commit,
+            None => return Ok(None),
+        };
+
+        if commit.id() == id {
+            break;
+        }
+
+        next = first_parent(&commit);
+        newer.push(commit);
+    }
+
+    let path = match path {
+        Some(path) => path,
+        None => return Ok(newer.last().map(|c| c.id().into())),
+    };
+
+    let current = path_entry(&repo.find_commit(id)?, path);
+
+    Ok(newer
+        .iter()
+        .rev()
+        .find(|commit| path_entry(commit, path) != current)
+        .map(|commit| commit.id().into()))
+}
+
+fn first_parent<'a>(commit: &Commit<'a>) -> Option<Commit<'a>> {
+    commit.parent(0).ok()
+}
+
+/// the blob or tree at `path`, `None` if it does not exist
+fn path_entry(commit: &Commit, path: &Path) -> Option<Oid> {
+    commit
+        .tree()
+        .ok()
+        .and_then(|tree| tree.get_path(path).ok())
+        .map(|entry| entry.id())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::tests::{repo_init, write_commit_file};
+
+    #[test]
+    fn test_stepping() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        let c1 = write_commit_file(&repo, "a.txt", "1", "c1");
+        let c2 = write_commit_file(&repo, "b.txt", "1", "c2");
+        let c3 = write_commit_file(&repo, "a.txt", "2", "c3");
+        let c4 = write_commit_file(&repo, "b.txt", "2", "c4");
+
+        assert_eq!(
+            older_commit(repo_path, c4, None).unwrap(),
+            Some(c3)
+        );
+        assert_eq!(
+            newer_commit(repo_path, c2, None).unwrap(),
+            Some(c3)
+        );
+        assert_eq!(newer_commit(repo_path, c4, None).unwrap(), None);
+
+        let a = Some(Path::new("a.txt"));
+
+        assert_eq!(older_commit(repo_path, c4, a).unwrap(), Some(c1));
+        assert_eq!(older_commit(repo_path, c3, a).unwrap(), Some(c1));
+        assert_eq!(older_commit(repo_path, c2, a).unwrap(), None);
+        assert_eq!(newer_commit(repo_path, c1, a).unwrap(), Some(c3));
+        assert_eq!(newer_commit(repo_path, c2, a).unwrap(), Some(c3));
+        assert_eq!(newer_commit(repo_path, c3, a).unwrap(), None);
+    }
+}
@@ -1,38 +1,47 @@
-use super::utils::{repo, work_dir};
+use super::{
+    utils::{repo, work_dir},
+    RepoPath,
+};
 use crate::error::{Error, Result};
+use git2::Repository;
 use scopetime::scope_time;
 use std::{
-    fs::File,
+    ffi::OsStr,
+    fs::{self, File},
     io::{Read, Write},
-    path::Path,
+    path::{Path, PathBuf},
     process::Command,
 };
 
-const HOOK_POST_COMMIT: &str = ".git/hooks/post-commit";
-const HOOK_PRE_COMMIT: &str = ".git/hooks/pre-commit";
-const HOOK_COMMIT_MSG: &str = ".git/hooks/commit-msg";
+const HOOK_POST_COMMIT: &str = "post-commit";
+const HOOK_PRE_COMMIT: &str = "pre-commit";
+const HOOK_COMMIT_MSG: &str = "commit-msg";
 const HOOK_COMMIT_MSG_TEMP_FILE: &str = ".git/COMMIT_EDITMSG";
 
+const CONFIG_HOOKS_PATH: &str = "core.hooksPath";
+/// space separated names of the hooks gitui does not run
+const CONFIG_DISABLED_HOOKS: &str = "gitui.disabledHooks";
+
 /// this hook is documented here <https://git-scm.com/docs/githooks#_commit_msg>
 /// we use the same convention as other git clients to create a temp file containing
 /// the commit message at `.git/COMMIT_EDITMSG` and pass it's relative path as the only
 /// parameter to the hook script.
 pub fn hooks_commit_msg(
-    repo_path: &str,
+    repo_path: &RepoPath,
     msg: &mut String,
 ) -> Result<HookResult> {
     scope_time!("hooks_commit_msg");
 
     let work_dir = work_dir_as_string(repo_path)?;
 
-    if hook_runable(work_dir.as_str(), HOOK_COMMIT_MSG) {
+    if let Some(hook) = hook_to_run(repo_path, HOOK_COMMIT_MSG)? {
         let temp_file = Path::new(work_dir.as_str())
             .join(HOOK_COMMIT_MSG_TEMP_FILE);
         File::create(&temp_file)?.write_all(msg.as_bytes())?;
 
         let res = run_hook(
             work_dir.as_str(),
-            HOOK_COMMIT_MSG,
+            &hook,
             &[HOOK_COMMIT_MSG_TEMP_FILE],
         )?;
 
@@ -48,32 +57,228 @@ pub fn hooks_commit_msg(
 
 /// this hook is documented here <https://git-scm.com/docs/githooks#_pre_commit>
 ///
-pub fn hooks_pre_commit(repo_path: &str) -> Result<HookResult> {
+pub fn hooks_pre_commit(repo_path: &RepoPath) -> Result<HookResult> {
     scope_time!("hooks_pre_commit");
 
     let work_dir = work_dir_as_string(repo_path)?;
 
-    if hook_runable(work_dir.as_str(), HOOK_PRE_COMMIT) {
-        Ok(run_hook(work_dir.as_str(), HOOK_PRE_COMMIT, &[])?)
+    if let Some(hook) = hook_to_run(repo_path, HOOK_PRE_COMMIT)? {
+        Ok(run_hook(work_dir.as_str(), &hook, &[])?)
     } else {
         Ok(HookResult::Ok)
     }
 }
 ///
-pub fn hooks_post_commit(repo_path: &str) -> Result<HookResult> {
+pub fn hooks_post_commit(repo_path: &RepoPath) -> Result<HookResult> {
     scope_time!("hooks_post_commit");
 
     let work_dir = work_dir_as_string(repo_path)?;
     let work_dir_str = work_dir.as_str();
 
-    if hook_runable(work_dir_str, HOOK_POST_COMMIT) {
-        Ok(run_hook(work_dir_str, HOOK_POST_COMMIT, &[])?)
+    if let Some(hook) = hook_to_run(repo_path, HOOK_POST_COMMIT)? {
+        Ok(run_hook(work_dir_str, &hook, &[])?)
     } else {
         Ok(HookResult::Ok)
     }
 }
 
-fn work_dir_as_string(repo_path: &str) -> Result<String> {
+/// where a hook is installed
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum HookSource {
+    /// the hooks folder of the repository
+    Local,
+    /// the folder `core.hooksPath` points to
+    HooksPath,
+}
+
+/// an installed hook, samples are left out
+#[derive(Clone, Debug)]
+pub struct HookInfo {
+    /// file name like `pre-commit`
+    pub name: String,
+    ///
+    pub path: PathBuf,
+    ///
+    pub source: HookSource,
+    /// `false` if git ignores it because `core.hooksPath` is set
+    pub active: bool,
+    ///
+    pub executable: bool,
+    /// `false` if gitui does not run it (see `set_hook_enabled`)
+    pub enabled: bool,
+}
+
+/// all installed hooks of both the local hooks folder and the one
+/// `core.hooksPath` points to, sorted by name
+pub fn get_hooks(repo_path: &RepoPath) -> Result<Vec<HookInfo>> {
+    scope_time!("get_hooks");
+
+    let repo = repo(repo_path)?;
+    let disabled = disabled_hooks(&repo)?;
+    let local = repo.path().join("hooks");
+    let hooks_path = hooks_path(&repo)?;
+
+    let mut dirs = vec![(local.clone(), HookSource::Local)];
+    if let Some(hooks_path) = &hooks_path {
+        if !same_dir(hooks_path, &local) {
+            dirs.push((hooks_path.clone(), HookSource::HooksPath));
+        }
+    }
+
+    let active = hooks_path.unwrap_or(local);
+
+    let mut hooks = Vec::new();
+    for (dir, source) in dirs {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+
+        for entry in entries {
+            let path = entry?.path();
+            let name = match path.file_name() {
+                Some(name) => name.to_string_lossy().to_string(),
+                None => continue,
+            };
+
+            if !path.is_file() || name.ends_with(".sample") {
+                continue;
+            }
+
+            hooks.push(HookInfo {
+                active: same_dir(&dir, &active),
+                executable: is_executable(&path),
+                enabled: !disabled.contains(&name),
+                name,
+                path,
+                source,
+            });
+        }
+    }
+
+    hooks.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(hooks)
+}
+
+/// hooks stay installed but gitui skips the disabled ones when
+/// committing, this does not affect git itself
+pub fn set_hook_enabled(
+    repo_path: &RepoPath,
+    name: &str,
+    enabled: bool,
+) -> Result<()> {
+    scope_time!("set_hook_enabled");
+
+    let repo = repo(repo_path)?;
+    let mut disabled = disabled_hooks(&repo)?;
+
+    disabled.retain(|hook| hook != name);
+    if !enabled {
+        disabled.push(name.to_string());
+    }
+
+    let mut config = repo.config()?;
+    if disabled.is_empty() {
+        // fails if there was no entry
+        config.remove(CONFIG_DISABLED_HOOKS).ok();
+    } else {
+        config.set_str(CONFIG_DISABLED_HOOKS, &disabled.join(" "))?;
+    }
+
+    Ok(())
+}
+
+/// what a hook printed when it was run by hand
+#[derive(Debug, PartialEq)]
+pub struct HookOutput {
+    /// exited with status `0`
+    pub success: bool,
+    /// stdout followed by stderr
+    pub output: String,
+}
+
+/// runs a hook like git would (even if disabled in gitui), a
+/// `commit-msg` hook gets the last commit message file
+pub fn run_hook_manually(
+    repo_path: &RepoPath,
+    hook: &Path,
+) -> Result<HookOutput> {
+    scope_time!("run_hook_manually");
+
+    let work_dir = work_dir_as_string(repo_path)?;
+
+    let args: &[&str] =
+        if hook.file_name() == Some(OsStr::new(HOOK_COMMIT_MSG)) {
+            let temp_file = Path::new(work_dir.as_str())
+                .join(HOOK_COMMIT_MSG_TEMP_FILE);
+            if !temp_file.exists() {
+                File::create(&temp_file)?;
+            }
+            &[HOOK_COMMIT_MSG_TEMP_FILE]
+        } else {
+            &[]
+        };
+
+    run_hook_output(work_dir.as_str(), hook, args)
+}
+
+/// the hook gitui runs for `name`, `None` if it is missing, not
+/// executable or disabled
+fn hook_to_run(
+    repo_path: &RepoPath,
+    name: &str,
+) -> Result<Option<PathBuf>> {
+    let repo = repo(repo_path)?;
+
+    if disabled_hooks(&repo)?.iter().any(|hook| hook == name) {
+        return Ok(None);
+    }
+
+    let path = hooks_path(&repo)?
+        .unwrap_or_else(|| repo.path().join("hooks"))
+        .join(name);
+
+    Ok(if path.exists() && is_executable(&path) {
+        Some(path)
+    } else {
+        None
+    })
+}
+
+/// `core.hooksPath`, relative paths start at the work dir like they
+/// do for git
+fn hooks_path(repo: &Repository) -> Result<Option<PathBuf>> {
+    let path = match repo.config()?.get_path(CONFIG_HOOKS_PATH) {
+        Ok(path) => path,
+        Err(_) => return Ok(None),
+    };
+
+    Ok(Some(if path.is_relative() {
+        work_dir(repo)?.join(path)
+    } else {
+        path
+    }))
+}
+
+fn disabled_hooks(repo: &Repository) -> Result<Vec<String>> {
+    Ok(repo
+        .config()?
+        .get_string(CONFIG_DISABLED_HOOKS)
+        .map(|hooks| {
+            hooks.split_whitespace().map(String::from).collect()
+        })
+        .unwrap_or_default())
+}
+
+fn same_dir(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+fn work_dir_as_string(repo_path: &RepoPath) -> Result<String> {
     let repo = repo(repo_path)?;
     work_dir(&repo)?
         .to_str()
@@ -85,13 +290,6 @@ fn work_dir_as_string(repo_path: &str) -> Result<String> {
         })
 }
 
-fn hook_runable(path: &str, hook: &str) -> bool {
-    let path = Path::new(path);
-    let path = path.join(hook);
-
-    path.exists() && is_executable(&path)
-}
-
 ///
 #[derive(Debug, PartialEq)]
 pub enum HookResult {
@@ -105,10 +303,27 @@ pub enum HookResult {
 /// see <https://git-scm.com/docs/githooks>
 fn run_hook(
     path: &str,
-    hook_script: &str,
+    hook_script: &Path,
     args: &[&str],
 ) -> Result<HookResult> {
-    let arg_str = format!("{} {}", hook_script, args.join(" "));
+    let output = run_hook_output(path, hook_script, args)?;
+
+    if output.success {
+        Ok(HookResult::Ok)
+    } else {
+        Ok(HookResult::NotOk(output.output))
+    }
+}
+
+fn run_hook_output(
+    path: &str,
+    hook_script: &Path,
+    args: &[&str],
+) -> Result<HookOutput> {
+    // bash on windows understands forward slashes only
+    let hook_script =
+        hook_script.to_string_lossy().replace('\\', "/");
+    let arg_str = format!("\"{}\" {}", hook_script, args.join(" "));
     let bash_args = vec!["-c".to_string(), arg_str];
 
     let output = Command::new("bash")
@@ -123,15 +338,13 @@ fn run_hook(
         )
         .output()?;
 
-    if output.status.success() {
-        Ok(HookResult::Ok)
-    } else {
-        let err = String::from_utf8_lossy(&output.stderr);
-        let out = String::from_utf8_lossy(&output.stdout);
-        let formatted = format!("{}{}", out, err);
+    let err = String::from_utf8_lossy(&output.stderr);
+    let out = String::from_utf8_lossy(&output.stdout);
 
-        Ok(HookResult::NotOk(formatted))
-    }
+    Ok(HookOutput {
+        success: output.status.success(),
+        output: format!("{}{}", out, err),
+    })
 }
 
 #[cfg(not(windows))]
@@ -163,7 +376,8 @@ mod tests {
     fn test_smoke() {
         let (_td, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         let mut msg = String::from("test");
         let res = hooks_commit_msg(repo_path, &mut msg).unwrap();
@@ -175,8 +389,13 @@ mod tests {
         assert_eq!(res, HookResult::Ok);
     }
 
-    fn create_hook(path: &Path, hook_path: &str, hook_script: &[u8]) {
-        File::create(&path.join(hook_path))
+    fn create_hook(path: &Path, hook: &str, hook_script: &[u8]) {
+        create_hook_in(&path.join(".git/hooks"), hook, hook_script);
+    }
+
+    fn create_hook_in(dir: &Path, hook: &str, hook_script: &[u8]) {
+        fs::create_dir_all(dir).unwrap();
+        File::create(&dir.join(hook))
             .unwrap()
             .write_all(hook_script)
             .unwrap();
@@ -184,8 +403,8 @@ mod tests {
         #[cfg(not(windows))]
         {
             Command::new("chmod")
-                .args(&["+x", hook_path])
-                .current_dir(path)
+                .args(&["+x", hook])
+                .current_dir(dir)
                 .output()
                 .unwrap();
         }
@@ -195,7 +414,8 @@ mod tests {
     fn test_hooks_commit_msg_ok() {
         let (_td, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         let hook = b"#!/bin/sh
 exit 0
@@ -215,7 +435,8 @@ exit 0
     fn test_pre_commit_sh() {
         let (_td, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         let hook = b"#!/bin/sh
 exit 0
@@ -230,7 +451,8 @@ exit 0
     fn test_pre_commit_fail_sh() {
         let (_td, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         let hook = b"#!/bin/sh
 echo 'rejected'        
@@ -246,7 +468,8 @@ exit 1
     fn test_pre_commit_py() {
         let (_td, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         // mirror how python pre-commmit sets itself up
         #[cfg(not(windows))]
@@ -269,7 +492,8 @@ sys.exit(0)
     fn test_pre_commit_fail_py() {
         let (_td, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         // mirror how python pre-commmit sets itself up
         #[cfg(not(windows))]
@@ -292,7 +516,8 @@ sys.exit(1)
     fn test_hooks_commit_msg_reject() {
         let (_td, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         let hook = b"#!/bin/sh
 echo 'msg' > $1
@@ -331,9 +556,11 @@ exit 1
         fs::create_dir_all(&subfolder).unwrap();
 
         let mut msg = String::from("test");
-        let res =
-            hooks_commit_msg(subfolder.to_str().unwrap(), &mut msg)
-                .unwrap();
+        let res = hooks_commit_msg(
+            &subfolder.to_str().unwrap().into(),
+            &mut msg,
+        )
+        .unwrap();
 
         assert_eq!(
             res,
@@ -347,7 +574,8 @@ exit 1
     fn test_commit_msg_no_block_but_alter() {
         let (_td, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         let hook = b"#!/bin/sh
 echo 'msg' > $1
@@ -379,11 +607,73 @@ exit 1
         fs::create_dir_all(&subfolder).unwrap();
 
         let res =
-            hooks_post_commit(subfolder.to_str().unwrap()).unwrap();
+            hooks_post_commit(&subfolder.to_str().unwrap().into())
+                .unwrap();
 
         assert_eq!(
             res,
             HookResult::NotOk(String::from("rejected\n"))
         );
     }
+
+    #[test]
+    fn test_hooks_path_and_disabled() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        let reject = b"#!/bin/sh
+echo 'rejected'
+exit 1
+        ";
+
+        create_hook(root, HOOK_PRE_COMMIT, reject);
+        create_hook_in(&root.join("hooks"), HOOK_POST_COMMIT, reject);
+        repo.config()
+            .unwrap()
+            .set_str(CONFIG_HOOKS_PATH, "hooks")
+            .unwrap();
+
+        // the local one is shadowed by `core.hooksPath`
+        assert_eq!(
+            hooks_pre_commit(repo_path).unwrap(),
+            HookResult::Ok
+        );
+        assert!(
+            hooks_post_commit(repo_path).unwrap() != HookResult::Ok
+        );
+
+        let hooks = get_hooks(repo_path).unwrap();
+        assert_eq!(hooks.len(), 2);
+        assert_eq!(hooks[0].name, HOOK_POST_COMMIT);
+        assert_eq!(hooks[0].source, HookSource::HooksPath);
+        assert!(hooks[0].active);
+        assert_eq!(hooks[1].name, HOOK_PRE_COMMIT);
+        assert_eq!(hooks[1].source, HookSource::Local);
+        assert!(!hooks[1].active);
+
+        set_hook_enabled(repo_path, HOOK_POST_COMMIT, false).unwrap();
+        assert_eq!(
+            hooks_post_commit(repo_path).unwrap(),
+            HookResult::Ok
+        );
+        assert!(!get_hooks(repo_path).unwrap()[0].enabled);
+
+        // running by hand ignores the disabled state
+        let res =
+            run_hook_manually(repo_path, &hooks[0].path).unwrap();
+        assert_eq!(
+            res,
+            HookOutput {
+                success: false,
+                output: String::from("rejected\n"),
+            }
+        );
+
+        set_hook_enabled(repo_path, HOOK_POST_COMMIT, true).unwrap();
+        assert!(
+            hooks_post_commit(repo_path).unwrap() != HookResult::Ok
+        );
+    }
 }
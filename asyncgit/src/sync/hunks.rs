@@ -1,85 +1,292 @@
 use super::{
-    diff::{get_diff_raw, HunkHeader},
+    diff::{get_diff_raw, DiffLinePosition, DiffOptions, HunkHeader},
     utils::repo,
+    InstanceLock, RepoPath,
 };
 use crate::{
     error::{Error, Result},
     hash,
 };
-use git2::{ApplyLocation, ApplyOptions, Diff};
+use git2::{ApplyLocation, ApplyOptions, Diff, Patch, Repository};
 use scopetime::scope_time;
+use std::collections::HashSet;
 
 ///
 pub fn stage_hunk(
-    repo_path: &str,
+    repo_path: &RepoPath,
     file_path: &str,
     hunk_hash: u64,
+    options: Option<DiffOptions>,
 ) -> Result<()> {
     scope_time!("stage_hunk");
 
+    let _lock = InstanceLock::acquire(repo_path)?;
+
     let repo = repo(repo_path)?;
 
-    let diff = get_diff_raw(&repo, file_path, false, false, None)?;
+    let hunks =
+        hunks_to_apply(&repo, file_path, false, hunk_hash, options)?;
 
-    let mut opt = ApplyOptions::new();
-    opt.hunk_callback(|hunk| {
-        hunk.map_or(false, |hunk| {
-            let header = HunkHeader::from(hunk);
-            hash(&header) == hunk_hash
-        })
-    });
+    let diff = get_diff_raw(&repo, file_path, false, false, None)?;
 
-    repo.apply(&diff, ApplyLocation::Index, Some(&mut opt))?;
+    apply_hunks(&repo, &diff, ApplyLocation::Index, &hunks)?;
 
     Ok(())
 }
 
+/// reverts a single unstaged hunk in the workdir,
 /// this will fail for an all untracked file
-pub fn reset_hunk(
-    repo_path: &str,
+pub fn reset_hunk_workdir(
+    repo_path: &RepoPath,
     file_path: &str,
     hunk_hash: u64,
+    options: Option<DiffOptions>,
 ) -> Result<()> {
-    scope_time!("reset_hunk");
+    scope_time!("reset_hunk_workdir");
+
+    let _lock = InstanceLock::acquire(repo_path)?;
 
     let repo = repo(repo_path)?;
 
-    let diff = get_diff_raw(&repo, file_path, false, false, None)?;
+    let hunks =
+        hunks_to_apply(&repo, file_path, false, hunk_hash, options)?;
+
+    let diff = get_diff_raw(&repo, file_path, false, true, None)?;
+
+    apply_hunks(&repo, &diff, ApplyLocation::WorkDir, &hunks)?;
+
+    Ok(())
+}
+
+/// reverts the selected unstaged `lines` in the workdir by applying
+/// a reversed patch that only contains those lines.
+/// `lines` are positions as seen in the (non reversed) unstaged diff.
+pub fn reset_lines_workdir(
+    repo_path: &RepoPath,
+    file_path: &str,
+    lines: &[DiffLinePosition],
+) -> Result<()> {
+    scope_time!("reset_lines_workdir");
+
+    let _lock = InstanceLock::acquire(repo_path)?;
+
+    if lines.is_empty() {
+        return Ok(());
+    }
+
+    let repo = repo(repo_path)?;
+
+    let diff = get_diff_raw(&repo, file_path, false, true, None)?;
+    if diff.deltas().len() != 1 {
+        return Err(Error::Generic(String::from("patch error")));
+    }
+
+    let patch = Patch::from_diff(&diff, 0)?.ok_or_else(|| {
+        Error::Generic(String::from("no patch found"))
+    })?;
+
+    let buffer = reverse_patch_of_lines(&patch, file_path, lines)?;
+    if buffer.is_empty() {
+        return Err(Error::Generic(String::from(
+            "selected lines not found",
+        )));
+    }
+
+    let diff = Diff::from_buffer(&buffer)?;
+    repo.apply(&diff, ApplyLocation::WorkDir, None)?;
+
+    Ok(())
+}
+
+/// indices of the hunks to apply for the hunk `hunk_hash` of the
+/// diff displayed with `options`: the hunks of the diff with default
+/// options that change lines within its range. ignored whitespace
+/// and the context only change how a diff is displayed, a patch
+/// applied has to match the real contents
+fn hunks_to_apply(
+    repo: &Repository,
+    file_path: &str,
+    stage: bool,
+    hunk_hash: u64,
+    options: Option<DiffOptions>,
+) -> Result<HashSet<usize>> {
+    let displayed =
+        get_diff_raw(repo, file_path, stage, false, options)?;
+    let header =
+        find_hunk_header(&displayed, hunk_hash).ok_or_else(|| {
+            Error::Generic("hunk not found".to_string())
+        })?;
+
+    let diff = get_diff_raw(repo, file_path, stage, false, None)?;
+    if diff.deltas().len() != 1 {
+        return Err(Error::Generic("hunk not found".to_string()));
+    }
+
+    let patch = Patch::from_diff(&diff, 0)?.ok_or_else(|| {
+        Error::Generic(String::from("no patch found"))
+    })?;
+
+    let mut hunks = HashSet::new();
+
+    for hunk_idx in 0..patch.num_hunks() {
+        let (_hunk, line_count) = patch.hunk(hunk_idx)?;
+
+        for line_idx in 0..line_count {
+            let line = patch.line_in_hunk(hunk_idx, line_idx)?;
+            if header.spans_change(&line) {
+                hunks.insert(hunk_idx);
+                break;
+            }
+        }
+    }
+
+    Ok(hunks)
+}
+
+/// applies the hunks of `diff` with these indices,
+/// returns how many got applied
+fn apply_hunks(
+    repo: &Repository,
+    diff: &Diff,
+    location: ApplyLocation,
+    hunks: &HashSet<usize>,
+) -> Result<usize> {
+    let mut count = 0;
 
-    let hunk_index = find_hunk_index(&diff, hunk_hash);
-    if let Some(hunk_index) = hunk_index {
+    {
         let mut hunk_idx = 0;
         let mut opt = ApplyOptions::new();
         opt.hunk_callback(|_hunk| {
-            let res = hunk_idx == hunk_index;
+            let res = hunks.contains(&hunk_idx);
+            if res {
+                count += 1;
+            }
+
             hunk_idx += 1;
+
             res
         });
 
-        let diff = get_diff_raw(&repo, file_path, false, true, None)?;
+        repo.apply(diff, location, Some(&mut opt))?;
+    }
 
-        repo.apply(&diff, ApplyLocation::WorkDir, Some(&mut opt))?;
+    Ok(count)
+}
 
-        Ok(())
-    } else {
-        Err(Error::Generic("hunk not found".to_string()))
+/// builds a patch from the reversed workdir `patch` that
+/// only reverts the selected `lines`: unselected removals are kept
+/// as context and unselected additions are dropped.
+/// returns an empty buffer if no selected line was found.
+fn reverse_patch_of_lines(
+    patch: &Patch,
+    file_path: &str,
+    lines: &[DiffLinePosition],
+) -> Result<Vec<u8>> {
+    // the patch is reversed, so old and new sides are swapped
+    let selection = lines
+        .iter()
+        .map(|line| DiffLinePosition {
+            old_lineno: line.new_lineno,
+            new_lineno: line.old_lineno,
+        })
+        .collect::<HashSet<_>>();
+
+    let mut hunks = Vec::new();
+    let mut offset = 0_i64;
+
+    for hunk_idx in 0..patch.num_hunks() {
+        let (hunk, line_count) = patch.hunk(hunk_idx)?;
+
+        let mut body = Vec::new();
+        let mut old_count = 0_u32;
+        let mut new_count = 0_u32;
+        let mut any_selected = false;
+
+        for line_idx in 0..line_count {
+            let line = patch.line_in_hunk(hunk_idx, line_idx)?;
+            let selected =
+                selection.contains(&DiffLinePosition::from(&line));
+
+            let origin = match (line.origin(), selected) {
+                (' ', _) | ('-', false) => b' ',
+                ('-', true) => b'-',
+                ('+', true) => b'+',
+                // unselected additions and end of file markers
+                _ => continue,
+            };
+
+            any_selected |= selected;
+            if origin != b'+' {
+                old_count += 1;
+            }
+            if origin != b'-' {
+                new_count += 1;
+            }
+
+            body.push(origin);
+            body.extend_from_slice(line.content());
+            if !line.content().ends_with(b"\n") {
+                body.extend_from_slice(
+                    b"\n\\ No newline at end of file\n",
+                );
+            }
+        }
+
+        if !any_selected {
+            continue;
+        }
+
+        let old_pos = if hunk.old_lines() == 0 {
+            i64::from(hunk.old_start())
+        } else {
+            i64::from(hunk.old_start()) - 1
+        };
+        let new_pos = old_pos + offset;
+        let new_start =
+            if new_count == 0 { new_pos } else { new_pos + 1 };
+        offset += i64::from(new_count) - i64::from(old_count);
+
+        hunks.extend_from_slice(
+            format!(
+                "@@ -{},{} +{},{} @@\n",
+                hunk.old_start(),
+                old_count,
+                new_start,
+                new_count
+            )
+            .as_bytes(),
+        );
+        hunks.append(&mut body);
     }
+
+    if hunks.is_empty() {
+        return Ok(hunks);
+    }
+
+    let mut buffer = format!(
+        "diff --git a/{0} b/{0}\n--- a/{0}\n+++ b/{0}\n",
+        file_path
+    )
+    .into_bytes();
+    buffer.append(&mut hunks);
+
+    Ok(buffer)
 }
 
-fn find_hunk_index(diff: &Diff, hunk_hash: u64) -> Option<usize> {
+fn find_hunk_header(
+    diff: &Diff,
+    hunk_hash: u64,
+) -> Option<HunkHeader> {
     let mut result = None;
 
-    let mut hunk_count = 0;
-
     let foreach_result = diff.foreach(
         &mut |_, _| true,
         None,
         Some(&mut |_, hunk| {
             let header = HunkHeader::from(hunk);
             if hash(&header) == hunk_hash {
-                result = Some(hunk_count);
+                result = Some(header);
             }
-            hunk_count += 1;
             true
         }),
         None,
@@ -94,54 +301,33 @@ fn find_hunk_index(diff: &Diff, hunk_hash: u64) -> Option<usize> {
 
 ///
 pub fn unstage_hunk(
-    repo_path: &str,
+    repo_path: &RepoPath,
     file_path: &str,
     hunk_hash: u64,
+    options: Option<DiffOptions>,
 ) -> Result<bool> {
     scope_time!("revert_hunk");
 
-    let repo = repo(repo_path)?;
+    let _lock = InstanceLock::acquire(repo_path)?;
 
-    let diff = get_diff_raw(&repo, file_path, true, false, None)?;
-    let diff_count_positive = diff.deltas().len();
+    let repo = repo(repo_path)?;
 
-    let hunk_index = find_hunk_index(&diff, hunk_hash);
-    let hunk_index = hunk_index.map_or_else(
-        || Err(Error::Generic("hunk not found".to_string())),
-        Ok,
-    )?;
+    let hunks =
+        hunks_to_apply(&repo, file_path, true, hunk_hash, options)?;
 
     let diff = get_diff_raw(&repo, file_path, true, true, None)?;
 
-    if diff.deltas().len() != diff_count_positive {
+    if diff.deltas().len() != 1 {
         return Err(Error::Generic(format!(
-            "hunk error: {}!={}",
-            diff.deltas().len(),
-            diff_count_positive
+            "hunk error: {}!=1",
+            diff.deltas().len()
         )));
     }
 
-    let mut count = 0;
-    {
-        let mut hunk_idx = 0;
-        let mut opt = ApplyOptions::new();
-        opt.hunk_callback(|_hunk| {
-            let res = if hunk_idx == hunk_index {
-                count += 1;
-                true
-            } else {
-                false
-            };
-
-            hunk_idx += 1;
+    let count =
+        apply_hunks(&repo, &diff, ApplyLocation::Index, &hunks)?;
 
-            res
-        });
-
-        repo.apply(&diff, ApplyLocation::Index, Some(&mut opt))?;
-    }
-
-    Ok(count == 1)
+    Ok(count == hunks.len())
 }
 
 #[cfg(test)]
@@ -149,7 +335,11 @@ mod tests {
     use super::*;
     use crate::{
         error::Result,
-        sync::{diff::get_diff, tests::repo_init_empty},
+        sync::{
+            diff::{get_diff, DiffLineType, DiffOptions, FileDiff},
+            tests::{repo_init, repo_init_empty, write_commit_file},
+            utils::{repo_read_file, repo_write_file},
+        },
     };
     use std::{
         fs::{self, File},
@@ -162,7 +352,8 @@ mod tests {
         let file_path = Path::new("foo/foo.txt");
         let (_td, repo) = repo_init_empty()?;
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         let sub_path = root.join("foo/");
 
@@ -170,18 +361,423 @@ mod tests {
         File::create(&root.join(file_path))?.write_all(b"test")?;
 
         let diff = get_diff(
-            sub_path.to_str().unwrap(),
+            &sub_path.to_str().unwrap().into(),
             file_path.to_str().unwrap(),
             false,
+            None,
         )?;
 
-        assert!(reset_hunk(
+        assert!(reset_hunk_workdir(
             repo_path,
             file_path.to_str().unwrap(),
             diff.hunks[0].header_hash,
+            None,
         )
         .is_err());
 
         Ok(())
     }
+
+    #[test]
+    fn test_stage_hunk_ignoring_whitespace() -> Result<()> {
+        let (_td, repo) = repo_init_empty()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        write_commit_file(&repo, "foo.txt", "a\nb\nc\n", "c1");
+        repo_write_file(&repo, "foo.txt", "a  \nb\nC\n")?;
+
+        let options = DiffOptions {
+            ignore_whitespace: true,
+            ..DiffOptions::default()
+        };
+        let changes = |diff: &FileDiff| {
+            diff.hunks
+                .iter()
+                .flat_map(|hunk| hunk.lines.iter())
+                .filter(|line| {
+                    matches!(
+                        line.line_type,
+                        DiffLineType::Add | DiffLineType::Delete
+                    )
+                })
+                .count()
+        };
+
+        // the whitespace change of `a` is not displayed
+        let diff =
+            get_diff(repo_path, "foo.txt", false, Some(options))?;
+        assert_eq!(diff.hunks.len(), 1);
+        assert_eq!(changes(&diff), 2);
+
+        stage_hunk(
+            repo_path,
+            "foo.txt",
+            diff.hunks[0].header_hash,
+            Some(options),
+        )?;
+
+        // it is staged along with the change it is displayed with
+        let diff = get_diff(repo_path, "foo.txt", false, None)?;
+        assert!(diff.hunks.is_empty());
+        let staged = get_diff(repo_path, "foo.txt", true, None)?;
+        assert_eq!(changes(&staged), 4);
+
+        let staged =
+            get_diff(repo_path, "foo.txt", true, Some(options))?;
+        assert!(unstage_hunk(
+            repo_path,
+            "foo.txt",
+            staged.hunks[0].header_hash,
+            Some(options),
+        )?);
+
+        let staged = get_diff(repo_path, "foo.txt", true, None)?;
+        assert!(staged.hunks.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reset_lines_workdir() -> Result<()> {
+        let (_td, repo) = repo_init_empty()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        write_commit_file(&repo, "foo.txt", "a\nb\nc\nd\n", "c1");
+
+        File::create(&root.join("foo.txt"))?
+            .write_all(b"a\nB\nc\nd\ne\n")?;
+
+        let diff = get_diff(repo_path, "foo.txt", false, None)?;
+        let position = |content: &str| {
+            diff.hunks
+                .iter()
+                .flat_map(|hunk| hunk.lines.iter())
+                .find(|line| line.content.trim_end() == content)
+                .map(|line| line.position)
+                .unwrap()
+        };
+
+        // discard the added line at the end only
+        reset_lines_workdir(repo_path, "foo.txt", &[position("e")])?;
+
+        assert_eq!(
+            fs::read_to_string(root.join("foo.txt"))?,
+            "a\nB\nc\nd\n"
+        );
+
+        // discard the modification of `b`
+        reset_lines_workdir(
+            repo_path,
+            "foo.txt",
+            &[position("b"), position("B")],
+        )?;
+
+        assert_eq!(
+            fs::read_to_string(root.join("foo.txt"))?,
+            "a\nb\nc\nd\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_discard() {
+        static FILE_1: &str = r"0
+1
+2
+3
+4
+";
+
+        static FILE_2: &str = r"0
+
+
+3
+4
+";
+
+        static FILE_3: &str = r"0
+2
+
+3
+4
+";
+
+        let (td, repo) = repo_init().unwrap();
+        let path: &RepoPath = &td.path().to_str().unwrap().into();
+
+        write_commit_file(&repo, "test.txt", FILE_1, "c1");
+
+        repo_write_file(&repo, "test.txt", FILE_2).unwrap();
+
+        reset_lines_workdir(
+            path,
+            "test.txt",
+            &[
+                DiffLinePosition {
+                    old_lineno: Some(3),
+                    new_lineno: None,
+                },
+                DiffLinePosition {
+                    old_lineno: None,
+                    new_lineno: Some(2),
+                },
+            ],
+        )
+        .unwrap();
+
+        let result_file = repo_read_file(&repo, "test.txt").unwrap();
+
+        assert_eq!(result_file.as_str(), FILE_3);
+    }
+
+    #[test]
+    fn test_discard2() {
+        static FILE_1: &str = r"start
+end
+";
+
+        static FILE_2: &str = r"start
+1
+2
+end
+";
+
+        static FILE_3: &str = r"start
+1
+end
+";
+
+        let (td, repo) = repo_init().unwrap();
+        let path: &RepoPath = &td.path().to_str().unwrap().into();
+
+        write_commit_file(&repo, "test.txt", FILE_1, "c1");
+
+        repo_write_file(&repo, "test.txt", FILE_2).unwrap();
+
+        reset_lines_workdir(
+            path,
+            "test.txt",
+            &[DiffLinePosition {
+                old_lineno: None,
+                new_lineno: Some(3),
+            }],
+        )
+        .unwrap();
+
+        let result_file = repo_read_file(&repo, "test.txt").unwrap();
+
+        assert_eq!(result_file.as_str(), FILE_3);
+    }
+
+    #[test]
+    fn test_discard3() {
+        static FILE_1: &str = r"start
+1
+end
+";
+
+        static FILE_2: &str = r"start
+2
+end
+";
+
+        static FILE_3: &str = r"start
+1
+end
+";
+
+        let (td, repo) = repo_init().unwrap();
+        let path: &RepoPath = &td.path().to_str().unwrap().into();
+
+        write_commit_file(&repo, "test.txt", FILE_1, "c1");
+
+        repo_write_file(&repo, "test.txt", FILE_2).unwrap();
+
+        reset_lines_workdir(
+            path,
+            "test.txt",
+            &[
+                DiffLinePosition {
+                    old_lineno: Some(2),
+                    new_lineno: None,
+                },
+                DiffLinePosition {
+                    old_lineno: None,
+                    new_lineno: Some(2),
+                },
+            ],
+        )
+        .unwrap();
+
+        let result_file = repo_read_file(&repo, "test.txt").unwrap();
+
+        assert_eq!(result_file.as_str(), FILE_3);
+    }
+
+    #[test]
+    fn test_discard4() {
+        static FILE_1: &str = r"start
+mid
+end
+";
+
+        static FILE_2: &str = r"start
+1
+mid
+2
+end
+";
+
+        static FILE_3: &str = r"start
+mid
+end
+";
+
+        let (td, repo) = repo_init().unwrap();
+        let path: &RepoPath = &td.path().to_str().unwrap().into();
+
+        write_commit_file(&repo, "test.txt", FILE_1, "c1");
+
+        repo_write_file(&repo, "test.txt", FILE_2).unwrap();
+
+        reset_lines_workdir(
+            path,
+            "test.txt",
+            &[
+                DiffLinePosition {
+                    old_lineno: None,
+                    new_lineno: Some(2),
+                },
+                DiffLinePosition {
+                    old_lineno: None,
+                    new_lineno: Some(4),
+                },
+            ],
+        )
+        .unwrap();
+
+        let result_file = repo_read_file(&repo, "test.txt").unwrap();
+
+        assert_eq!(result_file.as_str(), FILE_3);
+    }
+
+    #[test]
+    fn test_discard_if_first_selected_line_is_not_in_any_hunk() {
+        static FILE_1: &str = r"start
+end
+";
+
+        static FILE_2: &str = r"start
+1
+end
+";
+
+        static FILE_3: &str = r"start
+end
+";
+
+        let (td, repo) = repo_init().unwrap();
+        let path: &RepoPath = &td.path().to_str().unwrap().into();
+
+        write_commit_file(&repo, "test.txt", FILE_1, "c1");
+
+        repo_write_file(&repo, "test.txt", FILE_2).unwrap();
+
+        reset_lines_workdir(
+            path,
+            "test.txt",
+            &[
+                DiffLinePosition {
+                    old_lineno: None,
+                    new_lineno: Some(1),
+                },
+                DiffLinePosition {
+                    old_lineno: None,
+                    new_lineno: Some(2),
+                },
+            ],
+        )
+        .unwrap();
+
+        let result_file = repo_read_file(&repo, "test.txt").unwrap();
+
+        assert_eq!(result_file.as_str(), FILE_3);
+    }
+
+    //this test shows that we require at least a diff context around add/removes of 1
+    #[test]
+    fn test_discard_deletions_filestart_breaking_with_zero_context() {
+        static FILE_1: &str = r"start
+mid
+end
+";
+
+        static FILE_2: &str = r"start
+end
+";
+
+        static FILE_3: &str = r"start
+mid
+end
+";
+
+        let (td, repo) = repo_init().unwrap();
+        let path: &RepoPath = &td.path().to_str().unwrap().into();
+
+        write_commit_file(&repo, "test.txt", FILE_1, "c1");
+
+        repo_write_file(&repo, "test.txt", FILE_2).unwrap();
+
+        reset_lines_workdir(
+            path,
+            "test.txt",
+            &[DiffLinePosition {
+                old_lineno: Some(2),
+                new_lineno: None,
+            }],
+        )
+        .unwrap();
+
+        let result_file = repo_read_file(&repo, "test.txt").unwrap();
+
+        assert_eq!(result_file.as_str(), FILE_3);
+    }
+
+    #[test]
+    fn test_discard5() {
+        static FILE_1: &str = r"start
+";
+
+        static FILE_2: &str = r"start
+1";
+
+        static FILE_3: &str = r"start
+";
+
+        let (td, repo) = repo_init().unwrap();
+        let path: &RepoPath = &td.path().to_str().unwrap().into();
+
+        write_commit_file(&repo, "test.txt", FILE_1, "c1");
+
+        repo_write_file(&repo, "test.txt", FILE_2).unwrap();
+
+        reset_lines_workdir(
+            path,
+            "test.txt",
+            &[DiffLinePosition {
+                old_lineno: None,
+                new_lineno: Some(2),
+            }],
+        )
+        .unwrap();
+
+        let result_file = repo_read_file(&repo, "test.txt").unwrap();
+
+        assert_eq!(result_file.as_str(), FILE_3);
+    }
 }
@@ -1,17 +1,71 @@
-use super::utils::{repo, work_dir};
+use super::{
+    utils::{repo, work_dir},
+    RepoPath,
+};
 use crate::error::Result;
+use git2::Repository;
 use scopetime::scope_time;
 use std::{
-    fs::{File, OpenOptions},
+    fs::{self, File, OpenOptions},
     io::{Read, Seek, SeekFrom, Write},
-    path::Path,
+    path::{Path, PathBuf},
 };
 
 static GITIGNORE: &str = ".gitignore";
 
-/// add file or path to root ignore file
+/// what of a path gets ignored
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IgnoreKind {
+    /// exactly this file (or folder)
+    File,
+    /// all files with the same extension anywhere
+    Extension,
+    /// the folder containing the file
+    Directory,
+}
+
+/// pattern for the root ignore file ignoring the repo-root-relative
+/// `path` (folders end with `/`), `None` if that kind does not apply
+/// to it (no extension or no parent folder)
+pub fn ignore_pattern(
+    path: &str,
+    kind: IgnoreKind,
+) -> Option<String> {
+    let path = path.replace('\\', "/");
+    let path = path.trim_start_matches('/');
+    let is_folder = path.ends_with('/');
+    let path = path.trim_end_matches('/');
+
+    if path.is_empty() {
+        return None;
+    }
+
+    // a leading slash anchors the pattern at the root, otherwise a
+    // path without inner slashes would match in every folder
+    match kind {
+        IgnoreKind::File => Some(if is_folder {
+            format!("/{}/", path)
+        } else {
+            format!("/{}", path)
+        }),
+        IgnoreKind::Extension if !is_folder => Path::new(path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| format!("*.{}", ext)),
+        IgnoreKind::Extension => None,
+        IgnoreKind::Directory if is_folder => {
+            Some(format!("/{}/", path))
+        }
+        IgnoreKind::Directory => {
+            path.rfind('/').map(|idx| format!("/{}/", &path[..idx]))
+        }
+    }
+}
+
+/// add file or path to root ignore file, patterns already in there
+/// are not added again
 pub fn add_to_ignore(
-    repo_path: &str,
+    repo_path: &RepoPath,
     path_to_ignore: &str,
 ) -> Result<()> {
     scope_time!("add_to_ignore");
@@ -20,6 +74,14 @@ pub fn add_to_ignore(
 
     let ignore_file = work_dir(&repo)?.join(GITIGNORE);
 
+    if ignore_file.exists()
+        && fs::read_to_string(&ignore_file)?
+            .lines()
+            .any(|line| line.trim_end() == path_to_ignore)
+    {
+        return Ok(());
+    }
+
     let optional_newline = ignore_file.exists()
         && !file_ends_with_newline(&ignore_file)?;
 
@@ -38,6 +100,51 @@ pub fn add_to_ignore(
     Ok(())
 }
 
+/// tells ignored paths of the working tree apart, keeping the
+/// repository open for the many paths a file watcher reports
+pub struct IgnoreFilter {
+    repo: Repository,
+    work_dir: PathBuf,
+}
+
+impl IgnoreFilter {
+    ///
+    pub fn new(repo_path: &RepoPath) -> Result<Self> {
+        let repo = repo(repo_path)?;
+        let work_dir = work_dir(&repo)?.to_path_buf();
+
+        Ok(Self { repo, work_dir })
+    }
+
+    ///
+    pub fn work_dir(&self) -> &Path {
+        &self.work_dir
+    }
+
+    /// the `.git` folder
+    pub fn git_dir(&self) -> &Path {
+        self.repo.path()
+    }
+
+    /// `true` if the absolute `path` is ignored, this includes paths
+    /// in ignored folders. paths outside the working tree are not
+    pub fn is_ignored(&self, path: &Path, is_folder: bool) -> bool {
+        let relative = match path.strip_prefix(&self.work_dir) {
+            Ok(relative) if relative != Path::new("") => relative,
+            _ => return false,
+        };
+
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        let relative = if is_folder {
+            format!("{}/", relative)
+        } else {
+            relative
+        };
+
+        self.repo.is_path_ignored(&relative).unwrap_or_default()
+    }
+}
+
 fn file_ends_with_newline(file: &Path) -> Result<bool> {
     let mut file = File::open(file)?;
     let size = file.metadata()?.len();
@@ -62,7 +169,8 @@ mod tests {
         let file_path = Path::new("foo.txt");
         let (_td, repo) = repo_init()?;
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         File::create(&root.join(file_path))?.write_all(b"test")?;
 
@@ -89,7 +197,8 @@ mod tests {
         let file_path = Path::new("foo.txt");
         let (_td, repo) = repo_init()?;
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         File::create(&root.join(file_path))?.write_all(b"test")?;
         File::create(&root.join(ignore_file_path))?
@@ -110,7 +219,8 @@ mod tests {
         let file_path = Path::new("foo.txt");
         let (_td, repo) = repo_init()?;
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         File::create(&root.join(file_path))?.write_all(b"test")?;
         File::create(&root.join(ignore_file_path))?
@@ -124,4 +234,85 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_no_duplicates() -> Result<()> {
+        let ignore_file_path = Path::new(".gitignore");
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        add_to_ignore(repo_path, "/foo.txt")?;
+        add_to_ignore(repo_path, "/foo.txt")?;
+
+        assert_eq!(
+            read_lines(&root.join(ignore_file_path))?.count(),
+            1
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_patterns() {
+        assert_eq!(
+            ignore_pattern("a/b/c.txt", IgnoreKind::File).as_deref(),
+            Some("/a/b/c.txt")
+        );
+        assert_eq!(
+            ignore_pattern("a/b/c.txt", IgnoreKind::Extension)
+                .as_deref(),
+            Some("*.txt")
+        );
+        assert_eq!(
+            ignore_pattern("a/b/c.txt", IgnoreKind::Directory)
+                .as_deref(),
+            Some("/a/b/")
+        );
+        assert_eq!(ignore_pattern("c", IgnoreKind::Extension), None);
+        assert_eq!(
+            ignore_pattern("c.txt", IgnoreKind::Directory),
+            None
+        );
+        assert_eq!(
+            ignore_pattern("a/b/", IgnoreKind::Directory).as_deref(),
+            Some("/a/b/")
+        );
+        assert_eq!(
+            ignore_pattern("a/b/", IgnoreKind::Extension),
+            None
+        );
+        assert_eq!(
+            ignore_pattern("a\\c.txt", IgnoreKind::File).as_deref(),
+            Some("/a/c.txt")
+        );
+    }
+
+    #[test]
+    fn test_ignore_filter() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        fs::create_dir_all(root.join("target/debug"))?;
+        fs::create_dir_all(root.join("src"))?;
+        File::create(&root.join(".gitignore"))?
+            .write_all(b"target/\n*.log\n")?;
+
+        let filter = IgnoreFilter::new(repo_path)?;
+
+        assert!(filter.is_ignored(&root.join("target"), true));
+        assert!(filter.is_ignored(&root.join("target/debug"), true));
+        assert!(
+            filter.is_ignored(&root.join("target/debug/a.o"), false)
+        );
+        assert!(filter.is_ignored(&root.join("src/a.log"), false));
+        assert!(!filter.is_ignored(&root.join("src"), true));
+        assert!(!filter.is_ignored(&root.join("src/main.rs"), false));
+        assert!(!filter.is_ignored(root, true));
+
+        Ok(())
+    }
 }
@@ -0,0 +1,192 @@
+//! index entry flags hiding local changes of tracked files from the
+//! status, like `git update-index --assume-unchanged` and
+//! `--skip-worktree`
+
+use super::{utils::repo, InstanceLock, RepoPath};
+use crate::error::{Error, Result};
+use git2::Index;
+use scopetime::scope_time;
+use std::{collections::HashMap, path::Path};
+
+// see `git_index_entry_flag_t` and `git_index_entry_extended_flag_t`
+const ENTRY_VALID: u16 = 1 << 15;
+const ENTRY_EXTENDED: u16 = 1 << 14;
+const ENTRY_SKIP_WORKTREE: u16 = 1 << 14;
+
+/// flag of an index entry making git ignore changes of the file
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum IndexFlag {
+    /// `--assume-unchanged`, a promise the file does not change
+    AssumeUnchanged,
+    /// `--skip-worktree`, the file is meant to differ locally
+    SkipWorktree,
+}
+
+/// sets or clears `--assume-unchanged` of the tracked file at `path`
+pub fn set_assume_unchanged(
+    repo_path: &RepoPath,
+    path: &str,
+    enabled: bool,
+) -> Result<()> {
+    scope_time!("set_assume_unchanged");
+
+    let _lock = InstanceLock::acquire(repo_path)?;
+
+    set_flag(repo_path, path, IndexFlag::AssumeUnchanged, enabled)
+}
+
+/// sets or clears `--skip-worktree` of the tracked file at `path`
+pub fn set_skip_worktree(
+    repo_path: &RepoPath,
+    path: &str,
+    enabled: bool,
+) -> Result<()> {
+    scope_time!("set_skip_worktree");
+
+    let _lock = InstanceLock::acquire(repo_path)?;
+
+    set_flag(repo_path, path, IndexFlag::SkipWorktree, enabled)
+}
+
+/// files in the index having any of the flags, sorted by path
+pub fn get_index_flags(
+    repo_path: &RepoPath,
+) -> Result<Vec<(String, IndexFlag)>> {
+    scope_time!("get_index_flags");
+
+    let repo = repo(repo_path)?;
+    let mut res: Vec<_> =
+        index_flags(&repo.index()?).into_iter().collect();
+    res.sort_by(|a, b| a.0.cmp(&b.0));
+
+    Ok(res)
+}
+
+/// paths of the flagged files (skip-worktree wins if both are set)
+pub fn index_flags(index: &Index) -> HashMap<String, IndexFlag> {
+    index
+        .iter()
+        .filter_map(|entry| {
+            let flag =
+                if entry.flags_extended & ENTRY_SKIP_WORKTREE != 0 {
+                    IndexFlag::SkipWorktree
+                } else if entry.flags & ENTRY_VALID != 0 {
+                    IndexFlag::AssumeUnchanged
+                } else {
+                    return None;
+                };
+
+            let path = String::from_utf8(entry.path).ok()?;
+            Some((path, flag))
+        })
+        .collect()
+}
+
+fn set_flag(
+    repo_path: &RepoPath,
+    path: &str,
+    flag: IndexFlag,
+    enabled: bool,
+) -> Result<()> {
+    let repo = repo(repo_path)?;
+    let mut index = repo.index()?;
+
+    let mut entry =
+        index.get_path(Path::new(path), 0).ok_or_else(|| {
+            Error::Generic(format!("'{}' is not tracked", path))
+        })?;
+
+    match flag {
+        IndexFlag::AssumeUnchanged => {
+            entry.flags =
+                toggle_bits(entry.flags, ENTRY_VALID, enabled);
+        }
+        IndexFlag::SkipWorktree => {
+            entry.flags_extended = toggle_bits(
+                entry.flags_extended,
+                ENTRY_SKIP_WORKTREE,
+                enabled,
+            );
+        }
+    }
+
+    // git only reads extended flags of entries saying they have some
+    entry.flags = toggle_bits(
+        entry.flags,
+        ENTRY_EXTENDED,
+        entry.flags_extended != 0,
+    );
+
+    index.add(&entry)?;
+    index.write()?;
+
+    Ok(())
+}
+
+const fn toggle_bits(value: u16, bits: u16, enabled: bool) -> u16 {
+    if enabled {
+        value | bits
+    } else {
+        value & !bits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::{
+        status::{get_status, StatusType},
+        tests::{repo_init, write_commit_file},
+        utils::repo_write_file,
+    };
+
+    #[test]
+    fn test_flags_hide_changes() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        write_commit_file(&repo, "a.txt", "a", "a");
+        write_commit_file(&repo, "b.txt", "b", "b");
+        repo_write_file(&repo, "a.txt", "local a").unwrap();
+        repo_write_file(&repo, "b.txt", "local b").unwrap();
+
+        let status = || {
+            get_status(repo_path, StatusType::WorkingDir, true)
+                .unwrap()
+                .len()
+        };
+
+        assert_eq!(status(), 2);
+
+        set_skip_worktree(repo_path, "a.txt", true).unwrap();
+        set_assume_unchanged(repo_path, "b.txt", true).unwrap();
+
+        assert_eq!(status(), 0);
+        assert_eq!(
+            get_index_flags(repo_path).unwrap(),
+            vec![
+                (String::from("a.txt"), IndexFlag::SkipWorktree),
+                (String::from("b.txt"), IndexFlag::AssumeUnchanged),
+            ]
+        );
+
+        set_skip_worktree(repo_path, "a.txt", false).unwrap();
+
+        assert_eq!(status(), 1);
+        assert_eq!(get_index_flags(repo_path).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_untracked() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        repo_write_file(&repo, "a.txt", "a").unwrap();
+
+        assert!(set_skip_worktree(repo_path, "a.txt", true).is_err());
+    }
+}
@@ -0,0 +1,411 @@
+//! coordinates gitui instances running on the same repository
+
+use super::{utils::repo_dir, RepoPath};
+use crate::error::{Error, Result};
+use once_cell::sync::Lazy;
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    fs::{self, OpenOptions},
+    io::{ErrorKind, Write},
+    path::{Path, PathBuf},
+    process,
+    sync::Mutex,
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// lock files older than this were left behind by a crashed instance
+const LOCK_STALE: Duration = Duration::from_secs(30);
+
+const LOCK_FILE: &str = "gitui.lock";
+const CHANGE_FILE: &str = "gitui_changed";
+
+/// lock files this process holds
+static HELD: Lazy<Mutex<HashMap<PathBuf, Held>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// a lock file held by this process
+struct Held {
+    /// number of guards alive
+    guards: usize,
+    /// whether one of them changed the repository
+    changes: bool,
+    /// what this process wrote into the lock file
+    owner: InstanceChange,
+}
+
+/// the last change to the repository some gitui instance made
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InstanceChange {
+    /// process id of the instance
+    pub pid: u32,
+    /// time of the change in nanoseconds since epoch
+    pub nanos: u128,
+}
+
+impl InstanceChange {
+    fn now() -> Self {
+        Self {
+            pid: process::id(),
+            nanos: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|time| time.as_nanos())
+                .unwrap_or_default(),
+        }
+    }
+
+    fn parse(content: &str) -> Option<Self> {
+        let mut parts = content.split_whitespace();
+        let pid = parts.next()?.parse().ok()?;
+        let nanos = parts.next()?.parse().ok()?;
+
+        Some(Self { pid, nanos })
+    }
+
+    /// `true` if this process made the change
+    pub fn is_own(&self) -> bool {
+        self.pid == process::id()
+    }
+
+    fn age(&self) -> Option<Duration> {
+        let now = Self::now().nanos;
+        let age = now.checked_sub(self.nanos)?;
+
+        Some(Duration::from_nanos(u64::try_from(age).ok()?))
+    }
+
+    fn write(&self, file: &mut fs::File) -> Result<()> {
+        write!(file, "{} {}", self.pid, self.nanos)?;
+        Ok(())
+    }
+
+    /// the instance holding the lock `file`, `None` if it is gone or
+    /// unreadable
+    fn read(file: &Path) -> Option<Self> {
+        fs::read_to_string(file)
+            .ok()
+            .as_deref()
+            .and_then(Self::parse)
+    }
+}
+
+/// serializes the changes of the index and refs between gitui
+/// instances by a lock file in the `.git` folder. it never waits:
+/// while another instance holds the lock acquiring fails with
+/// `Error::InstanceLocked`. the lock is reentrant within the process,
+/// only other processes are locked out, acquiring it again refreshes
+/// it so it does not turn stale. releasing the lock after a change
+/// announces it to the other instances, see `last_instance_change`
+pub struct InstanceLock {
+    file: PathBuf,
+}
+
+impl InstanceLock {
+    /// locks the repository for a change, to be held for the whole
+    /// read-modify-write of the index or refs
+    pub fn acquire(repo_path: &RepoPath) -> Result<Self> {
+        Self::lock(&repo_dir(repo_path)?, true)
+    }
+
+    /// locks the repository without announcing a change when
+    /// released, for writes that do not change its content (like
+    /// refreshing the stat data of the index)
+    pub fn acquire_silent(repo_path: &RepoPath) -> Result<Self> {
+        Self::lock(&repo_dir(repo_path)?, false)
+    }
+
+    /// like `acquire` but retries until `timeout`, never to be used
+    /// on the ui thread
+    pub fn acquire_wait(
+        repo_path: &RepoPath,
+        timeout: Duration,
+    ) -> Result<Self> {
+        let dir = repo_dir(repo_path)?;
+        let start = Instant::now();
+
+        loop {
+            match Self::lock(&dir, true) {
+                Err(Error::InstanceLocked)
+                    if start.elapsed() < timeout =>
+                {
+                    thread::sleep(Duration::from_millis(10));
+                }
+                res => return res,
+            }
+        }
+    }
+
+    fn lock(dir: &Path, changes: bool) -> Result<Self> {
+        let file = dir.join(LOCK_FILE);
+
+        let mut held = HELD.lock()?;
+
+        if let Some(entry) = held.get_mut(&file) {
+            entry.guards += 1;
+            entry.changes |= changes;
+            entry.owner = Self::refresh(&file, entry.owner);
+            return Ok(Self { file });
+        }
+
+        if Self::is_stale(&file) {
+            log::warn!("removing stale instance lock: {:?}", file);
+            fs::remove_file(&file)?;
+        }
+
+        let mut lock_file = match OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&file)
+        {
+            Ok(lock_file) => lock_file,
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                return Err(Error::InstanceLocked)
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let owner = InstanceChange::now();
+        if let Err(e) = owner.write(&mut lock_file) {
+            fs::remove_file(&file)?;
+            return Err(e);
+        }
+
+        held.insert(
+            file.clone(),
+            Held {
+                guards: 1,
+                changes,
+                owner,
+            },
+        );
+
+        Ok(Self { file })
+    }
+
+    /// writes the current time into the lock `file` if it is still
+    /// `owner`'s, returns its new owner
+    fn refresh(file: &Path, owner: InstanceChange) -> InstanceChange {
+        if InstanceChange::read(file) != Some(owner) {
+            log::warn!("instance lock was taken over: {:?}", file);
+            return owner;
+        }
+
+        let refreshed = InstanceChange::now();
+        let res =
+            fs::File::create(file).map_err(Error::from).and_then(
+                |mut lock_file| refreshed.write(&mut lock_file),
+            );
+
+        match res {
+            Ok(()) => refreshed,
+            Err(e) => {
+                log::error!("instance lock refresh error: {}", e);
+                owner
+            }
+        }
+    }
+
+    /// a lock file is stale if it was taken (see the time written
+    /// into it, or its modification time if unreadable) longer than
+    /// `LOCK_STALE` ago
+    fn is_stale(file: &Path) -> bool {
+        let age = InstanceChange::read(file)
+            .and_then(|change| change.age())
+            .or_else(|| {
+                fs::metadata(file)
+                    .and_then(|meta| meta.modified())
+                    .ok()
+                    .and_then(|modified| modified.elapsed().ok())
+            });
+
+        age.map_or(false, |age| age > LOCK_STALE)
+    }
+
+    fn release(&self) -> Result<()> {
+        let mut held = HELD.lock()?;
+
+        let (changes, owner) = match held.get_mut(&self.file) {
+            Some(entry) if entry.guards > 1 => {
+                entry.guards -= 1;
+                return Ok(());
+            }
+            Some(entry) => (entry.changes, entry.owner),
+            None => return Ok(()),
+        };
+
+        held.remove(&self.file);
+
+        if changes {
+            if let Some(dir) = self.file.parent() {
+                let mut change_file =
+                    fs::File::create(dir.join(CHANGE_FILE))?;
+                InstanceChange::now().write(&mut change_file)?;
+            }
+        }
+
+        // held past `LOCK_STALE` another instance may have taken the
+        // lock over, it is theirs to remove then
+        if InstanceChange::read(&self.file) == Some(owner) {
+            fs::remove_file(&self.file)?;
+        } else {
+            log::warn!(
+                "instance lock was taken over: {:?}",
+                self.file
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for InstanceLock {
+    fn drop(&mut self) {
+        if let Err(e) = self.release() {
+            log::error!("instance unlock error: {}", e);
+        }
+    }
+}
+
+/// the change announced last by any gitui instance working on this
+/// repository, `None` if there was none yet
+pub fn last_instance_change(
+    repo_path: &RepoPath,
+) -> Result<Option<InstanceChange>> {
+    let file = repo_dir(repo_path)?.join(CHANGE_FILE);
+
+    match fs::read_to_string(file) {
+        Ok(content) => Ok(InstanceChange::parse(&content)),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::tests::repo_init;
+
+    fn foreign_lock(repo_path: &RepoPath, nanos: u128) -> PathBuf {
+        let file = repo_dir(repo_path).unwrap().join(LOCK_FILE);
+        fs::write(&file, format!("{} {}", process::id() + 1, nanos))
+            .unwrap();
+        file
+    }
+
+    #[test]
+    fn test_contention() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath = &root.to_str().unwrap().into();
+
+        let file =
+            foreign_lock(repo_path, InstanceChange::now().nanos);
+
+        assert!(matches!(
+            InstanceLock::acquire(repo_path),
+            Err(Error::InstanceLocked)
+        ));
+        assert!(matches!(
+            InstanceLock::acquire_wait(
+                repo_path,
+                Duration::from_millis(50)
+            ),
+            Err(Error::InstanceLocked)
+        ));
+
+        // the other instance releases it
+        fs::remove_file(&file).unwrap();
+
+        let lock = InstanceLock::acquire(repo_path).unwrap();
+        assert!(file.exists());
+        drop(lock);
+        assert!(!file.exists());
+    }
+
+    #[test]
+    fn test_stale() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath = &root.to_str().unwrap().into();
+
+        let taken = InstanceChange::now().nanos
+            - LOCK_STALE.as_nanos()
+            - Duration::from_secs(1).as_nanos();
+        let file = foreign_lock(repo_path, taken);
+
+        let lock = InstanceLock::acquire(repo_path).unwrap();
+        let content = fs::read_to_string(&file).unwrap();
+        assert_eq!(
+            InstanceChange::parse(&content).map(|change| change.pid),
+            Some(process::id())
+        );
+        drop(lock);
+        assert!(!file.exists());
+    }
+
+    #[test]
+    fn test_taken_over() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath = &root.to_str().unwrap().into();
+
+        let lock = InstanceLock::acquire(repo_path).unwrap();
+
+        // held so long another instance found it stale
+        let file =
+            foreign_lock(repo_path, InstanceChange::now().nanos);
+        let content = fs::read_to_string(&file).unwrap();
+
+        let inner = InstanceLock::acquire(repo_path).unwrap();
+        drop(inner);
+        drop(lock);
+
+        assert_eq!(fs::read_to_string(&file).unwrap(), content);
+    }
+
+    #[test]
+    fn test_refresh() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath = &root.to_str().unwrap().into();
+        let file = repo_dir(repo_path).unwrap().join(LOCK_FILE);
+
+        let lock = InstanceLock::acquire(repo_path).unwrap();
+        let taken = InstanceChange::read(&file).unwrap();
+
+        thread::sleep(Duration::from_millis(10));
+
+        let inner = InstanceLock::acquire(repo_path).unwrap();
+        let refreshed = InstanceChange::read(&file).unwrap();
+        assert!(refreshed.is_own());
+        assert!(refreshed.nanos > taken.nanos);
+
+        drop(inner);
+        drop(lock);
+        assert!(!file.exists());
+    }
+
+    #[test]
+    fn test_reentrant_and_notify() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath = &root.to_str().unwrap().into();
+
+        {
+            let _lock =
+                InstanceLock::acquire_silent(repo_path).unwrap();
+        }
+        assert_eq!(last_instance_change(repo_path).unwrap(), None);
+
+        let outer = InstanceLock::acquire_silent(repo_path).unwrap();
+        let inner = InstanceLock::acquire(repo_path).unwrap();
+        drop(inner);
+        assert_eq!(last_instance_change(repo_path).unwrap(), None);
+        drop(outer);
+
+        let change =
+            last_instance_change(repo_path).unwrap().unwrap();
+        assert!(change.is_own());
+    }
+}
@@ -0,0 +1,517 @@
+//! snapshots of what destructive operations change, to undo them
+
+use super::{
+    stash::STASH_REF,
+    utils::{get_head_repo, repo, work_dir},
+    InstanceLock, RepoPath, ResetType,
+};
+use crate::error::Result;
+use git2::{Oid, Repository, Signature, StatusOptions};
+use scopetime::scope_time;
+use std::{fs, path::Path};
+
+/// how many operations can be undone
+const JOURNAL_LIMIT: usize = 20;
+
+/// a destructive operation the journal can undo
+#[derive(Debug, Clone, PartialEq)]
+pub enum JournalOp {
+    /// discarding the unstaged changes of these files or folders,
+    /// of everything if empty
+    Discard(Vec<String>),
+    /// moving the current branch back to the parent commit, the
+    /// changes stay staged
+    UndoCommit,
+    /// moving the current branch to another commit
+    Reset(ResetType),
+    /// deleting the branch with this full ref name
+    DeleteBranch(String),
+    /// dropping a stash
+    DropStash,
+}
+
+struct StashEntry {
+    id: Oid,
+    message: String,
+    committer: Signature<'static>,
+}
+
+/// the state of everything an operation changes, `None` meaning
+/// the ref or file did not exist
+#[derive(Default)]
+struct Snapshot {
+    head: Option<(Oid, ResetType)>,
+    /// the tree of the index
+    index: Option<Oid>,
+    refs: Vec<(String, Option<Oid>)>,
+    files: Vec<(String, Option<Oid>)>,
+    stashes: Option<Vec<StashEntry>>,
+}
+
+impl Snapshot {
+    fn take(repo: &Repository, op: &JournalOp) -> Result<Self> {
+        let mut snapshot = Self::default();
+
+        match op {
+            JournalOp::Discard(paths) => {
+                snapshot.files = changed_files(repo, paths)?;
+            }
+            JournalOp::UndoCommit => {
+                snapshot.head = Some((
+                    get_head_repo(repo)?.into(),
+                    ResetType::Soft,
+                ));
+            }
+            JournalOp::Reset(kind) => {
+                snapshot.head =
+                    Some((get_head_repo(repo)?.into(), *kind));
+                if *kind != ResetType::Soft {
+                    snapshot.index =
+                        Some(repo.index()?.write_tree()?);
+                }
+                if *kind == ResetType::Hard {
+                    snapshot.files = changed_files(repo, &[])?;
+                }
+            }
+            JournalOp::DeleteBranch(name) => {
+                snapshot.refs = vec![(
+                    name.clone(),
+                    repo.refname_to_id(name).ok(),
+                )];
+            }
+            JournalOp::DropStash => {
+                snapshot.stashes = Some(stashes(repo)?);
+            }
+        }
+
+        Ok(snapshot)
+    }
+
+    /// the current state of what this snapshot covers, restoring
+    /// it redoes what restoring `self` undid
+    fn retake(&self, repo: &Repository) -> Result<Self> {
+        let work_dir = work_dir(repo)?;
+
+        Ok(Self {
+            head: match self.head {
+                Some((_, kind)) => {
+                    Some((get_head_repo(repo)?.into(), kind))
+                }
+                None => None,
+            },
+            index: match self.index {
+                Some(_) => Some(repo.index()?.write_tree()?),
+                None => None,
+            },
+            refs: self
+                .refs
+                .iter()
+                .map(|(name, _)| {
+                    (name.clone(), repo.refname_to_id(name).ok())
+                })
+                .collect(),
+            files: self
+                .files
+                .iter()
+                .map(|(path, _)| {
+                    Ok((
+                        path.clone(),
+                        file_blob(repo, work_dir, path)?,
+                    ))
+                })
+                .collect::<Result<_>>()?,
+            stashes: if self.stashes.is_some() {
+                Some(stashes(repo)?)
+            } else {
+                None
+            },
+        })
+    }
+
+    fn restore(&self, repo: &Repository) -> Result<()> {
+        if let Some((id, kind)) = self.head {
+            repo.reset(
+                &repo.find_object(id, None)?,
+                kind.into(),
+                None,
+            )?;
+        }
+
+        if let Some(tree) = self.index {
+            let mut index = repo.index()?;
+            index.read_tree(&repo.find_tree(tree)?)?;
+            index.write()?;
+        }
+
+        for (name, id) in &self.refs {
+            if let Some(id) = id {
+                repo.reference(name, *id, true, "gitui: undo")?;
+            } else if let Ok(mut reference) =
+                repo.find_reference(name)
+            {
+                reference.delete()?;
+            }
+        }
+
+        let work_dir = work_dir(repo)?;
+        for (path, blob) in &self.files {
+            restore_file(repo, &work_dir.join(path), *blob)?;
+        }
+
+        if let Some(stashes) = &self.stashes {
+            restore_stashes(repo, stashes)?;
+        }
+
+        Ok(())
+    }
+}
+
+struct Entry {
+    op: JournalOp,
+    snapshot: Snapshot,
+}
+
+/// operations that can be undone and the undone ones that can be
+/// redone, newest last
+#[derive(Default)]
+pub struct Journal {
+    undo: Vec<Entry>,
+    redo: Vec<Entry>,
+}
+
+impl Journal {
+    /// snapshots what `op` is about to change, call right before
+    /// running it
+    pub fn record(
+        &mut self,
+        repo_path: &RepoPath,
+        op: JournalOp,
+    ) -> Result<()> {
+        scope_time!("journal_record");
+
+        let repo = repo(repo_path)?;
+        let snapshot = Snapshot::take(&repo, &op)?;
+
+        if self.undo.len() == JOURNAL_LIMIT {
+            self.undo.remove(0);
+        }
+        self.undo.push(Entry { op, snapshot });
+        self.redo.clear();
+
+        Ok(())
+    }
+
+    /// forgets the last recorded operation, for when it failed
+    pub fn cancel(&mut self) {
+        self.undo.pop();
+    }
+
+    /// the operation `undo` reverts
+    pub fn last_undo(&self) -> Option<&JournalOp> {
+        self.undo.last().map(|entry| &entry.op)
+    }
+
+    /// the operation `redo` runs again
+    pub fn last_redo(&self) -> Option<&JournalOp> {
+        self.redo.last().map(|entry| &entry.op)
+    }
+
+    /// restores the state before the last operation
+    pub fn undo(
+        &mut self,
+        repo_path: &RepoPath,
+    ) -> Result<Option<JournalOp>> {
+        scope_time!("journal_undo");
+
+        Self::step(repo_path, &mut self.undo, &mut self.redo)
+    }
+
+    /// restores the state the last undo reverted
+    pub fn redo(
+        &mut self,
+        repo_path: &RepoPath,
+    ) -> Result<Option<JournalOp>> {
+        scope_time!("journal_redo");
+
+        Self::step(repo_path, &mut self.redo, &mut self.undo)
+    }
+
+    fn step(
+        repo_path: &RepoPath,
+        from: &mut Vec<Entry>,
+        to: &mut Vec<Entry>,
+    ) -> Result<Option<JournalOp>> {
+        if from.is_empty() {
+            return Ok(None);
+        }
+
+        // another instance must not change the repo in between
+        let _lock = InstanceLock::acquire(repo_path)?;
+
+        let entry = match from.pop() {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+
+        let repo = repo(repo_path)?;
+        let current = entry.snapshot.retake(&repo)?;
+        entry.snapshot.restore(&repo)?;
+
+        let op = entry.op.clone();
+        to.push(Entry {
+            op: entry.op,
+            snapshot: current,
+        });
+
+        Ok(Some(op))
+    }
+}
+
+/// files with staged or unstaged changes, limited to `paths` and
+/// their content if `paths` is not empty
+fn changed_files(
+    repo: &Repository,
+    paths: &[String],
+) -> Result<Vec<(String, Option<Oid>)>> {
+    let work_dir = work_dir(repo)?;
+
+    let statuses = repo.statuses(Some(
+        StatusOptions::default()
+            .include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .exclude_submodules(true),
+    ))?;
+
+    let selected = |path: &str| {
+        paths.is_empty()
+            || paths.iter().any(|p| {
+                let p = p.trim_end_matches('/');
+                path.strip_prefix(p).map_or(false, |rest| {
+                    rest.is_empty() || rest.starts_with('/')
+                })
+            })
+    };
+
+    statuses
+        .iter()
+        .filter_map(|entry| entry.path().map(String::from))
+        // nested repositories show up as a single directory entry
+        .filter(|path| !path.ends_with('/') && selected(path))
+        .map(|path| {
+            let blob = file_blob(repo, work_dir, &path)?;
+            Ok((path, blob))
+        })
+        .collect()
+}
+
+/// stores the content of the file in the object database
+fn file_blob(
+    repo: &Repository,
+    work_dir: &Path,
+    path: &str,
+) -> Result<Option<Oid>> {
+    let path = work_dir.join(path);
+
+    if path.is_file() {
+        Ok(Some(repo.blob_path(&path)?))
+    } else {
+        Ok(None)
+    }
+}
+
+fn restore_file(
+    repo: &Repository,
+    path: &Path,
+    blob: Option<Oid>,
+) -> Result<()> {
+    if let Some(blob) = blob {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, repo.find_blob(blob)?.content())?;
+    } else if path.is_file() {
+        fs::remove_file(path)?;
+    }
+
+    Ok(())
+}
+
+/// the stash list, newest first
+fn stashes(repo: &Repository) -> Result<Vec<StashEntry>> {
+    if repo.find_reference(STASH_REF).is_err() {
+        return Ok(Vec::new());
+    }
+
+    Ok(repo
+        .reflog(STASH_REF)?
+        .iter()
+        .map(|entry| StashEntry {
+            id: entry.id_new(),
+            message: entry.message().unwrap_or_default().to_string(),
+            committer: entry.committer().to_owned(),
+        })
+        .collect())
+}
+
+/// rewrites the stash list (the reflog of `refs/stash`)
+fn restore_stashes(
+    repo: &Repository,
+    stashes: &[StashEntry],
+) -> Result<()> {
+    let newest = match stashes.first() {
+        Some(newest) => newest,
+        None => {
+            if let Ok(mut reference) = repo.find_reference(STASH_REF)
+            {
+                reference.delete()?;
+            }
+            return Ok(());
+        }
+    };
+
+    repo.reference(STASH_REF, newest.id, true, &newest.message)?;
+
+    let mut reflog = repo.reflog(STASH_REF)?;
+    while !reflog.is_empty() {
+        reflog.remove(0, false)?;
+    }
+    for stash in stashes.iter().rev() {
+        reflog.append(
+            stash.id,
+            &stash.committer,
+            Some(&stash.message),
+        )?;
+    }
+    reflog.write()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Journal, JournalOp};
+    use crate::sync::{
+        create_branch_at, delete_branch, get_head, get_stashes,
+        reset_to_commit, reset_workdir, stage_add_file, stash_drop,
+        stash_save,
+        tests::{get_statuses, repo_init, write_commit_file},
+        utils::repo_write_file,
+        RepoPath, ResetType,
+    };
+    use std::{fs, path::Path};
+
+    #[test]
+    fn test_undo_redo_discard() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        write_commit_file(&repo, "foo", "a", "c1");
+        repo_write_file(&repo, "foo", "b").unwrap();
+
+        let mut journal = Journal::default();
+        journal
+            .record(repo_path, JournalOp::Discard(vec!["foo".into()]))
+            .unwrap();
+        reset_workdir(repo_path, "foo").unwrap();
+        assert_eq!(
+            fs::read_to_string(root.join("foo")).unwrap(),
+            "a"
+        );
+
+        assert_eq!(
+            journal.undo(repo_path).unwrap(),
+            Some(JournalOp::Discard(vec!["foo".into()]))
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("foo")).unwrap(),
+            "b"
+        );
+        assert!(journal.last_undo().is_none());
+
+        journal.redo(repo_path).unwrap();
+        assert_eq!(
+            fs::read_to_string(root.join("foo")).unwrap(),
+            "a"
+        );
+    }
+
+    #[test]
+    fn test_undo_delete_branch() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        let c1 = write_commit_file(&repo, "foo", "a", "c1");
+        create_branch_at(repo_path, "feature", c1).unwrap();
+
+        let mut journal = Journal::default();
+        let op = JournalOp::DeleteBranch("refs/heads/feature".into());
+        journal.record(repo_path, op).unwrap();
+        delete_branch(repo_path, "refs/heads/feature").unwrap();
+
+        journal.undo(repo_path).unwrap();
+        assert_eq!(
+            repo.refname_to_id("refs/heads/feature").unwrap(),
+            c1.into()
+        );
+
+        journal.redo(repo_path).unwrap();
+        assert!(repo.refname_to_id("refs/heads/feature").is_err());
+    }
+
+    #[test]
+    fn test_undo_reset_mixed() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        let c1 = write_commit_file(&repo, "foo", "a", "c1");
+        let c2 = write_commit_file(&repo, "foo", "b", "c2");
+        repo_write_file(&repo, "bar", "staged").unwrap();
+        stage_add_file(repo_path, Path::new("bar")).unwrap();
+
+        let mut journal = Journal::default();
+        journal
+            .record(repo_path, JournalOp::Reset(ResetType::Mixed))
+            .unwrap();
+        reset_to_commit(repo_path, c1, ResetType::Mixed).unwrap();
+        assert_eq!(get_head(repo_path).unwrap(), c1);
+        assert_eq!(get_statuses(repo_path), (2, 0));
+
+        journal.undo(repo_path).unwrap();
+        assert_eq!(get_head(repo_path).unwrap(), c2);
+        assert_eq!(get_statuses(repo_path), (0, 1));
+
+        journal.redo(repo_path).unwrap();
+        assert_eq!(get_head(repo_path).unwrap(), c1);
+        assert_eq!(get_statuses(repo_path), (2, 0));
+    }
+
+    #[test]
+    fn test_undo_drop_stash() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        repo_write_file(&repo, "foo", "a").unwrap();
+        stash_save(repo_path, Some("one"), true, false, false)
+            .unwrap();
+        repo_write_file(&repo, "foo", "b").unwrap();
+        stash_save(repo_path, Some("two"), true, false, false)
+            .unwrap();
+        let stashes = get_stashes(repo_path).unwrap();
+
+        let mut journal = Journal::default();
+        journal.record(repo_path, JournalOp::DropStash).unwrap();
+        stash_drop(repo_path, stashes[1]).unwrap();
+        assert_eq!(get_stashes(repo_path).unwrap().len(), 1);
+
+        journal.undo(repo_path).unwrap();
+        assert_eq!(get_stashes(repo_path).unwrap(), stashes);
+    }
+}
@@ -1,23 +1,284 @@
-use super::CommitId;
+use super::{replace::Replacements, CommitId};
 use crate::error::Result;
-use git2::{Repository, Revwalk};
+use git2::{Commit, Oid, Repository, Revwalk};
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashSet},
+    path::Path,
+};
 
-///
+/// limits the commits of a `LogWalker`, like the options of the same
+/// name of `git log`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LogFilter {
+    /// part of the author name or email, ignoring case
+    pub author: Option<String>,
+    /// only commits changing the file or folder (relative to the
+    /// workdir)
+    pub path: Option<String>,
+    /// committed at or after this time (seconds since epoch)
+    pub since: Option<i64>,
+    /// committed at or before this time (seconds since epoch)
+    pub until: Option<i64>,
+    /// walks the history of this revision (e.g. a remote branch)
+    /// instead of `HEAD`, like `git log <rev>`
+    pub rev: Option<String>,
+    /// also walks the branches and tags matching this glob (e.g.
+    /// `release/*`), like `git log --glob=<glob> HEAD`
+    pub refs: Option<String>,
+}
+
+impl LogFilter {
+    /// no commit gets filtered out
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// the commit the walk starts at, `None` on an unborn branch
+    #[allow(clippy::redundant_pub_crate)]
+    pub(crate) fn start(
+        &self,
+        repo: &Repository,
+    ) -> Result<Option<Oid>> {
+        if let Some(rev) = &self.rev {
+            return Ok(Some(
+                repo.revparse_single(rev)?.peel_to_commit()?.id(),
+            ));
+        }
+
+        Ok(repo.head()?.target())
+    }
+
+    /// the commits of the refs matching `refs`, a glob not starting
+    /// with `refs/` matches local and remote branches and tags
+    fn ref_tips(&self, repo: &Repository) -> Result<Vec<Oid>> {
+        let pattern = match &self.refs {
+            Some(pattern) => pattern,
+            None => return Ok(Vec::new()),
+        };
+
+        let globs = if pattern.starts_with("refs/") {
+            vec![pattern.clone()]
+        } else {
+            ["refs/heads/", "refs/remotes/", "refs/tags/"]
+                .iter()
+                .map(|prefix| format!("{}{}", prefix, pattern))
+                .collect()
+        };
+
+        let mut tips = Vec::new();
+        for glob in &globs {
+            for reference in repo.references_glob(glob)? {
+                if let Ok(commit) = reference?.peel_to_commit() {
+                    tips.push(commit.id());
+                }
+            }
+        }
+
+        Ok(tips)
+    }
+
+    fn matches(
+        &self,
+        repo: &Repository,
+        walk: &Walk,
+        id: Oid,
+    ) -> Result<bool> {
+        if self.is_empty() {
+            return Ok(true);
+        }
+
+        let commit = walk.commit(repo, id)?;
+        let time = commit.time().seconds();
+
+        if self.since.map_or(false, |since| time < since)
+            || self.until.map_or(false, |until| time > until)
+        {
+            return Ok(false);
+        }
+
+        if let Some(author) = &self.author {
+            let author = author.to_lowercase();
+            let signature = commit.author();
+
+            if ![signature.name_bytes(), signature.email_bytes()]
+                .iter()
+                .any(|part| {
+                    String::from_utf8_lossy(part)
+                        .to_lowercase()
+                        .contains(&author)
+                })
+            {
+                return Ok(false);
+            }
+        }
+
+        if let Some(path) = &self.path {
+            return Ok(Self::changes_path(
+                repo,
+                walk,
+                id,
+                &commit,
+                Path::new(path.trim_end_matches('/')),
+            ));
+        }
+
+        Ok(true)
+    }
+
+    /// `commit` differs from all of its parents at `path` (merges
+    /// taking it from one parent do not count, like in `git log`)
+    fn changes_path(
+        repo: &Repository,
+        walk: &Walk,
+        id: Oid,
+        commit: &Commit,
+        path: &Path,
+    ) -> bool {
+        let entry = |commit: &Commit| {
+            commit
+                .tree()
+                .ok()
+                .and_then(|tree| tree.get_path(path).ok())
+                .map(|entry| entry.id())
+        };
+
+        let own = entry(commit);
+        let parents = walk.parents(id, commit);
+
+        if parents.is_empty() {
+            return own.is_some();
+        }
+
+        parents.iter().all(|parent| {
+            walk.commit(repo, *parent)
+                .map_or(true, |parent| entry(&parent) != own)
+        })
+    }
+}
+
+enum Walk<'a> {
+    Revwalk(Revwalk<'a>),
+    /// libgit2 does not know about replacements, so these histories
+    /// get walked by hand
+    Replaced(ReplacedWalk),
+}
+
+impl<'a> Walk<'a> {
+    fn next(&mut self, repo: &Repository) -> Option<Oid> {
+        match self {
+            Self::Revwalk(walk) => {
+                walk.find_map(std::result::Result::ok)
+            }
+            Self::Replaced(walk) => walk.next(repo),
+        }
+    }
+
+    /// the commit shown for `id`
+    fn commit<'r>(
+        &self,
+        repo: &'r Repository,
+        id: Oid,
+    ) -> Result<Commit<'r>> {
+        match self {
+            Self::Revwalk(_) => Ok(repo.find_commit(id)?),
+            Self::Replaced(walk) => {
+                walk.replacements.commit(repo, id)
+            }
+        }
+    }
+
+    /// the parents shown for `id`
+    fn parents(&self, id: Oid, commit: &Commit) -> Vec<Oid> {
+        match self {
+            Self::Revwalk(_) => commit.parent_ids().collect(),
+            Self::Replaced(walk) => {
+                walk.replacements.parents(id, commit)
+            }
+        }
+    }
+}
+
+/// newest commits first, commits of the same time in the order they
+/// were found
+struct ReplacedWalk {
+    replacements: Replacements,
+    queue: BinaryHeap<(i64, Reverse<usize>, Oid)>,
+    seen: HashSet<Oid>,
+}
+
+impl ReplacedWalk {
+    fn new(
+        repo: &Repository,
+        replacements: Replacements,
+        starts: &[Oid],
+    ) -> Self {
+        let mut walk = Self {
+            replacements,
+            queue: BinaryHeap::new(),
+            seen: HashSet::new(),
+        };
+
+        for start in starts {
+            walk.push(repo, *start);
+        }
+
+        walk
+    }
+
+    fn push(&mut self, repo: &Repository, id: Oid) {
+        if self.seen.insert(id) {
+            // parents missing without a shallow boundary end the
+            // walk there
+            if let Ok(commit) = self.replacements.commit(repo, id) {
+                self.queue.push((
+                    commit.time().seconds(),
+                    Reverse(self.seen.len()),
+                    id,
+                ));
+            }
+        }
+    }
+
+    fn next(&mut self, repo: &Repository) -> Option<Oid> {
+        let (_, _, id) = self.queue.pop()?;
+
+        if let Ok(commit) = self.replacements.commit(repo, id) {
+            for parent in self.replacements.parents(id, &commit) {
+                self.push(repo, parent);
+            }
+        }
+
+        Some(id)
+    }
+}
+
+/// walks the history of `HEAD` the way git shows it (see
+/// `replace.rs`)
 pub struct LogWalker<'a> {
     repo: &'a Repository,
-    revwalk: Option<Revwalk<'a>>,
+    walk: Option<Walk<'a>>,
+    filter: LogFilter,
 }
 
 impl<'a> LogWalker<'a> {
     ///
-    pub const fn new(repo: &'a Repository) -> Self {
+    pub fn new(repo: &'a Repository) -> Self {
         Self {
             repo,
-            revwalk: None,
+            walk: None,
+            filter: LogFilter::default(),
         }
     }
 
-    ///
+    /// only yields the commits matching `filter`
+    pub fn with_filter(mut self, filter: LogFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// walks up to `limit` commits and adds the ones matching the
+    /// filter to `out`, returns how many were walked
     pub fn read(
         &mut self,
         out: &mut Vec<CommitId>,
@@ -25,15 +286,32 @@ impl<'a> LogWalker<'a> {
     ) -> Result<usize> {
         let mut count = 0_usize;
 
-        if self.revwalk.is_none() {
-            let mut walk = self.repo.revwalk()?;
-            walk.push_head()?;
-            self.revwalk = Some(walk);
+        if self.walk.is_none() {
+            let replacements = Replacements::new(self.repo)?;
+            let mut starts: Vec<Oid> =
+                self.filter.start(self.repo)?.into_iter().collect();
+            starts.extend(self.filter.ref_tips(self.repo)?);
+
+            self.walk = Some(if replacements.is_empty() {
+                let mut walk = self.repo.revwalk()?;
+                for start in &starts {
+                    walk.push(*start)?;
+                }
+                Walk::Revwalk(walk)
+            } else {
+                Walk::Replaced(ReplacedWalk::new(
+                    self.repo,
+                    replacements,
+                    &starts,
+                ))
+            });
         }
 
-        if let Some(ref mut walk) = self.revwalk {
-            for id in walk.into_iter().flatten() {
-                out.push(id.into());
+        if let Some(ref mut walk) = self.walk {
+            while let Some(id) = walk.next(self.repo) {
+                if self.filter.matches(self.repo, walk, id)? {
+                    out.push(id.into());
+                }
                 count += 1;
 
                 if count == limit {
@@ -60,7 +338,8 @@ mod tests {
         let file_path = Path::new("foo");
         let (_td, repo) = repo_init_empty().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         File::create(&root.join(file_path))?.write_all(b"a")?;
         stage_add_file(repo_path, file_path).unwrap();
@@ -84,7 +363,8 @@ mod tests {
         let file_path = Path::new("foo");
         let (_td, repo) = repo_init_empty().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         File::create(&root.join(file_path))?.write_all(b"a")?;
         stage_add_file(repo_path, file_path).unwrap();
@@ -110,4 +390,134 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_filter() -> Result<()> {
+        let (_td, repo) = repo_init_empty().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        File::create(&root.join("foo"))?.write_all(b"a")?;
+        stage_add_file(repo_path, Path::new("foo")).unwrap();
+        let oid1 = commit(repo_path, "commit1").unwrap();
+        File::create(&root.join("bar"))?.write_all(b"a")?;
+        stage_add_file(repo_path, Path::new("bar")).unwrap();
+        let oid2 = commit(repo_path, "commit2").unwrap();
+
+        let walk = |filter: LogFilter| {
+            let mut items = Vec::new();
+            let walked = LogWalker::new(&repo)
+                .with_filter(filter)
+                .read(&mut items, 100)
+                .unwrap();
+            assert_eq!(walked, 2);
+            items
+        };
+
+        assert_eq!(
+            walk(LogFilter {
+                path: Some(String::from("foo")),
+                ..LogFilter::default()
+            }),
+            vec![oid1]
+        );
+        assert_eq!(
+            walk(LogFilter {
+                author: Some(String::from("NAME")),
+                ..LogFilter::default()
+            }),
+            vec![oid2, oid1]
+        );
+        assert!(walk(LogFilter {
+            author: Some(String::from("nobody")),
+            ..LogFilter::default()
+        })
+        .is_empty());
+        assert!(walk(LogFilter {
+            since: Some(i64::MAX),
+            ..LogFilter::default()
+        })
+        .is_empty());
+        assert_eq!(
+            walk(LogFilter {
+                until: Some(i64::MAX),
+                ..LogFilter::default()
+            })
+            .len(),
+            2
+        );
+
+        let mut items = Vec::new();
+        LogWalker::new(&repo)
+            .with_filter(LogFilter {
+                rev: Some(oid1.to_string()),
+                ..LogFilter::default()
+            })
+            .read(&mut items, 100)
+            .unwrap();
+        assert_eq!(items, vec![oid1]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_filter_refs() -> Result<()> {
+        let (_td, repo) = repo_init_empty().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        File::create(&root.join("foo"))?.write_all(b"a")?;
+        stage_add_file(repo_path, Path::new("foo")).unwrap();
+        let oid1 = commit(repo_path, "commit1").unwrap();
+
+        let branch = |name: &str, file: &str| {
+            let base = repo.find_commit(oid1.into()).unwrap();
+            repo.branch(name, &base, false).unwrap();
+            repo.set_head(&format!("refs/heads/{}", name)).unwrap();
+            repo.checkout_head(Some(
+                git2::build::CheckoutBuilder::new().force(),
+            ))
+            .unwrap();
+            File::create(&root.join(file))
+                .unwrap()
+                .write_all(b"a")
+                .unwrap();
+            stage_add_file(repo_path, Path::new(file)).unwrap();
+            commit(repo_path, file).unwrap()
+        };
+
+        let release = branch("release/1", "release");
+        let feature = branch("feature/x", "feature");
+        let head = branch("main", "main");
+
+        let walk = |refs: Option<&str>| {
+            let mut items = Vec::new();
+            LogWalker::new(&repo)
+                .with_filter(LogFilter {
+                    refs: refs.map(String::from),
+                    ..LogFilter::default()
+                })
+                .read(&mut items, 100)
+                .unwrap();
+            items.sort();
+            items
+        };
+
+        let mut expected = vec![oid1, release, head];
+        expected.sort();
+        assert_eq!(walk(Some("release/*")), expected);
+
+        let mut expected = vec![oid1, release, feature, head];
+        expected.sort();
+        assert_eq!(walk(Some("refs/heads/*")), expected);
+
+        let mut expected = vec![oid1, head];
+        expected.sort();
+        assert_eq!(walk(None), expected);
+        assert_eq!(walk(Some("nothing/*")), expected);
+
+        Ok(())
+    }
 }
@@ -4,14 +4,15 @@ use crate::{
     error::{Error, Result},
     sync::{
         branch::merge_commit::commit_merge_with_head, reset_stage,
-        reset_workdir, utils, CommitId,
+        reset_workdir, stash::apply_pending_autostash, utils,
+        CommitId, InstanceLock, RepoPath,
     },
 };
-use git2::{BranchType, Commit, MergeOptions, Repository};
+use git2::{BranchType, Commit, MergeOptions};
 use scopetime::scope_time;
 
 ///
-pub fn mergehead_ids(repo_path: &str) -> Result<Vec<CommitId>> {
+pub fn mergehead_ids(repo_path: &RepoPath) -> Result<Vec<CommitId>> {
     scope_time!("mergehead_ids");
 
     let mut repo = utils::repo(repo_path)?;
@@ -29,9 +30,11 @@ pub fn mergehead_ids(repo_path: &str) -> Result<Vec<CommitId>> {
 /// * reset all staged changes,
 /// * revert all changes in workdir
 /// * cleanup repo merge state
-pub fn abort_merge(repo_path: &str) -> Result<()> {
+pub fn abort_merge(repo_path: &RepoPath) -> Result<()> {
     scope_time!("cleanup_state");
 
+    let _lock = InstanceLock::acquire(repo_path)?;
+
     let repo = utils::repo(repo_path)?;
 
     reset_stage(repo_path, "*")?;
@@ -39,29 +42,51 @@ pub fn abort_merge(repo_path: &str) -> Result<()> {
 
     repo.cleanup_state()?;
 
+    apply_pending_autostash(repo_path)?;
+
     Ok(())
 }
 
-///
-pub fn merge_branch(repo_path: &str, branch: &str) -> Result<()> {
-    scope_time!("merge_branch");
-
-    let repo = utils::repo(repo_path)?;
-
-    merge_branch_repo(&repo, branch)?;
+/// how `merge_branch` treats a possible fast forward
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum MergeFastForward {
+    /// fast forward if possible, merge otherwise
+    Allow,
+    /// always merge (`--no-ff`)
+    Never,
+    /// fail unless a fast forward is possible (`--ff-only`)
+    Only,
+}
 
-    Ok(())
+/// what `merge_branch` did
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum MergeOutcome {
+    /// the branch was merged already
+    UpToDate,
+    /// the current branch moved to the merged one
+    FastForward,
+    /// index and working tree hold the merge (and its conflicts),
+    /// committing concludes it
+    Merge,
 }
 
-///
-pub fn merge_branch_repo(
-    repo: &Repository,
+/// merges the local or remote branch `branch` into the current one
+pub fn merge_branch(
+    repo_path: &RepoPath,
     branch: &str,
-) -> Result<()> {
-    let branch = repo.find_branch(branch, BranchType::Local)?;
+    fast_forward: MergeFastForward,
+) -> Result<MergeOutcome> {
+    scope_time!("merge_branch");
 
-    let annotated =
-        repo.reference_to_annotated_commit(&branch.into_reference())?;
+    let _lock = InstanceLock::acquire(repo_path)?;
+
+    let repo = utils::repo(repo_path)?;
+
+    let reference = repo
+        .find_branch(branch, BranchType::Local)
+        .or_else(|_| repo.find_branch(branch, BranchType::Remote))?
+        .into_reference();
+    let annotated = repo.reference_to_annotated_commit(&reference)?;
 
     let (analysis, _) = repo.merge_analysis(&[&annotated])?;
 
@@ -70,15 +95,91 @@ pub fn merge_branch_repo(
         return Err(Error::Generic("head is unborn".into()));
     }
 
+    if analysis.is_up_to_date() {
+        return Ok(MergeOutcome::UpToDate);
+    }
+
+    if analysis.is_fast_forward()
+        && fast_forward != MergeFastForward::Never
+    {
+        let commit = reference.peel_to_commit()?;
+        repo.checkout_tree(commit.as_object(), None)?;
+        repo.head()?.set_target(
+            commit.id(),
+            &format!("merge {}: Fast-forward", branch),
+        )?;
+
+        return Ok(MergeOutcome::FastForward);
+    }
+
+    if fast_forward == MergeFastForward::Only {
+        return Err(Error::Generic(
+            "fast forward merge not possible".into(),
+        ));
+    }
+
     let mut opt = MergeOptions::default();
 
     repo.merge(&[&annotated], Some(&mut opt), None)?;
 
-    Ok(())
+    Ok(MergeOutcome::Merge)
+}
+
+/// size of the change to the tracked files
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct DiffStat {
+    ///
+    pub files: usize,
+    ///
+    pub insertions: usize,
+    ///
+    pub deletions: usize,
+}
+
+/// what merging or rebasing onto `rev` changes in the worktree,
+/// compared against the trees merged in memory (or the merge base if
+/// that conflicts)
+pub fn merge_diffstat(
+    repo_path: &RepoPath,
+    rev: &str,
+) -> Result<DiffStat> {
+    scope_time!("merge_diffstat");
+
+    let repo = utils::repo(repo_path)?;
+
+    let head = repo.head()?.peel_to_commit()?;
+    let theirs = repo.revparse_single(rev)?.peel_to_commit()?;
+    let base = repo.merge_base(head.id(), theirs.id())?;
+
+    let merged = if base == head.id() {
+        Some(theirs.tree_id())
+    } else {
+        let mut index = repo.merge_commits(&head, &theirs, None)?;
+        if index.has_conflicts() {
+            None
+        } else {
+            Some(index.write_tree_to(&repo)?)
+        }
+    };
+
+    let (from, to) = match merged {
+        Some(tree) => (head.tree()?, repo.find_tree(tree)?),
+        None => (repo.find_commit(base)?.tree()?, theirs.tree()?),
+    };
+
+    let stats = repo
+        .diff_tree_to_tree(Some(&from), Some(&to), None)?
+        .stats()?;
+
+    Ok(DiffStat {
+        files: stats.files_changed(),
+        insertions: stats.insertions(),
+        deletions: stats.deletions(),
+    })
 }
 
 ///
-pub fn merge_msg(repo_path: &str) -> Result<String> {
+pub fn merge_msg(repo_path: &RepoPath) -> Result<String> {
     scope_time!("merge_msg");
 
     let repo = utils::repo(repo_path)?;
@@ -92,12 +193,14 @@ pub fn merge_msg(repo_path: &str) -> Result<String> {
 
 ///
 pub fn merge_commit(
-    repo_path: &str,
+    repo_path: &RepoPath,
     msg: &str,
     ids: &[CommitId],
 ) -> Result<CommitId> {
     scope_time!("merge_commit");
 
+    let _lock = InstanceLock::acquire(repo_path)?;
+
     let repo = utils::repo(repo_path)?;
 
     let mut commits: Vec<Commit> = Vec::new();
@@ -108,6 +211,8 @@ pub fn merge_commit(
 
     let id = commit_merge_with_head(&repo, &commits, msg)?;
 
+    apply_pending_autostash(repo_path)?;
+
     Ok(id)
 }
 
@@ -115,27 +220,120 @@ pub fn merge_commit(
 mod tests {
     use super::*;
     use crate::sync::{
-        create_branch,
+        checkout_branch, create_branch, get_head, repo_state,
         tests::{repo_init, write_commit_file},
+        RepoState,
     };
 
+    #[test]
+    fn test_merge_diffstat() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        write_commit_file(&repo, "a.txt", "a\n", "commit1");
+
+        create_branch(repo_path, "foo").unwrap();
+
+        write_commit_file(&repo, "b.txt", "1\n2\n", "commit2");
+
+        checkout_branch(repo_path, "refs/heads/master").unwrap();
+
+        let incoming = DiffStat {
+            files: 1,
+            insertions: 2,
+            deletions: 0,
+        };
+
+        // fast forward
+        assert_eq!(
+            merge_diffstat(repo_path, "foo").unwrap(),
+            incoming
+        );
+
+        write_commit_file(&repo, "a.txt", "a\nb\n", "commit3");
+
+        // the own change to `a.txt` does not count
+        assert_eq!(
+            merge_diffstat(repo_path, "foo").unwrap(),
+            incoming
+        );
+    }
+
     #[test]
     fn test_smoke() {
         let (_td, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
-        let c1 =
-            write_commit_file(&repo, "test.txt", "test", "commit1");
+        write_commit_file(&repo, "test.txt", "test", "commit1");
 
         create_branch(repo_path, "foo").unwrap();
 
         write_commit_file(&repo, "test.txt", "test2", "commit2");
 
-        merge_branch(repo_path, "master").unwrap();
+        checkout_branch(repo_path, "refs/heads/master").unwrap();
+
+        let c3 =
+            write_commit_file(&repo, "other.txt", "o", "commit3");
+
+        checkout_branch(repo_path, "refs/heads/foo").unwrap();
+
+        assert_eq!(
+            merge_branch(repo_path, "master", MergeFastForward::Only)
+                .is_err(),
+            true
+        );
+        assert_eq!(
+            merge_branch(
+                repo_path,
+                "master",
+                MergeFastForward::Allow
+            )
+            .unwrap(),
+            MergeOutcome::Merge
+        );
 
         let mergeheads = mergehead_ids(repo_path).unwrap();
 
-        assert_eq!(mergeheads[0], c1);
+        assert_eq!(mergeheads[0], c3);
+    }
+
+    #[test]
+    fn test_fast_forward() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        write_commit_file(&repo, "test.txt", "test", "commit1");
+        create_branch(repo_path, "foo").unwrap();
+        let c2 =
+            write_commit_file(&repo, "test.txt", "test2", "commit2");
+        checkout_branch(repo_path, "refs/heads/master").unwrap();
+
+        assert_eq!(
+            merge_branch(repo_path, "foo", MergeFastForward::Never)
+                .unwrap(),
+            MergeOutcome::Merge
+        );
+        assert_eq!(repo_state(repo_path).unwrap(), RepoState::Merge);
+        abort_merge(repo_path).unwrap();
+
+        assert_eq!(
+            merge_branch(repo_path, "foo", MergeFastForward::Allow)
+                .unwrap(),
+            MergeOutcome::FastForward
+        );
+        assert_eq!(get_head(repo_path).unwrap(), c2);
+        assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+
+        assert_eq!(
+            merge_branch(repo_path, "foo", MergeFastForward::Only)
+                .unwrap(),
+            MergeOutcome::UpToDate
+        );
     }
 }
@@ -3,73 +3,162 @@
 //TODO: remove once we have this activated on the toplevel
 #![deny(clippy::expect_used)]
 
+mod apply_patch;
+mod archive;
 pub mod blame;
 pub mod branch;
 mod commit;
 mod commit_details;
 mod commit_files;
+mod commit_msg_lint;
 mod commits_info;
+mod content_type;
 pub mod cred;
+mod decorations;
 pub mod diff;
+mod format_patch;
+mod funcname;
+mod history;
 mod hooks;
 mod hunks;
 mod ignore;
+mod index_flags;
+mod instance_lock;
+mod journal;
 mod logwalker;
 mod merge;
 mod patches;
+mod rebase;
 pub mod remotes;
+mod replace;
+mod repository;
 mod reset;
+mod signature;
+mod squash;
 mod staging;
 mod stash;
 mod state;
 pub mod status;
+mod submodules;
 mod tags;
 mod tree;
 pub mod utils;
+mod word_diff;
+mod workflow;
 
-pub use blame::{blame_file, BlameHunk, FileBlame};
+pub use apply_patch::apply_patch;
+pub use archive::{archive_commit, ArchiveFormat};
+pub use blame::{
+    blame_file, BlameCopyDetection, BlameHunk, FileBlame,
+};
 pub use branch::{
-    branch_compare_upstream, checkout_branch, config_is_pull_rebase,
-    create_branch, delete_branch, get_branch_remote,
-    get_branches_info, merge_commit::merge_upstream_commit,
-    merge_ff::branch_merge_upstream_fastforward,
-    merge_rebase::merge_upstream_rebase, rename::rename_branch,
-    BranchCompare, BranchInfo,
-};
-pub use commit::{amend, commit, tag};
+    branch_compare_push, branch_compare_upstream, checkout_branch,
+    checkout_commit, checkout_with_strategy, config_is_pull_rebase,
+    create_branch, create_branch_at, delete_branch, get_branch_names,
+    get_branch_remote, get_branches_info, get_branches_info_for,
+    get_push_remote, get_upstream, has_uncommitted_changes,
+    is_head_detached,
+    merge_commit::merge_upstream_commit,
+    merge_ff::{
+        branch_merge_upstream_fastforward, merge_upstream_ff,
+    },
+    merge_rebase::merge_upstream_rebase,
+    rename::rename_branch,
+    set_upstream, BranchCompare, BranchInfo, CheckoutStrategy,
+    PushCompare,
+};
+pub use commit::{
+    amend, amend_staged, commit, commit_with_msg_from,
+    commit_with_options, tag, CommitOptions,
+};
 pub use commit_details::{
     get_commit_details, CommitDetails, CommitMessage, CommitSignature,
 };
-pub use commit_files::get_commit_files;
+pub use commit_files::{get_commit_files, get_compare_files};
+pub use commit_msg_lint::{
+    lint_commit_msg, CommitMsgLint, CommitMsgLintOptions,
+};
 pub use commits_info::{
     get_commit_info, get_commits_info, CommitId, CommitInfo,
 };
-pub use diff::get_diff_commit;
+pub use content_type::{content_type, ContentType, ImageType};
+pub use decorations::{
+    decoration_tags, get_decorations, Decoration, DecorationKind,
+    Decorations,
+};
+pub use diff::{
+    get_diff_base, get_diff_commit, get_diff_commits, DiffOptions,
+};
+pub use format_patch::{format_patch, patch_file_name};
+pub use history::{newer_commit, older_commit};
 pub use hooks::{
-    hooks_commit_msg, hooks_post_commit, hooks_pre_commit, HookResult,
+    get_hooks, hooks_commit_msg, hooks_post_commit, hooks_pre_commit,
+    run_hook_manually, set_hook_enabled, HookInfo, HookOutput,
+    HookResult, HookSource,
+};
+pub use hunks::{
+    reset_hunk_workdir, reset_lines_workdir, stage_hunk, unstage_hunk,
+};
+pub use ignore::{
+    add_to_ignore, ignore_pattern, IgnoreFilter, IgnoreKind,
+};
+pub use index_flags::{
+    get_index_flags, set_assume_unchanged, set_skip_worktree,
+    IndexFlag,
 };
-pub use hunks::{reset_hunk, stage_hunk, unstage_hunk};
-pub use ignore::add_to_ignore;
-pub use logwalker::LogWalker;
+pub use instance_lock::{
+    last_instance_change, InstanceChange, InstanceLock,
+};
+pub use journal::{Journal, JournalOp};
+pub use logwalker::{LogFilter, LogWalker};
 pub use merge::{
-    abort_merge, merge_branch, merge_commit, merge_msg, mergehead_ids,
+    abort_merge, merge_branch, merge_commit, merge_diffstat,
+    merge_msg, mergehead_ids, DiffStat, MergeFastForward,
+    MergeOutcome,
+};
+pub use rebase::{
+    abort_rebase, continue_rebase, move_rebase_step, rebase_branch,
+    rebase_progress, rebase_todo, skip_rebase_step, RebaseProgress,
+    RebaseRisk, RebaseState, RebaseStep, RebaseTodo,
 };
 pub use remotes::{
-    get_default_remote, get_remotes, push::AsyncProgress,
-    tags::PushTagsProgress,
+    get_default_remote, get_remotes, is_shallow, push::AsyncProgress,
+    tags::PushTagsProgress, FetchDepth,
+};
+pub use replace::{
+    is_replaced, set_use_replace_refs, use_replace_refs,
+};
+pub use repository::RepoPath;
+pub use reset::{
+    discard_all, reset_soft_head_parent, reset_stage,
+    reset_stage_paths, reset_to_commit, reset_workdir,
+    reset_workdir_paths, unstage_all, ResetType,
 };
-pub use reset::{reset_stage, reset_workdir};
-pub use staging::{discard_lines, stage_lines};
+pub use signature::{SignatureInfo, SignatureStatus};
+pub use squash::{squash_commits, SquashType};
+pub use staging::stage_lines;
 pub use stash::{
-    get_stashes, stash_apply, stash_drop, stash_pop, stash_save,
+    apply_pending_autostash, autostash, autostash_pending,
+    get_stashes, stash_apply, stash_branch, stash_drop, stash_pop,
+    stash_rename, stash_save, stash_save_paths,
 };
 pub use state::{repo_state, RepoState};
-pub use tags::{get_tags, CommitTags, Tags};
-pub use tree::{tree_file_content, tree_files, TreeFile};
+pub use submodules::{
+    update_submodule, SubmoduleCommit, SubmoduleLog, SubmoduleState,
+};
+pub use tags::{
+    delete_tag, get_tag_infos, get_tags, retag, CommitTags, TagInfo,
+    Tags,
+};
+pub use tree::{
+    tree_file_bytes, tree_file_content, tree_files, TreeFile,
+};
 pub use utils::{
-    get_head, get_head_tuple, is_bare_repo, is_repo, stage_add_all,
-    stage_add_file, stage_addremoved, Head,
+    get_head, get_head_tuple, is_bare_repo, is_repo, merge_base,
+    resolve_rev, stage_add_all, stage_add_file, stage_addremoved,
+    stage_all_paths, stage_paths, Head,
 };
+pub use workflow::{flow_finish, flow_start, FlowBranch, FlowNaming};
 
 #[cfg(test)]
 mod tests {
@@ -77,7 +166,7 @@ mod tests {
         commit, stage_add_file,
         status::{get_status, StatusType},
         utils::repo_write_file,
-        CommitId, LogWalker,
+        CommitId, LogWalker, RepoPath,
     };
     use crate::error::Result;
     use git2::Repository;
@@ -116,13 +205,16 @@ mod tests {
         repo_write_file(repo, file, content).unwrap();
 
         stage_add_file(
-            repo.workdir().unwrap().to_str().unwrap(),
+            &repo.workdir().unwrap().to_str().unwrap().into(),
             Path::new(file),
         )
         .unwrap();
 
-        commit(repo.workdir().unwrap().to_str().unwrap(), commit_name)
-            .unwrap()
+        commit(
+            &repo.workdir().unwrap().to_str().unwrap().into(),
+            commit_name,
+        )
+        .unwrap()
     }
 
     ///
@@ -192,7 +284,7 @@ mod tests {
     }
 
     /// helper returning amount of files with changes in the (wd,stage)
-    pub fn get_statuses(repo_path: &str) -> (usize, usize) {
+    pub fn get_statuses(repo_path: &RepoPath) -> (usize, usize) {
         (
             get_status(repo_path, StatusType::WorkingDir, true)
                 .unwrap()
@@ -204,7 +296,7 @@ mod tests {
     }
 
     ///
-    pub fn debug_cmd_print(path: &str, cmd: &str) {
+    pub fn debug_cmd_print(path: &RepoPath, cmd: &str) {
         let cmd = debug_cmd(path, cmd);
         eprintln!("\n----\n{}", cmd);
     }
@@ -220,18 +312,18 @@ mod tests {
         commit_ids
     }
 
-    fn debug_cmd(path: &str, cmd: &str) -> String {
+    fn debug_cmd(path: &RepoPath, cmd: &str) -> String {
         let output = if cfg!(target_os = "windows") {
             Command::new("cmd")
                 .args(&["/C", cmd])
-                .current_dir(path)
+                .current_dir(path.gitpath())
                 .output()
                 .unwrap()
         } else {
             Command::new("sh")
                 .arg("-c")
                 .arg(cmd)
-                .current_dir(path)
+                .current_dir(path.gitpath())
                 .output()
                 .unwrap()
         };
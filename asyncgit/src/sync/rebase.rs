@@ -0,0 +1,569 @@
+//! rebases that stop on conflicts: resolve them and continue, skip
+//! the commit or abort, the commits still to go can be reordered
+
+use super::{
+    commit::signature_allow_undefined_name,
+    stash::apply_pending_autostash,
+    utils::{self, bytes2string},
+    CommitId, InstanceLock, RepoPath,
+};
+use crate::error::{Error, Result};
+use git2::{
+    build::CheckoutBuilder, Branch, BranchType, ErrorCode, Oid,
+    Rebase, Repository, RepositoryState, Signature, Sort,
+};
+use scopetime::scope_time;
+use std::fs;
+
+/// a commit of the rebase in progress
+#[derive(Debug, Clone, PartialEq)]
+pub struct RebaseStep {
+    /// the original commit
+    pub id: CommitId,
+    ///
+    pub summary: String,
+}
+
+/// the rebase in progress
+#[derive(Debug, Clone, PartialEq)]
+pub struct RebaseProgress {
+    /// index into `steps` of the commit being replayed
+    pub current: Option<usize>,
+    ///
+    pub steps: Vec<RebaseStep>,
+}
+
+/// consequence of rewriting a commit the user may not expect
+#[derive(Debug, Clone, PartialEq)]
+pub enum RebaseRisk {
+    /// merges are not replayed, the commits they merged are
+    Merge,
+    /// the rewritten commit is not signed anymore
+    Signed,
+    /// the commit is on this remote branch already, rewriting it
+    /// makes the branches diverge
+    Published(String),
+}
+
+/// a commit a rebase would replay
+#[derive(Debug, Clone, PartialEq)]
+pub struct RebaseTodo {
+    ///
+    pub id: CommitId,
+    ///
+    pub summary: String,
+    ///
+    pub risks: Vec<RebaseRisk>,
+}
+
+/// where a rebase stopped
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RebaseState {
+    ///
+    Finished,
+    /// on conflicts of the current step
+    Conflicted,
+}
+
+/// rebases `HEAD` onto the branch `onto` (see `find_onto`), stops on
+/// the first step with conflicts
+pub fn rebase_branch(
+    repo_path: &RepoPath,
+    onto: &str,
+) -> Result<RebaseState> {
+    scope_time!("rebase_branch");
+
+    let _lock = InstanceLock::acquire(repo_path)?;
+
+    let repo = utils::repo(repo_path)?;
+    let onto = find_onto(&repo, onto)?;
+    let onto = repo.reference_to_annotated_commit(onto.get())?;
+
+    let mut rebase = repo.rebase(None, Some(&onto), None, None)?;
+    let signature = signature_allow_undefined_name(&repo)?;
+
+    run(&repo, &mut rebase, &signature)
+}
+
+/// the commits `rebase_branch` would replay onto `onto` (oldest
+/// first), with what rewriting them means
+pub fn rebase_todo(
+    repo_path: &RepoPath,
+    onto: &str,
+) -> Result<Vec<RebaseTodo>> {
+    scope_time!("rebase_todo");
+
+    let repo = utils::repo(repo_path)?;
+    let onto = find_onto(&repo, onto)?;
+    let onto = onto.get().peel_to_commit()?.id();
+
+    let remotes = remote_tips(&repo)?;
+
+    let mut walk = repo.revwalk()?;
+    walk.set_sorting(Sort::TOPOLOGICAL | Sort::REVERSE)?;
+    walk.push_head()?;
+    walk.hide(onto)?;
+
+    let mut todo = Vec::new();
+    for id in walk {
+        let id = id?;
+        let commit = repo.find_commit(id)?;
+
+        let mut risks = Vec::new();
+        if commit.parent_count() > 1 {
+            risks.push(RebaseRisk::Merge);
+        }
+        if repo.extract_signature(&id, None).is_ok() {
+            risks.push(RebaseRisk::Signed);
+        }
+        for (name, tip) in &remotes {
+            if *tip == id || repo.graph_descendant_of(*tip, id)? {
+                risks.push(RebaseRisk::Published(name.clone()));
+                break;
+            }
+        }
+
+        todo.push(RebaseTodo {
+            id: id.into(),
+            summary: bytes2string(
+                commit.summary_bytes().unwrap_or_default(),
+            )?,
+            risks,
+        });
+    }
+
+    Ok(todo)
+}
+
+/// names and commits of the remote tracking branches
+fn remote_tips(repo: &Repository) -> Result<Vec<(String, Oid)>> {
+    let mut tips = Vec::new();
+    for branch in repo.branches(Some(BranchType::Remote))? {
+        let (branch, _) = branch?;
+        // `origin/HEAD` points to one of the others
+        if branch.get().symbolic_target_bytes().is_some() {
+            continue;
+        }
+
+        if let (Some(name), Some(tip)) =
+            (branch.name()?, branch.get().target())
+        {
+            tips.push((name.to_string(), tip));
+        }
+    }
+
+    Ok(tips)
+}
+
+/// `None` if there is no rebase in progress. rebases started by
+/// other tools (like the git cli) can not be opened
+pub fn rebase_progress(
+    repo_path: &RepoPath,
+) -> Result<Option<RebaseProgress>> {
+    scope_time!("rebase_progress");
+
+    let repo = utils::repo(repo_path)?;
+    if !is_rebasing(&repo) {
+        return Ok(None);
+    }
+
+    let mut rebase = match repo.open_rebase(None) {
+        Ok(rebase) => rebase,
+        Err(e) => {
+            log::warn!("rebase_progress: {}", e);
+            return Ok(None);
+        }
+    };
+
+    let mut steps = Vec::with_capacity(rebase.len());
+    for idx in 0..rebase.len() {
+        if let Some(op) = rebase.nth(idx) {
+            let commit = repo.find_commit(op.id())?;
+            steps.push(RebaseStep {
+                id: op.id().into(),
+                summary: bytes2string(
+                    commit.summary_bytes().unwrap_or_default(),
+                )?,
+            });
+        }
+    }
+
+    Ok(Some(RebaseProgress {
+        current: rebase.operation_current(),
+        steps,
+    }))
+}
+
+/// commits the resolved current step (with `message` instead of the
+/// original one) and goes on with the others
+pub fn continue_rebase(
+    repo_path: &RepoPath,
+    message: Option<&str>,
+) -> Result<RebaseState> {
+    scope_time!("continue_rebase");
+
+    let _lock = InstanceLock::acquire(repo_path)?;
+
+    let repo = utils::repo(repo_path)?;
+    let mut rebase = repo.open_rebase(None)?;
+    let signature = signature_allow_undefined_name(&repo)?;
+
+    if repo.index()?.has_conflicts() {
+        return Err(Error::Generic(String::from(
+            "resolve and stage all conflicts first",
+        )));
+    }
+
+    commit_step(&mut rebase, &signature, message)?;
+
+    let state = run(&repo, &mut rebase, &signature)?;
+
+    finish_autostash(repo_path, state)
+}
+
+/// drops the changes of the current step and goes on with the others
+pub fn skip_rebase_step(repo_path: &RepoPath) -> Result<RebaseState> {
+    scope_time!("skip_rebase_step");
+
+    let _lock = InstanceLock::acquire(repo_path)?;
+
+    let repo = utils::repo(repo_path)?;
+    let mut rebase = repo.open_rebase(None)?;
+    let signature = signature_allow_undefined_name(&repo)?;
+
+    // a reset would clean up the rebase state as well
+    let head = repo.head()?.peel_to_tree()?;
+    let mut index = repo.index()?;
+    index.read_tree(&head)?;
+    index.write()?;
+    repo.checkout_head(Some(CheckoutBuilder::new().force()))?;
+
+    let state = run(&repo, &mut rebase, &signature)?;
+
+    finish_autostash(repo_path, state)
+}
+
+/// restores the branch as it was before the rebase
+pub fn abort_rebase(repo_path: &RepoPath) -> Result<()> {
+    scope_time!("abort_rebase");
+
+    let _lock = InstanceLock::acquire(repo_path)?;
+
+    let repo = utils::repo(repo_path)?;
+    repo.open_rebase(None)?.abort()?;
+
+    apply_pending_autostash(repo_path)?;
+
+    Ok(())
+}
+
+/// swaps the step at `idx` with the one before (`up`) or after it,
+/// only steps not replayed yet can be moved
+pub fn move_rebase_step(
+    repo_path: &RepoPath,
+    idx: usize,
+    up: bool,
+) -> Result<()> {
+    scope_time!("move_rebase_step");
+
+    let _lock = InstanceLock::acquire(repo_path)?;
+
+    let repo = utils::repo(repo_path)?;
+    let mut rebase = repo.open_rebase(None)?;
+
+    let other = if up {
+        idx.checked_sub(1)
+    } else {
+        idx.checked_add(1)
+    };
+    let len = rebase.len();
+    let current = rebase.operation_current();
+    let pending =
+        |i: usize| i < len && current.map_or(true, |c| i > c);
+
+    let other = match other {
+        Some(other) if pending(idx) && pending(other) => other,
+        _ => {
+            return Err(Error::Generic(String::from(
+                "only commits not replayed yet can be moved",
+            )))
+        }
+    };
+
+    // libgit2 keeps the commit of each step in `cmt.<n>`, counting
+    // from one
+    let dir = repo.path().join("rebase-merge");
+    let file = |i: usize| dir.join(format!("cmt.{}", i + 1));
+
+    let a = fs::read(file(idx))?;
+    let b = fs::read(file(other))?;
+    fs::write(file(idx), b)?;
+    fs::write(file(other), a)?;
+
+    Ok(())
+}
+
+fn is_rebasing(repo: &Repository) -> bool {
+    matches!(
+        repo.state(),
+        RepositoryState::Rebase
+            | RepositoryState::RebaseInteractive
+            | RepositoryState::RebaseMerge
+    )
+}
+
+/// applies the changes stashed away before the rebase once it is done
+fn finish_autostash(
+    repo_path: &RepoPath,
+    state: RebaseState,
+) -> Result<RebaseState> {
+    if state == RebaseState::Finished {
+        apply_pending_autostash(repo_path)?;
+    }
+
+    Ok(state)
+}
+
+/// replays the remaining steps until one has conflicts
+fn run(
+    repo: &Repository,
+    rebase: &mut Rebase,
+    signature: &Signature,
+) -> Result<RebaseState> {
+    while let Some(op) = rebase.next() {
+        op?;
+
+        if repo.index()?.has_conflicts() {
+            return Ok(RebaseState::Conflicted);
+        }
+
+        commit_step(rebase, signature, None)?;
+    }
+
+    rebase.finish(Some(signature))?;
+
+    Ok(RebaseState::Finished)
+}
+
+fn commit_step(
+    rebase: &mut Rebase,
+    signature: &Signature,
+    message: Option<&str>,
+) -> Result<()> {
+    match rebase.commit(None, signature, message) {
+        // the changes of the step are there already
+        Err(e) if e.code() == ErrorCode::Applied => Ok(()),
+        res => res.map(|_| ()).map_err(Into::into),
+    }
+}
+
+/// the local branch `name`, the remote one (like `origin/master`) if
+/// there is no such local branch
+fn find_onto<'a>(
+    repo: &'a Repository,
+    name: &str,
+) -> Result<Branch<'a>> {
+    let branch = repo
+        .find_branch(name, BranchType::Local)
+        .or_else(|_| repo.find_branch(name, BranchType::Remote))?;
+
+    Ok(branch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::{
+        checkout_branch, create_branch, get_commits_info, repo_state,
+        stage_add_file,
+        tests::{get_commit_ids, repo_init, write_commit_file},
+        utils::repo_write_file,
+        RepoState,
+    };
+    use std::path::Path;
+
+    fn msgs(repo: &Repository, repo_path: &RepoPath) -> Vec<String> {
+        get_commits_info(repo_path, &get_commit_ids(repo, 10), 10)
+            .unwrap()
+            .into_iter()
+            .map(|c| c.message)
+            .collect()
+    }
+
+    /// `feature` with `f1` (conflicting), `f2` and `f3` on top of
+    /// `base`, `master` with `m1` on it, `feature` checked out
+    fn setup(repo: &Repository, repo_path: &RepoPath) {
+        write_commit_file(repo, "a.txt", "base", "base");
+        create_branch(repo_path, "feature").unwrap();
+        write_commit_file(repo, "a.txt", "feature", "f1");
+        write_commit_file(repo, "b.txt", "b", "f2");
+        write_commit_file(repo, "c.txt", "c", "f3");
+        checkout_branch(repo_path, "refs/heads/master").unwrap();
+        write_commit_file(repo, "a.txt", "master", "m1");
+        checkout_branch(repo_path, "refs/heads/feature").unwrap();
+    }
+
+    #[test]
+    fn test_without_conflicts() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        write_commit_file(&repo, "a.txt", "base", "base");
+        create_branch(repo_path, "feature").unwrap();
+        write_commit_file(&repo, "b.txt", "b", "f1");
+        checkout_branch(repo_path, "refs/heads/master").unwrap();
+        write_commit_file(&repo, "c.txt", "c", "m1");
+        checkout_branch(repo_path, "refs/heads/feature").unwrap();
+
+        assert_eq!(
+            rebase_branch(repo_path, "master").unwrap(),
+            RebaseState::Finished
+        );
+        assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+        assert!(rebase_progress(repo_path).unwrap().is_none());
+        assert_eq!(
+            &msgs(&repo, repo_path)[..3],
+            &["f1", "m1", "base"]
+        );
+    }
+
+    #[test]
+    fn test_onto_remote_branch() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        write_commit_file(&repo, "a.txt", "base", "base");
+        create_branch(repo_path, "feature").unwrap();
+        write_commit_file(&repo, "b.txt", "b", "f1");
+        checkout_branch(repo_path, "refs/heads/master").unwrap();
+        let m1 = write_commit_file(&repo, "c.txt", "c", "m1");
+        repo.reference(
+            "refs/remotes/origin/master",
+            m1.into(),
+            false,
+            "",
+        )
+        .unwrap();
+        checkout_branch(repo_path, "refs/heads/feature").unwrap();
+
+        assert_eq!(
+            rebase_todo(repo_path, "origin/master").unwrap().len(),
+            1
+        );
+        assert_eq!(
+            rebase_branch(repo_path, "origin/master").unwrap(),
+            RebaseState::Finished
+        );
+        assert_eq!(
+            &msgs(&repo, repo_path)[..3],
+            &["f1", "m1", "base"]
+        );
+    }
+
+    #[test]
+    fn test_continue() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        setup(&repo, repo_path);
+
+        assert_eq!(
+            rebase_branch(repo_path, "master").unwrap(),
+            RebaseState::Conflicted
+        );
+        assert_eq!(repo_state(repo_path).unwrap(), RepoState::Rebase);
+
+        let progress = rebase_progress(repo_path).unwrap().unwrap();
+        assert_eq!(progress.current, Some(0));
+        assert_eq!(progress.steps.len(), 3);
+        assert_eq!(progress.steps[0].summary, "f1");
+
+        assert!(move_rebase_step(repo_path, 1, true).is_err());
+        move_rebase_step(repo_path, 2, true).unwrap();
+
+        assert!(continue_rebase(repo_path, None).is_err());
+
+        repo_write_file(&repo, "a.txt", "resolved").unwrap();
+        stage_add_file(repo_path, Path::new("a.txt")).unwrap();
+
+        assert_eq!(
+            continue_rebase(repo_path, Some("f1 resolved")).unwrap(),
+            RebaseState::Finished
+        );
+        assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+        assert_eq!(
+            &msgs(&repo, repo_path)[..4],
+            &["f2", "f3", "f1 resolved", "m1"]
+        );
+    }
+
+    #[test]
+    fn test_todo() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        setup(&repo, repo_path);
+
+        // `f1` and `f2` were pushed already
+        let f2 = repo.head().unwrap().peel_to_commit().unwrap();
+        let f2 = f2.parent(0).unwrap().id();
+        repo.reference("refs/remotes/origin/feature", f2, false, "")
+            .unwrap();
+
+        let todo = rebase_todo(repo_path, "master").unwrap();
+
+        assert_eq!(
+            todo.iter()
+                .map(|t| t.summary.as_str())
+                .collect::<Vec<_>>(),
+            vec!["f1", "f2", "f3"]
+        );
+        assert_eq!(
+            todo[0].risks,
+            vec![RebaseRisk::Published(String::from(
+                "origin/feature"
+            ))]
+        );
+        assert_eq!(
+            todo[1].risks,
+            vec![RebaseRisk::Published(String::from(
+                "origin/feature"
+            ))]
+        );
+        assert!(todo[2].risks.is_empty());
+    }
+
+    #[test]
+    fn test_skip_and_abort() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        setup(&repo, repo_path);
+        let head = repo.head().unwrap().target().unwrap();
+
+        rebase_branch(repo_path, "master").unwrap();
+        abort_rebase(repo_path).unwrap();
+
+        assert_eq!(repo_state(repo_path).unwrap(), RepoState::Clean);
+        assert_eq!(repo.head().unwrap().target().unwrap(), head);
+        assert!(rebase_progress(repo_path).unwrap().is_none());
+
+        rebase_branch(repo_path, "master").unwrap();
+
+        assert_eq!(
+            skip_rebase_step(repo_path).unwrap(),
+            RebaseState::Finished
+        );
+        assert_eq!(&msgs(&repo, repo_path)[..3], &["f3", "f2", "m1"]);
+    }
+}
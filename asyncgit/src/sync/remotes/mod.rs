@@ -7,20 +7,63 @@ use crate::{
     error::{Error, Result},
     sync::{
         cred::BasicAuthCredential,
-        remotes::push::ProgressNotification, utils,
+        remotes::push::ProgressNotification, utils, InstanceLock,
+        RepoPath,
     },
 };
 use crossbeam_channel::Sender;
-use git2::{BranchType, FetchOptions, Repository};
-use push::remote_callbacks;
+use easy_cast::{Conv, ConvFloat};
+use git2::{
+    AutotagOption, BranchType, FetchOptions, Oid, Remote, Repository,
+};
+use push::{bytes_per_sec, remote_callbacks};
 use scopetime::scope_time;
+use std::{
+    fs,
+    io::{BufReader, Read},
+    process::{Command, Stdio},
+    time::{Duration, Instant},
+};
 use utils::bytes2string;
 
 /// origin
 pub const DEFAULT_REMOTE_NAME: &str = "origin";
 
+/// how long a fetch waits for another instance to finish its change
+/// before updating the refs
+const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// credential helper of `fetch_cli` answering with the credential
+/// from its environment
+const CREDENTIAL_HELPER: &str = "!f() { test \"$1\" = get && \
+    echo \"username=$GITUI_USERNAME\" && \
+    echo \"password=$GITUI_PASSWORD\"; }; f";
+
+/// how much history a fetch gets
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FetchDepth {
+    /// everything missing, a shallow repository stays shallow
+    Full,
+    /// only this many commits of the fetched branch
+    Depth(u32),
+    /// the whole history, turns a shallow repository into a complete
+    /// one
+    Unshallow,
+}
+
+impl Default for FetchDepth {
+    fn default() -> Self {
+        Self::Full
+    }
+}
+
+/// `true` if the repository was fetched with a limited depth
+pub fn is_shallow(repo_path: &RepoPath) -> Result<bool> {
+    Ok(utils::repo(repo_path)?.is_shallow())
+}
+
 ///
-pub fn get_remotes(repo_path: &str) -> Result<Vec<String>> {
+pub fn get_remotes(repo_path: &RepoPath) -> Result<Vec<String>> {
     scope_time!("get_remotes");
 
     let repo = utils::repo(repo_path)?;
@@ -33,7 +76,7 @@ pub fn get_remotes(repo_path: &str) -> Result<Vec<String>> {
 
 /// tries to find origin or the only remote that is defined if any
 /// in case of multiple remotes and none named *origin* we fail
-pub fn get_default_remote(repo_path: &str) -> Result<String> {
+pub fn get_default_remote(repo_path: &RepoPath) -> Result<String> {
     let repo = utils::repo(repo_path)?;
     get_default_remote_in_repo(&repo)
 }
@@ -74,8 +117,9 @@ pub(crate) fn get_default_remote_in_repo(
 
 /// fetches from upstream/remote for `branch`
 pub(crate) fn fetch(
-    repo_path: &str,
+    repo_path: &RepoPath,
     branch: &str,
+    depth: FetchDepth,
     basic_credential: Option<BasicAuthCredential>,
     progress_sender: Option<Sender<ProgressNotification>>,
 ) -> Result<usize> {
@@ -88,6 +132,19 @@ pub(crate) fn fetch(
     let branch_ref = bytes2string(branch_ref.name_bytes())?;
     let remote_name = repo.branch_upstream_remote(&branch_ref)?;
     let remote_name = bytes2string(&*remote_name)?;
+
+    if depth != FetchDepth::Full {
+        return fetch_cli(
+            &repo,
+            repo_path,
+            &remote_name,
+            branch,
+            depth,
+            basic_credential,
+            progress_sender.as_ref(),
+        );
+    }
+
     let mut remote = repo.find_remote(&remote_name)?;
 
     let mut options = FetchOptions::new();
@@ -96,7 +153,278 @@ pub(crate) fn fetch(
         basic_credential,
     ));
 
-    remote.fetch(&[branch], Some(&mut options), None)?;
+    fetch_locked(repo_path, &mut remote, &[branch], options, false)?;
+
+    Ok(remote.stats().received_bytes())
+}
+
+/// `Remote::fetch` but only the update of the refs holds the
+/// `InstanceLock`, not the download
+fn fetch_locked(
+    repo_path: &RepoPath,
+    remote: &mut Remote<'_>,
+    specs: &[&str],
+    mut options: FetchOptions<'_>,
+    prune: bool,
+) -> Result<()> {
+    remote.download(specs, Some(&mut options))?;
+    remote.disconnect()?;
+
+    // this runs in a background thread, waiting is fine
+    let _lock = InstanceLock::acquire_wait(repo_path, LOCK_TIMEOUT)?;
+
+    remote.update_tips(
+        None,
+        true,
+        AutotagOption::Unspecified,
+        None,
+    )?;
+
+    if prune {
+        remote.prune(None)?;
+    }
+
+    Ok(())
+}
+
+/// the libgit2 we use cannot limit the history of a fetch, the git
+/// cli does this instead. `basic_credential` is handed to it by a
+/// credential helper and its progress output is reported like the
+/// one of libgit2. it only downloads into `FETCH_HEAD`, the remote
+/// branch is updated afterwards while holding the `InstanceLock`
+fn fetch_cli(
+    repo: &Repository,
+    repo_path: &RepoPath,
+    remote: &str,
+    branch: &str,
+    depth: FetchDepth,
+    basic_credential: Option<BasicAuthCredential>,
+    progress_sender: Option<&Sender<ProgressNotification>>,
+) -> Result<usize> {
+    scope_time!("fetch_cli");
+
+    let mut cmd = Command::new("git");
+    cmd.arg("--git-dir").arg(repo.path());
+
+    if let Some(cred) = basic_credential {
+        // only our helper, the credential is passed in the
+        // environment to keep it off the command line
+        cmd.args(&["-c", "credential.helper=", "-c"])
+            .arg(format!("credential.helper={}", CREDENTIAL_HELPER))
+            .env("GITUI_USERNAME", cred.username.unwrap_or_default())
+            .env("GITUI_PASSWORD", cred.password.unwrap_or_default());
+    }
+
+    // an empty `--refmap` keeps the cli from updating the remote
+    // branch itself, it is set from `FETCH_HEAD` below
+    cmd.args(&["fetch", "--progress", "--no-tags", "--refmap="])
+        // never wait for a password prompt nobody sees
+        .env("GIT_TERMINAL_PROMPT", "0")
+        // the progress is parsed, it must not be translated
+        .env("LC_ALL", "C")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped());
+
+    match depth {
+        FetchDepth::Depth(depth) => {
+            cmd.arg(format!("--depth={}", depth));
+        }
+        FetchDepth::Unshallow => {
+            cmd.arg("--unshallow");
+        }
+        FetchDepth::Full => (),
+    }
+
+    cmd.arg(remote).arg(branch);
+
+    let mut child = cmd.spawn().map_err(|e| {
+        Error::Generic(format!(
+            "fetching with a depth needs the git cli: {}",
+            e
+        ))
+    })?;
+
+    let (bytes, messages) = match child.stderr.take() {
+        Some(stderr) => read_cli_progress(stderr, progress_sender)?,
+        None => (0, Vec::new()),
+    };
+
+    if !child.wait()?.success() {
+        return Err(Error::Generic(messages.join("\n")));
+    }
+
+    // this runs in a background thread, waiting is fine
+    let _lock = InstanceLock::acquire_wait(repo_path, LOCK_TIMEOUT)?;
+
+    // a fresh repository sees the objects the cli downloaded
+    let repo = utils::repo(repo_path)?;
+    let fetch_head =
+        fs::read_to_string(repo.path().join("FETCH_HEAD"))?;
+    let id = fetch_head
+        .lines()
+        .next()
+        .and_then(|line| line.split_whitespace().next())
+        .ok_or_else(|| Error::Generic("nothing fetched".into()))?;
+
+    repo.reference(
+        &format!("refs/remotes/{}/{}", remote, branch),
+        Oid::from_str(id)?,
+        true,
+        "fetch",
+    )?;
+
+    Ok(bytes)
+}
+
+/// reads the progress `git fetch --progress` writes to `stderr`,
+/// returns the received bytes and all lines that are no progress
+/// (like the error messages)
+fn read_cli_progress(
+    stderr: impl Read,
+    progress_sender: Option<&Sender<ProgressNotification>>,
+) -> Result<(usize, Vec<String>)> {
+    let start = Instant::now();
+    let mut progress = CliProgress::default();
+    let mut messages = Vec::new();
+    let mut line = Vec::new();
+
+    for byte in BufReader::new(stderr).bytes() {
+        let byte = byte?;
+
+        // progress lines are rewritten in place ending in `\r`
+        if byte != b'\r' && byte != b'\n' {
+            line.push(byte);
+            continue;
+        }
+
+        let text = String::from_utf8_lossy(&line).trim().to_string();
+        line.clear();
+
+        if progress.update(&text) {
+            if let Some(sender) = progress_sender {
+                if let Err(e) =
+                    sender.send(progress.notification(start))
+                {
+                    log::error!("fetch progress error: {}", e);
+                }
+            }
+        } else if !text.is_empty() {
+            messages.push(text);
+        }
+    }
+
+    Ok((progress.bytes, messages))
+}
+
+/// progress of a `git fetch --progress`
+#[derive(Default)]
+struct CliProgress {
+    objects: usize,
+    total_objects: usize,
+    indexed_deltas: usize,
+    total_deltas: usize,
+    bytes: usize,
+}
+
+impl CliProgress {
+    /// updates from a progress `line` like `Receiving objects:  45%
+    /// (450/1000), 1.20 MiB | 1.00 MiB/s`, `false` if it is none
+    fn update(&mut self, line: &str) -> bool {
+        let mut parts = line.splitn(2, ':');
+        let (stage, rest) = match (parts.next(), parts.next()) {
+            (Some(stage), Some(rest)) => (stage, rest),
+            _ => return false,
+        };
+
+        let counts = rest
+            .split('(')
+            .nth(1)
+            .and_then(|rest| rest.split(')').next())
+            .and_then(|counts| {
+                let mut counts = counts.split('/');
+                Some((
+                    counts.next()?.parse().ok()?,
+                    counts.next()?.parse().ok()?,
+                ))
+            });
+        let (current, total) = match counts {
+            Some(counts) => counts,
+            None => return false,
+        };
+
+        match stage {
+            "Receiving objects" => {
+                self.objects = current;
+                self.total_objects = total;
+
+                // the size is only shown once the transfer takes a
+                // while
+                if let Some(bytes) = rest
+                    .split(", ")
+                    .nth(1)
+                    .and_then(|size| size.split(" | ").next())
+                    .and_then(parse_size)
+                {
+                    self.bytes = bytes;
+                }
+            }
+            "Resolving deltas" => {
+                self.indexed_deltas = current;
+                self.total_deltas = total;
+            }
+            _ => return false,
+        }
+
+        true
+    }
+
+    fn notification(&self, start: Instant) -> ProgressNotification {
+        ProgressNotification::Transfer {
+            objects: self.objects,
+            total_objects: self.total_objects,
+            indexed_deltas: self.indexed_deltas,
+            total_deltas: self.total_deltas,
+            bytes: self.bytes,
+            bytes_per_sec: bytes_per_sec(start, self.bytes),
+        }
+    }
+}
+
+/// parses a size the way git prints it, like `1.20 MiB`
+fn parse_size(text: &str) -> Option<usize> {
+    let mut parts = text.split_whitespace();
+    let value: f64 = parts.next()?.parse().ok()?;
+    let unit: u32 = match parts.next()? {
+        "bytes" | "byte" => 1,
+        "KiB" => 1 << 10,
+        "MiB" => 1 << 20,
+        "GiB" => 1 << 30,
+        _ => return None,
+    };
+
+    Some(usize::conv_nearest(value * f64::conv(unit)))
+}
+
+/// fetches all branches of `remote` (using its configured refspecs),
+/// remote branches deleted on the remote are pruned
+pub(crate) fn fetch_all(
+    repo_path: &RepoPath,
+    remote: &str,
+    basic_credential: Option<BasicAuthCredential>,
+    progress_sender: Option<Sender<ProgressNotification>>,
+) -> Result<usize> {
+    scope_time!("fetch_all");
+
+    let repo = utils::repo(repo_path)?;
+    let mut remote = repo.find_remote(remote)?;
+
+    let mut options = FetchOptions::new();
+    options.remote_callbacks(remote_callbacks(
+        progress_sender,
+        basic_credential,
+    ));
+    fetch_locked(repo_path, &mut remote, &[], options, true)?;
 
     Ok(remote.stats().received_bytes())
 }
@@ -104,22 +432,123 @@ pub(crate) fn fetch(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::sync::tests::{
-        debug_cmd_print, repo_clone, repo_init,
+    use crate::sync::{
+        create_branch, get_branches_info,
+        tests::{
+            debug_cmd_print, repo_clone, repo_init, write_commit_file,
+        },
     };
+    use tempfile::TempDir;
 
     #[test]
     fn test_smoke() {
         let (remote_dir, _remote) = repo_init().unwrap();
         let remote_path = remote_dir.path().to_str().unwrap();
         let (repo_dir, _repo) = repo_clone(remote_path).unwrap();
-        let repo_path = repo_dir.path().as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &repo_dir.path().as_os_str().to_str().unwrap().into();
 
         let remotes = get_remotes(repo_path).unwrap();
 
         assert_eq!(remotes, vec![String::from("origin")]);
 
-        fetch(repo_path, "master", None, None).unwrap();
+        fetch(repo_path, "master", FetchDepth::Full, None, None)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_fetch_depth() {
+        let (remote_dir, remote) = repo_init().unwrap();
+        let remote_path = remote_dir.path().to_str().unwrap();
+
+        write_commit_file(&remote, "a.txt", "a", "a");
+
+        let repo_dir = TempDir::new().unwrap();
+        let status = Command::new("git")
+            .args(&["clone", "--quiet", "--depth=1"])
+            .arg(format!("file://{}", remote_path))
+            .arg(repo_dir.path())
+            .status()
+            .unwrap();
+        assert!(status.success());
+        let repo_path: &RepoPath =
+            &repo_dir.path().as_os_str().to_str().unwrap().into();
+
+        assert!(is_shallow(repo_path).unwrap());
+
+        write_commit_file(&remote, "b.txt", "b", "b");
+
+        fetch(repo_path, "master", FetchDepth::Depth(1), None, None)
+            .unwrap();
+
+        assert!(is_shallow(repo_path).unwrap());
+
+        fetch(repo_path, "master", FetchDepth::Unshallow, None, None)
+            .unwrap();
+
+        assert!(!is_shallow(repo_path).unwrap());
+
+        let repo = utils::repo(repo_path).unwrap();
+        let mut walk = repo.revwalk().unwrap();
+        walk.push_ref("refs/remotes/origin/master").unwrap();
+        assert_eq!(walk.count(), 3);
+    }
+
+    #[test]
+    fn test_cli_progress() {
+        let stderr = "remote: Enumerating objects: 5, done.\n\
+            Receiving objects:  50% (1/2)\r\
+            Receiving objects: 100% (2/2), 1.50 KiB | 1.00 KiB/s, done.\n\
+            Resolving deltas: 100% (1/1), done.\n\
+            fatal: something\n";
+        let (sender, receiver) = crossbeam_channel::unbounded();
+
+        let (bytes, messages) =
+            read_cli_progress(stderr.as_bytes(), Some(&sender))
+                .unwrap();
+
+        assert_eq!(bytes, 1536);
+        assert_eq!(
+            messages,
+            vec![
+                String::from("remote: Enumerating objects: 5, done."),
+                String::from("fatal: something")
+            ]
+        );
+        assert!(matches!(
+            receiver.try_iter().last(),
+            Some(ProgressNotification::Transfer {
+                objects: 2,
+                total_deltas: 1,
+                bytes: 1536,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_fetch_all_new_branch() {
+        let (remote_dir, _remote) = repo_init().unwrap();
+        let remote_path = remote_dir.path().to_str().unwrap();
+        let (repo_dir, _repo) = repo_clone(remote_path).unwrap();
+        let repo_path: &RepoPath =
+            &repo_dir.path().as_os_str().to_str().unwrap().into();
+
+        create_branch(&remote_path.into(), "foo").unwrap();
+
+        let remote_branches = || {
+            get_branches_info(repo_path, false)
+                .unwrap()
+                .into_iter()
+                .map(|b| b.name)
+                .collect::<Vec<_>>()
+        };
+
+        assert!(!remote_branches().contains(&"origin/foo".into()));
+
+        fetch_all(repo_path, "origin", None, None).unwrap();
+
+        assert!(remote_branches().contains(&"origin/foo".into()));
     }
 
     #[test]
@@ -127,7 +556,8 @@ mod tests {
         let (remote_dir, _remote) = repo_init().unwrap();
         let remote_path = remote_dir.path().to_str().unwrap();
         let (repo_dir, _repo) = repo_clone(remote_path).unwrap();
-        let repo_path = repo_dir.path().as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &repo_dir.path().as_os_str().to_str().unwrap().into();
 
         debug_cmd_print(
             repo_path,
@@ -153,7 +583,8 @@ mod tests {
         let (remote_dir, _remote) = repo_init().unwrap();
         let remote_path = remote_dir.path().to_str().unwrap();
         let (repo_dir, _repo) = repo_clone(remote_path).unwrap();
-        let repo_path = repo_dir.path().as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &repo_dir.path().as_os_str().to_str().unwrap().into();
 
         debug_cmd_print(
             repo_path,
@@ -185,7 +616,8 @@ mod tests {
         let (remote_dir, _remote) = repo_init().unwrap();
         let remote_path = remote_dir.path().to_str().unwrap();
         let (repo_dir, _repo) = repo_clone(remote_path).unwrap();
-        let repo_path = repo_dir.path().as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &repo_dir.path().as_os_str().to_str().unwrap().into();
 
         debug_cmd_print(
             repo_path,
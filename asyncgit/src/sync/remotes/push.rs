@@ -1,4 +1,5 @@
 use super::utils;
+use crate::sync::RepoPath;
 use crate::{
     error::Result,
     progress::ProgressPercent,
@@ -8,11 +9,13 @@ use crate::{
     },
 };
 use crossbeam_channel::Sender;
+use easy_cast::{Conv, ConvFloat};
 use git2::{
-    Cred, Error as GitError, PackBuilderStage, PushOptions,
-    RemoteCallbacks,
+    Cred, CredentialType, Error as GitError, PackBuilderStage,
+    PushOptions, RemoteCallbacks,
 };
 use scopetime::scope_time;
+use std::time::Instant;
 
 ///
 pub trait AsyncProgress: Clone + Send + Sync {
@@ -40,6 +43,14 @@ pub enum ProgressNotification {
         objects: usize,
         ///
         total_objects: usize,
+        /// deltas resolved after all objects were received
+        indexed_deltas: usize,
+        ///
+        total_deltas: usize,
+        /// received so far
+        bytes: usize,
+        /// average since the transfer started
+        bytes_per_sec: usize,
     },
     ///
     PushTransfer {
@@ -49,6 +60,8 @@ pub enum ProgressNotification {
         total: usize,
         ///
         bytes: usize,
+        /// average since the transfer started
+        bytes_per_sec: usize,
     },
     ///
     Packing {
@@ -85,8 +98,16 @@ impl AsyncProgress for ProgressNotification {
             Self::Transfer {
                 objects,
                 total_objects,
+                indexed_deltas,
+                total_deltas,
                 ..
-            } => ProgressPercent::new(objects, total_objects),
+            } => {
+                if objects == total_objects && total_deltas > 0 {
+                    ProgressPercent::new(indexed_deltas, total_deltas)
+                } else {
+                    ProgressPercent::new(objects, total_objects)
+                }
+            }
             _ => ProgressPercent::full(),
         }
     }
@@ -94,7 +115,7 @@ impl AsyncProgress for ProgressNotification {
 
 #[allow(clippy::redundant_pub_crate)]
 pub(crate) fn push(
-    repo_path: &str,
+    repo_path: &RepoPath,
     remote: &str,
     branch: &str,
     force: bool,
@@ -104,7 +125,8 @@ pub(crate) fn push(
     scope_time!("push");
 
     let repo = utils::repo(repo_path)?;
-    let mut remote = repo.find_remote(remote)?;
+    let remote_name = remote;
+    let mut remote = repo.find_remote(remote_name)?;
 
     let mut options = PushOptions::new();
 
@@ -123,17 +145,53 @@ pub(crate) fn push(
     } else {
         remote.push(&[branch_name.as_str()], Some(&mut options))?;
     }
-    branch_set_upstream(&repo, branch)?;
+    branch_set_upstream(&repo, branch, remote_name)?;
 
     Ok(())
 }
 
+/// average throughput since `start`
+pub(super) fn bytes_per_sec(start: Instant, bytes: usize) -> usize {
+    let secs = start.elapsed().as_secs_f64().max(0.001);
+    usize::conv_nearest(f64::conv(bytes) / secs)
+}
+
+/// credentials for the first attempt to authenticate, see the
+/// `credentials` callback in `remote_callbacks`
+fn credentials(
+    basic_credential: Option<&BasicAuthCredential>,
+    username_from_url: Option<&str>,
+    allowed_types: CredentialType,
+) -> std::result::Result<Cred, GitError> {
+    match basic_credential {
+        _ if allowed_types.is_ssh_key() => match username_from_url {
+            Some(username) => Cred::ssh_key_from_agent(username),
+            None => Err(GitError::from_str(
+                " Couldn't extract username from url.",
+            )),
+        },
+        Some(BasicAuthCredential {
+            username: Some(user),
+            password: Some(pwd),
+        }) if allowed_types.is_user_pass_plaintext() => {
+            Cred::userpass_plaintext(user, pwd)
+        }
+        Some(BasicAuthCredential {
+            username: Some(user),
+            password: _,
+        }) if allowed_types.is_username() => Cred::username(user),
+        _ if allowed_types.is_default() => Cred::default(),
+        _ => Err(GitError::from_str("Couldn't find credentials")),
+    }
+}
+
 #[allow(clippy::redundant_pub_crate)]
 pub(crate) fn remote_callbacks<'a>(
     sender: Option<Sender<ProgressNotification>>,
     basic_credential: Option<BasicAuthCredential>,
 ) -> RemoteCallbacks<'a> {
     let mut callbacks = RemoteCallbacks::new();
+    let start = Instant::now();
     let sender_clone = sender.clone();
     callbacks.push_transfer_progress(move |current, total, bytes| {
         log::debug!("progress: {}/{} ({} B)", current, total, bytes,);
@@ -143,6 +201,7 @@ pub(crate) fn remote_callbacks<'a>(
                 current,
                 total,
                 bytes,
+                bytes_per_sec: bytes_per_sec(start, bytes),
             })
         });
     });
@@ -164,15 +223,25 @@ pub(crate) fn remote_callbacks<'a>(
     let sender_clone = sender.clone();
     callbacks.transfer_progress(move |p| {
         log::debug!(
-            "transfer: {}/{}",
+            "transfer: {}/{} ({}/{} deltas, {} B)",
             p.received_objects(),
-            p.total_objects()
+            p.total_objects(),
+            p.indexed_deltas(),
+            p.total_deltas(),
+            p.received_bytes()
         );
 
         sender_clone.clone().map(|sender| {
             sender.send(ProgressNotification::Transfer {
                 objects: p.received_objects(),
                 total_objects: p.total_objects(),
+                indexed_deltas: p.indexed_deltas(),
+                total_deltas: p.total_deltas(),
+                bytes: p.received_bytes(),
+                bytes_per_sec: bytes_per_sec(
+                    start,
+                    p.received_bytes(),
+                ),
             })
         });
         true
@@ -210,34 +279,11 @@ pub(crate) fn remote_callbacks<'a>(
                 return Err(GitError::from_str("Bad credentials."));
             }
 
-            match &basic_credential {
-                _ if allowed_types.is_ssh_key() => {
-                    match username_from_url {
-                        Some(username) => {
-                            Cred::ssh_key_from_agent(username)
-                        }
-                        None => Err(GitError::from_str(
-                            " Couldn't extract username from url.",
-                        )),
-                    }
-                }
-                Some(BasicAuthCredential {
-                    username: Some(user),
-                    password: Some(pwd),
-                }) if allowed_types.is_user_pass_plaintext() => {
-                    Cred::userpass_plaintext(user, pwd)
-                }
-                Some(BasicAuthCredential {
-                    username: Some(user),
-                    password: _,
-                }) if allowed_types.is_username() => {
-                    Cred::username(user)
-                }
-                _ if allowed_types.is_default() => Cred::default(),
-                _ => Err(GitError::from_str(
-                    "Couldn't find credentials",
-                )),
-            }
+            credentials(
+                basic_credential.as_ref(),
+                username_from_url,
+                allowed_types,
+            )
         },
     );
 
@@ -284,13 +330,13 @@ mod tests {
         writeln!(tmp_repo_file, "TempSomething").unwrap();
 
         sync::commit(
-            tmp_repo_dir.path().to_str().unwrap(),
+            &tmp_repo_dir.path().to_str().unwrap().into(),
             "repo_1_commit",
         )
         .unwrap();
 
         push(
-            tmp_repo_dir.path().to_str().unwrap(),
+            &tmp_repo_dir.path().to_str().unwrap().into(),
             "origin",
             "master",
             false,
@@ -306,7 +352,7 @@ mod tests {
         writeln!(tmp_other_repo_file, "TempElse").unwrap();
 
         sync::commit(
-            tmp_other_repo_dir.path().to_str().unwrap(),
+            &tmp_other_repo_dir.path().to_str().unwrap().into(),
             "repo_2_commit",
         )
         .unwrap();
@@ -315,7 +361,7 @@ mod tests {
         // should fail as branches diverged
         assert_eq!(
             push(
-                tmp_other_repo_dir.path().to_str().unwrap(),
+                &tmp_other_repo_dir.path().to_str().unwrap().into(),
                 "origin",
                 "master",
                 false,
@@ -330,7 +376,7 @@ mod tests {
         // should work as it forces the push through
         assert_eq!(
             push(
-                tmp_other_repo_dir.path().to_str().unwrap(),
+                &tmp_other_repo_dir.path().to_str().unwrap().into(),
                 "origin",
                 "master",
                 true,
@@ -372,13 +418,13 @@ mod tests {
         writeln!(tmp_repo_file, "TempSomething").unwrap();
 
         sync::stage_add_file(
-            tmp_repo_dir.path().to_str().unwrap(),
+            &tmp_repo_dir.path().to_str().unwrap().into(),
             Path::new("temp_file.txt"),
         )
         .unwrap();
 
         let repo_1_commit = sync::commit(
-            tmp_repo_dir.path().to_str().unwrap(),
+            &tmp_repo_dir.path().to_str().unwrap().into(),
             "repo_1_commit",
         )
         .unwrap();
@@ -386,7 +432,7 @@ mod tests {
         //NOTE: make sure the commit actually contains that file
         assert_eq!(
             sync::get_commit_files(
-                tmp_repo_dir.path().to_str().unwrap(),
+                &tmp_repo_dir.path().to_str().unwrap().into(),
                 repo_1_commit
             )
             .unwrap()[0]
@@ -398,7 +444,7 @@ mod tests {
         assert!(commits.contains(&repo_1_commit));
 
         push(
-            tmp_repo_dir.path().to_str().unwrap(),
+            &tmp_repo_dir.path().to_str().unwrap().into(),
             "origin",
             "master",
             false,
@@ -414,13 +460,13 @@ mod tests {
         writeln!(tmp_other_repo_file, "TempElse").unwrap();
 
         sync::stage_add_file(
-            tmp_other_repo_dir.path().to_str().unwrap(),
+            &tmp_other_repo_dir.path().to_str().unwrap().into(),
             Path::new("temp_file.txt"),
         )
         .unwrap();
 
         let repo_2_commit = sync::commit(
-            tmp_other_repo_dir.path().to_str().unwrap(),
+            &tmp_other_repo_dir.path().to_str().unwrap().into(),
             "repo_2_commit",
         )
         .unwrap();
@@ -440,7 +486,7 @@ mod tests {
         // should fail as branches diverged
         assert_eq!(
             push(
-                tmp_other_repo_dir.path().to_str().unwrap(),
+                &tmp_other_repo_dir.path().to_str().unwrap().into(),
                 "origin",
                 "master",
                 false,
@@ -460,7 +506,7 @@ mod tests {
         // should work as it forces the push through
 
         push(
-            tmp_other_repo_dir.path().to_str().unwrap(),
+            &tmp_other_repo_dir.path().to_str().unwrap().into(),
             "origin",
             "master",
             true,
@@ -483,4 +529,46 @@ mod tests {
                 .id();
         assert_eq!(new_upstream_parent, repo_2_parent,);
     }
+
+    #[test]
+    fn test_push_sets_upstream_to_pushed_remote() {
+        let (tmp_repo_dir, repo) = repo_init().unwrap();
+        let (tmp_origin_dir, _) = repo_init_bare().unwrap();
+        let (tmp_fork_dir, _) = repo_init_bare().unwrap();
+        let repo_path: &RepoPath =
+            &tmp_repo_dir.path().to_str().unwrap().into();
+
+        repo.remote(
+            "origin",
+            tmp_origin_dir.path().to_str().unwrap(),
+        )
+        .unwrap();
+        repo.remote("fork", tmp_fork_dir.path().to_str().unwrap())
+            .unwrap();
+
+        push(repo_path, "fork", "master", false, None, None).unwrap();
+
+        assert_eq!(
+            sync::get_upstream(repo_path, "master").unwrap(),
+            Some(String::from("fork/master"))
+        );
+        let config = repo.config().unwrap();
+        assert_eq!(
+            config.get_string("branch.master.remote").unwrap(),
+            "fork"
+        );
+        assert_eq!(
+            config.get_string("branch.master.merge").unwrap(),
+            "refs/heads/master"
+        );
+
+        // an existing upstream is kept
+        push(repo_path, "origin", "master", false, None, None)
+            .unwrap();
+
+        assert_eq!(
+            sync::get_upstream(repo_path, "master").unwrap(),
+            Some(String::from("fork/master"))
+        );
+    }
 }
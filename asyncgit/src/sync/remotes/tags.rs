@@ -5,8 +5,9 @@ use super::{
     utils,
 };
 use crate::{
-    error::Result, progress::ProgressPercent,
-    sync::cred::BasicAuthCredential,
+    error::Result,
+    progress::ProgressPercent,
+    sync::{cred::BasicAuthCredential, RepoPath},
 };
 use crossbeam_channel::Sender;
 use git2::{Direction, PushOptions};
@@ -46,7 +47,7 @@ impl AsyncProgress for PushTagsProgress {
 
 /// lists the remotes tags
 fn remote_tag_refs(
-    repo_path: &str,
+    repo_path: &RepoPath,
     remote: &str,
     basic_credential: Option<BasicAuthCredential>,
 ) -> Result<Vec<String>> {
@@ -74,7 +75,7 @@ fn remote_tag_refs(
 
 /// lists the remotes tags missing
 fn tags_missing_remote(
-    repo_path: &str,
+    repo_path: &RepoPath,
     remote: &str,
     basic_credential: Option<BasicAuthCredential>,
 ) -> Result<Vec<String>> {
@@ -97,10 +98,14 @@ fn tags_missing_remote(
     Ok(local_tags.into_iter().collect())
 }
 
-///
+/// pushes `tags` (by name), all tags missing on the remote if there
+/// are none. `force` replaces the remote's tags of the same name,
+/// e.g. after moving them
 pub fn push_tags(
-    repo_path: &str,
+    repo_path: &RepoPath,
     remote: &str,
+    tags: &[String],
+    force: bool,
     basic_credential: Option<BasicAuthCredential>,
     progress_sender: Option<Sender<PushTagsProgress>>,
 ) -> Result<()> {
@@ -110,11 +115,23 @@ pub fn push_tags(
         .as_ref()
         .map(|sender| sender.send(PushTagsProgress::CheckRemote));
 
-    let tags_missing = tags_missing_remote(
-        repo_path,
-        remote,
-        basic_credential.clone(),
-    )?;
+    let tags_missing = if tags.is_empty() {
+        tags_missing_remote(
+            repo_path,
+            remote,
+            basic_credential.clone(),
+        )?
+    } else {
+        tags.iter()
+            .map(|tag| {
+                format!(
+                    "{}refs/tags/{}",
+                    if force { "+" } else { "" },
+                    tag
+                )
+            })
+            .collect()
+    };
 
     let repo = utils::repo(repo_path)?;
     let mut remote = repo.find_remote(remote)?;
@@ -154,7 +171,7 @@ mod tests {
     use super::*;
     use crate::sync::{
         self,
-        remotes::{fetch, push::push},
+        remotes::{fetch, push::push, FetchDepth},
         tests::{repo_clone, repo_init_bare},
     };
     use sync::tests::write_commit_file;
@@ -166,11 +183,13 @@ mod tests {
 
         let (clone1_dir, clone1) = repo_clone(r1_dir).unwrap();
 
-        let clone1_dir = clone1_dir.path().to_str().unwrap();
+        let clone1_dir: &RepoPath =
+            &clone1_dir.path().to_str().unwrap().into();
 
         let (clone2_dir, clone2) = repo_clone(r1_dir).unwrap();
 
-        let clone2_dir = clone2_dir.path().to_str().unwrap();
+        let clone2_dir: &RepoPath =
+            &clone2_dir.path().to_str().unwrap().into();
 
         // clone1
 
@@ -181,7 +200,8 @@ mod tests {
 
         push(clone1_dir, "origin", "master", false, None, None)
             .unwrap();
-        push_tags(clone1_dir, "origin", None, None).unwrap();
+        push_tags(clone1_dir, "origin", &[], false, None, None)
+            .unwrap();
 
         // clone2
 
@@ -195,7 +215,9 @@ mod tests {
         assert_eq!(sync::get_tags(clone2_dir).unwrap().len(), 0);
 
         //lets fetch from origin
-        let bytes = fetch(clone2_dir, "master", None, None).unwrap();
+        let bytes =
+            fetch(clone2_dir, "master", FetchDepth::Full, None, None)
+                .unwrap();
         assert!(bytes > 0);
 
         sync::merge_upstream_commit(clone2_dir, "master").unwrap();
@@ -210,11 +232,13 @@ mod tests {
 
         let (clone1_dir, clone1) = repo_clone(r1_dir).unwrap();
 
-        let clone1_dir = clone1_dir.path().to_str().unwrap();
+        let clone1_dir: &RepoPath =
+            &clone1_dir.path().to_str().unwrap().into();
 
         let (clone2_dir, _clone2) = repo_clone(r1_dir).unwrap();
 
-        let clone2_dir = clone2_dir.path().to_str().unwrap();
+        let clone2_dir: &RepoPath =
+            &clone2_dir.path().to_str().unwrap().into();
 
         // clone1
 
@@ -225,7 +249,8 @@ mod tests {
 
         push(clone1_dir, "origin", "master", false, None, None)
             .unwrap();
-        push_tags(clone1_dir, "origin", None, None).unwrap();
+        push_tags(clone1_dir, "origin", &[], false, None, None)
+            .unwrap();
 
         // clone2
 
@@ -245,7 +270,8 @@ mod tests {
 
         let (clone1_dir, clone1) = repo_clone(r1_dir).unwrap();
 
-        let clone1_dir = clone1_dir.path().to_str().unwrap();
+        let clone1_dir: &RepoPath =
+            &clone1_dir.path().to_str().unwrap().into();
 
         // clone1
 
@@ -264,9 +290,52 @@ mod tests {
             tags_missing.as_slice(),
             &[String::from("refs/tags/tag1")]
         );
-        push_tags(clone1_dir, "origin", None, None).unwrap();
+        push_tags(clone1_dir, "origin", &[], false, None, None)
+            .unwrap();
         let tags_missing =
             tags_missing_remote(clone1_dir, "origin", None).unwrap();
         assert!(tags_missing.is_empty());
     }
+
+    #[test]
+    fn test_force_push_moved_tag() {
+        let (r1_dir, remote) = repo_init_bare().unwrap();
+        let r1_dir = r1_dir.path().to_str().unwrap();
+
+        let (clone1_dir, clone1) = repo_clone(r1_dir).unwrap();
+
+        let clone1_dir: &RepoPath =
+            &clone1_dir.path().to_str().unwrap().into();
+
+        let commit1 =
+            write_commit_file(&clone1, "test.txt", "test", "commit1");
+        sync::tag(clone1_dir, &commit1, "tag1").unwrap();
+
+        push(clone1_dir, "origin", "master", false, None, None)
+            .unwrap();
+        push_tags(clone1_dir, "origin", &[], false, None, None)
+            .unwrap();
+
+        let commit2 =
+            write_commit_file(&clone1, "test.txt", "fix", "commit2");
+        sync::retag(clone1_dir, "tag1", commit2).unwrap();
+
+        push_tags(
+            clone1_dir,
+            "origin",
+            &[String::from("tag1")],
+            true,
+            None,
+            None,
+        )
+        .unwrap();
+
+        let remote_target = remote
+            .find_reference("refs/tags/tag1")
+            .unwrap()
+            .peel_to_commit()
+            .unwrap()
+            .id();
+        assert_eq!(sync::CommitId::new(remote_target), commit2);
+    }
 }
@@ -0,0 +1,279 @@
+//! commits as git shows them: replaced by `refs/replace/*`, with the
+//! parents given in `info/grafts` or cut off at the boundary of a
+//! shallow clone. libgit2 knows about none of this.
+
+use super::{utils::repo, CommitId, RepoPath};
+use crate::error::Result;
+use git2::{Commit, Oid, Repository};
+use scopetime::scope_time;
+use std::{
+    collections::{HashMap, HashSet},
+    env, fs,
+    path::Path,
+};
+
+const REPLACE_REFS: &str = "refs/replace/";
+const CONFIG_USE_REPLACE_REFS: &str = "core.useReplaceRefs";
+const ENV_NO_REPLACE_OBJECTS: &str = "GIT_NO_REPLACE_OBJECTS";
+
+/// commits that git shows differently than they are stored
+#[derive(Default, Debug)]
+pub struct Replacements {
+    /// replaced commits and their replacements
+    commits: HashMap<Oid, Oid>,
+    /// commits with grafted parents
+    grafts: HashMap<Oid, Vec<Oid>>,
+    /// commits whose parents are missing in a shallow clone
+    shallow: HashSet<Oid>,
+}
+
+impl Replacements {
+    /// replace refs and grafts are left out when turned off (see
+    /// `use_replace_refs`), the shallow boundary always applies
+    pub fn new(repo: &Repository) -> Result<Self> {
+        scope_time!("Replacements::new");
+
+        let mut res = Self {
+            shallow: read_oid_lines(&repo.path().join("shallow"))?
+                .into_iter()
+                .filter_map(|ids| ids.first().copied())
+                .collect(),
+            ..Self::default()
+        };
+
+        if !use_replace_refs_repo(repo)? {
+            return Ok(res);
+        }
+
+        for reference in
+            repo.references_glob(&format!("{}*", REPLACE_REFS))?
+        {
+            let reference = reference?;
+            let original = reference
+                .name()
+                .and_then(|name| name.strip_prefix(REPLACE_REFS))
+                .and_then(|id| Oid::from_str(id).ok());
+
+            if let (Some(original), Some(replacement)) =
+                (original, reference.target())
+            {
+                res.commits.insert(original, replacement);
+            }
+        }
+
+        for ids in read_oid_lines(&repo.path().join("info/grafts"))? {
+            if let Some((id, parents)) = ids.split_first() {
+                res.grafts.insert(*id, parents.to_vec());
+            }
+        }
+
+        Ok(res)
+    }
+
+    /// nothing to take care of, the history is walked as stored
+    pub fn is_empty(&self) -> bool {
+        self.commits.is_empty()
+            && self.grafts.is_empty()
+            && self.shallow.is_empty()
+    }
+
+    /// content or parents of the commit are not the stored ones
+    pub fn is_replaced(&self, id: Oid) -> bool {
+        self.commits.contains_key(&id)
+            || self.grafts.contains_key(&id)
+    }
+
+    /// the commit shown for `id`, the stored one if its replacement
+    /// is no commit
+    pub fn commit<'a>(
+        &self,
+        repo: &'a Repository,
+        id: Oid,
+    ) -> Result<Commit<'a>> {
+        if let Some(replacement) = self.commits.get(&id) {
+            if let Ok(commit) = repo.find_commit(*replacement) {
+                return Ok(commit);
+            }
+        }
+
+        Ok(repo.find_commit(id)?)
+    }
+
+    /// parents shown for `id`, `commit` is the one shown for it
+    pub fn parents(&self, id: Oid, commit: &Commit) -> Vec<Oid> {
+        if self.shallow.contains(&id) {
+            Vec::new()
+        } else if let Some(parents) = self.grafts.get(&id) {
+            parents.clone()
+        } else {
+            commit.parent_ids().collect()
+        }
+    }
+}
+
+/// lines of object ids (like `info/grafts` or `shallow`), a missing
+/// file has none
+fn read_oid_lines(file: &Path) -> Result<Vec<Vec<Oid>>> {
+    if !file.exists() {
+        return Ok(Vec::new());
+    }
+
+    Ok(fs::read_to_string(file)?
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .map(|line| {
+            line.split_whitespace()
+                .filter_map(|id| Oid::from_str(id).ok())
+                .collect::<Vec<_>>()
+        })
+        .filter(|ids| !ids.is_empty())
+        .collect())
+}
+
+fn use_replace_refs_repo(repo: &Repository) -> Result<bool> {
+    if env::var_os(ENV_NO_REPLACE_OBJECTS).is_some() {
+        return Ok(false);
+    }
+
+    Ok(repo
+        .config()?
+        .get_bool(CONFIG_USE_REPLACE_REFS)
+        .unwrap_or(true))
+}
+
+/// whether replace refs and grafts are honored, like git they are
+/// unless `core.useReplaceRefs` is off or `GIT_NO_REPLACE_OBJECTS`
+/// is set
+pub fn use_replace_refs(repo_path: &RepoPath) -> Result<bool> {
+    scope_time!("use_replace_refs");
+
+    use_replace_refs_repo(&repo(repo_path)?)
+}
+
+/// turns replace refs and grafts on or off in the repository config
+/// (`core.useReplaceRefs`)
+pub fn set_use_replace_refs(
+    repo_path: &RepoPath,
+    enabled: bool,
+) -> Result<()> {
+    scope_time!("set_use_replace_refs");
+
+    repo(repo_path)?
+        .config()?
+        .set_bool(CONFIG_USE_REPLACE_REFS, enabled)?;
+
+    Ok(())
+}
+
+/// whether the commit shown for `id` is not the stored one
+pub fn is_replaced(
+    repo_path: &RepoPath,
+    id: CommitId,
+) -> Result<bool> {
+    let repo = repo(repo_path)?;
+
+    Ok(Replacements::new(&repo)?.is_replaced(id.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::{
+        get_commit_details, get_commits_info,
+        tests::{get_commit_ids, repo_init_empty, write_commit_file},
+    };
+    use git2::Signature;
+
+    /// three commits `a <- b <- c`
+    fn history(repo: &Repository) -> (Oid, Oid, Oid) {
+        let a = write_commit_file(repo, "a.txt", "a", "a");
+        let b = write_commit_file(repo, "b.txt", "b", "b");
+        let c = write_commit_file(repo, "c.txt", "c", "c");
+
+        (a.into(), b.into(), c.into())
+    }
+
+    fn ids(repo: &Repository) -> Vec<Oid> {
+        get_commit_ids(repo, 100)
+            .into_iter()
+            .map(Oid::from)
+            .collect()
+    }
+
+    #[test]
+    fn test_replace_ref() {
+        let (_td, repo) = repo_init_empty().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        let (a, b, c) = history(&repo);
+
+        // b without parents and another message
+        let tree = repo.find_commit(b).unwrap().tree().unwrap();
+        let sig = Signature::now("name", "email").unwrap();
+        let replacement = repo
+            .commit(None, &sig, &sig, "replaced", &tree, &[])
+            .unwrap();
+        repo.reference(
+            &format!("{}{}", REPLACE_REFS, b),
+            replacement,
+            false,
+            "replace",
+        )
+        .unwrap();
+
+        assert_eq!(ids(&repo), vec![c, b]);
+        assert!(is_replaced(repo_path, b.into()).unwrap());
+        assert!(!is_replaced(repo_path, c.into()).unwrap());
+
+        let info =
+            get_commits_info(repo_path, &[b.into()], 50).unwrap();
+        assert_eq!(info[0].message, "replaced");
+        assert_eq!(info[0].id, b.into());
+        assert!(info[0].replaced);
+
+        let details =
+            get_commit_details(repo_path, b.into()).unwrap();
+        assert_eq!(details.message.unwrap().subject, "replaced");
+        assert!(details.replaced);
+
+        set_use_replace_refs(repo_path, false).unwrap();
+        assert!(!use_replace_refs(repo_path).unwrap());
+
+        assert_eq!(ids(&repo), vec![c, b, a]);
+        assert!(!is_replaced(repo_path, b.into()).unwrap());
+    }
+
+    #[test]
+    fn test_grafts() {
+        let (_td, repo) = repo_init_empty().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        let (a, b, c) = history(&repo);
+
+        fs::create_dir_all(repo.path().join("info")).unwrap();
+        fs::write(
+            repo.path().join("info/grafts"),
+            format!("{} {}\n", c, a),
+        )
+        .unwrap();
+
+        assert_eq!(ids(&repo), vec![c, a]);
+        assert!(!is_replaced(repo_path, b.into()).unwrap());
+        assert!(is_replaced(repo_path, c.into()).unwrap());
+    }
+
+    #[test]
+    fn test_shallow() {
+        let (_td, repo) = repo_init_empty().unwrap();
+        let (_a, b, c) = history(&repo);
+
+        fs::write(repo.path().join("shallow"), format!("{}\n", b))
+            .unwrap();
+
+        assert_eq!(ids(&repo), vec![c, b]);
+    }
+}
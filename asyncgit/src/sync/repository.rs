@@ -0,0 +1,65 @@
+//! location of a repository
+
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+/// where to find a repository
+#[derive(Clone, Debug, PartialEq)]
+pub enum RepoPath {
+    /// any path inside of a repository, the repository is searched
+    /// upwards from here
+    Path(PathBuf),
+    /// git dir and work tree living in separate places (e.g. a bare
+    /// dotfiles repository using `$HOME` as work tree)
+    Workdir {
+        /// path to the git dir (or the folder containing it)
+        gitdir: PathBuf,
+        /// work tree overriding the one configured in the repository
+        workdir: PathBuf,
+    },
+}
+
+impl RepoPath {
+    /// path the repository is opened from
+    pub fn gitpath(&self) -> &Path {
+        match self {
+            Self::Path(path) => path.as_path(),
+            Self::Workdir { gitdir, .. } => gitdir.as_path(),
+        }
+    }
+
+    /// explicit work tree, `None` if the one of the repository is used
+    pub fn workdir(&self) -> Option<&Path> {
+        match self {
+            Self::Path(_) => None,
+            Self::Workdir { workdir, .. } => Some(workdir.as_path()),
+        }
+    }
+
+    /// honors `GIT_DIR` and `GIT_WORK_TREE` like git does and falls
+    /// back to searching the repository from `path`
+    pub fn from_env(path: &str) -> Self {
+        let gitdir = env::var_os("GIT_DIR").map(PathBuf::from);
+        let workdir = env::var_os("GIT_WORK_TREE").map(PathBuf::from);
+
+        match (gitdir, workdir) {
+            (Some(gitdir), Some(workdir)) => {
+                Self::Workdir { gitdir, workdir }
+            }
+            (Some(gitdir), None) => Self::Path(gitdir),
+            (None, Some(workdir)) => Self::Workdir {
+                gitdir: PathBuf::from(path),
+                workdir,
+            },
+            (None, None) => Self::Path(PathBuf::from(path)),
+        }
+    }
+}
+
+impl From<&str> for RepoPath {
+    fn from(path: &str) -> Self {
+        Self::Path(PathBuf::from(path))
+    }
+}
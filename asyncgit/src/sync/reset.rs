@@ -1,54 +1,216 @@
-use super::utils::{get_head_repo, repo};
-use crate::error::Result;
-use git2::{build::CheckoutBuilder, ObjectType};
+use super::{
+    utils::{get_head_repo, repo, work_dir},
+    CommitId, InstanceLock, RepoPath,
+};
+use crate::error::{Error, Result};
+use git2::{build::CheckoutBuilder, ObjectType, StatusOptions};
 use scopetime::scope_time;
+use std::fs;
 
 ///
-pub fn reset_stage(repo_path: &str, path: &str) -> Result<()> {
+pub fn reset_stage(repo_path: &RepoPath, path: &str) -> Result<()> {
     scope_time!("reset_stage");
 
+    reset_stage_paths(repo_path, &[path.to_string()])
+}
+
+/// unstages all `paths` in one go
+pub fn reset_stage_paths(
+    repo_path: &RepoPath,
+    paths: &[String],
+) -> Result<()> {
+    scope_time!("reset_stage_paths");
+
+    let _lock = InstanceLock::acquire(repo_path)?;
+
+    // no paths would reset everything
+    if paths.is_empty() {
+        return Ok(());
+    }
+
     let repo = repo(repo_path)?;
 
     if let Ok(id) = get_head_repo(&repo) {
         let obj =
             repo.find_object(id.into(), Some(ObjectType::Commit))?;
 
-        repo.reset_default(Some(&obj), &[path])?;
+        repo.reset_default(Some(&obj), paths)?;
     } else {
-        repo.reset_default(None, &[path])?;
+        repo.reset_default(None, paths)?;
     }
 
     Ok(())
 }
 
 ///
-pub fn reset_workdir(repo_path: &str, path: &str) -> Result<()> {
+pub fn reset_workdir(repo_path: &RepoPath, path: &str) -> Result<()> {
     scope_time!("reset_workdir");
 
+    reset_workdir_paths(repo_path, &[path.to_string()])
+}
+
+/// discards the unstaged changes of all `paths` in one go
+pub fn reset_workdir_paths(
+    repo_path: &RepoPath,
+    paths: &[String],
+) -> Result<()> {
+    scope_time!("reset_workdir_paths");
+
+    let _lock = InstanceLock::acquire(repo_path)?;
+
+    // no paths would check out everything
+    if paths.is_empty() {
+        return Ok(());
+    }
+
     let repo = repo(repo_path)?;
 
     let mut checkout_opts = CheckoutBuilder::new();
     checkout_opts
         .update_index(true) // windows: needs this to be true WTF?!
         .remove_untracked(true)
-        .force()
-        .path(path);
+        .force();
+
+    for path in paths {
+        checkout_opts.path(path);
+    }
+
+    repo.checkout_index(None, Some(&mut checkout_opts))?;
+    Ok(())
+}
+
+/// unstages everything, also works in a repo without any commit yet
+pub fn unstage_all(repo_path: &RepoPath) -> Result<()> {
+    scope_time!("unstage_all");
+
+    let _lock = InstanceLock::acquire(repo_path)?;
+
+    reset_stage(repo_path, "*")
+}
+
+/// reverts all unstaged changes: tracked files are checked out from
+/// the index and untracked files get removed. submodules and nested
+/// repositories are left untouched.
+pub fn discard_all(repo_path: &RepoPath) -> Result<()> {
+    scope_time!("discard_all");
+
+    let _lock = InstanceLock::acquire(repo_path)?;
+
+    let repo = repo(repo_path)?;
+
+    let mut checkout_opts = CheckoutBuilder::new();
+    checkout_opts
+        .update_index(true) // windows: needs this to be true WTF?!
+        .force();
 
     repo.checkout_index(None, Some(&mut checkout_opts))?;
+
+    let work_dir = work_dir(&repo)?;
+
+    let statuses = repo.statuses(Some(
+        StatusOptions::default()
+            .include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .exclude_submodules(true),
+    ))?;
+
+    for entry in statuses.iter() {
+        if !entry.status().is_wt_new() {
+            continue;
+        }
+
+        // nested repositories show up as a single directory entry
+        if let Some(path) = entry.path() {
+            let path = work_dir.join(path);
+            if !fs::symlink_metadata(&path)?.is_dir() {
+                fs::remove_file(path)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// undoes the last commit by moving `HEAD` to its parent, the
+/// changes of the commit stay staged (`git reset --soft HEAD~`)
+pub fn reset_soft_head_parent(repo_path: &RepoPath) -> Result<()> {
+    scope_time!("reset_soft_head_parent");
+
+    let _lock = InstanceLock::acquire(repo_path)?;
+
+    let repo = repo(repo_path)?;
+
+    let head = repo.find_commit(get_head_repo(&repo)?.into())?;
+    if head.parent_count() == 0 {
+        return Err(Error::Generic(
+            "cannot undo the initial commit".to_string(),
+        ));
+    }
+
+    let parent = head.parent(0)?;
+    repo.reset(parent.as_object(), git2::ResetType::Soft, None)?;
+
+    Ok(())
+}
+
+/// how `reset_to_commit` treats index and working tree
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ResetType {
+    /// only moves the branch, changes stay staged
+    Soft,
+    /// also resets the index, changes stay in the working tree
+    Mixed,
+    /// also resets the working tree, changes are lost
+    Hard,
+}
+
+impl From<ResetType> for git2::ResetType {
+    fn from(kind: ResetType) -> Self {
+        match kind {
+            ResetType::Soft => Self::Soft,
+            ResetType::Mixed => Self::Mixed,
+            ResetType::Hard => Self::Hard,
+        }
+    }
+}
+
+/// moves the current branch to `commit` (`git reset --<kind>`)
+pub fn reset_to_commit(
+    repo_path: &RepoPath,
+    commit: CommitId,
+    kind: ResetType,
+) -> Result<()> {
+    scope_time!("reset_to_commit");
+
+    let _lock = InstanceLock::acquire(repo_path)?;
+
+    let repo = repo(repo_path)?;
+
+    let commit = repo.find_commit(commit.into())?;
+    repo.reset(commit.as_object(), kind.into(), None)?;
+
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{reset_stage, reset_workdir};
+    use super::{
+        discard_all, reset_soft_head_parent, reset_stage,
+        reset_stage_paths, reset_to_commit, reset_workdir,
+        reset_workdir_paths, unstage_all, ResetType,
+    };
     use crate::error::Result;
+    use crate::sync::RepoPath;
     use crate::sync::{
         commit,
         status::{get_status, StatusType},
         tests::{
-            debug_cmd_print, get_statuses, repo_init, repo_init_empty,
+            debug_cmd_print, get_statuses, repo_init,
+            repo_init_empty, write_commit_file,
+        },
+        utils::{
+            get_head, stage_add_all, stage_add_file, stage_all_paths,
         },
-        utils::{stage_add_all, stage_add_file},
     };
     use std::{
         fs::{self, File},
@@ -86,7 +248,8 @@ mod tests {
     fn test_reset_only_unstaged() {
         let (_td, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         let res = get_status(repo_path, StatusType::WorkingDir, true)
             .unwrap();
@@ -130,7 +293,8 @@ mod tests {
     fn test_reset_untracked_in_subdir() {
         let (_td, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         {
             fs::create_dir(&root.join("foo")).unwrap();
@@ -155,7 +319,8 @@ mod tests {
     fn test_reset_folder() -> Result<()> {
         let (_td, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         {
             fs::create_dir(&root.join("foo"))?;
@@ -200,7 +365,8 @@ mod tests {
     fn test_reset_untracked_in_subdir_and_index() {
         let (_td, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
         let file = "foo/bar.txt";
 
         {
@@ -240,7 +406,8 @@ mod tests {
         let file_path = Path::new("foo.txt");
         let (_td, repo) = repo_init_empty().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         File::create(&root.join(file_path))
             .unwrap()
@@ -262,7 +429,8 @@ mod tests {
     fn test_reset_untracked_in_subdir_with_cwd_in_subdir() {
         let (_td, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         {
             fs::create_dir(&root.join("foo")).unwrap();
@@ -277,7 +445,7 @@ mod tests {
         assert_eq!(get_statuses(repo_path), (1, 0));
 
         reset_workdir(
-            &root.join("foo").as_os_str().to_str().unwrap(),
+            &root.join("foo").as_os_str().to_str().unwrap().into(),
             "foo/bar.txt",
         )
         .unwrap();
@@ -291,7 +459,8 @@ mod tests {
     fn test_reset_untracked_subdir() {
         let (_td, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         {
             fs::create_dir_all(&root.join("foo/bar")).unwrap();
@@ -311,4 +480,181 @@ mod tests {
 
         assert_eq!(get_statuses(repo_path), (0, 0));
     }
+
+    #[test]
+    fn test_unstage_all_in_empty_repo() -> Result<()> {
+        let (_td, repo) = repo_init_empty()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        File::create(&root.join("foo.txt"))?.write_all(b"foo")?;
+        File::create(&root.join("bar.txt"))?.write_all(b"bar")?;
+
+        stage_all_paths(repo_path)?;
+
+        assert_eq!(get_statuses(repo_path), (0, 2));
+
+        unstage_all(repo_path)?;
+
+        assert_eq!(get_statuses(repo_path), (2, 0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reset_paths() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        for file in &["a.txt", "b.txt", "c.txt"] {
+            File::create(&root.join(file))?.write_all(b"foo")?;
+        }
+
+        stage_all_paths(repo_path)?;
+        assert_eq!(get_statuses(repo_path), (0, 3));
+
+        // no paths must not mean all paths
+        reset_stage_paths(repo_path, &[])?;
+        assert_eq!(get_statuses(repo_path), (0, 3));
+
+        let paths =
+            vec![String::from("a.txt"), String::from("b.txt")];
+        reset_stage_paths(repo_path, &paths)?;
+        assert_eq!(get_statuses(repo_path), (2, 1));
+
+        reset_workdir_paths(repo_path, &[])?;
+        assert_eq!(get_statuses(repo_path), (2, 1));
+
+        reset_workdir_paths(repo_path, &paths)?;
+        assert_eq!(get_statuses(repo_path), (0, 1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_stage_and_discard_all() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        {
+            fs::create_dir(&root.join("foo"))?;
+            File::create(&root.join("foo/file1.txt"))?
+                .write_all(b"file1")?;
+            File::create(&root.join("file2.txt"))?
+                .write_all(b"file2")?;
+        }
+
+        stage_add_all(repo_path, "*")?;
+        commit(repo_path, "msg")?;
+
+        {
+            File::create(&root.join("foo/file1.txt"))?
+                .write_all(b"file1\nadded line")?;
+            fs::remove_file(&root.join("file2.txt"))?;
+            File::create(&root.join("foo/file3.txt"))?
+                .write_all(b"file3")?;
+        }
+
+        assert_eq!(get_statuses(repo_path), (3, 0));
+
+        stage_all_paths(repo_path)?;
+
+        assert_eq!(get_statuses(repo_path), (0, 3));
+
+        unstage_all(repo_path)?;
+
+        assert_eq!(get_statuses(repo_path), (3, 0));
+
+        discard_all(repo_path)?;
+
+        assert_eq!(get_statuses(repo_path), (0, 0));
+        assert!(root.join("file2.txt").exists());
+        assert!(!root.join("foo/file3.txt").exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_discard_all_keeps_nested_repo() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        {
+            fs::create_dir(&root.join("sub"))?;
+            File::create(&root.join("untracked.txt"))?
+                .write_all(b"untracked")?;
+        }
+
+        let nested = root.join("sub");
+        git2::Repository::init(&nested)?;
+        File::create(&nested.join("file.txt"))?.write_all(b"file")?;
+
+        discard_all(repo_path)?;
+
+        assert!(!root.join("untracked.txt").exists());
+        assert!(nested.join("file.txt").exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reset_soft_head_parent() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        let first = write_commit_file(&repo, "a.txt", "a", "first");
+        write_commit_file(&repo, "b.txt", "b", "second");
+
+        reset_soft_head_parent(repo_path)?;
+
+        assert_eq!(get_head(repo_path)?, first);
+        assert_eq!(get_statuses(repo_path), (0, 1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reset_soft_head_parent_initial_commit() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        assert!(reset_soft_head_parent(repo_path).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reset_to_commit() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        let first = write_commit_file(&repo, "a.txt", "a", "first");
+        write_commit_file(&repo, "a.txt", "b", "second");
+
+        reset_to_commit(repo_path, first, ResetType::Soft)?;
+        assert_eq!(get_head(repo_path)?, first);
+        assert_eq!(get_statuses(repo_path), (0, 1));
+
+        reset_to_commit(repo_path, first, ResetType::Mixed)?;
+        assert_eq!(get_statuses(repo_path), (1, 0));
+
+        reset_to_commit(repo_path, first, ResetType::Hard)?;
+        assert_eq!(get_statuses(repo_path), (0, 0));
+        assert_eq!(fs::read_to_string(root.join("a.txt"))?, "a");
+
+        Ok(())
+    }
 }
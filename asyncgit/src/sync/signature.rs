@@ -0,0 +1,354 @@
+//! commit signatures checked like `git verify-commit` does: gpg
+//! signatures by `gpg.program`, ssh ones by `gpg.ssh.program` against
+//! the `gpg.ssh.allowedSignersFile`
+
+use crate::error::Result;
+use git2::{ErrorCode, Oid, Repository};
+use scopetime::scope_time;
+use std::{
+    env, fs,
+    io::Write,
+    path::{Path, PathBuf},
+    process::{self, Command, Stdio},
+};
+
+const SSH_SIGNATURE: &str = "-----BEGIN SSH SIGNATURE-----";
+const SSH_NAMESPACE: &str = "git";
+const GPG_STATUS_PREFIX: &str = "[GNUPG:] ";
+
+/// outcome of checking a commit signature
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SignatureStatus {
+    /// good signature by a trusted key or an allowed signer
+    Verified,
+    /// could not be checked (unknown key, no allowed signers, missing
+    /// program) or made by an expired, revoked or untrusted key
+    Unverified,
+    /// does not match the signed commit
+    Bad,
+}
+
+/// signature of a commit
+#[derive(Clone, Debug, PartialEq)]
+pub struct SignatureInfo {
+    ///
+    pub status: SignatureStatus,
+    /// user id of the gpg key or ssh principal, if known
+    pub signer: Option<String>,
+}
+
+impl SignatureInfo {
+    const fn unverified() -> Self {
+        Self {
+            status: SignatureStatus::Unverified,
+            signer: None,
+        }
+    }
+}
+
+/// checks the signature of commit `id`, `None` if it is unsigned
+pub fn verify_signature(
+    repo: &Repository,
+    id: Oid,
+) -> Result<Option<SignatureInfo>> {
+    scope_time!("verify_signature");
+
+    let (signature, data) = match repo.extract_signature(&id, None) {
+        Ok(res) => res,
+        Err(e) if e.code() == ErrorCode::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+
+    let file = SignatureFile::new(id, &signature)?;
+
+    let info = if signature.starts_with(SSH_SIGNATURE.as_bytes()) {
+        verify_ssh(repo, file.path(), &data)?
+    } else {
+        verify_gpg(repo, file.path(), &data)?
+    };
+
+    Ok(Some(info))
+}
+
+fn verify_gpg(
+    repo: &Repository,
+    signature: &Path,
+    data: &[u8],
+) -> Result<SignatureInfo> {
+    let config = repo.config()?;
+    let program = config
+        .get_string("gpg.openpgp.program")
+        .or_else(|_| config.get_string("gpg.program"))
+        .unwrap_or_else(|_| String::from("gpg"));
+
+    Ok(run(
+        Command::new(program)
+            .args(&["--status-fd=1", "--verify"])
+            .arg(signature)
+            .arg("-"),
+        data,
+    )
+    .map_or_else(SignatureInfo::unverified, |(_, status)| {
+        parse_gpg_status(&status)
+    }))
+}
+
+fn verify_ssh(
+    repo: &Repository,
+    signature: &Path,
+    data: &[u8],
+) -> Result<SignatureInfo> {
+    let config = repo.config()?;
+    let program = config
+        .get_string("gpg.ssh.program")
+        .unwrap_or_else(|_| String::from("ssh-keygen"));
+
+    if let Ok(allowed_signers) =
+        config.get_path("gpg.ssh.allowedSignersFile")
+    {
+        let principal = run(
+            Command::new(&program)
+                .args(&["-Y", "find-principals", "-f"])
+                .arg(&allowed_signers)
+                .arg("-s")
+                .arg(signature),
+            &[],
+        )
+        .filter(|(success, _)| *success)
+        .and_then(|(_, out)| out.lines().next().map(String::from));
+
+        if let Some(principal) = principal {
+            let verified = run(
+                Command::new(&program)
+                    .args(&[
+                        "-Y",
+                        "verify",
+                        "-n",
+                        SSH_NAMESPACE,
+                        "-f",
+                    ])
+                    .arg(&allowed_signers)
+                    .args(&["-I", principal.as_str(), "-s"])
+                    .arg(signature),
+                data,
+            )
+            .map_or(false, |(success, _)| success);
+
+            return Ok(SignatureInfo {
+                status: if verified {
+                    SignatureStatus::Verified
+                } else {
+                    SignatureStatus::Bad
+                },
+                signer: Some(principal),
+            });
+        }
+    }
+
+    // no allowed signer, but the signature may still be broken
+    let valid = run(
+        Command::new(&program)
+            .args(&["-Y", "check-novalidate", "-n", SSH_NAMESPACE])
+            .arg("-s")
+            .arg(signature),
+        data,
+    )
+    .map(|(success, _)| success);
+
+    Ok(if valid == Some(false) {
+        SignatureInfo {
+            status: SignatureStatus::Bad,
+            signer: None,
+        }
+    } else {
+        SignatureInfo::unverified()
+    })
+}
+
+/// reads the machine readable `--status-fd` output of gpg, only keys
+/// trusted at least marginally count as verified
+fn parse_gpg_status(status: &str) -> SignatureInfo {
+    let mut res = SignatureInfo::unverified();
+    let mut good = false;
+    let mut trusted = false;
+
+    for line in status
+        .lines()
+        .filter_map(|line| line.strip_prefix(GPG_STATUS_PREFIX))
+    {
+        let mut words = line.splitn(2, ' ');
+        let keyword = words.next().unwrap_or_default();
+        // "<keyword> <key id> <user id>"
+        let user_id = words
+            .next()
+            .and_then(|args| args.splitn(2, ' ').nth(1))
+            .map(String::from);
+
+        match keyword {
+            "GOODSIG" => {
+                good = true;
+                res.signer = user_id;
+            }
+            "BADSIG" => {
+                return SignatureInfo {
+                    status: SignatureStatus::Bad,
+                    signer: user_id,
+                };
+            }
+            "EXPSIG" | "EXPKEYSIG" | "REVKEYSIG" => {
+                res.signer = user_id;
+            }
+            "TRUST_MARGINAL" | "TRUST_FULLY" | "TRUST_ULTIMATE" => {
+                trusted = true;
+            }
+            _ => (),
+        }
+    }
+
+    if good && trusted {
+        res.status = SignatureStatus::Verified;
+    }
+
+    res
+}
+
+/// runs `cmd` with `input` on stdin, `None` if it could not be run,
+/// otherwise whether it succeeded and its stdout
+fn run(cmd: &mut Command, input: &[u8]) -> Option<(bool, String)> {
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // programs may quit before reading all of it
+        stdin.write_all(input).ok();
+    }
+
+    let output = child.wait_with_output().ok()?;
+
+    Some((
+        output.status.success(),
+        String::from_utf8_lossy(&output.stdout).to_string(),
+    ))
+}
+
+/// the signature in a temporary file for the verifying programs,
+/// removed again on drop
+struct SignatureFile(PathBuf);
+
+impl SignatureFile {
+    fn new(id: Oid, signature: &[u8]) -> Result<Self> {
+        let path = env::temp_dir().join(format!(
+            "gitui-{}-{}.sig",
+            process::id(),
+            id
+        ));
+        fs::write(&path, signature)?;
+
+        Ok(Self(path))
+    }
+
+    fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for SignatureFile {
+    fn drop(&mut self) {
+        fs::remove_file(&self.0).ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::tests::{repo_init, write_commit_file};
+    use git2::Signature;
+
+    const GOOD_TRUSTED: &str = "\
+[GNUPG:] NEWSIG
+[GNUPG:] KEY_CONSIDERED 1234 0
+[GNUPG:] SIG_ID abc 2021-05-01 1619870000
+[GNUPG:] GOODSIG ABCD1234 Jane Doe <jane@example.com>
+[GNUPG:] VALIDSIG 1234 2021-05-01 1619870000 0 4 0 1 10 00 1234
+[GNUPG:] TRUST_ULTIMATE 0 pgp";
+
+    #[test]
+    fn test_gpg_status() {
+        assert_eq!(
+            parse_gpg_status(GOOD_TRUSTED),
+            SignatureInfo {
+                status: SignatureStatus::Verified,
+                signer: Some(String::from(
+                    "Jane Doe <jane@example.com>"
+                )),
+            }
+        );
+
+        let untrusted =
+            GOOD_TRUSTED.replace("TRUST_ULTIMATE", "TRUST_UNDEFINED");
+        assert_eq!(
+            parse_gpg_status(&untrusted).status,
+            SignatureStatus::Unverified
+        );
+
+        let bad =
+            "[GNUPG:] BADSIG ABCD1234 Jane Doe <jane@example.com>";
+        assert_eq!(
+            parse_gpg_status(bad).status,
+            SignatureStatus::Bad
+        );
+
+        let missing_key = "\
+[GNUPG:] ERRSIG ABCD1234 1 10 00 1619870000 9 -
+[GNUPG:] NO_PUBKEY ABCD1234";
+        assert_eq!(
+            parse_gpg_status(missing_key),
+            SignatureInfo::unverified()
+        );
+    }
+
+    #[test]
+    fn test_unsigned() {
+        let (_td, repo) = repo_init().unwrap();
+        let id = write_commit_file(&repo, "a.txt", "a", "a");
+
+        assert_eq!(verify_signature(&repo, id.into()).unwrap(), None);
+    }
+
+    #[test]
+    fn test_unverifiable() {
+        let (_td, repo) = repo_init().unwrap();
+        repo.config()
+            .unwrap()
+            .set_str("gpg.program", "gitui-missing-gpg")
+            .unwrap();
+
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        let sig = Signature::now("name", "email").unwrap();
+        let buffer = repo
+            .commit_create_buffer(
+                &sig,
+                &sig,
+                "signed",
+                &head.tree().unwrap(),
+                &[&head],
+            )
+            .unwrap();
+        let id = repo
+            .commit_signed(
+                buffer.as_str().unwrap(),
+                "-----BEGIN PGP SIGNATURE-----\n\nabc\n-----END PGP SIGNATURE-----",
+                None,
+            )
+            .unwrap();
+
+        assert_eq!(
+            verify_signature(&repo, id).unwrap(),
+            Some(SignatureInfo::unverified())
+        );
+    }
+}
@@ -0,0 +1,296 @@
+//! squashing a range of commits without an interactive rebase
+
+use super::{
+    branch::get_branch_name_repo,
+    commit::signature_allow_undefined_name,
+    utils::{get_head_repo, repo},
+    CommitId, InstanceLock, RepoPath,
+};
+use crate::error::{Error, Result};
+use git2::{BranchType, Commit, Oid, Repository, StatusOptions};
+use scopetime::scope_time;
+
+/// how `squash_commits` builds the message of the new commit
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SquashType {
+    /// all messages, oldest first
+    Squash,
+    /// only the message of the oldest commit
+    Fixup,
+}
+
+/// squashes `commits` into a single commit and rebases the commits
+/// following them on top of it, returns the new commit.
+///
+/// `commits` (in any order) have to be a contiguous first parent
+/// chain of non merge commits reachable from `HEAD` that are not
+/// part of the upstream branch yet.
+pub fn squash_commits(
+    repo_path: &RepoPath,
+    commits: &[CommitId],
+    kind: SquashType,
+) -> Result<CommitId> {
+    scope_time!("squash_commits");
+
+    let _lock = InstanceLock::acquire(repo_path)?;
+
+    let repo = repo(repo_path)?;
+
+    let chain = commit_chain(&repo, commits)?;
+    let newest = &chain[0];
+    let oldest = &chain[chain.len() - 1];
+
+    let head = get_head_repo(&repo)?;
+    if newest.id() != head.get_oid()
+        && !repo.graph_descendant_of(head.get_oid(), newest.id())?
+    {
+        return Err(Error::Generic(
+            "commits are not part of the current branch".to_string(),
+        ));
+    }
+
+    if is_pushed(&repo, oldest.id())? {
+        return Err(Error::Generic(
+            "commits are already pushed upstream".to_string(),
+        ));
+    }
+
+    if !repo
+        .statuses(Some(
+            StatusOptions::new()
+                .include_untracked(false)
+                .include_ignored(false),
+        ))?
+        .is_empty()
+    {
+        return Err(Error::UncommittedChanges);
+    }
+
+    let msg = match kind {
+        SquashType::Squash => chain
+            .iter()
+            .rev()
+            .map(|c| c.message().unwrap_or_default().trim())
+            .filter(|msg| !msg.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+        SquashType::Fixup => {
+            oldest.message().unwrap_or_default().to_string()
+        }
+    };
+
+    let signature = signature_allow_undefined_name(&repo)?;
+    let parent = oldest.parent(0)?;
+    let squashed = repo.commit(
+        None,
+        &oldest.author(),
+        &signature,
+        &msg,
+        &newest.tree()?,
+        &[&parent],
+    )?;
+
+    if newest.id() == head.get_oid() {
+        // same tree as `HEAD`, moving it is enough
+        repo.reset(
+            repo.find_commit(squashed)?.as_object(),
+            git2::ResetType::Soft,
+            None,
+        )?;
+    } else {
+        rebase_onto(&repo, newest.id(), squashed)?;
+    }
+
+    Ok(CommitId::new(squashed))
+}
+
+/// `commits` ordered newest first, fails if they are no first parent
+/// chain
+fn commit_chain<'a>(
+    repo: &'a Repository,
+    commits: &[CommitId],
+) -> Result<Vec<Commit<'a>>> {
+    if commits.len() < 2 {
+        return Err(Error::Generic(
+            "select at least two commits to squash".to_string(),
+        ));
+    }
+
+    let ids: Vec<Oid> =
+        commits.iter().map(|id| id.get_oid()).collect();
+
+    let mut newest = None;
+    for id in &ids {
+        let commit = repo.find_commit(*id)?;
+        if commit.parent_count() != 1 {
+            return Err(Error::Generic(
+                "cannot squash merge or initial commits".to_string(),
+            ));
+        }
+
+        let is_parent_of_other = ids.iter().any(|other| {
+            repo.find_commit(*other)
+                .ok()
+                .and_then(|c| c.parent_id(0).ok())
+                == Some(*id)
+        });
+
+        if !is_parent_of_other {
+            if newest.is_some() {
+                return Err(not_contiguous());
+            }
+            newest = Some(commit);
+        }
+    }
+
+    let mut chain = vec![newest.ok_or_else(not_contiguous)?];
+    while chain.len() < ids.len() {
+        let parent = chain[chain.len() - 1].parent(0)?;
+        if !ids.contains(&parent.id()) {
+            return Err(not_contiguous());
+        }
+        chain.push(parent);
+    }
+
+    Ok(chain)
+}
+
+fn not_contiguous() -> Error {
+    Error::Generic("commits are not contiguous".to_string())
+}
+
+/// `id` is reachable from the upstream of the current branch
+pub fn is_pushed(repo: &Repository, id: Oid) -> Result<bool> {
+    let upstream = get_branch_name_repo(repo)
+        .ok()
+        .and_then(|name| {
+            repo.find_branch(&name, BranchType::Local).ok()
+        })
+        .and_then(|branch| branch.upstream().ok())
+        .and_then(|upstream| upstream.get().target());
+
+    Ok(match upstream {
+        Some(upstream) => {
+            upstream == id
+                || repo.graph_descendant_of(upstream, id)?
+        }
+        None => false,
+    })
+}
+
+/// replays the commits after `base` up to `HEAD` onto `onto`
+fn rebase_onto(
+    repo: &Repository,
+    base: Oid,
+    onto: Oid,
+) -> Result<()> {
+    let upstream = repo.find_annotated_commit(base)?;
+    let onto = repo.find_annotated_commit(onto)?;
+
+    let mut rebase =
+        repo.rebase(None, Some(&upstream), Some(&onto), None)?;
+
+    let signature = signature_allow_undefined_name(repo)?;
+
+    while let Some(op) = rebase.next() {
+        let _op = op?;
+
+        if repo.index()?.has_conflicts() {
+            rebase.abort()?;
+            return Err(Error::Generic(String::from(
+                "conflicts while rebasing",
+            )));
+        }
+
+        rebase.commit(None, &signature, None)?;
+    }
+
+    rebase.finish(Some(&signature))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{squash_commits, SquashType};
+    use crate::sync::{
+        get_commit_details, get_head,
+        tests::{get_commit_ids, repo_init, write_commit_file},
+        utils::repo_read_file,
+        RepoPath,
+    };
+
+    #[test]
+    fn test_squash_below_head() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        let c1 = write_commit_file(&repo, "a.txt", "1", "first");
+        let c2 = write_commit_file(&repo, "a.txt", "2", "second");
+        write_commit_file(&repo, "b.txt", "3", "third");
+
+        let squashed =
+            squash_commits(repo_path, &[c1, c2], SquashType::Squash)
+                .unwrap();
+
+        let ids = get_commit_ids(&repo, 10);
+        assert_eq!(ids.len(), 3);
+        assert_eq!(ids[1], squashed);
+
+        let details =
+            get_commit_details(repo_path, squashed).unwrap();
+        assert_eq!(
+            details.message.unwrap().combine(),
+            "first\n\nsecond"
+        );
+
+        assert_eq!(repo_read_file(&repo, "a.txt").unwrap(), "2");
+        assert_eq!(repo_read_file(&repo, "b.txt").unwrap(), "3");
+    }
+
+    #[test]
+    fn test_fixup_head() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        let c1 = write_commit_file(&repo, "a.txt", "1", "first");
+        let c2 = write_commit_file(&repo, "a.txt", "2", "oops");
+
+        let squashed =
+            squash_commits(repo_path, &[c2, c1], SquashType::Fixup)
+                .unwrap();
+
+        assert_eq!(get_head(repo_path).unwrap(), squashed);
+        assert_eq!(get_commit_ids(&repo, 10).len(), 2);
+
+        let details =
+            get_commit_details(repo_path, squashed).unwrap();
+        assert_eq!(details.message.unwrap().subject, "first");
+        assert_eq!(repo_read_file(&repo, "a.txt").unwrap(), "2");
+    }
+
+    #[test]
+    fn test_squash_not_contiguous() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        let c1 = write_commit_file(&repo, "a.txt", "1", "first");
+        write_commit_file(&repo, "a.txt", "2", "second");
+        let c3 = write_commit_file(&repo, "a.txt", "3", "third");
+
+        assert!(squash_commits(
+            repo_path,
+            &[c1, c3],
+            SquashType::Squash
+        )
+        .is_err());
+        assert!(squash_commits(repo_path, &[c3], SquashType::Squash)
+            .is_err());
+    }
+}
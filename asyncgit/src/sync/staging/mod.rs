@@ -1,17 +1,13 @@
-mod discard_tracked;
+#[cfg(test)]
+mod proptests;
 mod stage_tracked;
 
-pub use discard_tracked::discard_lines;
 pub use stage_tracked::stage_lines;
 
-use super::{
-    diff::DiffLinePosition, patches::HunkLines, utils::work_dir,
-};
+use super::{diff::DiffLinePosition, patches::HunkLines};
 use crate::error::Result;
-use git2::{DiffLine, DiffLineType, Repository};
-use std::{
-    collections::HashSet, convert::TryFrom, fs::File, io::Read,
-};
+use git2::{DiffLine, DiffLineType};
+use std::{collections::HashSet, convert::TryFrom};
 
 const NEWLINE: char = '\n';
 
@@ -70,32 +66,23 @@ impl NewFromOldContent {
     }
 }
 
-// this is the heart of the per line discard,stage,unstage. heavily inspired by the great work in nodegit: https://github.com/nodegit/nodegit
+// this is the heart of the per line stage,unstage. heavily inspired by the great work in nodegit: https://github.com/nodegit/nodegit
 #[allow(clippy::redundant_pub_crate)]
 pub(crate) fn apply_selection(
     lines: &[DiffLinePosition],
     hunks: &[HunkLines],
     old_lines: &[&str],
     is_staged: bool,
-    reverse: bool,
 ) -> Result<String> {
     let mut new_content = NewFromOldContent::default();
     let lines = lines.iter().collect::<HashSet<_>>();
 
-    let added = if reverse {
-        DiffLineType::Deletion
-    } else {
-        DiffLineType::Addition
-    };
-    let deleted = if reverse {
-        DiffLineType::Addition
-    } else {
-        DiffLineType::Deletion
-    };
+    let added = DiffLineType::Addition;
+    let deleted = DiffLineType::Deletion;
 
     let mut first_hunk_encountered = false;
     for hunk in hunks {
-        let hunk_start = if is_staged || reverse {
+        let hunk_start = if is_staged {
             usize::try_from(hunk.hunk.new_start)?
         } else {
             usize::try_from(hunk.hunk.old_start)?
@@ -172,15 +159,3 @@ pub(crate) fn apply_selection(
 
     Ok(new_content.finish(old_lines))
 }
-
-pub fn load_file(
-    repo: &Repository,
-    file_path: &str,
-) -> Result<String> {
-    let repo_path = work_dir(repo)?;
-    let mut file = File::open(repo_path.join(file_path).as_path())?;
-    let mut res = String::new();
-    file.read_to_string(&mut res)?;
-
-    Ok(res)
-}
@@ -0,0 +1,267 @@
+//! property based round trips through line and hunk staging: random
+//! files get random edits, a random selection of the changed lines
+//! is staged (or discarded) and afterwards index and worktree have
+//! to hold exactly what the selection implies. lines and hunks are
+//! picked from the diff as displayed with random diff options and
+//! passed on the way the ui does
+
+use super::stage_lines;
+use crate::sync::{
+    diff::{get_diff, DiffLinePosition, DiffLineType, DiffOptions},
+    reset_hunk_workdir, reset_lines_workdir, stage_hunk,
+    tests::{repo_init, write_commit_file},
+    utils::{repo_read_file, repo_write_file},
+    RepoPath,
+};
+use git2::Repository;
+use proptest::{prelude::*, sample::Index};
+use std::path::Path;
+use tempfile::TempDir;
+
+const FILE: &str = "test.txt";
+
+#[derive(Debug, Clone)]
+enum Edit {
+    Insert(Index, &'static str),
+    Delete(Index),
+    Replace(Index, &'static str),
+}
+
+fn line() -> impl Strategy<Value = &'static str> {
+    // few distinct lines make for ambiguous diffs
+    prop::sample::select(vec!["a", "b", "c", "{", "}", ""])
+}
+
+fn edit() -> impl Strategy<Value = Edit> {
+    prop_oneof![
+        (any::<Index>(), line())
+            .prop_map(|(idx, line)| Edit::Insert(idx, line)),
+        any::<Index>().prop_map(Edit::Delete),
+        (any::<Index>(), line())
+            .prop_map(|(idx, line)| Edit::Replace(idx, line)),
+    ]
+}
+
+/// file content before and after the edits, both keep at least one
+/// line and end with a newline
+fn file_pair() -> impl Strategy<Value = (String, String)> {
+    (
+        prop::collection::vec(line(), 1..20),
+        prop::collection::vec(edit(), 1..8),
+    )
+        .prop_map(|(old, edits)| {
+            let mut new = old.clone();
+            for edit in edits {
+                match edit {
+                    Edit::Insert(idx, line) => {
+                        new.insert(idx.index(new.len() + 1), line)
+                    }
+                    Edit::Delete(idx) if new.len() > 1 => {
+                        new.remove(idx.index(new.len()));
+                    }
+                    Edit::Delete(_) => (),
+                    Edit::Replace(idx, line) => {
+                        let idx = idx.index(new.len());
+                        new[idx] = line;
+                    }
+                }
+            }
+
+            (content(&old), content(&new))
+        })
+}
+
+/// the diff options the user could have set
+fn diff_options() -> impl Strategy<Value = DiffOptions> {
+    (1_u32..6).prop_map(|context| DiffOptions {
+        context,
+        ..DiffOptions::default()
+    })
+}
+
+/// picks the changed lines to select, repeated cyclically
+fn selection() -> impl Strategy<Value = Vec<bool>> {
+    prop::collection::vec(any::<bool>(), 1..32)
+}
+
+fn content(lines: &[&str]) -> String {
+    format!("{}\n", lines.join("\n"))
+}
+
+struct Fixture {
+    _td: TempDir,
+    repo: Repository,
+    path: RepoPath,
+}
+
+impl Fixture {
+    /// `old` committed, `new` in the worktree
+    fn new(old: &str, new: &str) -> Self {
+        let (td, repo) = repo_init().unwrap();
+        let path = td.path().to_str().unwrap().into();
+
+        write_commit_file(&repo, FILE, old, "old");
+        repo_write_file(&repo, FILE, new).unwrap();
+
+        Self {
+            _td: td,
+            repo,
+            path,
+        }
+    }
+
+    fn index(&self) -> String {
+        let mut index = self.repo.index().unwrap();
+        index.read(true).unwrap();
+        let entry = index.get_path(Path::new(FILE), 0).unwrap();
+        let blob = self.repo.find_blob(entry.id).unwrap();
+        String::from_utf8(blob.content().into()).unwrap()
+    }
+
+    fn worktree(&self) -> String {
+        repo_read_file(&self.repo, FILE).unwrap()
+    }
+
+    /// added and deleted lines of the staged or unstaged diff,
+    /// filtered by `mask` if given
+    fn changed_lines(
+        &self,
+        staged: bool,
+        options: DiffOptions,
+        mask: Option<&[bool]>,
+    ) -> Vec<DiffLinePosition> {
+        let diff = get_diff(&self.path, FILE, staged, Some(options))
+            .unwrap();
+
+        diff.hunks
+            .iter()
+            .flat_map(|hunk| hunk.lines.iter())
+            .filter(|line| {
+                matches!(
+                    line.line_type,
+                    DiffLineType::Add | DiffLineType::Delete
+                )
+            })
+            .enumerate()
+            .filter(|(idx, _)| {
+                mask.map_or(true, |mask| mask[idx % mask.len()])
+            })
+            .map(|(_, line)| line.position)
+            .collect()
+    }
+
+    /// hash of the first hunk of the unstaged diff
+    fn first_hunk(&self, options: DiffOptions) -> Option<u64> {
+        get_diff(&self.path, FILE, false, Some(options))
+            .unwrap()
+            .hunks
+            .first()
+            .map(|hunk| hunk.header_hash)
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn stage_selection_then_rest(
+        (old, new) in file_pair(),
+        mask in selection(),
+        options in diff_options(),
+    ) {
+        let fixture = Fixture::new(&old, &new);
+
+        let lines = fixture.changed_lines(false, options, Some(&mask));
+        stage_lines(&fixture.path, FILE, false, &lines).unwrap();
+        prop_assert_eq!(&fixture.worktree(), &new);
+
+        let rest = fixture.changed_lines(false, options, None);
+        stage_lines(&fixture.path, FILE, false, &rest).unwrap();
+        prop_assert_eq!(&fixture.index(), &new);
+        prop_assert_eq!(&fixture.worktree(), &new);
+    }
+
+    #[test]
+    fn stage_then_unstage(
+        (old, new) in file_pair(),
+        mask in selection(),
+        options in diff_options(),
+    ) {
+        let fixture = Fixture::new(&old, &new);
+
+        let lines = fixture.changed_lines(false, options, Some(&mask));
+        stage_lines(&fixture.path, FILE, false, &lines).unwrap();
+
+        let staged = fixture.changed_lines(true, options, None);
+        stage_lines(&fixture.path, FILE, true, &staged).unwrap();
+        prop_assert_eq!(&fixture.index(), &old);
+        prop_assert_eq!(&fixture.worktree(), &new);
+    }
+
+    #[test]
+    fn reset_selection_then_rest(
+        (old, new) in file_pair(),
+        mask in selection(),
+        options in diff_options(),
+    ) {
+        let fixture = Fixture::new(&old, &new);
+
+        let lines = fixture.changed_lines(false, options, Some(&mask));
+        reset_lines_workdir(&fixture.path, FILE, &lines)
+            .unwrap();
+        prop_assert_eq!(&fixture.index(), &old);
+
+        let rest = fixture.changed_lines(false, options, None);
+        reset_lines_workdir(&fixture.path, FILE, &rest)
+            .unwrap();
+        prop_assert_eq!(&fixture.worktree(), &old);
+        prop_assert_eq!(&fixture.index(), &old);
+    }
+
+    #[test]
+    fn stage_all_hunks(
+        (old, new) in file_pair(),
+        options in diff_options(),
+    ) {
+        let fixture = Fixture::new(&old, &new);
+
+        // staging a hunk moves the following ones, so their
+        // headers (and hashes) are only valid in a fresh diff
+        for _ in 0..20 {
+            if let Some(hash) = fixture.first_hunk(options) {
+                stage_hunk(&fixture.path, FILE, hash, Some(options))
+                    .unwrap();
+            } else {
+                break;
+            }
+        }
+
+        prop_assert_eq!(&fixture.index(), &new);
+        prop_assert_eq!(&fixture.worktree(), &new);
+    }
+
+    #[test]
+    fn reset_all_hunks(
+        (old, new) in file_pair(),
+        options in diff_options(),
+    ) {
+        let fixture = Fixture::new(&old, &new);
+
+        for _ in 0..20 {
+            if let Some(hash) = fixture.first_hunk(options) {
+                reset_hunk_workdir(
+                    &fixture.path,
+                    FILE,
+                    hash,
+                    Some(options),
+                )
+                .unwrap();
+            } else {
+                break;
+            }
+        }
+
+        prop_assert_eq!(&fixture.worktree(), &old);
+        prop_assert_eq!(&fixture.index(), &old);
+    }
+}
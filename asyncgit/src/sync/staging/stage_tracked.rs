@@ -4,6 +4,7 @@ use crate::{
     sync::{
         diff::DiffLinePosition,
         patches::get_file_diff_patch_and_hunklines, utils::repo,
+        InstanceLock, RepoPath,
     },
 };
 use easy_cast::Conv;
@@ -12,13 +13,15 @@ use std::path::Path;
 
 ///
 pub fn stage_lines(
-    repo_path: &str,
+    repo_path: &RepoPath,
     file_path: &str,
     is_stage: bool,
     lines: &[DiffLinePosition],
 ) -> Result<()> {
     scope_time!("stage_lines");
 
+    let _lock = InstanceLock::acquire(repo_path)?;
+
     if lines.is_empty() {
         return Ok(());
     }
@@ -44,7 +47,7 @@ pub fn stage_lines(
 
         let old_lines = indexed_content.lines().collect::<Vec<_>>();
 
-        apply_selection(lines, &hunks, &old_lines, is_stage, false)?
+        apply_selection(lines, &hunks, &old_lines, is_stage)?
     };
 
     let blob_id = repo.blob(new_content.as_bytes())?;
@@ -81,7 +84,7 @@ mod test {
 ";
 
         let (path, repo) = repo_init().unwrap();
-        let path = path.path().to_str().unwrap();
+        let path: &RepoPath = &path.path().to_str().unwrap().into();
 
         write_commit_file(&repo, "test.txt", FILE_1, "c1");
 
@@ -98,7 +101,7 @@ mod test {
         )
         .unwrap();
 
-        let diff = get_diff(path, "test.txt", true).unwrap();
+        let diff = get_diff(path, "test.txt", true, None).unwrap();
 
         assert_eq!(diff.lines, 3);
         assert_eq!(
@@ -117,7 +120,7 @@ b = 3
 c = 4";
 
         let (path, repo) = repo_init().unwrap();
-        let path = path.path().to_str().unwrap();
+        let path: &RepoPath = &path.path().to_str().unwrap().into();
 
         write_commit_file(&repo, "test.txt", FILE_1, "c1");
 
@@ -140,7 +143,7 @@ c = 4";
         )
         .unwrap();
 
-        let diff = get_diff(path, "test.txt", true).unwrap();
+        let diff = get_diff(path, "test.txt", true, None).unwrap();
 
         assert_eq!(diff.lines, 5);
         assert_eq!(
@@ -161,7 +164,7 @@ c = 4";
 ";
 
         let (path, repo) = repo_init().unwrap();
-        let path = path.path().to_str().unwrap();
+        let path: &RepoPath = &path.path().to_str().unwrap().into();
 
         write_commit_file(&repo, "test.txt", FILE_1, "c1");
 
@@ -173,7 +176,8 @@ c = 4";
 
         assert_eq!(get_statuses(path), (0, 1));
 
-        let diff_before = get_diff(path, "test.txt", true).unwrap();
+        let diff_before =
+            get_diff(path, "test.txt", true, None).unwrap();
 
         assert_eq!(diff_before.lines, 5);
 
@@ -190,7 +194,7 @@ c = 4";
 
         assert_eq!(get_statuses(path), (1, 1));
 
-        let diff = get_diff(path, "test.txt", true).unwrap();
+        let diff = get_diff(path, "test.txt", true, None).unwrap();
 
         assert_eq!(diff.lines, 4);
     }
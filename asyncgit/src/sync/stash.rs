@@ -1,13 +1,29 @@
-use super::{utils::repo, CommitId};
+use super::{
+    branch::get_branch_name_repo,
+    checkout_branch, create_branch_at, repo_state,
+    status::{get_status, StatusType},
+    utils::{repo, work_dir},
+    CommitId, InstanceLock, RepoPath, RepoState,
+};
 use crate::error::{Error, Result};
 use git2::{
-    build::CheckoutBuilder, Oid, Repository, StashApplyOptions,
-    StashFlags,
+    build::CheckoutBuilder, Index, IndexEntry, IndexTime, Oid,
+    Repository, StashApplyOptions, StashFlags, Tree,
 };
 use scopetime::scope_time;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+pub(crate) const STASH_REF: &str = "refs/stash";
+const FILE_MODE: u32 = 0o100_644;
+/// holds the autostash kept back while conflicts are resolved, like
+/// `rebase-merge/autostash` of git
+const AUTOSTASH_FILE: &str = "gitui_autostash";
 
 ///
-pub fn get_stashes(repo_path: &str) -> Result<Vec<CommitId>> {
+pub fn get_stashes(repo_path: &RepoPath) -> Result<Vec<CommitId>> {
     scope_time!("get_stashes");
 
     let mut repo = repo(repo_path)?;
@@ -24,17 +40,26 @@ pub fn get_stashes(repo_path: &str) -> Result<Vec<CommitId>> {
 
 /// checks whether a given commit is a stash commit.
 pub fn is_stash_commit(
-    repo_path: &str,
+    repo: &Repository,
     id: &CommitId,
 ) -> Result<bool> {
-    let stashes = get_stashes(repo_path)?;
-    Ok(stashes.contains(id))
+    // the stash list is the reflog of `refs/stash`
+    let reflog = repo.reflog(STASH_REF)?;
+
+    Ok(reflog
+        .iter()
+        .any(|entry| CommitId::from(entry.id_new()) == *id))
 }
 
 ///
-pub fn stash_drop(repo_path: &str, stash_id: CommitId) -> Result<()> {
+pub fn stash_drop(
+    repo_path: &RepoPath,
+    stash_id: CommitId,
+) -> Result<()> {
     scope_time!("stash_drop");
 
+    let _lock = InstanceLock::acquire(repo_path)?;
+
     let mut repo = repo(repo_path)?;
 
     let index = get_stash_index(&mut repo, stash_id.into())?;
@@ -45,9 +70,14 @@ pub fn stash_drop(repo_path: &str, stash_id: CommitId) -> Result<()> {
 }
 
 ///
-pub fn stash_pop(repo_path: &str, stash_id: CommitId) -> Result<()> {
+pub fn stash_pop(
+    repo_path: &RepoPath,
+    stash_id: CommitId,
+) -> Result<()> {
     scope_time!("stash_pop");
 
+    let _lock = InstanceLock::acquire(repo_path)?;
+
     let mut repo = repo(repo_path)?;
 
     let index = get_stash_index(&mut repo, stash_id.into())?;
@@ -59,12 +89,14 @@ pub fn stash_pop(repo_path: &str, stash_id: CommitId) -> Result<()> {
 
 ///
 pub fn stash_apply(
-    repo_path: &str,
+    repo_path: &RepoPath,
     stash_id: CommitId,
     allow_conflicts: bool,
 ) -> Result<()> {
     scope_time!("stash_apply");
 
+    let _lock = InstanceLock::acquire(repo_path)?;
+
     let mut repo = repo(repo_path)?;
 
     let index = get_stash_index(&mut repo, stash_id.get_oid())?;
@@ -79,6 +111,125 @@ pub fn stash_apply(
     Ok(())
 }
 
+/// gives the stash a new message keeping its place in the list.
+/// the list shows the stash commit's message, so that commit is
+/// recreated with the new one
+pub fn stash_rename(
+    repo_path: &RepoPath,
+    stash_id: CommitId,
+    message: &str,
+) -> Result<CommitId> {
+    scope_time!("stash_rename");
+
+    let _lock = InstanceLock::acquire(repo_path)?;
+
+    let repo = repo(repo_path)?;
+
+    let stash = repo.find_commit(stash_id.into())?;
+    let message =
+        renamed_message(stash.message().unwrap_or_default(), message);
+    let parents = stash.parents().collect::<Vec<_>>();
+    let id = repo.commit(
+        None,
+        &stash.author(),
+        &stash.committer(),
+        &message,
+        &stash.tree()?,
+        &parents.iter().collect::<Vec<_>>(),
+    )?;
+
+    let entries = repo
+        .reflog(STASH_REF)?
+        .iter()
+        .map(|entry| {
+            if entry.id_new() == stash.id() {
+                (
+                    id,
+                    entry.committer().to_owned(),
+                    Some(message.clone()),
+                )
+            } else {
+                (
+                    entry.id_new(),
+                    entry.committer().to_owned(),
+                    entry.message().map(String::from),
+                )
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if !entries.iter().any(|(entry, ..)| *entry == id) {
+        return Err(Error::Generic(
+            "stash commit not found".to_string(),
+        ));
+    }
+
+    if entries.first().map(|(entry, ..)| *entry) == Some(id) {
+        repo.reference(STASH_REF, id, true, &message)?;
+    }
+
+    // the reflog is the stash list, rewritten without the entry the
+    // ref update added
+    let mut reflog = repo.reflog(STASH_REF)?;
+    for _ in 0..reflog.len() {
+        reflog.remove(0, false)?;
+    }
+    for (id, committer, message) in entries.iter().rev() {
+        reflog.append(*id, committer, message.as_deref())?;
+    }
+    reflog.write()?;
+
+    Ok(id.into())
+}
+
+/// `git stash` messages are `On <branch>: <message>` (`WIP on ...`
+/// without a message), the new message stays on that branch
+fn renamed_message(old: &str, message: &str) -> String {
+    old.find(": ").map_or_else(
+        || message.to_string(),
+        |idx| {
+            let branch = old[..idx]
+                .trim_start_matches("WIP on ")
+                .trim_start_matches("On ");
+            format!("On {}: {}", branch, message)
+        },
+    )
+}
+
+/// creates `branch` at the commit the stash was made on, checks it
+/// out and pops the stash (index included) there like
+/// `git stash branch`
+pub fn stash_branch(
+    repo_path: &RepoPath,
+    stash_id: CommitId,
+    branch: &str,
+) -> Result<()> {
+    scope_time!("stash_branch");
+
+    let _lock = InstanceLock::acquire(repo_path)?;
+
+    let mut repo = repo(repo_path)?;
+
+    let base = repo.find_commit(stash_id.into())?.parent_id(0)?;
+    create_branch_at(repo_path, branch, base.into())?;
+
+    if let Err(e) =
+        checkout_branch(repo_path, &format!("refs/heads/{}", branch))
+    {
+        repo.find_branch(branch, git2::BranchType::Local)?
+            .delete()?;
+        return Err(e);
+    }
+
+    let index = get_stash_index(&mut repo, stash_id.into())?;
+
+    let mut opt = StashApplyOptions::default();
+    opt.reinstantiate_index();
+    repo.stash_pop(index, Some(&mut opt))?;
+
+    Ok(())
+}
+
 fn get_stash_index(
     repo: &mut Repository,
     stash_id: Oid,
@@ -101,13 +252,16 @@ fn get_stash_index(
 
 ///
 pub fn stash_save(
-    repo_path: &str,
+    repo_path: &RepoPath,
     message: Option<&str>,
     include_untracked: bool,
+    include_ignored: bool,
     keep_index: bool,
 ) -> Result<CommitId> {
     scope_time!("stash_save");
 
+    let _lock = InstanceLock::acquire(repo_path)?;
+
     let mut repo = repo(repo_path)?;
 
     let sig = repo.signature()?;
@@ -117,6 +271,9 @@ pub fn stash_save(
     if include_untracked {
         options.insert(StashFlags::INCLUDE_UNTRACKED);
     }
+    if include_ignored {
+        options.insert(StashFlags::INCLUDE_IGNORED);
+    }
     if keep_index {
         options.insert(StashFlags::KEEP_INDEX)
     }
@@ -126,11 +283,237 @@ pub fn stash_save(
     Ok(CommitId::new(id))
 }
 
+/// runs `f` with the uncommitted changes stashed away and applies
+/// them again afterwards, like `rebase.autoStash` does (untracked
+/// files included, they would block a checkout as well).
+/// if `f` leaves conflicts behind the changes stay in the stash and
+/// are recorded as pending, continuing or aborting the operation
+/// applies them (see `apply_pending_autostash`). if they do not apply
+/// cleanly anymore they stay in the stash. `enabled: false` just
+/// runs `f`
+pub fn autostash<T, F>(
+    repo_path: &RepoPath,
+    enabled: bool,
+    f: F,
+) -> Result<T>
+where
+    F: FnOnce() -> Result<T>,
+{
+    scope_time!("autostash");
+
+    if !enabled
+        || get_status(repo_path, StatusType::Both, true)?.is_empty()
+    {
+        return f();
+    }
+
+    let id =
+        stash_save(repo_path, Some("autostash"), true, false, false)?;
+
+    let res = f();
+
+    if repo_state(repo_path)? != RepoState::Clean {
+        fs::write(autostash_file(repo_path)?, id.to_string())?;
+
+        return res;
+    }
+
+    let applied = pop_autostash(repo_path, id);
+
+    res.and_then(|value| applied.map(|_| value))
+}
+
+/// the autostash waiting for the conflicts of the operation run in
+/// `autostash` to be resolved
+pub fn autostash_pending(
+    repo_path: &RepoPath,
+) -> Result<Option<CommitId>> {
+    let file = autostash_file(repo_path)?;
+
+    if !file.exists() {
+        return Ok(None);
+    }
+
+    let id = Oid::from_str(fs::read_to_string(file)?.trim())?;
+
+    Ok(Some(id.into()))
+}
+
+/// applies the pending autostash once the operation that stopped on
+/// conflicts was finished or aborted, returns whether there was one
+pub fn apply_pending_autostash(repo_path: &RepoPath) -> Result<bool> {
+    scope_time!("apply_pending_autostash");
+
+    let _lock = InstanceLock::acquire(repo_path)?;
+
+    if repo_state(repo_path)? != RepoState::Clean {
+        return Ok(false);
+    }
+
+    let id = match autostash_pending(repo_path)? {
+        Some(id) => id,
+        None => return Ok(false),
+    };
+
+    fs::remove_file(autostash_file(repo_path)?)?;
+
+    pop_autostash(repo_path, id)?;
+
+    Ok(true)
+}
+
+fn autostash_file(repo_path: &RepoPath) -> Result<PathBuf> {
+    Ok(repo(repo_path)?.path().join(AUTOSTASH_FILE))
+}
+
+fn pop_autostash(repo_path: &RepoPath, id: CommitId) -> Result<()> {
+    stash_pop(repo_path, id).map_err(|e| {
+        Error::Generic(format!(
+            "applying the autostash failed, the changes are kept in the stash: {}",
+            e
+        ))
+    })
+}
+
+/// stashes only the changes (staged and unstaged) of `paths` like
+/// `git stash push -- <paths>` does, all other changes stay.
+/// libgit2 cannot limit a stash to paths, so the stash commits are
+/// built here: the index commit holds the staged state of `paths` on
+/// top of `HEAD`, the stash commit their worktree state (untracked
+/// files among `paths` included)
+pub fn stash_save_paths(
+    repo_path: &RepoPath,
+    message: Option<&str>,
+    paths: &[String],
+) -> Result<CommitId> {
+    scope_time!("stash_save_paths");
+
+    let _lock = InstanceLock::acquire(repo_path)?;
+
+    let repo = repo(repo_path)?;
+    let sig = repo.signature()?;
+    let head = repo.head()?.peel_to_commit()?;
+    let head_tree = head.tree()?;
+
+    let (index_tree, work_tree) =
+        stash_trees(&repo, &head_tree, paths)?;
+
+    if index_tree.id() == head_tree.id()
+        && work_tree.id() == head_tree.id()
+    {
+        return Err(Error::Generic(
+            "no local changes to stash".to_string(),
+        ));
+    }
+
+    // same messages `git stash` uses
+    let branch = get_branch_name_repo(&repo)
+        .unwrap_or_else(|_| String::from("(no branch)"));
+    let head_info = format!(
+        "{}: {} {}",
+        branch,
+        CommitId::new(head.id()).get_short_string(),
+        head.summary().unwrap_or_default()
+    );
+    let message = message.map_or_else(
+        || format!("WIP on {}", head_info),
+        |message| format!("On {}: {}", branch, message),
+    );
+
+    let index_commit = repo.find_commit(repo.commit(
+        None,
+        &sig,
+        &sig,
+        &format!("index on {}", head_info),
+        &index_tree,
+        &[&head],
+    )?)?;
+    let id = repo.commit(
+        None,
+        &sig,
+        &sig,
+        &message,
+        &work_tree,
+        &[&head, &index_commit],
+    )?;
+
+    // `stash_foreach` lists the reflog entries
+    repo.reference_ensure_log(STASH_REF)?;
+    repo.reference(STASH_REF, id, true, &message)?;
+
+    // remove what got stashed from index and worktree
+    let mut checkout_opts = CheckoutBuilder::new();
+    checkout_opts.force().remove_untracked(true);
+    for path in paths {
+        checkout_opts.path(path);
+    }
+    repo.checkout_tree(
+        head_tree.as_object(),
+        Some(&mut checkout_opts),
+    )?;
+
+    Ok(CommitId::new(id))
+}
+
+/// trees with the staged and the worktree state of `paths` on top
+/// of `head_tree`
+fn stash_trees<'a>(
+    repo: &'a Repository,
+    head_tree: &Tree,
+    paths: &[String],
+) -> Result<(Tree<'a>, Tree<'a>)> {
+    let work_dir = work_dir(repo)?;
+    let index = repo.index()?;
+    let mut stash_index = Index::new()?;
+    stash_index.read_tree(head_tree)?;
+
+    for path in paths {
+        let path = Path::new(path);
+        if let Some(entry) = index.get_path(path, 0) {
+            stash_index.add(&entry)?;
+        } else {
+            stash_index.remove_path(path)?;
+        }
+    }
+    let index_tree =
+        repo.find_tree(stash_index.write_tree_to(repo)?)?;
+
+    for path in paths {
+        let full_path = work_dir.join(path);
+        if full_path.is_file() {
+            let mode = stash_index
+                .get_path(Path::new(path), 0)
+                .map_or(FILE_MODE, |entry| entry.mode);
+            stash_index.add(&IndexEntry {
+                ctime: IndexTime::new(0, 0),
+                mtime: IndexTime::new(0, 0),
+                dev: 0,
+                ino: 0,
+                mode,
+                uid: 0,
+                gid: 0,
+                file_size: 0,
+                id: repo.blob_path(&full_path)?,
+                flags: 0,
+                flags_extended: 0,
+                path: path.as_bytes().to_vec(),
+            })?;
+        } else {
+            stash_index.remove_path(Path::new(path))?;
+        }
+    }
+    let work_tree =
+        repo.find_tree(stash_index.write_tree_to(repo)?)?;
+
+    Ok((index_tree, work_tree))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::sync::{
-        commit, get_commit_files, get_commits_info, stage_add_file,
+        abort_merge, commit, get_commit_files, get_commits_info,
+        stage_add_file,
         tests::{
             debug_cmd_print, get_statuses, repo_init,
             write_commit_file,
@@ -143,10 +526,11 @@ mod tests {
     fn test_smoke() {
         let (_td, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         assert_eq!(
-            stash_save(repo_path, None, true, false).is_ok(),
+            stash_save(repo_path, None, true, false, false).is_ok(),
             false
         );
 
@@ -157,30 +541,128 @@ mod tests {
     fn test_stashing() -> Result<()> {
         let (_td, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         File::create(&root.join("foo.txt"))?
             .write_all(b"test\nfoo")?;
 
         assert_eq!(get_statuses(repo_path), (1, 0));
 
-        stash_save(repo_path, None, true, false)?;
+        stash_save(repo_path, None, true, false, false)?;
 
         assert_eq!(get_statuses(repo_path), (0, 0));
 
         Ok(())
     }
 
+    #[test]
+    fn test_autostash() -> Result<()> {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        write_commit_file(&repo, "foo.txt", "a", "commit1");
+        File::create(&root.join("foo.txt"))?.write_all(b"b")?;
+
+        let statuses = autostash(repo_path, true, || {
+            Ok(get_statuses(repo_path))
+        })?;
+
+        assert_eq!(statuses, (0, 0));
+        assert_eq!(repo_read_file(&repo, "foo.txt")?, "b");
+        assert!(get_stashes(repo_path)?.is_empty());
+
+        // a failing operation gets the changes back too
+        let res: Result<()> = autostash(repo_path, true, || {
+            Err(Error::Generic("failed".into()))
+        });
+
+        assert!(res.is_err());
+        assert_eq!(repo_read_file(&repo, "foo.txt")?, "b");
+        assert!(get_stashes(repo_path)?.is_empty());
+
+        let statuses = autostash(repo_path, false, || {
+            Ok(get_statuses(repo_path))
+        })?;
+
+        assert_eq!(statuses, (1, 0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_autostash_pending() -> Result<()> {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        let head =
+            write_commit_file(&repo, "foo.txt", "a", "commit1");
+        File::create(&root.join("foo.txt"))?.write_all(b"b")?;
+
+        // stopping on conflicts of a merge
+        autostash(repo_path, true, || {
+            fs::write(
+                repo.path().join("MERGE_HEAD"),
+                head.to_string(),
+            )?;
+            Ok(())
+        })?;
+
+        let pending = autostash_pending(repo_path)?;
+
+        assert!(pending.is_some());
+        assert_eq!(get_stashes(repo_path)?, vec![pending.unwrap()]);
+        assert_eq!(repo_read_file(&repo, "foo.txt")?, "a");
+        assert!(!apply_pending_autostash(repo_path)?);
+
+        abort_merge(repo_path)?;
+
+        assert_eq!(autostash_pending(repo_path)?, None);
+        assert!(get_stashes(repo_path)?.is_empty());
+        assert_eq!(repo_read_file(&repo, "foo.txt")?, "b");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_stash_ignored() -> Result<()> {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        write_commit_file(&repo, ".gitignore", "*.log", "ignore");
+        File::create(&root.join("foo.log"))?.write_all(b"log")?;
+        File::create(&root.join("foo.txt"))?.write_all(b"foo")?;
+
+        let id = stash_save(repo_path, None, false, true, false)?;
+
+        // `foo.txt` is untracked but not ignored
+        assert!(!root.join("foo.log").exists());
+        assert!(root.join("foo.txt").exists());
+
+        stash_pop(repo_path, id)?;
+
+        assert_eq!(repo_read_file(&repo, "foo.log")?, "log");
+
+        Ok(())
+    }
+
     #[test]
     fn test_stashes() -> Result<()> {
         let (_td, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         File::create(&root.join("foo.txt"))?
             .write_all(b"test\nfoo")?;
 
-        stash_save(repo_path, Some("foo"), true, false)?;
+        stash_save(repo_path, Some("foo"), true, false, false)?;
 
         let res = get_stashes(repo_path)?;
 
@@ -194,18 +676,73 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_stash_paths() -> Result<()> {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        write_commit_file(&repo, "a.txt", "a", "c1");
+        write_commit_file(&repo, "b.txt", "b", "c2");
+
+        repo_write_file(&repo, "a.txt", "a\nstaged")?;
+        stage_add_file(repo_path, Path::new("a.txt"))?;
+        repo_write_file(&repo, "a.txt", "a\nstaged\nunstaged")?;
+        repo_write_file(&repo, "b.txt", "b\nchanged")?;
+        repo_write_file(&repo, "new.txt", "new")?;
+
+        let paths =
+            vec![String::from("a.txt"), String::from("new.txt")];
+        stash_save_paths(repo_path, Some("foo"), &paths)?;
+
+        // only `b.txt` is left
+        assert_eq!(get_statuses(repo_path), (1, 0));
+        assert_eq!(repo_read_file(&repo, "a.txt")?, "a");
+        assert!(!root.join("new.txt").exists());
+
+        let stashes = get_stashes(repo_path)?;
+        assert_eq!(stashes.len(), 1);
+        let infos = get_commits_info(repo_path, &stashes, 100)?;
+        assert_eq!(infos[0].message, "On master: foo");
+
+        stash_apply(repo_path, stashes[0], false)?;
+
+        assert_eq!(
+            repo_read_file(&repo, "a.txt")?,
+            "a\nstaged\nunstaged"
+        );
+        assert_eq!(repo_read_file(&repo, "new.txt")?, "new");
+
+        // nothing to stash in there
+        assert!(stash_save_paths(
+            repo_path,
+            None,
+            &[String::from("c.txt")]
+        )
+        .is_err());
+
+        Ok(())
+    }
+
     #[test]
     fn test_stash_nothing_untracked() -> Result<()> {
         let (_td, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         File::create(&root.join("foo.txt"))?
             .write_all(b"test\nfoo")?;
 
-        assert!(
-            stash_save(repo_path, Some("foo"), false, false).is_err()
-        );
+        assert!(stash_save(
+            repo_path,
+            Some("foo"),
+            false,
+            false,
+            false
+        )
+        .is_err());
 
         Ok(())
     }
@@ -215,7 +752,8 @@ mod tests {
         let file_path1 = Path::new("file1.txt");
         let (_td, repo) = repo_init()?;
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         File::create(&root.join(file_path1))?.write_all(b"test")?;
         stage_add_file(repo_path, file_path1)?;
@@ -242,12 +780,14 @@ mod tests {
     fn test_stash_apply_conflict() {
         let (_td, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         repo_write_file(&repo, "test.txt", "test").unwrap();
 
         let id =
-            stash_save(repo_path, Some("foo"), true, false).unwrap();
+            stash_save(repo_path, Some("foo"), true, false, false)
+                .unwrap();
 
         repo_write_file(&repo, "test.txt", "foo").unwrap();
 
@@ -260,14 +800,16 @@ mod tests {
     fn test_stash_apply_conflict2() {
         let (_td, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         write_commit_file(&repo, "test.txt", "test", "c1");
 
         repo_write_file(&repo, "test.txt", "test2").unwrap();
 
         let id =
-            stash_save(repo_path, Some("foo"), true, false).unwrap();
+            stash_save(repo_path, Some("foo"), true, false, false)
+                .unwrap();
 
         repo_write_file(&repo, "test.txt", "test3").unwrap();
 
@@ -280,14 +822,16 @@ mod tests {
     fn test_stash_apply_creating_conflict() {
         let (_td, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         write_commit_file(&repo, "test.txt", "test", "c1");
 
         repo_write_file(&repo, "test.txt", "test2").unwrap();
 
         let id =
-            stash_save(repo_path, Some("foo"), true, false).unwrap();
+            stash_save(repo_path, Some("foo"), true, false, false)
+                .unwrap();
 
         repo_write_file(&repo, "test.txt", "test3").unwrap();
 
@@ -304,14 +848,16 @@ mod tests {
     fn test_stash_pop_no_conflict() {
         let (_td, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         write_commit_file(&repo, "test.txt", "test", "c1");
 
         repo_write_file(&repo, "test.txt", "test2").unwrap();
 
         let id =
-            stash_save(repo_path, Some("foo"), true, false).unwrap();
+            stash_save(repo_path, Some("foo"), true, false, false)
+                .unwrap();
 
         let res = stash_pop(repo_path, id);
 
@@ -326,12 +872,14 @@ mod tests {
     fn test_stash_pop_conflict() {
         let (_td, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         repo_write_file(&repo, "test.txt", "test").unwrap();
 
         let id =
-            stash_save(repo_path, Some("foo"), true, false).unwrap();
+            stash_save(repo_path, Some("foo"), true, false, false)
+                .unwrap();
 
         repo_write_file(&repo, "test.txt", "test2").unwrap();
 
@@ -348,14 +896,16 @@ mod tests {
     fn test_stash_pop_conflict_after_commit() {
         let (_td, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         write_commit_file(&repo, "test.txt", "test", "c1");
 
         repo_write_file(&repo, "test.txt", "test2").unwrap();
 
         let id =
-            stash_save(repo_path, Some("foo"), true, false).unwrap();
+            stash_save(repo_path, Some("foo"), true, false, false)
+                .unwrap();
 
         repo_write_file(&repo, "test.txt", "test3").unwrap();
 
@@ -367,4 +917,69 @@ mod tests {
             "test3"
         );
     }
+
+    #[test]
+    fn test_stash_rename() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        repo_write_file(&repo, "test.txt", "foo").unwrap();
+        let first =
+            stash_save(repo_path, Some("foo"), true, false, false)
+                .unwrap();
+        repo_write_file(&repo, "test.txt", "bar").unwrap();
+        let second =
+            stash_save(repo_path, Some("bar"), true, false, false)
+                .unwrap();
+
+        let renamed = stash_rename(repo_path, first, "baz").unwrap();
+
+        assert_eq!(
+            get_stashes(repo_path).unwrap(),
+            vec![second, renamed]
+        );
+        let infos =
+            get_commits_info(repo_path, &[renamed], 100).unwrap();
+        assert_eq!(infos[0].message, "On master: baz");
+
+        // the newest one moves the ref as well
+        let renamed = stash_rename(repo_path, second, "qux").unwrap();
+        assert_eq!(get_stashes(repo_path).unwrap()[0], renamed);
+
+        stash_apply(repo_path, renamed, false).unwrap();
+        assert_eq!(repo_read_file(&repo, "test.txt").unwrap(), "bar");
+    }
+
+    #[test]
+    fn test_stash_branch() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        let base = write_commit_file(&repo, "test.txt", "test", "c1");
+        repo_write_file(&repo, "test.txt", "test2").unwrap();
+        stage_add_file(repo_path, Path::new("test.txt")).unwrap();
+        let id =
+            stash_save(repo_path, Some("foo"), true, false, false)
+                .unwrap();
+
+        write_commit_file(&repo, "test.txt", "test3", "c2");
+
+        stash_branch(repo_path, id, "foo").unwrap();
+
+        assert_eq!(repo.head().unwrap().shorthand().unwrap(), "foo");
+        assert_eq!(
+            CommitId::new(repo.head().unwrap().target().unwrap()),
+            base
+        );
+        assert_eq!(
+            repo_read_file(&repo, "test.txt").unwrap(),
+            "test2"
+        );
+        assert_eq!(get_statuses(repo_path), (0, 1));
+        assert!(get_stashes(repo_path).unwrap().is_empty());
+    }
 }
@@ -1,4 +1,7 @@
-use crate::{error::Result, sync::utils};
+use crate::{
+    error::Result,
+    sync::{utils, RepoPath},
+};
 use git2::RepositoryState;
 use scopetime::scope_time;
 
@@ -10,6 +13,8 @@ pub enum RepoState {
     ///
     Merge,
     ///
+    Rebase,
+    ///
     Other,
 }
 
@@ -18,13 +23,16 @@ impl From<RepositoryState> for RepoState {
         match state {
             RepositoryState::Clean => Self::Clean,
             RepositoryState::Merge => Self::Merge,
+            RepositoryState::Rebase
+            | RepositoryState::RebaseInteractive
+            | RepositoryState::RebaseMerge => Self::Rebase,
             _ => Self::Other,
         }
     }
 }
 
 ///
-pub fn repo_state(repo_path: &str) -> Result<RepoState> {
+pub fn repo_state(repo_path: &RepoPath) -> Result<RepoState> {
     scope_time!("repo_state");
 
     let repo = utils::repo(repo_path)?;
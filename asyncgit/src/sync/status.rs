@@ -1,9 +1,26 @@
 //! sync git api for fetching a status
 
-use crate::{error::Error, error::Result, sync::utils};
-use git2::{Delta, Status, StatusOptions, StatusShow};
+use crate::{
+    error::Error,
+    error::Result,
+    sync::{
+        index_flags::index_flags,
+        submodules::{submodule_states, SubmoduleState},
+        utils, CommitId, InstanceLock, RepoPath,
+    },
+};
+use git2::{
+    Delta, DiffOptions, IndexEntry, Repository, Status,
+    StatusOptions, StatusShow,
+};
 use scopetime::scope_time;
-use std::path::Path;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    convert::TryFrom,
+    fs,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 ///
 #[derive(Copy, Clone, Hash, PartialEq, Debug)]
@@ -43,7 +60,7 @@ impl From<Status> for StatusItemType {
 impl From<Delta> for StatusItemType {
     fn from(d: Delta) -> Self {
         match d {
-            Delta::Added => Self::New,
+            Delta::Added | Delta::Untracked => Self::New,
             Delta::Deleted => Self::Deleted,
             Delta::Renamed => Self::Renamed,
             Delta::Typechange => Self::Typechange,
@@ -59,6 +76,14 @@ pub struct StatusItem {
     pub path: String,
     ///
     pub status: StatusItemType,
+    /// working copy state if the item is a submodule (workdir only)
+    pub submodule: Option<SubmoduleState>,
+    /// the item is an untracked repository nested in the worktree
+    /// (not a submodule), its content is not listed
+    pub nested_repo: bool,
+    /// the item is a top-level folder with changes found by
+    /// `get_status_shallow`, its files are not listed
+    pub summarized: bool,
 }
 
 ///
@@ -70,6 +95,9 @@ pub enum StatusType {
     Stage,
     ///
     Both,
+    /// workdir (including staged changes) compared against the tree
+    /// of a commit instead of `HEAD`
+    Base(CommitId),
 }
 
 impl Default for StatusType {
@@ -83,29 +111,74 @@ impl From<StatusType> for StatusShow {
         match s {
             StatusType::WorkingDir => Self::Workdir,
             StatusType::Stage => Self::Index,
-            StatusType::Both => Self::IndexAndWorkdir,
+            StatusType::Both | StatusType::Base(_) => {
+                Self::IndexAndWorkdir
+            }
         }
     }
 }
 
 ///
 pub fn get_status(
-    repo_path: &str,
+    repo_path: &RepoPath,
     status_type: StatusType,
     include_untracked: bool,
+) -> Result<Vec<StatusItem>> {
+    get_status_scoped(repo_path, status_type, include_untracked, None)
+}
+
+/// like `get_status` but only looks at the files inside of the
+/// `scope` folder (relative to the workdir), which is a lot faster in
+/// big monorepos
+pub fn get_status_scoped(
+    repo_path: &RepoPath,
+    status_type: StatusType,
+    include_untracked: bool,
+    scope: Option<&str>,
 ) -> Result<Vec<StatusItem>> {
     scope_time!("get_status");
 
     let repo = utils::repo(repo_path)?;
 
-    let statuses = repo.statuses(Some(
-        StatusOptions::default()
-            .show(status_type.into())
-            .update_index(true)
-            .include_untracked(include_untracked)
-            .renames_head_to_index(true)
-            .recurse_untracked_dirs(true),
-    ))?;
+    if let StatusType::Base(base) = status_type {
+        return get_status_base(
+            &repo,
+            base,
+            include_untracked,
+            scope,
+        );
+    }
+
+    // refreshing the stat data in the index is a write, skipped while
+    // another instance changes the repository
+    let lock = InstanceLock::acquire_silent(repo_path).ok();
+
+    let mut options = StatusOptions::default();
+    options
+        .show(status_type.into())
+        .update_index(lock.is_some())
+        .include_untracked(include_untracked)
+        .renames_head_to_index(true)
+        .recurse_untracked_dirs(true);
+    if let Some(scope) = scope {
+        options.pathspec(scope);
+    }
+
+    let statuses = repo.statuses(Some(&mut options))?;
+    drop(lock);
+
+    let submodules = if status_type == StatusType::Stage {
+        HashMap::new()
+    } else {
+        submodule_states(&repo)
+    };
+
+    // local changes git is told to ignore
+    let flagged = if status_type == StatusType::Stage {
+        HashMap::new()
+    } else {
+        index_flags(&repo.index()?)
+    };
 
     let mut res = Vec::with_capacity(statuses.len());
 
@@ -132,9 +205,25 @@ pub fn get_status(
             })?,
         };
 
+        // libgit2 does not recurse into nested repositories and
+        // reports them as a single untracked directory instead
+        let (path, nested_repo) = match path.strip_suffix('/') {
+            Some(dir) if utils::is_nested_repo(&repo, dir) => {
+                (dir.to_string(), true)
+            }
+            _ => (path, false),
+        };
+
+        if flagged.contains_key(&path) && !is_index_change(status) {
+            continue;
+        }
+
         res.push(StatusItem {
+            submodule: submodules.get(&path).copied(),
             path,
             status: StatusItemType::from(status),
+            nested_repo,
+            summarized: false,
         });
     }
 
@@ -144,3 +233,287 @@ pub fn get_status(
 
     Ok(res)
 }
+
+/// a quick status for gigantic worktrees: one item per top-level
+/// folder or file with changes, told from the stat data kept in the
+/// index (size and mtime) and the mtimes of the folders (for new and
+/// removed files) without reading any file. files touched but not
+/// changed count as well, `get_status_scoped` on such a folder tells
+/// for sure
+pub fn get_status_shallow(
+    repo_path: &RepoPath,
+) -> Result<Vec<StatusItem>> {
+    scope_time!("get_status_shallow");
+
+    let repo = utils::repo(repo_path)?;
+    let work_dir = utils::work_dir(&repo)?;
+    let index = repo.index()?;
+
+    // folders changed after the index was written got files created
+    // or removed
+    let index_time = modified(&repo.path().join("index"));
+
+    // top-level path -> (status, is a folder)
+    let mut changes: BTreeMap<String, (StatusItemType, bool)> =
+        BTreeMap::new();
+    let mut tracked = HashSet::new();
+    let mut folders = HashSet::new();
+
+    for entry in index.iter() {
+        let path = String::from_utf8_lossy(&entry.path).to_string();
+        let (top, is_folder) = match path.find('/') {
+            Some(idx) => (path[..idx].to_string(), true),
+            None => (path.clone(), false),
+        };
+
+        tracked.insert(top.clone());
+        if changes.contains_key(&top) {
+            continue;
+        }
+
+        if let Some(status) =
+            entry_change(&entry, &work_dir.join(&path))
+        {
+            let status = if is_folder {
+                StatusItemType::Modified
+            } else {
+                status
+            };
+            changes.insert(top, (status, is_folder));
+        } else if let Some(parent) = Path::new(&path).parent() {
+            if is_folder {
+                folders.insert(parent.to_path_buf());
+            }
+        }
+    }
+
+    for folder in folders {
+        let top = folder
+            .components()
+            .next()
+            .map(|top| top.as_os_str().to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        if !changes.contains_key(&top)
+            && modified(&work_dir.join(&folder)) > index_time
+        {
+            changes.insert(top, (StatusItemType::Modified, true));
+        }
+    }
+
+    for entry in fs::read_dir(work_dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        let is_folder = entry.path().is_dir();
+        let ignore_path = if is_folder {
+            format!("{}/", name)
+        } else {
+            name.clone()
+        };
+
+        if name != ".git"
+            && !tracked.contains(&name)
+            && !repo.is_path_ignored(&ignore_path).unwrap_or_default()
+        {
+            changes.insert(name, (StatusItemType::New, is_folder));
+        }
+    }
+
+    Ok(changes
+        .into_iter()
+        .map(|(path, (status, is_folder))| StatusItem {
+            path,
+            status,
+            submodule: None,
+            nested_repo: false,
+            summarized: is_folder,
+        })
+        .collect())
+}
+
+/// the change the stat data of the index tells about, `None` for
+/// entries without any or the ones git is told to ignore
+fn entry_change(
+    entry: &IndexEntry,
+    path: &Path,
+) -> Option<StatusItemType> {
+    const MODE_GITLINK: u32 = 0o160_000;
+    const FLAG_STAGE: u16 = 0x3000;
+    const FLAG_ASSUME_VALID: u16 = 0x8000;
+    const FLAG_EXTENDED_SKIP_WORKTREE: u16 = 0x4000;
+
+    if entry.mode == MODE_GITLINK
+        || entry.flags & FLAG_ASSUME_VALID != 0
+        || entry.flags_extended & FLAG_EXTENDED_SKIP_WORKTREE != 0
+    {
+        return None;
+    }
+
+    if entry.flags & FLAG_STAGE != 0 {
+        return Some(StatusItemType::Conflicted);
+    }
+
+    let meta = match fs::symlink_metadata(path) {
+        Ok(meta) => meta,
+        Err(_) => return Some(StatusItemType::Deleted),
+    };
+
+    // the index keeps the lower 32 bits of the size only
+    let size_changed = meta.len() & u64::from(u32::MAX)
+        != u64::from(entry.file_size);
+    let mtime = meta
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|time| time.as_secs());
+    let mtime_changed =
+        mtime != u64::try_from(entry.mtime.seconds()).ok();
+
+    if size_changed || mtime_changed {
+        Some(StatusItemType::Modified)
+    } else {
+        None
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+fn is_index_change(status: Status) -> bool {
+    status.intersects(
+        Status::INDEX_NEW
+            | Status::INDEX_MODIFIED
+            | Status::INDEX_DELETED
+            | Status::INDEX_RENAMED
+            | Status::INDEX_TYPECHANGE,
+    )
+}
+
+/// files changed between the tree of `base` and the workdir
+fn get_status_base(
+    repo: &Repository,
+    base: CommitId,
+    include_untracked: bool,
+    scope: Option<&str>,
+) -> Result<Vec<StatusItem>> {
+    scope_time!("get_status_base");
+
+    let tree = repo.find_commit(base.into())?.tree()?;
+
+    let mut opt = DiffOptions::new();
+    opt.include_untracked(include_untracked)
+        .recurse_untracked_dirs(true);
+    if let Some(scope) = scope {
+        opt.pathspec(scope);
+    }
+
+    let mut diff = repo.diff_tree_to_workdir_with_index(
+        Some(&tree),
+        Some(&mut opt),
+    )?;
+    diff.find_similar(None)?;
+
+    let submodules = submodule_states(repo);
+
+    let mut res = diff
+        .deltas()
+        .filter_map(|delta| {
+            let path = delta
+                .new_file()
+                .path()
+                .or_else(|| delta.old_file().path())?
+                .to_str()?
+                .to_string();
+
+            Some(StatusItem {
+                submodule: submodules.get(&path).copied(),
+                path,
+                status: StatusItemType::from(delta.status()),
+                nested_repo: false,
+                summarized: false,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    res.sort_by(|a, b| {
+        Path::new(a.path.as_str()).cmp(Path::new(b.path.as_str()))
+    });
+
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::{
+        stage_add_file,
+        tests::{repo_init, write_commit_file},
+        utils::repo_write_file,
+    };
+    use std::fs;
+
+    #[test]
+    fn test_scoped() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        fs::create_dir_all(root.join("a")).unwrap();
+        repo_write_file(&repo, "a/foo.txt", "foo").unwrap();
+        repo_write_file(&repo, "b.txt", "b").unwrap();
+
+        let paths = |status_type| {
+            get_status_scoped(repo_path, status_type, true, Some("a"))
+                .unwrap()
+                .into_iter()
+                .map(|item| item.path)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(paths(StatusType::WorkingDir), vec!["a/foo.txt"]);
+
+        stage_add_file(repo_path, Path::new("a/foo.txt")).unwrap();
+        stage_add_file(repo_path, Path::new("b.txt")).unwrap();
+
+        assert_eq!(paths(StatusType::Stage), vec!["a/foo.txt"]);
+        assert_eq!(
+            get_status(repo_path, StatusType::Stage, true)
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_shallow() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        fs::create_dir_all(root.join("a")).unwrap();
+        write_commit_file(&repo, "a/foo.txt", "foo", "commit1");
+        write_commit_file(&repo, "b.txt", "b", "commit2");
+
+        assert!(get_status_shallow(repo_path).unwrap().is_empty());
+
+        repo_write_file(&repo, "a/foo.txt", "changed").unwrap();
+        repo_write_file(&repo, "c.txt", "c").unwrap();
+
+        let items = get_status_shallow(repo_path)
+            .unwrap()
+            .into_iter()
+            .map(|item| (item.path, item.status, item.summarized))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            items,
+            vec![
+                (String::from("a"), StatusItemType::Modified, true),
+                (String::from("c.txt"), StatusItemType::New, false),
+            ]
+        );
+    }
+}
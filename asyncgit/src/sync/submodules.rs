@@ -0,0 +1,355 @@
+//! submodule helpers
+
+use super::{utils::repo, CommitId, InstanceLock, RepoPath};
+use crate::error::{Error, Result};
+use git2::{
+    DiffDelta, FileMode, Oid, Repository, Sort, SubmoduleIgnore,
+    SubmoduleStatus,
+};
+use scopetime::scope_time;
+use std::{collections::HashMap, path::Path};
+
+/// upper bound of commits listed in a `SubmoduleLog`
+const MAX_LOG_COMMITS: usize = 100;
+
+/// single commit in the log of a submodule
+#[derive(Clone, Hash, Debug, PartialEq)]
+pub struct SubmoduleCommit {
+    ///
+    pub id: CommitId,
+    /// first line of the commit message
+    pub summary: String,
+    ///
+    pub author: String,
+}
+
+/// commits of a submodule between its old and new pointer
+#[derive(Default, Clone, Hash, Debug, PartialEq)]
+pub struct SubmoduleLog {
+    /// path of the submodule in the superproject
+    pub path: String,
+    /// pointer before the change, `None` if the submodule was added
+    pub old: Option<CommitId>,
+    /// pointer after the change, `None` if the submodule was removed
+    pub new: Option<CommitId>,
+    /// commits between `old` and `new` (newest first), `None` if the
+    /// submodule is not checked out or lacks one of the commits
+    pub commits: Option<Vec<SubmoduleCommit>>,
+    /// the pointer moved backwards, `commits` are the dropped ones
+    pub rewind: bool,
+    /// there are more commits than listed in `commits`
+    pub truncated: bool,
+}
+
+/// state of the working copy of a submodule
+#[derive(Copy, Clone, Default, Hash, PartialEq, Debug)]
+pub struct SubmoduleState {
+    /// checked out commit differs from the recorded pointer
+    pub new_commits: bool,
+    /// tracked files inside the submodule are modified
+    pub modified_content: bool,
+    /// the submodule contains untracked files
+    pub untracked_content: bool,
+    /// the submodule is not checked out
+    pub uninitialized: bool,
+}
+
+impl From<SubmoduleStatus> for SubmoduleState {
+    fn from(status: SubmoduleStatus) -> Self {
+        Self {
+            new_commits: status.is_wd_modified(),
+            modified_content: status.is_wd_index_modified()
+                || status.is_wd_wd_modified(),
+            untracked_content: status.is_wd_untracked(),
+            uninitialized: status.is_wd_uninitialized(),
+        }
+    }
+}
+
+/// states of all submodules of `repo` by their path, submodules
+/// that fail to load are skipped
+pub(crate) fn submodule_states(
+    repo: &Repository,
+) -> HashMap<String, SubmoduleState> {
+    repo.submodules()
+        .map(|submodules| {
+            submodules
+                .iter()
+                .filter_map(|submodule| {
+                    let status = repo
+                        .submodule_status(
+                            submodule.name()?,
+                            SubmoduleIgnore::None,
+                        )
+                        .ok()?;
+
+                    Some((
+                        submodule
+                            .path()
+                            .to_string_lossy()
+                            .to_string(),
+                        SubmoduleState::from(status),
+                    ))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// checks out the commit recorded in the superproject, initializes
+/// and clones the submodule first if needed
+pub fn update_submodule(
+    repo_path: &RepoPath,
+    path: &str,
+) -> Result<()> {
+    scope_time!("update_submodule");
+
+    let _lock = InstanceLock::acquire(repo_path)?;
+
+    let repo = repo(repo_path)?;
+
+    let mut submodule = repo
+        .submodules()?
+        .into_iter()
+        .find(|submodule| submodule.path() == Path::new(path))
+        .ok_or_else(|| {
+            Error::Generic(format!("submodule not found: {}", path))
+        })?;
+
+    submodule.update(true, None)?;
+
+    Ok(())
+}
+
+fn non_zero(id: Oid) -> Option<CommitId> {
+    if id.is_zero() {
+        None
+    } else {
+        Some(id.into())
+    }
+}
+
+/// returns the log of the submodule changed by `delta` or `None` if
+/// `delta` is no submodule pointer change
+pub(crate) fn submodule_log(
+    work_dir: &Path,
+    delta: &DiffDelta,
+) -> Option<SubmoduleLog> {
+    if delta.old_file().mode() != FileMode::Commit
+        && delta.new_file().mode() != FileMode::Commit
+    {
+        return None;
+    }
+
+    let path = delta
+        .new_file()
+        .path()
+        .or_else(|| delta.old_file().path())?;
+
+    let mut log = SubmoduleLog {
+        path: path.to_string_lossy().to_string(),
+        old: non_zero(delta.old_file().id()),
+        new: non_zero(delta.new_file().id()),
+        ..SubmoduleLog::default()
+    };
+
+    if let Ok(repo) = Repository::open(work_dir.join(path)) {
+        if let Ok((commits, rewind, truncated)) =
+            log_between(&repo, log.old, log.new)
+        {
+            log.commits = Some(commits);
+            log.rewind = rewind;
+            log.truncated = truncated;
+        }
+    }
+
+    Some(log)
+}
+
+fn log_between(
+    repo: &Repository,
+    old: Option<CommitId>,
+    new: Option<CommitId>,
+) -> Result<(Vec<SubmoduleCommit>, bool, bool), git2::Error> {
+    let (from, hide, rewind) = match (old, new) {
+        (Some(old), Some(new))
+            if repo
+                .graph_descendant_of(old.into(), new.into())? =>
+        {
+            (Some(old), Some(new), true)
+        }
+        (old, new) => (new, old, false),
+    };
+
+    let mut walk = repo.revwalk()?;
+    walk.set_sorting(Sort::TOPOLOGICAL)?;
+    if let Some(from) = from {
+        walk.push(from.into())?;
+    }
+    if let Some(hide) = hide {
+        walk.hide(hide.into())?;
+    }
+
+    let mut commits = Vec::new();
+    for id in walk {
+        if commits.len() == MAX_LOG_COMMITS {
+            return Ok((commits, rewind, true));
+        }
+
+        let commit = repo.find_commit(id?)?;
+        commits.push(SubmoduleCommit {
+            id: commit.id().into(),
+            summary: commit.summary().unwrap_or_default().to_string(),
+            author: commit
+                .author()
+                .name()
+                .unwrap_or("<unknown>")
+                .to_string(),
+        });
+    }
+
+    Ok((commits, rewind, false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::update_submodule;
+    use crate::error::Result;
+    use crate::sync::RepoPath;
+    use crate::sync::{
+        commit,
+        diff::get_diff_commit,
+        status::{get_status, StatusType},
+        tests::repo_init,
+        tests::write_commit_file,
+        CommitId,
+    };
+    use git2::{IndexEntry, IndexTime, Repository};
+    use std::{fs::File, io::Write, path::Path};
+
+    fn stage_gitlink(repo: &Repository, path: &str, id: CommitId) {
+        let mut index = repo.index().unwrap();
+        index
+            .add(&IndexEntry {
+                ctime: IndexTime::new(0, 0),
+                mtime: IndexTime::new(0, 0),
+                dev: 0,
+                ino: 0,
+                mode: 0o160_000,
+                uid: 0,
+                gid: 0,
+                file_size: 0,
+                id: id.into(),
+                flags: 0,
+                flags_extended: 0,
+                path: path.as_bytes().to_vec(),
+            })
+            .unwrap();
+        index.write().unwrap();
+    }
+
+    #[test]
+    fn test_submodule_log() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        let sub = Repository::init(root.join("sub"))?;
+        {
+            let mut config = sub.config()?;
+            config.set_str("user.name", "name")?;
+            config.set_str("user.email", "email")?;
+        }
+
+        let first = write_commit_file(&sub, "a.txt", "a", "first");
+        stage_gitlink(&repo, "sub", first);
+        commit(repo_path, "add sub")?;
+
+        write_commit_file(&sub, "a.txt", "b", "second");
+        let third = write_commit_file(&sub, "a.txt", "c", "third");
+        stage_gitlink(&repo, "sub", third);
+        let bump = commit(repo_path, "bump sub")?;
+
+        stage_gitlink(&repo, "sub", first);
+        let rewind = commit(repo_path, "rewind sub")?;
+
+        let log =
+            get_diff_commit(repo_path, bump, "sub".into(), None)?
+                .submodule
+                .unwrap();
+
+        assert_eq!(log.old, Some(first));
+        assert_eq!(log.new, Some(third));
+        assert!(!log.rewind);
+        assert_eq!(
+            log.commits
+                .unwrap()
+                .iter()
+                .map(|c| c.summary.as_str())
+                .collect::<Vec<_>>(),
+            vec!["third", "second"]
+        );
+
+        let log =
+            get_diff_commit(repo_path, rewind, "sub".into(), None)?
+                .submodule
+                .unwrap();
+
+        assert!(log.rewind);
+        assert_eq!(log.commits.unwrap().len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_submodule_status() -> Result<()> {
+        let (_td_sub, sub) = repo_init()?;
+        let sub_path = sub.workdir().unwrap().to_str().unwrap();
+        let first = write_commit_file(&sub, "a.txt", "a", "first");
+
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        let cloned = {
+            let mut submodule =
+                repo.submodule(sub_path, Path::new("sub"), true)?;
+            let cloned = submodule.clone(None)?;
+            submodule.add_finalize()?;
+            cloned
+        };
+        commit(repo_path, "add sub")?;
+
+        assert!(get_status(repo_path, StatusType::WorkingDir, true)?
+            .is_empty());
+
+        {
+            let mut config = cloned.config()?;
+            config.set_str("user.name", "name")?;
+            config.set_str("user.email", "email")?;
+        }
+        write_commit_file(&cloned, "b.txt", "b", "local");
+        File::create(&root.join("sub/c.txt"))?.write_all(b"c")?;
+
+        let items =
+            get_status(repo_path, StatusType::WorkingDir, true)?;
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].path, "sub");
+
+        let state = items[0].submodule.unwrap();
+        assert!(state.new_commits);
+        assert!(state.untracked_content);
+        assert!(!state.modified_content);
+
+        update_submodule(repo_path, "sub")?;
+
+        assert_eq!(
+            cloned.head()?.target().map(CommitId::from),
+            Some(first)
+        );
+
+        Ok(())
+    }
+}
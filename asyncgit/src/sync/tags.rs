@@ -1,5 +1,9 @@
-use super::{utils::repo, CommitId};
+use super::{
+    commit::signature_allow_undefined_name, utils::repo, CommitId,
+    InstanceLock, RepoPath,
+};
 use crate::error::Result;
+use git2::{ObjectType, Oid, Reference, Repository};
 use scopetime::scope_time;
 use std::collections::BTreeMap;
 
@@ -8,8 +12,21 @@ pub type CommitTags = Vec<String>;
 /// hashmap of tag target commit hash to tag names
 pub type Tags = BTreeMap<CommitId, CommitTags>;
 
+/// a tag and the commit it points to
+#[derive(Clone, Debug, PartialEq)]
+pub struct TagInfo {
+    ///
+    pub name: String,
+    ///
+    pub commit: CommitId,
+    /// tag object with a message of its own instead of a plain ref
+    pub annotated: bool,
+    /// summary of the commit's message
+    pub commit_summary: String,
+}
+
 /// returns `Tags` type filled with all tags found in repo
-pub fn get_tags(repo_path: &str) -> Result<Tags> {
+pub fn get_tags(repo_path: &RepoPath) -> Result<Tags> {
     scope_time!("get_tags");
 
     let mut res = Tags::new();
@@ -23,39 +40,145 @@ pub fn get_tags(repo_path: &str) -> Result<Tags> {
 
     let repo = repo(repo_path)?;
 
-    repo.tag_foreach(|id, name| {
-        if let Ok(name) =
-            // skip the `refs/tags/` part
-            String::from_utf8(name[10..name.len()].into())
-        {
-            //NOTE: find_tag (git_tag_lookup) only works on annotated tags
-            // lightweight tags `id` already points to the target commit
-            // see https://github.com/libgit2/libgit2/issues/5586
-            if let Ok(tag) = repo.find_tag(id) {
-                adder(CommitId::new(tag.target_id()), name);
-            } else if repo.find_commit(id).is_ok() {
-                adder(CommitId::new(id), name);
-            }
-
-            return true;
+    // unlike `tag_foreach` the glob does not resolve every other
+    // ref (mirrors can have 100k+ of them) on the way
+    for reference in repo.references_glob("refs/tags/*")? {
+        let reference = reference?;
+
+        let name = match tag_name(&reference) {
+            Some(name) => name,
+            None => continue,
+        };
+
+        if let Some((target, _)) = tag_target(&repo, &reference) {
+            adder(CommitId::new(target), name);
         }
-        false
-    })?;
+    }
 
     Ok(res)
 }
 
+/// all tags sorted by name
+pub fn get_tag_infos(repo_path: &RepoPath) -> Result<Vec<TagInfo>> {
+    scope_time!("get_tag_infos");
+
+    let repo = repo(repo_path)?;
+    let mut tags = Vec::new();
+
+    for reference in repo.references_glob("refs/tags/*")? {
+        let reference = reference?;
+
+        if let (Some(name), Some((target, annotated))) =
+            (tag_name(&reference), tag_target(&repo, &reference))
+        {
+            let commit_summary = repo
+                .find_commit(target)
+                .ok()
+                .and_then(|commit| {
+                    commit.summary().map(ToString::to_string)
+                })
+                .unwrap_or_default();
+
+            tags.push(TagInfo {
+                name,
+                commit: CommitId::new(target),
+                annotated,
+                commit_summary,
+            });
+        }
+    }
+
+    tags.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(tags)
+}
+
+/// deletes the local tag
+pub fn delete_tag(repo_path: &RepoPath, name: &str) -> Result<()> {
+    scope_time!("delete_tag");
+
+    let _lock = InstanceLock::acquire(repo_path)?;
+
+    repo(repo_path)?.tag_delete(name)?;
+
+    Ok(())
+}
+
+/// moves the tag to `commit`, an annotated tag keeps its message
+pub fn retag(
+    repo_path: &RepoPath,
+    name: &str,
+    commit: CommitId,
+) -> Result<()> {
+    scope_time!("retag");
+
+    let _lock = InstanceLock::acquire(repo_path)?;
+
+    let repo = repo(repo_path)?;
+    let reference =
+        repo.find_reference(&format!("refs/tags/{}", name))?;
+    let target =
+        repo.find_object(commit.into(), Some(ObjectType::Commit))?;
+
+    let annotation =
+        reference.target().and_then(|id| repo.find_tag(id).ok());
+
+    if let Some(annotation) = annotation {
+        let signature = signature_allow_undefined_name(&repo)?;
+        let message = annotation.message().unwrap_or_default();
+
+        repo.tag(name, &target, &signature, message, true)?;
+    } else {
+        repo.tag_lightweight(name, &target, true)?;
+    }
+
+    Ok(())
+}
+
+/// name of a `refs/tags/` reference without that part
+fn tag_name(reference: &Reference) -> Option<String> {
+    String::from_utf8(reference.name_bytes()[10..].into()).ok()
+}
+
+/// commit the tag points to and whether the tag is annotated
+pub fn tag_target(
+    repo: &Repository,
+    reference: &Reference,
+) -> Option<(Oid, bool)> {
+    // packed refs know the peeled target of annotated tags, this
+    // saves looking up the tag object
+    if let Some(peeled) = reference.target_peel() {
+        return Some((peeled, true));
+    }
+
+    let id = reference.target()?;
+
+    //NOTE: find_tag (git_tag_lookup) only works on annotated tags
+    // lightweight tags `id` already points to the target commit
+    // see https://github.com/libgit2/libgit2/issues/5586
+    match repo.find_object(id, None).ok()?.kind()? {
+        ObjectType::Tag => {
+            repo.find_tag(id).ok().map(|tag| (tag.target_id(), true))
+        }
+        ObjectType::Commit => Some((id, false)),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::sync::tests::repo_init;
-    use git2::ObjectType;
+    use crate::sync::{
+        tag,
+        tests::{debug_cmd_print, repo_init, write_commit_file},
+    };
 
     #[test]
     fn test_smoke() {
         let (_td, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         assert_eq!(get_tags(repo_path).unwrap().is_empty(), true);
     }
@@ -64,7 +187,8 @@ mod tests {
     fn test_multitags() {
         let (_td, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         let sig = repo.signature().unwrap();
         let head_id = repo.head().unwrap().target().unwrap();
@@ -83,4 +207,68 @@ mod tests {
             vec!["a", "b"]
         );
     }
+
+    #[test]
+    fn test_packed_tags() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        let sig = repo.signature().unwrap();
+        let head_id = repo.head().unwrap().target().unwrap();
+        let target = repo
+            .find_object(head_id, Some(ObjectType::Commit))
+            .unwrap();
+
+        repo.tag("annotated", &target, &sig, "", false).unwrap();
+        repo.tag_lightweight("lightweight", &target, false).unwrap();
+        repo.reference("refs/pull/1/head", head_id, false, "")
+            .unwrap();
+
+        debug_cmd_print(repo_path, "git pack-refs --all");
+
+        let tags = get_tags(repo_path).unwrap();
+        assert_eq!(tags.len(), 1);
+        assert_eq!(
+            tags[&CommitId::new(head_id)],
+            vec!["annotated", "lightweight"]
+        );
+    }
+
+    #[test]
+    fn test_retag_and_delete() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        let c1 = write_commit_file(&repo, "a.txt", "1", "c1");
+        let c2 = write_commit_file(&repo, "a.txt", "2", "c2");
+
+        tag(repo_path, &c1, "v1").unwrap();
+        let target = repo.find_object(c1.into(), None).unwrap();
+        repo.tag_lightweight("light", &target, false).unwrap();
+
+        let tags = get_tag_infos(repo_path).unwrap();
+        assert_eq!(
+            tags.iter()
+                .map(|tag| (tag.name.as_str(), tag.annotated))
+                .collect::<Vec<_>>(),
+            vec![("light", false), ("v1", true)]
+        );
+        assert_eq!(tags[1].commit, c1);
+        assert_eq!(tags[1].commit_summary, "c1");
+
+        retag(repo_path, "v1", c2).unwrap();
+        retag(repo_path, "light", c2).unwrap();
+
+        let tags = get_tag_infos(repo_path).unwrap();
+        assert!(tags.iter().all(|tag| tag.commit == c2));
+        assert!(tags[1].annotated);
+
+        delete_tag(repo_path, "v1").unwrap();
+
+        assert_eq!(get_tags(repo_path).unwrap()[&c2], vec!["light"]);
+    }
 }
@@ -1,6 +1,6 @@
-use super::{utils::bytes2string, CommitId};
+use super::{utils::bytes2string, CommitId, RepoPath};
 use crate::{error::Result, sync::utils::repo};
-use git2::{Oid, Repository, Tree};
+use git2::{Blob, Oid, Repository, Tree};
 use scopetime::scope_time;
 use std::path::{Path, PathBuf};
 
@@ -17,7 +17,7 @@ pub struct TreeFile {
 
 ///
 pub fn tree_files(
-    repo_path: &str,
+    repo_path: &RepoPath,
     commit: CommitId,
 ) -> Result<Vec<TreeFile>> {
     scope_time!("tree_files");
@@ -36,7 +36,7 @@ pub fn tree_files(
 
 ///
 pub fn tree_file_content(
-    repo_path: &str,
+    repo_path: &RepoPath,
     file: &TreeFile,
 ) -> Result<String> {
     scope_time!("tree_file_content");
@@ -49,6 +49,29 @@ pub fn tree_file_content(
     Ok(content)
 }
 
+/// raw content of a file, use for non text files
+pub fn tree_file_bytes(
+    repo_path: &RepoPath,
+    file: &TreeFile,
+) -> Result<Vec<u8>> {
+    scope_time!("tree_file_bytes");
+
+    let repo = repo(repo_path)?;
+
+    let blob = repo.find_blob(file.id)?;
+
+    Ok(blob.content().to_vec())
+}
+
+/// blob of a file in an already opened `repo`
+#[allow(clippy::redundant_pub_crate)]
+pub(crate) fn tree_file_blob<'a>(
+    repo: &'a Repository,
+    file: &TreeFile,
+) -> Result<Blob<'a>> {
+    Ok(repo.find_blob(file.id)?)
+}
+
 ///
 fn tree_recurse(
     repo: &Repository,
@@ -87,7 +110,8 @@ mod tests {
     fn test_smoke() {
         let (_td, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         let c1 =
             write_commit_file(&repo, "test.txt", "content", "c1");
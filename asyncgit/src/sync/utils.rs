@@ -1,10 +1,12 @@
 //! sync git api (various methods)
 
-use super::CommitId;
+use super::{CommitId, InstanceLock, RepoPath};
 use crate::error::{Error, Result};
 use git2::{IndexAddOption, Repository, RepositoryOpenFlags};
 use scopetime::scope_time;
-use std::{fs::File, io::Write, path::Path};
+use std::path::{Path, PathBuf};
+#[cfg(test)]
+use std::{fs::File, io::Write};
 
 ///
 #[derive(PartialEq, Debug, Clone)]
@@ -16,33 +18,34 @@ pub struct Head {
 }
 
 ///
-pub fn is_repo(repo_path: &str) -> bool {
-    Repository::open_ext(
-        repo_path,
-        RepositoryOpenFlags::empty(),
-        Vec::<&Path>::new(),
-    )
-    .is_ok()
+pub fn is_repo(repo_path: &RepoPath) -> bool {
+    open_repo(repo_path).is_ok()
 }
 
-/// checks if the git repo at path `repo_path` is a bare repo
-pub fn is_bare_repo(repo_path: &str) -> Result<bool> {
+/// checks if the git repo at path `repo_path` is a bare repo (and no
+/// separate work tree was given)
+pub fn is_bare_repo(repo_path: &RepoPath) -> Result<bool> {
+    Ok(open_repo(repo_path)?.is_bare())
+}
+
+/// opens the repository and applies an explicit work tree
+fn open_repo(repo_path: &RepoPath) -> Result<Repository> {
     let repo = Repository::open_ext(
-        repo_path,
+        repo_path.gitpath(),
         RepositoryOpenFlags::empty(),
         Vec::<&Path>::new(),
     )?;
 
-    Ok(repo.is_bare())
+    if let Some(workdir) = repo_path.workdir() {
+        repo.set_workdir(workdir, false)?;
+    }
+
+    Ok(repo)
 }
 
 ///
-pub(crate) fn repo(repo_path: &str) -> Result<Repository> {
-    let repo = Repository::open_ext(
-        repo_path,
-        RepositoryOpenFlags::empty(),
-        Vec::<&Path>::new(),
-    )?;
+pub(crate) fn repo(repo_path: &RepoPath) -> Result<Repository> {
+    let repo = open_repo(repo_path)?;
 
     if repo.is_bare() {
         return Err(Error::Generic("bare repo".to_string()));
@@ -57,7 +60,7 @@ pub(crate) fn work_dir(repo: &Repository) -> Result<&Path> {
 }
 
 ///
-pub fn repo_work_dir(repo_path: &str) -> Result<String> {
+pub fn repo_work_dir(repo_path: &RepoPath) -> Result<String> {
     let repo = repo(repo_path)?;
     work_dir(&repo)?.to_str().map_or_else(
         || Err(Error::Generic("invalid workdir".to_string())),
@@ -65,14 +68,47 @@ pub fn repo_work_dir(repo_path: &str) -> Result<String> {
     )
 }
 
+/// path to the `.git` folder of the repository
+pub fn repo_dir(repo_path: &RepoPath) -> Result<PathBuf> {
+    let repo = repo(repo_path)?;
+    Ok(repo.path().to_owned())
+}
+
 ///
-pub fn get_head(repo_path: &str) -> Result<CommitId> {
+pub fn get_head(repo_path: &RepoPath) -> Result<CommitId> {
     let repo = repo(repo_path)?;
     get_head_repo(&repo)
 }
 
+/// resolves a revision (branch, tag, `origin/main`, sha...) to the
+/// commit it points to
+pub fn resolve_rev(
+    repo_path: &RepoPath,
+    rev: &str,
+) -> Result<CommitId> {
+    scope_time!("resolve_rev");
+
+    let repo = repo(repo_path)?;
+    let commit = repo.revparse_single(rev)?.peel_to_commit()?;
+
+    Ok(commit.id().into())
+}
+
+/// best common ancestor of the commits `a` and `b`
+pub fn merge_base(
+    repo_path: &RepoPath,
+    a: CommitId,
+    b: CommitId,
+) -> Result<CommitId> {
+    scope_time!("merge_base");
+
+    let repo = repo(repo_path)?;
+
+    Ok(repo.merge_base(a.into(), b.into())?.into())
+}
+
 ///
-pub fn get_head_tuple(repo_path: &str) -> Result<Head> {
+pub fn get_head_tuple(repo_path: &RepoPath) -> Result<Head> {
     let repo = repo(repo_path)?;
     let id = get_head_repo(&repo)?;
     let name = get_head_refname(&repo)?;
@@ -98,9 +134,14 @@ pub fn get_head_repo(repo: &Repository) -> Result<CommitId> {
 }
 
 /// add a file diff from workingdir to stage (will not add removed files see `stage_addremoved`)
-pub fn stage_add_file(repo_path: &str, path: &Path) -> Result<()> {
+pub fn stage_add_file(
+    repo_path: &RepoPath,
+    path: &Path,
+) -> Result<()> {
     scope_time!("stage_add_file");
 
+    let _lock = InstanceLock::acquire(repo_path)?;
+
     let repo = repo(repo_path)?;
 
     let mut index = repo.index()?;
@@ -112,9 +153,14 @@ pub fn stage_add_file(repo_path: &str, path: &Path) -> Result<()> {
 }
 
 /// like `stage_add_file` but uses a pattern to match/glob multiple files/folders
-pub fn stage_add_all(repo_path: &str, pattern: &str) -> Result<()> {
+pub fn stage_add_all(
+    repo_path: &RepoPath,
+    pattern: &str,
+) -> Result<()> {
     scope_time!("stage_add_all");
 
+    let _lock = InstanceLock::acquire(repo_path)?;
+
     let repo = repo(repo_path)?;
 
     let mut index = repo.index()?;
@@ -125,10 +171,91 @@ pub fn stage_add_all(repo_path: &str, pattern: &str) -> Result<()> {
     Ok(())
 }
 
+/// returns true if `path` (relative to the workdir) is the root of
+/// another repository, submodules included
+pub(crate) fn is_nested_repo(repo: &Repository, path: &str) -> bool {
+    repo.workdir().map_or(false, |work_dir| {
+        work_dir.join(path).join(".git").exists()
+    })
+}
+
+/// stages every change in the workdir: modified and untracked
+/// files as well as deletions (submodules are staged as their
+/// currently checked out commit, nested repositories are skipped)
+pub fn stage_all_paths(repo_path: &RepoPath) -> Result<()> {
+    scope_time!("stage_all_paths");
+
+    let _lock = InstanceLock::acquire(repo_path)?;
+
+    let repo = repo(repo_path)?;
+
+    let mut index = repo.index()?;
+
+    let submodules = repo
+        .submodules()?
+        .iter()
+        .map(|submodule| submodule.path().to_path_buf())
+        .collect::<Vec<_>>();
+
+    index.add_all(
+        vec!["*"],
+        IndexAddOption::DEFAULT,
+        Some(&mut |path: &Path, _: &[u8]| {
+            let path_str = path.to_string_lossy();
+            let nested = is_nested_repo(&repo, &path_str)
+                && !submodules
+                    .iter()
+                    .any(|submodule| submodule == path);
+
+            // a positive value skips the path
+            i32::from(nested)
+        }),
+    )?;
+    index.update_all(vec!["*"], None)?;
+    index.write()?;
+
+    Ok(())
+}
+
+/// stages the changes of all `paths` (files relative to the workdir)
+/// writing the index just once, removed files are staged as removed
+pub fn stage_paths(
+    repo_path: &RepoPath,
+    paths: &[String],
+) -> Result<()> {
+    scope_time!("stage_paths");
+
+    let _lock = InstanceLock::acquire(repo_path)?;
+
+    let repo = repo(repo_path)?;
+    let work_dir = work_dir(&repo)?;
+
+    let mut index = repo.index()?;
+
+    for path in paths {
+        let path = Path::new(path);
+        // `symlink_metadata` to not follow dangling symlinks
+        if work_dir.join(path).symlink_metadata().is_ok() {
+            index.add_path(path)?;
+        } else {
+            index.remove_path(path)?;
+        }
+    }
+
+    index.write()?;
+
+    Ok(())
+}
+
 /// stage a removed file
-pub fn stage_addremoved(repo_path: &str, path: &Path) -> Result<()> {
+pub fn stage_addremoved(
+    repo_path: &RepoPath,
+    path: &Path,
+) -> Result<()> {
     scope_time!("stage_addremoved");
 
+    let _lock = InstanceLock::acquire(repo_path)?;
+
     let repo = repo(repo_path)?;
 
     let mut index = repo.index()?;
@@ -141,7 +268,7 @@ pub fn stage_addremoved(repo_path: &str, path: &Path) -> Result<()> {
 
 /// get string from config
 pub fn get_config_string(
-    repo_path: &str,
+    repo_path: &RepoPath,
     key: &str,
 ) -> Result<Option<String>> {
     let repo = repo(repo_path)?;
@@ -168,6 +295,7 @@ pub(crate) fn bytes2string(bytes: &[u8]) -> Result<String> {
 }
 
 /// write a file in repo
+#[cfg(test)]
 pub(crate) fn repo_write_file(
     repo: &Repository,
     file: &str,
@@ -207,8 +335,10 @@ mod tests {
     use crate::sync::{
         commit,
         status::{get_status, StatusType},
+        tag,
         tests::{
-            debug_cmd_print, get_statuses, repo_init, repo_init_empty,
+            debug_cmd_print, get_statuses, repo_init, repo_init_bare,
+            repo_init_empty, write_commit_file,
         },
     };
     use std::{
@@ -216,13 +346,15 @@ mod tests {
         io::Write,
         path::Path,
     };
+    use tempfile::TempDir;
 
     #[test]
     fn test_stage_add_smoke() {
         let file_path = Path::new("foo");
         let (_td, repo) = repo_init_empty().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         assert_eq!(
             stage_add_file(repo_path, file_path).is_ok(),
@@ -231,13 +363,16 @@ mod tests {
     }
     #[test]
     fn test_get_config() {
-        let bad_dir_cfg =
-            get_config_string("oodly_noodly", "this.doesnt.exist");
+        let bad_dir_cfg = get_config_string(
+            &"oodly_noodly".into(),
+            "this.doesnt.exist",
+        );
         assert!(bad_dir_cfg.is_err());
 
         let (_td, repo) = repo_init().unwrap();
         let path = repo.path();
-        let rpath = path.as_os_str().to_str().unwrap();
+        let rpath: &RepoPath =
+            &path.as_os_str().to_str().unwrap().into();
         let bad_cfg = get_config_string(rpath, "this.doesnt.exist");
         assert!(bad_cfg.is_ok());
         assert!(bad_cfg.unwrap().is_none());
@@ -251,7 +386,8 @@ mod tests {
         let file_path = Path::new("file1.txt");
         let (_td, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         File::create(&root.join(file_path))
             .unwrap()
@@ -270,11 +406,40 @@ mod tests {
         assert_eq!(get_statuses(repo_path), (1, 1));
     }
 
+    #[test]
+    fn test_staging_paths() -> Result<()> {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        File::create(&root.join("deleted.txt"))?.write_all(b"foo")?;
+        stage_add_file(repo_path, Path::new("deleted.txt"))?;
+        commit(repo_path, "commit msg")?;
+
+        remove_file(&root.join("deleted.txt"))?;
+        fs::create_dir_all(&root.join("a"))?;
+        File::create(&root.join("a/new.txt"))?.write_all(b"foo")?;
+        File::create(&root.join("other.txt"))?.write_all(b"foo")?;
+
+        assert_eq!(get_statuses(repo_path), (3, 0));
+
+        stage_paths(
+            repo_path,
+            &[String::from("deleted.txt"), String::from("a/new.txt")],
+        )?;
+
+        assert_eq!(get_statuses(repo_path), (1, 2));
+
+        Ok(())
+    }
+
     #[test]
     fn test_staging_folder() -> Result<()> {
         let (_td, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         let status_count = |s: StatusType| -> usize {
             get_status(repo_path, s, true).unwrap().len()
@@ -303,7 +468,8 @@ mod tests {
         let file_path = Path::new("file1.txt");
         let (_td, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         let status_count = |s: StatusType| -> usize {
             get_status(repo_path, s, true).unwrap().len()
@@ -337,7 +503,8 @@ mod tests {
     fn test_staging_sub_git_folder() -> Result<()> {
         let (_td, repo) = repo_init().unwrap();
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         let status_count = |s: StatusType| -> usize {
             get_status(repo_path, s, true).unwrap().len()
@@ -347,7 +514,10 @@ mod tests {
 
         fs::create_dir_all(sub)?;
 
-        debug_cmd_print(sub.to_str().unwrap(), "git init subgit");
+        debug_cmd_print(
+            &sub.to_str().unwrap().into(),
+            "git init subgit",
+        );
 
         File::create(sub.join("subgit/foo.txt"))
             .unwrap()
@@ -362,11 +532,62 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_status_and_stage_all_with_nested_repo() -> Result<()> {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        let nested = root.join("sub/nested");
+        Repository::init(&nested)?;
+        File::create(nested.join("foo.txt"))?
+            .write_all(b"content")?;
+        File::create(root.join("bar.txt"))?.write_all(b"content")?;
+
+        let items =
+            get_status(repo_path, StatusType::WorkingDir, true)?;
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[1].path, "sub/nested");
+        assert!(items[1].nested_repo);
+        assert!(!items[0].nested_repo);
+
+        stage_all_paths(repo_path)?;
+
+        assert_eq!(get_statuses(repo_path), (1, 1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_separate_work_tree() -> Result<()> {
+        let (git_dir, _repo) = repo_init_bare()?;
+        let work_dir = TempDir::new()?;
+        let repo_path = RepoPath::Workdir {
+            gitdir: git_dir.path().to_path_buf(),
+            workdir: work_dir.path().to_path_buf(),
+        };
+
+        assert!(!is_bare_repo(&repo_path)?);
+
+        File::create(&work_dir.path().join("foo.txt"))?
+            .write_all(b"test")?;
+
+        assert_eq!(get_statuses(&repo_path), (1, 0));
+
+        stage_add_file(&repo_path, Path::new("foo.txt"))?;
+
+        assert_eq!(get_statuses(&repo_path), (0, 1));
+
+        Ok(())
+    }
+
     #[test]
     fn test_head_empty() -> Result<()> {
         let (_td, repo) = repo_init_empty()?;
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         assert_eq!(get_head(repo_path).is_ok(), false);
 
@@ -377,10 +598,33 @@ mod tests {
     fn test_head() -> Result<()> {
         let (_td, repo) = repo_init()?;
         let root = repo.path().parent().unwrap();
-        let repo_path = root.as_os_str().to_str().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
 
         assert_eq!(get_head(repo_path).is_ok(), true);
 
         Ok(())
     }
+
+    #[test]
+    fn test_resolve_rev() -> Result<()> {
+        let (_td, repo) = repo_init()?;
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+
+        let first = get_head(repo_path)?;
+        tag(repo_path, &first, "v1")?;
+        let second = write_commit_file(&repo, "a.txt", "a", "second");
+
+        assert_eq!(resolve_rev(repo_path, "HEAD")?, second);
+        assert_eq!(resolve_rev(repo_path, "v1")?, first);
+        assert_eq!(
+            resolve_rev(repo_path, &first.get_short_string())?,
+            first
+        );
+        assert!(resolve_rev(repo_path, "missing").is_err());
+
+        Ok(())
+    }
 }
@@ -0,0 +1,253 @@
+//! intra-line (word level) change detection for diff lines
+
+use super::diff::{DiffLine, DiffLineType, LineChange};
+use std::cmp;
+
+/// upper bound of `old tokens * new tokens` we are willing to
+/// compare, protects us from quadratic blowup on huge lines
+const MAX_TOKEN_PRODUCT: usize = 250_000;
+
+#[derive(Copy, Clone, PartialEq)]
+enum TokenKind {
+    Word,
+    Whitespace,
+    Other,
+}
+
+impl TokenKind {
+    fn from_char(c: char) -> Self {
+        if c.is_alphanumeric() || c == '_' {
+            Self::Word
+        } else if c.is_whitespace() {
+            Self::Whitespace
+        } else {
+            Self::Other
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+struct Token {
+    start: usize,
+    end: usize,
+    kind: TokenKind,
+}
+
+/// splits `text` into words, whitespace runs and single
+/// punctuation chars, returning their byte ranges
+fn tokenize(text: &str) -> Vec<Token> {
+    let mut tokens: Vec<Token> = Vec::new();
+
+    for (idx, c) in text.char_indices() {
+        let kind = TokenKind::from_char(c);
+        let end = idx + c.len_utf8();
+
+        match tokens.last_mut() {
+            Some(last)
+                if last.kind == kind && kind != TokenKind::Other =>
+            {
+                last.end = end;
+            }
+            _ => tokens.push(Token {
+                start: idx,
+                end,
+                kind,
+            }),
+        }
+    }
+
+    tokens
+}
+
+fn push_change(changes: &mut Vec<LineChange>, token: &Token) {
+    if let Some(last) = changes.last_mut() {
+        if last.end == token.start {
+            last.end = token.end;
+            return;
+        }
+    }
+
+    changes.push(LineChange {
+        start: token.start,
+        end: token.end,
+    });
+}
+
+/// returns changed byte ranges of `old` and `new` or `None` if the
+/// two lines have nothing meaningful in common
+fn changed_ranges(
+    old: &str,
+    new: &str,
+) -> Option<(Vec<LineChange>, Vec<LineChange>)> {
+    let old_tokens = tokenize(old);
+    let new_tokens = tokenize(new);
+
+    let n = old_tokens.len();
+    let m = new_tokens.len();
+
+    if n == 0 || m == 0 || n.saturating_mul(m) > MAX_TOKEN_PRODUCT {
+        return None;
+    }
+
+    let token_eq = |i: usize, j: usize| {
+        old[old_tokens[i].start..old_tokens[i].end]
+            == new[new_tokens[j].start..new_tokens[j].end]
+    };
+
+    // longest common subsequence table, row major with `m + 1` columns
+    let width = m + 1;
+    let mut table = vec![0_usize; (n + 1) * width];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            table[i * width + j] = if token_eq(i, j) {
+                table[(i + 1) * width + j + 1] + 1
+            } else {
+                cmp::max(
+                    table[(i + 1) * width + j],
+                    table[i * width + j + 1],
+                )
+            };
+        }
+    }
+
+    let mut old_changes = Vec::new();
+    let mut new_changes = Vec::new();
+    let mut common_words = 0_usize;
+
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if token_eq(i, j) {
+            if old_tokens[i].kind != TokenKind::Whitespace {
+                common_words += 1;
+            }
+            i += 1;
+            j += 1;
+        } else if table[(i + 1) * width + j]
+            >= table[i * width + j + 1]
+        {
+            push_change(&mut old_changes, &old_tokens[i]);
+            i += 1;
+        } else {
+            push_change(&mut new_changes, &new_tokens[j]);
+            j += 1;
+        }
+    }
+
+    for token in &old_tokens[i..] {
+        push_change(&mut old_changes, token);
+    }
+    for token in &new_tokens[j..] {
+        push_change(&mut new_changes, token);
+    }
+
+    if common_words == 0 {
+        return None;
+    }
+
+    Some((old_changes, new_changes))
+}
+
+/// pairs up blocks of deleted lines with directly following added
+/// lines inside a hunk and fills their `changes`
+pub(crate) fn highlight_changes(lines: &mut [DiffLine]) {
+    let mut idx = 0;
+
+    while idx < lines.len() {
+        if lines[idx].line_type != DiffLineType::Delete {
+            idx += 1;
+            continue;
+        }
+
+        let del_start = idx;
+        while idx < lines.len()
+            && lines[idx].line_type == DiffLineType::Delete
+        {
+            idx += 1;
+        }
+        let add_start = idx;
+        while idx < lines.len()
+            && lines[idx].line_type == DiffLineType::Add
+        {
+            idx += 1;
+        }
+
+        let pairs = cmp::min(add_start - del_start, idx - add_start);
+
+        for offset in 0..pairs {
+            let (old_idx, new_idx) =
+                (del_start + offset, add_start + offset);
+
+            if let Some((old, new)) = changed_ranges(
+                &lines[old_idx].content,
+                &lines[new_idx].content,
+            ) {
+                lines[old_idx].changes = old;
+                lines[new_idx].changes = new;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{changed_ranges, highlight_changes};
+    use crate::sync::diff::{DiffLine, DiffLineType, LineChange};
+
+    fn line(content: &str, line_type: DiffLineType) -> DiffLine {
+        DiffLine {
+            content: content.to_string(),
+            line_type,
+            ..DiffLine::default()
+        }
+    }
+
+    #[test]
+    fn test_single_word_change() {
+        let (old, new) =
+            changed_ranges("let foo = 1;\n", "let bar = 1;\n")
+                .unwrap();
+
+        assert_eq!(old, vec![LineChange { start: 4, end: 7 }]);
+        assert_eq!(new, vec![LineChange { start: 4, end: 7 }]);
+    }
+
+    #[test]
+    fn test_single_char_change_on_long_line() {
+        let (old, new) = changed_ranges(
+            "assert_eq!(value, some_function(a, b, c));\n",
+            "assert_eq!(value, some_function(a, d, c));\n",
+        )
+        .unwrap();
+
+        assert_eq!(old, vec![LineChange { start: 35, end: 36 }]);
+        assert_eq!(new, vec![LineChange { start: 35, end: 36 }]);
+    }
+
+    #[test]
+    fn test_nothing_in_common() {
+        assert!(changed_ranges("foo\n", "bar\n").is_none());
+    }
+
+    #[test]
+    fn test_pairs_only_adjacent_blocks() {
+        let mut lines = vec![
+            line("@@ -1,3 +1,3 @@\n", DiffLineType::Header),
+            line("a = 1\n", DiffLineType::Delete),
+            line("a = 2\n", DiffLineType::Add),
+            line("unrelated\n", DiffLineType::None),
+            line("b = 1\n", DiffLineType::Add),
+        ];
+
+        highlight_changes(&mut lines);
+
+        assert_eq!(
+            lines[1].changes,
+            vec![LineChange { start: 4, end: 5 }]
+        );
+        assert_eq!(
+            lines[2].changes,
+            vec![LineChange { start: 4, end: 5 }]
+        );
+        assert!(lines[4].changes.is_empty());
+    }
+}
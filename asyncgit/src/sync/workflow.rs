@@ -0,0 +1,446 @@
+//! git-flow and trunk-based branches as composites of the branch,
+//! merge and tag primitives
+
+use super::{
+    checkout_branch, commit::signature_allow_undefined_name,
+    create_branch_at, utils, InstanceLock, RepoPath,
+};
+use crate::error::{Error, Result};
+use git2::{BranchType, Commit, Oid, Repository, StatusOptions};
+use scopetime::scope_time;
+
+/// the short-lived branches of a workflow
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum FlowBranch {
+    /// git-flow: from develop back into develop
+    Feature,
+    /// git-flow: from develop into main (tagged) and develop
+    Release,
+    /// git-flow: from main into main (tagged) and develop
+    Hotfix,
+    /// trunk-based: from main, fast forwarded into main
+    Trunk,
+}
+
+/// how the branches of a workflow are named
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlowNaming {
+    /// the production branch
+    pub main: String,
+    /// the git-flow integration branch
+    pub develop: String,
+    ///
+    pub feature_prefix: String,
+    ///
+    pub release_prefix: String,
+    ///
+    pub hotfix_prefix: String,
+    /// short-lived trunk-based branches, may be empty
+    pub trunk_prefix: String,
+    /// prefixed to the name of a finished release or hotfix to tag it
+    pub tag_prefix: String,
+}
+
+impl Default for FlowNaming {
+    fn default() -> Self {
+        Self {
+            main: "master".into(),
+            develop: "develop".into(),
+            feature_prefix: "feature/".into(),
+            release_prefix: "release/".into(),
+            hotfix_prefix: "hotfix/".into(),
+            trunk_prefix: String::new(),
+            tag_prefix: "v".into(),
+        }
+    }
+}
+
+impl FlowNaming {
+    ///
+    pub fn prefix(&self, kind: FlowBranch) -> &str {
+        match kind {
+            FlowBranch::Feature => &self.feature_prefix,
+            FlowBranch::Release => &self.release_prefix,
+            FlowBranch::Hotfix => &self.hotfix_prefix,
+            FlowBranch::Trunk => &self.trunk_prefix,
+        }
+    }
+
+    /// the branch a `kind` branch starts from
+    pub fn base(&self, kind: FlowBranch) -> &str {
+        match kind {
+            FlowBranch::Feature | FlowBranch::Release => {
+                &self.develop
+            }
+            FlowBranch::Hotfix | FlowBranch::Trunk => &self.main,
+        }
+    }
+
+    /// the full name of the `kind` branch `name`
+    pub fn branch(&self, kind: FlowBranch, name: &str) -> String {
+        format!("{}{}", self.prefix(kind), name)
+    }
+
+    /// kind and name of `branch` if it is one of `kinds`,
+    /// the main and develop branches are never flow branches
+    pub fn parse(
+        &self,
+        branch: &str,
+        kinds: &[FlowBranch],
+    ) -> Option<(FlowBranch, String)> {
+        if branch == self.main || branch == self.develop {
+            return None;
+        }
+
+        kinds.iter().find_map(|kind| {
+            branch
+                .strip_prefix(self.prefix(*kind))
+                .filter(|name| !name.is_empty())
+                .map(|name| (*kind, name.to_string()))
+        })
+    }
+}
+
+/// creates the `kind` branch `name` on top of its base and checks it
+/// out, fails on uncommitted changes before creating anything
+pub fn flow_start(
+    repo_path: &RepoPath,
+    naming: &FlowNaming,
+    kind: FlowBranch,
+    name: &str,
+) -> Result<String> {
+    scope_time!("flow_start");
+
+    let _lock = InstanceLock::acquire(repo_path)?;
+
+    let repo = utils::repo(repo_path)?;
+    ensure_clean(&repo)?;
+
+    let base = branch_tip(&repo, naming.base(kind))?;
+    let branch = naming.branch(kind, name);
+
+    create_branch_at(repo_path, &branch, base.id().into())?;
+    checkout_branch(repo_path, &format!("refs/heads/{}", branch))?;
+
+    Ok(branch)
+}
+
+/// finishes the `kind` branch `name`:
+/// * features are merged into develop,
+/// * releases and hotfixes are merged into main, tagged there and
+///   merged into develop,
+/// * trunk branches fast forward main (rebase them otherwise).
+///
+/// merges always create a merge commit like `git merge --no-ff`.
+/// merges are computed in memory first, so nothing changes if one of
+/// them conflicts. the branch gets deleted and develop (main for
+/// trunk-based) checked out.
+pub fn flow_finish(
+    repo_path: &RepoPath,
+    naming: &FlowNaming,
+    kind: FlowBranch,
+    name: &str,
+) -> Result<()> {
+    scope_time!("flow_finish");
+
+    let _lock = InstanceLock::acquire(repo_path)?;
+
+    let repo = utils::repo(repo_path)?;
+    ensure_clean(&repo)?;
+
+    let branch = naming.branch(kind, name);
+    let tip = branch_tip(&repo, &branch)?;
+
+    let mut updates: Vec<(&str, Oid)> = Vec::new();
+    let mut tag = None;
+
+    match kind {
+        FlowBranch::Feature => {
+            updates.push((
+                naming.develop.as_str(),
+                merge_no_ff(&repo, &naming.develop, &branch, &tip)?,
+            ));
+        }
+        FlowBranch::Release | FlowBranch::Hotfix => {
+            let main =
+                merge_no_ff(&repo, &naming.main, &branch, &tip)?;
+            updates.push((naming.main.as_str(), main));
+            updates.push((
+                naming.develop.as_str(),
+                merge_no_ff(&repo, &naming.develop, &branch, &tip)?,
+            ));
+            tag = Some((
+                format!("{}{}", naming.tag_prefix, name),
+                main,
+            ));
+        }
+        FlowBranch::Trunk => {
+            let main = branch_tip(&repo, &naming.main)?;
+            if main.id() != tip.id()
+                && !repo.graph_descendant_of(tip.id(), main.id())?
+            {
+                return Err(Error::Generic(format!(
+                    "'{}' can't fast forward '{}', rebase it first",
+                    branch, naming.main
+                )));
+            }
+            updates.push((naming.main.as_str(), tip.id()));
+        }
+    }
+
+    if let Some((tag, id)) = tag {
+        let signature = signature_allow_undefined_name(&repo)?;
+        repo.tag(
+            &tag,
+            &repo.find_object(id, None)?,
+            &signature,
+            &tag,
+            false,
+        )?;
+    }
+
+    let log_msg = format!("finish: {}", branch);
+    for (target, id) in updates {
+        repo.reference(
+            &format!("refs/heads/{}", target),
+            id,
+            true,
+            &log_msg,
+        )?;
+    }
+
+    let end = if kind == FlowBranch::Trunk {
+        &naming.main
+    } else {
+        &naming.develop
+    };
+    repo.set_head(&format!("refs/heads/{}", end))?;
+    // safe since `ensure_clean` passed
+    repo.checkout_head(Some(
+        git2::build::CheckoutBuilder::new().force(),
+    ))?;
+
+    repo.find_branch(&branch, BranchType::Local)?.delete()?;
+
+    Ok(())
+}
+
+fn ensure_clean(repo: &Repository) -> Result<()> {
+    let statuses = repo.statuses(Some(
+        StatusOptions::new().include_ignored(false),
+    ))?;
+
+    if statuses.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::UncommittedChanges)
+    }
+}
+
+fn branch_tip<'a>(
+    repo: &'a Repository,
+    branch: &str,
+) -> Result<Commit<'a>> {
+    Ok(repo
+        .find_branch(branch, BranchType::Local)?
+        .get()
+        .peel_to_commit()?)
+}
+
+/// merge commit of `from` into the branch `into` without moving any
+/// ref, `into`s tip if it contains `from` already
+fn merge_no_ff(
+    repo: &Repository,
+    into: &str,
+    from_name: &str,
+    from: &Commit,
+) -> Result<Oid> {
+    let target = branch_tip(repo, into)?;
+
+    if target.id() == from.id()
+        || repo.graph_descendant_of(target.id(), from.id())?
+    {
+        return Ok(target.id());
+    }
+
+    let mut index = repo.merge_commits(&target, from, None)?;
+    if index.has_conflicts() {
+        return Err(Error::Generic(format!(
+            "merging '{}' into '{}' conflicts, merge it manually",
+            from_name, into
+        )));
+    }
+
+    let tree = repo.find_tree(index.write_tree_to(repo)?)?;
+    let signature = signature_allow_undefined_name(repo)?;
+
+    Ok(repo.commit(
+        None,
+        &signature,
+        &signature,
+        &format!("Merge branch '{}' into {}", from_name, into),
+        &tree,
+        &[&target, from],
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sync::{
+        branch::get_branch_name,
+        get_tags,
+        tests::{get_commit_ids, repo_init, write_commit_file},
+        CommitId,
+    };
+
+    fn tip(repo: &Repository, branch: &str) -> Oid {
+        branch_tip(repo, branch).unwrap().id()
+    }
+
+    #[test]
+    fn test_parse() {
+        let naming = FlowNaming::default();
+        let git_flow = [
+            FlowBranch::Feature,
+            FlowBranch::Release,
+            FlowBranch::Hotfix,
+        ];
+
+        assert_eq!(
+            naming.parse("feature/foo", &git_flow),
+            Some((FlowBranch::Feature, String::from("foo")))
+        );
+        assert_eq!(naming.parse("feature/", &git_flow), None);
+        assert_eq!(naming.parse("foo", &git_flow), None);
+        assert_eq!(
+            naming.parse("foo", &[FlowBranch::Trunk]),
+            Some((FlowBranch::Trunk, String::from("foo")))
+        );
+        assert_eq!(
+            naming.parse("master", &[FlowBranch::Trunk]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_feature() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+        let naming = FlowNaming::default();
+
+        create_branch_at(
+            repo_path,
+            "develop",
+            tip(&repo, "master").into(),
+        )
+        .unwrap();
+
+        let branch = flow_start(
+            repo_path,
+            &naming,
+            FlowBranch::Feature,
+            "foo",
+        )
+        .unwrap();
+        assert_eq!(branch, "feature/foo");
+        assert_eq!(get_branch_name(repo_path).unwrap(), branch);
+
+        let c1 = write_commit_file(&repo, "foo.txt", "foo", "c1");
+
+        flow_finish(repo_path, &naming, FlowBranch::Feature, "foo")
+            .unwrap();
+
+        assert_eq!(get_branch_name(repo_path).unwrap(), "develop");
+        assert!(repo
+            .find_branch("feature/foo", BranchType::Local)
+            .is_err());
+
+        let merge = repo.find_commit(tip(&repo, "develop")).unwrap();
+        assert_eq!(merge.parent_count(), 2);
+        assert_eq!(CommitId::new(merge.parent_id(1).unwrap()), c1);
+        assert!(root.join("foo.txt").exists());
+    }
+
+    #[test]
+    fn test_release() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+        let naming = FlowNaming::default();
+
+        create_branch_at(
+            repo_path,
+            "develop",
+            tip(&repo, "master").into(),
+        )
+        .unwrap();
+
+        flow_start(repo_path, &naming, FlowBranch::Release, "1.0")
+            .unwrap();
+        write_commit_file(&repo, "version", "1.0", "bump");
+
+        flow_finish(repo_path, &naming, FlowBranch::Release, "1.0")
+            .unwrap();
+
+        let main = tip(&repo, "master");
+        assert_eq!(repo.find_commit(main).unwrap().parent_count(), 2);
+        assert_eq!(
+            repo.find_commit(tip(&repo, "develop"))
+                .unwrap()
+                .parent_count(),
+            2
+        );
+        assert_eq!(
+            get_tags(repo_path).unwrap()[&CommitId::new(main)],
+            vec![String::from("v1.0")]
+        );
+    }
+
+    #[test]
+    fn test_trunk() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+        let naming = FlowNaming::default();
+
+        flow_start(repo_path, &naming, FlowBranch::Trunk, "foo")
+            .unwrap();
+        let c1 = write_commit_file(&repo, "foo.txt", "foo", "c1");
+
+        flow_finish(repo_path, &naming, FlowBranch::Trunk, "foo")
+            .unwrap();
+
+        assert_eq!(get_branch_name(repo_path).unwrap(), "master");
+        assert_eq!(get_commit_ids(&repo, 1), vec![c1]);
+    }
+
+    #[test]
+    fn test_trunk_diverged() {
+        let (_td, repo) = repo_init().unwrap();
+        let root = repo.path().parent().unwrap();
+        let repo_path: &RepoPath =
+            &root.as_os_str().to_str().unwrap().into();
+        let naming = FlowNaming::default();
+
+        flow_start(repo_path, &naming, FlowBranch::Trunk, "foo")
+            .unwrap();
+        write_commit_file(&repo, "foo.txt", "foo", "c1");
+        checkout_branch(repo_path, "refs/heads/master").unwrap();
+        let c2 = write_commit_file(&repo, "bar.txt", "bar", "c2");
+
+        assert!(flow_finish(
+            repo_path,
+            &naming,
+            FlowBranch::Trunk,
+            "foo"
+        )
+        .is_err());
+        assert_eq!(CommitId::new(tip(&repo, "master")), c2);
+        assert!(repo.find_branch("foo", BranchType::Local).is_ok());
+    }
+}
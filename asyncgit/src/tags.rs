@@ -1,8 +1,8 @@
 use crate::{
     error::Result,
     hash,
-    sync::{self},
-    AsyncNotification, CWD,
+    sync::{self, RepoPath},
+    AsyncNotification,
 };
 use crossbeam_channel::Sender;
 use std::{
@@ -12,17 +12,19 @@ use std::{
     },
     time::{Duration, Instant},
 };
-use sync::Tags;
+use sync::{Decorations, Tags};
 
 ///
 #[derive(Default, Clone)]
 struct TagsResult {
     hash: u64,
     tags: Tags,
+    decorations: Decorations,
 }
 
 ///
 pub struct AsyncTags {
+    repo: RepoPath,
     last: Arc<Mutex<Option<(Instant, TagsResult)>>>,
     sender: Sender<AsyncNotification>,
     pending: Arc<AtomicUsize>,
@@ -30,8 +32,12 @@ pub struct AsyncTags {
 
 impl AsyncTags {
     ///
-    pub fn new(sender: &Sender<AsyncNotification>) -> Self {
+    pub fn new(
+        repo: RepoPath,
+        sender: &Sender<AsyncNotification>,
+    ) -> Self {
         Self {
+            repo,
             last: Arc::new(Mutex::new(None)),
             sender: sender.clone(),
             pending: Arc::new(AtomicUsize::new(0)),
@@ -45,6 +51,15 @@ impl AsyncTags {
         Ok(last.clone().map(|last| last.1.tags))
     }
 
+    /// branches, remote branches and tags of the last fetch
+    pub fn last_decorations(
+        &mut self,
+    ) -> Result<Option<Decorations>> {
+        let last = self.last.lock()?;
+
+        Ok(last.clone().map(|last| last.1.decorations))
+    }
+
     ///
     pub fn is_pending(&self) -> bool {
         self.pending.load(Ordering::Relaxed) > 0
@@ -73,12 +88,13 @@ impl AsyncTags {
         let arc_last = Arc::clone(&self.last);
         let sender = self.sender.clone();
         let arc_pending = Arc::clone(&self.pending);
+        let repo = self.repo.clone();
 
         self.pending.fetch_add(1, Ordering::Relaxed);
 
         rayon_core::spawn(move || {
-            let notify =
-                Self::getter(&arc_last).expect("error getting tags");
+            let notify = Self::getter(&repo, &arc_last)
+                .expect("error getting tags");
 
             arc_pending.fetch_sub(1, Ordering::Relaxed);
 
@@ -95,11 +111,14 @@ impl AsyncTags {
     }
 
     fn getter(
+        repo_path: &RepoPath,
         arc_last: &Arc<Mutex<Option<(Instant, TagsResult)>>>,
     ) -> Result<bool> {
-        let tags = sync::get_tags(CWD)?;
+        // tags are part of the decorations, one walk over the refs
+        // gets both
+        let decorations = sync::get_decorations(repo_path)?;
 
-        let hash = hash(&tags);
+        let hash = hash(&decorations);
 
         if Self::last_hash(arc_last)
             .map(|last| last == hash)
@@ -111,7 +130,14 @@ impl AsyncTags {
         {
             let mut last = arc_last.lock()?;
             let now = Instant::now();
-            *last = Some((now, TagsResult { hash, tags }));
+            *last = Some((
+                now,
+                TagsResult {
+                    hash,
+                    tags: sync::decoration_tags(&decorations),
+                    decorations,
+                },
+            ));
         }
 
         Ok(true)
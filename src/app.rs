@@ -1,43 +1,65 @@
 use crate::{
-    accessors,
+    accessors, clipboard,
     cmdbar::CommandBar,
     components::{
-        event_pump, BlameFileComponent, BranchListComponent,
-        CommandBlocking, CommandInfo, CommitComponent, Component,
-        CreateBranchComponent, DrawableComponent,
-        ExternalEditorComponent, HelpComponent,
-        InspectCommitComponent, MsgComponent, PullComponent,
-        PushComponent, PushTagsComponent, RenameBranchComponent,
-        ResetComponent, RevisionFilesComponent, StashMsgComponent,
-        TagCommitComponent,
+        event_pump, ApplyPatchComponent, ArchiveCommitComponent,
+        BlameFileComponent, BranchListComponent,
+        CheckoutStrategyComponent, CommandBlocking, CommandInfo,
+        CommandPaletteComponent, CommitComponent, Component,
+        ContextMenuComponent, CreateBranchComponent,
+        DiffBaseComponent, DrawableComponent, ExportPatchComponent,
+        ExternalEditorComponent, FetchOptionsComponent,
+        GotoCommitComponent, HelpComponent, HooksComponent,
+        IgnorePathComponent, InspectCommitComponent,
+        LogFilterComponent, MsgComponent, OpenLinkComponent,
+        PullComponent, PushComponent, PushRemoteComponent,
+        PushTagsComponent, RebaseComponent, RebasePreviewComponent,
+        RenameBranchComponent, RepoSwitcherComponent,
+        ResetCommitComponent, ResetComponent, ReviewComponent,
+        RevisionFilesComponent, StashMsgComponent,
+        TagCommitComponent, TagListComponent,
+        UpstreamBranchComponent,
     },
+    crash_report,
     input::{Input, InputEvent, InputState},
-    keys::{KeyConfig, SharedKeyConfig},
+    instance_sync::InstanceSync,
+    keys::{ChordMatch, SharedKeyConfig},
+    options::{Options, SharedOptions},
+    perf_hud::PerfHud,
     queue::{Action, InternalEvent, NeedsUpdate, Queue},
     strings::{self, order},
     tabs::{Revlog, StashList, Stashing, Status},
-    ui::style::{SharedTheme, Theme},
+    ui::style::SharedTheme,
 };
 use anyhow::{bail, Result};
-use asyncgit::{sync, AsyncNotification, CWD};
+use asyncgit::{
+    sync::{self, CommitId, Journal, JournalOp, RepoPath},
+    AsyncNotification,
+};
 use crossbeam_channel::Sender;
-use crossterm::event::{Event, KeyEvent};
+use crossterm::event::{Event, KeyEvent, MouseEvent, MouseEventKind};
 use std::{
     cell::{Cell, RefCell},
     path::Path,
     rc::Rc,
+    time::Duration,
 };
 use tui::{
     backend::Backend,
-    layout::{Constraint, Direction, Layout, Margin, Rect},
+    layout::{
+        Alignment, Constraint, Direction, Layout, Margin, Rect,
+    },
     text::{Span, Spans},
-    widgets::{Block, Borders, Tabs},
+    widgets::{Block, Borders, Paragraph, Tabs},
     Frame,
 };
 
 /// the main app type
 pub struct App {
+    repo: RepoPath,
     do_quit: bool,
+    /// keys of a chord typed so far (see `KeyConfig::chords`)
+    pending_chord: Vec<KeyEvent>,
     help: HelpComponent,
     msg: MsgComponent,
     reset: ResetComponent,
@@ -48,12 +70,33 @@ pub struct App {
     external_editor_popup: ExternalEditorComponent,
     revision_files_popup: RevisionFilesComponent,
     push_popup: PushComponent,
+    push_remote_popup: PushRemoteComponent,
+    ignore_path_popup: IgnorePathComponent,
+    open_link_popup: OpenLinkComponent,
     push_tags_popup: PushTagsComponent,
     pull_popup: PullComponent,
     tag_commit_popup: TagCommitComponent,
+    tag_list_popup: TagListComponent,
+    reset_commit_popup: ResetCommitComponent,
+    checkout_strategy_popup: CheckoutStrategyComponent,
     create_branch_popup: CreateBranchComponent,
     rename_branch_popup: RenameBranchComponent,
+    upstream_branch_popup: UpstreamBranchComponent,
     select_branch_popup: BranchListComponent,
+    repo_switcher_popup: RepoSwitcherComponent,
+    hooks_popup: HooksComponent,
+    command_palette: CommandPaletteComponent,
+    rebase_popup: RebaseComponent,
+    rebase_preview_popup: RebasePreviewComponent,
+    diff_base_popup: DiffBaseComponent,
+    goto_commit_popup: GotoCommitComponent,
+    log_filter_popup: LogFilterComponent,
+    export_patch_popup: ExportPatchComponent,
+    apply_patch_popup: ApplyPatchComponent,
+    archive_commit_popup: ArchiveCommitComponent,
+    fetch_options_popup: FetchOptionsComponent,
+    review_popup: ReviewComponent,
+    context_menu: ContextMenuComponent,
     cmdbar: RefCell<CommandBar>,
     tab: usize,
     revlog: Revlog,
@@ -63,11 +106,19 @@ pub struct App {
     queue: Queue,
     theme: SharedTheme,
     key_config: SharedKeyConfig,
+    options: SharedOptions,
+    instance_sync: InstanceSync,
+    perf_hud: PerfHud,
+    /// destructive actions that can be undone
+    journal: Journal,
     input: Input,
 
     // "Flags"
     requires_redraw: Cell<bool>,
     file_to_open: Option<String>,
+    pager_file_to_open: Option<String>,
+    repo_to_open: Option<String>,
+    repo_to_switch: Option<String>,
 }
 
 // public interface
@@ -75,14 +126,23 @@ impl App {
     ///
     #[allow(clippy::too_many_lines)]
     pub fn new(
+        repo: RepoPath,
         sender: &Sender<AsyncNotification>,
         input: Input,
-        theme: Theme,
-        key_config: KeyConfig,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+        path_scope: Option<String>,
     ) -> Self {
+        crash_report::set_state("repo", format!("{:?}", repo));
+        crash_report::set_state("tab", "0".to_string());
+
         let queue = Queue::default();
-        let theme = Rc::new(theme);
-        let key_config = Rc::new(key_config);
+        let options = Options::new(repo.clone());
+        options.borrow_mut().set_path_scope(path_scope);
+        clipboard::set_backend(options.borrow().clipboard());
+        asyncgit::set_network_limits(
+            options.borrow().network_limits(),
+        );
 
         Self {
             input,
@@ -92,77 +152,222 @@ impl App {
                 key_config.clone(),
             ),
             commit: CommitComponent::new(
+                repo.clone(),
                 queue.clone(),
                 theme.clone(),
                 key_config.clone(),
+                options.clone(),
             ),
             blame_file_popup: BlameFileComponent::new(
+                &repo,
                 &queue,
                 sender,
                 &strings::blame_title(&key_config),
                 theme.clone(),
                 key_config.clone(),
+                options.clone(),
             ),
             revision_files_popup: RevisionFilesComponent::new(
+                repo.clone(),
                 &queue,
                 sender,
                 theme.clone(),
                 key_config.clone(),
             ),
             stashmsg_popup: StashMsgComponent::new(
+                repo.clone(),
                 queue.clone(),
                 theme.clone(),
                 key_config.clone(),
             ),
             inspect_commit_popup: InspectCommitComponent::new(
+                &repo,
                 &queue,
                 sender,
                 theme.clone(),
                 key_config.clone(),
+                options.clone(),
             ),
             external_editor_popup: ExternalEditorComponent::new(
                 theme.clone(),
                 key_config.clone(),
             ),
             push_popup: PushComponent::new(
+                repo.clone(),
                 &queue,
                 sender,
                 theme.clone(),
                 key_config.clone(),
             ),
+            push_remote_popup: PushRemoteComponent::new(
+                repo.clone(),
+                queue.clone(),
+                theme.clone(),
+                key_config.clone(),
+            ),
+            ignore_path_popup: IgnorePathComponent::new(
+                repo.clone(),
+                queue.clone(),
+                theme.clone(),
+                key_config.clone(),
+            ),
+            open_link_popup: OpenLinkComponent::new(
+                queue.clone(),
+                theme.clone(),
+                key_config.clone(),
+            ),
             push_tags_popup: PushTagsComponent::new(
+                repo.clone(),
                 &queue,
                 sender,
                 theme.clone(),
                 key_config.clone(),
             ),
             pull_popup: PullComponent::new(
+                repo.clone(),
                 &queue,
                 sender,
                 theme.clone(),
                 key_config.clone(),
+                options.clone(),
             ),
             tag_commit_popup: TagCommitComponent::new(
+                repo.clone(),
+                queue.clone(),
+                theme.clone(),
+                key_config.clone(),
+            ),
+            tag_list_popup: TagListComponent::new(
+                repo.clone(),
+                queue.clone(),
+                theme.clone(),
+                key_config.clone(),
+            ),
+            reset_commit_popup: ResetCommitComponent::new(
+                queue.clone(),
+                theme.clone(),
+                key_config.clone(),
+            ),
+            checkout_strategy_popup: CheckoutStrategyComponent::new(
+                repo.clone(),
                 queue.clone(),
                 theme.clone(),
                 key_config.clone(),
             ),
             create_branch_popup: CreateBranchComponent::new(
+                repo.clone(),
                 queue.clone(),
                 theme.clone(),
                 key_config.clone(),
+                options.clone(),
             ),
             rename_branch_popup: RenameBranchComponent::new(
+                repo.clone(),
+                queue.clone(),
+                theme.clone(),
+                key_config.clone(),
+            ),
+            upstream_branch_popup: UpstreamBranchComponent::new(
+                repo.clone(),
                 queue.clone(),
                 theme.clone(),
                 key_config.clone(),
             ),
             select_branch_popup: BranchListComponent::new(
+                repo.clone(),
+                queue.clone(),
+                sender,
+                theme.clone(),
+                key_config.clone(),
+                options.clone(),
+            ),
+            repo_switcher_popup: RepoSwitcherComponent::new(
+                &repo,
+                queue.clone(),
+                theme.clone(),
+                key_config.clone(),
+            ),
+            hooks_popup: HooksComponent::new(
+                repo.clone(),
+                queue.clone(),
+                theme.clone(),
+                key_config.clone(),
+            ),
+            command_palette: CommandPaletteComponent::new(
+                queue.clone(),
+                theme.clone(),
+                key_config.clone(),
+            ),
+            rebase_popup: RebaseComponent::new(
+                repo.clone(),
+                queue.clone(),
+                theme.clone(),
+                key_config.clone(),
+            ),
+            rebase_preview_popup: RebasePreviewComponent::new(
+                repo.clone(),
+                queue.clone(),
+                theme.clone(),
+                key_config.clone(),
+                options.clone(),
+            ),
+            diff_base_popup: DiffBaseComponent::new(
+                repo.clone(),
+                queue.clone(),
+                theme.clone(),
+                key_config.clone(),
+            ),
+            goto_commit_popup: GotoCommitComponent::new(
+                repo.clone(),
+                queue.clone(),
+                theme.clone(),
+                key_config.clone(),
+            ),
+            fetch_options_popup: FetchOptionsComponent::new(
+                repo.clone(),
+                queue.clone(),
+                theme.clone(),
+                key_config.clone(),
+            ),
+            log_filter_popup: LogFilterComponent::new(
+                repo.clone(),
+                queue.clone(),
+                theme.clone(),
+                key_config.clone(),
+            ),
+            export_patch_popup: ExportPatchComponent::new(
+                repo.clone(),
+                queue.clone(),
+                theme.clone(),
+                key_config.clone(),
+            ),
+            apply_patch_popup: ApplyPatchComponent::new(
+                repo.clone(),
+                queue.clone(),
+                theme.clone(),
+                key_config.clone(),
+            ),
+            archive_commit_popup: ArchiveCommitComponent::new(
+                repo.clone(),
+                queue.clone(),
+                theme.clone(),
+                key_config.clone(),
+            ),
+            review_popup: ReviewComponent::new(
+                repo.clone(),
+                sender,
+                queue.clone(),
+                theme.clone(),
+                key_config.clone(),
+                options.clone(),
+            ),
+            context_menu: ContextMenuComponent::new(
                 queue.clone(),
                 theme.clone(),
                 key_config.clone(),
             ),
             do_quit: false,
+            pending_chord: Vec::new(),
             cmdbar: RefCell::new(CommandBar::new(
                 theme.clone(),
                 key_config.clone(),
@@ -174,24 +379,30 @@ impl App {
             msg: MsgComponent::new(theme.clone(), key_config.clone()),
             tab: 0,
             revlog: Revlog::new(
+                repo.clone(),
                 &queue,
                 sender,
                 theme.clone(),
                 key_config.clone(),
+                options.clone(),
             ),
             status_tab: Status::new(
+                repo.clone(),
                 &queue,
                 sender,
                 theme.clone(),
                 key_config.clone(),
+                options.clone(),
             ),
             stashing_tab: Stashing::new(
+                &repo,
                 sender,
                 &queue,
                 theme.clone(),
                 key_config.clone(),
             ),
             stashlist_tab: StashList::new(
+                repo.clone(),
                 &queue,
                 theme.clone(),
                 key_config.clone(),
@@ -199,8 +410,16 @@ impl App {
             queue,
             theme,
             key_config,
+            options,
+            instance_sync: InstanceSync::new(&repo),
+            perf_hud: PerfHud::default(),
+            journal: Journal::default(),
             requires_redraw: Cell::new(false),
             file_to_open: None,
+            pager_file_to_open: None,
+            repo_to_open: None,
+            repo_to_switch: None,
+            repo,
         }
     }
 
@@ -237,14 +456,62 @@ impl App {
 
         self.draw_popups(f)?;
 
+        self.perf_hud.draw(f, &self.theme);
+
+        Ok(())
+    }
+
+    /// time the last draw took, shown in the perf hud
+    pub fn set_frame_time(&mut self, time: Duration) {
+        self.perf_hud.set_frame_time(time);
+    }
+
+    /// events waiting to be processed, shown in the perf hud
+    pub fn set_backlog(&mut self, backlog: usize) {
+        self.perf_hud.set_backlog(backlog);
+    }
+
+    /// shows problems found in the config files when (re)loading them
+    pub fn show_config_errors(
+        &mut self,
+        mut errors: Vec<String>,
+    ) -> Result<()> {
+        errors.extend(self.options.borrow_mut().take_load_errors());
+
+        if !errors.is_empty() {
+            for e in &errors {
+                log::error!("config error: {}", e);
+            }
+            self.msg
+                .show_error(&strings::config_errors_msg(&errors))?;
+        }
+
+        Ok(())
+    }
+
+    /// theme and key config changed in place, the command bar has
+    /// to show the new keys
+    pub fn config_reloaded(
+        &mut self,
+        errors: Vec<String>,
+    ) -> Result<()> {
+        self.show_config_errors(errors)?;
+        self.update_commands();
+
         Ok(())
     }
 
     ///
     pub fn event(&mut self, ev: InputEvent) -> Result<()> {
         log::trace!("event: {:?}", ev);
+        crash_report::record_event(format!("input: {:?}", ev));
 
         if let InputEvent::Input(ev) = ev {
+            let ev = match self.continue_chord(ev) {
+                Some(ev) => self.mouse_wheel_to_key(ev),
+                None => return Ok(()),
+            };
+
             if self.check_quit_key(ev) {
                 return Ok(());
             }
@@ -256,22 +523,64 @@ impl App {
             {
                 flags.insert(NeedsUpdate::COMMANDS);
             } else if let Event::Key(k) = ev {
-                let new_flags = if k == self.key_config.tab_toggle {
+                let new_flags = if k
+                    == self.key_config.borrow().tab_toggle
+                {
                     self.toggle_tabs(false)?;
                     NeedsUpdate::COMMANDS
-                } else if k == self.key_config.tab_toggle_reverse {
+                } else if k
+                    == self.key_config.borrow().tab_toggle_reverse
+                {
                     self.toggle_tabs(true)?;
                     NeedsUpdate::COMMANDS
-                } else if k == self.key_config.tab_status
-                    || k == self.key_config.tab_log
-                    || k == self.key_config.tab_stashing
-                    || k == self.key_config.tab_stashes
+                } else if k == self.key_config.borrow().tab_status
+                    || k == self.key_config.borrow().tab_log
+                    || k == self.key_config.borrow().tab_stashing
+                    || k == self.key_config.borrow().tab_stashes
                 {
                     self.switch_tab(k)?;
                     NeedsUpdate::COMMANDS
-                } else if k == self.key_config.cmd_bar_toggle {
+                } else if k == self.key_config.borrow().cmd_bar_toggle
+                {
                     self.cmdbar.borrow_mut().toggle_more();
                     NeedsUpdate::empty()
+                } else if k
+                    == self.key_config.borrow().open_repo_switcher
+                {
+                    self.repo_switcher_popup.open()?;
+                    NeedsUpdate::COMMANDS
+                } else if k == self.key_config.borrow().open_hooks {
+                    self.hooks_popup.open()?;
+                    NeedsUpdate::COMMANDS
+                } else if k
+                    == self.key_config.borrow().open_command_palette
+                {
+                    self.command_palette
+                        .open(self.commands(false))?;
+                    NeedsUpdate::COMMANDS
+                } else if k == self.key_config.borrow().undo_operation
+                {
+                    self.step_journal(false)
+                } else if k == self.key_config.borrow().redo_operation
+                {
+                    self.step_journal(true)
+                } else if k
+                    == self.key_config.borrow().toggle_path_scope
+                    && self.path_scope().is_some()
+                {
+                    self.options.borrow_mut().toggle_path_scope();
+                    self.revlog.path_scope_changed()?;
+                    NeedsUpdate::ALL | NeedsUpdate::COMMANDS
+                } else if k
+                    == self.key_config.borrow().toggle_perf_hud
+                {
+                    self.perf_hud.toggle();
+                    NeedsUpdate::empty()
+                } else if self.key_config.borrow().chord_match(&[k])
+                    == ChordMatch::Prefix
+                {
+                    self.pending_chord.push(k);
+                    NeedsUpdate::empty()
                 } else {
                     NeedsUpdate::empty()
                 };
@@ -283,24 +592,7 @@ impl App {
         } else if let InputEvent::State(polling_state) = ev {
             self.external_editor_popup.hide();
             if let InputState::Paused = polling_state {
-                let result = match self.file_to_open.take() {
-                    Some(path) => {
-                        ExternalEditorComponent::open_file_in_editor(
-                            Path::new(&path),
-                        )
-                    }
-                    None => self.commit.show_editor(),
-                };
-
-                if let Err(e) = result {
-                    let msg =
-                        format!("failed to launch editor:\n{}", e);
-                    log::error!("{}", msg.as_str());
-                    self.msg.show_error(msg.as_str())?;
-                }
-
-                self.requires_redraw.set(true);
-                self.input.set_polling(true);
+                self.launch_external()?;
             }
         }
 
@@ -312,6 +604,29 @@ impl App {
     pub fn update(&mut self) -> Result<()> {
         log::trace!("update");
 
+        self.perf_hud.refresh_started();
+
+        let options_reloaded =
+            self.options.borrow_mut().reload_if_changed();
+        if options_reloaded {
+            clipboard::set_backend(self.options.borrow().clipboard());
+            asyncgit::set_network_limits(
+                self.options.borrow().network_limits(),
+            );
+            self.show_config_errors(Vec::new())?;
+        }
+
+        let changed_by_other = self.instance_sync.changed_by_other();
+        if changed_by_other && self.select_branch_popup.is_visible() {
+            self.select_branch_popup.update_branches()?;
+        }
+
+        if options_reloaded || changed_by_other {
+            self.status_tab.update_diff()?;
+            self.inspect_commit_popup.update_diff()?;
+            self.review_popup.update_diff()?;
+        }
+
         self.commit.update()?;
         self.status_tab.update()?;
         self.revlog.update()?;
@@ -330,14 +645,18 @@ impl App {
     ) -> Result<()> {
         log::trace!("update_git: {:?}", ev);
 
+        self.perf_hud.job_finished(ev);
+
         self.status_tab.update_git(ev)?;
         self.stashing_tab.update_git(ev)?;
         self.revlog.update_git(ev)?;
         self.blame_file_popup.update_git(ev)?;
         self.inspect_commit_popup.update_git(ev)?;
+        self.review_popup.update_git(ev)?;
         self.push_popup.update_git(ev)?;
         self.push_tags_popup.update_git(ev)?;
         self.pull_popup.update_git(ev)?;
+        self.select_branch_popup.update_git(ev)?;
 
         //TODO: better system for this
         // can we simply process the queue here and everyone just uses the queue to schedule a cmd update?
@@ -351,6 +670,27 @@ impl App {
         self.do_quit
     }
 
+    ///
+    pub const fn repo(&self) -> &RepoPath {
+        &self.repo
+    }
+
+    /// see `CliArgs::path_scope`
+    pub fn path_scope(&self) -> Option<String> {
+        self.options.borrow().path_scope_setting().map(String::from)
+    }
+
+    /// see `Options::status_watch_files`
+    pub fn watch_files(&self) -> bool {
+        self.options.borrow().status_watch_files()
+    }
+
+    /// workdir of the repository the user switched to, the app has
+    /// to be recreated inside of it
+    pub fn take_repo_switch(&mut self) -> Option<String> {
+        self.repo_to_switch.take()
+    }
+
     ///
     pub fn any_work_pending(&self) -> bool {
         self.status_tab.anything_pending()
@@ -358,10 +698,12 @@ impl App {
             || self.stashing_tab.anything_pending()
             || self.blame_file_popup.any_work_pending()
             || self.inspect_commit_popup.any_work_pending()
+            || self.review_popup.any_work_pending()
             || self.input.is_state_changing()
             || self.push_popup.any_work_pending()
             || self.push_tags_popup.any_work_pending()
             || self.pull_popup.any_work_pending()
+            || self.select_branch_popup.any_work_pending()
     }
 
     ///
@@ -382,18 +724,39 @@ impl App {
         [
             msg,
             reset,
+            context_menu,
             commit,
             blame_file_popup,
             stashmsg_popup,
             inspect_commit_popup,
             external_editor_popup,
             push_popup,
+            push_remote_popup,
+            ignore_path_popup,
+            open_link_popup,
             push_tags_popup,
             pull_popup,
             tag_commit_popup,
+            reset_commit_popup,
+            checkout_strategy_popup,
             create_branch_popup,
             rename_branch_popup,
+            upstream_branch_popup,
             select_branch_popup,
+            tag_list_popup,
+            repo_switcher_popup,
+            hooks_popup,
+            command_palette,
+            rebase_popup,
+            rebase_preview_popup,
+            diff_base_popup,
+            goto_commit_popup,
+            log_filter_popup,
+            export_patch_popup,
+            apply_patch_popup,
+            archive_commit_popup,
+            fetch_options_popup,
+            review_popup,
             revision_files_popup,
             help,
             revlog,
@@ -403,9 +766,52 @@ impl App {
         ]
     );
 
+    /// feeds `ev` to a started chord, `None` while the chord is
+    /// incomplete
+    fn continue_chord(&mut self, ev: Event) -> Option<Event> {
+        if self.pending_chord.is_empty() {
+            return Some(ev);
+        }
+
+        if let Event::Key(k) = ev {
+            self.pending_chord.push(k);
+
+            match self
+                .key_config
+                .borrow()
+                .chord_match(&self.pending_chord)
+            {
+                ChordMatch::Prefix => return None,
+                ChordMatch::Complete(action) => {
+                    self.pending_chord.clear();
+                    return Some(Event::Key(action));
+                }
+                ChordMatch::None => (),
+            }
+        }
+
+        self.pending_chord.clear();
+        Some(ev)
+    }
+
+    /// the wheel moves the focused list or diff like the arrow keys
+    fn mouse_wheel_to_key(&self, ev: Event) -> Event {
+        match ev {
+            Event::Mouse(MouseEvent {
+                kind: MouseEventKind::ScrollDown,
+                ..
+            }) => Event::Key(self.key_config.borrow().move_down),
+            Event::Mouse(MouseEvent {
+                kind: MouseEventKind::ScrollUp,
+                ..
+            }) => Event::Key(self.key_config.borrow().move_up),
+            _ => ev,
+        }
+    }
+
     fn check_quit_key(&mut self, ev: Event) -> bool {
         if let Event::Key(e) = ev {
-            if e == self.key_config.exit {
+            if e == self.key_config.borrow().exit {
                 self.do_quit = true;
                 return true;
             }
@@ -413,6 +819,52 @@ impl App {
         false
     }
 
+    /// runs the program the input was paused for: a nested gitui,
+    /// the pager or the editor
+    fn launch_external(&mut self) -> Result<()> {
+        let (result, program) = match (
+            self.repo_to_open.take(),
+            self.pager_file_to_open.take(),
+            self.file_to_open.take(),
+        ) {
+            (Some(path), _, _) => (
+                ExternalEditorComponent::open_nested_repo(
+                    &self.repo,
+                    Path::new(&path),
+                ),
+                "nested gitui",
+            ),
+            (None, Some(path), _) => (
+                ExternalEditorComponent::open_file_in_pager(
+                    &self.repo,
+                    Path::new(&path),
+                ),
+                "pager",
+            ),
+            (None, None, Some(path)) => (
+                ExternalEditorComponent::open_file_in_editor(
+                    &self.repo,
+                    Path::new(&path),
+                ),
+                "editor",
+            ),
+            (None, None, None) => {
+                (self.commit.show_editor(), "editor")
+            }
+        };
+
+        if let Err(e) = result {
+            let msg = format!("failed to launch {}:\n{}", program, e);
+            log::error!("{}", msg.as_str());
+            self.msg.show_error(msg.as_str())?;
+        }
+
+        self.requires_redraw.set(true);
+        self.input.set_polling(true);
+
+        Ok(())
+    }
+
     fn get_tabs(&mut self) -> Vec<&mut dyn Component> {
         vec![
             &mut self.status_tab,
@@ -434,20 +886,21 @@ impl App {
     }
 
     fn switch_tab(&mut self, k: KeyEvent) -> Result<()> {
-        if k == self.key_config.tab_status {
+        if k == self.key_config.borrow().tab_status {
             self.set_tab(0)?
-        } else if k == self.key_config.tab_log {
+        } else if k == self.key_config.borrow().tab_log {
             self.set_tab(1)?
-        } else if k == self.key_config.tab_stashing {
+        } else if k == self.key_config.borrow().tab_stashing {
             self.set_tab(2)?
-        } else if k == self.key_config.tab_stashes {
+        } else if k == self.key_config.borrow().tab_stashes {
             self.set_tab(3)?
         }
 
         Ok(())
     }
 
-    fn set_tab(&mut self, tab: usize) -> Result<()> {
+    /// selects the tab at `tab`
+    pub fn set_tab(&mut self, tab: usize) -> Result<()> {
         let tabs = self.get_tabs();
         for (i, t) in tabs.into_iter().enumerate() {
             if tab == i {
@@ -458,6 +911,7 @@ impl App {
         }
 
         self.tab = tab;
+        crash_report::set_state("tab", tab.to_string());
 
         Ok(())
     }
@@ -480,6 +934,7 @@ impl App {
         if flags.contains(NeedsUpdate::DIFF) {
             self.status_tab.update_diff()?;
             self.inspect_commit_popup.update_diff()?;
+            self.review_popup.update_diff()?;
         }
         if flags.contains(NeedsUpdate::COMMANDS) {
             self.update_commands();
@@ -511,6 +966,7 @@ impl App {
         &mut self,
         ev: InternalEvent,
     ) -> Result<NeedsUpdate> {
+        crash_report::record_event(format!("internal: {:?}", ev));
         let mut flags = NeedsUpdate::empty();
         match ev {
             InternalEvent::ConfirmedAction(action) => {
@@ -526,7 +982,74 @@ impl App {
                     .insert(NeedsUpdate::ALL | NeedsUpdate::COMMANDS);
             }
             InternalEvent::Update(u) => flags.insert(u),
+            InternalEvent::TabSwitch => self.set_tab(0)?,
+            InternalEvent::OpenExternalEditor(path) => {
+                self.input.set_polling(false);
+                self.external_editor_popup.show()?;
+                self.file_to_open = path;
+                flags.insert(NeedsUpdate::COMMANDS)
+            }
+            InternalEvent::OpenExternalPager(path) => {
+                self.input.set_polling(false);
+                self.pager_file_to_open = Some(path);
+                flags.insert(NeedsUpdate::COMMANDS)
+            }
+            InternalEvent::ExpandFolderStatus(path) => {
+                self.options.borrow_mut().set_path_scope(Some(path));
+                self.revlog.path_scope_changed()?;
+                flags.insert(NeedsUpdate::ALL);
+            }
+            InternalEvent::OpenNestedRepo(path) => {
+                self.input.set_polling(false);
+                self.repo_to_open = Some(path);
+                flags.insert(NeedsUpdate::ALL)
+            }
+            InternalEvent::SwitchRepo(path) => {
+                if self.any_work_pending() {
+                    self.msg.show_error(
+                        "cannot switch repository while git operations are in progress",
+                    )?;
+                } else {
+                    self.repo_to_switch = Some(path);
+                }
+            }
+            InternalEvent::StatusLastFileMoved => {
+                self.status_tab.last_file_moved()?;
+            }
+            InternalEvent::GotoCommit(id, rev) => {
+                self.revlog.goto_commit(id, rev)?;
+                flags.insert(NeedsUpdate::COMMANDS)
+            }
+            InternalEvent::FilterLog(query, filter) => {
+                self.revlog.set_filter(query, filter)?;
+                self.set_tab(1)?;
+                flags.insert(NeedsUpdate::ALL | NeedsUpdate::COMMANDS)
+            }
+            InternalEvent::SimulateKey(key) => {
+                self.event(InputEvent::Input(Event::Key(key)))?;
+            }
+            InternalEvent::SetDiffBase(base) => {
+                self.status_tab.set_diff_base(base)?;
+                flags.insert(NeedsUpdate::ALL | NeedsUpdate::COMMANDS)
+            }
+            ev => self.process_popup_event(ev, &mut flags)?,
+        };
+
+        Ok(flags)
+    }
+
+    /// opens the popups asked for by `ev`
+    fn process_popup_event(
+        &mut self,
+        ev: InternalEvent,
+        flags: &mut NeedsUpdate,
+    ) -> Result<()> {
+        match ev {
             InternalEvent::OpenCommit => self.commit.show()?,
+            InternalEvent::OpenRebase => self.rebase_popup.open()?,
+            InternalEvent::ContinueResolved => {
+                self.commit.continue_resolved()?;
+            }
             InternalEvent::PopupStashing(opts) => {
                 self.stashmsg_popup.options(opts);
                 self.stashmsg_popup.show()?
@@ -534,81 +1057,268 @@ impl App {
             InternalEvent::TagCommit(id) => {
                 self.tag_commit_popup.open(id)?;
             }
+            InternalEvent::OpenTags(id) => {
+                self.tag_list_popup.open(id)?;
+                flags.insert(NeedsUpdate::COMMANDS)
+            }
+            InternalEvent::ResetCommit(id) => {
+                self.reset_commit_popup.open(id)?;
+                flags.insert(NeedsUpdate::COMMANDS)
+            }
             InternalEvent::BlameFile(path) => {
                 self.blame_file_popup.open(&path)?;
                 flags.insert(NeedsUpdate::ALL | NeedsUpdate::COMMANDS)
             }
+            InternalEvent::RenameStash(id, message) => {
+                self.stashmsg_popup.open_rename(id, message)?;
+            }
+            InternalEvent::InspectCommit(id, tags) => {
+                self.inspect_commit_popup.open(id, tags)?;
+                flags.insert(NeedsUpdate::ALL | NeedsUpdate::COMMANDS)
+            }
+            InternalEvent::IgnorePath(path, is_folder) => {
+                self.ignore_path_popup.open(&path, is_folder)?;
+                flags.insert(NeedsUpdate::COMMANDS);
+            }
+            InternalEvent::OpenLinks(text) => {
+                let template = self
+                    .options
+                    .borrow()
+                    .issue_url_template()
+                    .map(String::from);
+                self.open_link_popup
+                    .open(&text, template.as_deref())?;
+                flags.insert(NeedsUpdate::COMMANDS);
+            }
+            InternalEvent::OpenFileTree(c) => {
+                self.revision_files_popup.open(c)?;
+                flags.insert(NeedsUpdate::ALL | NeedsUpdate::COMMANDS)
+            }
+            InternalEvent::OpenDiffBase => {
+                self.diff_base_popup
+                    .open(self.status_tab.diff_base_name())?;
+                flags.insert(NeedsUpdate::COMMANDS)
+            }
+            InternalEvent::OpenGotoCommit => {
+                self.goto_commit_popup.open()?;
+                flags.insert(NeedsUpdate::COMMANDS)
+            }
+            InternalEvent::OpenLogFilter => {
+                self.log_filter_popup
+                    .open(self.revlog.filter_query())?;
+                flags.insert(NeedsUpdate::COMMANDS)
+            }
+            InternalEvent::OpenArchiveCommit(id) => {
+                self.archive_commit_popup.open(id)?;
+                flags.insert(NeedsUpdate::COMMANDS)
+            }
+            InternalEvent::OpenApplyPatch => {
+                self.apply_patch_popup.open()?;
+                flags.insert(NeedsUpdate::COMMANDS)
+            }
+            InternalEvent::ExportPatch(id) => {
+                self.export_patch_popup.open(id)?;
+                flags.insert(NeedsUpdate::COMMANDS)
+            }
+            InternalEvent::OpenContextMenu(items) => {
+                self.context_menu.open(items)?;
+                flags.insert(NeedsUpdate::COMMANDS)
+            }
+            ev => self.process_branch_event(ev, flags)?,
+        };
+
+        Ok(())
+    }
+
+    /// branch popups, pushing, fetching and reviewing branches
+    fn process_branch_event(
+        &mut self,
+        ev: InternalEvent,
+        flags: &mut NeedsUpdate,
+    ) -> Result<()> {
+        match ev {
+            InternalEvent::PreviewRebase(branch) => {
+                self.rebase_preview_popup.open(branch)?;
+            }
+            InternalEvent::CheckoutStrategy(branch_ref) => {
+                self.checkout_strategy_popup.open(branch_ref)?;
+                flags.insert(NeedsUpdate::COMMANDS)
+            }
             InternalEvent::CreateBranch => {
                 self.create_branch_popup.open()?;
             }
+            InternalEvent::CreateBranchAt(id) => {
+                self.create_branch_popup.open_at(id)?;
+            }
+            InternalEvent::StartFlowBranch => {
+                self.create_branch_popup.open_flow()?;
+            }
+            InternalEvent::BranchFromStash(id) => {
+                self.create_branch_popup.open_from_stash(id)?;
+            }
             InternalEvent::RenameBranch(branch_ref, cur_name) => {
                 self.rename_branch_popup
                     .open(branch_ref, cur_name)?;
             }
+            InternalEvent::SetUpstream(branch) => {
+                self.upstream_branch_popup.open(branch)?;
+            }
             InternalEvent::SelectBranch => {
                 self.select_branch_popup.open()?;
             }
-            InternalEvent::TabSwitch => self.set_tab(0)?,
-            InternalEvent::InspectCommit(id, tags) => {
-                self.inspect_commit_popup.open(id, tags)?;
-                flags.insert(NeedsUpdate::ALL | NeedsUpdate::COMMANDS)
-            }
-            InternalEvent::OpenExternalEditor(path) => {
-                self.input.set_polling(false);
-                self.external_editor_popup.show()?;
-                self.file_to_open = path;
-                flags.insert(NeedsUpdate::COMMANDS)
-            }
             InternalEvent::Push(branch, force) => {
-                self.push_popup.push(branch, force)?;
+                if sync::get_branch_remote(&self.repo, &branch)?
+                    .is_some()
+                {
+                    self.push_popup.push(branch, None, force)?;
+                } else {
+                    self.push_remote_popup.open(branch, force)?;
+                }
+                flags.insert(NeedsUpdate::ALL)
+            }
+            InternalEvent::PushTo(branch, remote, force) => {
+                self.push_popup.push(branch, Some(remote), force)?;
                 flags.insert(NeedsUpdate::ALL)
             }
             InternalEvent::Pull(branch) => {
                 self.pull_popup.fetch(branch)?;
                 flags.insert(NeedsUpdate::ALL)
             }
-            InternalEvent::PushTags => {
-                self.push_tags_popup.push_tags()?;
+            InternalEvent::FetchRemoteBranches => {
+                self.pull_popup.fetch_all_branches()?;
                 flags.insert(NeedsUpdate::ALL)
             }
-            InternalEvent::StatusLastFileMoved => {
-                self.status_tab.last_file_moved()?;
+            InternalEvent::OpenFetchOptions(branch) => {
+                self.fetch_options_popup.open(branch)?;
+                flags.insert(NeedsUpdate::COMMANDS)
             }
-            InternalEvent::OpenFileTree(c) => {
-                self.revision_files_popup.open(c)?;
-                flags.insert(NeedsUpdate::ALL | NeedsUpdate::COMMANDS)
+            InternalEvent::FetchDepth(branch, depth) => {
+                self.pull_popup.fetch_depth(branch, depth)?;
+                flags.insert(NeedsUpdate::ALL)
+            }
+            InternalEvent::PushTags(tags, force) => {
+                self.push_tags_popup.push_tags(tags, force)?;
+                flags.insert(NeedsUpdate::ALL)
             }
+            InternalEvent::OpenReview => {
+                self.review_popup.open()?;
+                flags.insert(NeedsUpdate::COMMANDS)
+            }
+            InternalEvent::ReviewBranch(branch) => {
+                if let Err(e) = self.review_popup.open_branch(&branch)
+                {
+                    self.queue.borrow_mut().push_back(
+                        InternalEvent::ShowErrorMsg(format!(
+                            "review error:\n{}",
+                            e
+                        )),
+                    );
+                }
+                flags.insert(NeedsUpdate::COMMANDS)
+            }
+            // handled by `process_internal_event` and
+            // `process_popup_event`
+            _ => (),
         };
 
-        Ok(flags)
+        Ok(())
     }
 
+    /// journals destructive actions, an action that does not ask
+    /// for an update failed and changed nothing
     fn process_confirmed_action(
         &mut self,
         action: Action,
         flags: &mut NeedsUpdate,
     ) -> Result<()> {
-        match action {
-            Action::Reset(r) => {
-                if self.status_tab.reset(&r) {
-                    flags.insert(NeedsUpdate::ALL);
+        let journaled = match Self::journal_op(&action) {
+            Some(op) => {
+                if let Err(e) = self.journal.record(&self.repo, op) {
+                    log::error!("journal error: {}", e);
+                    false
+                } else {
+                    true
                 }
             }
-            Action::StashDrop(_) | Action::StashPop(_) => {
-                if self.stashlist_tab.action_confirmed(&action) {
-                    flags.insert(NeedsUpdate::ALL);
-                }
+            None => false,
+        };
+
+        let mut action_flags = NeedsUpdate::empty();
+        let res =
+            self.run_confirmed_action(action, &mut action_flags);
+
+        if journaled
+            && (res.is_err()
+                || !action_flags.contains(NeedsUpdate::ALL))
+        {
+            self.journal.cancel();
+        }
+
+        flags.insert(action_flags);
+
+        res
+    }
+
+    fn journal_op(action: &Action) -> Option<JournalOp> {
+        match action {
+            Action::Reset(item) => {
+                Some(JournalOp::Discard(vec![item.path.clone()]))
             }
-            Action::ResetHunk(path, hash) => {
-                sync::reset_hunk(CWD, &path, hash)?;
-                flags.insert(NeedsUpdate::ALL);
+            Action::ResetHunk(path, _)
+            | Action::ResetLines(path, _) => {
+                Some(JournalOp::Discard(vec![path.clone()]))
             }
-            Action::ResetLines(path, lines) => {
-                sync::discard_lines(CWD, &path, &lines)?;
-                flags.insert(NeedsUpdate::ALL);
+            // no paths would mean everything to the journal
+            Action::ResetPaths(paths) if !paths.is_empty() => {
+                Some(JournalOp::Discard(paths.clone()))
+            }
+            Action::DiscardAll => {
+                Some(JournalOp::Discard(Vec::new()))
             }
+            Action::StashDrop(_) => Some(JournalOp::DropStash),
             Action::DeleteBranch(branch_ref) => {
-                if let Err(e) = sync::delete_branch(CWD, &branch_ref)
+                Some(JournalOp::DeleteBranch(branch_ref.clone()))
+            }
+            Action::UndoCommit => Some(JournalOp::UndoCommit),
+            Action::ResetToCommit(_, kind) => {
+                Some(JournalOp::Reset(*kind))
+            }
+            _ => None,
+        }
+    }
+
+    /// undoes the last journaled action or redoes the last undone
+    fn step_journal(&mut self, redo: bool) -> NeedsUpdate {
+        let res = if redo {
+            self.journal.redo(&self.repo)
+        } else {
+            self.journal.undo(&self.repo)
+        };
+
+        match res {
+            Ok(Some(_)) => NeedsUpdate::ALL | NeedsUpdate::COMMANDS,
+            Ok(None) => NeedsUpdate::empty(),
+            Err(e) => {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::ShowErrorMsg(format!(
+                        "undo error:\n{}",
+                        e
+                    )),
+                );
+                NeedsUpdate::empty()
+            }
+        }
+    }
+
+    fn run_confirmed_action(
+        &mut self,
+        action: Action,
+        flags: &mut NeedsUpdate,
+    ) -> Result<()> {
+        match action {
+            Action::DeleteBranch(branch_ref) => {
+                if let Err(e) =
+                    sync::delete_branch(&self.repo, &branch_ref)
                 {
                     self.queue.borrow_mut().push_back(
                         InternalEvent::ShowErrorMsg(e.to_string()),
@@ -618,6 +1328,30 @@ impl App {
                     self.select_branch_popup.update_branches()?;
                 }
             }
+            Action::FlowFinish(kind, name) => {
+                let naming = self.options.borrow().flow_naming();
+                if let Err(e) = sync::flow_finish(
+                    &self.repo, &naming, kind, &name,
+                ) {
+                    self.queue.borrow_mut().push_back(
+                        InternalEvent::ShowErrorMsg(format!(
+                            "finish branch error:\n{}",
+                            e
+                        )),
+                    );
+                } else {
+                    flags.insert(NeedsUpdate::ALL);
+                    self.select_branch_popup.update_branches()?;
+                }
+            }
+            Action::DeleteTags(tags) => {
+                self.delete_tags(&tags)?;
+                flags.insert(NeedsUpdate::ALL);
+            }
+            Action::Retag(tag, id) => {
+                self.retag(tag, id)?;
+                flags.insert(NeedsUpdate::ALL);
+            }
             Action::ForcePush(branch, force) => self
                 .queue
                 .borrow_mut()
@@ -626,15 +1360,137 @@ impl App {
                 self.pull_popup.try_conflict_free_merge(rebase);
                 flags.insert(NeedsUpdate::ALL);
             }
+            Action::ForceCheckout(branch_ref, _) => {
+                sync::checkout_with_strategy(
+                    &self.repo,
+                    &branch_ref,
+                    sync::CheckoutStrategy::Force,
+                )?;
+                flags.insert(NeedsUpdate::ALL);
+            }
+            action => self.run_reset_action(action, flags)?,
+        };
+
+        Ok(())
+    }
+
+    /// actions resetting changes or commits
+    fn run_reset_action(
+        &mut self,
+        action: Action,
+        flags: &mut NeedsUpdate,
+    ) -> Result<()> {
+        match action {
+            Action::Reset(r) => {
+                if self.status_tab.reset(&r) {
+                    flags.insert(NeedsUpdate::ALL);
+                }
+            }
+            Action::StashDrop(_) | Action::StashPop(_) => {
+                if self.stashlist_tab.action_confirmed(&action) {
+                    flags.insert(NeedsUpdate::ALL);
+                }
+            }
+            Action::ResetHunk(path, hash) => {
+                sync::reset_hunk_workdir(
+                    &self.repo,
+                    &path,
+                    hash,
+                    Some(self.options.borrow().diff_options()),
+                )?;
+                flags.insert(NeedsUpdate::ALL);
+            }
+            Action::ResetLines(path, lines) => {
+                sync::reset_lines_workdir(&self.repo, &path, &lines)?;
+                flags.insert(NeedsUpdate::ALL);
+            }
+            Action::ResetPaths(paths) => {
+                sync::reset_workdir_paths(&self.repo, &paths)?;
+                flags.insert(NeedsUpdate::ALL);
+            }
             Action::AbortMerge => {
                 self.status_tab.abort_merge();
                 flags.insert(NeedsUpdate::ALL);
             }
+            Action::AbortRebase => {
+                sync::abort_rebase(&self.repo)?;
+                self.rebase_popup.hide();
+                flags.insert(NeedsUpdate::ALL);
+            }
+            Action::DiscardAll => {
+                sync::discard_all(&self.repo)?;
+                flags.insert(NeedsUpdate::ALL);
+            }
+            Action::UndoCommit => {
+                if let Err(e) =
+                    sync::reset_soft_head_parent(&self.repo)
+                {
+                    self.queue.borrow_mut().push_back(
+                        InternalEvent::ShowErrorMsg(format!(
+                            "undo commit error:\n{}",
+                            e
+                        )),
+                    );
+                } else {
+                    flags.insert(NeedsUpdate::ALL);
+                }
+            }
+            Action::ResetToCommit(id, kind) => {
+                if let Err(e) =
+                    sync::reset_to_commit(&self.repo, id, kind)
+                {
+                    self.queue.borrow_mut().push_back(
+                        InternalEvent::ShowErrorMsg(format!(
+                            "reset error:\n{}",
+                            e
+                        )),
+                    );
+                } else {
+                    flags.insert(NeedsUpdate::ALL);
+                }
+            }
+            // handled by `run_confirmed_action`
+            _ => (),
         };
 
         Ok(())
     }
 
+    /// stops at the first tag that can not be deleted
+    fn delete_tags(&mut self, tags: &[String]) -> Result<()> {
+        for tag in tags {
+            if let Err(e) = sync::delete_tag(&self.repo, tag) {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::ShowErrorMsg(format!(
+                        "delete tag error:\n{}",
+                        e
+                    )),
+                );
+                break;
+            }
+        }
+
+        self.tag_list_popup.update_tags()
+    }
+
+    /// moves the tag and force pushes it
+    fn retag(&mut self, tag: String, id: CommitId) -> Result<()> {
+        if let Err(e) = sync::retag(&self.repo, &tag, id) {
+            self.queue.borrow_mut().push_back(
+                InternalEvent::ShowErrorMsg(format!(
+                    "move tag error:\n{}",
+                    e
+                )),
+            );
+        } else {
+            self.queue
+                .borrow_mut()
+                .push_back(InternalEvent::PushTags(vec![tag], true));
+        }
+
+        self.tag_list_popup.update_tags()
+    }
+
     fn commands(&self, force_all: bool) -> Vec<CommandInfo> {
         let mut res = Vec::new();
 
@@ -653,7 +1509,8 @@ impl App {
                 true,
                 !self.any_popup_visible(),
             )
-            .order(order::NAV),
+            .order(order::NAV)
+            .key(self.key_config.borrow().tab_toggle),
         );
         res.push(
             CommandInfo::new(
@@ -665,6 +1522,18 @@ impl App {
             )
             .order(order::NAV),
         );
+        self.commands_app(&mut res);
+
+        res.push(
+            CommandInfo::new(
+                strings::commands::open_command_palette(
+                    &self.key_config,
+                ),
+                true,
+                !self.any_popup_visible(),
+            )
+            .order(order::RARE_ACTION),
+        );
 
         res.push(
             CommandInfo::new(
@@ -678,6 +1547,73 @@ impl App {
         res
     }
 
+    /// commands of the app available on every tab
+    fn commands_app(&self, res: &mut Vec<CommandInfo>) {
+        res.push(
+            CommandInfo::new(
+                strings::commands::open_repo_switcher(
+                    &self.key_config,
+                ),
+                true,
+                !self.any_popup_visible(),
+            )
+            .order(order::RARE_ACTION)
+            .key(self.key_config.borrow().open_repo_switcher),
+        );
+        res.push(
+            CommandInfo::new(
+                strings::commands::open_hooks(&self.key_config),
+                true,
+                !self.any_popup_visible(),
+            )
+            .order(order::RARE_ACTION)
+            .key(self.key_config.borrow().open_hooks),
+        );
+        res.push(
+            CommandInfo::new(
+                strings::commands::undo_operation(
+                    &self.key_config,
+                    self.journal.last_undo(),
+                ),
+                self.journal.last_undo().is_some(),
+                !self.any_popup_visible(),
+            )
+            .key(self.key_config.borrow().undo_operation),
+        );
+        res.push(
+            CommandInfo::new(
+                strings::commands::redo_operation(
+                    &self.key_config,
+                    self.journal.last_redo(),
+                ),
+                self.journal.last_redo().is_some(),
+                !self.any_popup_visible(),
+            )
+            .key(self.key_config.borrow().redo_operation),
+        );
+        res.push(
+            CommandInfo::new(
+                strings::commands::toggle_path_scope(
+                    &self.key_config,
+                    self.options.borrow().path_scope().is_some(),
+                ),
+                true,
+                self.path_scope().is_some()
+                    && !self.any_popup_visible(),
+            )
+            .key(self.key_config.borrow().toggle_path_scope),
+        );
+        res.push(
+            CommandInfo::new(
+                strings::commands::toggle_perf_hud(&self.key_config),
+                true,
+                !self.any_popup_visible(),
+            )
+            .order(order::RARE_ACTION)
+            .key(self.key_config.borrow().toggle_perf_hud),
+        );
+    }
+
     //TODO: make this automatic, i keep forgetting to add popups here
     fn any_popup_visible(&self) -> bool {
         self.commit.is_visible()
@@ -689,13 +1625,34 @@ impl App {
             || self.blame_file_popup.is_visible()
             || self.external_editor_popup.is_visible()
             || self.tag_commit_popup.is_visible()
+            || self.tag_list_popup.is_visible()
+            || self.reset_commit_popup.is_visible()
+            || self.checkout_strategy_popup.is_visible()
             || self.create_branch_popup.is_visible()
             || self.push_popup.is_visible()
+            || self.push_remote_popup.is_visible()
+            || self.ignore_path_popup.is_visible()
+            || self.open_link_popup.is_visible()
             || self.push_tags_popup.is_visible()
             || self.pull_popup.is_visible()
             || self.select_branch_popup.is_visible()
             || self.rename_branch_popup.is_visible()
+            || self.upstream_branch_popup.is_visible()
             || self.revision_files_popup.is_visible()
+            || self.repo_switcher_popup.is_visible()
+            || self.hooks_popup.is_visible()
+            || self.rebase_popup.is_visible()
+            || self.rebase_preview_popup.is_visible()
+            || self.command_palette.is_visible()
+            || self.diff_base_popup.is_visible()
+            || self.goto_commit_popup.is_visible()
+            || self.log_filter_popup.is_visible()
+            || self.export_patch_popup.is_visible()
+            || self.apply_patch_popup.is_visible()
+            || self.archive_commit_popup.is_visible()
+            || self.fetch_options_popup.is_visible()
+            || self.review_popup.is_visible()
+            || self.context_menu.is_visible()
     }
 
     fn draw_popups<B: Backend>(
@@ -720,13 +1677,34 @@ impl App {
         self.blame_file_popup.draw(f, size)?;
         self.external_editor_popup.draw(f, size)?;
         self.tag_commit_popup.draw(f, size)?;
+        self.reset_commit_popup.draw(f, size)?;
+        self.checkout_strategy_popup.draw(f, size)?;
         self.select_branch_popup.draw(f, size)?;
+        self.tag_list_popup.draw(f, size)?;
         self.create_branch_popup.draw(f, size)?;
         self.rename_branch_popup.draw(f, size)?;
+        self.upstream_branch_popup.draw(f, size)?;
         self.revision_files_popup.draw(f, size)?;
+        self.repo_switcher_popup.draw(f, size)?;
+        self.hooks_popup.draw(f, size)?;
+        self.rebase_popup.draw(f, size)?;
+        self.rebase_preview_popup.draw(f, size)?;
+        self.command_palette.draw(f, size)?;
+        self.diff_base_popup.draw(f, size)?;
+        self.goto_commit_popup.draw(f, size)?;
+        self.log_filter_popup.draw(f, size)?;
+        self.export_patch_popup.draw(f, size)?;
+        self.apply_patch_popup.draw(f, size)?;
+        self.archive_commit_popup.draw(f, size)?;
+        self.fetch_options_popup.draw(f, size)?;
+        self.review_popup.draw(f, size)?;
+        self.push_remote_popup.draw(f, size)?;
+        self.ignore_path_popup.draw(f, size)?;
+        self.open_link_popup.draw(f, size)?;
         self.push_popup.draw(f, size)?;
         self.push_tags_popup.draw(f, size)?;
         self.pull_popup.draw(f, size)?;
+        self.context_menu.draw(f, size)?;
         self.reset.draw(f, size)?;
         self.msg.draw(f, size)?;
 
@@ -740,6 +1718,15 @@ impl App {
             horizontal: 1,
         });
 
+        if let Some(scope) = self.options.borrow().path_scope() {
+            f.render_widget(
+                Paragraph::new(strings::path_scope(scope))
+                    .style(self.theme.borrow().tab(true))
+                    .alignment(Alignment::Right),
+                r,
+            );
+        }
+
         let tabs = [
             Span::raw(strings::tab_status(&self.key_config)),
             Span::raw(strings::tab_log(&self.key_config)),
@@ -756,10 +1743,12 @@ impl App {
                 .block(
                     Block::default()
                         .borders(Borders::BOTTOM)
-                        .border_style(self.theme.block(false)),
+                        .border_style(
+                            self.theme.borrow().block(false),
+                        ),
                 )
-                .style(self.theme.tab(false))
-                .highlight_style(self.theme.tab(true))
+                .style(self.theme.borrow().tab(false))
+                .highlight_style(self.theme.borrow().tab(true))
                 .divider(strings::tab_divider(&self.key_config))
                 .select(self.tab),
             r,
@@ -1,22 +1,34 @@
-use crate::bug_report;
+use crate::{
+    bug_report, config, keys::KeyConfig, settings_bundle,
+    ui::style::Theme,
+};
 use anyhow::{anyhow, Result};
+use asyncgit::{
+    sync::{self, RepoPath},
+    CWD,
+};
 use clap::{
     crate_authors, crate_description, crate_name, crate_version,
     App as ClapApp, Arg,
 };
 use simplelog::{Config, LevelFilter, WriteLogger};
+#[cfg(test)]
+use std::cell::RefCell;
 use std::{
     env,
     fs::{self, File},
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
 
 pub struct CliArgs {
     pub theme: PathBuf,
+    /// folder (relative to the workdir) the status and log are
+    /// restricted to
+    pub path_scope: Option<String>,
 }
 
-pub fn process_cmdline() -> Result<CliArgs> {
-    let app = ClapApp::new(crate_name!())
+fn app() -> ClapApp<'static, 'static> {
+    ClapApp::new(crate_name!())
         .author(crate_authors!())
         .version(crate_version!())
         .about(crate_description!())
@@ -39,19 +51,51 @@ pub fn process_cmdline() -> Result<CliArgs> {
                 .help("Generate a bug report")
                 .long("bugreport"),
         )
+        .arg(
+            Arg::with_name("default-config")
+                .help("Write commented default key config and theme files and the vim key preset")
+                .long("default-config"),
+        )
+        .arg(
+            Arg::with_name("export-settings")
+                .help("Write key config, theme and options into a single file")
+                .long("export-settings")
+                .value_name("FILE")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("import-settings")
+                .help("Replace key config, theme and options by the ones exported to a file or url")
+                .long("import-settings")
+                .value_name("FILE_OR_URL")
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name("directory")
                 .help("Set the working directory")
                 .short("d")
                 .long("directory")
                 .takes_value(true),
-        );
+        )
+        .arg(
+            Arg::with_name("path-scope")
+                .help("Only show the status and log of this folder of a monorepo")
+                .long("path-scope")
+                .value_name("DIR")
+                .takes_value(true),
+        )
+}
 
-    let arg_matches = app.get_matches();
+pub fn process_cmdline() -> Result<CliArgs> {
+    let arg_matches = app().get_matches();
     if arg_matches.is_present("bugreport") {
         bug_report::generate_bugreport()?;
         std::process::exit(0);
     }
+    if arg_matches.is_present("default-config") {
+        write_default_configs()?;
+        std::process::exit(0);
+    }
     if arg_matches.is_present("logging") {
         setup_logging()?;
     }
@@ -62,17 +106,111 @@ pub fn process_cmdline() -> Result<CliArgs> {
     }
     let arg_theme =
         arg_matches.value_of("theme").unwrap_or("theme.ron");
-    if get_app_config_path()?.join(arg_theme).is_file() {
-        Ok(CliArgs {
-            theme: get_app_config_path()?.join(arg_theme),
-        })
+    let theme = if get_app_config_path()?.join(arg_theme).is_file() {
+        get_app_config_path()?.join(arg_theme)
+    } else {
+        get_app_config_path()?.join("theme.ron")
+    };
+
+    if let Some(file) = arg_matches.value_of("export-settings") {
+        settings_bundle::export(
+            Path::new(file),
+            &theme,
+            current_repo().as_ref(),
+        )?;
+        std::process::exit(0);
+    }
+    if let Some(source) = arg_matches.value_of("import-settings") {
+        settings_bundle::import(
+            source,
+            &theme,
+            current_repo().as_ref(),
+        )?;
+        std::process::exit(0);
+    }
+
+    let path_scope = arg_matches
+        .value_of("path-scope")
+        .map(path_scope)
+        .transpose()?
+        // the whole repository
+        .filter(|scope| !scope.is_empty());
+
+    Ok(CliArgs { theme, path_scope })
+}
+
+/// `dir` relative to the workdir of the current repository
+fn path_scope(dir: &str) -> Result<String> {
+    let repo = current_repo()
+        .ok_or_else(|| anyhow!("--path-scope needs a repository"))?;
+    let workdir = Path::new(&sync::utils::repo_work_dir(&repo)?)
+        .canonicalize()?;
+    let dir = Path::new(dir)
+        .canonicalize()
+        .map_err(|e| anyhow!("--path-scope {}: {}", dir, e))?;
+
+    if !dir.is_dir() {
+        return Err(anyhow!(
+            "--path-scope {} is no folder",
+            dir.display()
+        ));
+    }
+
+    Ok(dir
+        .strip_prefix(&workdir)
+        .map_err(|_| {
+            anyhow!(
+                "--path-scope {} is outside of the repository",
+                dir.display()
+            )
+        })?
+        .to_str()
+        .ok_or_else(|| anyhow!("--path-scope is no valid utf8"))?
+        .replace('\\', "/"))
+}
+
+/// the repository in the working directory, its options are part of
+/// the exported settings
+fn current_repo() -> Option<RepoPath> {
+    let repo = RepoPath::from_env(CWD);
+    if sync::is_repo(&repo) {
+        Some(repo)
     } else {
-        Ok(CliArgs {
-            theme: get_app_config_path()?.join("theme.ron"),
-        })
+        None
     }
 }
 
+/// writes the commented defaults next to the real config files so
+/// they never get overwritten
+fn write_default_configs() -> Result<()> {
+    let path = get_app_config_path()?;
+
+    for (name, data) in &[
+        (
+            "key_config.default.ron",
+            config::commented_default::<KeyConfig>(
+                "gitui key config, rename to key_config.ron and\nuncomment the entries to change",
+            )?,
+        ),
+        (
+            "theme.default.ron",
+            config::commented_default::<Theme>(
+                "gitui theme, rename to theme.ron and\nuncomment the entries to change",
+            )?,
+        ),
+        (
+            "key_config.vim.ron",
+            include_str!("../vim_style_key_config.ron").to_string(),
+        ),
+    ] {
+        let file = path.join(name);
+        fs::write(&file, data)?;
+        println!("written: {}", file.to_string_lossy());
+    }
+
+    Ok(())
+}
+
 fn setup_logging() -> Result<()> {
     let mut path = get_app_cache_path()?;
     path.push("gitui.log");
@@ -86,24 +224,89 @@ fn setup_logging() -> Result<()> {
     Ok(())
 }
 
-fn get_app_cache_path() -> Result<PathBuf> {
-    let mut path = dirs_next::cache_dir()
-        .ok_or_else(|| anyhow!("failed to find os cache dir."))?;
+/// overrides the folder of the config files
+const CONFIG_DIR_ENV: &str = "GITUI_CONFIG_DIR";
+/// overrides the folder of logs and other data that can be deleted
+const CACHE_DIR_ENV: &str = "GITUI_CACHE_DIR";
+/// folder next to the executable that enables the portable mode,
+/// config and cache are kept in it instead of the os folders
+const PORTABLE_DIR: &str = "gitui-portable";
 
-    path.push("gitui");
-    fs::create_dir_all(&path)?;
-    Ok(path)
+#[cfg(test)]
+thread_local! {
+    static TEST_APP_DIR: RefCell<Option<PathBuf>> = RefCell::new(None);
+}
+
+/// keeps config and cache of the test running on the calling thread
+/// in `dir` (like the portable mode does). unlike the environment
+/// variables this does not leak into the tests running in parallel
+#[cfg(test)]
+pub fn set_test_app_dir(dir: Option<PathBuf>) {
+    TEST_APP_DIR.with(|test_dir| *test_dir.borrow_mut() = dir);
 }
 
+#[cfg(test)]
+fn test_app_dir() -> Option<PathBuf> {
+    TEST_APP_DIR.with(|dir| dir.borrow().clone())
+}
+
+#[cfg(not(test))]
+const fn test_app_dir() -> Option<PathBuf> {
+    None
+}
+
+/// logs, recent repositories and temporary files
+pub fn get_app_cache_path() -> Result<PathBuf> {
+    app_dir(CACHE_DIR_ENV, "cache", || {
+        dirs_next::cache_dir()
+            .ok_or_else(|| anyhow!("failed to find os cache dir."))
+    })
+}
+
+/// key config, themes
 pub fn get_app_config_path() -> Result<PathBuf> {
-    let mut path = if cfg!(target_os = "macos") {
-        dirs_next::home_dir().map(|h| h.join(".config"))
+    app_dir(CONFIG_DIR_ENV, "config", || {
+        if cfg!(target_os = "macos") {
+            dirs_next::home_dir().map(|h| h.join(".config"))
+        } else {
+            dirs_next::config_dir()
+        }
+        .ok_or_else(|| anyhow!("failed to find os config dir."))
+    })
+}
+
+/// the folder from `env_var`, the portable folder or the `gitui`
+/// folder inside of the os folder returned by `os_dir`
+fn app_dir<F>(
+    env_var: &str,
+    portable_sub_dir: &str,
+    os_dir: F,
+) -> Result<PathBuf>
+where
+    F: FnOnce() -> Result<PathBuf>,
+{
+    let path = if let Some(dir) = test_app_dir() {
+        dir.join(portable_sub_dir)
+    } else if let Some(dir) =
+        env::var_os(env_var).filter(|dir| !dir.is_empty())
+    {
+        PathBuf::from(dir)
+    } else if let Some(dir) = portable_dir() {
+        dir.join(portable_sub_dir)
     } else {
-        dirs_next::config_dir()
-    }
-    .ok_or_else(|| anyhow!("failed to find os config dir."))?;
+        os_dir()?.join("gitui")
+    };
 
-    path.push("gitui");
     fs::create_dir_all(&path)?;
     Ok(path)
 }
+
+fn portable_dir() -> Option<PathBuf> {
+    let dir = env::current_exe().ok()?.parent()?.join(PORTABLE_DIR);
+
+    if dir.is_dir() {
+        Some(dir)
+    } else {
+        None
+    }
+}
@@ -18,6 +18,8 @@ pub fn generate_bugreport() -> Result<()> {
             "EDITOR",
             "GIT_EDITOR",
             "VISUAL",
+            "GITUI_CONFIG_DIR",
+            "GITUI_CACHE_DIR",
         ]))
         .info(CommandLine::default())
         .print::<Markdown>();
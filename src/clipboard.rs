@@ -1,9 +1,85 @@
 use anyhow::{anyhow, Result};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::env;
 #[cfg(target_family = "unix")]
 #[cfg(not(target_os = "macos"))]
 use std::ffi::OsStr;
-use std::io::Write;
+use std::io::{self, Write};
 use std::process::{Command, Stdio};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+/// how `copy_string` gets the text into the clipboard
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ClipboardBackend {
+    /// OSC52 over ssh, otherwise the platform tool falling back to
+    /// OSC52 if it fails
+    Auto,
+    /// only the platform tool (xclip/xsel, pbcopy, clip)
+    Native,
+    /// only the OSC52 escape sequence, for terminals over ssh or in
+    /// tmux where no clipboard tool can be spawned
+    Osc52,
+}
+
+impl Default for ClipboardBackend {
+    fn default() -> Self {
+        Self::Auto
+    }
+}
+
+static BACKEND: Lazy<Mutex<ClipboardBackend>> =
+    Lazy::new(Mutex::default);
+
+fn backend() -> MutexGuard<'static, ClipboardBackend> {
+    BACKEND.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// picks the backend of all following copies (see `Options`)
+pub fn set_backend(backend_to_use: ClipboardBackend) {
+    *backend() = backend_to_use;
+}
+
+/// copies `text` to the system clipboard using the configured
+/// `ClipboardBackend`
+pub fn copy_string(text: &str) -> Result<()> {
+    let selected = *backend();
+    match selected {
+        ClipboardBackend::Native => copy_string_native(text),
+        ClipboardBackend::Osc52 => copy_string_osc52(text),
+        ClipboardBackend::Auto if is_remote_session() => {
+            copy_string_osc52(text)
+        }
+        ClipboardBackend::Auto => {
+            copy_string_native(text).or_else(|e| {
+                log::warn!("clipboard: {}, falling back to OSC52", e);
+                copy_string_osc52(text)
+            })
+        }
+    }
+}
+
+fn is_remote_session() -> bool {
+    env::var_os("SSH_CONNECTION").is_some()
+        || env::var_os("SSH_TTY").is_some()
+}
+
+/// the terminal (even one on the other side of a ssh connection) sets
+/// its clipboard on this escape sequence, tmux needs it wrapped
+fn copy_string_osc52(text: &str) -> Result<()> {
+    let sequence = format!("\x1b]52;c;{}\x07", base64::encode(text));
+    let sequence = if env::var_os("TMUX").is_some() {
+        format!("\x1bPtmux;\x1b{}\x1b\\", sequence)
+    } else {
+        sequence
+    };
+
+    let mut stdout = io::stdout();
+    stdout.write_all(sequence.as_bytes())?;
+    stdout.flush()?;
+
+    Ok(())
+}
 
 fn execute_copy_command(command: Command, text: &str) -> Result<()> {
     let mut command = command;
@@ -28,6 +104,23 @@ fn execute_copy_command(command: Command, text: &str) -> Result<()> {
     Ok(())
 }
 
+fn execute_paste_command(command: Command) -> Result<String> {
+    let mut command = command;
+
+    let output = command
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .map_err(|e| anyhow!("`{:?}`: {}", command, e))?;
+
+    if !output.status.success() {
+        return Err(anyhow!("`{:?}`: {}", command, output.status));
+    }
+
+    String::from_utf8(output.stdout)
+        .map_err(|e| anyhow!("`{:?}`: {}", command, e))
+}
+
 #[cfg(all(target_family = "unix", not(target_os = "macos")))]
 fn gen_command(
     path: impl AsRef<OsStr>,
@@ -44,7 +137,7 @@ fn gen_command(
 }
 
 #[cfg(all(target_family = "unix", not(target_os = "macos")))]
-pub fn copy_string(string: &str) -> Result<()> {
+fn copy_string_native(string: &str) -> Result<()> {
     use std::path::PathBuf;
     use which::which;
     let (path, xclip_syntax) = which("xclip").ok().map_or_else(
@@ -63,12 +156,43 @@ pub fn copy_string(string: &str) -> Result<()> {
     execute_copy_command(cmd, string)
 }
 
+#[cfg(all(target_family = "unix", not(target_os = "macos")))]
+pub fn paste_string() -> Result<String> {
+    use which::which;
+    let cmd = which("xclip").ok().map_or_else(
+        || {
+            let mut cmd = gen_command("xsel", false);
+            cmd.arg("--output");
+            cmd
+        },
+        |path| {
+            let mut cmd = gen_command(path, true);
+            cmd.arg("-out");
+            cmd
+        },
+    );
+
+    execute_paste_command(cmd)
+}
+
 #[cfg(target_os = "macos")]
-pub fn copy_string(string: &str) -> Result<()> {
+fn copy_string_native(string: &str) -> Result<()> {
     execute_copy_command(Command::new("pbcopy"), string)
 }
 
+#[cfg(target_os = "macos")]
+pub fn paste_string() -> Result<String> {
+    execute_paste_command(Command::new("pbpaste"))
+}
+
 #[cfg(windows)]
-pub fn copy_string(string: &str) -> Result<()> {
+fn copy_string_native(string: &str) -> Result<()> {
     execute_copy_command(Command::new("clip"), string)
 }
+
+#[cfg(windows)]
+pub fn paste_string() -> Result<String> {
+    let mut cmd = Command::new("powershell");
+    cmd.args(&["-NoProfile", "-Command", "Get-Clipboard -Raw"]);
+    execute_paste_command(cmd)
+}
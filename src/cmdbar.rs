@@ -158,7 +158,7 @@ impl CommandBar {
                             DrawListEntry::Command(c) => {
                                 Span::styled(
                                     Cow::from(c.txt.as_str()),
-                                    self.theme.commandbar(
+                                    self.theme.borrow().commandbar(
                                         c.enabled, c.line,
                                     ),
                                 )
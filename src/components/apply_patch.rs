@@ -0,0 +1,187 @@
+use super::{
+    textinput::TextInputComponent, visibility_blocking,
+    CommandBlocking, CommandInfo, Component, DrawableComponent,
+    EventState, InputType,
+};
+use crate::{
+    keys::SharedKeyConfig,
+    queue::{InternalEvent, NeedsUpdate, Queue},
+    strings,
+    ui::style::SharedTheme,
+};
+use anyhow::Result;
+use asyncgit::sync::{self, RepoPath};
+use crossterm::event::Event;
+use std::{fs, path::Path};
+use tui::{backend::Backend, layout::Rect, Frame};
+
+/// asks for a patch file to apply to the working tree, or applies
+/// the patch in the clipboard instead
+pub struct ApplyPatchComponent {
+    repo: RepoPath,
+    input: TextInputComponent,
+    queue: Queue,
+    key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for ApplyPatchComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        self.input.draw(f, rect)?;
+
+        Ok(())
+    }
+}
+
+impl Component for ApplyPatchComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.is_visible() || force_all {
+            self.input.commands(out, force_all);
+
+            out.push(CommandInfo::new(
+                strings::commands::apply_patch_confirm(
+                    &self.key_config,
+                ),
+                !self.input.get_text().trim().is_empty(),
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::apply_patch_clipboard(
+                    &self.key_config,
+                ),
+                true,
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<EventState> {
+        if self.is_visible() {
+            if let Event::Key(e) = ev {
+                if e == self.key_config.borrow().apply_patch_clipboard
+                {
+                    self.apply(None);
+                    return Ok(EventState::Consumed);
+                }
+            }
+
+            if self.input.event(ev)?.is_consumed() {
+                return Ok(EventState::Consumed);
+            }
+
+            if let Event::Key(e) = ev {
+                if e == self.key_config.borrow().enter {
+                    let file =
+                        self.input.get_text().trim().to_string();
+
+                    if !file.is_empty() {
+                        self.apply(Some(&file));
+                    }
+                }
+
+                return Ok(EventState::Consumed);
+            }
+        }
+        Ok(EventState::NotConsumed)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.input.is_visible()
+    }
+
+    fn hide(&mut self) {
+        self.input.hide()
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.input.show()?;
+
+        Ok(())
+    }
+}
+
+impl ApplyPatchComponent {
+    ///
+    pub fn new(
+        repo: RepoPath,
+        queue: Queue,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+    ) -> Self {
+        Self {
+            queue,
+            input: TextInputComponent::new(
+                theme,
+                key_config.clone(),
+                &strings::apply_patch_popup_title(&key_config),
+                &strings::apply_patch_popup_msg(&key_config),
+                true,
+            )
+            .with_input_type(InputType::Singleline),
+            key_config,
+            repo,
+        }
+    }
+
+    ///
+    pub fn open(&mut self) -> Result<()> {
+        self.input.clear();
+        self.show()
+    }
+
+    /// applies the patch in `file` (relative to the work dir) or in
+    /// the clipboard if there is none
+    fn apply(&mut self, file: Option<&str>) {
+        self.hide();
+
+        match self.apply_patch(file) {
+            Ok(conflicts) => {
+                if !conflicts.is_empty() {
+                    self.queue.borrow_mut().push_back(
+                        InternalEvent::ShowErrorMsg(
+                            strings::apply_patch_conflicts(
+                                &conflicts,
+                            ),
+                        ),
+                    );
+                }
+            }
+            Err(e) => {
+                log::error!("apply patch: {}", e);
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::ShowErrorMsg(format!(
+                        "apply patch error:\n{}",
+                        e,
+                    )),
+                );
+            }
+        }
+
+        self.queue
+            .borrow_mut()
+            .push_back(InternalEvent::Update(NeedsUpdate::ALL));
+    }
+
+    fn apply_patch(&self, file: Option<&str>) -> Result<Vec<String>> {
+        let patch = if let Some(file) = file {
+            fs::read_to_string(
+                Path::new(&sync::utils::repo_work_dir(&self.repo)?)
+                    .join(file),
+            )?
+        } else {
+            crate::clipboard::paste_string()?
+        };
+
+        Ok(sync::apply_patch(&self.repo, &patch, false)?)
+    }
+}
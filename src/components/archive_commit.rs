@@ -0,0 +1,179 @@
+use super::{
+    textinput::TextInputComponent, visibility_blocking,
+    CommandBlocking, CommandInfo, Component, DrawableComponent,
+    EventState, InputType,
+};
+use crate::{
+    keys::SharedKeyConfig,
+    queue::{InternalEvent, Queue},
+    strings,
+    ui::style::SharedTheme,
+};
+use anyhow::{anyhow, Result};
+use asyncgit::sync::{self, ArchiveFormat, CommitId, RepoPath};
+use crossterm::event::Event;
+use std::path::Path;
+use tui::{backend::Backend, layout::Rect, Frame};
+
+/// asks for the file to archive the tree of a commit to, the
+/// extension picks the format
+pub struct ArchiveCommitComponent {
+    repo: RepoPath,
+    input: TextInputComponent,
+    commit: Option<CommitId>,
+    queue: Queue,
+    key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for ArchiveCommitComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        self.input.draw(f, rect)?;
+
+        Ok(())
+    }
+}
+
+impl Component for ArchiveCommitComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.is_visible() || force_all {
+            self.input.commands(out, force_all);
+
+            out.push(CommandInfo::new(
+                strings::commands::archive_commit_confirm(
+                    &self.key_config,
+                ),
+                ArchiveFormat::from_file_name(
+                    self.input.get_text().trim(),
+                )
+                .is_some(),
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<EventState> {
+        if self.is_visible() {
+            if self.input.event(ev)?.is_consumed() {
+                return Ok(EventState::Consumed);
+            }
+
+            if let Event::Key(e) = ev {
+                if e == self.key_config.borrow().enter {
+                    self.archive();
+                }
+
+                return Ok(EventState::Consumed);
+            }
+        }
+        Ok(EventState::NotConsumed)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.input.is_visible()
+    }
+
+    fn hide(&mut self) {
+        self.input.hide()
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.input.show()?;
+
+        Ok(())
+    }
+}
+
+impl ArchiveCommitComponent {
+    ///
+    pub fn new(
+        repo: RepoPath,
+        queue: Queue,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+    ) -> Self {
+        Self {
+            queue,
+            input: TextInputComponent::new(
+                theme,
+                key_config.clone(),
+                &strings::archive_commit_popup_title(&key_config),
+                &strings::archive_commit_popup_msg(&key_config),
+                true,
+            )
+            .with_input_type(InputType::Singleline),
+            commit: None,
+            key_config,
+            repo,
+        }
+    }
+
+    /// prefills the file name like `gitui-1a2b3c4.tar.gz`
+    pub fn open(&mut self, id: CommitId) -> Result<()> {
+        let work_dir = sync::utils::repo_work_dir(&self.repo)?;
+        let name = Path::new(&work_dir).file_name().map_or_else(
+            || String::from("archive"),
+            |name| name.to_string_lossy().to_string(),
+        );
+
+        self.commit = Some(id);
+        self.input.set_text(format!(
+            "{}-{}.tar.gz",
+            name,
+            id.get_short_string()
+        ));
+        self.show()
+    }
+
+    fn archive(&mut self) {
+        if let Some(id) = self.commit {
+            let file = self.input.get_text().trim().to_string();
+
+            if let Some(format) = ArchiveFormat::from_file_name(&file)
+            {
+                self.hide();
+
+                if let Err(e) = self.write_archive(id, format, &file)
+                {
+                    log::error!("archive commit: {}", e);
+                    self.queue.borrow_mut().push_back(
+                        InternalEvent::ShowErrorMsg(format!(
+                            "archive error:\n{}",
+                            e,
+                        )),
+                    );
+                }
+            } else {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::ShowErrorMsg(
+                        strings::archive_commit_unknown_format(&file),
+                    ),
+                );
+            }
+        }
+    }
+
+    /// `file` is relative to the work dir
+    fn write_archive(
+        &self,
+        id: CommitId,
+        format: ArchiveFormat,
+        file: &str,
+    ) -> Result<()> {
+        let path =
+            Path::new(&sync::utils::repo_work_dir(&self.repo)?)
+                .join(file);
+
+        sync::archive_commit(&self.repo, id, format, &path)
+            .map_err(|e| anyhow!("{}: {}", path.display(), e))
+    }
+}
@@ -5,18 +5,27 @@ use super::{
 use crate::{
     components::{utils::string_width_align, ScrollType},
     keys::SharedKeyConfig,
+    options::SharedOptions,
     queue::{InternalEvent, Queue},
     strings,
-    ui::{self, style::SharedTheme},
+    ui::{
+        self,
+        style::SharedTheme,
+        syntax_text::{
+            highlighted_spans, AsyncSyntaxHighlighting,
+            HighlightedLine, SyntaxHighlighter, SyntaxStream,
+        },
+    },
 };
 use anyhow::Result;
 use asyncgit::{
-    sync::{BlameHunk, CommitId, FileBlame},
+    hash,
+    sync::{BlameHunk, CommitId, FileBlame, RepoPath},
     AsyncBlame, AsyncNotification, BlameParams,
 };
 use crossbeam_channel::Sender;
 use crossterm::event::Event;
-use std::convert::TryInto;
+use std::{convert::TryInto, path::Path};
 use tui::{
     backend::Backend,
     layout::{Constraint, Rect},
@@ -34,13 +43,16 @@ pub struct BlameFileComponent {
     visible: bool,
     file_path: Option<String>,
     file_blame: Option<FileBlame>,
+    syntax: AsyncSyntaxHighlighting,
     table_state: std::cell::Cell<TableState>,
     key_config: SharedKeyConfig,
+    options: SharedOptions,
     current_height: std::cell::Cell<usize>,
 }
 
 static NO_COMMIT_ID: &str = "0000000";
 static NO_AUTHOR: &str = "<no author>";
+static PENDING_COMMIT_ID: &str = "\u{2026}";
 static MIN_AUTHOR_WIDTH: usize = 3;
 static MAX_AUTHOR_WIDTH: usize = 20;
 
@@ -93,15 +105,17 @@ impl DrawableComponent for BlameFileComponent {
             let table = Table::new(rows)
                 .widths(&constraints)
                 .column_spacing(1)
-                .highlight_style(self.theme.text(true, true))
+                .highlight_style(self.theme.borrow().text(true, true))
                 .block(
                     Block::default()
                         .borders(Borders::ALL)
                         .title(Span::styled(
                             title,
-                            self.theme.title(true),
+                            self.theme.borrow().title(true),
                         ))
-                        .border_style(self.theme.block(true)),
+                        .border_style(
+                            self.theme.borrow().block(true),
+                        ),
                 );
 
             let mut table_state = self.table_state.take();
@@ -174,6 +188,16 @@ impl Component for BlameFileComponent {
                 )
                 .order(1),
             );
+            out.push(
+                CommandInfo::new(
+                    strings::commands::blame_copy_detection(
+                        &self.key_config,
+                    ),
+                    true,
+                    self.file_path.is_some(),
+                )
+                .order(1),
+            );
         }
 
         visibility_blocking(self)
@@ -185,25 +209,38 @@ impl Component for BlameFileComponent {
     ) -> Result<EventState> {
         if self.is_visible() {
             if let Event::Key(key) = event {
-                if key == self.key_config.exit_popup {
+                if key == self.key_config.borrow().exit_popup {
                     self.hide();
-                } else if key == self.key_config.move_up {
+                } else if key == self.key_config.borrow().move_up {
                     self.move_selection(ScrollType::Up);
-                } else if key == self.key_config.move_down {
+                } else if key == self.key_config.borrow().move_down {
                     self.move_selection(ScrollType::Down);
-                } else if key == self.key_config.shift_up
-                    || key == self.key_config.home
+                } else if key == self.key_config.borrow().shift_up
+                    || key == self.key_config.borrow().home
                 {
                     self.move_selection(ScrollType::Home);
-                } else if key == self.key_config.shift_down
-                    || key == self.key_config.end
+                } else if key == self.key_config.borrow().shift_down
+                    || key == self.key_config.borrow().end
                 {
                     self.move_selection(ScrollType::End);
-                } else if key == self.key_config.page_down {
+                } else if key == self.key_config.borrow().page_down {
                     self.move_selection(ScrollType::PageDown);
-                } else if key == self.key_config.page_up {
+                } else if key == self.key_config.borrow().page_up {
                     self.move_selection(ScrollType::PageUp);
-                } else if key == self.key_config.focus_right {
+                } else if key
+                    == self.key_config.borrow().blame_copy_detection
+                {
+                    let copy_detection =
+                        self.options.borrow().blame_copy_detection();
+                    self.options
+                        .borrow_mut()
+                        .set_blame_copy_detection(
+                            copy_detection.next(),
+                        );
+                    self.file_blame = None;
+                    self.update()?;
+                } else if key == self.key_config.borrow().focus_right
+                {
                     self.hide();
 
                     return self.selected_commit().map_or(
@@ -231,7 +268,11 @@ impl Component for BlameFileComponent {
     }
 
     fn hide(&mut self) {
-        self.visible = false
+        self.visible = false;
+
+        if let Err(e) = self.async_blame.cancel() {
+            log::error!("blame cancel error: {}", e);
+        }
     }
 
     fn show(&mut self) -> Result<()> {
@@ -244,22 +285,26 @@ impl Component for BlameFileComponent {
 impl BlameFileComponent {
     ///
     pub fn new(
+        repo: &RepoPath,
         queue: &Queue,
         sender: &Sender<AsyncNotification>,
         title: &str,
         theme: SharedTheme,
         key_config: SharedKeyConfig,
+        options: SharedOptions,
     ) -> Self {
         Self {
             title: String::from(title),
             theme,
-            async_blame: AsyncBlame::new(sender),
+            async_blame: AsyncBlame::new(repo.clone(), sender),
             queue: queue.clone(),
             visible: false,
             file_path: None,
             file_blame: None,
+            syntax: AsyncSyntaxHighlighting::new(sender),
             table_state: std::cell::Cell::new(TableState::default()),
             key_config,
+            options,
             current_height: std::cell::Cell::new(0),
         }
     }
@@ -268,6 +313,7 @@ impl BlameFileComponent {
     pub fn open(&mut self, file_path: &str) -> Result<()> {
         self.file_path = Some(file_path.into());
         self.file_blame = None;
+        self.syntax.clear();
         self.table_state.get_mut().select(Some(0));
         self.show()?;
 
@@ -278,7 +324,7 @@ impl BlameFileComponent {
 
     ///
     pub fn any_work_pending(&self) -> bool {
-        self.async_blame.is_pending()
+        self.async_blame.is_pending() || self.syntax.is_pending()
     }
 
     ///
@@ -300,34 +346,105 @@ impl BlameFileComponent {
             if let Some(file_path) = &self.file_path {
                 let blame_params = BlameParams {
                     file_path: file_path.into(),
+                    copy_detection: self
+                        .options
+                        .borrow()
+                        .blame_copy_detection(),
                 };
 
-                if let Some((
-                    previous_blame_params,
-                    last_file_blame,
-                )) = self.async_blame.last()?
-                {
-                    if previous_blame_params == blame_params {
-                        self.file_blame = Some(last_file_blame);
+                let last = self
+                    .async_blame
+                    .last()?
+                    .filter(|(params, _)| *params == blame_params)
+                    .map(|(_, file_blame)| file_blame);
 
-                        return Ok(());
-                    }
-                }
+                // returns the partial result if still in progress
+                let file_blame = match last {
+                    Some(file_blame) => Some(file_blame),
+                    None => self.async_blame.request(blame_params)?,
+                };
 
-                self.async_blame.request(blame_params)?;
+                if let Some(file_blame) = file_blame {
+                    self.request_syntax(&file_blame);
+                    self.file_blame = Some(file_blame);
+                }
             }
         }
 
         Ok(())
     }
 
+    /// highlights the file in the background, once per content (the
+    /// partial results of the blame and other copy detections share
+    /// it)
+    fn request_syntax(&mut self, file_blame: &FileBlame) {
+        let highlighter =
+            self.theme.borrow().syntax_theme().and_then(|theme| {
+                SyntaxHighlighter::new(
+                    Path::new(&file_blame.path),
+                    theme,
+                )
+            });
+
+        if let Some(highlighter) = highlighter {
+            let lines: Vec<&str> = file_blame
+                .lines
+                .iter()
+                .map(|(_, line)| line.as_str())
+                .collect();
+
+            self.syntax.request(
+                hash(&(&file_blame.path, &lines)),
+                highlighter,
+                || {
+                    vec![lines
+                        .iter()
+                        .enumerate()
+                        .map(|(idx, line)| {
+                            (Some(idx), (*line).to_string())
+                        })
+                        .collect::<SyntaxStream>()]
+                },
+                lines.len(),
+            );
+        }
+    }
+
     ///
     fn get_title(&self) -> String {
+        let title = format!(
+            "{} [{}]",
+            self.get_base_title(),
+            strings::blame_copy_detection(
+                self.options.borrow().blame_copy_detection()
+            )
+        );
+
+        match self.selected_orig_path() {
+            Some(orig_path) => {
+                format!("{} -- from: {}", title, orig_path)
+            }
+            None => title,
+        }
+    }
+
+    fn get_base_title(&self) -> String {
         match (
             self.any_work_pending(),
             self.file_path.as_ref(),
             self.file_blame.as_ref(),
         ) {
+            (true, Some(file_path), Some(file_blame))
+                if file_blame.is_partial() =>
+            {
+                format!(
+                    "{} -- {} -- <calculating.. {}%>",
+                    self.title,
+                    file_path,
+                    file_blame.blamed_lines * 100
+                        / file_blame.lines.len().max(1)
+                )
+            }
             (true, Some(file_path), _) => {
                 format!(
                     "{} -- {} -- <calculating.. (who is to blame?)>",
@@ -355,6 +472,8 @@ impl BlameFileComponent {
     ///
     fn get_rows(&self, width: usize) -> Vec<Row> {
         if let Some(ref file_blame) = self.file_blame {
+            let highlighted = self.syntax.get();
+
             file_blame
                 .lines
                 .iter()
@@ -365,6 +484,9 @@ impl BlameFileComponent {
                         i,
                         (blame_hunk.as_ref(), line.as_ref()),
                         file_blame,
+                        highlighted
+                            .as_ref()
+                            .and_then(|lines| lines.get(i)),
                     )
                 })
                 .collect()
@@ -379,6 +501,7 @@ impl BlameFileComponent {
         line_number: usize,
         hunk_and_line: (Option<&BlameHunk>, &str),
         file_blame: &FileBlame,
+        highlighted: Option<&HighlightedLine>,
     ) -> Row {
         let (hunk_for_line, line) = hunk_and_line;
 
@@ -396,7 +519,15 @@ impl BlameFileComponent {
             }
         };
 
-        let mut cells = if show_metadata {
+        let mut cells = if line_number >= file_blame.blamed_lines {
+            // not blamed yet
+            vec![
+                Cell::from(PENDING_COMMIT_ID)
+                    .style(self.theme.borrow().text(false, false)),
+                Cell::from(""),
+                Cell::from(""),
+            ]
+        } else if show_metadata {
             self.get_metadata_for_line_blame(width, hunk_for_line)
         } else {
             vec![Cell::from(""), Cell::from(""), Cell::from("")]
@@ -410,12 +541,18 @@ impl BlameFileComponent {
                 VERTICAL,
                 line_number_width = line_number_width,
             ))
-            .style(self.theme.text(true, false)),
-        );
-        cells.push(
-            Cell::from(String::from(line))
-                .style(self.theme.text(true, false)),
+            .style(self.theme.borrow().text(true, false)),
         );
+        let highlighted_line = highlighted
+            .and_then(|ranges| highlighted_spans(line, ranges));
+
+        cells.push(highlighted_line.map_or_else(
+            || {
+                Cell::from(String::from(line))
+                    .style(self.theme.borrow().text(true, false))
+            },
+            Cell::from,
+        ));
 
         Row::new(cells)
     }
@@ -456,10 +593,14 @@ impl BlameFileComponent {
 
         vec![
             Cell::from(commit_hash).style(
-                self.theme.commit_hash_in_blame(is_blamed_commit),
+                self.theme
+                    .borrow()
+                    .commit_hash_in_blame(is_blamed_commit),
             ),
-            Cell::from(time).style(self.theme.commit_time(false)),
-            Cell::from(author).style(self.theme.commit_author(false)),
+            Cell::from(time)
+                .style(self.theme.borrow().commit_time(false)),
+            Cell::from(author)
+                .style(self.theme.borrow().commit_author(false)),
         ]
     }
 
@@ -506,6 +647,25 @@ impl BlameFileComponent {
         needs_update
     }
 
+    fn selected_orig_path(&self) -> Option<String> {
+        self.file_blame.as_ref().and_then(|file_blame| {
+            let table_state = self.table_state.take();
+
+            let orig_path =
+                table_state.selected().and_then(|selected| {
+                    file_blame.lines.get(selected).and_then(|line| {
+                        line.0
+                            .as_ref()
+                            .and_then(|hunk| hunk.orig_path.clone())
+                    })
+                });
+
+            self.table_state.set(table_state);
+
+            orig_path
+        })
+    }
+
     fn selected_commit(&self) -> Option<CommitId> {
         self.file_blame.as_ref().and_then(|file_blame| {
             let table_state = self.table_state.take();
@@ -1,10 +1,12 @@
 use super::{
-    visibility_blocking, CommandBlocking, CommandInfo, Component,
-    DrawableComponent, EventState,
+    open_context_menu, utils::time_to_string, visibility_blocking,
+    CommandBlocking, CommandInfo, Component, DrawableComponent,
+    EventState,
 };
 use crate::{
     components::ScrollType,
     keys::SharedKeyConfig,
+    options::{SharedOptions, Workflow},
     queue::{Action, InternalEvent, NeedsUpdate, Queue},
     strings, try_or_popup,
     ui::{self, calc_scroll_top, Size},
@@ -13,12 +15,18 @@ use anyhow::Result;
 use asyncgit::{
     sync::{
         self, branch::checkout_remote_branch, checkout_branch,
-        get_branches_info, BranchInfo,
+        get_branch_names, BranchInfo, FlowBranch, LogFilter,
+        MergeFastForward, RepoPath,
     },
-    CWD,
+    AsyncBranches, AsyncNotification,
+};
+use crossbeam_channel::Sender;
+use crossterm::event::{Event, KeyEvent};
+use std::{
+    cell::Cell,
+    collections::{HashMap, HashSet},
+    convert::TryInto,
 };
-use crossterm::event::Event;
-use std::{cell::Cell, convert::TryInto};
 use tui::{
     backend::Backend,
     layout::{
@@ -31,15 +39,35 @@ use tui::{
 use ui::style::SharedTheme;
 use unicode_truncate::UnicodeTruncateStr;
 
+/// details are loaded for this many branches before and after the
+/// selection, more than fit on the screen
+const PAGE_SIZE: usize = 100;
+/// "↑12 ↓3    "
+const UPSTREAM_COMPARE_LENGTH: usize = 10;
+/// "2021-02-03 "
+const DATE_LENGTH: usize = 11;
+const COMMIT_HASH_LENGTH: usize = 8;
+
 ///
 pub struct BranchListComponent {
-    branches: Vec<BranchInfo>,
+    repo: RepoPath,
+    /// names of all branches, sorted
+    names: Vec<String>,
+    /// details of the branches around the selection, loaded lazily
+    /// because there can be (tens of) thousands of branches
+    branches: HashMap<String, BranchInfo>,
+    /// names whose details were requested from `async_branches`
+    requested: HashSet<String>,
+    async_branches: AsyncBranches,
     local: bool,
+    /// mode of the merge key, cycled by `merge_ff_mode`
+    merge_fast_forward: MergeFastForward,
     visible: bool,
-    selection: u16,
+    selection: usize,
     scroll_top: Cell<usize>,
     current_height: Cell<u16>,
     queue: Queue,
+    options: SharedOptions,
     theme: SharedTheme,
     key_config: SharedKeyConfig,
 }
@@ -116,55 +144,54 @@ impl Component for BranchListComponent {
                 true,
             ));
 
-            out.push(CommandInfo::new(
-                strings::commands::toggle_branch_popup(
-                    &self.key_config,
-                    self.local,
-                ),
-                true,
-                true,
-            ));
-
-            out.push(CommandInfo::new(
-                strings::commands::select_branch_popup(
-                    &self.key_config,
-                ),
-                !self.selection_is_cur_branch()
-                    && self.valid_selection(),
-                true,
-            ));
+            out.push(
+                CommandInfo::new(
+                    strings::commands::toggle_branch_popup(
+                        &self.key_config,
+                        self.local,
+                    ),
+                    true,
+                    true,
+                )
+                .key(self.key_config.borrow().tab_toggle),
+            );
 
-            out.push(CommandInfo::new(
-                strings::commands::open_branch_create_popup(
-                    &self.key_config,
-                ),
-                true,
-                self.local,
-            ));
+            out.push(
+                CommandInfo::new(
+                    strings::commands::select_branch_popup(
+                        &self.key_config,
+                    ),
+                    !self.selection_is_cur_branch()
+                        && self.valid_selection(),
+                    true,
+                )
+                .key(self.key_config.borrow().enter),
+            );
 
-            out.push(CommandInfo::new(
-                strings::commands::delete_branch_popup(
-                    &self.key_config,
-                ),
-                !self.selection_is_cur_branch(),
-                self.local,
-            ));
+            out.push(
+                CommandInfo::new(
+                    strings::commands::open_branch_create_popup(
+                        &self.key_config,
+                    ),
+                    true,
+                    self.local,
+                )
+                .key(self.key_config.borrow().create_branch),
+            );
 
-            out.push(CommandInfo::new(
-                strings::commands::merge_branch_popup(
-                    &self.key_config,
-                ),
-                !self.selection_is_cur_branch(),
-                self.local,
-            ));
+            out.push(
+                CommandInfo::new(
+                    strings::commands::delete_branch_popup(
+                        &self.key_config,
+                    ),
+                    !self.selection_is_cur_branch(),
+                    self.local,
+                )
+                .key(self.key_config.borrow().delete_branch),
+            );
 
-            out.push(CommandInfo::new(
-                strings::commands::rename_branch_popup(
-                    &self.key_config,
-                ),
-                true,
-                self.local,
-            ));
+            self.commands_merge(out);
+            self.commands_selected(out);
         }
         visibility_blocking(self)
     }
@@ -172,79 +199,73 @@ impl Component for BranchListComponent {
     fn event(&mut self, ev: Event) -> Result<EventState> {
         if self.visible {
             if let Event::Key(e) = ev {
-                if e == self.key_config.exit_popup {
+                if e == self.key_config.borrow().exit_popup {
                     self.hide()
-                } else if e == self.key_config.move_down {
-                    return self
+                } else if e == self.key_config.borrow().move_down {
+                    return Ok(self
                         .move_selection(ScrollType::Up)
-                        .map(Into::into);
-                } else if e == self.key_config.move_up {
-                    return self
+                        .into());
+                } else if e == self.key_config.borrow().move_up {
+                    return Ok(self
                         .move_selection(ScrollType::Down)
-                        .map(Into::into);
-                } else if e == self.key_config.page_down {
-                    return self
+                        .into());
+                } else if e == self.key_config.borrow().page_down {
+                    return Ok(self
                         .move_selection(ScrollType::PageDown)
-                        .map(Into::into);
-                } else if e == self.key_config.page_up {
-                    return self
+                        .into());
+                } else if e == self.key_config.borrow().page_up {
+                    return Ok(self
                         .move_selection(ScrollType::PageUp)
-                        .map(Into::into);
-                } else if e == self.key_config.enter {
+                        .into());
+                } else if e == self.key_config.borrow().enter {
                     try_or_popup!(
                         self,
                         "switch branch error:",
                         self.switch_to_selected_branch()
                     );
-                } else if e == self.key_config.create_branch
+                } else if e == self.key_config.borrow().create_branch
                     && self.local
                 {
                     self.queue
                         .borrow_mut()
                         .push_back(InternalEvent::CreateBranch);
-                } else if e == self.key_config.rename_branch
+                } else if e == self.key_config.borrow().rename_branch
                     && self.valid_selection()
                 {
-                    let cur_branch =
-                        &self.branches[self.selection as usize];
-                    self.queue.borrow_mut().push_back(
-                        InternalEvent::RenameBranch(
-                            cur_branch.reference.clone(),
-                            cur_branch.name.clone(),
-                        ),
-                    );
+                    if let Some(cur_branch) = self.selected_branch() {
+                        self.queue.borrow_mut().push_back(
+                            InternalEvent::RenameBranch(
+                                cur_branch.reference.clone(),
+                                cur_branch.name.clone(),
+                            ),
+                        );
+                    }
 
                     self.update_branches()?;
-                } else if e == self.key_config.delete_branch
+                } else if e == self.key_config.borrow().delete_branch
                     && !self.selection_is_cur_branch()
                     && self.valid_selection()
                 {
-                    self.queue.borrow_mut().push_back(
-                        InternalEvent::ConfirmAction(
-                            Action::DeleteBranch(
-                                self.branches
-                                    [self.selection as usize]
-                                    .reference
-                                    .clone(),
+                    if let Some(cur_branch) = self.selected_branch() {
+                        self.queue.borrow_mut().push_back(
+                            InternalEvent::ConfirmAction(
+                                Action::DeleteBranch(
+                                    cur_branch.reference.clone(),
+                                ),
                             ),
-                        ),
-                    );
-                } else if e == self.key_config.merge_branch
-                    && !self.selection_is_cur_branch()
-                    && self.valid_selection()
+                        );
+                    }
+                } else if self.merge_event(e)
+                    || self.selected_event(e)
                 {
-                    try_or_popup!(
-                        self,
-                        "merge branch error:",
-                        self.merge_branch()
-                    );
-                    self.hide();
-                    self.queue.borrow_mut().push_back(
-                        InternalEvent::Update(NeedsUpdate::ALL),
-                    );
-                } else if e == self.key_config.tab_toggle {
+                    return Ok(EventState::Consumed);
+                } else if e == self.key_config.borrow().tab_toggle {
                     self.local = !self.local;
                     self.update_branches()?;
+                } else if e
+                    == self.key_config.borrow().open_context_menu
+                {
+                    open_context_menu(self, &self.queue);
                 }
             }
 
@@ -271,20 +292,29 @@ impl Component for BranchListComponent {
 
 impl BranchListComponent {
     pub fn new(
+        repo: RepoPath,
         queue: Queue,
+        sender: &Sender<AsyncNotification>,
         theme: SharedTheme,
         key_config: SharedKeyConfig,
+        options: SharedOptions,
     ) -> Self {
         Self {
-            branches: Vec::new(),
+            names: Vec::new(),
+            branches: HashMap::new(),
+            requested: HashSet::new(),
+            async_branches: AsyncBranches::new(repo.clone(), sender),
             local: true,
+            merge_fast_forward: MergeFastForward::Allow,
             visible: false,
             selection: 0,
             scroll_top: Cell::new(0),
             queue,
+            options,
             theme,
             key_config,
             current_height: Cell::new(0),
+            repo,
         }
     }
 
@@ -298,80 +328,385 @@ impl BranchListComponent {
 
     /// fetch list of branches
     pub fn update_branches(&mut self) -> Result<()> {
-        self.branches = get_branches_info(CWD, self.local)?;
+        self.names = get_branch_names(&self.repo, self.local)?;
         //remove remote branch called `HEAD`
         if !self.local {
-            self.branches
-                .iter()
-                .position(|b| b.name.ends_with("/HEAD"))
-                .map(|idx| self.branches.remove(idx));
+            self.names.retain(|name| !name.ends_with("/HEAD"));
         }
-        self.set_selection(self.selection)?;
+        self.branches.clear();
+        self.requested.clear();
+        self.async_branches.invalidate()?;
+        self.set_selection(self.selection);
         Ok(())
     }
 
+    ///
+    pub fn any_work_pending(&self) -> bool {
+        self.async_branches.is_pending()
+    }
+
+    ///
+    pub fn update_git(
+        &mut self,
+        ev: AsyncNotification,
+    ) -> Result<()> {
+        if let AsyncNotification::Branches = ev {
+            for branch in self.async_branches.take()? {
+                self.branches.insert(branch.name.clone(), branch);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// requests the details of the branches close to the selection
+    /// that are not loaded yet, computing ahead/behind of many
+    /// branches takes a while so this happens in the background
+    fn load_branches(&mut self) {
+        let start = self.selection.saturating_sub(PAGE_SIZE);
+        let end = self
+            .selection
+            .saturating_add(PAGE_SIZE)
+            .min(self.names.len());
+
+        let missing: Vec<String> = self.names[start..end]
+            .iter()
+            .filter(|name| !self.requested.contains(*name))
+            .cloned()
+            .collect();
+
+        if !missing.is_empty() {
+            self.requested.extend(missing.iter().cloned());
+            self.async_branches.request(self.local, missing);
+        }
+    }
+
+    fn selected_branch(&self) -> Option<&BranchInfo> {
+        self.names
+            .get(self.selection)
+            .and_then(|name| self.branches.get(name))
+    }
+
     fn valid_selection(&self) -> bool {
-        !self.branches.is_empty()
+        self.selected_branch().is_some()
     }
 
-    fn merge_branch(&self) -> Result<()> {
-        if let Some(branch) =
-            self.branches.get(usize::from(self.selection))
+    /// kind and name of the selected local branch if it belongs to
+    /// the configured workflow
+    fn selected_flow_branch(&self) -> Option<(FlowBranch, String)> {
+        if !self.local {
+            return None;
+        }
+
+        self.names
+            .get(self.selection)
+            .and_then(|name| self.options.borrow().flow_branch(name))
+    }
+
+    /// shows the log, files or changes of the selected branch
+    /// (depending on the `key` pressed) without checking it out
+    fn inspect_branch(&mut self, key: KeyEvent) {
+        let branch = match self.selected_branch() {
+            Some(branch) => branch,
+            None => return,
+        };
+        let name = branch.name.clone();
+
+        let event = if key == self.key_config.borrow().branch_show_log
         {
-            sync::merge_branch(CWD, &branch.name)?;
+            InternalEvent::FilterLog(
+                format!("rev:{}", name),
+                LogFilter {
+                    rev: Some(name),
+                    ..LogFilter::default()
+                },
+            )
+        } else if key == self.key_config.borrow().open_file_tree {
+            InternalEvent::OpenFileTree(branch.top_commit)
+        } else {
+            InternalEvent::ReviewBranch(name)
+        };
+
+        self.hide();
+        self.queue.borrow_mut().push_back(event);
+    }
+
+    /// merging, rebasing and the git flow
+    fn commands_merge(&self, out: &mut Vec<CommandInfo>) {
+        out.push(
+            CommandInfo::new(
+                strings::commands::merge_branch_popup(
+                    &self.key_config,
+                    self.merge_fast_forward,
+                ),
+                !self.selection_is_cur_branch()
+                    && self.valid_selection(),
+                true,
+            )
+            .key(self.key_config.borrow().merge_branch),
+        );
+
+        out.push(
+            CommandInfo::new(
+                strings::commands::merge_ff_mode(
+                    &self.key_config,
+                    self.merge_fast_forward,
+                ),
+                true,
+                true,
+            )
+            .key(self.key_config.borrow().merge_ff_mode),
+        );
+
+        out.push(
+            CommandInfo::new(
+                strings::commands::rebase_branch(&self.key_config),
+                !self.selection_is_cur_branch()
+                    && self.valid_selection(),
+                true,
+            )
+            .key(self.key_config.borrow().rebase_branch),
+        );
+
+        let workflow =
+            self.options.borrow().workflow() != Workflow::None;
+
+        out.push(
+            CommandInfo::new(
+                strings::commands::flow_start(&self.key_config),
+                true,
+                self.local && workflow,
+            )
+            .key(self.key_config.borrow().flow_start),
+        );
+
+        out.push(
+            CommandInfo::new(
+                strings::commands::flow_finish(&self.key_config),
+                self.selected_flow_branch().is_some(),
+                self.local && workflow,
+            )
+            .key(self.key_config.borrow().flow_finish),
+        );
+    }
+
+    /// the remaining commands on the selected branch
+    fn commands_selected(&self, out: &mut Vec<CommandInfo>) {
+        out.push(
+            CommandInfo::new(
+                strings::commands::rename_branch_popup(
+                    &self.key_config,
+                ),
+                true,
+                self.local,
+            )
+            .key(self.key_config.borrow().rename_branch),
+        );
+
+        out.push(
+            CommandInfo::new(
+                strings::commands::set_upstream_popup(
+                    &self.key_config,
+                ),
+                self.valid_selection(),
+                self.local,
+            )
+            .key(self.key_config.borrow().branch_set_upstream),
+        );
+
+        out.push(
+            CommandInfo::new(
+                strings::commands::fetch_remote_branches(
+                    &self.key_config,
+                ),
+                true,
+                !self.local,
+            )
+            .key(self.key_config.borrow().pull),
+        );
+
+        out.push(
+            CommandInfo::new(
+                strings::commands::branch_show_log(&self.key_config),
+                self.valid_selection(),
+                true,
+            )
+            .key(self.key_config.borrow().branch_show_log),
+        );
+
+        out.push(
+            CommandInfo::new(
+                strings::commands::branch_file_tree(&self.key_config),
+                self.valid_selection(),
+                true,
+            )
+            .key(self.key_config.borrow().open_file_tree),
+        );
+
+        out.push(
+            CommandInfo::new(
+                strings::commands::branch_review(&self.key_config),
+                !self.selection_is_cur_branch()
+                    && self.valid_selection(),
+                true,
+            )
+            .key(self.key_config.borrow().open_review),
+        );
+
+        out.push(CommandInfo::new(
+            strings::commands::copy_branch_name(&self.key_config),
+            self.valid_selection(),
+            true,
+        ));
+
+        out.push(CommandInfo::new(
+            strings::commands::open_context_menu(&self.key_config),
+            self.valid_selection(),
+            true,
+        ));
+    }
+
+    /// merging, rebasing and the git flow, `false` if `e` is none
+    /// of their keys
+    fn merge_event(&mut self, e: KeyEvent) -> bool {
+        if e == self.key_config.borrow().merge_branch
+            && !self.selection_is_cur_branch()
+            && self.valid_selection()
+        {
+            try_or_popup!(
+                self,
+                "merge branch error:",
+                self.merge_branch()
+            );
+            self.hide();
+            self.queue
+                .borrow_mut()
+                .push_back(InternalEvent::Update(NeedsUpdate::ALL));
+        } else if e == self.key_config.borrow().merge_ff_mode {
+            self.merge_fast_forward = match self.merge_fast_forward {
+                MergeFastForward::Allow => MergeFastForward::Never,
+                MergeFastForward::Never => MergeFastForward::Only,
+                MergeFastForward::Only => MergeFastForward::Allow,
+            };
+        } else if e == self.key_config.borrow().rebase_branch
+            && !self.selection_is_cur_branch()
+            && self.valid_selection()
+        {
+            if let Some(branch) = self.selected_branch() {
+                let name = branch.name.clone();
+                self.hide();
+                self.queue
+                    .borrow_mut()
+                    .push_back(InternalEvent::PreviewRebase(name));
+            }
+        } else if e == self.key_config.borrow().flow_start
+            && self.local
+        {
+            self.queue
+                .borrow_mut()
+                .push_back(InternalEvent::StartFlowBranch);
+        } else if e == self.key_config.borrow().flow_finish {
+            if let Some((kind, name)) = self.selected_flow_branch() {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::ConfirmAction(Action::FlowFinish(
+                        kind, name,
+                    )),
+                );
+            }
+        } else {
+            return false;
+        }
+
+        true
+    }
+
+    /// the remaining keys acting on the selected branch, `false` if
+    /// `e` is none of them
+    fn selected_event(&mut self, e: KeyEvent) -> bool {
+        if e == self.key_config.borrow().branch_set_upstream
+            && self.local
+            && self.valid_selection()
+        {
+            self.queue.borrow_mut().push_back(
+                InternalEvent::SetUpstream(
+                    self.names[self.selection].clone(),
+                ),
+            );
+        } else if e == self.key_config.borrow().pull && !self.local {
+            self.queue
+                .borrow_mut()
+                .push_back(InternalEvent::FetchRemoteBranches);
+        } else if e == self.key_config.borrow().branch_show_log
+            || e == self.key_config.borrow().open_file_tree
+            || (e == self.key_config.borrow().open_review
+                && !self.selection_is_cur_branch())
+        {
+            self.inspect_branch(e);
+        } else if e == self.key_config.borrow().copy {
+            if let Some(branch) = self.selected_branch() {
+                try_or_popup!(
+                    self,
+                    "copy to clipboard error:",
+                    crate::clipboard::copy_string(&branch.name)
+                );
+            }
+        } else {
+            return false;
+        }
+
+        true
+    }
+
+    fn merge_branch(&self) -> Result<()> {
+        if let Some(branch) = self.selected_branch() {
+            sync::autostash(
+                &self.repo,
+                self.options.borrow().autostash(),
+                || {
+                    sync::merge_branch(
+                        &self.repo,
+                        &branch.name,
+                        self.merge_fast_forward,
+                    )
+                },
+            )?;
         }
 
         Ok(())
     }
 
     fn selection_is_cur_branch(&self) -> bool {
-        self.branches
-            .iter()
-            .enumerate()
-            .filter(|(index, b)| {
-                b.local_details()
-                    .map(|details| {
-                        details.is_head
-                            && *index == self.selection as usize
-                    })
-                    .unwrap_or_default()
-            })
-            .count()
-            > 0
+        self.selected_branch()
+            .and_then(BranchInfo::local_details)
+            .map(|details| details.is_head)
+            .unwrap_or_default()
     }
 
     ///
-    fn move_selection(&mut self, scroll: ScrollType) -> Result<bool> {
+    fn move_selection(&mut self, scroll: ScrollType) -> bool {
         let new_selection = match scroll {
             ScrollType::Up => self.selection.saturating_add(1),
             ScrollType::Down => self.selection.saturating_sub(1),
-            ScrollType::PageDown => self
-                .selection
-                .saturating_add(self.current_height.get()),
-            ScrollType::PageUp => self
-                .selection
-                .saturating_sub(self.current_height.get()),
+            ScrollType::PageDown => self.selection.saturating_add(
+                usize::from(self.current_height.get()),
+            ),
+            ScrollType::PageUp => self.selection.saturating_sub(
+                usize::from(self.current_height.get()),
+            ),
             _ => self.selection,
         };
 
-        self.set_selection(new_selection)?;
+        self.set_selection(new_selection);
 
-        Ok(true)
+        true
     }
 
-    fn set_selection(&mut self, selection: u16) -> Result<()> {
-        let num_branches: u16 = self.branches.len().try_into()?;
-        let num_branches = num_branches.saturating_sub(1);
+    fn set_selection(&mut self, selection: usize) {
+        let num_branches = self.names.len().saturating_sub(1);
 
-        let selection = if selection > num_branches {
-            num_branches
-        } else {
-            selection
-        };
-
-        self.selection = selection;
+        self.selection = selection.min(num_branches);
 
-        Ok(())
+        self.load_branches();
     }
 
     /// Get branches to display
@@ -381,7 +716,6 @@ impl BranchListComponent {
         width_available: u16,
         height: usize,
     ) -> Text {
-        const COMMIT_HASH_LENGTH: usize = 8;
         const IS_HEAD_STAR_LENGTH: usize = 3; // "*  "
         const THREE_DOTS_LENGTH: usize = 3; // "..."
 
@@ -389,21 +723,25 @@ impl BranchListComponent {
             width_available as usize * 40 / 100;
         // commit message takes up the remaining width
         let commit_message_length: usize = (width_available as usize)
-            .saturating_sub(COMMIT_HASH_LENGTH)
+            .saturating_sub(self.details_length())
             .saturating_sub(branch_name_length)
             .saturating_sub(IS_HEAD_STAR_LENGTH)
             .saturating_sub(THREE_DOTS_LENGTH);
         let mut txt = Vec::new();
 
-        for (i, displaybranch) in self
-            .branches
+        for (i, name) in self
+            .names
             .iter()
             .skip(self.scroll_top.get())
             .take(height)
             .enumerate()
         {
-            let mut commit_message =
-                displaybranch.top_commit_message.clone();
+            // not loaded (yet) rows only show the name
+            let displaybranch = self.branches.get(name);
+
+            let mut commit_message = displaybranch
+                .map(|branch| branch.top_commit_message.clone())
+                .unwrap_or_default();
             if commit_message.len() > commit_message_length {
                 commit_message.unicode_truncate(
                     commit_message_length
@@ -412,7 +750,7 @@ impl BranchListComponent {
                 commit_message += "...";
             }
 
-            let mut branch_name = displaybranch.name.clone();
+            let mut branch_name = name.clone();
             if branch_name.len()
                 > branch_name_length.saturating_sub(THREE_DOTS_LENGTH)
             {
@@ -427,15 +765,15 @@ impl BranchListComponent {
             }
 
             let selected =
-                self.selection as usize - self.scroll_top.get() == i;
+                self.selection - self.scroll_top.get() == i;
 
-            let is_head = displaybranch
-                .local_details()
+            let details =
+                displaybranch.and_then(BranchInfo::local_details);
+            let is_head = details
                 .map(|details| details.is_head)
                 .unwrap_or_default();
             let is_head_str = if is_head { "*" } else { " " };
-            let has_upstream_str = if displaybranch
-                .local_details()
+            let has_upstream_str = if details
                 .map(|details| details.has_upstream)
                 .unwrap_or_default()
             {
@@ -446,18 +784,11 @@ impl BranchListComponent {
 
             let span_prefix = Span::styled(
                 format!("{}{} ", is_head_str, has_upstream_str),
-                theme.commit_author(selected),
-            );
-            let span_hash = Span::styled(
-                format!(
-                    "{} ",
-                    displaybranch.top_commit.get_short_string()
-                ),
-                theme.commit_hash(selected),
+                theme.borrow().commit_author(selected),
             );
             let span_msg = Span::styled(
                 commit_message.to_string(),
-                theme.text(true, selected),
+                theme.borrow().text(true, selected),
             );
             let span_name = Span::styled(
                 format!(
@@ -465,37 +796,121 @@ impl BranchListComponent {
                     branch_name,
                     w = branch_name_length
                 ),
-                theme.branch(selected, is_head),
+                theme.borrow().branch(selected, is_head),
             );
 
-            txt.push(Spans::from(vec![
-                span_prefix,
-                span_name,
-                span_hash,
-                span_msg,
-            ]));
+            let mut spans = vec![span_prefix, span_name];
+            spans.extend(self.details_spans(
+                theme,
+                displaybranch,
+                selected,
+            ));
+            spans.push(span_msg);
+
+            txt.push(Spans::from(spans));
         }
 
         Text::from(txt)
     }
 
+    const fn details_length(&self) -> usize {
+        if self.local {
+            UPSTREAM_COMPARE_LENGTH + DATE_LENGTH + COMMIT_HASH_LENGTH
+        } else {
+            DATE_LENGTH + COMMIT_HASH_LENGTH
+        }
+    }
+
+    /// ahead/behind the upstream (local branches only), date and hash
+    /// of the tip commit, blank while the details are still loading
+    fn details_spans(
+        &self,
+        theme: &SharedTheme,
+        branch: Option<&BranchInfo>,
+        selected: bool,
+    ) -> Vec<Span<'static>> {
+        let mut spans = Vec::with_capacity(3);
+
+        if self.local {
+            let compare = branch
+                .and_then(BranchInfo::local_details)
+                .and_then(|details| details.upstream_compare)
+                .map(|compare| {
+                    format!(
+                        "\u{2191}{} \u{2193}{}",
+                        compare.ahead, compare.behind
+                    )
+                })
+                .unwrap_or_default();
+
+            spans.push(Span::styled(
+                format!(
+                    "{:w$}",
+                    compare,
+                    w = UPSTREAM_COMPARE_LENGTH
+                ),
+                theme.borrow().commit_author(selected),
+            ));
+        }
+
+        let date = branch
+            .map(|branch| {
+                time_to_string(branch.top_commit_time, true)
+            })
+            .unwrap_or_default();
+
+        spans.push(Span::styled(
+            format!("{:w$}", date, w = DATE_LENGTH),
+            theme.borrow().commit_time(selected),
+        ));
+
+        spans.push(Span::styled(
+            branch.map_or_else(
+                || " ".repeat(COMMIT_HASH_LENGTH),
+                |branch| {
+                    format!(
+                        "{} ",
+                        branch.top_commit.get_short_string()
+                    )
+                },
+            ),
+            theme.borrow().commit_hash(selected),
+        ));
+
+        spans
+    }
+
     ///
     fn switch_to_selected_branch(&mut self) -> Result<()> {
-        if !self.valid_selection() {
-            anyhow::bail!("no valid branch selected");
+        let branch = match self.selected_branch() {
+            Some(branch) => branch,
+            None => anyhow::bail!("no valid branch selected"),
+        };
+
+        let autostash = self.options.borrow().autostash();
+
+        if self.local
+            && !autostash
+            && sync::has_uncommitted_changes(&self.repo)?
+        {
+            self.queue.borrow_mut().push_back(
+                InternalEvent::CheckoutStrategy(
+                    branch.reference.clone(),
+                ),
+            );
+            self.hide();
+            return Ok(());
         }
 
         if self.local {
-            checkout_branch(
-                asyncgit::CWD,
-                &self.branches[self.selection as usize].reference,
-            )?;
+            sync::autostash(&self.repo, autostash, || {
+                checkout_branch(&self.repo, &branch.reference)
+            })?;
             self.hide()
         } else {
-            checkout_remote_branch(
-                CWD,
-                &self.branches[self.selection as usize],
-            )?;
+            sync::autostash(&self.repo, autostash, || {
+                checkout_remote_branch(&self.repo, branch)
+            })?;
             self.local = true;
             self.update_branches()?;
         }
@@ -519,10 +934,12 @@ impl BranchListComponent {
                 .block(
                     Block::default()
                         .borders(Borders::BOTTOM)
-                        .border_style(self.theme.block(false)),
+                        .border_style(
+                            self.theme.borrow().block(false),
+                        ),
                 )
-                .style(self.theme.tab(false))
-                .highlight_style(self.theme.tab(true))
+                .style(self.theme.borrow().tab(false))
+                .highlight_style(self.theme.borrow().tab(true))
                 .divider(strings::tab_divider(&self.key_config))
                 .select(if self.local { 0 } else { 1 }),
             r,
@@ -540,7 +957,7 @@ impl BranchListComponent {
         self.scroll_top.set(calc_scroll_top(
             self.scroll_top.get(),
             height_in_lines,
-            self.selection as usize,
+            self.selection,
         ));
 
         f.render_widget(
@@ -562,7 +979,7 @@ impl BranchListComponent {
             f,
             r,
             &self.theme,
-            self.branches.len().saturating_sub(height_in_lines),
+            self.names.len().saturating_sub(height_in_lines),
             self.scroll_top.get(),
         );
 
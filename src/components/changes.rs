@@ -4,61 +4,192 @@ use super::{
     CommandBlocking, DrawableComponent,
 };
 use crate::{
-    components::{CommandInfo, Component, EventState},
+    components::{
+        open_context_menu, CommandInfo, Component, EventState,
+    },
     keys::SharedKeyConfig,
+    options::SharedOptions,
+    path_patterns::PathPatterns,
     queue::{Action, InternalEvent, NeedsUpdate, Queue, ResetItem},
     strings, try_or_popup,
     ui::style::SharedTheme,
 };
 use anyhow::Result;
-use asyncgit::{sync, StatusItem, StatusItemType, CWD};
+use asyncgit::{
+    sync::{self, IndexFlag, RepoPath},
+    StatusItem, StatusItemType,
+};
 use crossterm::event::Event;
-use std::path::Path;
+use std::{collections::HashMap, path::Path};
 use tui::{backend::Backend, layout::Rect, Frame};
 
 ///
 pub struct ChangesComponent {
+    repo: RepoPath,
+    title: String,
     files: FileTreeComponent,
     is_working_dir: bool,
+    hidden: usize,
+    /// tracked files whose changes git is told to skip (workdir only)
+    skipped: HashMap<String, IndexFlag>,
     queue: Queue,
     key_config: SharedKeyConfig,
+    options: SharedOptions,
 }
 
 impl ChangesComponent {
     ///
     pub fn new(
+        repo: RepoPath,
         title: &str,
         focus: bool,
         is_working_dir: bool,
         queue: Queue,
         theme: SharedTheme,
         key_config: SharedKeyConfig,
+        options: SharedOptions,
     ) -> Self {
+        let mut files = FileTreeComponent::new(
+            title,
+            focus,
+            Some(queue.clone()),
+            theme,
+            key_config.clone(),
+        );
+        files.enable_marking();
+
         Self {
-            files: FileTreeComponent::new(
-                title,
-                focus,
-                Some(queue.clone()),
-                theme,
-                key_config.clone(),
-            ),
+            title: title.to_string(),
+            files,
             is_working_dir,
+            hidden: 0,
+            skipped: HashMap::new(),
             queue,
             key_config,
+            options,
+            repo,
         }
     }
 
     ///
     pub fn set_items(&mut self, list: &[StatusItem]) -> Result<()> {
-        self.files.update(list)?;
+        if self.is_working_dir {
+            self.skipped = sync::get_index_flags(&self.repo)?
+                .into_iter()
+                .collect();
+        }
+
+        let options = self.options.borrow();
+        let patterns =
+            PathPatterns::new(options.status_hide_patterns());
+
+        if options.status_show_hidden() {
+            self.hidden = 0;
+            let items = self.with_skipped(list);
+            self.files.update(&items)?;
+        } else {
+            let visible: Vec<StatusItem> = list
+                .iter()
+                .filter(|item| !patterns.matches(&item.path))
+                .cloned()
+                .collect();
+
+            self.hidden = list.len() - visible.len();
+            self.files.update(&visible)?;
+        }
+
+        self.files.set_title(format!(
+            "{}{}{}",
+            self.title,
+            strings::hidden_files_info(self.hidden),
+            strings::skipped_files_info(self.skipped.len())
+        ));
+
+        Ok(())
+    }
+
+    /// the list plus the skipped files, so they can be unskipped
+    fn with_skipped(&self, list: &[StatusItem]) -> Vec<StatusItem> {
+        let mut items = list.to_vec();
+        items.extend(
+            self.skipped
+                .keys()
+                .filter(|path| {
+                    !list.iter().any(|item| &item.path == *path)
+                })
+                .map(|path| StatusItem {
+                    path: path.clone(),
+                    status: StatusItemType::Modified,
+                    submodule: None,
+                    nested_repo: false,
+                    summarized: false,
+                }),
+        );
+        items.sort_by(|a, b| {
+            Path::new(a.path.as_str()).cmp(Path::new(b.path.as_str()))
+        });
+
+        items
+    }
+
+    /// skipped files get unskipped, all others skipped
+    fn toggle_skip_worktree(&mut self) -> bool {
+        if let Some(item) = self.files.selection_file() {
+            let skip = !self.skipped.contains_key(&item.path);
+            try_or_popup!(
+                self,
+                "skip changes error:",
+                self.set_skipped(&item.path, skip)
+            );
+
+            return true;
+        }
+
+        false
+    }
+
+    fn set_skipped(&self, path: &str, skip: bool) -> Result<()> {
+        sync::set_skip_worktree(&self.repo, path, skip)?;
+        if !skip {
+            sync::set_assume_unchanged(&self.repo, path, false)?;
+        }
+
+        self.queue
+            .borrow_mut()
+            .push_back(InternalEvent::Update(NeedsUpdate::ALL));
+
         Ok(())
     }
 
+    fn is_selection_skipped(&self) -> bool {
+        self.files.selection_file().map_or(false, |item| {
+            self.skipped.contains_key(&item.path)
+        })
+    }
+
+    /// replaces the title, shown from the next `set_items` on
+    pub fn set_title(&mut self, title: &str) {
+        self.title = title.to_string();
+    }
+
+    fn toggle_hidden(&mut self) {
+        self.options.borrow_mut().toggle_status_show_hidden();
+
+        self.queue
+            .borrow_mut()
+            .push_back(InternalEvent::Update(NeedsUpdate::ALL));
+    }
+
     ///
     pub fn selection(&self) -> Option<FileTreeItem> {
         self.files.selection()
     }
 
+    /// see `FileTreeComponent::select_at_row`
+    pub fn select_at_row(&mut self, row: u16) {
+        self.files.select_at_row(row);
+    }
+
     ///
     pub fn focus_select(&mut self, focus: bool) {
         self.files.focus(focus);
@@ -76,15 +207,37 @@ impl ChangesComponent {
     }
 
     fn index_add_remove(&mut self) -> Result<bool> {
+        let marked = self.files.marked();
+        if !marked.is_empty() {
+            if self.is_working_dir {
+                sync::stage_paths(&self.repo, &marked)?;
+            } else {
+                sync::reset_stage_paths(&self.repo, &marked)?;
+            }
+            self.files.clear_marked();
+
+            return Ok(true);
+        }
+
         if let Some(tree_item) = self.selection() {
             if self.is_working_dir {
                 if let FileTreeItemKind::File(i) = tree_item.kind {
+                    if i.nested_repo {
+                        // nested repositories can only be opened
+                        return Ok(false);
+                    }
+
+                    if i.summarized {
+                        sync::stage_add_all(&self.repo, &i.path)?;
+                        return Ok(true);
+                    }
+
                     let path = Path::new(i.path.as_str());
                     match i.status {
                         StatusItemType::Deleted => {
-                            sync::stage_addremoved(CWD, path)?
+                            sync::stage_addremoved(&self.repo, path)?
                         }
-                        _ => sync::stage_add_file(CWD, path)?,
+                        _ => sync::stage_add_file(&self.repo, path)?,
                     };
 
                     if self.is_empty() {
@@ -98,7 +251,7 @@ impl ChangesComponent {
 
                 //TODO: check if we can handle the one file case with it aswell
                 sync::stage_add_all(
-                    CWD,
+                    &self.repo,
                     tree_item.info.full_path.as_str(),
                 )?;
 
@@ -106,7 +259,7 @@ impl ChangesComponent {
             }
 
             let path = tree_item.info.full_path.as_str();
-            sync::reset_stage(CWD, path)?;
+            sync::reset_stage(&self.repo, path)?;
             return Ok(true);
         }
 
@@ -114,7 +267,7 @@ impl ChangesComponent {
     }
 
     fn index_add_all(&mut self) -> Result<()> {
-        sync::stage_add_all(CWD, "*")?;
+        sync::stage_all_paths(&self.repo)?;
 
         self.queue
             .borrow_mut()
@@ -124,7 +277,7 @@ impl ChangesComponent {
     }
 
     fn stage_remove_all(&mut self) -> Result<()> {
-        sync::reset_stage(CWD, "*")?;
+        sync::unstage_all(&self.repo)?;
 
         self.queue
             .borrow_mut()
@@ -134,9 +287,23 @@ impl ChangesComponent {
     }
 
     fn dispatch_reset_workdir(&mut self) -> bool {
+        let marked = self.files.marked();
+        if !marked.is_empty() {
+            self.files.clear_marked();
+            self.queue.borrow_mut().push_back(
+                InternalEvent::ConfirmAction(Action::ResetPaths(
+                    marked,
+                )),
+            );
+
+            return true;
+        }
+
         if let Some(tree_item) = self.selection() {
-            let is_folder =
-                matches!(tree_item.kind, FileTreeItemKind::Path(_));
+            let is_folder = match &tree_item.kind {
+                FileTreeItemKind::Path(_) => true,
+                FileTreeItemKind::File(item) => item.summarized,
+            };
             self.queue.borrow_mut().push_back(
                 InternalEvent::ConfirmAction(Action::Reset(
                     ResetItem {
@@ -151,28 +318,169 @@ impl ChangesComponent {
         false
     }
 
+    /// path of the selected item if it is a submodule or a nested
+    /// repository
+    fn selected_repo(&self) -> Option<String> {
+        self.files
+            .selection_file()
+            .filter(|item| {
+                item.submodule.is_some() || item.nested_repo
+            })
+            .map(|item| item.path)
+    }
+
+    /// path of the selected item if it is a folder summarized by the
+    /// shallow status
+    fn selected_summarized(&self) -> Option<String> {
+        self.files
+            .selection_file()
+            .filter(|item| item.summarized)
+            .map(|item| item.path)
+    }
+
+    /// path of the selected item if it is a submodule
+    fn selected_submodule(&self) -> Option<String> {
+        self.files
+            .selection_file()
+            .filter(|item| item.submodule.is_some())
+            .map(|item| item.path)
+    }
+
+    /// opens the selected nested repository or expands the selected
+    /// summarized folder
+    fn open_selected_folder(&self) -> bool {
+        let event = self
+            .selected_repo()
+            .map(InternalEvent::OpenNestedRepo)
+            .or_else(|| {
+                self.selected_summarized()
+                    .map(InternalEvent::ExpandFolderStatus)
+            });
+
+        event.map_or(false, |event| {
+            self.queue.borrow_mut().push_back(event);
+            true
+        })
+    }
+
+    fn update_submodule(&mut self, path: &str) -> Result<()> {
+        sync::update_submodule(&self.repo, path)?;
+
+        self.queue
+            .borrow_mut()
+            .push_back(InternalEvent::Update(NeedsUpdate::ALL));
+
+        Ok(())
+    }
+
+    fn dispatch_discard_all(&mut self) {
+        self.queue.borrow_mut().push_back(
+            InternalEvent::ConfirmAction(Action::DiscardAll),
+        );
+    }
+
     fn add_to_ignore(&mut self) -> bool {
         if let Some(tree_item) = self.selection() {
-            if let Err(e) =
-                sync::add_to_ignore(CWD, &tree_item.info.full_path)
-            {
-                self.queue.borrow_mut().push_back(
-                    InternalEvent::ShowErrorMsg(format!(
-                        "ignore error:\n{}\nfile:\n{:?}",
-                        e, tree_item.info.full_path
-                    )),
-                );
-            } else {
-                self.queue.borrow_mut().push_back(
-                    InternalEvent::Update(NeedsUpdate::ALL),
-                );
+            let is_folder =
+                matches!(tree_item.kind, FileTreeItemKind::Path(_));
+            self.queue.borrow_mut().push_back(
+                InternalEvent::IgnorePath(
+                    tree_item.info.full_path,
+                    is_folder,
+                ),
+            );
 
-                return true;
-            }
+            return true;
         }
 
         false
     }
+
+    fn commands_working_dir(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        some_selection: bool,
+    ) {
+        out.push(
+            CommandInfo::new(
+                strings::commands::stage_all(&self.key_config),
+                some_selection,
+                self.focused(),
+            )
+            .key(self.key_config.borrow().status_stage_all),
+        );
+        out.push(
+            CommandInfo::new(
+                strings::commands::stage_item(&self.key_config),
+                some_selection,
+                self.focused(),
+            )
+            .key(self.key_config.borrow().enter),
+        );
+        out.push(
+            CommandInfo::new(
+                strings::commands::reset_item(&self.key_config),
+                some_selection,
+                self.focused(),
+            )
+            .key(self.key_config.borrow().status_reset_item),
+        );
+        out.push(
+            CommandInfo::new(
+                strings::commands::discard_all(&self.key_config),
+                !self.is_empty(),
+                self.focused(),
+            )
+            .key(self.key_config.borrow().status_discard_all),
+        );
+
+        out.push(
+            CommandInfo::new(
+                strings::commands::open_nested_repo(&self.key_config),
+                true,
+                self.focused() && self.selected_repo().is_some(),
+            )
+            .key(self.key_config.borrow().status_open_repo),
+        );
+        out.push(
+            CommandInfo::new(
+                strings::commands::expand_folder_status(
+                    &self.key_config,
+                ),
+                true,
+                self.focused()
+                    && self.selected_summarized().is_some(),
+            )
+            .key(self.key_config.borrow().status_open_repo),
+        );
+        out.push(
+            CommandInfo::new(
+                strings::commands::update_submodule(&self.key_config),
+                true,
+                self.focused() && self.selected_submodule().is_some(),
+            )
+            .key(self.key_config.borrow().status_update_submodule),
+        );
+        out.push(
+            CommandInfo::new(
+                strings::commands::ignore_item(&self.key_config),
+                some_selection,
+                self.focused(),
+            )
+            .key(self.key_config.borrow().status_ignore_file),
+        );
+        out.push(
+            CommandInfo::new(
+                strings::commands::status_skip_worktree(
+                    &self.key_config,
+                    self.is_selection_skipped(),
+                ),
+                self.files.selection_file().is_some(),
+                self.focused(),
+            )
+            .key(self.key_config.borrow().status_skip_worktree),
+        );
+    }
 }
 
 impl DrawableComponent for ChangesComponent {
@@ -198,47 +506,59 @@ impl Component for ChangesComponent {
         let some_selection = self.selection().is_some();
 
         if self.is_working_dir {
-            out.push(CommandInfo::new(
-                strings::commands::stage_all(&self.key_config),
-                some_selection,
-                self.focused(),
-            ));
-            out.push(CommandInfo::new(
-                strings::commands::stage_item(&self.key_config),
-                some_selection,
-                self.focused(),
-            ));
-            out.push(CommandInfo::new(
-                strings::commands::reset_item(&self.key_config),
-                some_selection,
-                self.focused(),
-            ));
-            out.push(CommandInfo::new(
-                strings::commands::ignore_item(&self.key_config),
-                some_selection,
-                self.focused(),
-            ));
+            self.commands_working_dir(out, some_selection);
         } else {
-            out.push(CommandInfo::new(
-                strings::commands::unstage_item(&self.key_config),
-                some_selection,
-                self.focused(),
-            ));
-            out.push(CommandInfo::new(
-                strings::commands::unstage_all(&self.key_config),
-                some_selection,
-                self.focused(),
-            ));
+            out.push(
+                CommandInfo::new(
+                    strings::commands::unstage_item(&self.key_config),
+                    some_selection,
+                    self.focused(),
+                )
+                .key(self.key_config.borrow().enter),
+            );
+            out.push(
+                CommandInfo::new(
+                    strings::commands::unstage_all(&self.key_config),
+                    some_selection,
+                    self.focused(),
+                )
+                .key(self.key_config.borrow().status_stage_all),
+            );
             out.push(
                 CommandInfo::new(
                     strings::commands::commit_open(&self.key_config),
-                    !self.is_empty(),
+                    true,
                     self.focused() || force_all,
                 )
-                .order(-1),
+                .order(-1)
+                .key(self.key_config.borrow().open_commit),
             );
         }
 
+        out.push(
+            CommandInfo::new(
+                strings::commands::status_toggle_hidden(
+                    &self.key_config,
+                    self.options.borrow().status_show_hidden(),
+                ),
+                true,
+                self.focused()
+                    && (!self
+                        .options
+                        .borrow()
+                        .status_hide_patterns()
+                        .is_empty()
+                        || !self.skipped.is_empty()),
+            )
+            .key(self.key_config.borrow().status_toggle_hidden),
+        );
+
+        out.push(CommandInfo::new(
+            strings::commands::open_context_menu(&self.key_config),
+            some_selection,
+            self.focused(),
+        ));
+
         CommandBlocking::PassingOn
     }
 
@@ -249,15 +569,15 @@ impl Component for ChangesComponent {
 
         if self.focused() {
             if let Event::Key(e) = ev {
-                return if e == self.key_config.open_commit
+                // nothing staged makes an empty commit
+                return if e == self.key_config.borrow().open_commit
                     && !self.is_working_dir
-                    && !self.is_empty()
                 {
                     self.queue
                         .borrow_mut()
                         .push_back(InternalEvent::OpenCommit);
                     Ok(EventState::Consumed)
-                } else if e == self.key_config.enter {
+                } else if e == self.key_config.borrow().enter {
                     try_or_popup!(
                         self,
                         "staging error:",
@@ -268,7 +588,8 @@ impl Component for ChangesComponent {
                         InternalEvent::Update(NeedsUpdate::ALL),
                     );
                     Ok(EventState::Consumed)
-                } else if e == self.key_config.status_stage_all
+                } else if e
+                    == self.key_config.borrow().status_stage_all
                     && !self.is_empty()
                 {
                     if self.is_working_dir {
@@ -284,15 +605,61 @@ impl Component for ChangesComponent {
                         InternalEvent::StatusLastFileMoved,
                     );
                     Ok(EventState::Consumed)
-                } else if e == self.key_config.status_reset_item
+                } else if e
+                    == self.key_config.borrow().status_reset_item
                     && self.is_working_dir
                 {
                     Ok(self.dispatch_reset_workdir().into())
-                } else if e == self.key_config.status_ignore_file
+                } else if e
+                    == self.key_config.borrow().status_discard_all
+                    && self.is_working_dir
+                    && !self.is_empty()
+                {
+                    self.dispatch_discard_all();
+                    Ok(EventState::Consumed)
+                } else if e
+                    == self.key_config.borrow().status_open_repo
+                    && self.is_working_dir
+                {
+                    Ok(self.open_selected_folder().into())
+                } else if e
+                    == self
+                        .key_config
+                        .borrow()
+                        .status_update_submodule
+                    && self.is_working_dir
+                {
+                    if let Some(path) = self.selected_submodule() {
+                        try_or_popup!(
+                            self,
+                            "update submodule error:",
+                            self.update_submodule(&path)
+                        );
+                        Ok(EventState::Consumed)
+                    } else {
+                        Ok(EventState::NotConsumed)
+                    }
+                } else if e
+                    == self.key_config.borrow().status_ignore_file
                     && self.is_working_dir
                     && !self.is_empty()
                 {
                     Ok(self.add_to_ignore().into())
+                } else if e
+                    == self.key_config.borrow().status_skip_worktree
+                    && self.is_working_dir
+                {
+                    Ok(self.toggle_skip_worktree().into())
+                } else if e
+                    == self.key_config.borrow().status_toggle_hidden
+                {
+                    self.toggle_hidden();
+                    Ok(EventState::Consumed)
+                } else if e
+                    == self.key_config.borrow().open_context_menu
+                {
+                    open_context_menu(self, &self.queue);
+                    Ok(EventState::Consumed)
                 } else {
                     Ok(EventState::NotConsumed)
                 };
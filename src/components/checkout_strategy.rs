@@ -0,0 +1,241 @@
+use super::{
+    popup_paragraph, visibility_blocking, CommandBlocking,
+    CommandInfo, Component, DrawableComponent, EventState,
+};
+use crate::{
+    keys::SharedKeyConfig,
+    queue::{Action, InternalEvent, NeedsUpdate, Queue},
+    strings,
+    ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::sync::{self, CheckoutStrategy, RepoPath};
+use crossterm::event::Event;
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    text::{Span, Spans},
+    widgets::Clear,
+    Frame,
+};
+
+const STRATEGIES: [CheckoutStrategy; 3] = [
+    CheckoutStrategy::Stash,
+    CheckoutStrategy::Keep,
+    CheckoutStrategy::Force,
+];
+
+/// lets the user choose what happens to uncommitted changes that
+/// block checking out a branch
+pub struct CheckoutStrategyComponent {
+    repo: RepoPath,
+    branch_ref: Option<String>,
+    selection: usize,
+    visible: bool,
+    queue: Queue,
+    theme: SharedTheme,
+    key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for CheckoutStrategyComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        if self.visible {
+            let mut lines: Vec<Spans> = STRATEGIES
+                .iter()
+                .enumerate()
+                .map(|(idx, strategy)| {
+                    let (name, desc) =
+                        strings::checkout_strategy(*strategy);
+                    Spans::from(Span::styled(
+                        format!("{:6} {}", name, desc),
+                        self.theme
+                            .borrow()
+                            .text(true, idx == self.selection),
+                    ))
+                })
+                .collect();
+
+            if self.selected_strategy() == CheckoutStrategy::Force {
+                lines.push(Spans::default());
+                lines.push(Spans::from(Span::styled(
+                    strings::checkout_force_warning(),
+                    self.theme.borrow().text_danger(),
+                )));
+            }
+
+            let title = strings::checkout_strategy_popup_title(
+                &self.branch_name(),
+            );
+
+            let area = ui::centered_rect_absolute(60, 8, rect);
+            f.render_widget(Clear, area);
+            f.render_widget(
+                popup_paragraph(
+                    &title,
+                    lines,
+                    &self.theme.borrow(),
+                    true,
+                ),
+                area,
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl Component for CheckoutStrategyComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.visible || force_all {
+            out.clear();
+
+            out.push(CommandInfo::new(
+                strings::commands::scroll(&self.key_config),
+                true,
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::close_popup(&self.key_config),
+                true,
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::checkout_strategy_confirm(
+                    &self.key_config,
+                ),
+                true,
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<EventState> {
+        if self.visible {
+            if let Event::Key(e) = ev {
+                if e == self.key_config.borrow().exit_popup {
+                    self.hide();
+                } else if e == self.key_config.borrow().move_down {
+                    self.selection = self
+                        .selection
+                        .saturating_add(1)
+                        .min(STRATEGIES.len() - 1);
+                } else if e == self.key_config.borrow().move_up {
+                    self.selection = self.selection.saturating_sub(1);
+                } else if e == self.key_config.borrow().enter {
+                    self.confirm();
+                }
+            }
+
+            return Ok(EventState::Consumed);
+        }
+
+        Ok(EventState::NotConsumed)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+
+        Ok(())
+    }
+}
+
+impl CheckoutStrategyComponent {
+    ///
+    pub fn new(
+        repo: RepoPath,
+        queue: Queue,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+    ) -> Self {
+        Self {
+            repo,
+            branch_ref: None,
+            selection: 0,
+            visible: false,
+            queue,
+            theme,
+            key_config,
+        }
+    }
+
+    ///
+    pub fn open(&mut self, branch_ref: String) -> Result<()> {
+        self.branch_ref = Some(branch_ref);
+        self.selection = 0;
+        self.show()
+    }
+
+    fn selected_strategy(&self) -> CheckoutStrategy {
+        STRATEGIES[self.selection]
+    }
+
+    fn branch_name(&self) -> String {
+        self.branch_ref
+            .as_deref()
+            .map(|branch_ref| {
+                branch_ref
+                    .strip_prefix("refs/heads/")
+                    .unwrap_or(branch_ref)
+                    .to_string()
+            })
+            .unwrap_or_default()
+    }
+
+    /// stashing and keeping the changes is done right away,
+    /// discarding them needs another confirmation
+    fn confirm(&mut self) {
+        self.hide();
+
+        let branch_name = self.branch_name();
+
+        if let Some(branch_ref) = self.branch_ref.take() {
+            let strategy = self.selected_strategy();
+
+            if strategy == CheckoutStrategy::Force {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::ConfirmAction(
+                        Action::ForceCheckout(
+                            branch_ref,
+                            branch_name,
+                        ),
+                    ),
+                );
+            } else if let Err(e) = sync::checkout_with_strategy(
+                &self.repo,
+                &branch_ref,
+                strategy,
+            ) {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::ShowErrorMsg(format!(
+                        "switch branch error:\n{}",
+                        e
+                    )),
+                );
+            } else {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::Update(NeedsUpdate::ALL),
+                );
+            }
+        }
+    }
+}
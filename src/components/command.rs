@@ -1,5 +1,7 @@
+use crossterm::event::KeyEvent;
+
 ///
-#[derive(Clone, PartialEq, PartialOrd, Ord, Eq)]
+#[derive(Clone, Debug, PartialEq, PartialOrd, Ord, Eq)]
 pub struct CommandText {
     ///
     pub name: String,
@@ -46,6 +48,9 @@ pub struct CommandInfo {
     pub available: bool,
     /// used to order commands in quickbar
     pub order: i8,
+    /// key triggering the command, commands with a key are listed
+    /// in the context menu
+    pub key: Option<KeyEvent>,
 }
 
 impl CommandInfo {
@@ -61,6 +66,7 @@ impl CommandInfo {
             quick_bar: true,
             available,
             order: 0,
+            key: None,
         }
     }
 
@@ -71,6 +77,13 @@ impl CommandInfo {
         res
     }
 
+    ///
+    pub const fn key(self, key: KeyEvent) -> Self {
+        let mut res = self;
+        res.key = Some(key);
+        res
+    }
+
     ///
     pub const fn hidden(self) -> Self {
         let mut res = self;
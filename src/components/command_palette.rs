@@ -0,0 +1,259 @@
+use super::{
+    utils::fuzzy_match, visibility_blocking, CommandBlocking,
+    CommandInfo, CommandText, Component, DrawableComponent,
+    EventState,
+};
+use crate::{
+    keys::SharedKeyConfig,
+    queue::{InternalEvent, Queue},
+    strings,
+    ui::{self, calc_scroll_top, style::SharedTheme, Size},
+};
+use anyhow::Result;
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use std::cell::Cell;
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    text::{Span, Spans, Text},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
+    Frame,
+};
+use unicode_truncate::UnicodeTruncateStr;
+
+/// searches all commands available right now (the ones components
+/// list with their key) by typing parts of their name or description,
+/// choosing one presses its key
+pub struct CommandPaletteComponent {
+    items: Vec<(CommandText, KeyEvent)>,
+    filter: String,
+    visible: bool,
+    selection: usize,
+    scroll_top: Cell<usize>,
+    queue: Queue,
+    theme: SharedTheme,
+    key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for CommandPaletteComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        if self.visible {
+            const PERCENT_SIZE: Size = Size::new(60, 50);
+            const MIN_SIZE: Size = Size::new(60, 12);
+
+            let area = ui::centered_rect(
+                PERCENT_SIZE.width,
+                PERCENT_SIZE.height,
+                f.size(),
+            );
+            let area =
+                ui::rect_inside(MIN_SIZE, f.size().into(), area);
+            let area = area.intersection(rect);
+
+            f.render_widget(Clear, area);
+
+            let height = usize::from(area.height.saturating_sub(2));
+            self.scroll_top.set(calc_scroll_top(
+                self.scroll_top.get(),
+                height,
+                self.selection,
+            ));
+
+            f.render_widget(
+                Paragraph::new(
+                    self.get_text(
+                        area.width.saturating_sub(2),
+                        height,
+                    ),
+                )
+                .block(
+                    Block::default()
+                        .title(Span::styled(
+                            strings::title_command_palette(
+                                &self.filter,
+                            ),
+                            self.theme.borrow().title(true),
+                        ))
+                        .border_type(BorderType::Thick)
+                        .borders(Borders::ALL)
+                        .border_style(
+                            self.theme.borrow().block(true),
+                        ),
+                ),
+                area,
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl Component for CommandPaletteComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.visible || force_all {
+            out.clear();
+
+            out.push(CommandInfo::new(
+                strings::commands::close_popup(&self.key_config),
+                true,
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::command_palette_run(
+                    &self.key_config,
+                ),
+                self.filtered().next().is_some(),
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<EventState> {
+        if self.visible {
+            if let Event::Key(e) = ev {
+                if e == self.key_config.borrow().exit_popup {
+                    self.hide();
+                } else if e == self.key_config.borrow().enter {
+                    self.run_selected();
+                } else if e.code == KeyCode::Down {
+                    self.selection =
+                        self.selection.saturating_add(1).min(
+                            self.filtered().count().saturating_sub(1),
+                        );
+                } else if e.code == KeyCode::Up {
+                    self.selection = self.selection.saturating_sub(1);
+                } else if e.code == KeyCode::Backspace {
+                    self.filter.pop();
+                    self.selection = 0;
+                } else if let KeyCode::Char(c) = e.code {
+                    if !e.modifiers.contains(KeyModifiers::CONTROL) {
+                        self.filter.push(c);
+                        self.selection = 0;
+                    }
+                }
+            }
+
+            Ok(EventState::Consumed)
+        } else {
+            Ok(EventState::NotConsumed)
+        }
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn hide(&mut self) {
+        self.visible = false
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+
+        Ok(())
+    }
+}
+
+impl CommandPaletteComponent {
+    ///
+    pub fn new(
+        queue: Queue,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+    ) -> Self {
+        Self {
+            items: Vec::new(),
+            filter: String::new(),
+            visible: false,
+            selection: 0,
+            scroll_top: Cell::new(0),
+            queue,
+            theme,
+            key_config,
+        }
+    }
+
+    /// lists the `commands` that have a key and can be used now
+    pub fn open(&mut self, commands: Vec<CommandInfo>) -> Result<()> {
+        self.items = commands
+            .into_iter()
+            .filter(|cmd| cmd.enabled && cmd.available)
+            .filter_map(|cmd| cmd.key.map(|key| (cmd.text, key)))
+            .collect();
+        self.items.sort_by(|a, b| a.0.name.cmp(&b.0.name));
+        self.items.dedup_by(|a, b| a.0.name == b.0.name);
+
+        self.filter.clear();
+        self.selection = 0;
+        self.show()
+    }
+
+    /// commands matching the filter by name or description
+    fn filtered(
+        &self,
+    ) -> impl Iterator<Item = &(CommandText, KeyEvent)> {
+        self.items.iter().filter(move |(text, _)| {
+            fuzzy_match(&self.filter, &text.name)
+                || fuzzy_match(&self.filter, text.desc)
+        })
+    }
+
+    fn run_selected(&mut self) {
+        let key =
+            self.filtered().nth(self.selection).map(|(_, k)| *k);
+
+        if let Some(key) = key {
+            self.hide();
+            self.queue
+                .borrow_mut()
+                .push_back(InternalEvent::SimulateKey(key));
+        }
+    }
+
+    fn get_text(&self, width: u16, height: usize) -> Text {
+        let width = usize::from(width);
+        let name_length = width * 40 / 100;
+        let desc_length = width.saturating_sub(name_length + 1);
+
+        let lines = self
+            .filtered()
+            .enumerate()
+            .skip(self.scroll_top.get())
+            .take(height)
+            .map(|(idx, (text, _))| {
+                let selected = idx == self.selection;
+
+                Spans::from(vec![
+                    Span::styled(
+                        format!(
+                            "{:w$} ",
+                            text.name.unicode_truncate(name_length).0,
+                            w = name_length
+                        ),
+                        self.theme.borrow().text(true, selected),
+                    ),
+                    Span::styled(
+                        text.desc
+                            .unicode_truncate(desc_length)
+                            .0
+                            .to_string(),
+                        self.theme.borrow().commit_hash(selected),
+                    ),
+                ])
+            })
+            .collect::<Vec<_>>();
+
+        Text::from(lines)
+    }
+}
@@ -1,11 +1,11 @@
 use super::{
     textinput::TextInputComponent, visibility_blocking,
     CommandBlocking, CommandInfo, Component, DrawableComponent,
-    EventState, ExternalEditorComponent,
+    EventState, ExternalEditorComponent, InputType,
 };
 use crate::{
-    args::get_app_config_path,
     keys::SharedKeyConfig,
+    options::SharedOptions,
     queue::{InternalEvent, NeedsUpdate, Queue},
     strings,
     ui::style::SharedTheme,
@@ -14,14 +14,17 @@ use anyhow::Result;
 use asyncgit::{
     cached,
     sync::{
-        self, utils::get_config_string, CommitId, HookResult,
+        self,
+        status::{StatusItem, StatusItemType, StatusType},
+        utils::{get_config_string, repo_dir},
+        CommitId, CommitOptions, HookResult, RebaseState, RepoPath,
         RepoState,
     },
-    CWD,
 };
 use crossterm::event::Event;
 use easy_cast::Cast;
 use std::{
+    convert::TryFrom,
     fs::{read_to_string, File},
     io::{Read, Write},
     path::PathBuf,
@@ -29,7 +32,8 @@ use std::{
 use tui::{
     backend::Backend,
     layout::{Alignment, Rect},
-    widgets::Paragraph,
+    text::{Span, Spans},
+    widgets::{Block, Borders, Clear, Paragraph},
     Frame,
 };
 
@@ -37,20 +41,41 @@ enum Mode {
     Normal,
     Amend(CommitId),
     Merge(Vec<CommitId>),
+    /// resolving the conflicts of a rebase step
+    Rebase,
 }
 
 pub struct CommitComponent {
+    repo: RepoPath,
     input: TextInputComponent,
     mode: Mode,
     queue: Queue,
     key_config: SharedKeyConfig,
     git_branch_name: cached::BranchName,
     commit_template: Option<String>,
+    /// position in the message history while browsing it
+    history_idx: Option<usize>,
+    /// message typed before browsing the history
+    draft: String,
+    /// message taken from this commit, committing it unchanged
+    /// also reuses its author
+    reuse: Option<(CommitId, String)>,
+    /// modified tracked files staged with the commit (`git commit
+    /// -a`), `None` unless the user asked for it
+    stage_tracked: Option<Vec<String>>,
+    /// the popup was opened without staged changes, committing
+    /// records an empty commit (`git commit --allow-empty`)
+    nothing_staged: bool,
+    /// branch created at `HEAD` to commit to instead of the current
+    /// one
+    new_branch: Option<String>,
+    /// matches of the last path completion and the one inserted
+    completion: Option<(Vec<String>, usize)>,
+    branch_input: TextInputComponent,
     theme: SharedTheme,
+    options: SharedOptions,
 }
 
-const FIRST_LINE_LIMIT: usize = 50;
-
 impl DrawableComponent for CommitComponent {
     fn draw<B: Backend>(
         &self,
@@ -61,6 +86,8 @@ impl DrawableComponent for CommitComponent {
             self.input.draw(f, rect)?;
             self.draw_branch_name(f);
             self.draw_warnings(f);
+            self.draw_stage_tracked(f);
+            self.branch_input.draw(f, rect)?;
         }
 
         Ok(())
@@ -73,6 +100,20 @@ impl Component for CommitComponent {
         out: &mut Vec<CommandInfo>,
         force_all: bool,
     ) -> CommandBlocking {
+        if self.branch_input.is_visible() {
+            self.branch_input.commands(out, force_all);
+
+            out.push(CommandInfo::new(
+                strings::commands::commit_new_branch_confirm(
+                    &self.key_config,
+                ),
+                !self.branch_input.get_text().trim().is_empty(),
+                true,
+            ));
+
+            return visibility_blocking(self);
+        }
+
         self.input.commands(out, force_all);
 
         if self.is_visible() || force_all {
@@ -95,29 +136,112 @@ impl Component for CommitComponent {
                 true,
                 true,
             ));
+
+            out.push(CommandInfo::new(
+                strings::commands::commit_history(&self.key_config),
+                !self
+                    .options
+                    .borrow()
+                    .commit_msg_history()
+                    .is_empty(),
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::commit_reuse_msg(&self.key_config),
+                self.can_reuse(),
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::commit_stage_tracked(
+                    &self.key_config,
+                    self.stage_tracked.is_some(),
+                ),
+                true,
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::commit_complete_path(
+                    &self.key_config,
+                ),
+                true,
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::commit_new_branch(
+                    &self.key_config,
+                    self.new_branch.is_some(),
+                ),
+                matches!(self.mode, Mode::Normal),
+                true,
+            ));
         }
 
         visibility_blocking(self)
     }
 
     fn event(&mut self, ev: Event) -> Result<EventState> {
+        if self.branch_input.is_visible() {
+            if !self.branch_input.event(ev)?.is_consumed() {
+                if let Event::Key(e) = ev {
+                    if e == self.key_config.borrow().enter {
+                        self.set_new_branch();
+                    }
+                }
+            }
+            return Ok(EventState::Consumed);
+        }
+
         if self.is_visible() {
             if self.input.event(ev)?.is_consumed() {
+                if !self.input.is_visible() {
+                    // aborted, keep the message around
+                    self.remember_msg();
+                }
                 return Ok(EventState::Consumed);
             }
 
             if let Event::Key(e) = ev {
-                if e == self.key_config.enter && self.can_commit() {
+                if e == self.key_config.borrow().enter
+                    && self.can_commit()
+                {
                     self.commit()?;
-                } else if e == self.key_config.commit_amend
+                } else if e == self.key_config.borrow().commit_amend
                     && self.can_amend()
                 {
                     self.amend()?;
-                } else if e == self.key_config.open_commit_editor {
+                } else if e
+                    == self.key_config.borrow().open_commit_editor
+                {
                     self.queue.borrow_mut().push_back(
                         InternalEvent::OpenExternalEditor(None),
                     );
                     self.hide();
+                } else if e == self.key_config.borrow().move_up {
+                    self.browse_history(true);
+                } else if e == self.key_config.borrow().move_down {
+                    self.browse_history(false);
+                } else if e
+                    == self.key_config.borrow().commit_reuse_msg
+                    && self.can_reuse()
+                {
+                    self.reuse_head_msg()?;
+                } else if e
+                    == self.key_config.borrow().commit_stage_tracked
+                {
+                    self.toggle_stage_tracked()?;
+                } else if e
+                    == self.key_config.borrow().commit_new_branch
+                    && matches!(self.mode, Mode::Normal)
+                {
+                    self.toggle_new_branch()?;
+                } else if e
+                    == self.key_config.borrow().commit_complete_path
+                {
+                    self.complete_path()?;
                 } else {
                 }
                 // stop key event propagation
@@ -133,6 +257,7 @@ impl Component for CommitComponent {
     }
 
     fn hide(&mut self) {
+        self.branch_input.hide();
         self.input.hide()
     }
 
@@ -143,26 +268,49 @@ impl Component for CommitComponent {
         }
 
         self.mode = Mode::Normal;
+        self.history_idx = None;
+        self.completion = None;
 
-        self.mode = if sync::repo_state(CWD)? == RepoState::Merge {
-            let ids = sync::mergehead_ids(CWD)?;
+        if self.stage_tracked.is_some() {
+            // the workdir may have changed since the popup was closed
+            self.stage_tracked = Some(self.tracked_changes()?);
+        }
+
+        self.mode = if sync::repo_state(&self.repo)?
+            == RepoState::Merge
+        {
+            let ids = sync::mergehead_ids(&self.repo)?;
             self.input.set_title(strings::commit_title_merge());
-            self.input.set_text(sync::merge_msg(CWD)?);
+            self.input.set_text(sync::merge_msg(&self.repo)?);
             Mode::Merge(ids)
+        } else if sync::repo_state(&self.repo)? == RepoState::Rebase {
+            self.input.set_title(strings::commit_title_rebase());
+            self.input.set_text(self.rebase_step_msg()?);
+            Mode::Rebase
         } else {
-            self.commit_template =
-                get_config_string(CWD, "commit.template")
-                    .ok()
-                    .flatten()
-                    .and_then(|path| read_to_string(path).ok());
+            let staged = sync::status::get_status(
+                &self.repo,
+                StatusType::Stage,
+                false,
+            )?;
+            self.nothing_staged = staged.is_empty();
 
-            if self.is_empty() {
+            // a prefill nobody edited follows the staged files
+            let untouched = self.is_empty() || !self.is_changed();
+
+            self.commit_template = self.prefill(&staged);
+
+            if untouched {
                 if let Some(s) = &self.commit_template {
                     self.input.set_text(s.clone());
                 }
             }
 
-            self.input.set_title(strings::commit_title());
+            self.input.set_title(if self.nothing_staged {
+                strings::commit_title_empty()
+            } else {
+                strings::commit_title()
+            });
             Mode::Normal
         };
 
@@ -175,9 +323,11 @@ impl Component for CommitComponent {
 impl CommitComponent {
     ///
     pub fn new(
+        repo: RepoPath,
         queue: Queue,
         theme: SharedTheme,
         key_config: SharedKeyConfig,
+        options: SharedOptions,
     ) -> Self {
         Self {
             queue,
@@ -190,10 +340,27 @@ impl CommitComponent {
                 &strings::commit_msg(&key_config),
                 true,
             ),
+            branch_input: TextInputComponent::new(
+                theme.clone(),
+                key_config.clone(),
+                &strings::commit_new_branch_popup_title(&key_config),
+                &strings::commit_new_branch_popup_msg(&key_config),
+                true,
+            )
+            .with_input_type(InputType::Singleline),
             key_config,
-            git_branch_name: cached::BranchName::new(CWD),
+            git_branch_name: cached::BranchName::new(repo.clone()),
             commit_template: None,
+            history_idx: None,
+            draft: String::new(),
+            reuse: None,
+            stage_tracked: None,
+            nothing_staged: false,
+            new_branch: None,
+            completion: None,
             theme,
+            options,
+            repo,
         }
     }
 
@@ -206,8 +373,11 @@ impl CommitComponent {
 
     fn draw_branch_name<B: Backend>(&self, f: &mut Frame<B>) {
         if let Some(name) = self.git_branch_name.last() {
-            let w = Paragraph::new(format!("{{{}}}", name))
-                .alignment(Alignment::Right);
+            let name = self.new_branch.as_ref().map_or_else(
+                || format!("{{{}}}", name),
+                |new| strings::commit_new_branch_name(&name, new),
+            );
+            let w = Paragraph::new(name).alignment(Alignment::Right);
 
             let rect = {
                 let mut rect = self.input.get_area();
@@ -221,19 +391,20 @@ impl CommitComponent {
     }
 
     fn draw_warnings<B: Backend>(&self, f: &mut Frame<B>) {
-        let first_line = self
-            .input
-            .get_text()
-            .lines()
-            .next()
-            .map(str::len)
-            .unwrap_or_default();
-
-        if first_line > FIRST_LINE_LIMIT {
-            let msg = strings::commit_first_line_warning(first_line);
+        let lints = sync::lint_commit_msg(
+            self.input.get_text(),
+            &self.options.borrow().commit_msg_lint(),
+        );
+
+        if !lints.is_empty() {
+            let msg = lints
+                .iter()
+                .map(strings::commit_lint_warning)
+                .collect::<Vec<_>>()
+                .join(" ");
             let msg_length: u16 = msg.len().cast();
-            let w =
-                Paragraph::new(msg).style(self.theme.text_danger());
+            let w = Paragraph::new(msg)
+                .style(self.theme.borrow().text_danger());
 
             let rect = {
                 let mut rect = self.input.get_area();
@@ -251,11 +422,108 @@ impl CommitComponent {
         }
     }
 
+    /// lists the files staged with the commit below the input
+    fn draw_stage_tracked<B: Backend>(&self, f: &mut Frame<B>) {
+        if let Some(paths) = &self.stage_tracked {
+            let input = self.input.get_area();
+            let top = input.y.saturating_add(input.height);
+            let height = u16::try_from(paths.len().max(1))
+                .unwrap_or(u16::MAX)
+                .saturating_add(2)
+                .min(f.size().height.saturating_sub(top));
+
+            let text: Vec<Spans> = if paths.is_empty() {
+                vec![Spans::from(Span::styled(
+                    strings::commit_stage_tracked_empty(),
+                    self.theme.borrow().text(false, false),
+                ))]
+            } else {
+                paths
+                    .iter()
+                    .map(|path| {
+                        Spans::from(Span::styled(
+                            path.as_str(),
+                            self.theme.borrow().text(true, false),
+                        ))
+                    })
+                    .collect()
+            };
+
+            let area = Rect::new(input.x, top, input.width, height);
+
+            f.render_widget(Clear, area);
+            f.render_widget(
+                Paragraph::new(text).block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .border_style(self.theme.borrow().block(true))
+                        .title(Span::styled(
+                            strings::commit_stage_tracked_title(
+                                paths.len(),
+                            ),
+                            self.theme.borrow().title(true),
+                        )),
+                ),
+                area,
+            );
+        }
+    }
+
+    /// toggles staging the modified and deleted tracked files with
+    /// the commit like `git commit -a`, the files are listed once
+    /// when enabling it and only those get staged
+    fn toggle_stage_tracked(&mut self) -> Result<()> {
+        self.stage_tracked = if self.stage_tracked.is_some() {
+            None
+        } else {
+            Some(self.tracked_changes()?)
+        };
+
+        Ok(())
+    }
+
+    /// asks for the name of the branch to commit to, or goes back
+    /// to committing to the current branch
+    fn toggle_new_branch(&mut self) -> Result<()> {
+        if self.new_branch.take().is_none() {
+            self.branch_input.clear();
+            self.branch_input.show()?;
+        }
+
+        Ok(())
+    }
+
+    fn set_new_branch(&mut self) {
+        let name = self.branch_input.get_text().trim().to_string();
+
+        if !name.is_empty() {
+            self.new_branch = Some(name);
+            self.branch_input.hide();
+        }
+    }
+
+    fn tracked_changes(&self) -> Result<Vec<String>> {
+        Ok(sync::status::get_status(
+            &self.repo,
+            StatusType::WorkingDir,
+            false,
+        )?
+        .into_iter()
+        .filter(|item| {
+            item.status != StatusItemType::New
+                && item.submodule.is_none()
+        })
+        .map(|item| item.path)
+        .collect())
+    }
+
+    /// lets the user write the message in the external editor like
+    /// `git commit` does and commits it, an empty message returns to
+    /// the commit popup
     pub fn show_editor(&mut self) -> Result<()> {
-        const COMMIT_MSG_FILE_NAME: &str = "COMMITMSG_EDITOR";
-        //TODO: use a tmpfile here
-        let mut config_path: PathBuf = get_app_config_path()?;
-        config_path.push(COMMIT_MSG_FILE_NAME);
+        const COMMIT_MSG_FILE_NAME: &str = "COMMIT_EDITMSG";
+        let config_path: PathBuf =
+            repo_dir(&self.repo)?.join(COMMIT_MSG_FILE_NAME);
 
         {
             let mut file = File::create(&config_path)?;
@@ -269,14 +537,16 @@ impl CommitComponent {
             )?;
         }
 
-        ExternalEditorComponent::open_file_in_editor(&config_path)?;
+        ExternalEditorComponent::open_file_in_editor(
+            &self.repo,
+            &config_path,
+        )?;
 
         let mut message = String::new();
 
         let mut file = File::open(&config_path)?;
         file.read_to_string(&mut message)?;
         drop(file);
-        std::fs::remove_file(&config_path)?;
 
         let message: String = message
             .lines()
@@ -292,19 +562,162 @@ impl CommitComponent {
         let message = message.trim().to_string();
 
         self.input.set_text(message);
-        self.input.show()?;
 
-        Ok(())
+        if self.can_commit() {
+            self.commit()?;
+
+            // a successful commit clears the message
+            if self.is_empty() {
+                return Ok(());
+            }
+        }
+
+        // empty or failed to commit, the popup lets the user retry
+        self.input.show()
     }
 
+    /// the message stays in the input until the commit succeeded,
+    /// failing hooks do not lose it
     fn commit(&mut self) -> Result<()> {
         let msg = self.input.get_text().clone();
-        self.input.clear();
+        self.remember_msg();
         self.commit_with_msg(msg)
     }
 
+    fn remember_msg(&self) {
+        if self.can_commit() {
+            self.options
+                .borrow_mut()
+                .add_commit_msg(self.input.get_text());
+        }
+    }
+
+    /// `older` moves back in the history, moving past the newest
+    /// entry restores the draft
+    fn browse_history(&mut self, older: bool) {
+        let history =
+            self.options.borrow().commit_msg_history().to_vec();
+
+        let idx = match (self.history_idx, older) {
+            (None, true) if !history.is_empty() => {
+                self.draft = self.input.get_text().clone();
+                Some(0)
+            }
+            (Some(idx), true) => {
+                Some(idx.saturating_add(1).min(history.len() - 1))
+            }
+            (Some(0), false) => None,
+            (Some(idx), false) => Some(idx - 1),
+            (None, _) => return,
+        };
+
+        self.history_idx = idx;
+        self.input.set_text(idx.map_or_else(
+            || std::mem::take(&mut self.draft),
+            |idx| history[idx].clone(),
+        ));
+    }
+
+    fn can_reuse(&self) -> bool {
+        matches!(self.mode, Mode::Normal)
+            && sync::get_head(&self.repo).is_ok()
+    }
+
+    /// commits the merge or the rebase step with its message without
+    /// showing the popup, a failing hook stops it like a commit
+    pub fn continue_resolved(&mut self) -> Result<()> {
+        let state = sync::repo_state(&self.repo)?;
+        let (mode, msg) = if state == RepoState::Merge {
+            (
+                Mode::Merge(sync::mergehead_ids(&self.repo)?),
+                sync::merge_msg(&self.repo)?,
+            )
+        } else if state == RepoState::Rebase {
+            (Mode::Rebase, self.rebase_step_msg()?)
+        } else {
+            return Ok(());
+        };
+
+        self.mode = mode;
+        self.stage_tracked = None;
+        self.commit_with_msg(msg)
+    }
+
+    /// the original message of the commit being replayed
+    fn rebase_step_msg(&self) -> Result<String> {
+        let step = sync::rebase_progress(&self.repo)?.and_then(|p| {
+            p.current
+                .and_then(|current| p.steps.get(current).cloned())
+        });
+
+        Ok(match step {
+            Some(step) => {
+                sync::get_commit_details(&self.repo, step.id)?
+                    .message
+                    .map(|msg| msg.combine())
+                    .unwrap_or_default()
+            }
+            None => String::new(),
+        })
+    }
+
+    /// opens the rebase popup again if the next step has conflicts
+    fn rebase_continued(&self, state: RebaseState) {
+        if state == RebaseState::Conflicted {
+            self.queue
+                .borrow_mut()
+                .push_back(InternalEvent::OpenRebase);
+        }
+    }
+
+    fn reuse_head_msg(&mut self) -> Result<()> {
+        let id = sync::get_head(&self.repo)?;
+        let details = sync::get_commit_details(&self.repo, id)?;
+
+        if let Some(msg) = details.message {
+            let msg = msg.combine();
+            self.input.set_text(msg.clone());
+            self.reuse = Some((id, msg));
+        }
+
+        Ok(())
+    }
+
+    /// `false` if the branch to commit on could not be created
+    fn create_new_branch(&mut self) -> bool {
+        if let (Mode::Normal, Some(name)) =
+            (&self.mode, &self.new_branch)
+        {
+            // the branch starts at HEAD, switching to it keeps the
+            // index and the working tree as they are
+            if let Err(e) = sync::create_branch(&self.repo, name) {
+                log::error!("create branch error: {}", e);
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::ShowErrorMsg(format!(
+                        "create branch error:\n{}",
+                        e
+                    )),
+                );
+                return false;
+            }
+            self.new_branch = None;
+        }
+
+        true
+    }
+
     fn commit_with_msg(&mut self, msg: String) -> Result<()> {
-        if let HookResult::NotOk(e) = sync::hooks_pre_commit(CWD)? {
+        if !self.create_new_branch() {
+            return Ok(());
+        }
+
+        if let Some(paths) = &self.stage_tracked {
+            sync::stage_paths(&self.repo, paths)?;
+        }
+
+        if let HookResult::NotOk(e) =
+            sync::hooks_pre_commit(&self.repo)?
+        {
             log::error!("pre-commit hook error: {}", e);
             self.queue.borrow_mut().push_back(
                 InternalEvent::ShowErrorMsg(format!(
@@ -316,7 +729,7 @@ impl CommitComponent {
         }
         let mut msg = msg;
         if let HookResult::NotOk(e) =
-            sync::hooks_commit_msg(CWD, &mut msg)?
+            sync::hooks_commit_msg(&self.repo, &mut msg)?
         {
             log::error!("commit-msg hook error: {}", e);
             self.queue.borrow_mut().push_back(
@@ -328,10 +741,34 @@ impl CommitComponent {
             return Ok(());
         }
 
-        let res = match &self.mode {
-            Mode::Normal => sync::commit(CWD, &msg),
-            Mode::Amend(amend) => sync::amend(CWD, *amend, &msg),
-            Mode::Merge(ids) => sync::merge_commit(CWD, &msg, ids),
+        let res = match (&self.mode, &self.reuse) {
+            (Mode::Normal, Some((id, reused))) if *reused == msg => {
+                sync::commit_with_msg_from(&self.repo, *id)
+            }
+            (Mode::Normal, _) => sync::commit_with_options(
+                &self.repo,
+                &msg,
+                CommitOptions {
+                    allow_empty: self.nothing_staged,
+                    allow_empty_message: self
+                        .options
+                        .borrow()
+                        .commit_allow_empty_message(),
+                },
+            ),
+            (Mode::Amend(amend), _) => {
+                sync::amend(&self.repo, *amend, &msg)
+            }
+            (Mode::Merge(ids), _) => {
+                sync::merge_commit(&self.repo, &msg, ids)
+            }
+            (Mode::Rebase, _) => {
+                sync::continue_rebase(&self.repo, Some(&msg))
+                    .and_then(|state| {
+                        self.rebase_continued(state);
+                        sync::get_head(&self.repo)
+                    })
+            }
         };
 
         if let Err(e) = res {
@@ -345,7 +782,9 @@ impl CommitComponent {
             return Ok(());
         }
 
-        if let HookResult::NotOk(e) = sync::hooks_post_commit(CWD)? {
+        if let HookResult::NotOk(e) =
+            sync::hooks_post_commit(&self.repo)?
+        {
             log::error!("post-commit hook error: {}", e);
             self.queue.borrow_mut().push_back(
                 InternalEvent::ShowErrorMsg(format!(
@@ -355,6 +794,9 @@ impl CommitComponent {
             );
         }
 
+        self.input.clear();
+        self.reuse = None;
+        self.stage_tracked = None;
         self.hide();
 
         self.queue
@@ -365,15 +807,73 @@ impl CommitComponent {
     }
 
     fn can_commit(&self) -> bool {
-        !self.is_empty() && self.is_changed()
+        self.options.borrow().commit_allow_empty_message()
+            || (!self.is_empty() && self.is_changed())
     }
 
     fn can_amend(&self) -> bool {
         matches!(self.mode, Mode::Normal)
-            && sync::get_head(CWD).is_ok()
+            && sync::get_head(&self.repo).is_ok()
             && (self.is_empty() || !self.is_changed())
     }
 
+    /// completes the word at the cursor to a staged path, cycles
+    /// through the matches when used again right away
+    fn complete_path(&mut self) -> Result<()> {
+        let word = self.input.word_before_cursor().to_string();
+
+        if let Some((matches, idx)) = &mut self.completion {
+            if matches.get(*idx) == Some(&word) {
+                *idx = (*idx + 1) % matches.len();
+                self.input.replace_word_before_cursor(&matches[*idx]);
+                return Ok(());
+            }
+        }
+
+        let staged = sync::status::get_status(
+            &self.repo,
+            StatusType::Stage,
+            false,
+        )?;
+        let matches = path_completions(
+            staged.iter().map(|item| item.path.as_str()),
+            &word,
+        );
+
+        if let Some(first) = matches.first() {
+            self.input.replace_word_before_cursor(first);
+        }
+        self.completion = if matches.is_empty() {
+            None
+        } else {
+            Some((matches, 0))
+        };
+
+        Ok(())
+    }
+
+    /// the scope of the staged files (see `commit_scopes` option)
+    /// followed by the `commit.template`
+    fn prefill(&self, staged: &[StatusItem]) -> Option<String> {
+        let paths = staged
+            .iter()
+            .map(|item| item.path.as_str())
+            .collect::<Vec<_>>();
+        let scope = self.options.borrow().commit_scope_prefix(&paths);
+        let template =
+            get_config_string(&self.repo, "commit.template")
+                .ok()
+                .flatten()
+                .and_then(|path| read_to_string(path).ok());
+
+        match (scope, template) {
+            (Some(scope), Some(template)) => {
+                Some(format!("{}{}", scope, template))
+            }
+            (scope, template) => scope.or(template),
+        }
+    }
+
     fn is_empty(&self) -> bool {
         self.input.get_text().is_empty()
     }
@@ -385,10 +885,10 @@ impl CommitComponent {
 
     fn amend(&mut self) -> Result<()> {
         if self.can_amend() {
-            let id = sync::get_head(CWD)?;
+            let id = sync::get_head(&self.repo)?;
             self.mode = Mode::Amend(id);
 
-            let details = sync::get_commit_details(CWD, id)?;
+            let details = sync::get_commit_details(&self.repo, id)?;
 
             self.input.set_title(strings::commit_title_amend(
                 &self.key_config,
@@ -402,3 +902,31 @@ impl CommitComponent {
         Ok(())
     }
 }
+
+/// the staged `paths`, their folders and their parts after each
+/// folder (down to the file name) starting with `fragment`, shortest
+/// first
+fn path_completions<'a>(
+    paths: impl Iterator<Item = &'a str>,
+    fragment: &str,
+) -> Vec<String> {
+    let mut matches = paths
+        .flat_map(|path| {
+            let folders =
+                path.match_indices('/').flat_map(move |(idx, _)| {
+                    vec![&path[..idx], &path[idx + 1..]]
+                });
+            std::iter::once(path).chain(folders)
+        })
+        .filter(|part| {
+            part.starts_with(fragment) && *part != fragment
+        })
+        .map(String::from)
+        .collect::<Vec<_>>();
+
+    matches
+        .sort_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)));
+    matches.dedup();
+
+    matches
+}
@@ -5,13 +5,14 @@ use crate::{
         ScrollType,
     },
     keys::SharedKeyConfig,
+    queue::{InternalEvent, Queue},
     strings::{self, order},
-    ui::{self, style::SharedTheme},
+    ui::{self, markdown::markdown_lines, style::SharedTheme},
 };
 use anyhow::Result;
-use asyncgit::{
-    sync::{self, CommitDetails, CommitId, CommitMessage},
-    CWD,
+use asyncgit::sync::{
+    self, CommitDetails, CommitId, CommitMessage, RepoPath,
+    SignatureInfo,
 };
 use crossterm::event::Event;
 use itertools::Itertools;
@@ -33,6 +34,7 @@ enum Detail {
 }
 
 pub struct DetailsComponent {
+    repo: RepoPath,
     data: Option<CommitDetails>,
     tags: Vec<String>,
     theme: SharedTheme,
@@ -41,6 +43,9 @@ pub struct DetailsComponent {
     scroll_top: Cell<usize>,
     key_config: SharedKeyConfig,
     scroll_to_bottom_on_redraw: Cell<bool>,
+    /// message body shown as is instead of rendered markdown
+    raw_message: bool,
+    queue: Queue,
 }
 
 type WrappedCommitMessage<'a> =
@@ -49,6 +54,8 @@ type WrappedCommitMessage<'a> =
 impl DetailsComponent {
     ///
     pub const fn new(
+        repo: RepoPath,
+        queue: Queue,
         theme: SharedTheme,
         key_config: SharedKeyConfig,
         focused: bool,
@@ -61,7 +68,10 @@ impl DetailsComponent {
             current_size: Cell::new((0, 0)),
             scroll_top: Cell::new(0),
             scroll_to_bottom_on_redraw: Cell::new(false),
+            raw_message: false,
+            queue,
             key_config,
+            repo,
         }
     }
 
@@ -72,8 +82,9 @@ impl DetailsComponent {
     ) -> Result<()> {
         self.tags.clear();
 
-        self.data =
-            id.and_then(|id| sync::get_commit_details(CWD, id).ok());
+        self.data = id.and_then(|id| {
+            sync::get_commit_details(&self.repo, id).ok()
+        });
 
         self.scroll_top.set(0);
 
@@ -125,9 +136,12 @@ impl DetailsComponent {
 
     fn get_theme_for_line(&self, bold: bool) -> Style {
         if bold {
-            self.theme.text(true, false).add_modifier(Modifier::BOLD)
+            self.theme
+                .borrow()
+                .text(true, false)
+                .add_modifier(Modifier::BOLD)
         } else {
-            self.theme.text(true, false)
+            self.theme.borrow().text(true, false)
         }
     }
 
@@ -136,6 +150,15 @@ impl DetailsComponent {
         width: usize,
         height: usize,
     ) -> Vec<Spans> {
+        if let Some(body) = self.markdown_body() {
+            return self
+                .markdown_message(body, width)
+                .into_iter()
+                .skip(self.scroll_top.get())
+                .take(height)
+                .collect();
+        }
+
         let (wrapped_title, wrapped_message) =
             Self::get_wrapped_lines(&self.data, width);
 
@@ -154,35 +177,130 @@ impl DetailsComponent {
             .collect()
     }
 
+    /// lets the user choose a link of the message to open
+    fn open_links(&self) {
+        if let Some(message) =
+            self.data.as_ref().and_then(|data| data.message.clone())
+        {
+            self.queue.borrow_mut().push_back(
+                InternalEvent::OpenLinks(message.combine()),
+            );
+        }
+    }
+
+    fn has_body(&self) -> bool {
+        self.data
+            .as_ref()
+            .and_then(|data| data.message.as_ref())
+            .map_or(false, |message| message.body.is_some())
+    }
+
+    /// message body to render as markdown unless the raw text is
+    /// asked for
+    fn markdown_body(&self) -> Option<&str> {
+        if self.raw_message {
+            return None;
+        }
+
+        self.data
+            .as_ref()
+            .and_then(|data| data.message.as_ref())
+            .and_then(|message| message.body.as_deref())
+    }
+
+    /// the subject as is, followed by the rendered `body`
+    fn markdown_message(
+        &self,
+        body: &str,
+        width: usize,
+    ) -> Vec<Spans<'static>> {
+        let subject = self
+            .data
+            .as_ref()
+            .and_then(|data| data.message.as_ref())
+            .map(|message| message.subject.as_str())
+            .unwrap_or_default();
+
+        textwrap::wrap(subject, width)
+            .into_iter()
+            .map(|line| {
+                Spans::from(Span::styled(
+                    line.to_string(),
+                    self.get_theme_for_line(true),
+                ))
+            })
+            .chain(markdown_lines(
+                body,
+                Some(width),
+                &self.theme.borrow(),
+            ))
+            .collect()
+    }
+
+    fn number_of_lines(&self, width: usize) -> usize {
+        self.markdown_body().map_or_else(
+            || Self::get_number_of_lines(&self.data, width),
+            |body| self.markdown_message(body, width).len(),
+        )
+    }
+
     fn style_detail(&self, field: &Detail) -> Span {
         match field {
             Detail::Author => Span::styled(
                 Cow::from(strings::commit::details_author(
                     &self.key_config,
                 )),
-                self.theme.text(false, false),
+                self.theme.borrow().text(false, false),
             ),
             Detail::Date => Span::styled(
                 Cow::from(strings::commit::details_date(
                     &self.key_config,
                 )),
-                self.theme.text(false, false),
+                self.theme.borrow().text(false, false),
             ),
             Detail::Commiter => Span::styled(
                 Cow::from(strings::commit::details_committer(
                     &self.key_config,
                 )),
-                self.theme.text(false, false),
+                self.theme.borrow().text(false, false),
             ),
             Detail::Sha => Span::styled(
                 Cow::from(strings::commit::details_tags(
                     &self.key_config,
                 )),
-                self.theme.text(false, false),
+                self.theme.borrow().text(false, false),
             ),
         }
     }
 
+    fn signature_line(&self, signature: &SignatureInfo) -> Spans {
+        let mut spans = vec![
+            Span::styled(
+                Cow::from(strings::commit::details_signature(
+                    &self.key_config,
+                )),
+                self.theme.borrow().text(false, false),
+            ),
+            Span::styled(
+                Cow::from(strings::commit::details_signature_status(
+                    signature.status,
+                )),
+                self.theme
+                    .borrow()
+                    .commit_signature(signature.status),
+            ),
+        ];
+
+        if let Some(ref signer) = signature.signer {
+            spans.push(Span::styled(
+                Cow::from(format!(" {}", signer)),
+                self.theme.borrow().text(true, false),
+            ));
+        }
+
+        Spans::from(spans)
+    }
+
     #[allow(unstable_name_collisions)]
     fn get_text_info(&self) -> Vec<Spans> {
         if let Some(ref data) = self.data {
@@ -194,7 +312,7 @@ impl DetailsComponent {
                             "{} <{}>",
                             data.author.name, data.author.email
                         )),
-                        self.theme.text(true, false),
+                        self.theme.borrow().text(true, false),
                     ),
                 ]),
                 Spans::from(vec![
@@ -204,7 +322,7 @@ impl DetailsComponent {
                             data.author.time,
                             false,
                         )),
-                        self.theme.text(true, false),
+                        self.theme.borrow().text(true, false),
                     ),
                 ]),
             ];
@@ -218,7 +336,7 @@ impl DetailsComponent {
                                 "{} <{}>",
                                 committer.name, committer.email
                             )),
-                            self.theme.text(true, false),
+                            self.theme.borrow().text(true, false),
                         ),
                     ]),
                     Spans::from(vec![
@@ -228,7 +346,7 @@ impl DetailsComponent {
                                 committer.time,
                                 false,
                             )),
-                            self.theme.text(true, false),
+                            self.theme.borrow().text(true, false),
                         ),
                     ]),
                 ]);
@@ -239,14 +357,26 @@ impl DetailsComponent {
                     Cow::from(strings::commit::details_sha(
                         &self.key_config,
                     )),
-                    self.theme.text(false, false),
+                    self.theme.borrow().text(false, false),
                 ),
                 Span::styled(
-                    Cow::from(data.hash.clone()),
-                    self.theme.text(true, false),
+                    Cow::from(if data.replaced {
+                        format!(
+                            "{} {}",
+                            data.hash,
+                            strings::REPLACED_COMMIT_MARKER
+                        )
+                    } else {
+                        data.hash.clone()
+                    }),
+                    self.theme.borrow().text(true, false),
                 ),
             ]));
 
+            if let Some(ref signature) = data.signature {
+                res.push(self.signature_line(signature));
+            }
+
             if !self.tags.is_empty() {
                 res.push(Spans::from(
                     self.style_detail(&Detail::Sha),
@@ -258,12 +388,12 @@ impl DetailsComponent {
                         .map(|tag| {
                             Span::styled(
                                 Cow::from(tag),
-                                self.theme.text(true, false),
+                                self.theme.borrow().text(true, false),
                             )
                         })
                         .intersperse(Span::styled(
                             Cow::from(","),
-                            self.theme.text(true, false),
+                            self.theme.borrow().text(true, false),
                         ))
                         .collect::<Vec<Span>>(),
                 ));
@@ -281,8 +411,7 @@ impl DetailsComponent {
             let width = self.current_size.get().0 as usize;
             let height = self.current_size.get().1 as usize;
 
-            let number_of_lines =
-                Self::get_number_of_lines(&self.data, width);
+            let number_of_lines = self.number_of_lines(width);
 
             let max = number_of_lines.saturating_sub(height) as usize;
 
@@ -314,10 +443,20 @@ impl DrawableComponent for DetailsComponent {
         f: &mut Frame<B>,
         rect: Rect,
     ) -> Result<()> {
+        let signature_height = u16::from(
+            self.data
+                .as_ref()
+                .map_or(false, |data| data.signature.is_some()),
+        );
+
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints(
-                [Constraint::Length(8), Constraint::Min(10)].as_ref(),
+                [
+                    Constraint::Length(8 + signature_height),
+                    Constraint::Min(10),
+                ]
+                .as_ref(),
             )
             .split(rect);
 
@@ -327,7 +466,7 @@ impl DrawableComponent for DetailsComponent {
                     &self.key_config,
                 ),
                 Text::from(self.get_text_info()),
-                &self.theme,
+                &self.theme.borrow(),
                 false,
             ),
             chunks[0],
@@ -344,11 +483,8 @@ impl DrawableComponent for DetailsComponent {
 
         if self.scroll_to_bottom_on_redraw.get() {
             self.scroll_top.set(
-                Self::get_number_of_lines(
-                    &self.data,
-                    usize::from(width),
-                )
-                .saturating_sub(usize::from(height)),
+                self.number_of_lines(usize::from(width))
+                    .saturating_sub(usize::from(height)),
             );
             self.scroll_to_bottom_on_redraw.set(false);
         }
@@ -364,7 +500,7 @@ impl DrawableComponent for DetailsComponent {
                     &self.key_config,
                 ),
                 Text::from(wrapped_lines),
-                &self.theme,
+                &self.theme.borrow(),
                 self.focused,
             ),
             chunks[1],
@@ -375,7 +511,7 @@ impl DrawableComponent for DetailsComponent {
                 f,
                 chunks[1],
                 &self.theme,
-                Self::get_number_of_lines(&self.data, width as usize),
+                self.number_of_lines(width as usize),
                 self.scroll_top.get(),
             )
         }
@@ -393,8 +529,7 @@ impl Component for DetailsComponent {
         // visibility_blocking(self)
 
         let width = self.current_size.get().0 as usize;
-        let number_of_lines =
-            Self::get_number_of_lines(&self.data, width);
+        let number_of_lines = self.number_of_lines(width);
 
         out.push(
             CommandInfo::new(
@@ -407,27 +542,59 @@ impl Component for DetailsComponent {
             .order(order::NAV),
         );
 
+        out.push(CommandInfo::new(
+            strings::commands::markdown_toggle_raw(
+                &self.key_config,
+                self.raw_message,
+            ),
+            self.has_body(),
+            self.focused || force_all,
+        ));
+
+        out.push(CommandInfo::new(
+            strings::commands::open_link(&self.key_config),
+            number_of_lines > 0,
+            self.focused || force_all,
+        ));
+
         CommandBlocking::PassingOn
     }
 
     fn event(&mut self, event: Event) -> Result<EventState> {
         if self.focused {
             if let Event::Key(e) = event {
-                return Ok(if e == self.key_config.move_up {
-                    self.move_scroll_top(ScrollType::Up).into()
-                } else if e == self.key_config.move_down {
-                    self.move_scroll_top(ScrollType::Down).into()
-                } else if e == self.key_config.home
-                    || e == self.key_config.shift_up
-                {
-                    self.move_scroll_top(ScrollType::Home).into()
-                } else if e == self.key_config.end
-                    || e == self.key_config.shift_down
-                {
-                    self.move_scroll_top(ScrollType::End).into()
-                } else {
-                    EventState::NotConsumed
-                });
+                return Ok(
+                    if e == self.key_config.borrow().move_up {
+                        self.move_scroll_top(ScrollType::Up).into()
+                    } else if e == self.key_config.borrow().move_down
+                    {
+                        self.move_scroll_top(ScrollType::Down).into()
+                    } else if e == self.key_config.borrow().home
+                        || e == self.key_config.borrow().shift_up
+                    {
+                        self.move_scroll_top(ScrollType::Home).into()
+                    } else if e == self.key_config.borrow().end
+                        || e == self.key_config.borrow().shift_down
+                    {
+                        self.move_scroll_top(ScrollType::End).into()
+                    } else if e
+                        == self
+                            .key_config
+                            .borrow()
+                            .markdown_toggle_raw
+                        && self.has_body()
+                    {
+                        self.raw_message = !self.raw_message;
+                        self.scroll_top.set(0);
+                        EventState::Consumed
+                    } else if e == self.key_config.borrow().open_link
+                    {
+                        self.open_links();
+                        EventState::Consumed
+                    } else {
+                        EventState::NotConsumed
+                    },
+                );
             }
         }
 
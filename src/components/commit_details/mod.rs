@@ -10,7 +10,7 @@ use crate::{
 };
 use anyhow::Result;
 use asyncgit::{
-    sync::{CommitId, CommitTags},
+    sync::{CommitId, CommitTags, RepoPath},
     AsyncCommitFiles, AsyncNotification,
 };
 use crossbeam_channel::Sender;
@@ -35,6 +35,7 @@ impl CommitDetailsComponent {
 
     ///
     pub fn new(
+        repo: &RepoPath,
         queue: &Queue,
         sender: &Sender<AsyncNotification>,
         theme: SharedTheme,
@@ -42,11 +43,16 @@ impl CommitDetailsComponent {
     ) -> Self {
         Self {
             details: DetailsComponent::new(
+                repo.clone(),
+                queue.clone(),
                 theme.clone(),
                 key_config.clone(),
                 false,
             ),
-            git_commit_files: AsyncCommitFiles::new(sender),
+            git_commit_files: AsyncCommitFiles::new(
+                repo.clone(),
+                sender,
+            ),
             file_tree: FileTreeComponent::new(
                 "",
                 false,
@@ -167,13 +173,13 @@ impl Component for CommitDetailsComponent {
 
         if self.focused() {
             if let Event::Key(e) = ev {
-                return if e == self.key_config.focus_below
+                return if e == self.key_config.borrow().focus_below
                     && self.details.focused()
                 {
                     self.details.focus(false);
                     self.file_tree.focus(true);
                     Ok(EventState::Consumed)
-                } else if e == self.key_config.focus_above
+                } else if e == self.key_config.borrow().focus_above
                     && self.file_tree.focused()
                 {
                     self.file_tree.focus(false);
@@ -10,9 +10,11 @@ use crate::{
     ui::style::{SharedTheme, Theme},
 };
 use anyhow::Result;
-use asyncgit::sync::Tags;
+use asyncgit::sync::{CommitId, Decoration, Decorations, Tags};
 use chrono::{DateTime, Local};
-use crossterm::event::Event;
+use crossterm::event::{
+    Event, MouseButton, MouseEvent, MouseEventKind,
+};
 use std::{
     borrow::Cow, cell::Cell, cmp, convert::TryFrom, time::Instant,
 };
@@ -30,12 +32,17 @@ const ELEMENTS_PER_LINE: usize = 10;
 pub struct CommitList {
     title: String,
     selection: usize,
+    marked: Vec<CommitId>,
     branch: Option<String>,
     count_total: usize,
     items: ItemBatch,
     scroll_state: (Instant, f32),
     tags: Option<Tags>,
+    decorations: Option<Decorations>,
+    show_decorations: bool,
     current_size: Cell<(u16, u16)>,
+    /// where the list was drawn last, to map mouse clicks
+    area: Cell<Rect>,
     scroll_top: Cell<usize>,
     theme: SharedTheme,
     key_config: SharedKeyConfig,
@@ -51,11 +58,15 @@ impl CommitList {
         Self {
             items: ItemBatch::default(),
             selection: 0,
+            marked: Vec::new(),
             branch: None,
             count_total: 0,
             scroll_state: (Instant::now(), 0_f32),
             tags: None,
+            decorations: None,
+            show_decorations: true,
             current_size: Cell::new((0, 0)),
+            area: Cell::new(Rect::default()),
             scroll_top: Cell::new(0),
             theme,
             key_config,
@@ -68,6 +79,11 @@ impl CommitList {
         &mut self.items
     }
 
+    ///
+    pub fn set_title(&mut self, title: String) {
+        self.title = title;
+    }
+
     ///
     pub fn set_branch(&mut self, name: Option<String>) {
         self.branch = name;
@@ -111,6 +127,21 @@ impl CommitList {
         self.tags = Some(tags);
     }
 
+    /// branches, remote branches and tags shown next to the commits
+    pub fn set_decorations(&mut self, decorations: Decorations) {
+        self.decorations = Some(decorations);
+    }
+
+    ///
+    pub const fn decorations_shown(&self) -> bool {
+        self.show_decorations
+    }
+
+    ///
+    pub fn toggle_decorations(&mut self) {
+        self.show_decorations = !self.show_decorations;
+    }
+
     ///
     pub fn selected_entry(&self) -> Option<&LogEntry> {
         self.items.iter().nth(
@@ -118,6 +149,34 @@ impl CommitList {
         )
     }
 
+    /// selects the commit at `position` of the log
+    pub fn select_entry(&mut self, position: usize) {
+        self.selection = cmp::min(position, self.selection_max());
+    }
+
+    /// commits marked by the user
+    pub fn marked(&self) -> &[CommitId] {
+        &self.marked
+    }
+
+    /// marks the selected commit or unmarks it if it was marked
+    pub fn toggle_mark_selected(&mut self) {
+        if let Some(id) = self.selected_entry().map(|e| e.id) {
+            if let Some(idx) =
+                self.marked.iter().position(|marked| *marked == id)
+            {
+                self.marked.remove(idx);
+            } else {
+                self.marked.push(id);
+            }
+        }
+    }
+
+    ///
+    pub fn clear_marked(&mut self) {
+        self.marked.clear();
+    }
+
     pub fn copy_entry_hash(&self) -> Result<()> {
         if let Some(e) = self.items.iter().nth(
             self.selection.saturating_sub(self.items.index_offset()),
@@ -164,6 +223,27 @@ impl CommitList {
         Ok(needs_update)
     }
 
+    /// selects the commit drawn in the terminal `row` (mouse click)
+    fn select_at_row(&mut self, column: u16, row: u16) -> bool {
+        let area = self.area.get();
+        if !area.intersects(Rect::new(column, row, 1, 1))
+            || row <= area.y
+            || row + 1 >= area.y + area.height
+        {
+            return false;
+        }
+
+        let new_selection = cmp::min(
+            self.scroll_top.get() + usize::from(row - area.y - 1),
+            self.selection_max(),
+        );
+        let needs_update = new_selection != self.selection;
+
+        self.selection = new_selection;
+
+        needs_update
+    }
+
     fn update_scroll_speed(&mut self) {
         const REPEATED_SCROLL_THRESHOLD_MILLIS: u128 = 300;
         const SCROLL_SPEED_START: f32 = 0.1_f32;
@@ -191,7 +271,8 @@ impl CommitList {
     fn get_entry_to_add<'a>(
         e: &'a LogEntry,
         selected: bool,
-        tags: Option<String>,
+        marked: Option<bool>,
+        decorations: &'a [Decoration],
         theme: &Theme,
         width: usize,
         now: DateTime<Local>,
@@ -203,6 +284,14 @@ impl CommitList {
         let splitter =
             Span::styled(splitter_txt, theme.text(true, selected));
 
+        // marker column, only while anything is marked
+        if let Some(marked) = marked {
+            txt.push(Span::styled(
+                Cow::from(if marked { "\u{221a} " } else { "  " }), //√
+                theme.commit_hash(selected),
+            ));
+        }
+
         // commit hash
         txt.push(Span::styled(
             Cow::from(e.hash_short.as_str()),
@@ -231,17 +320,23 @@ impl CommitList {
 
         txt.push(splitter.clone());
 
-        // commit tags
-        txt.push(Span::styled(
-            Cow::from(if let Some(tags) = tags {
-                format!(" {}", tags)
-            } else {
-                String::from("")
-            }),
-            theme.tags(selected),
-        ));
+        // branches and tags
+        for decoration in decorations {
+            txt.push(Span::styled(
+                Cow::from(format!(" {}", decoration.name)),
+                theme.decoration(decoration.kind, selected),
+            ));
+        }
 
-        txt.push(splitter);
+        txt.push(splitter.clone());
+
+        if e.replaced {
+            txt.push(Span::styled(
+                Cow::from(strings::REPLACED_COMMIT_MARKER),
+                theme.tags(selected),
+            ));
+            txt.push(splitter);
+        }
 
         // commit msg
         txt.push(Span::styled(
@@ -265,16 +360,23 @@ impl CommitList {
             .take(height)
             .enumerate()
         {
-            let tags = self
-                .tags
+            let decorations = self
+                .decorations
                 .as_ref()
-                .and_then(|t| t.get(&e.id))
-                .map(|tags| tags.join(" "));
+                .filter(|_| self.show_decorations)
+                .and_then(|d| d.get(&e.id))
+                .map_or(&[][..], Vec::as_slice);
+            let marked = if self.marked.is_empty() {
+                None
+            } else {
+                Some(self.marked.contains(&e.id))
+            };
             txt.push(Self::get_entry_to_add(
                 e,
                 idx + self.scroll_top.get() == selection,
-                tags,
-                &self.theme,
+                marked,
+                decorations,
+                &self.theme.borrow(),
                 width,
                 now,
             ));
@@ -300,6 +402,7 @@ impl DrawableComponent for CommitList {
             area.height.saturating_sub(2),
         );
         self.current_size.set(current_size);
+        self.area.set(area);
 
         let height_in_lines = self.current_size.get().1 as usize;
         let selection = self.relative_selection();
@@ -333,9 +436,9 @@ impl DrawableComponent for CommitList {
                     .borders(Borders::ALL)
                     .title(Span::styled(
                         title.as_str(),
-                        self.theme.title(true),
+                        self.theme.borrow().title(true),
                     ))
-                    .border_style(self.theme.block(true)),
+                    .border_style(self.theme.borrow().block(true)),
             )
             .alignment(Alignment::Left),
             area,
@@ -348,28 +451,39 @@ impl DrawableComponent for CommitList {
 impl Component for CommitList {
     fn event(&mut self, ev: Event) -> Result<EventState> {
         if let Event::Key(k) = ev {
-            let selection_changed = if k == self.key_config.move_up {
-                self.move_selection(ScrollType::Up)?
-            } else if k == self.key_config.move_down {
-                self.move_selection(ScrollType::Down)?
-            } else if k == self.key_config.shift_up
-                || k == self.key_config.home
-            {
-                self.move_selection(ScrollType::Home)?
-            } else if k == self.key_config.shift_down
-                || k == self.key_config.end
-            {
-                self.move_selection(ScrollType::End)?
-            } else if k == self.key_config.page_up {
-                self.move_selection(ScrollType::PageUp)?
-            } else if k == self.key_config.page_down {
-                self.move_selection(ScrollType::PageDown)?
-            } else {
-                false
-            };
+            let selection_changed =
+                if k == self.key_config.borrow().move_up {
+                    self.move_selection(ScrollType::Up)?
+                } else if k == self.key_config.borrow().move_down {
+                    self.move_selection(ScrollType::Down)?
+                } else if k == self.key_config.borrow().shift_up
+                    || k == self.key_config.borrow().home
+                {
+                    self.move_selection(ScrollType::Home)?
+                } else if k == self.key_config.borrow().shift_down
+                    || k == self.key_config.borrow().end
+                {
+                    self.move_selection(ScrollType::End)?
+                } else if k == self.key_config.borrow().page_up {
+                    self.move_selection(ScrollType::PageUp)?
+                } else if k == self.key_config.borrow().page_down {
+                    self.move_selection(ScrollType::PageDown)?
+                } else {
+                    false
+                };
             return Ok(selection_changed.into());
         }
 
+        if let Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column,
+            row,
+            ..
+        }) = ev
+        {
+            return Ok(self.select_at_row(column, row).into());
+        }
+
         Ok(EventState::NotConsumed)
     }
 
@@ -0,0 +1,209 @@
+use super::{
+    visibility_blocking, CommandBlocking, CommandInfo, CommandText,
+    Component, DrawableComponent, EventState,
+};
+use crate::{
+    keys::SharedKeyConfig,
+    queue::{InternalEvent, Queue},
+    strings,
+    ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use crossterm::event::{Event, KeyEvent};
+use std::convert::TryFrom;
+use tui::{
+    backend::Backend, layout::Rect, text::Span, widgets::Clear, Frame,
+};
+
+/// opens the context menu listing all commands of `component` that
+/// have a key and are applicable right now
+pub fn open_context_menu<C: Component>(component: &C, queue: &Queue) {
+    let mut commands = Vec::new();
+    component.commands(&mut commands, false);
+
+    let items: Vec<(CommandText, KeyEvent)> = commands
+        .into_iter()
+        .filter(|cmd| cmd.enabled && cmd.available)
+        .filter_map(|cmd| cmd.key.map(|key| (cmd.text, key)))
+        .collect();
+
+    if !items.is_empty() {
+        queue
+            .borrow_mut()
+            .push_back(InternalEvent::OpenContextMenu(items));
+    }
+}
+
+/// popup listing the actions of the selected item, choosing one
+/// presses its key
+pub struct ContextMenuComponent {
+    items: Vec<(CommandText, KeyEvent)>,
+    selection: usize,
+    visible: bool,
+    queue: Queue,
+    theme: SharedTheme,
+    key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for ContextMenuComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        if self.visible {
+            const WIDTH: u16 = 70;
+
+            let height = u16::try_from(self.items.len())
+                .unwrap_or(u16::MAX)
+                .saturating_add(2);
+
+            let area = ui::centered_rect_absolute(
+                WIDTH.min(rect.width),
+                height.min(rect.height),
+                rect,
+            );
+
+            let name_width = self
+                .items
+                .iter()
+                .map(|(text, _)| text.name.chars().count())
+                .max()
+                .unwrap_or_default();
+
+            let items = self.items.iter().enumerate().map(
+                |(idx, (text, _))| {
+                    Span::styled(
+                        format!(
+                            "{:width$} {}",
+                            text.name,
+                            text.desc,
+                            width = name_width
+                        ),
+                        self.theme
+                            .borrow()
+                            .text(true, idx == self.selection),
+                    )
+                },
+            );
+
+            f.render_widget(Clear, area);
+            ui::draw_list(
+                f,
+                area,
+                &strings::title_context_menu(),
+                items,
+                true,
+                &self.theme,
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl Component for ContextMenuComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.visible || force_all {
+            out.clear();
+
+            out.push(CommandInfo::new(
+                strings::commands::scroll(&self.key_config),
+                true,
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::close_popup(&self.key_config),
+                true,
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::context_menu_confirm(
+                    &self.key_config,
+                ),
+                true,
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<EventState> {
+        if self.visible {
+            if let Event::Key(e) = ev {
+                if e == self.key_config.borrow().exit_popup {
+                    self.hide();
+                } else if e == self.key_config.borrow().move_down {
+                    self.selection = self
+                        .selection
+                        .saturating_add(1)
+                        .min(self.items.len().saturating_sub(1));
+                } else if e == self.key_config.borrow().move_up {
+                    self.selection = self.selection.saturating_sub(1);
+                } else if e == self.key_config.borrow().enter {
+                    self.hide();
+                    if let Some((_, key)) =
+                        self.items.get(self.selection)
+                    {
+                        self.queue.borrow_mut().push_back(
+                            InternalEvent::SimulateKey(*key),
+                        );
+                    }
+                }
+            }
+
+            return Ok(EventState::Consumed);
+        }
+
+        Ok(EventState::NotConsumed)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+
+        Ok(())
+    }
+}
+
+impl ContextMenuComponent {
+    ///
+    pub fn new(
+        queue: Queue,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+    ) -> Self {
+        Self {
+            items: Vec::new(),
+            selection: 0,
+            visible: false,
+            queue,
+            theme,
+            key_config,
+        }
+    }
+
+    ///
+    pub fn open(
+        &mut self,
+        items: Vec<(CommandText, KeyEvent)>,
+    ) -> Result<()> {
+        self.items = items;
+        self.selection = 0;
+        self.show()
+    }
+}
@@ -5,18 +5,34 @@ use super::{
 };
 use crate::{
     keys::SharedKeyConfig,
+    options::SharedOptions,
     queue::{InternalEvent, NeedsUpdate, Queue},
     strings,
     ui::style::SharedTheme,
 };
 use anyhow::Result;
-use asyncgit::{sync, CWD};
+use asyncgit::sync::{self, CommitId, FlowBranch, RepoPath};
 use crossterm::event::Event;
 use tui::{backend::Backend, layout::Rect, Frame};
 
+/// where the new branch starts
+#[derive(Copy, Clone)]
+enum Start {
+    Head,
+    /// the new branch is only checked out if this is `HEAD` already
+    Commit(CommitId),
+    /// a branch of the configured workflow
+    Flow(FlowBranch),
+    /// `git stash branch`
+    Stash(CommitId),
+}
+
 pub struct CreateBranchComponent {
+    repo: RepoPath,
+    start: Start,
     input: TextInputComponent,
     queue: Queue,
+    options: SharedOptions,
     key_config: SharedKeyConfig,
 }
 
@@ -48,6 +64,14 @@ impl Component for CreateBranchComponent {
                 true,
                 true,
             ));
+
+            out.push(CommandInfo::new(
+                strings::commands::flow_branch_kind(&self.key_config),
+                true,
+                matches!(self.start, Start::Flow(_))
+                    && self.options.borrow().workflow().kinds().len()
+                        > 1,
+            ));
         }
 
         visibility_blocking(self)
@@ -60,8 +84,10 @@ impl Component for CreateBranchComponent {
             }
 
             if let Event::Key(e) = ev {
-                if e == self.key_config.enter {
+                if e == self.key_config.borrow().enter {
                     self.create_branch();
+                } else if e == self.key_config.borrow().tab_toggle {
+                    self.cycle_flow_kind();
                 }
 
                 return Ok(EventState::Consumed);
@@ -88,12 +114,15 @@ impl Component for CreateBranchComponent {
 impl CreateBranchComponent {
     ///
     pub fn new(
+        repo: RepoPath,
         queue: Queue,
         theme: SharedTheme,
         key_config: SharedKeyConfig,
+        options: SharedOptions,
     ) -> Self {
         Self {
             queue,
+            options,
             input: TextInputComponent::new(
                 theme,
                 key_config.clone(),
@@ -102,20 +131,103 @@ impl CreateBranchComponent {
                 true,
             ),
             key_config,
+            start: Start::Head,
+            repo,
         }
     }
 
     ///
     pub fn open(&mut self) -> Result<()> {
+        self.start = Start::Head;
+        self.input.set_title(strings::create_branch_popup_title(
+            &self.key_config,
+        ));
+        self.show()?;
+
+        Ok(())
+    }
+
+    /// branch from `id`, the new branch is only checked out if `id`
+    /// is `HEAD` already
+    pub fn open_at(&mut self, id: CommitId) -> Result<()> {
+        self.start = Start::Commit(id);
+        self.input.set_title(strings::create_branch_at_popup_title(
+            &id.get_short_string(),
+        ));
+        self.show()?;
+
+        Ok(())
+    }
+
+    /// branch from the commit the stash `id` was made on and pop it
+    /// there
+    pub fn open_from_stash(&mut self, id: CommitId) -> Result<()> {
+        self.start = Start::Stash(id);
+        self.input.set_title(strings::stash_branch_popup_title());
         self.show()?;
 
         Ok(())
     }
 
+    /// start a branch of the configured workflow, its kind is cycled
+    /// with `tab_toggle`
+    pub fn open_flow(&mut self) -> Result<()> {
+        let kinds = self.options.borrow().workflow().kinds();
+        if let Some(kind) = kinds.first() {
+            self.set_flow(*kind);
+            self.show()?;
+        }
+
+        Ok(())
+    }
+
+    fn set_flow(&mut self, kind: FlowBranch) {
+        let naming = self.options.borrow().flow_naming();
+        self.start = Start::Flow(kind);
+        self.input.set_title(strings::flow_start_popup_title(
+            kind,
+            naming.prefix(kind),
+        ));
+    }
+
+    fn cycle_flow_kind(&mut self) {
+        if let Start::Flow(kind) = self.start {
+            let kinds = self.options.borrow().workflow().kinds();
+            let next = kinds
+                .iter()
+                .position(|k| *k == kind)
+                .and_then(|idx| kinds.get(idx + 1))
+                .or_else(|| kinds.first());
+
+            if let Some(next) = next {
+                self.set_flow(*next);
+            }
+        }
+    }
+
     ///
     pub fn create_branch(&mut self) {
-        let res =
-            sync::create_branch(CWD, self.input.get_text().as_str());
+        let name = self.input.get_text().clone();
+        let res = match self.start {
+            Start::Flow(kind) => sync::flow_start(
+                &self.repo,
+                &self.options.borrow().flow_naming(),
+                kind,
+                &name,
+            )
+            .map(|_| ()),
+            Start::Stash(id) => {
+                sync::stash_branch(&self.repo, id, &name)
+            }
+            Start::Commit(id)
+                if sync::get_head(&self.repo).ok() != Some(id) =>
+            {
+                sync::create_branch_at(&self.repo, &name, id)
+            }
+            Start::Commit(_) | Start::Head => {
+                sync::create_branch(&self.repo, &name)
+            }
+        };
 
         self.input.clear();
         self.hide();
@@ -123,7 +235,9 @@ impl CreateBranchComponent {
         match res {
             Ok(_) => {
                 self.queue.borrow_mut().push_back(
-                    InternalEvent::Update(NeedsUpdate::BRANCHES),
+                    InternalEvent::Update(
+                        NeedsUpdate::ALL | NeedsUpdate::BRANCHES,
+                    ),
                 );
             }
             Err(e) => {
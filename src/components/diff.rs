@@ -4,22 +4,37 @@ use super::{
 use crate::{
     components::{CommandInfo, Component, EventState},
     keys::SharedKeyConfig,
+    options::SharedOptions,
+    path_patterns::PathPatterns,
     queue::{Action, InternalEvent, NeedsUpdate, Queue, ResetItem},
     strings, try_or_popup,
-    ui::{self, calc_scroll_top, style::SharedTheme},
+    ui::{
+        self, calc_scroll_top,
+        style::SharedTheme,
+        syntax_text::{
+            AsyncSyntaxHighlighting, HighlightedLine,
+            SyntaxHighlighter, SyntaxStream,
+        },
+        ImagePreview,
+    },
 };
 use anyhow::Result;
 use asyncgit::{
     hash,
-    sync::{self, diff::DiffLinePosition},
-    DiffLine, DiffLineType, FileDiff, CWD,
+    sync::{
+        self, diff::DiffLinePosition, ContentType, RepoPath,
+        SubmoduleLog,
+    },
+    AsyncNotification, DiffLine, DiffLineType, FileDiff,
 };
 use bytesize::ByteSize;
-use crossterm::event::Event;
-use std::{borrow::Cow, cell::Cell, cmp, path::Path};
+use crossbeam_channel::Sender;
+use crossterm::event::{Event, KeyEvent};
+use std::{borrow::Cow, cell::Cell, cmp, ops::Range, path::Path};
 use tui::{
     backend::Backend,
-    layout::Rect,
+    layout::{self, Constraint, Layout, Rect},
+    style::{Modifier, Style},
     symbols,
     text::{Span, Spans},
     widgets::{Block, Borders, Paragraph},
@@ -96,8 +111,17 @@ impl Selection {
     }
 }
 
+/// one row of the split view, referencing lines of the flattened
+/// diff shown on the old (left) and new (right) side
+#[derive(Clone, Copy)]
+struct SplitRow {
+    left: Option<usize>,
+    right: Option<usize>,
+}
+
 ///
 pub struct DiffComponent {
+    repo: RepoPath,
     diff: Option<FileDiff>,
     pending: bool,
     selection: Selection,
@@ -109,15 +133,23 @@ pub struct DiffComponent {
     queue: Queue,
     theme: SharedTheme,
     key_config: SharedKeyConfig,
+    options: SharedOptions,
     is_immutable: bool,
+    highlighter: Option<SyntaxHighlighter>,
+    syntax: AsyncSyntaxHighlighting,
+    image_preview: Option<ImagePreview>,
+    folded: bool,
 }
 
 impl DiffComponent {
     ///
     pub fn new(
+        repo: RepoPath,
         queue: Queue,
+        sender: &Sender<AsyncNotification>,
         theme: SharedTheme,
         key_config: SharedKeyConfig,
+        options: SharedOptions,
         is_immutable: bool,
     ) -> Self {
         Self {
@@ -132,16 +164,30 @@ impl DiffComponent {
             scroll_top: Cell::new(0),
             theme,
             key_config,
+            options,
             is_immutable,
+            highlighter: None,
+            syntax: AsyncSyntaxHighlighting::new(sender),
+            image_preview: None,
+            folded: false,
+            repo,
         }
     }
     ///
+    pub fn any_work_pending(&self) -> bool {
+        self.syntax.is_pending()
+    }
+    ///
     fn can_scroll(&self) -> bool {
         self.diff
             .as_ref()
             .map(|diff| diff.lines > 1)
             .unwrap_or_default()
     }
+    /// disables the hunk and line actions (stage, reset)
+    pub fn set_immutable(&mut self, immutable: bool) {
+        self.is_immutable = immutable;
+    }
     ///
     pub fn current(&self) -> (String, bool) {
         (self.current.path.clone(), self.current.is_stage)
@@ -153,6 +199,10 @@ impl DiffComponent {
         self.scroll_top.set(0);
         self.selection = Selection::Single(0);
         self.selected_hunk = None;
+        self.highlighter = None;
+        self.syntax.clear();
+        self.image_preview = None;
+        self.folded = false;
         self.pending = pending;
 
         Ok(())
@@ -171,13 +221,32 @@ impl DiffComponent {
         if self.current.hash != hash {
             let reset_selection = self.current.path != path;
 
+            if reset_selection {
+                self.highlighter =
+                    self.theme.borrow().syntax_theme().and_then(
+                        |theme| {
+                            SyntaxHighlighter::new(
+                                Path::new(&path),
+                                theme,
+                            )
+                        },
+                    );
+                self.folded = PathPatterns::new(
+                    self.options.borrow().diff_fold_patterns(),
+                )
+                .matches(&path);
+            }
+
             self.current = Current {
                 path,
                 is_stage,
                 hash,
             };
 
+            self.image_preview =
+                diff.preview.as_deref().and_then(ImagePreview::new);
             self.diff = Some(diff);
+            self.request_syntax();
 
             if reset_selection {
                 self.scroll_top.set(0);
@@ -236,6 +305,28 @@ impl DiffComponent {
         }
     }
 
+    /// moves the selection to the header of the selected hunk, if
+    /// already there to the header of the hunk before
+    fn jump_to_symbol(&mut self) {
+        if let Some(diff) = &self.diff {
+            let start = self.selection.get_start();
+
+            let target = diff
+                .hunks
+                .iter()
+                .scan(0, |offset, hunk| {
+                    let header = *offset;
+                    *offset += hunk.lines.len();
+                    Some(header)
+                })
+                .take_while(|header| *header < start)
+                .last()
+                .unwrap_or_default();
+
+            self.update_selection(target);
+        }
+    }
+
     fn lines_count(&self) -> usize {
         self.diff.as_ref().map_or(0, |diff| diff.lines)
     }
@@ -274,6 +365,25 @@ impl DiffComponent {
         }
     }
 
+    /// lets the user pick one of the links in the whole diff
+    fn open_links(&self) {
+        if let Some(diff) = &self.diff {
+            let lines: Vec<&str> = diff
+                .hunks
+                .iter()
+                .flat_map(|hunk| hunk.lines.iter())
+                .map(|line| {
+                    line.content
+                        .trim_matches(|c| c == '\n' || c == '\r')
+                })
+                .collect();
+
+            self.queue.borrow_mut().push_back(
+                InternalEvent::OpenLinks(lines.join("\n")),
+            );
+        }
+    }
+
     fn find_selected_hunk(
         diff: &FileDiff,
         line_selected: usize,
@@ -297,52 +407,62 @@ impl DiffComponent {
         None
     }
 
+    /// content info and size change of a diff without hunks
+    fn get_size_text(&self, diff: &FileDiff) -> Vec<Spans> {
+        let mut res = Vec::new();
+        if let Some(info) = self.content_info(diff) {
+            res.push(Spans::from(Span::styled(
+                Cow::from(info),
+                self.theme.borrow().text(true, false),
+            )));
+        }
+
+        let is_positive = diff.size_delta >= 0;
+        let delta_byte_size =
+            ByteSize::b(diff.size_delta.abs() as u64);
+        let sign = if is_positive { "+" } else { "-" };
+        res.push(Spans::from(vec![
+            Span::raw(Cow::from("size: ")),
+            Span::styled(
+                Cow::from(format!("{}", ByteSize::b(diff.sizes.0))),
+                self.theme.borrow().text(false, false),
+            ),
+            Span::raw(Cow::from(" -> ")),
+            Span::styled(
+                Cow::from(format!("{}", ByteSize::b(diff.sizes.1))),
+                self.theme.borrow().text(false, false),
+            ),
+            Span::raw(Cow::from(" (")),
+            Span::styled(
+                Cow::from(format!("{}{:}", sign, delta_byte_size)),
+                self.theme.borrow().diff_line(
+                    if is_positive {
+                        DiffLineType::Add
+                    } else {
+                        DiffLineType::Delete
+                    },
+                    false,
+                ),
+            ),
+            Span::raw(Cow::from(")")),
+        ]));
+
+        res
+    }
+
     fn get_text(&self, width: u16, height: u16) -> Vec<Spans> {
         let mut res: Vec<Spans> = Vec::new();
         if let Some(diff) = &self.diff {
-            if diff.hunks.is_empty() {
-                let is_positive = diff.size_delta >= 0;
-                let delta_byte_size =
-                    ByteSize::b(diff.size_delta.abs() as u64);
-                let sign = if is_positive { "+" } else { "-" };
-                res.extend(vec![Spans::from(vec![
-                    Span::raw(Cow::from("size: ")),
-                    Span::styled(
-                        Cow::from(format!(
-                            "{}",
-                            ByteSize::b(diff.sizes.0)
-                        )),
-                        self.theme.text(false, false),
-                    ),
-                    Span::raw(Cow::from(" -> ")),
-                    Span::styled(
-                        Cow::from(format!(
-                            "{}",
-                            ByteSize::b(diff.sizes.1)
-                        )),
-                        self.theme.text(false, false),
-                    ),
-                    Span::raw(Cow::from(" (")),
-                    Span::styled(
-                        Cow::from(format!(
-                            "{}{:}",
-                            sign, delta_byte_size
-                        )),
-                        self.theme.diff_line(
-                            if is_positive {
-                                DiffLineType::Add
-                            } else {
-                                DiffLineType::Delete
-                            },
-                            false,
-                        ),
-                    ),
-                    Span::raw(Cow::from(")")),
-                ])]);
+            if self.folded && !diff.hunks.is_empty() {
+                res.push(self.get_folded_summary(diff));
+            } else if diff.hunks.is_empty() {
+                res = self.get_size_text(diff);
             } else {
                 let min = self.scroll_top.get();
                 let max = min + height as usize;
 
+                let highlighted = self.syntax.get();
+
                 let mut line_cursor = 0_usize;
                 let mut lines_added = 0_usize;
 
@@ -378,6 +498,11 @@ impl DiffComponent {
                                     hunk_selected,
                                     i == hunk_len as usize - 1,
                                     &self.theme,
+                                    highlighted.as_ref().and_then(
+                                        |lines| {
+                                            lines.get(line_cursor)
+                                        },
+                                    ),
                                 ));
                                 lines_added += 1;
                             }
@@ -388,11 +513,291 @@ impl DiffComponent {
                         line_cursor += hunk_len;
                     }
                 }
+
+                if let Some(log) = &diff.submodule {
+                    res.extend(
+                        self.get_submodule_log(log).into_iter().take(
+                            (height as usize)
+                                .saturating_sub(lines_added),
+                        ),
+                    );
+                }
             }
         }
         res
     }
 
+    fn get_submodule_log<'a>(
+        &self,
+        log: &'a SubmoduleLog,
+    ) -> Vec<Spans<'a>> {
+        let mut res = vec![
+            Spans::default(),
+            Spans::from(Span::styled(
+                Cow::from(strings::submodule_log_info(log)),
+                self.theme.borrow().text(true, false),
+            )),
+        ];
+
+        if let Some(commits) = &log.commits {
+            res.extend(commits.iter().map(|commit| {
+                Spans::from(vec![
+                    Span::styled(
+                        Cow::from(commit.id.get_short_string()),
+                        self.theme.borrow().commit_hash(false),
+                    ),
+                    Span::raw(Cow::from(" ")),
+                    Span::styled(
+                        Cow::from(commit.summary.as_str()),
+                        self.theme.borrow().text(true, false),
+                    ),
+                    Span::raw(Cow::from(" ")),
+                    Span::styled(
+                        Cow::from(commit.author.as_str()),
+                        self.theme.borrow().commit_author(false),
+                    ),
+                ])
+            }));
+        }
+
+        res
+    }
+
+    fn is_split(&self) -> bool {
+        self.options.borrow().diff_split()
+    }
+
+    fn queue_diff_update(&self) {
+        self.queue
+            .as_ref()
+            .borrow_mut()
+            .push_back(InternalEvent::Update(NeedsUpdate::DIFF));
+    }
+
+    fn toggle_split(&mut self) {
+        self.options.borrow_mut().toggle_diff_split();
+        // scroll position is measured in rows in split mode
+        self.scroll_top.set(0);
+    }
+
+    /// pairs deleted lines with the added lines directly following
+    /// them, everything else is shown on both sides
+    fn split_rows(diff: &FileDiff) -> Vec<SplitRow> {
+        let types: Vec<DiffLineType> = diff
+            .hunks
+            .iter()
+            .flat_map(|hunk| hunk.lines.iter())
+            .map(|line| line.line_type)
+            .collect();
+
+        let mut rows = Vec::with_capacity(types.len());
+        let mut idx = 0;
+
+        while idx < types.len() {
+            match types[idx] {
+                DiffLineType::Delete | DiffLineType::Add => {
+                    let del_start = idx;
+                    while idx < types.len()
+                        && types[idx] == DiffLineType::Delete
+                    {
+                        idx += 1;
+                    }
+                    let add_start = idx;
+                    while idx < types.len()
+                        && types[idx] == DiffLineType::Add
+                    {
+                        idx += 1;
+                    }
+
+                    let deleted = add_start - del_start;
+                    let added = idx - add_start;
+
+                    for offset in 0..cmp::max(deleted, added) {
+                        rows.push(SplitRow {
+                            left: (offset < deleted)
+                                .then(|| del_start + offset),
+                            right: (offset < added)
+                                .then(|| add_start + offset),
+                        });
+                    }
+                }
+                _ => {
+                    rows.push(SplitRow {
+                        left: Some(idx),
+                        right: Some(idx),
+                    });
+                    idx += 1;
+                }
+            }
+        }
+
+        rows
+    }
+
+    fn split_row_of(rows: &[SplitRow], line: usize) -> usize {
+        rows.iter()
+            .position(|row| {
+                row.left == Some(line) || row.right == Some(line)
+            })
+            .unwrap_or_default()
+    }
+
+    /// highlights the hunks of the diff in the background, once per
+    /// diff
+    fn request_syntax(&mut self) {
+        match (self.highlighter, &self.diff) {
+            (Some(highlighter), Some(diff)) => self.syntax.request(
+                self.current.hash,
+                highlighter,
+                || Self::syntax_streams(diff),
+                diff.hunks.iter().map(|hunk| hunk.lines.len()).sum(),
+            ),
+            _ => self.syntax.clear(),
+        }
+    }
+
+    /// an old and a new stream per hunk: deleted lines are parsed on
+    /// the old side, added lines on the new one and context lines on
+    /// both, shown with the state of the new side
+    fn syntax_streams(diff: &FileDiff) -> Vec<SyntaxStream> {
+        let mut streams = Vec::with_capacity(diff.hunks.len() * 2);
+        let mut idx = 0;
+
+        for hunk in &diff.hunks {
+            let mut old = SyntaxStream::new();
+            let mut new = SyntaxStream::new();
+
+            for line in &hunk.lines {
+                let content = line
+                    .content
+                    .trim_end_matches(|c| c == '\n' || c == '\r')
+                    .to_string();
+
+                match line.line_type {
+                    DiffLineType::Delete => {
+                        old.push((Some(idx), content));
+                    }
+                    DiffLineType::Add => {
+                        new.push((Some(idx), content))
+                    }
+                    DiffLineType::None => {
+                        old.push((None, content.clone()));
+                        new.push((Some(idx), content));
+                    }
+                    DiffLineType::Header => (),
+                }
+
+                idx += 1;
+            }
+
+            streams.push(old);
+            streams.push(new);
+        }
+
+        streams
+    }
+
+    fn get_split_text(
+        &self,
+        width: u16,
+        height: u16,
+        rows: &[SplitRow],
+    ) -> (Vec<Spans>, Vec<Spans>) {
+        let mut left = Vec::new();
+        let mut right = Vec::new();
+
+        if let Some(diff) = &self.diff {
+            let lines: Vec<(usize, bool, &DiffLine)> = diff
+                .hunks
+                .iter()
+                .enumerate()
+                .flat_map(|(hunk_idx, hunk)| {
+                    let hunk_len = hunk.lines.len();
+                    hunk.lines.iter().enumerate().map(
+                        move |(i, line)| {
+                            (hunk_idx, i + 1 == hunk_len, line)
+                        },
+                    )
+                })
+                .collect();
+
+            let highlighted = self.syntax.get();
+
+            let side = |idx: Option<usize>| {
+                idx.and_then(|idx| {
+                    lines.get(idx).map(
+                        |(hunk_idx, end_of_hunk, line)| {
+                            Self::get_line_to_add(
+                                width,
+                                line,
+                                self.focused()
+                                    && self.selection.contains(idx),
+                                self.focused()
+                                    && self.selected_hunk
+                                        == Some(*hunk_idx),
+                                *end_of_hunk,
+                                &self.theme,
+                                highlighted
+                                    .as_ref()
+                                    .and_then(|lines| lines.get(idx)),
+                            )
+                        },
+                    )
+                })
+                .unwrap_or_default()
+            };
+
+            for row in rows
+                .iter()
+                .skip(self.scroll_top.get())
+                .take(height as usize)
+            {
+                left.push(side(row.left));
+                right.push(side(row.right));
+            }
+        }
+
+        (left, right)
+    }
+
+    fn get_folded_summary(&self, diff: &FileDiff) -> Spans {
+        let count = |line_type: DiffLineType| {
+            diff.hunks
+                .iter()
+                .flat_map(|hunk| hunk.lines.iter())
+                .filter(|line| line.line_type == line_type)
+                .count()
+        };
+
+        Spans::from(Span::styled(
+            Cow::from(strings::diff_folded_info(
+                &self.key_config,
+                diff.hunks.len(),
+                count(DiffLineType::Add),
+                count(DiffLineType::Delete),
+            )),
+            self.theme.borrow().text(false, false),
+        ))
+    }
+
+    fn toggle_fold(&mut self) {
+        self.folded = !self.folded;
+        self.scroll_top.set(0);
+    }
+
+    fn content_info(&self, diff: &FileDiff) -> Option<String> {
+        match diff.content_type {
+            ContentType::Text => None,
+            ContentType::Binary => Some(strings::binary_info()),
+            ContentType::Image(image) => Some(strings::image_info(
+                image,
+                self.image_preview
+                    .as_ref()
+                    .map(ImagePreview::dimensions),
+            )),
+        }
+    }
+
     fn get_line_to_add<'a>(
         width: u16,
         line: &'a DiffLine,
@@ -400,8 +805,9 @@ impl DiffComponent {
         selected_hunk: bool,
         end_of_hunk: bool,
         theme: &SharedTheme,
+        highlighted: Option<&HighlightedLine>,
     ) -> Spans<'a> {
-        let style = theme.diff_hunk_marker(selected_hunk);
+        let style = theme.borrow().diff_hunk_marker(selected_hunk);
 
         let left_side_of_line = if end_of_hunk {
             Span::styled(Cow::from(symbols::line::BOTTOM_LEFT), style)
@@ -419,7 +825,7 @@ impl DiffComponent {
         };
 
         let trimmed =
-            line.content.trim_matches(|c| c == '\n' || c == '\r');
+            line.content.trim_end_matches(|c| c == '\n' || c == '\r');
 
         let filled = if selected {
             // selected line
@@ -428,16 +834,126 @@ impl DiffComponent {
             // weird eof missing eol line
             format!("{}\n", trimmed)
         };
-        //TODO: allow customize tabsize
-        let content = Cow::from(filled.replace("\t", "  "));
 
-        Spans::from(vec![
-            left_side_of_line,
-            Span::styled(
-                content,
-                theme.diff_line(line.line_type, selected),
-            ),
-        ])
+        let line_style =
+            theme.borrow().diff_line(line.line_type, selected);
+
+        let mut spans = vec![left_side_of_line];
+        let mut cursor = 0;
+
+        // highlight the enclosing symbol following `@@ .. @@`
+        let symbol_start = (line.line_type == DiffLineType::Header)
+            .then(|| {
+                trimmed
+                    .strip_prefix("@@")
+                    .and_then(|rest| rest.find("@@"))
+                    .map(|idx| idx + 4)
+                    .filter(|idx| *idx < trimmed.len())
+            })
+            .flatten();
+
+        if let Some(symbol_start) = symbol_start {
+            spans.push(Span::styled(
+                Self::tabs_to_spaces(&filled[..symbol_start]),
+                line_style,
+            ));
+            spans.push(Span::styled(
+                Self::tabs_to_spaces(
+                    &filled[symbol_start..trimmed.len()],
+                ),
+                line_style.add_modifier(Modifier::BOLD),
+            ));
+            cursor = trimmed.len();
+        } else {
+            // syntax styles if the line has them, the whole line
+            // otherwise
+            let base = highlighted
+                .filter(|ranges| {
+                    ranges.last().map(|(range, _)| range.end)
+                        == Some(trimmed.len())
+                })
+                .and_then(|ranges| {
+                    ranges
+                        .iter()
+                        .map(|(range, style)| {
+                            theme
+                                .borrow()
+                                .diff_syntax(
+                                    line.line_type,
+                                    *style,
+                                    selected,
+                                )
+                                .map(|style| (range.clone(), style))
+                        })
+                        .collect::<Option<Vec<_>>>()
+                })
+                .unwrap_or_else(|| {
+                    vec![(0..trimmed.len(), line_style)]
+                });
+
+            for (range, style) in Self::split_by_changes(
+                base,
+                line,
+                theme.borrow().diff_word(line.line_type, selected),
+            ) {
+                // ranges are into `trimmed` which is a prefix of `filled`
+                spans.push(Span::styled(
+                    Self::tabs_to_spaces(&filled[range.clone()]),
+                    style,
+                ));
+                cursor = range.end;
+            }
+        }
+
+        spans.push(Span::styled(
+            Self::tabs_to_spaces(&filled[cursor..]),
+            line_style,
+        ));
+
+        Spans::from(spans)
+    }
+
+    /// splits the styled byte ranges of a line where its changed
+    /// words start and end, changed words get `word_style`
+    fn split_by_changes(
+        base: Vec<(Range<usize>, Style)>,
+        line: &DiffLine,
+        word_style: Style,
+    ) -> Vec<(Range<usize>, Style)> {
+        if line.changes.is_empty() {
+            return base;
+        }
+
+        let mut res = Vec::with_capacity(base.len());
+
+        for (range, style) in base {
+            let mut start = range.start;
+
+            for change in &line.changes {
+                let change_start = cmp::max(change.start, start);
+                let change_end = cmp::min(change.end, range.end);
+
+                if change_end <= change_start {
+                    continue;
+                }
+                if change_start > start {
+                    res.push((start..change_start, style));
+                }
+                res.push((change_start..change_end, word_style));
+                start = change_end;
+            }
+
+            if range.end > start {
+                res.push((start..range.end, style));
+            }
+        }
+
+        res
+    }
+
+    //TODO: allow customize tabsize
+    fn tabs_to_spaces(text: &str) -> Cow<'static, str> {
+        Cow::from(text.replace("\t", "  "))
     }
 
     const fn hunk_visible(
@@ -465,7 +981,12 @@ impl DiffComponent {
         if let Some(diff) = &self.diff {
             if let Some(hunk) = self.selected_hunk {
                 let hash = diff.hunks[hunk].header_hash;
-                sync::unstage_hunk(CWD, &self.current.path, hash)?;
+                sync::unstage_hunk(
+                    &self.repo,
+                    &self.current.path,
+                    hash,
+                    Some(self.options.borrow().diff_options()),
+                )?;
                 self.queue_update();
             }
         }
@@ -478,12 +999,17 @@ impl DiffComponent {
             if let Some(hunk) = self.selected_hunk {
                 if diff.untracked {
                     sync::stage_add_file(
-                        CWD,
+                        &self.repo,
                         Path::new(&self.current.path),
                     )?;
                 } else {
                     let hash = diff.hunks[hunk].header_hash;
-                    sync::stage_hunk(CWD, &self.current.path, hash)?;
+                    sync::stage_hunk(
+                        &self.repo,
+                        &self.current.path,
+                        hash,
+                        Some(self.options.borrow().diff_options()),
+                    )?;
                 }
 
                 self.queue_update();
@@ -500,6 +1026,17 @@ impl DiffComponent {
             .push_back(InternalEvent::Update(NeedsUpdate::ALL));
     }
 
+    /// an untracked file has no hunks, the whole file is reset
+    fn reset_hunk_or_untracked(&self) {
+        if let Some(diff) = &self.diff {
+            if diff.untracked {
+                self.reset_untracked();
+            } else {
+                self.reset_hunk();
+            }
+        }
+    }
+
     fn reset_hunk(&self) {
         if let Some(diff) = &self.diff {
             if let Some(hunk) = self.selected_hunk {
@@ -534,7 +1071,7 @@ impl DiffComponent {
                     self,
                     "(un)stage lines:",
                     sync::stage_lines(
-                        CWD,
+                        &self.repo,
                         &self.current.path,
                         self.is_stage(),
                         &selected_lines,
@@ -546,6 +1083,23 @@ impl DiffComponent {
         }
     }
 
+    /// position of the line the cursor is on, `None` for headers
+    pub fn selected_line_position(&self) -> Option<DiffLinePosition> {
+        let idx = self.selection.get_end();
+
+        self.diff.as_ref().and_then(|diff| {
+            diff.hunks
+                .iter()
+                .flat_map(|hunk| hunk.lines.iter())
+                .nth(idx)
+                .map(|line| line.position)
+                .filter(|pos| {
+                    pos.new_lineno.is_some()
+                        || pos.old_lineno.is_some()
+                })
+        })
+    }
+
     fn selected_lines(&self) -> Vec<DiffLinePosition> {
         self.diff
             .as_ref()
@@ -590,6 +1144,71 @@ impl DiffComponent {
         Ok(())
     }
 
+    /// the unified diff, above the image preview if there is one
+    fn draw_text<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        r: Rect,
+        block: Block,
+        current_height: u16,
+    ) {
+        let txt = if self.pending {
+            vec![Spans::from(vec![Span::styled(
+                Cow::from(strings::loading_text(&self.key_config)),
+                self.theme.borrow().text(false, false),
+            )])]
+        } else {
+            self.get_text(r.width, current_height)
+        };
+
+        match &self.image_preview {
+            Some(preview) if !self.pending => {
+                let inner = block.inner(r);
+                f.render_widget(block, r);
+
+                let chunks = Layout::default()
+                    .direction(layout::Direction::Vertical)
+                    .constraints(
+                        [Constraint::Length(3), Constraint::Min(1)]
+                            .as_ref(),
+                    )
+                    .split(inner);
+
+                f.render_widget(Paragraph::new(txt), chunks[0]);
+                preview.draw(f, chunks[1]);
+            }
+            _ => {
+                f.render_widget(Paragraph::new(txt).block(block), r);
+            }
+        }
+    }
+
+    /// moves or extends the selection, `false` if `e` is no
+    /// movement key
+    fn move_event(&mut self, e: KeyEvent) -> bool {
+        if e == self.key_config.borrow().move_down {
+            self.move_selection(ScrollType::Down);
+        } else if e == self.key_config.borrow().shift_down {
+            self.modify_selection(Direction::Down);
+        } else if e == self.key_config.borrow().shift_up {
+            self.modify_selection(Direction::Up);
+        } else if e == self.key_config.borrow().end {
+            self.move_selection(ScrollType::End);
+        } else if e == self.key_config.borrow().home {
+            self.move_selection(ScrollType::Home);
+        } else if e == self.key_config.borrow().move_up {
+            self.move_selection(ScrollType::Up);
+        } else if e == self.key_config.borrow().page_up {
+            self.move_selection(ScrollType::PageUp);
+        } else if e == self.key_config.borrow().page_down {
+            self.move_selection(ScrollType::PageDown);
+        } else {
+            return false;
+        }
+
+        true
+    }
+
     const fn is_stage(&self) -> bool {
         self.current.is_stage
     }
@@ -608,46 +1227,90 @@ impl DrawableComponent for DiffComponent {
 
         let current_height = self.current_size.get().1;
 
+        let split_rows =
+            if self.is_split() && !self.pending && !self.folded {
+                self.diff
+                    .as_ref()
+                    .filter(|diff| !diff.hunks.is_empty())
+                    .map(Self::split_rows)
+            } else {
+                None
+            };
+
+        let selected_row = split_rows.as_ref().map_or_else(
+            || self.selection.get_end(),
+            |rows| Self::split_row_of(rows, self.selection.get_end()),
+        );
+
         self.scroll_top.set(calc_scroll_top(
             self.scroll_top.get(),
             current_height as usize,
-            self.selection.get_end(),
+            selected_row,
         ));
 
         let title = format!(
-            "{}{}",
+            "{}{}{}",
             strings::title_diff(&self.key_config),
-            self.current.path
+            self.current.path,
+            strings::diff_options_info(
+                self.options.borrow().diff_options()
+            ),
         );
 
-        let txt = if self.pending {
-            vec![Spans::from(vec![Span::styled(
-                Cow::from(strings::loading_text(&self.key_config)),
-                self.theme.text(false, false),
-            )])]
+        let block = Block::default()
+            .title(Span::styled(
+                title.as_str(),
+                self.theme.borrow().title(self.focused),
+            ))
+            .borders(Borders::ALL)
+            .border_style(self.theme.borrow().block(self.focused));
+
+        if let Some(rows) = &split_rows {
+            let inner = block.inner(r);
+            f.render_widget(block, r);
+
+            let halves = Layout::default()
+                .direction(layout::Direction::Horizontal)
+                .constraints(
+                    [
+                        Constraint::Percentage(50),
+                        Constraint::Percentage(50),
+                    ]
+                    .as_ref(),
+                )
+                .split(inner);
+
+            let (left, right) = self.get_split_text(
+                halves[1].width,
+                current_height,
+                rows,
+            );
+
+            f.render_widget(
+                Paragraph::new(left).block(
+                    Block::default()
+                        .borders(Borders::RIGHT)
+                        .border_style(
+                            self.theme.borrow().block(self.focused),
+                        ),
+                ),
+                halves[0],
+            );
+            f.render_widget(Paragraph::new(right), halves[1]);
         } else {
-            self.get_text(r.width, current_height)
-        };
+            self.draw_text(f, r, block, current_height);
+        }
+
+        if self.focused && !self.folded {
+            let total = split_rows
+                .as_ref()
+                .map_or_else(|| self.lines_count(), Vec::len);
 
-        f.render_widget(
-            Paragraph::new(txt).block(
-                Block::default()
-                    .title(Span::styled(
-                        title.as_str(),
-                        self.theme.title(self.focused),
-                    ))
-                    .borders(Borders::ALL)
-                    .border_style(self.theme.block(self.focused)),
-            ),
-            r,
-        );
-        if self.focused {
             ui::draw_scrollbar(
                 f,
                 r,
                 &self.theme,
-                self.lines_count()
-                    .saturating_sub(usize::from(current_height)),
+                total.saturating_sub(usize::from(current_height)),
                 self.scroll_top.get(),
             );
         }
@@ -677,7 +1340,7 @@ impl Component for DiffComponent {
             .hidden(),
         );
 
-        if !self.is_immutable {
+        if !self.is_immutable && !self.folded {
             out.push(CommandInfo::new(
                 strings::commands::diff_hunk_remove(&self.key_config),
                 self.selected_hunk.is_some(),
@@ -723,6 +1386,44 @@ impl Component for DiffComponent {
             self.focused,
         ));
 
+        out.push(CommandInfo::new(
+            strings::commands::open_link(&self.key_config),
+            self.diff.is_some(),
+            self.focused,
+        ));
+
+        out.push(CommandInfo::new(
+            strings::commands::diff_split_toggle(&self.key_config),
+            self.diff.is_some(),
+            self.focused,
+        ));
+
+        out.push(CommandInfo::new(
+            strings::commands::diff_toggle_fold(&self.key_config),
+            self.diff
+                .as_ref()
+                .map_or(false, |diff| !diff.hunks.is_empty()),
+            self.focused,
+        ));
+
+        out.push(CommandInfo::new(
+            strings::commands::diff_jump_symbol(&self.key_config),
+            self.selected_hunk.is_some(),
+            self.focused,
+        ));
+
+        out.push(CommandInfo::new(
+            strings::commands::diff_whitespace(&self.key_config),
+            true,
+            self.focused,
+        ));
+
+        out.push(CommandInfo::new(
+            strings::commands::diff_context(&self.key_config),
+            true,
+            self.focused,
+        ));
+
         CommandBlocking::PassingOn
     }
 
@@ -730,31 +1431,22 @@ impl Component for DiffComponent {
     fn event(&mut self, ev: Event) -> Result<EventState> {
         if self.focused {
             if let Event::Key(e) = ev {
-                return if e == self.key_config.move_down {
-                    self.move_selection(ScrollType::Down);
-                    Ok(EventState::Consumed)
-                } else if e == self.key_config.shift_down {
-                    self.modify_selection(Direction::Down);
-                    Ok(EventState::Consumed)
-                } else if e == self.key_config.shift_up {
-                    self.modify_selection(Direction::Up);
-                    Ok(EventState::Consumed)
-                } else if e == self.key_config.end {
-                    self.move_selection(ScrollType::End);
-                    Ok(EventState::Consumed)
-                } else if e == self.key_config.home {
-                    self.move_selection(ScrollType::Home);
-                    Ok(EventState::Consumed)
-                } else if e == self.key_config.move_up {
-                    self.move_selection(ScrollType::Up);
-                    Ok(EventState::Consumed)
-                } else if e == self.key_config.page_up {
-                    self.move_selection(ScrollType::PageUp);
-                    Ok(EventState::Consumed)
-                } else if e == self.key_config.page_down {
-                    self.move_selection(ScrollType::PageDown);
+                if self.folded {
+                    // expanding is the only thing to do on a folded diff
+                    return if e
+                        == self.key_config.borrow().diff_toggle_fold
+                        || e == self.key_config.borrow().enter
+                    {
+                        self.toggle_fold();
+                        Ok(EventState::Consumed)
+                    } else {
+                        Ok(EventState::NotConsumed)
+                    };
+                }
+
+                return if self.move_event(e) {
                     Ok(EventState::Consumed)
-                } else if e == self.key_config.enter
+                } else if e == self.key_config.borrow().enter
                     && !self.is_immutable
                 {
                     try_or_popup!(
@@ -764,24 +1456,21 @@ impl Component for DiffComponent {
                     );
 
                     Ok(EventState::Consumed)
-                } else if e == self.key_config.status_reset_item
+                } else if e
+                    == self.key_config.borrow().status_reset_item
                     && !self.is_immutable
                     && !self.is_stage()
                 {
-                    if let Some(diff) = &self.diff {
-                        if diff.untracked {
-                            self.reset_untracked();
-                        } else {
-                            self.reset_hunk();
-                        }
-                    }
+                    self.reset_hunk_or_untracked();
                     Ok(EventState::Consumed)
-                } else if e == self.key_config.diff_stage_lines
+                } else if e
+                    == self.key_config.borrow().diff_stage_lines
                     && !self.is_immutable
                 {
                     self.stage_lines();
                     Ok(EventState::Consumed)
-                } else if e == self.key_config.diff_reset_lines
+                } else if e
+                    == self.key_config.borrow().diff_reset_lines
                     && !self.is_immutable
                     && !self.is_stage()
                 {
@@ -792,9 +1481,48 @@ impl Component for DiffComponent {
                         }
                     }
                     Ok(EventState::Consumed)
-                } else if e == self.key_config.copy {
+                } else if e == self.key_config.borrow().copy {
                     self.copy_selection();
                     Ok(EventState::Consumed)
+                } else if e == self.key_config.borrow().open_link {
+                    self.open_links();
+                    Ok(EventState::Consumed)
+                } else if e
+                    == self.key_config.borrow().diff_split_toggle
+                {
+                    self.toggle_split();
+                    Ok(EventState::Consumed)
+                } else if e
+                    == self.key_config.borrow().diff_toggle_fold
+                {
+                    self.toggle_fold();
+                    Ok(EventState::Consumed)
+                } else if e
+                    == self.key_config.borrow().diff_jump_symbol
+                {
+                    self.jump_to_symbol();
+                    Ok(EventState::Consumed)
+                } else if e
+                    == self.key_config.borrow().diff_whitespace
+                {
+                    self.options.borrow_mut().diff_cycle_whitespace();
+                    self.queue_diff_update();
+                    Ok(EventState::Consumed)
+                } else if e
+                    == self.key_config.borrow().diff_context_increase
+                    || e == self
+                        .key_config
+                        .borrow()
+                        .diff_context_decrease
+                {
+                    self.options.borrow_mut().diff_context_change(
+                        e == self
+                            .key_config
+                            .borrow()
+                            .diff_context_increase,
+                    );
+                    self.queue_diff_update();
+                    Ok(EventState::Consumed)
                 } else {
                     Ok(EventState::NotConsumed)
                 };
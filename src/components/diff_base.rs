@@ -0,0 +1,153 @@
+use super::{
+    textinput::TextInputComponent, visibility_blocking,
+    CommandBlocking, CommandInfo, Component, DrawableComponent,
+    EventState, InputType,
+};
+use crate::{
+    keys::SharedKeyConfig,
+    queue::{InternalEvent, Queue},
+    strings,
+    ui::style::SharedTheme,
+};
+use anyhow::Result;
+use asyncgit::sync::{self, RepoPath};
+use crossterm::event::Event;
+use tui::{backend::Backend, layout::Rect, Frame};
+
+/// asks for the ref the status tab compares the working tree with
+pub struct DiffBaseComponent {
+    repo: RepoPath,
+    input: TextInputComponent,
+    queue: Queue,
+    key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for DiffBaseComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        self.input.draw(f, rect)?;
+
+        Ok(())
+    }
+}
+
+impl Component for DiffBaseComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.is_visible() || force_all {
+            self.input.commands(out, force_all);
+
+            out.push(CommandInfo::new(
+                strings::commands::diff_base_confirm_msg(
+                    &self.key_config,
+                ),
+                true,
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<EventState> {
+        if self.is_visible() {
+            if self.input.event(ev)?.is_consumed() {
+                return Ok(EventState::Consumed);
+            }
+
+            if let Event::Key(e) = ev {
+                if e == self.key_config.borrow().enter {
+                    self.set_base();
+                }
+
+                return Ok(EventState::Consumed);
+            }
+        }
+        Ok(EventState::NotConsumed)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.input.is_visible()
+    }
+
+    fn hide(&mut self) {
+        self.input.hide()
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.input.show()?;
+
+        Ok(())
+    }
+}
+
+impl DiffBaseComponent {
+    ///
+    pub fn new(
+        repo: RepoPath,
+        queue: Queue,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+    ) -> Self {
+        Self {
+            queue,
+            input: TextInputComponent::new(
+                theme,
+                key_config.clone(),
+                &strings::diff_base_popup_title(&key_config),
+                &strings::diff_base_popup_msg(&key_config),
+                true,
+            )
+            .with_input_type(InputType::Singleline),
+            key_config,
+            repo,
+        }
+    }
+
+    /// prefills the input with the currently used base
+    pub fn open(&mut self, current: Option<String>) -> Result<()> {
+        self.input.set_text(current.unwrap_or_default());
+        self.show()?;
+
+        Ok(())
+    }
+
+    /// resolves the entered ref, an empty input resets the base
+    /// back to `HEAD`
+    fn set_base(&mut self) {
+        let rev = self.input.get_text().trim().to_string();
+
+        self.input.clear();
+        self.hide();
+
+        if rev.is_empty() {
+            self.queue
+                .borrow_mut()
+                .push_back(InternalEvent::SetDiffBase(None));
+            return;
+        }
+
+        match sync::resolve_rev(&self.repo, &rev) {
+            Ok(id) => {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::SetDiffBase(Some((rev, id))),
+                );
+            }
+            Err(e) => {
+                log::error!("diff base: {}", e);
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::ShowErrorMsg(format!(
+                        "diff base error:\n{}",
+                        e,
+                    )),
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,184 @@
+use super::{
+    textinput::TextInputComponent, visibility_blocking,
+    CommandBlocking, CommandInfo, Component, DrawableComponent,
+    EventState, InputType,
+};
+use crate::{
+    keys::SharedKeyConfig,
+    queue::{InternalEvent, Queue},
+    strings,
+    ui::style::SharedTheme,
+};
+use anyhow::Result;
+use asyncgit::sync::{self, CommitId, RepoPath};
+use crossterm::event::Event;
+use std::{fs, path::Path};
+use tui::{backend::Backend, layout::Rect, Frame};
+
+/// asks for the file to save the patch of a commit to, or copies
+/// the patch to the clipboard instead
+pub struct ExportPatchComponent {
+    repo: RepoPath,
+    input: TextInputComponent,
+    commit: Option<CommitId>,
+    queue: Queue,
+    key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for ExportPatchComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        self.input.draw(f, rect)?;
+
+        Ok(())
+    }
+}
+
+impl Component for ExportPatchComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.is_visible() || force_all {
+            self.input.commands(out, force_all);
+
+            out.push(CommandInfo::new(
+                strings::commands::export_patch_confirm(
+                    &self.key_config,
+                ),
+                !self.input.get_text().trim().is_empty(),
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::export_patch_copy(
+                    &self.key_config,
+                ),
+                true,
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<EventState> {
+        if self.is_visible() {
+            if let Event::Key(e) = ev {
+                if e == self.key_config.borrow().export_patch_copy {
+                    self.export(None);
+                    return Ok(EventState::Consumed);
+                }
+            }
+
+            if self.input.event(ev)?.is_consumed() {
+                return Ok(EventState::Consumed);
+            }
+
+            if let Event::Key(e) = ev {
+                if e == self.key_config.borrow().enter {
+                    let file =
+                        self.input.get_text().trim().to_string();
+
+                    if !file.is_empty() {
+                        self.export(Some(&file));
+                    }
+                }
+
+                return Ok(EventState::Consumed);
+            }
+        }
+        Ok(EventState::NotConsumed)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.input.is_visible()
+    }
+
+    fn hide(&mut self) {
+        self.input.hide()
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.input.show()?;
+
+        Ok(())
+    }
+}
+
+impl ExportPatchComponent {
+    ///
+    pub fn new(
+        repo: RepoPath,
+        queue: Queue,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+    ) -> Self {
+        Self {
+            queue,
+            input: TextInputComponent::new(
+                theme,
+                key_config.clone(),
+                &strings::export_patch_popup_title(&key_config),
+                &strings::export_patch_popup_msg(&key_config),
+                true,
+            )
+            .with_input_type(InputType::Singleline),
+            commit: None,
+            key_config,
+            repo,
+        }
+    }
+
+    /// prefills the file name from the subject of the commit
+    pub fn open(&mut self, id: CommitId) -> Result<()> {
+        let info = sync::get_commit_info(&self.repo, &id)?;
+
+        self.commit = Some(id);
+        self.input.set_text(sync::patch_file_name(
+            info.message.lines().next().unwrap_or_default(),
+        ));
+        self.show()
+    }
+
+    /// writes the patch to `file` (relative to the work dir) or to
+    /// the clipboard if there is none
+    fn export(&mut self, file: Option<&str>) {
+        if let Some(id) = self.commit {
+            self.hide();
+
+            if let Err(e) = self.write_patch(id, file) {
+                log::error!("export patch: {}", e);
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::ShowErrorMsg(format!(
+                        "export patch error:\n{}",
+                        e,
+                    )),
+                );
+            }
+        }
+    }
+
+    fn write_patch(
+        &self,
+        id: CommitId,
+        file: Option<&str>,
+    ) -> Result<()> {
+        let patch = sync::format_patch(&self.repo, id)?;
+
+        if let Some(file) = file {
+            let path =
+                Path::new(&sync::utils::repo_work_dir(&self.repo)?)
+                    .join(file);
+            fs::write(path, patch)?;
+        } else {
+            crate::clipboard::copy_string(&patch)?;
+        }
+
+        Ok(())
+    }
+}
@@ -8,11 +8,11 @@ use crate::{
     ui::{self, style::SharedTheme},
 };
 use anyhow::{anyhow, bail, Result};
-use asyncgit::{
-    sync::utils::get_config_string, sync::utils::repo_work_dir, CWD,
+use asyncgit::sync::{
+    utils::get_config_string, utils::repo_work_dir, RepoPath,
 };
 use crossterm::{
-    event::Event,
+    event::{DisableMouseCapture, EnableMouseCapture, Event},
     terminal::{EnterAlternateScreen, LeaveAlternateScreen},
     ExecutableCommand,
 };
@@ -48,8 +48,11 @@ impl ExternalEditorComponent {
     }
 
     /// opens file at given `path` in an available editor
-    pub fn open_file_in_editor(path: &Path) -> Result<()> {
-        let work_dir = repo_work_dir(CWD)?;
+    pub fn open_file_in_editor(
+        repo: &RepoPath,
+        path: &Path,
+    ) -> Result<()> {
+        let work_dir = repo_work_dir(repo)?;
 
         let path = if path.is_relative() {
             Path::new(&work_dir).join(path)
@@ -61,31 +64,81 @@ impl ExternalEditorComponent {
             bail!("file not found: {:?}", path);
         }
 
-        io::stdout().execute(LeaveAlternateScreen)?;
-        defer! {
-            io::stdout().execute(EnterAlternateScreen).expect("reset terminal");
-        }
-
         let environment_options = ["GIT_EDITOR", "VISUAL", "EDITOR"];
 
         let editor = env::var(environment_options[0])
             .ok()
-            .or_else(|| get_config_string(CWD, "core.editor").ok()?)
+            .or_else(|| {
+                get_config_string(repo, "core.editor").ok()?
+            })
             .or_else(|| env::var(environment_options[1]).ok())
             .or_else(|| env::var(environment_options[2]).ok())
             .unwrap_or_else(|| String::from("vi"));
 
+        Self::run_with_file(
+            &work_dir,
+            &editor,
+            &path,
+            &environment_options,
+        )
+    }
+
+    /// shows the file at the absolute `path` in the pager git uses
+    pub fn open_file_in_pager(
+        repo: &RepoPath,
+        path: &Path,
+    ) -> Result<()> {
+        let work_dir = repo_work_dir(repo)?;
+
+        if !path.exists() {
+            bail!("file not found: {:?}", path);
+        }
+
+        let environment_options = ["GIT_PAGER", "PAGER"];
+
+        let pager = env::var(environment_options[0])
+            .ok()
+            .or_else(|| get_config_string(repo, "core.pager").ok()?)
+            .or_else(|| env::var(environment_options[1]).ok())
+            .unwrap_or_else(|| String::from("less"));
+
+        Self::run_with_file(
+            &work_dir,
+            &pager,
+            path,
+            &environment_options,
+        )
+    }
+
+    /// runs the `program` command line with `path` as last argument
+    /// outside of the alternate screen
+    fn run_with_file(
+        work_dir: &str,
+        program: &str,
+        path: &Path,
+        environment_options: &[&str],
+    ) -> Result<()> {
+        io::stdout()
+            .execute(DisableMouseCapture)?
+            .execute(LeaveAlternateScreen)?;
+        defer! {
+            io::stdout()
+                .execute(EnterAlternateScreen)
+                .and_then(|out| out.execute(EnableMouseCapture))
+                .expect("reset terminal");
+        }
+
         // TODO: proper handling arguments containing whitespaces
         // This does not do the right thing if the input is `editor --something "with spaces"`
 
         // deal with "editor name with spaces" p1 p2 p3
         // and with "editor_no_spaces" p1 p2 p3
         // does not address spaces in pn
-        let mut echars = editor.chars().peekable();
+        let mut echars = program.chars().peekable();
 
         let first_char = *echars.peek().ok_or_else(|| {
             anyhow!(
-                "editor env variable found empty: {}",
+                "env variable found empty: {}",
                 environment_options.join(" or ")
             )
         })?;
@@ -115,6 +168,39 @@ impl ExternalEditorComponent {
 
         Ok(())
     }
+
+    /// runs another instance of gitui inside of the submodule or
+    /// nested repository at given `path` and returns once it quits
+    pub fn open_nested_repo(
+        repo: &RepoPath,
+        path: &Path,
+    ) -> Result<()> {
+        let work_dir = Path::new(&repo_work_dir(repo)?).join(path);
+
+        if !work_dir.join(".git").exists() {
+            bail!("no repository checked out at: {:?}", path);
+        }
+
+        io::stdout()
+            .execute(DisableMouseCapture)?
+            .execute(LeaveAlternateScreen)?;
+        defer! {
+            io::stdout()
+                .execute(EnterAlternateScreen)
+                .and_then(|out| out.execute(EnableMouseCapture))
+                .expect("reset terminal");
+        }
+
+        // the nested repository must not pick up our own
+        // `GIT_DIR`/`GIT_WORK_TREE`
+        Command::new(env::current_exe()?)
+            .current_dir(work_dir)
+            .env_remove("GIT_DIR")
+            .env_remove("GIT_WORK_TREE")
+            .status()?;
+
+        Ok(())
+    }
 }
 
 impl DrawableComponent for ExternalEditorComponent {
@@ -141,9 +227,11 @@ impl DrawableComponent for ExternalEditorComponent {
                         Block::default()
                             .borders(Borders::ALL)
                             .border_type(BorderType::Thick)
-                            .border_style(self.theme.block(true)),
+                            .border_style(
+                                self.theme.borrow().block(true),
+                            ),
                     )
-                    .style(self.theme.block(true)),
+                    .style(self.theme.borrow().block(true)),
                 area,
             );
         }
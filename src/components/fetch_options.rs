@@ -0,0 +1,165 @@
+use super::{
+    textinput::TextInputComponent, visibility_blocking,
+    CommandBlocking, CommandInfo, Component, DrawableComponent,
+    EventState, InputType,
+};
+use crate::{
+    keys::SharedKeyConfig,
+    queue::{InternalEvent, Queue},
+    strings,
+    ui::style::SharedTheme,
+};
+use anyhow::Result;
+use asyncgit::sync::{self, FetchDepth, RepoPath};
+use crossterm::event::Event;
+use tui::{backend::Backend, layout::Rect, Frame};
+
+/// asks for the depth to fetch the current branch with
+pub struct FetchOptionsComponent {
+    repo: RepoPath,
+    input: TextInputComponent,
+    branch: String,
+    shallow: bool,
+    queue: Queue,
+    key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for FetchOptionsComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        self.input.draw(f, rect)?;
+
+        Ok(())
+    }
+}
+
+impl Component for FetchOptionsComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.is_visible() || force_all {
+            self.input.commands(out, force_all);
+
+            out.push(CommandInfo::new(
+                strings::commands::fetch_options_confirm(
+                    &self.key_config,
+                ),
+                true,
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<EventState> {
+        if self.is_visible() {
+            if self.input.event(ev)?.is_consumed() {
+                return Ok(EventState::Consumed);
+            }
+
+            if let Event::Key(e) = ev {
+                if e == self.key_config.borrow().enter {
+                    self.fetch();
+                }
+
+                return Ok(EventState::Consumed);
+            }
+        }
+        Ok(EventState::NotConsumed)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.input.is_visible()
+    }
+
+    fn hide(&mut self) {
+        self.input.hide()
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.input.show()?;
+
+        Ok(())
+    }
+}
+
+impl FetchOptionsComponent {
+    ///
+    pub fn new(
+        repo: RepoPath,
+        queue: Queue,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+    ) -> Self {
+        Self {
+            queue,
+            input: TextInputComponent::new(
+                theme,
+                key_config.clone(),
+                &strings::fetch_options_popup_title(
+                    &key_config,
+                    false,
+                ),
+                &strings::fetch_options_popup_msg(&key_config),
+                true,
+            )
+            .with_input_type(InputType::Singleline),
+            branch: String::new(),
+            shallow: false,
+            key_config,
+            repo,
+        }
+    }
+
+    ///
+    pub fn open(&mut self, branch: String) -> Result<()> {
+        self.branch = branch;
+        self.shallow = sync::is_shallow(&self.repo)?;
+        self.input.set_title(strings::fetch_options_popup_title(
+            &self.key_config,
+            self.shallow,
+        ));
+        self.input.clear();
+        self.show()
+    }
+
+    fn fetch(&mut self) {
+        let text = self.input.get_text().trim().to_string();
+
+        let depth = if text.is_empty() {
+            if self.shallow {
+                FetchDepth::Unshallow
+            } else {
+                FetchDepth::Full
+            }
+        } else {
+            match text.parse::<u32>() {
+                Ok(depth) if depth > 0 => FetchDepth::Depth(depth),
+                _ => {
+                    self.queue.borrow_mut().push_back(
+                        InternalEvent::ShowErrorMsg(
+                            strings::fetch_options_invalid_depth(
+                                &text,
+                            ),
+                        ),
+                    );
+                    return;
+                }
+            }
+        };
+
+        self.input.clear();
+        self.hide();
+
+        self.queue.borrow_mut().push_back(InternalEvent::FetchDepth(
+            self.branch.clone(),
+            depth,
+        ));
+    }
+}
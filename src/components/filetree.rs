@@ -1,6 +1,7 @@
 use super::{
     utils::{
         filetree::{FileTreeItem, FileTreeItemKind},
+        fuzzy_match,
         statustree::{MoveSelection, StatusTree},
     },
     CommandBlocking, DrawableComponent,
@@ -15,22 +16,41 @@ use crate::{
 };
 use anyhow::Result;
 use asyncgit::{hash, StatusItem, StatusItemType};
-use crossterm::event::Event;
-use std::{borrow::Cow, cell::Cell, convert::From, path::Path};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use std::{
+    borrow::Cow,
+    cell::Cell,
+    collections::{BTreeSet, HashSet},
+    convert::From,
+    path::Path,
+};
 use tui::{backend::Backend, layout::Rect, text::Span, Frame};
 
 ///
 pub struct FileTreeComponent {
     title: String,
     tree: StatusTree,
+    items: Vec<StatusItem>,
+    filter: String,
+    filter_editing: bool,
     pending: bool,
     current_hash: u64,
     focused: bool,
     show_selection: bool,
+    /// files can be marked to act on all of them at once
+    markable: bool,
+    /// paths of the marked files
+    marked: BTreeSet<String>,
+    /// tree index an unfinished range of marked files starts at
+    range_start: Option<usize>,
     queue: Option<Queue>,
     theme: SharedTheme,
     key_config: SharedKeyConfig,
     scroll_top: Cell<usize>,
+    /// where the list was drawn last, to map mouse clicks
+    area: Cell<Rect>,
+    /// row of the selection inside of `area` when drawn last
+    selection_row: Cell<usize>,
 }
 
 impl FileTreeComponent {
@@ -45,13 +65,21 @@ impl FileTreeComponent {
         Self {
             title: title.to_string(),
             tree: StatusTree::default(),
+            items: Vec::new(),
+            filter: String::new(),
+            filter_editing: false,
             current_hash: 0,
             focused: focus,
             show_selection: focus,
+            markable: false,
+            marked: BTreeSet::new(),
+            range_start: None,
             queue,
             theme,
             key_config,
             scroll_top: Cell::new(0),
+            area: Cell::new(Rect::default()),
+            selection_row: Cell::new(0),
             pending: true,
         }
     }
@@ -61,18 +89,123 @@ impl FileTreeComponent {
         self.pending = false;
         let new_hash = hash(list);
         if self.current_hash != new_hash {
-            self.tree.update(list)?;
+            self.items = list.to_vec();
+
+            let paths: HashSet<&str> =
+                list.iter().map(|item| item.path.as_str()).collect();
+            self.marked.retain(|path| paths.contains(path.as_str()));
+
+            self.update_tree()?;
             self.current_hash = new_hash;
         }
 
         Ok(())
     }
 
+    /// feeds the tree with all items matching the current filter
+    fn update_tree(&mut self) -> Result<()> {
+        // the tree indices change
+        self.range_start = None;
+
+        if self.filter.is_empty() {
+            return self.tree.update(&self.items);
+        }
+
+        let filtered = self
+            .items
+            .iter()
+            .filter(|item| fuzzy_match(&self.filter, &item.path))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        self.tree.update(&filtered)
+    }
+
+    fn set_filter(&mut self, filter: String) -> Result<()> {
+        self.filter = filter;
+        self.update_tree()?;
+
+        // jump to the first matching file instead of its folder
+        if !self.filter.is_empty() && !self.is_file_seleted() {
+            if let Some(idx) =
+                self.tree.tree.items().iter().position(|item| {
+                    item.info.visible
+                        && matches!(
+                            item.kind,
+                            FileTreeItemKind::File(_)
+                        )
+                })
+            {
+                self.tree.selection = Some(idx);
+            }
+        }
+
+        if let Some(ref queue) = self.queue {
+            queue
+                .borrow_mut()
+                .push_back(InternalEvent::Update(NeedsUpdate::DIFF));
+        }
+
+        Ok(())
+    }
+
+    /// handles keys while the filter is being typed,
+    /// all of them are consumed
+    fn filter_event(&mut self, key: KeyEvent) -> Result<()> {
+        if key == self.key_config.borrow().exit_popup {
+            self.filter_editing = false;
+            self.set_filter(String::new())?;
+        } else if key == self.key_config.borrow().enter {
+            self.filter_editing = false;
+        } else if key.code == KeyCode::Backspace {
+            let mut filter = self.filter.clone();
+            filter.pop();
+            self.set_filter(filter)?;
+        } else if let KeyCode::Char(c) = key.code {
+            if !key.modifiers.contains(KeyModifiers::CONTROL) {
+                let mut filter = self.filter.clone();
+                filter.push(c);
+                self.set_filter(filter)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn draw_title(&self) -> Cow<str> {
+        if self.filter.is_empty() && !self.filter_editing {
+            Cow::from(self.title.as_str())
+        } else {
+            Cow::from(format!(
+                "{}{}",
+                self.title,
+                strings::file_filter_info(
+                    &self.filter,
+                    self.filter_editing
+                )
+            ))
+        }
+    }
+
     ///
     pub fn selection(&self) -> Option<FileTreeItem> {
         self.tree.selected_item()
     }
 
+    fn copy_path(&self, path: &str) {
+        if let Err(e) = crate::clipboard::copy_string(path) {
+            log::error!("copy to clipboard error: {}", e);
+            if let Some(queue) = &self.queue {
+                queue.borrow_mut().push_back(
+                    InternalEvent::ShowErrorMsg(format!(
+                        "copy to clipboard error:\n{}",
+                        e
+                    )),
+                );
+            }
+        }
+    }
+
     ///
     pub fn selection_file(&self) -> Option<StatusItem> {
         self.tree.selected_item().and_then(|f| {
@@ -84,6 +217,119 @@ impl FileTreeComponent {
         })
     }
 
+    /// allows marking files (see `marked`)
+    pub fn enable_marking(&mut self) {
+        self.markable = true;
+    }
+
+    /// paths of the marked files including an unfinished range,
+    /// empty if nothing is marked
+    pub fn marked(&self) -> Vec<String> {
+        let mut marked = self.marked.clone();
+        if let (Some(start), Some(end)) =
+            (self.range_start, self.tree.selection)
+        {
+            marked.extend(self.files_between(start, end));
+        }
+
+        marked.into_iter().collect()
+    }
+
+    ///
+    pub fn clear_marked(&mut self) {
+        self.marked.clear();
+        self.range_start = None;
+    }
+
+    fn any_marked(&self) -> bool {
+        !self.marked.is_empty() || self.range_start.is_some()
+    }
+
+    fn is_marked(&self, index: usize, item: &FileTreeItem) -> bool {
+        if let FileTreeItemKind::File(file) = &item.kind {
+            let in_range =
+                match (self.range_start, self.tree.selection) {
+                    (Some(start), Some(end)) => {
+                        start.min(end) <= index
+                            && index <= start.max(end)
+                    }
+                    _ => false,
+                };
+
+            in_range || self.marked.contains(&file.path)
+        } else {
+            false
+        }
+    }
+
+    /// files of the tree items between both indices (inclusive),
+    /// nested repositories cannot be staged and are left out
+    fn files_between(&self, start: usize, end: usize) -> Vec<String> {
+        self.tree
+            .tree
+            .items()
+            .iter()
+            .skip(start.min(end))
+            .take(start.max(end) - start.min(end) + 1)
+            .filter_map(|item| match &item.kind {
+                FileTreeItemKind::File(file) if !file.nested_repo => {
+                    Some(file.path.clone())
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// the selected file or all files inside of the selected folder
+    fn selected_files(&self) -> Vec<String> {
+        let items = self.tree.tree.items();
+
+        self.tree.selection.map_or_else(Vec::new, |idx| {
+            let indent = items[idx].info.indent;
+            let end = items
+                .iter()
+                .enumerate()
+                .skip(idx + 1)
+                .find(|(_, item)| item.info.indent <= indent)
+                .map_or(items.len(), |(end, _)| end);
+
+            self.files_between(idx, end - 1)
+        })
+    }
+
+    /// marks the selected files or unmarks them if all of them are
+    /// marked already
+    fn toggle_mark_selected(&mut self) -> bool {
+        let files = self.selected_files();
+        if files.is_empty() {
+            return false;
+        }
+
+        if files.iter().all(|file| self.marked.contains(file)) {
+            for file in &files {
+                self.marked.remove(file);
+            }
+        } else {
+            self.marked.extend(files);
+        }
+
+        true
+    }
+
+    /// starts a range at the selection or marks all files from its
+    /// start to the selection
+    fn toggle_mark_range(&mut self) -> bool {
+        if let Some(start) = self.range_start.take() {
+            if let Some(end) = self.tree.selection {
+                self.marked.extend(self.files_between(start, end));
+            }
+        } else {
+            self.range_start = self.tree.selection;
+        }
+
+        self.tree.selection.is_some()
+    }
+
     ///
     pub fn show_selection(&mut self, show: bool) {
         self.show_selection = show;
@@ -108,6 +354,7 @@ impl FileTreeComponent {
     pub fn clear(&mut self) -> Result<()> {
         self.current_hash = 0;
         self.pending = true;
+        self.items.clear();
         self.tree.update(&[])
     }
 
@@ -121,6 +368,29 @@ impl FileTreeComponent {
         })
     }
 
+    /// selects the item drawn in the terminal `row` (mouse click),
+    /// rows outside of the list are ignored
+    pub fn select_at_row(&mut self, row: u16) {
+        let area = self.area.get();
+        if row <= area.y || row + 1 >= area.y + area.height {
+            return;
+        }
+
+        let target = usize::from(row - area.y - 1);
+        let current = self.selection_row.get();
+        let (dir, steps) = if target < current {
+            (MoveSelection::Up, current - target)
+        } else {
+            (MoveSelection::Down, target - current)
+        };
+
+        for _ in 0..steps {
+            if !self.move_selection(dir) {
+                break;
+            }
+        }
+    }
+
     fn move_selection(&mut self, dir: MoveSelection) -> bool {
         let changed = self.tree.move_selection(dir);
 
@@ -135,7 +405,7 @@ impl FileTreeComponent {
         changed
     }
 
-    const fn item_status_char(item_type: StatusItemType) -> char {
+    pub const fn item_status_char(item_type: StatusItemType) -> char {
         match item_type {
             StatusItemType::Modified => 'M',
             StatusItemType::New => '+',
@@ -147,25 +417,31 @@ impl FileTreeComponent {
     }
 
     fn item_to_text<'b>(
-        string: &str,
-        indent: usize,
-        visible: bool,
-        file_item_kind: &FileTreeItemKind,
+        draw_text_info: &TextDrawInfo,
         width: u16,
         selected: bool,
         theme: &'b SharedTheme,
     ) -> Option<Span<'b>> {
+        let string = draw_text_info.name.as_str();
+        let indent = draw_text_info.indent as usize;
         let indent_str = if indent == 0 {
             String::from("")
         } else {
-            format!("{:w$}", " ", w = (indent as usize) * 2)
+            format!("{:w$}", " ", w = indent * 2)
         };
 
-        if !visible {
+        if !draw_text_info.visible {
             return None;
         }
 
-        match file_item_kind {
+        // marker column, only while anything is marked
+        let marker = match draw_text_info.marked {
+            Some(true) => "\u{221a} ", //√
+            Some(false) => "  ",
+            None => "",
+        };
+
+        match draw_text_info.item_kind {
             FileTreeItemKind::File(status_item) => {
                 let status_char =
                     Self::item_status_char(status_item.status);
@@ -174,21 +450,50 @@ impl FileTreeComponent {
                     .and_then(std::ffi::OsStr::to_str)
                     .expect("invalid path.");
 
+                let file = if status_item.nested_repo {
+                    Cow::from(format!(
+                        "{}{}",
+                        file,
+                        strings::nested_repo_info()
+                    ))
+                } else if status_item.summarized {
+                    Cow::from(format!(
+                        "{}{}",
+                        file,
+                        strings::summarized_folder_info()
+                    ))
+                } else {
+                    status_item.submodule.map_or_else(
+                        || Cow::from(file),
+                        |state| {
+                            Cow::from(format!(
+                                "{}{}",
+                                file,
+                                strings::submodule_state_info(state)
+                            ))
+                        },
+                    )
+                };
+
                 let txt = if selected {
                     format!(
-                        "{} {}{:w$}",
+                        "{}{} {}{:w$}",
+                        marker,
                         status_char,
                         indent_str,
                         file,
                         w = width as usize
                     )
                 } else {
-                    format!("{} {}{}", status_char, indent_str, file)
+                    format!(
+                        "{}{} {}{}",
+                        marker, status_char, indent_str, file
+                    )
                 };
 
                 Some(Span::styled(
                     Cow::from(txt),
-                    theme.item(status_item.status, selected),
+                    theme.borrow().item(status_item.status, selected),
                 ))
             }
 
@@ -198,7 +503,8 @@ impl FileTreeComponent {
 
                 let txt = if selected {
                     format!(
-                        "  {}{}{:w$}",
+                        "{}  {}{}{:w$}",
+                        marker,
                         indent_str,
                         collapse_char,
                         string,
@@ -206,14 +512,14 @@ impl FileTreeComponent {
                     )
                 } else {
                     format!(
-                        "  {}{}{}",
-                        indent_str, collapse_char, string,
+                        "{}  {}{}{}",
+                        marker, indent_str, collapse_char, string,
                     )
                 };
 
                 Some(Span::styled(
                     Cow::from(txt),
-                    theme.text(true, selected),
+                    theme.borrow().text(true, selected),
                 ))
             }
         }
@@ -247,6 +553,11 @@ impl FileTreeComponent {
                 name: item.info.path.clone(),
                 indent: item.info.indent,
                 visible: item.info.visible,
+                marked: if self.any_marked() {
+                    Some(self.is_marked(index, item))
+                } else {
+                    None
+                },
                 item_kind: &item.kind,
             });
 
@@ -302,6 +613,8 @@ struct TextDrawInfo<'a> {
     name: String,
     indent: u8,
     visible: bool,
+    /// `None` while nothing is marked
+    marked: Option<bool>,
     item_kind: &'a FileTreeItemKind,
 }
 
@@ -311,16 +624,18 @@ impl DrawableComponent for FileTreeComponent {
         f: &mut Frame<B>,
         r: Rect,
     ) -> Result<()> {
+        let title = self.draw_title();
+
         if self.pending {
             let items = vec![Span::styled(
                 Cow::from(strings::loading_text(&self.key_config)),
-                self.theme.text(false, false),
+                self.theme.borrow().text(false, false),
             )];
 
             ui::draw_list(
                 f,
                 r,
-                self.title.as_str(),
+                &title,
                 items.into_iter(),
                 self.focused,
                 &self.theme,
@@ -344,16 +659,19 @@ impl DrawableComponent for FileTreeComponent {
                 tree_height,
                 select.saturating_sub(selection_offset_visible),
             ));
+            self.area.set(r);
+            self.selection_row.set(
+                select
+                    .saturating_sub(selection_offset_visible)
+                    .saturating_sub(self.scroll_top.get()),
+            );
 
             let items = vec_draw_text_info
                 .iter()
                 .enumerate()
                 .filter_map(|(index, draw_text_info)| {
                     Self::item_to_text(
-                        &draw_text_info.name,
-                        draw_text_info.indent as usize,
-                        draw_text_info.visible,
-                        draw_text_info.item_kind,
+                        draw_text_info,
                         r.width,
                         self.show_selection && select == index,
                         &self.theme,
@@ -363,7 +681,7 @@ impl DrawableComponent for FileTreeComponent {
             ui::draw_list(
                 f,
                 r,
-                self.title.as_str(),
+                &title,
                 items,
                 self.focused,
                 &self.theme,
@@ -388,6 +706,14 @@ impl Component for FileTreeComponent {
             )
             .order(order::NAV),
         );
+        out.push(
+            CommandInfo::new(
+                strings::commands::file_filter(&self.key_config),
+                !self.items.is_empty(),
+                self.focused || force_all,
+            )
+            .order(order::NAV),
+        );
         out.push(
             CommandInfo::new(
                 strings::commands::blame_file(&self.key_config),
@@ -396,6 +722,35 @@ impl Component for FileTreeComponent {
             )
             .order(order::RARE_ACTION),
         );
+        out.push(
+            CommandInfo::new(
+                strings::commands::copy_path(&self.key_config),
+                self.selection().is_some(),
+                self.focused || force_all,
+            )
+            .order(order::RARE_ACTION),
+        );
+
+        if self.markable {
+            out.push(
+                CommandInfo::new(
+                    strings::commands::file_mark(&self.key_config),
+                    self.selection().is_some(),
+                    self.focused || force_all,
+                )
+                .key(self.key_config.borrow().file_mark),
+            );
+            out.push(
+                CommandInfo::new(
+                    strings::commands::file_mark_range(
+                        &self.key_config,
+                    ),
+                    self.selection().is_some(),
+                    self.focused || force_all,
+                )
+                .key(self.key_config.borrow().file_mark_range),
+            );
+        }
 
         CommandBlocking::PassingOn
     }
@@ -403,7 +758,36 @@ impl Component for FileTreeComponent {
     fn event(&mut self, ev: Event) -> Result<EventState> {
         if self.focused {
             if let Event::Key(e) = ev {
-                return if e == self.key_config.blame {
+                if self.filter_editing {
+                    self.filter_event(e)?;
+                    return Ok(EventState::Consumed);
+                }
+
+                return if e == self.key_config.borrow().file_filter
+                    && !self.items.is_empty()
+                {
+                    self.filter_editing = true;
+                    Ok(EventState::Consumed)
+                } else if e == self.key_config.borrow().exit_popup
+                    && !self.filter.is_empty()
+                {
+                    self.set_filter(String::new())?;
+                    Ok(EventState::Consumed)
+                } else if e == self.key_config.borrow().exit_popup
+                    && self.any_marked()
+                {
+                    self.clear_marked();
+                    Ok(EventState::Consumed)
+                } else if e == self.key_config.borrow().file_mark
+                    && self.markable
+                {
+                    Ok(self.toggle_mark_selected().into())
+                } else if e
+                    == self.key_config.borrow().file_mark_range
+                    && self.markable
+                {
+                    Ok(self.toggle_mark_range().into())
+                } else if e == self.key_config.borrow().blame {
                     match (&self.queue, self.selection_file()) {
                         (Some(queue), Some(status_item)) => {
                             queue.borrow_mut().push_back(
@@ -416,26 +800,34 @@ impl Component for FileTreeComponent {
                         }
                         _ => Ok(EventState::NotConsumed),
                     }
-                } else if e == self.key_config.move_down {
+                } else if e == self.key_config.borrow().copy {
+                    match self.selection() {
+                        Some(item) => {
+                            self.copy_path(&item.info.full_path);
+                            Ok(EventState::Consumed)
+                        }
+                        None => Ok(EventState::NotConsumed),
+                    }
+                } else if e == self.key_config.borrow().move_down {
                     Ok(self.move_selection(MoveSelection::Down))
                         .map(Into::into)
-                } else if e == self.key_config.move_up {
+                } else if e == self.key_config.borrow().move_up {
                     Ok(self.move_selection(MoveSelection::Up).into())
-                } else if e == self.key_config.home
-                    || e == self.key_config.shift_up
+                } else if e == self.key_config.borrow().home
+                    || e == self.key_config.borrow().shift_up
                 {
                     Ok(self
                         .move_selection(MoveSelection::Home)
                         .into())
-                } else if e == self.key_config.end
-                    || e == self.key_config.shift_down
+                } else if e == self.key_config.borrow().end
+                    || e == self.key_config.borrow().shift_down
                 {
                     Ok(self.move_selection(MoveSelection::End).into())
-                } else if e == self.key_config.move_left {
+                } else if e == self.key_config.borrow().move_left {
                     Ok(self
                         .move_selection(MoveSelection::Left)
                         .into())
-                } else if e == self.key_config.move_right {
+                } else if e == self.key_config.borrow().move_right {
                     Ok(self
                         .move_selection(MoveSelection::Right)
                         .into())
@@ -468,6 +860,9 @@ mod tests {
             .map(|a| StatusItem {
                 path: String::from(*a),
                 status: StatusItemType::Modified,
+                submodule: None,
+                nested_repo: false,
+                summarized: false,
             })
             .collect::<Vec<_>>()
     }
@@ -553,4 +948,35 @@ mod tests {
 
         assert_eq!(ftc.scroll_top.get(), 0); // should still be at top
     }
+
+    #[test]
+    fn test_filter_keeps_matching_files_only() {
+        let items = string_vec_to_status(&[
+            "a/b/b1", //
+            "a/b/b2", //
+            "c/d1",   //
+        ]);
+
+        let mut ftc = FileTreeComponent::new(
+            "title",
+            true,
+            None,
+            SharedTheme::default(),
+            SharedKeyConfig::default(),
+        );
+        ftc.update(&items)
+            .expect("Updating FileTreeComponent failed");
+
+        ftc.set_filter(String::from("b2")).expect("filter failed");
+
+        assert_eq!(ftc.file_count(), 1);
+        assert_eq!(
+            ftc.selection_file().map(|item| item.path),
+            Some(String::from("a/b/b2"))
+        );
+
+        ftc.set_filter(String::new()).expect("filter failed");
+
+        assert_eq!(ftc.file_count(), 3);
+    }
 }
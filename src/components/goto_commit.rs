@@ -0,0 +1,146 @@
+use super::{
+    textinput::TextInputComponent, visibility_blocking,
+    CommandBlocking, CommandInfo, Component, DrawableComponent,
+    EventState, InputType,
+};
+use crate::{
+    keys::SharedKeyConfig,
+    queue::{InternalEvent, Queue},
+    strings,
+    ui::style::SharedTheme,
+};
+use anyhow::Result;
+use asyncgit::sync::{self, RepoPath};
+use crossterm::event::Event;
+use tui::{backend::Backend, layout::Rect, Frame};
+
+/// asks for a sha, branch or tag to select in the log
+pub struct GotoCommitComponent {
+    repo: RepoPath,
+    input: TextInputComponent,
+    queue: Queue,
+    key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for GotoCommitComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        self.input.draw(f, rect)?;
+
+        Ok(())
+    }
+}
+
+impl Component for GotoCommitComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.is_visible() || force_all {
+            self.input.commands(out, force_all);
+
+            out.push(CommandInfo::new(
+                strings::commands::goto_commit_confirm(
+                    &self.key_config,
+                ),
+                !self.input.get_text().trim().is_empty(),
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<EventState> {
+        if self.is_visible() {
+            if self.input.event(ev)?.is_consumed() {
+                return Ok(EventState::Consumed);
+            }
+
+            if let Event::Key(e) = ev {
+                if e == self.key_config.borrow().enter {
+                    self.goto();
+                }
+
+                return Ok(EventState::Consumed);
+            }
+        }
+        Ok(EventState::NotConsumed)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.input.is_visible()
+    }
+
+    fn hide(&mut self) {
+        self.input.hide()
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.input.show()?;
+
+        Ok(())
+    }
+}
+
+impl GotoCommitComponent {
+    ///
+    pub fn new(
+        repo: RepoPath,
+        queue: Queue,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+    ) -> Self {
+        Self {
+            queue,
+            input: TextInputComponent::new(
+                theme,
+                key_config.clone(),
+                &strings::goto_commit_popup_title(&key_config),
+                &strings::goto_commit_popup_msg(&key_config),
+                true,
+            )
+            .with_input_type(InputType::Singleline),
+            key_config,
+            repo,
+        }
+    }
+
+    ///
+    pub fn open(&mut self) -> Result<()> {
+        self.input.clear();
+        self.show()
+    }
+
+    fn goto(&mut self) {
+        let rev = self.input.get_text().trim().to_string();
+
+        if rev.is_empty() {
+            return;
+        }
+
+        self.input.clear();
+        self.hide();
+
+        match sync::resolve_rev(&self.repo, &rev) {
+            Ok(id) => {
+                self.queue
+                    .borrow_mut()
+                    .push_back(InternalEvent::GotoCommit(id, rev));
+            }
+            Err(e) => {
+                log::error!("goto commit: {}", e);
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::ShowErrorMsg(format!(
+                        "go to commit error:\n{}",
+                        e,
+                    )),
+                );
+            }
+        }
+    }
+}
@@ -1,11 +1,15 @@
 use super::{
-    visibility_blocking, CommandBlocking, CommandInfo, Component,
-    DrawableComponent, EventState,
+    utils::fuzzy_match, visibility_blocking, CommandBlocking,
+    CommandInfo, Component, DrawableComponent, EventState,
+};
+use crate::{
+    keys::{KeyConfig, KeyConflict, SharedKeyConfig},
+    strings, ui,
+    version::Version,
 };
-use crate::{keys::SharedKeyConfig, strings, ui, version::Version};
 use anyhow::Result;
 use asyncgit::hash;
-use crossterm::event::Event;
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
 use itertools::Itertools;
 use std::{borrow::Cow, cmp, convert::TryFrom};
 use tui::{
@@ -21,6 +25,9 @@ use ui::style::SharedTheme;
 ///
 pub struct HelpComponent {
     cmds: Vec<CommandInfo>,
+    conflicts: Vec<KeyConflict>,
+    filter: String,
+    filter_editing: bool,
     visible: bool,
     selection: u16,
     theme: SharedTheme,
@@ -43,9 +50,23 @@ impl DrawableComponent for HelpComponent {
                 ui::centered_rect_absolute(SIZE.0, SIZE.1, f.size());
 
             f.render_widget(Clear, area);
+            let title =
+                if self.filter.is_empty() && !self.filter_editing {
+                    strings::help_title(&self.key_config)
+                } else {
+                    format!(
+                        "{}{}",
+                        strings::help_title(&self.key_config),
+                        strings::file_filter_info(
+                            &self.filter,
+                            self.filter_editing
+                        )
+                    )
+                };
+
             f.render_widget(
                 Block::default()
-                    .title(strings::help_title(&self.key_config))
+                    .title(title)
                     .borders(Borders::ALL)
                     .border_type(BorderType::Thick),
                 area,
@@ -105,6 +126,12 @@ impl Component for HelpComponent {
                 true,
                 true,
             ));
+
+            out.push(CommandInfo::new(
+                strings::commands::help_filter(&self.key_config),
+                true,
+                true,
+            ));
         }
 
         if !self.visible || force_all {
@@ -124,11 +151,20 @@ impl Component for HelpComponent {
     fn event(&mut self, ev: Event) -> Result<EventState> {
         if self.visible {
             if let Event::Key(e) = ev {
-                if e == self.key_config.exit_popup {
-                    self.hide()
-                } else if e == self.key_config.move_down {
+                if self.filter_editing {
+                    self.filter_event(e);
+                } else if e == self.key_config.borrow().exit_popup {
+                    if self.filter.is_empty() {
+                        self.hide()
+                    } else {
+                        self.filter.clear();
+                        self.selection = 0;
+                    }
+                } else if e == self.key_config.borrow().file_filter {
+                    self.filter_editing = true;
+                } else if e == self.key_config.borrow().move_down {
                     self.move_selection(true)
-                } else if e == self.key_config.move_up {
+                } else if e == self.key_config.borrow().move_up {
                     self.move_selection(false)
                 } else {
                 }
@@ -136,7 +172,7 @@ impl Component for HelpComponent {
 
             Ok(EventState::Consumed)
         } else if let Event::Key(k) = ev {
-            if k == self.key_config.open_help {
+            if k == self.key_config.borrow().open_help {
                 self.show()?;
                 Ok(EventState::Consumed)
             } else {
@@ -157,6 +193,12 @@ impl Component for HelpComponent {
 
     fn show(&mut self) -> Result<()> {
         self.visible = true;
+        self.filter.clear();
+        self.filter_editing = false;
+        self.selection = 0;
+        self.conflicts = KeyConfig::get_config_file()
+            .map(|file| self.key_config.borrow().conflicts(&file))
+            .unwrap_or_default();
 
         Ok(())
     }
@@ -169,6 +211,9 @@ impl HelpComponent {
     ) -> Self {
         Self {
             cmds: vec![],
+            conflicts: Vec::new(),
+            filter: String::new(),
+            filter_editing: false,
             visible: false,
             selection: 0,
             theme,
@@ -186,6 +231,37 @@ impl HelpComponent {
         self.cmds.sort_by_key(|e| hash(&e.text.group));
     }
 
+    /// handles keys while the filter is being typed
+    fn filter_event(&mut self, key: KeyEvent) {
+        if key == self.key_config.borrow().exit_popup {
+            self.filter_editing = false;
+            self.filter.clear();
+        } else if key == self.key_config.borrow().enter {
+            self.filter_editing = false;
+            return;
+        } else if key.code == KeyCode::Backspace {
+            self.filter.pop();
+        } else if let KeyCode::Char(c) = key.code {
+            if key.modifiers.contains(KeyModifiers::CONTROL) {
+                return;
+            }
+            self.filter.push(c);
+        } else {
+            return;
+        }
+
+        self.selection = 0;
+    }
+
+    /// commands matching the filter by name, description or group
+    fn filtered_cmds(&self) -> impl Iterator<Item = &CommandInfo> {
+        self.cmds.iter().filter(move |cmd| {
+            fuzzy_match(&self.filter, cmd.text.name.as_str())
+                || fuzzy_match(&self.filter, cmd.text.desc)
+                || fuzzy_match(&self.filter, cmd.text.group)
+        })
+    }
+
     fn move_selection(&mut self, inc: bool) {
         let mut new_selection = self.selection;
 
@@ -196,9 +272,9 @@ impl HelpComponent {
         };
         new_selection = cmp::max(new_selection, 0);
 
-        if let Ok(max) =
-            u16::try_from(self.cmds.len().saturating_sub(1))
-        {
+        if let Ok(max) = u16::try_from(
+            self.filtered_cmds().count().saturating_sub(1),
+        ) {
             self.selection = cmp::min(new_selection, max);
         }
     }
@@ -206,10 +282,33 @@ impl HelpComponent {
     fn get_text(&self) -> Vec<Spans> {
         let mut txt: Vec<Spans> = Vec::new();
 
+        if !self.conflicts.is_empty() {
+            txt.push(Spans::from(Span::styled(
+                Cow::from(strings::help_key_conflicts_title()),
+                self.theme
+                    .borrow()
+                    .text_danger()
+                    .add_modifier(Modifier::REVERSED),
+            )));
+
+            for conflict in &self.conflicts {
+                txt.push(Spans::from(Span::styled(
+                    Cow::from(strings::help_key_conflict(
+                        &self
+                            .key_config
+                            .borrow()
+                            .get_hint(conflict.key),
+                        &conflict.actions,
+                    )),
+                    self.theme.borrow().text_danger(),
+                )));
+            }
+        }
+
         let mut processed = 0_u16;
 
         for (key, group) in
-            &self.cmds.iter().group_by(|e| e.text.group)
+            &self.filtered_cmds().group_by(|e| e.text.group)
         {
             txt.push(Spans::from(Span::styled(
                 Cow::from(key.to_string()),
@@ -227,7 +326,7 @@ impl HelpComponent {
                     } else {
                         format!(" {}", command_info.text.name)
                     }),
-                    self.theme.text(true, is_selected),
+                    self.theme.borrow().text(true, is_selected),
                 )));
 
                 if is_selected {
@@ -236,7 +335,7 @@ impl HelpComponent {
                             "  {}\n",
                             command_info.text.desc
                         )),
-                        self.theme.text(true, is_selected),
+                        self.theme.borrow().text(true, is_selected),
                     )));
                 }
             }
@@ -0,0 +1,396 @@
+use super::{
+    visibility_blocking, CommandBlocking, CommandInfo, Component,
+    DrawableComponent, EventState, ScrollType,
+};
+use crate::{
+    keys::SharedKeyConfig,
+    queue::{InternalEvent, Queue},
+    strings, try_or_popup,
+    ui::{self, calc_scroll_top, style::SharedTheme, Size},
+};
+use anyhow::Result;
+use asyncgit::sync::{self, HookInfo, HookOutput, RepoPath};
+use crossterm::event::Event;
+use std::cell::Cell;
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Rect},
+    text::{Span, Spans, Text},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph, Wrap},
+    Frame,
+};
+use unicode_truncate::UnicodeTruncateStr;
+
+/// lists the installed hooks to turn them on or off for gitui and to
+/// run them by hand
+pub struct HooksComponent {
+    repo: RepoPath,
+    hooks: Vec<HookInfo>,
+    /// name and output of the hook run last
+    output: Option<(String, HookOutput)>,
+    visible: bool,
+    selection: usize,
+    scroll_top: Cell<usize>,
+    current_height: Cell<usize>,
+    queue: Queue,
+    theme: SharedTheme,
+    key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for HooksComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        if self.visible {
+            const PERCENT_SIZE: Size = Size::new(80, 60);
+            const MIN_SIZE: Size = Size::new(60, 20);
+
+            let area = ui::centered_rect(
+                PERCENT_SIZE.width,
+                PERCENT_SIZE.height,
+                f.size(),
+            );
+            let area =
+                ui::rect_inside(MIN_SIZE, f.size().into(), area);
+            let area = area.intersection(rect);
+
+            f.render_widget(Clear, area);
+
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints(
+                    if self.output.is_some() {
+                        [
+                            Constraint::Percentage(40),
+                            Constraint::Percentage(60),
+                        ]
+                    } else {
+                        [
+                            Constraint::Percentage(100),
+                            Constraint::Length(0),
+                        ]
+                    }
+                    .as_ref(),
+                )
+                .split(area);
+
+            self.draw_list(f, chunks[0]);
+
+            if let Some((name, output)) = &self.output {
+                self.draw_output(f, chunks[1], name, output);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Component for HooksComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.visible || force_all {
+            out.clear();
+
+            out.push(CommandInfo::new(
+                strings::commands::scroll(&self.key_config),
+                true,
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::close_popup(&self.key_config),
+                true,
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::hooks_toggle(&self.key_config),
+                !self.hooks.is_empty(),
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::hooks_run(&self.key_config),
+                !self.hooks.is_empty(),
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<EventState> {
+        if self.visible {
+            if let Event::Key(e) = ev {
+                if e == self.key_config.borrow().exit_popup {
+                    self.hide();
+                } else if e == self.key_config.borrow().move_down {
+                    self.move_selection(ScrollType::Down);
+                } else if e == self.key_config.borrow().move_up {
+                    self.move_selection(ScrollType::Up);
+                } else if e == self.key_config.borrow().page_down {
+                    self.move_selection(ScrollType::PageDown);
+                } else if e == self.key_config.borrow().page_up {
+                    self.move_selection(ScrollType::PageUp);
+                } else if e == self.key_config.borrow().home {
+                    self.move_selection(ScrollType::Home);
+                } else if e == self.key_config.borrow().end {
+                    self.move_selection(ScrollType::End);
+                } else if e == self.key_config.borrow().file_mark {
+                    try_or_popup!(
+                        self,
+                        "hooks:",
+                        self.toggle_selected()
+                    );
+                } else if e == self.key_config.borrow().enter {
+                    try_or_popup!(
+                        self,
+                        "run hook:",
+                        self.run_selected()
+                    );
+                }
+            }
+
+            Ok(EventState::Consumed)
+        } else {
+            Ok(EventState::NotConsumed)
+        }
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn hide(&mut self) {
+        self.visible = false
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+
+        Ok(())
+    }
+}
+
+impl HooksComponent {
+    ///
+    pub fn new(
+        repo: RepoPath,
+        queue: Queue,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+    ) -> Self {
+        Self {
+            hooks: Vec::new(),
+            output: None,
+            visible: false,
+            selection: 0,
+            scroll_top: Cell::new(0),
+            current_height: Cell::new(0),
+            queue,
+            theme,
+            key_config,
+            repo,
+        }
+    }
+
+    ///
+    pub fn open(&mut self) -> Result<()> {
+        self.output = None;
+        self.selection = 0;
+        self.update_hooks()?;
+        self.show()
+    }
+
+    fn update_hooks(&mut self) -> Result<()> {
+        self.hooks = sync::get_hooks(&self.repo)?;
+        self.selection =
+            self.selection.min(self.hooks.len().saturating_sub(1));
+
+        Ok(())
+    }
+
+    fn toggle_selected(&mut self) -> Result<()> {
+        if let Some(hook) = self.hooks.get(self.selection) {
+            sync::set_hook_enabled(
+                &self.repo,
+                &hook.name,
+                !hook.enabled,
+            )?;
+            self.update_hooks()?;
+        }
+
+        Ok(())
+    }
+
+    fn run_selected(&mut self) -> Result<()> {
+        if let Some(hook) = self.hooks.get(self.selection) {
+            let output =
+                sync::run_hook_manually(&self.repo, &hook.path)?;
+            self.output = Some((hook.name.clone(), output));
+        }
+
+        Ok(())
+    }
+
+    fn move_selection(&mut self, scroll: ScrollType) {
+        let page = self.current_height.get().saturating_sub(1);
+        let max = self.hooks.len().saturating_sub(1);
+
+        let new_selection = match scroll {
+            ScrollType::Up => self.selection.saturating_sub(1),
+            ScrollType::Down => self.selection.saturating_add(1),
+            ScrollType::PageUp => self.selection.saturating_sub(page),
+            ScrollType::PageDown => {
+                self.selection.saturating_add(page)
+            }
+            ScrollType::Home => 0,
+            ScrollType::End => max,
+        };
+
+        self.selection = new_selection.min(max);
+    }
+
+    fn draw_list<B: Backend>(&self, f: &mut Frame<B>, area: Rect) {
+        let height = usize::from(area.height.saturating_sub(2));
+        self.current_height.set(height);
+
+        self.scroll_top.set(calc_scroll_top(
+            self.scroll_top.get(),
+            height,
+            self.selection,
+        ));
+
+        f.render_widget(
+            Paragraph::new(
+                self.get_text(area.width.saturating_sub(2), height),
+            )
+            .block(
+                Block::default()
+                    .title(Span::styled(
+                        strings::title_hooks(),
+                        self.theme.borrow().title(true),
+                    ))
+                    .border_type(BorderType::Thick)
+                    .borders(Borders::ALL)
+                    .border_style(self.theme.borrow().block(true)),
+            ),
+            area,
+        );
+
+        ui::draw_scrollbar(
+            f,
+            area,
+            &self.theme,
+            self.hooks.len().saturating_sub(height),
+            self.scroll_top.get(),
+        );
+    }
+
+    /// the end of long outputs is shown, that is where the reason a
+    /// hook failed usually is
+    fn draw_output<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        area: Rect,
+        name: &str,
+        output: &HookOutput,
+    ) {
+        let height = usize::from(area.height.saturating_sub(2));
+        let lines: Vec<&str> = output.output.lines().collect();
+        let text = lines
+            .iter()
+            .skip(lines.len().saturating_sub(height))
+            .map(|line| Spans::from(Span::raw(*line)))
+            .collect::<Vec<_>>();
+
+        f.render_widget(
+            Paragraph::new(text)
+                .block(
+                    Block::default()
+                        .title(Span::styled(
+                            strings::hook_output_title(
+                                name,
+                                output.success,
+                            ),
+                            if output.success {
+                                self.theme.borrow().title(true)
+                            } else {
+                                self.theme.borrow().text_danger()
+                            },
+                        ))
+                        .border_type(BorderType::Thick)
+                        .borders(Borders::ALL)
+                        .border_style(
+                            self.theme.borrow().block(true),
+                        ),
+                )
+                .wrap(Wrap { trim: false }),
+            area,
+        );
+    }
+
+    fn get_text(&self, width: u16, height: usize) -> Text {
+        const MARKER_LENGTH: usize = 2;
+
+        let width = usize::from(width);
+        let name_length = width * 30 / 100;
+        let source_length = width * 20 / 100;
+        let state_length = width
+            .saturating_sub(MARKER_LENGTH)
+            .saturating_sub(name_length + 1)
+            .saturating_sub(source_length + 1);
+
+        let lines = self
+            .hooks
+            .iter()
+            .enumerate()
+            .skip(self.scroll_top.get())
+            .take(height)
+            .map(|(idx, hook)| {
+                let selected = idx == self.selection;
+                let runs =
+                    hook.active && hook.executable && hook.enabled;
+
+                Spans::from(vec![
+                    Span::styled(
+                        if hook.enabled { "\u{221a} " } else { "  " }, //√
+                        self.theme.borrow().commit_hash(selected),
+                    ),
+                    Span::styled(
+                        format!(
+                            "{:w$} ",
+                            hook.name.unicode_truncate(name_length).0,
+                            w = name_length
+                        ),
+                        self.theme.borrow().text(runs, selected),
+                    ),
+                    Span::styled(
+                        format!(
+                            "{:w$} ",
+                            strings::hook_source(hook.source)
+                                .unicode_truncate(source_length)
+                                .0,
+                            w = source_length
+                        ),
+                        self.theme.borrow().commit_hash(selected),
+                    ),
+                    Span::styled(
+                        strings::hook_state(hook)
+                            .unicode_truncate(state_length)
+                            .0
+                            .to_string(),
+                        self.theme.borrow().text(runs, selected),
+                    ),
+                ])
+            })
+            .collect::<Vec<_>>();
+
+        Text::from(lines)
+    }
+}
@@ -0,0 +1,218 @@
+use super::{
+    visibility_blocking, CommandBlocking, CommandInfo, Component,
+    DrawableComponent, EventState,
+};
+use crate::{
+    keys::SharedKeyConfig,
+    queue::{InternalEvent, NeedsUpdate, Queue},
+    strings,
+    ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::sync::{self, IgnoreKind, RepoPath};
+use crossterm::event::Event;
+use std::convert::TryFrom;
+use tui::{
+    backend::Backend, layout::Rect, text::Span, widgets::Clear, Frame,
+};
+
+/// lets the user choose which pattern of a path to add to the root
+/// `.gitignore`
+pub struct IgnorePathComponent {
+    repo: RepoPath,
+    patterns: Vec<String>,
+    selection: usize,
+    visible: bool,
+    queue: Queue,
+    theme: SharedTheme,
+    key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for IgnorePathComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        if self.visible {
+            const WIDTH: u16 = 50;
+
+            let height = u16::try_from(self.patterns.len())
+                .unwrap_or(u16::MAX)
+                .saturating_add(2);
+
+            let area = ui::centered_rect_absolute(
+                WIDTH.min(rect.width),
+                height.min(rect.height),
+                rect,
+            );
+
+            let items = self.patterns.iter().enumerate().map(
+                |(idx, pattern)| {
+                    Span::styled(
+                        pattern.as_str(),
+                        self.theme
+                            .borrow()
+                            .text(true, idx == self.selection),
+                    )
+                },
+            );
+
+            f.render_widget(Clear, area);
+            ui::draw_list(
+                f,
+                area,
+                &strings::ignore_path_popup_title(&self.key_config),
+                items,
+                true,
+                &self.theme,
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl Component for IgnorePathComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.visible || force_all {
+            out.clear();
+
+            out.push(CommandInfo::new(
+                strings::commands::scroll(&self.key_config),
+                true,
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::close_popup(&self.key_config),
+                true,
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::ignore_path_confirm(
+                    &self.key_config,
+                ),
+                true,
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<EventState> {
+        if self.visible {
+            if let Event::Key(e) = ev {
+                if e == self.key_config.borrow().exit_popup {
+                    self.hide();
+                } else if e == self.key_config.borrow().move_down {
+                    self.selection = self
+                        .selection
+                        .saturating_add(1)
+                        .min(self.patterns.len().saturating_sub(1));
+                } else if e == self.key_config.borrow().move_up {
+                    self.selection = self.selection.saturating_sub(1);
+                } else if e == self.key_config.borrow().enter {
+                    self.hide();
+                    self.add_selected();
+                }
+            }
+
+            return Ok(EventState::Consumed);
+        }
+
+        Ok(EventState::NotConsumed)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+
+        Ok(())
+    }
+}
+
+impl IgnorePathComponent {
+    ///
+    pub fn new(
+        repo: RepoPath,
+        queue: Queue,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+    ) -> Self {
+        Self {
+            repo,
+            patterns: Vec::new(),
+            selection: 0,
+            visible: false,
+            queue,
+            theme,
+            key_config,
+        }
+    }
+
+    /// offers the file itself, its extension and its folder
+    pub fn open(
+        &mut self,
+        path: &str,
+        is_folder: bool,
+    ) -> Result<()> {
+        let path = if is_folder {
+            format!("{}/", path)
+        } else {
+            path.to_string()
+        };
+
+        self.patterns = [
+            IgnoreKind::File,
+            IgnoreKind::Extension,
+            IgnoreKind::Directory,
+        ]
+        .iter()
+        .filter_map(|kind| sync::ignore_pattern(&path, *kind))
+        .fold(Vec::new(), |mut patterns, pattern| {
+            // a folder is its own directory
+            if !patterns.contains(&pattern) {
+                patterns.push(pattern);
+            }
+            patterns
+        });
+        self.selection = 0;
+
+        if self.patterns.is_empty() {
+            return Ok(());
+        }
+
+        self.show()
+    }
+
+    fn add_selected(&mut self) {
+        if let Some(pattern) = self.patterns.get(self.selection) {
+            if let Err(e) = sync::add_to_ignore(&self.repo, pattern) {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::ShowErrorMsg(format!(
+                        "ignore error:\n{}\npattern:\n{:?}",
+                        e, pattern
+                    )),
+                );
+            } else {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::Update(NeedsUpdate::ALL),
+                );
+            }
+        }
+    }
+}
@@ -6,13 +6,14 @@ use super::{
 use crate::{
     accessors,
     keys::SharedKeyConfig,
+    options::SharedOptions,
     queue::{InternalEvent, Queue},
     strings,
     ui::style::SharedTheme,
 };
 use anyhow::Result;
 use asyncgit::{
-    sync::{CommitId, CommitTags},
+    sync::{CommitId, CommitTags, RepoPath},
     AsyncDiff, AsyncNotification, DiffParams, DiffType,
 };
 use crossbeam_channel::Sender;
@@ -33,6 +34,7 @@ pub struct InspectCommitComponent {
     git_diff: AsyncDiff,
     visible: bool,
     key_config: SharedKeyConfig,
+    options: SharedOptions,
 }
 
 impl DrawableComponent for InspectCommitComponent {
@@ -110,6 +112,12 @@ impl Component for InspectCommitComponent {
                 true,
                 true,
             ));
+
+            out.push(CommandInfo::new(
+                strings::commands::inspect_archive(&self.key_config),
+                true,
+                true,
+            ));
         }
 
         visibility_blocking(self)
@@ -124,26 +132,36 @@ impl Component for InspectCommitComponent {
             }
 
             if let Event::Key(e) = ev {
-                if e == self.key_config.exit_popup {
+                if e == self.key_config.borrow().exit_popup {
                     self.hide();
-                } else if e == self.key_config.focus_right
+                } else if e == self.key_config.borrow().focus_right
                     && self.can_focus_diff()
                 {
                     self.details.focus(false);
                     self.diff.focus(true);
-                } else if e == self.key_config.focus_left
+                } else if e == self.key_config.borrow().focus_left
                     && self.diff.focused()
                 {
                     self.details.focus(true);
                     self.diff.focus(false);
-                } else if e == self.key_config.open_file_tree {
+                } else if e == self.key_config.borrow().open_file_tree
+                {
                     if let Some(commit) = self.commit_id {
                         self.queue.borrow_mut().push_back(
                             InternalEvent::OpenFileTree(commit),
                         );
                         self.hide();
                     }
-                } else if e == self.key_config.focus_left {
+                } else if e
+                    == self.key_config.borrow().inspect_archive
+                {
+                    if let Some(commit) = self.commit_id {
+                        self.queue.borrow_mut().push_back(
+                            InternalEvent::OpenArchiveCommit(commit),
+                        );
+                        self.hide();
+                    }
+                } else if e == self.key_config.borrow().focus_left {
                     self.hide();
                 }
 
@@ -175,30 +193,37 @@ impl InspectCommitComponent {
 
     ///
     pub fn new(
+        repo: &RepoPath,
         queue: &Queue,
         sender: &Sender<AsyncNotification>,
         theme: SharedTheme,
         key_config: SharedKeyConfig,
+        options: SharedOptions,
     ) -> Self {
         Self {
             queue: queue.clone(),
             details: CommitDetailsComponent::new(
+                repo.clone(),
                 queue,
                 sender,
                 theme.clone(),
                 key_config.clone(),
             ),
             diff: DiffComponent::new(
+                repo.clone(),
                 queue.clone(),
+                sender,
                 theme,
                 key_config.clone(),
+                options.clone(),
                 true,
             ),
             commit_id: None,
             tags: None,
-            git_diff: AsyncDiff::new(sender),
+            git_diff: AsyncDiff::new(repo.clone(), sender),
             visible: false,
             key_config,
+            options,
         }
     }
 
@@ -217,7 +242,9 @@ impl InspectCommitComponent {
 
     ///
     pub fn any_work_pending(&self) -> bool {
-        self.git_diff.is_pending() || self.details.any_work_pending()
+        self.git_diff.is_pending()
+            || self.details.any_work_pending()
+            || self.diff.any_work_pending()
     }
 
     ///
@@ -245,6 +272,7 @@ impl InspectCommitComponent {
                     let diff_params = DiffParams {
                         path: f.path.clone(),
                         diff_type: DiffType::Commit(id),
+                        options: self.options.borrow().diff_options(),
                     };
 
                     if let Some((params, last)) =
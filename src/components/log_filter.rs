@@ -0,0 +1,234 @@
+use super::{
+    textinput::TextInputComponent, visibility_blocking,
+    CommandBlocking, CommandInfo, Component, DrawableComponent,
+    EventState, InputType,
+};
+use crate::{
+    keys::SharedKeyConfig,
+    queue::{InternalEvent, Queue},
+    strings,
+    ui::style::SharedTheme,
+};
+use anyhow::{anyhow, bail, Result};
+use asyncgit::sync::{self, LogFilter, RepoPath};
+use chrono::{Local, NaiveDate, TimeZone};
+use crossterm::event::Event;
+use tui::{backend::Backend, layout::Rect, Frame};
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+/// asks for the author, path and date range the log gets filtered by
+/// and the revision it starts at
+pub struct LogFilterComponent {
+    repo: RepoPath,
+    input: TextInputComponent,
+    queue: Queue,
+    key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for LogFilterComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        self.input.draw(f, rect)?;
+
+        Ok(())
+    }
+}
+
+impl Component for LogFilterComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.is_visible() || force_all {
+            self.input.commands(out, force_all);
+
+            out.push(CommandInfo::new(
+                strings::commands::log_filter_confirm(
+                    &self.key_config,
+                ),
+                true,
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<EventState> {
+        if self.is_visible() {
+            if self.input.event(ev)?.is_consumed() {
+                return Ok(EventState::Consumed);
+            }
+
+            if let Event::Key(e) = ev {
+                if e == self.key_config.borrow().enter {
+                    self.apply();
+                }
+
+                return Ok(EventState::Consumed);
+            }
+        }
+        Ok(EventState::NotConsumed)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.input.is_visible()
+    }
+
+    fn hide(&mut self) {
+        self.input.hide()
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.input.show()?;
+
+        Ok(())
+    }
+}
+
+impl LogFilterComponent {
+    ///
+    pub fn new(
+        repo: RepoPath,
+        queue: Queue,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+    ) -> Self {
+        Self {
+            queue,
+            input: TextInputComponent::new(
+                theme,
+                key_config.clone(),
+                &strings::log_filter_popup_title(&key_config),
+                &strings::log_filter_popup_msg(&key_config),
+                true,
+            )
+            .with_input_type(InputType::Singleline),
+            key_config,
+            repo,
+        }
+    }
+
+    /// prefills the input with the filter currently used
+    pub fn open(&mut self, current: &str) -> Result<()> {
+        self.input.set_text(current.to_string());
+        self.show()
+    }
+
+    /// an empty input shows the whole log again, an invalid one
+    /// stays to be fixed
+    fn apply(&mut self) {
+        let query = self.input.get_text().trim().to_string();
+
+        let filter = parse_filter(&query).and_then(|filter| {
+            if let Some(rev) = &filter.rev {
+                sync::resolve_rev(&self.repo, rev)
+                    .map_err(|e| anyhow!("`{}`: {}", rev, e))?;
+            }
+            Ok(filter)
+        });
+
+        match filter {
+            Ok(filter) => {
+                self.hide();
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::FilterLog(query, filter),
+                );
+            }
+            Err(e) => {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::ShowErrorMsg(format!(
+                        "log filter error:\n{}",
+                        e,
+                    )),
+                );
+            }
+        }
+    }
+}
+
+/// reads `author:jane path:src/ since:2021-01-31 until:2021-02-28`
+/// (and `rev:origin/main` to start there instead of `HEAD`,
+/// `refs:release/*` to also walk the matching branches and tags),
+/// dates are local days and both ends are included
+fn parse_filter(query: &str) -> Result<LogFilter> {
+    let mut filter = LogFilter::default();
+
+    for word in query.split_whitespace() {
+        let mut parts = word.splitn(2, ':');
+        let key = parts.next().unwrap_or_default();
+        let value = parts
+            .next()
+            .filter(|value| !value.is_empty())
+            .ok_or_else(|| {
+                anyhow!("expected `key:value` instead of `{}`", word)
+            })?;
+
+        match key {
+            "author" => filter.author = Some(value.to_string()),
+            "path" => filter.path = Some(value.to_string()),
+            "rev" => filter.rev = Some(value.to_string()),
+            "refs" => filter.refs = Some(value.to_string()),
+            "since" => filter.since = Some(day_start(value)?),
+            "until" => {
+                filter.until =
+                    Some(day_start(value)? + SECONDS_PER_DAY - 1);
+            }
+            _ => bail!("unknown filter `{}`", key),
+        }
+    }
+
+    Ok(filter)
+}
+
+/// seconds since epoch of the local midnight starting `YYYY-MM-DD`
+fn day_start(date: &str) -> Result<i64> {
+    let day = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| anyhow!("`{}` is no YYYY-MM-DD date", date))?;
+
+    Local
+        .from_local_datetime(&day.and_hms(0, 0, 0))
+        .earliest()
+        .map(|time| time.timestamp())
+        .ok_or_else(|| anyhow!("`{}` has no local midnight", date))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_filter() {
+        let filter = parse_filter(
+            "author:jane path:src/app.rs since:2021-03-01 until:2021-03-01",
+        )
+        .unwrap();
+
+        assert_eq!(filter.author.as_deref(), Some("jane"));
+        assert_eq!(filter.path.as_deref(), Some("src/app.rs"));
+        assert_eq!(
+            filter.until.unwrap() - filter.since.unwrap(),
+            SECONDS_PER_DAY - 1
+        );
+
+        assert_eq!(
+            parse_filter("rev:origin/main").unwrap().rev.as_deref(),
+            Some("origin/main")
+        );
+        assert_eq!(
+            parse_filter("refs:release/*").unwrap().refs.as_deref(),
+            Some("release/*")
+        );
+
+        assert!(parse_filter("").unwrap().is_empty());
+        assert!(parse_filter("jane").is_err());
+        assert!(parse_filter("author:").is_err());
+        assert!(parse_filter("since:yesterday").is_err());
+        assert!(parse_filter("committer:jane").is_err());
+    }
+}
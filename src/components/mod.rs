@@ -1,52 +1,94 @@
+mod apply_patch;
+mod archive_commit;
 mod blame_file;
 mod branchlist;
 mod changes;
+mod checkout_strategy;
 mod command;
+mod command_palette;
 mod commit;
 mod commit_details;
 mod commitlist;
+mod context_menu;
 mod create_branch;
 mod cred;
 mod diff;
+mod diff_base;
+mod export_patch;
 mod externaleditor;
+mod fetch_options;
 mod filetree;
+mod goto_commit;
 mod help;
+mod hooks;
+mod ignore_path;
 mod inspect_commit;
+mod log_filter;
 mod msg;
+mod open_link;
 mod pull;
 mod push;
+mod push_remote;
 mod push_tags;
+mod rebase;
+mod rebase_preview;
 mod rename_branch;
+mod repo_switcher;
 mod reset;
+mod reset_commit;
+mod review;
 mod revision_files;
 mod stashmsg;
 mod tag_commit;
+mod taglist;
 mod textinput;
+mod upstream_branch;
 mod utils;
 
+pub use apply_patch::ApplyPatchComponent;
+pub use archive_commit::ArchiveCommitComponent;
 pub use blame_file::BlameFileComponent;
 pub use branchlist::BranchListComponent;
 pub use changes::ChangesComponent;
+pub use checkout_strategy::CheckoutStrategyComponent;
 pub use command::{CommandInfo, CommandText};
+pub use command_palette::CommandPaletteComponent;
 pub use commit::CommitComponent;
 pub use commit_details::CommitDetailsComponent;
 pub use commitlist::CommitList;
+pub use context_menu::{open_context_menu, ContextMenuComponent};
 pub use create_branch::CreateBranchComponent;
 pub use diff::DiffComponent;
+pub use diff_base::DiffBaseComponent;
+pub use export_patch::ExportPatchComponent;
 pub use externaleditor::ExternalEditorComponent;
+pub use fetch_options::FetchOptionsComponent;
 pub use filetree::FileTreeComponent;
+pub use goto_commit::GotoCommitComponent;
 pub use help::HelpComponent;
+pub use hooks::HooksComponent;
+pub use ignore_path::IgnorePathComponent;
 pub use inspect_commit::InspectCommitComponent;
+pub use log_filter::LogFilterComponent;
 pub use msg::MsgComponent;
+pub use open_link::OpenLinkComponent;
 pub use pull::PullComponent;
 pub use push::PushComponent;
+pub use push_remote::PushRemoteComponent;
 pub use push_tags::PushTagsComponent;
+pub use rebase::RebaseComponent;
+pub use rebase_preview::RebasePreviewComponent;
 pub use rename_branch::RenameBranchComponent;
+pub use repo_switcher::RepoSwitcherComponent;
 pub use reset::ResetComponent;
+pub use reset_commit::ResetCommitComponent;
+pub use review::ReviewComponent;
 pub use revision_files::RevisionFilesComponent;
 pub use stashmsg::StashMsgComponent;
 pub use tag_commit::TagCommitComponent;
+pub use taglist::TagListComponent;
 pub use textinput::{InputType, TextInputComponent};
+pub use upstream_branch::UpstreamBranchComponent;
 pub use utils::filetree::FileTreeItemKind;
 
 use crate::ui::style::Theme;
@@ -60,7 +60,7 @@ impl DrawableComponent for MsgComponent {
                     Block::default()
                         .title(Span::styled(
                             self.title.as_str(),
-                            self.theme.text_danger(),
+                            self.theme.borrow().text_danger(),
                         ))
                         .borders(Borders::ALL)
                         .border_type(BorderType::Thick),
@@ -92,7 +92,7 @@ impl Component for MsgComponent {
     fn event(&mut self, ev: Event) -> Result<EventState> {
         if self.visible {
             if let Event::Key(e) = ev {
-                if e == self.key_config.enter {
+                if e == self.key_config.borrow().enter {
                     self.hide();
                 }
             }
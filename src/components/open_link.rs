@@ -0,0 +1,203 @@
+use super::{
+    visibility_blocking, CommandBlocking, CommandInfo, Component,
+    DrawableComponent, EventState,
+};
+use crate::{
+    keys::SharedKeyConfig,
+    links::{self, Link},
+    queue::{InternalEvent, Queue},
+    strings,
+    ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use crossterm::event::Event;
+use std::convert::TryFrom;
+use tui::{
+    backend::Backend, layout::Rect, text::Span, widgets::Clear, Frame,
+};
+
+/// lets the user choose one of the links found in a commit message
+/// or diff to open in the browser
+pub struct OpenLinkComponent {
+    links: Vec<Link>,
+    selection: usize,
+    visible: bool,
+    queue: Queue,
+    theme: SharedTheme,
+    key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for OpenLinkComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        if self.visible {
+            const WIDTH: u16 = 70;
+
+            let height = u16::try_from(self.links.len())
+                .unwrap_or(u16::MAX)
+                .saturating_add(2);
+
+            let area = ui::centered_rect_absolute(
+                WIDTH.min(rect.width),
+                height.min(rect.height),
+                rect,
+            );
+
+            let items =
+                self.links.iter().enumerate().map(|(idx, link)| {
+                    let text = if link.text == link.url {
+                        link.url.clone()
+                    } else {
+                        format!("{} ({})", link.text, link.url)
+                    };
+
+                    Span::styled(
+                        text,
+                        self.theme
+                            .borrow()
+                            .text(true, idx == self.selection),
+                    )
+                });
+
+            f.render_widget(Clear, area);
+            ui::draw_list(
+                f,
+                area,
+                &strings::open_link_popup_title(&self.key_config),
+                items,
+                true,
+                &self.theme,
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl Component for OpenLinkComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.visible || force_all {
+            out.clear();
+
+            out.push(CommandInfo::new(
+                strings::commands::scroll(&self.key_config),
+                true,
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::close_popup(&self.key_config),
+                true,
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::open_link_confirm(
+                    &self.key_config,
+                ),
+                true,
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<EventState> {
+        if self.visible {
+            if let Event::Key(e) = ev {
+                if e == self.key_config.borrow().exit_popup {
+                    self.hide();
+                } else if e == self.key_config.borrow().move_down {
+                    self.selection = self
+                        .selection
+                        .saturating_add(1)
+                        .min(self.links.len().saturating_sub(1));
+                } else if e == self.key_config.borrow().move_up {
+                    self.selection = self.selection.saturating_sub(1);
+                } else if e == self.key_config.borrow().enter {
+                    self.hide();
+                    self.open_selected();
+                }
+            }
+
+            return Ok(EventState::Consumed);
+        }
+
+        Ok(EventState::NotConsumed)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+
+        Ok(())
+    }
+}
+
+impl OpenLinkComponent {
+    ///
+    pub fn new(
+        queue: Queue,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+    ) -> Self {
+        Self {
+            links: Vec::new(),
+            selection: 0,
+            visible: false,
+            queue,
+            theme,
+            key_config,
+        }
+    }
+
+    /// lists the links of `text`, issue references are turned into
+    /// urls by `issue_url_template`
+    pub fn open(
+        &mut self,
+        text: &str,
+        issue_url_template: Option<&str>,
+    ) -> Result<()> {
+        self.links = links::find_links(text, issue_url_template);
+        self.selection = 0;
+
+        if self.links.is_empty() {
+            self.queue.borrow_mut().push_back(
+                InternalEvent::ShowErrorMsg(strings::no_links_msg(
+                    &self.key_config,
+                )),
+            );
+            return Ok(());
+        }
+
+        self.show()
+    }
+
+    fn open_selected(&mut self) {
+        if let Some(link) = self.links.get(self.selection) {
+            if let Err(e) = links::open_url(&link.url) {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::ShowErrorMsg(format!(
+                        "open link error:\n{}",
+                        e
+                    )),
+                );
+            }
+        }
+    }
+}
@@ -5,7 +5,8 @@ use crate::{
         CommandInfo, Component, DrawableComponent, EventState,
     },
     keys::SharedKeyConfig,
-    queue::{Action, InternalEvent, Queue},
+    options::SharedOptions,
+    queue::{Action, InternalEvent, NeedsUpdate, Queue},
     strings, try_or_popup,
     ui::{self, style::SharedTheme},
 };
@@ -17,9 +18,9 @@ use asyncgit::{
             extract_username_password, need_username_password,
             BasicAuthCredential,
         },
-        get_default_remote,
+        get_default_remote, FetchDepth, RepoPath,
     },
-    AsyncFetch, AsyncNotification, FetchRequest, RemoteProgress, CWD,
+    AsyncFetch, AsyncNotification, FetchRequest, RemoteProgress,
 };
 use crossbeam_channel::Sender;
 use crossterm::event::Event;
@@ -33,31 +34,43 @@ use tui::{
 
 ///
 pub struct PullComponent {
+    repo: RepoPath,
     visible: bool,
     git_fetch: AsyncFetch,
     progress: Option<RemoteProgress>,
     pending: bool,
     branch: String,
+    /// fetching all branches instead of only `branch`
+    all_branches: bool,
+    /// merging the fetched `branch` afterwards
+    merge: bool,
+    depth: FetchDepth,
     queue: Queue,
     theme: SharedTheme,
     key_config: SharedKeyConfig,
+    options: SharedOptions,
     input_cred: CredComponent,
 }
 
 impl PullComponent {
     ///
     pub fn new(
+        repo: RepoPath,
         queue: &Queue,
         sender: &Sender<AsyncNotification>,
         theme: SharedTheme,
         key_config: SharedKeyConfig,
+        options: SharedOptions,
     ) -> Self {
         Self {
             queue: queue.clone(),
             pending: false,
             visible: false,
             branch: String::new(),
-            git_fetch: AsyncFetch::new(sender),
+            all_branches: false,
+            merge: false,
+            depth: FetchDepth::Full,
+            git_fetch: AsyncFetch::new(repo.clone(), sender),
             progress: None,
             input_cred: CredComponent::new(
                 theme.clone(),
@@ -65,16 +78,49 @@ impl PullComponent {
             ),
             theme,
             key_config,
+            options,
+            repo,
         }
     }
 
     ///
     pub fn fetch(&mut self, branch: String) -> Result<()> {
         self.branch = branch;
+        self.all_branches = false;
+        self.merge = true;
+        self.depth = FetchDepth::Full;
+        self.start()
+    }
+
+    /// fetches all branches of the default remote to update the
+    /// remote branches, nothing gets merged
+    pub fn fetch_all_branches(&mut self) -> Result<()> {
+        self.branch = String::new();
+        self.all_branches = true;
+        self.merge = false;
+        self.depth = FetchDepth::Full;
+        self.start()
+    }
+
+    /// fetches `branch` limited to (or extended to) `depth`,
+    /// nothing gets merged
+    pub fn fetch_depth(
+        &mut self,
+        branch: String,
+        depth: FetchDepth,
+    ) -> Result<()> {
+        self.branch = branch;
+        self.all_branches = false;
+        self.merge = false;
+        self.depth = depth;
+        self.start()
+    }
+
+    fn start(&mut self) -> Result<()> {
         self.show()?;
-        if need_username_password()? {
-            let cred =
-                extract_username_password().unwrap_or_else(|_| {
+        if need_username_password(&self.repo)? {
+            let cred = extract_username_password(&self.repo)
+                .unwrap_or_else(|_| {
                     BasicAuthCredential::new(None, None)
                 });
             if cred.is_complete() {
@@ -95,8 +141,10 @@ impl PullComponent {
         self.pending = true;
         self.progress = None;
         self.git_fetch.request(FetchRequest {
-            remote: get_default_remote(CWD)?,
+            remote: get_default_remote(&self.repo)?,
             branch: self.branch.clone(),
+            all_branches: self.all_branches,
+            depth: self.depth,
             basic_credential: cred,
         })?;
 
@@ -131,7 +179,14 @@ impl PullComponent {
             if let Some((_bytes, err)) =
                 self.git_fetch.last_result()?
             {
-                if err.is_empty() {
+                if err.is_empty() && !self.merge {
+                    self.hide();
+                    self.queue.borrow_mut().push_back(
+                        InternalEvent::Update(
+                            NeedsUpdate::ALL | NeedsUpdate::BRANCHES,
+                        ),
+                    );
+                } else if err.is_empty() {
                     self.try_ff_merge()?;
                 } else {
                     self.pending = false;
@@ -152,11 +207,17 @@ impl PullComponent {
     // check if something is incoming and try a ff merge then
     fn try_ff_merge(&mut self) -> Result<()> {
         let branch_compare =
-            sync::branch_compare_upstream(CWD, &self.branch)?;
+            sync::branch_compare_upstream(&self.repo, &self.branch)?;
         if branch_compare.behind > 0 {
-            let ff_res = sync::branch_merge_upstream_fastforward(
-                CWD,
-                &self.branch,
+            let ff_res = sync::autostash(
+                &self.repo,
+                self.options.borrow().autostash(),
+                || {
+                    sync::branch_merge_upstream_fastforward(
+                        &self.repo,
+                        &self.branch,
+                    )
+                },
             );
             if let Err(err) = ff_res {
                 log::trace!("ff failed: {}", err);
@@ -170,17 +231,29 @@ impl PullComponent {
     }
 
     pub fn try_conflict_free_merge(&self, rebase: bool) {
+        let autostash = self.options.borrow().autostash();
+
         if rebase {
             try_or_popup!(
                 self,
                 "rebase failed:",
-                sync::merge_upstream_rebase(CWD, &self.branch)
+                sync::autostash(&self.repo, autostash, || {
+                    sync::merge_upstream_rebase(
+                        &self.repo,
+                        &self.branch,
+                    )
+                })
             );
         } else {
             try_or_popup!(
                 self,
                 "merge failed:",
-                sync::merge_upstream_commit(CWD, &self.branch)
+                sync::autostash(&self.repo, autostash, || {
+                    sync::merge_upstream_commit(
+                        &self.repo,
+                        &self.branch,
+                    )
+                })
             );
         }
     }
@@ -189,8 +262,13 @@ impl PullComponent {
         self.queue.borrow_mut().push_back(
             InternalEvent::ConfirmAction(Action::PullMerge {
                 incoming,
-                rebase: sync::config_is_pull_rebase(CWD)
+                rebase: sync::config_is_pull_rebase(&self.repo)
                     .unwrap_or_default(),
+                stat: sync::merge_diffstat(
+                    &self.repo,
+                    &format!("{}@{{upstream}}", self.branch),
+                )
+                .ok(),
             }),
         );
         self.hide();
@@ -207,7 +285,7 @@ impl DrawableComponent for PullComponent {
             let (state, progress) =
                 PushComponent::get_progress(&self.progress);
 
-            let area = ui::centered_rect_absolute(30, 3, f.size());
+            let area = ui::centered_rect_absolute(50, 3, f.size());
 
             f.render_widget(Clear, area);
             f.render_widget(
@@ -216,14 +294,20 @@ impl DrawableComponent for PullComponent {
                     .block(
                         Block::default()
                             .title(Span::styled(
-                                strings::PULL_POPUP_MSG,
-                                self.theme.title(true),
+                                if self.merge {
+                                    strings::PULL_POPUP_MSG
+                                } else {
+                                    strings::FETCH_POPUP_MSG
+                                },
+                                self.theme.borrow().title(true),
                             ))
                             .borders(Borders::ALL)
                             .border_type(BorderType::Thick)
-                            .border_style(self.theme.block(true)),
+                            .border_style(
+                                self.theme.borrow().block(true),
+                            ),
                     )
-                    .gauge_style(self.theme.push_gauge())
+                    .gauge_style(self.theme.borrow().push_gauge())
                     .percent(u16::from(progress)),
                 area,
             );
@@ -15,11 +15,12 @@ use asyncgit::{
             extract_username_password, need_username_password,
             BasicAuthCredential,
         },
-        get_branch_remote, get_default_remote,
+        get_default_remote, get_push_remote, RepoPath,
     },
     AsyncNotification, AsyncPush, PushRequest, RemoteProgress,
-    RemoteProgressState, CWD,
+    RemoteProgressState,
 };
+use bytesize::ByteSize;
 use crossbeam_channel::Sender;
 use crossterm::event::Event;
 use tui::{
@@ -32,12 +33,15 @@ use tui::{
 
 ///
 pub struct PushComponent {
+    repo: RepoPath,
     visible: bool,
     force: bool,
     git_push: AsyncPush,
     progress: Option<RemoteProgress>,
     pending: bool,
     branch: String,
+    /// chosen by the user, else the upstream or default remote
+    remote: Option<String>,
     queue: Queue,
     theme: SharedTheme,
     key_config: SharedKeyConfig,
@@ -47,6 +51,7 @@ pub struct PushComponent {
 impl PushComponent {
     ///
     pub fn new(
+        repo: RepoPath,
         queue: &Queue,
         sender: &Sender<AsyncNotification>,
         theme: SharedTheme,
@@ -58,7 +63,8 @@ impl PushComponent {
             pending: false,
             visible: false,
             branch: String::new(),
-            git_push: AsyncPush::new(sender),
+            remote: None,
+            git_push: AsyncPush::new(repo.clone(), sender),
             progress: None,
             input_cred: CredComponent::new(
                 theme.clone(),
@@ -66,6 +72,7 @@ impl PushComponent {
             ),
             theme,
             key_config,
+            repo,
         }
     }
 
@@ -73,15 +80,17 @@ impl PushComponent {
     pub fn push(
         &mut self,
         branch: String,
+        remote: Option<String>,
         force: bool,
     ) -> Result<()> {
         self.branch = branch;
+        self.remote = remote;
         self.force = force;
         self.show()?;
 
-        if need_username_password()? {
-            let cred =
-                extract_username_password().unwrap_or_else(|_| {
+        if need_username_password(&self.repo)? {
+            let cred = extract_username_password(&self.repo)
+                .unwrap_or_else(|_| {
                     BasicAuthCredential::new(None, None)
                 });
             if cred.is_complete() {
@@ -100,14 +109,21 @@ impl PushComponent {
         cred: Option<BasicAuthCredential>,
         force: bool,
     ) -> Result<()> {
-        let remote = if let Some(remote) =
-            get_branch_remote(CWD, &self.branch)?
+        let remote = if let Some(remote) = self.remote.clone() {
+            log::info!(
+                "push: branch '{}' to chosen remote '{}'",
+                self.branch,
+                remote
+            );
+            remote
+        } else if let Some(remote) =
+            get_push_remote(&self.repo, &self.branch)?
         {
-            log::info!("push: branch '{}' has upstream for remote '{}' - using that",self.branch,remote);
+            log::info!("push: branch '{}' has push remote or upstream for remote '{}' - using that",self.branch,remote);
             remote
         } else {
             log::info!("push: branch '{}' has no upstream - looking up default remote",self.branch);
-            let remote = get_default_remote(CWD)?;
+            let remote = get_default_remote(&self.repo)?;
             log::info!(
                 "push: branch '{}' to remote '{}'",
                 self.branch,
@@ -173,10 +189,22 @@ impl PushComponent {
         progress.as_ref().map_or(
             (strings::PUSH_POPUP_PROGRESS_NONE.into(), 0),
             |progress| {
-                (
-                    Self::progress_state_name(&progress.state),
-                    progress.get_progress_percent(),
-                )
+                let state =
+                    Self::progress_state_name(&progress.state);
+                let label = progress.transfer.map_or_else(
+                    || state.clone(),
+                    |transfer| {
+                        format!(
+                            "{} - {} ({}/s)",
+                            state,
+                            ByteSize::b(transfer.bytes as u64),
+                            ByteSize::b(
+                                transfer.bytes_per_sec as u64
+                            )
+                        )
+                    },
+                );
+                (label, progress.get_progress_percent())
             },
         )
     }
@@ -195,6 +223,9 @@ impl PushComponent {
             RemoteProgressState::Transfer => {
                 strings::PUSH_POPUP_STATES_TRANSFER
             }
+            RemoteProgressState::ResolvingDeltas => {
+                strings::PUSH_POPUP_STATES_RESOLVING
+            }
             RemoteProgressState::Done => {
                 strings::PUSH_POPUP_STATES_DONE
             }
@@ -213,7 +244,7 @@ impl DrawableComponent for PushComponent {
             let (state, progress) =
                 Self::get_progress(&self.progress);
 
-            let area = ui::centered_rect_absolute(30, 3, f.size());
+            let area = ui::centered_rect_absolute(50, 3, f.size());
 
             f.render_widget(Clear, area);
             f.render_widget(
@@ -227,13 +258,15 @@ impl DrawableComponent for PushComponent {
                                 } else {
                                     strings::PUSH_POPUP_MSG
                                 },
-                                self.theme.title(true),
+                                self.theme.borrow().title(true),
                             ))
                             .borders(Borders::ALL)
                             .border_type(BorderType::Thick)
-                            .border_style(self.theme.block(true)),
+                            .border_style(
+                                self.theme.borrow().block(true),
+                            ),
                     )
-                    .gauge_style(self.theme.push_gauge())
+                    .gauge_style(self.theme.borrow().push_gauge())
                     .percent(u16::from(progress)),
                 area,
             );
@@ -281,7 +314,7 @@ impl Component for PushComponent {
                         )?;
                         self.input_cred.hide();
                     }
-                } else if e == self.key_config.exit_popup
+                } else if e == self.key_config.borrow().exit_popup
                     && !self.pending
                 {
                     self.hide();
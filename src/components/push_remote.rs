@@ -0,0 +1,209 @@
+use super::{
+    visibility_blocking, CommandBlocking, CommandInfo, Component,
+    DrawableComponent, EventState,
+};
+use crate::{
+    keys::SharedKeyConfig,
+    queue::{InternalEvent, Queue},
+    strings,
+    ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::sync::{self, RepoPath};
+use crossterm::event::Event;
+use std::convert::TryFrom;
+use tui::{
+    backend::Backend, layout::Rect, text::Span, widgets::Clear, Frame,
+};
+
+/// asks which remote a branch without upstream gets pushed to, the
+/// push then sets it up to track the branch on that remote
+pub struct PushRemoteComponent {
+    repo: RepoPath,
+    remotes: Vec<String>,
+    selection: usize,
+    branch: String,
+    force: bool,
+    visible: bool,
+    queue: Queue,
+    theme: SharedTheme,
+    key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for PushRemoteComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        if self.visible {
+            const WIDTH: u16 = 50;
+
+            let height = u16::try_from(self.remotes.len())
+                .unwrap_or(u16::MAX)
+                .saturating_add(2);
+
+            let area = ui::centered_rect_absolute(
+                WIDTH.min(rect.width),
+                height.min(rect.height),
+                rect,
+            );
+
+            let items = self.remotes.iter().enumerate().map(
+                |(idx, remote)| {
+                    Span::styled(
+                        remote.as_str(),
+                        self.theme
+                            .borrow()
+                            .text(true, idx == self.selection),
+                    )
+                },
+            );
+
+            f.render_widget(Clear, area);
+            ui::draw_list(
+                f,
+                area,
+                &strings::push_remote_popup_title(&self.branch),
+                items,
+                true,
+                &self.theme,
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl Component for PushRemoteComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.visible || force_all {
+            out.clear();
+
+            out.push(CommandInfo::new(
+                strings::commands::scroll(&self.key_config),
+                true,
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::close_popup(&self.key_config),
+                true,
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::push_remote_confirm(
+                    &self.key_config,
+                ),
+                true,
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<EventState> {
+        if self.visible {
+            if let Event::Key(e) = ev {
+                if e == self.key_config.borrow().exit_popup {
+                    self.hide();
+                } else if e == self.key_config.borrow().move_down {
+                    self.selection = self
+                        .selection
+                        .saturating_add(1)
+                        .min(self.remotes.len().saturating_sub(1));
+                } else if e == self.key_config.borrow().move_up {
+                    self.selection = self.selection.saturating_sub(1);
+                } else if e == self.key_config.borrow().enter {
+                    self.hide();
+                    if let Some(remote) =
+                        self.remotes.get(self.selection)
+                    {
+                        self.queue.borrow_mut().push_back(
+                            InternalEvent::PushTo(
+                                self.branch.clone(),
+                                remote.clone(),
+                                self.force,
+                            ),
+                        );
+                    }
+                }
+            }
+
+            return Ok(EventState::Consumed);
+        }
+
+        Ok(EventState::NotConsumed)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+
+        Ok(())
+    }
+}
+
+impl PushRemoteComponent {
+    ///
+    pub fn new(
+        repo: RepoPath,
+        queue: Queue,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+    ) -> Self {
+        Self {
+            repo,
+            remotes: Vec::new(),
+            selection: 0,
+            branch: String::new(),
+            force: false,
+            visible: false,
+            queue,
+            theme,
+            key_config,
+        }
+    }
+
+    /// the default remote is preselected
+    pub fn open(
+        &mut self,
+        branch: String,
+        force: bool,
+    ) -> Result<()> {
+        self.remotes = sync::get_remotes(&self.repo)?;
+
+        if self.remotes.is_empty() {
+            self.queue.borrow_mut().push_back(
+                InternalEvent::ShowErrorMsg(strings::no_remote_msg(
+                    &self.key_config,
+                )),
+            );
+            return Ok(());
+        }
+
+        let default = sync::get_default_remote(&self.repo).ok();
+        self.selection = self
+            .remotes
+            .iter()
+            .position(|remote| Some(remote) == default.as_ref())
+            .unwrap_or_default();
+        self.branch = branch;
+        self.force = force;
+
+        self.show()
+    }
+}
@@ -16,8 +16,9 @@ use asyncgit::{
             BasicAuthCredential,
         },
         get_default_remote, AsyncProgress, PushTagsProgress,
+        RepoPath,
     },
-    AsyncNotification, AsyncPushTags, PushTagsRequest, CWD,
+    AsyncNotification, AsyncPushTags, PushTagsRequest,
 };
 use crossbeam_channel::Sender;
 use crossterm::event::Event;
@@ -31,8 +32,12 @@ use tui::{
 
 ///
 pub struct PushTagsComponent {
+    repo: RepoPath,
     visible: bool,
     git_push: AsyncPushTags,
+    /// tags to push, all missing on the remote if empty
+    tags: Vec<String>,
+    force: bool,
     progress: Option<PushTagsProgress>,
     pending: bool,
     queue: Queue,
@@ -44,6 +49,7 @@ pub struct PushTagsComponent {
 impl PushTagsComponent {
     ///
     pub fn new(
+        repo: RepoPath,
         queue: &Queue,
         sender: &Sender<AsyncNotification>,
         theme: SharedTheme,
@@ -53,7 +59,9 @@ impl PushTagsComponent {
             queue: queue.clone(),
             pending: false,
             visible: false,
-            git_push: AsyncPushTags::new(sender),
+            git_push: AsyncPushTags::new(repo.clone(), sender),
+            tags: Vec::new(),
+            force: false,
             progress: None,
             input_cred: CredComponent::new(
                 theme.clone(),
@@ -61,15 +69,22 @@ impl PushTagsComponent {
             ),
             theme,
             key_config,
+            repo,
         }
     }
 
-    ///
-    pub fn push_tags(&mut self) -> Result<()> {
+    /// pushes `tags`, all missing on the remote if there are none
+    pub fn push_tags(
+        &mut self,
+        tags: Vec<String>,
+        force: bool,
+    ) -> Result<()> {
+        self.tags = tags;
+        self.force = force;
         self.show()?;
-        if need_username_password()? {
-            let cred =
-                extract_username_password().unwrap_or_else(|_| {
+        if need_username_password(&self.repo)? {
+            let cred = extract_username_password(&self.repo)
+                .unwrap_or_else(|_| {
                     BasicAuthCredential::new(None, None)
                 });
             if cred.is_complete() {
@@ -90,7 +105,9 @@ impl PushTagsComponent {
         self.pending = true;
         self.progress = None;
         self.git_push.request(PushTagsRequest {
-            remote: get_default_remote(CWD)?,
+            remote: get_default_remote(&self.repo)?,
+            tags: self.tags.clone(),
+            force: self.force,
             basic_credential: cred,
         })?;
         Ok(())
@@ -184,13 +201,15 @@ impl DrawableComponent for PushTagsComponent {
                         Block::default()
                             .title(Span::styled(
                                 strings::PUSH_TAGS_POPUP_MSG,
-                                self.theme.title(true),
+                                self.theme.borrow().title(true),
                             ))
                             .borders(Borders::ALL)
                             .border_type(BorderType::Thick)
-                            .border_style(self.theme.block(true)),
+                            .border_style(
+                                self.theme.borrow().block(true),
+                            ),
                     )
-                    .gauge_style(self.theme.push_gauge())
+                    .gauge_style(self.theme.borrow().push_gauge())
                     .percent(u16::from(progress)),
                 area,
             );
@@ -237,7 +256,7 @@ impl Component for PushTagsComponent {
                         ))?;
                         self.input_cred.hide();
                     }
-                } else if e == self.key_config.exit_popup
+                } else if e == self.key_config.borrow().exit_popup
                     && !self.pending
                 {
                     self.hide();
@@ -0,0 +1,413 @@
+use super::{
+    visibility_blocking, CommandBlocking, CommandInfo, Component,
+    DrawableComponent, EventState, ScrollType,
+};
+use crate::{
+    keys::SharedKeyConfig,
+    queue::{Action, InternalEvent, NeedsUpdate, Queue},
+    strings, try_or_popup,
+    ui::{self, calc_scroll_top, style::SharedTheme, Size},
+};
+use anyhow::Result;
+use asyncgit::sync::{self, RebaseProgress, RebaseState, RepoPath};
+use crossterm::event::Event;
+use std::cell::Cell;
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    text::{Span, Spans, Text},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
+    Frame,
+};
+use unicode_truncate::UnicodeTruncateStr;
+
+/// the commits of a rebase stopped on conflicts: continue once they
+/// are resolved, skip the commit or abort, reorder the ones to come
+pub struct RebaseComponent {
+    repo: RepoPath,
+    progress: Option<RebaseProgress>,
+    visible: bool,
+    selection: usize,
+    scroll_top: Cell<usize>,
+    current_height: Cell<usize>,
+    queue: Queue,
+    theme: SharedTheme,
+    key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for RebaseComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        if self.visible {
+            const PERCENT_SIZE: Size = Size::new(80, 50);
+            const MIN_SIZE: Size = Size::new(60, 20);
+
+            let area = ui::centered_rect(
+                PERCENT_SIZE.width,
+                PERCENT_SIZE.height,
+                f.size(),
+            );
+            let area =
+                ui::rect_inside(MIN_SIZE, f.size().into(), area);
+            let area = area.intersection(rect);
+
+            f.render_widget(Clear, area);
+
+            let height = usize::from(area.height.saturating_sub(2));
+            self.current_height.set(height);
+
+            self.scroll_top.set(calc_scroll_top(
+                self.scroll_top.get(),
+                height,
+                self.selection,
+            ));
+
+            f.render_widget(
+                Paragraph::new(
+                    self.get_text(
+                        area.width.saturating_sub(2),
+                        height,
+                    ),
+                )
+                .block(
+                    Block::default()
+                        .title(Span::styled(
+                            strings::title_rebase(
+                                self.progress.as_ref(),
+                            ),
+                            self.theme.borrow().title(true),
+                        ))
+                        .border_type(BorderType::Thick)
+                        .borders(Borders::ALL)
+                        .border_style(
+                            self.theme.borrow().block(true),
+                        ),
+                ),
+                area,
+            );
+
+            ui::draw_scrollbar(
+                f,
+                area,
+                &self.theme,
+                self.steps_len().saturating_sub(height),
+                self.scroll_top.get(),
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl Component for RebaseComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.visible || force_all {
+            out.clear();
+
+            out.push(CommandInfo::new(
+                strings::commands::scroll(&self.key_config),
+                true,
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::close_popup(&self.key_config),
+                true,
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::rebase_continue(&self.key_config),
+                true,
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::rebase_edit_message(
+                    &self.key_config,
+                ),
+                true,
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::rebase_skip(&self.key_config),
+                true,
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::rebase_abort(&self.key_config),
+                true,
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::rebase_move_step(&self.key_config),
+                self.can_move(true) || self.can_move(false),
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<EventState> {
+        if self.visible {
+            if let Event::Key(e) = ev {
+                if e == self.key_config.borrow().exit_popup {
+                    self.hide();
+                } else if e == self.key_config.borrow().move_down {
+                    self.move_selection(ScrollType::Down);
+                } else if e == self.key_config.borrow().move_up {
+                    self.move_selection(ScrollType::Up);
+                } else if e == self.key_config.borrow().page_down {
+                    self.move_selection(ScrollType::PageDown);
+                } else if e == self.key_config.borrow().page_up {
+                    self.move_selection(ScrollType::PageUp);
+                } else if e == self.key_config.borrow().home {
+                    self.move_selection(ScrollType::Home);
+                } else if e == self.key_config.borrow().end {
+                    self.move_selection(ScrollType::End);
+                } else if e == self.key_config.borrow().enter {
+                    try_or_popup!(
+                        self,
+                        "continue rebase:",
+                        sync::continue_rebase(&self.repo, None)
+                            .map_err(Into::into)
+                            .and_then(|state| self.stopped(state))
+                    );
+                } else if e == self.key_config.borrow().open_commit {
+                    self.hide();
+                    self.queue
+                        .borrow_mut()
+                        .push_back(InternalEvent::OpenCommit);
+                } else if e == self.key_config.borrow().rebase_skip {
+                    try_or_popup!(
+                        self,
+                        "skip rebase step:",
+                        sync::skip_rebase_step(&self.repo)
+                            .map_err(Into::into)
+                            .and_then(|state| self.stopped(state))
+                    );
+                } else if e == self.key_config.borrow().abort_merge {
+                    self.queue.borrow_mut().push_back(
+                        InternalEvent::ConfirmAction(
+                            Action::AbortRebase,
+                        ),
+                    );
+                } else if e == self.key_config.borrow().shift_up
+                    && self.can_move(true)
+                {
+                    try_or_popup!(
+                        self,
+                        "move rebase step:",
+                        self.move_step(true)
+                    );
+                } else if e == self.key_config.borrow().shift_down
+                    && self.can_move(false)
+                {
+                    try_or_popup!(
+                        self,
+                        "move rebase step:",
+                        self.move_step(false)
+                    );
+                }
+            }
+
+            Ok(EventState::Consumed)
+        } else {
+            Ok(EventState::NotConsumed)
+        }
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn hide(&mut self) {
+        self.visible = false
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+
+        Ok(())
+    }
+}
+
+impl RebaseComponent {
+    ///
+    pub fn new(
+        repo: RepoPath,
+        queue: Queue,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+    ) -> Self {
+        Self {
+            progress: None,
+            visible: false,
+            selection: 0,
+            scroll_top: Cell::new(0),
+            current_height: Cell::new(0),
+            queue,
+            theme,
+            key_config,
+            repo,
+        }
+    }
+
+    /// selects the commit being replayed, nothing happens if no
+    /// rebase (of gitui) is in progress
+    pub fn open(&mut self) -> Result<()> {
+        self.update_progress()?;
+
+        if let Some(progress) = &self.progress {
+            self.selection = progress.current.unwrap_or_default();
+            self.show()?;
+        }
+
+        Ok(())
+    }
+
+    fn update_progress(&mut self) -> Result<()> {
+        self.progress = sync::rebase_progress(&self.repo)?;
+        self.selection =
+            self.selection.min(self.steps_len().saturating_sub(1));
+
+        Ok(())
+    }
+
+    fn steps_len(&self) -> usize {
+        self.progress.as_ref().map_or(0, |p| p.steps.len())
+    }
+
+    /// stays open on the next conflicts
+    fn stopped(&mut self, state: RebaseState) -> Result<()> {
+        match state {
+            RebaseState::Finished => self.hide(),
+            RebaseState::Conflicted => {
+                self.update_progress()?;
+                if let Some(current) =
+                    self.progress.as_ref().and_then(|p| p.current)
+                {
+                    self.selection = current;
+                }
+            }
+        }
+
+        self.queue
+            .borrow_mut()
+            .push_back(InternalEvent::Update(NeedsUpdate::ALL));
+
+        Ok(())
+    }
+
+    /// only commits not replayed yet can be moved, and only among
+    /// each other
+    fn can_move(&self, up: bool) -> bool {
+        let pending = |idx: usize| {
+            self.progress.as_ref().map_or(false, |p| {
+                idx < p.steps.len()
+                    && p.current.map_or(true, |current| idx > current)
+            })
+        };
+        let other = if up {
+            self.selection.checked_sub(1)
+        } else {
+            self.selection.checked_add(1)
+        };
+
+        pending(self.selection) && other.map_or(false, pending)
+    }
+
+    fn move_step(&mut self, up: bool) -> Result<()> {
+        sync::move_rebase_step(&self.repo, self.selection, up)?;
+
+        self.selection = if up {
+            self.selection.saturating_sub(1)
+        } else {
+            self.selection.saturating_add(1)
+        };
+
+        self.update_progress()
+    }
+
+    fn move_selection(&mut self, scroll: ScrollType) {
+        let page = self.current_height.get().saturating_sub(1);
+        let max = self.steps_len().saturating_sub(1);
+
+        let new_selection = match scroll {
+            ScrollType::Up => self.selection.saturating_sub(1),
+            ScrollType::Down => self.selection.saturating_add(1),
+            ScrollType::PageUp => self.selection.saturating_sub(page),
+            ScrollType::PageDown => {
+                self.selection.saturating_add(page)
+            }
+            ScrollType::Home => 0,
+            ScrollType::End => max,
+        };
+
+        self.selection = new_selection.min(max);
+    }
+
+    fn get_text(&self, width: u16, height: usize) -> Text {
+        const HASH_LENGTH: usize = 8;
+        const MARKER_LENGTH: usize = 2;
+
+        let progress = match &self.progress {
+            Some(progress) => progress,
+            None => return Text::default(),
+        };
+
+        let summary_length = usize::from(width)
+            .saturating_sub(MARKER_LENGTH)
+            .saturating_sub(HASH_LENGTH);
+
+        let lines = progress
+            .steps
+            .iter()
+            .enumerate()
+            .skip(self.scroll_top.get())
+            .take(height)
+            .map(|(idx, step)| {
+                let selected = idx == self.selection;
+                let current = progress.current;
+                let (marker, pending) = match current {
+                    Some(current) if idx < current => {
+                        ("\u{221a} ", false)
+                    } //√
+                    Some(current) if idx == current => ("> ", true),
+                    _ => ("  ", true),
+                };
+
+                Spans::from(vec![
+                    Span::styled(
+                        marker,
+                        self.theme.borrow().commit_hash(selected),
+                    ),
+                    Span::styled(
+                        format!("{} ", step.id.get_short_string()),
+                        self.theme.borrow().commit_hash(selected),
+                    ),
+                    Span::styled(
+                        step.summary
+                            .unicode_truncate(summary_length)
+                            .0
+                            .to_string(),
+                        self.theme.borrow().text(pending, selected),
+                    ),
+                ])
+            })
+            .collect::<Vec<_>>();
+
+        Text::from(lines)
+    }
+}
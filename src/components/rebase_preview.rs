@@ -0,0 +1,324 @@
+use super::{
+    visibility_blocking, CommandBlocking, CommandInfo, Component,
+    DrawableComponent, EventState, ScrollType,
+};
+use crate::{
+    keys::SharedKeyConfig,
+    options::SharedOptions,
+    queue::{InternalEvent, NeedsUpdate, Queue},
+    strings, try_or_popup,
+    ui::{self, calc_scroll_top, style::SharedTheme, Size},
+};
+use anyhow::Result;
+use asyncgit::sync::{
+    self, DiffStat, RebaseState, RebaseTodo, RepoPath,
+};
+use crossterm::event::Event;
+use std::cell::Cell;
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    text::{Span, Spans, Text},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
+    Frame,
+};
+use unicode_truncate::UnicodeTruncateStr;
+
+/// the commits a rebase onto a branch would replay, with warnings
+/// about what rewriting them means, confirming starts the rebase
+pub struct RebasePreviewComponent {
+    repo: RepoPath,
+    onto: String,
+    todo: Vec<RebaseTodo>,
+    /// what the rebase changes in the worktree
+    stat: Option<DiffStat>,
+    visible: bool,
+    selection: usize,
+    scroll_top: Cell<usize>,
+    current_height: Cell<usize>,
+    queue: Queue,
+    theme: SharedTheme,
+    key_config: SharedKeyConfig,
+    options: SharedOptions,
+}
+
+impl DrawableComponent for RebasePreviewComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        if self.visible {
+            const PERCENT_SIZE: Size = Size::new(80, 50);
+            const MIN_SIZE: Size = Size::new(60, 20);
+
+            let area = ui::centered_rect(
+                PERCENT_SIZE.width,
+                PERCENT_SIZE.height,
+                f.size(),
+            );
+            let area =
+                ui::rect_inside(MIN_SIZE, f.size().into(), area);
+            let area = area.intersection(rect);
+
+            f.render_widget(Clear, area);
+
+            let height = usize::from(area.height.saturating_sub(2));
+            self.current_height.set(height);
+
+            self.scroll_top.set(calc_scroll_top(
+                self.scroll_top.get(),
+                height,
+                self.selection,
+            ));
+
+            f.render_widget(
+                Paragraph::new(
+                    self.get_text(
+                        area.width.saturating_sub(2),
+                        height,
+                    ),
+                )
+                .block(
+                    Block::default()
+                        .title(Span::styled(
+                            strings::title_rebase_preview(
+                                &self.onto,
+                                self.todo.len(),
+                                self.stat,
+                            ),
+                            self.theme.borrow().title(true),
+                        ))
+                        .border_type(BorderType::Thick)
+                        .borders(Borders::ALL)
+                        .border_style(
+                            self.theme.borrow().block(true),
+                        ),
+                ),
+                area,
+            );
+
+            ui::draw_scrollbar(
+                f,
+                area,
+                &self.theme,
+                self.todo.len().saturating_sub(height),
+                self.scroll_top.get(),
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl Component for RebasePreviewComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.visible || force_all {
+            out.clear();
+
+            out.push(CommandInfo::new(
+                strings::commands::scroll(&self.key_config),
+                true,
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::close_popup(&self.key_config),
+                true,
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::rebase_start(&self.key_config),
+                !self.todo.is_empty(),
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<EventState> {
+        if self.visible {
+            if let Event::Key(e) = ev {
+                if e == self.key_config.borrow().exit_popup {
+                    self.hide();
+                } else if e == self.key_config.borrow().move_down {
+                    self.move_selection(ScrollType::Down);
+                } else if e == self.key_config.borrow().move_up {
+                    self.move_selection(ScrollType::Up);
+                } else if e == self.key_config.borrow().page_down {
+                    self.move_selection(ScrollType::PageDown);
+                } else if e == self.key_config.borrow().page_up {
+                    self.move_selection(ScrollType::PageUp);
+                } else if e == self.key_config.borrow().home {
+                    self.move_selection(ScrollType::Home);
+                } else if e == self.key_config.borrow().end {
+                    self.move_selection(ScrollType::End);
+                } else if e == self.key_config.borrow().enter
+                    && !self.todo.is_empty()
+                {
+                    try_or_popup!(
+                        self,
+                        "rebase branch error:",
+                        self.start()
+                    );
+                    self.hide();
+                    self.queue.borrow_mut().push_back(
+                        InternalEvent::Update(NeedsUpdate::ALL),
+                    );
+                }
+            }
+
+            Ok(EventState::Consumed)
+        } else {
+            Ok(EventState::NotConsumed)
+        }
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn hide(&mut self) {
+        self.visible = false
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+
+        Ok(())
+    }
+}
+
+impl RebasePreviewComponent {
+    ///
+    pub fn new(
+        repo: RepoPath,
+        queue: Queue,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+        options: SharedOptions,
+    ) -> Self {
+        Self {
+            onto: String::new(),
+            todo: Vec::new(),
+            stat: None,
+            visible: false,
+            selection: 0,
+            scroll_top: Cell::new(0),
+            current_height: Cell::new(0),
+            queue,
+            theme,
+            key_config,
+            options,
+            repo,
+        }
+    }
+
+    /// previews rebasing the current branch onto `branch`, a local
+    /// or remote one
+    pub fn open(&mut self, branch: String) -> Result<()> {
+        self.todo = sync::rebase_todo(&self.repo, &branch)?;
+        self.stat = sync::merge_diffstat(&self.repo, &branch).ok();
+        self.onto = branch;
+        self.selection = 0;
+        self.show()
+    }
+
+    /// opens the rebase popup if it stopped on conflicts
+    fn start(&self) -> Result<()> {
+        let state = sync::autostash(
+            &self.repo,
+            self.options.borrow().autostash(),
+            || sync::rebase_branch(&self.repo, &self.onto),
+        )?;
+
+        if state == RebaseState::Conflicted {
+            self.queue
+                .borrow_mut()
+                .push_back(InternalEvent::OpenRebase);
+        }
+
+        Ok(())
+    }
+
+    fn move_selection(&mut self, scroll: ScrollType) {
+        let page = self.current_height.get().saturating_sub(1);
+        let max = self.todo.len().saturating_sub(1);
+
+        let new_selection = match scroll {
+            ScrollType::Up => self.selection.saturating_sub(1),
+            ScrollType::Down => self.selection.saturating_add(1),
+            ScrollType::PageUp => self.selection.saturating_sub(page),
+            ScrollType::PageDown => {
+                self.selection.saturating_add(page)
+            }
+            ScrollType::Home => 0,
+            ScrollType::End => max,
+        };
+
+        self.selection = new_selection.min(max);
+    }
+
+    fn get_text(&self, width: u16, height: usize) -> Text {
+        const HASH_LENGTH: usize = 8;
+
+        let width = usize::from(width).saturating_sub(HASH_LENGTH);
+
+        let lines = self
+            .todo
+            .iter()
+            .enumerate()
+            .skip(self.scroll_top.get())
+            .take(height)
+            .map(|(idx, commit)| {
+                let selected = idx == self.selection;
+                let risks = commit
+                    .risks
+                    .iter()
+                    .map(strings::rebase_risk)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let summary_length = if risks.is_empty() {
+                    width
+                } else {
+                    width * 50 / 100
+                };
+                let risks_length =
+                    width.saturating_sub(summary_length + 1);
+
+                Spans::from(vec![
+                    Span::styled(
+                        format!("{} ", commit.id.get_short_string()),
+                        self.theme.borrow().commit_hash(selected),
+                    ),
+                    Span::styled(
+                        format!(
+                            "{:w$} ",
+                            commit
+                                .summary
+                                .unicode_truncate(summary_length)
+                                .0,
+                            w = summary_length
+                        ),
+                        self.theme.borrow().text(true, selected),
+                    ),
+                    Span::styled(
+                        risks
+                            .unicode_truncate(risks_length)
+                            .0
+                            .to_string(),
+                        self.theme.borrow().text_danger(),
+                    ),
+                ])
+            })
+            .collect::<Vec<_>>();
+
+        Text::from(lines)
+    }
+}
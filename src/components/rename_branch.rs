@@ -10,14 +10,12 @@ use crate::{
     ui::style::SharedTheme,
 };
 use anyhow::Result;
-use asyncgit::{
-    sync::{self},
-    CWD,
-};
+use asyncgit::sync::{self, RepoPath};
 use crossterm::event::Event;
 use tui::{backend::Backend, layout::Rect, Frame};
 
 pub struct RenameBranchComponent {
+    repo: RepoPath,
     input: TextInputComponent,
     branch_ref: Option<String>,
     queue: Queue,
@@ -64,7 +62,7 @@ impl Component for RenameBranchComponent {
             }
 
             if let Event::Key(e) = ev {
-                if e == self.key_config.enter {
+                if e == self.key_config.borrow().enter {
                     self.rename_branch();
                 }
 
@@ -92,6 +90,7 @@ impl Component for RenameBranchComponent {
 impl RenameBranchComponent {
     ///
     pub fn new(
+        repo: RepoPath,
         queue: Queue,
         theme: SharedTheme,
         key_config: SharedKeyConfig,
@@ -107,6 +106,7 @@ impl RenameBranchComponent {
             ),
             branch_ref: None,
             key_config,
+            repo,
         }
     }
 
@@ -128,7 +128,7 @@ impl RenameBranchComponent {
     pub fn rename_branch(&mut self) {
         if let Some(br) = &self.branch_ref {
             let res = sync::rename_branch(
-                CWD,
+                &self.repo,
                 br,
                 self.input.get_text().as_str(),
             );
@@ -0,0 +1,329 @@
+use super::{
+    textinput::TextInputComponent, visibility_blocking,
+    CommandBlocking, CommandInfo, Component, DrawableComponent,
+    EventState, InputType, ScrollType,
+};
+use crate::{
+    keys::SharedKeyConfig,
+    queue::{InternalEvent, Queue},
+    recent_repos::RecentRepos,
+    strings, try_or_popup,
+    ui::{self, calc_scroll_top, style::SharedTheme, Size},
+};
+use anyhow::Result;
+use asyncgit::sync::{utils::repo_work_dir, RepoPath};
+use crossterm::event::Event;
+use std::cell::Cell;
+use tui::{
+    backend::Backend, layout::Rect, text::Span, widgets::Clear, Frame,
+};
+
+/// popup listing recently opened repositories to switch to
+pub struct RepoSwitcherComponent {
+    recent: RecentRepos,
+    /// workdir of the repository we are in
+    current: Option<String>,
+    visible: bool,
+    selection: usize,
+    scroll_top: Cell<usize>,
+    current_height: Cell<usize>,
+    input: TextInputComponent,
+    queue: Queue,
+    theme: SharedTheme,
+    key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for RepoSwitcherComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        if self.visible {
+            const PERCENT_SIZE: Size = Size::new(80, 50);
+            const MIN_SIZE: Size = Size::new(60, 20);
+
+            let area = ui::centered_rect(
+                PERCENT_SIZE.width,
+                PERCENT_SIZE.height,
+                f.size(),
+            );
+            let area =
+                ui::rect_inside(MIN_SIZE, f.size().into(), area);
+            let area = area.intersection(rect);
+
+            f.render_widget(Clear, area);
+
+            let height = usize::from(area.height.saturating_sub(2));
+            self.current_height.set(height);
+
+            self.scroll_top.set(calc_scroll_top(
+                self.scroll_top.get(),
+                height,
+                self.selection,
+            ));
+
+            let items = self
+                .recent
+                .repos()
+                .iter()
+                .enumerate()
+                .skip(self.scroll_top.get())
+                .take(height)
+                .map(|(idx, repo)| {
+                    let is_current =
+                        self.current.as_ref() == Some(repo);
+                    Span::styled(
+                        format!(
+                            "{} {}",
+                            if is_current { "*" } else { " " },
+                            repo
+                        ),
+                        self.theme
+                            .borrow()
+                            .text(!is_current, idx == self.selection),
+                    )
+                });
+
+            ui::draw_list(
+                f,
+                area,
+                &strings::title_repos(),
+                items,
+                true,
+                &self.theme,
+            );
+
+            self.input.draw(f, rect)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Component for RepoSwitcherComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.visible || force_all {
+            if self.input.is_visible() {
+                self.input.commands(out, force_all);
+
+                out.push(CommandInfo::new(
+                    strings::commands::repo_path_confirm_msg(
+                        &self.key_config,
+                    ),
+                    true,
+                    true,
+                ));
+
+                return visibility_blocking(self);
+            }
+
+            out.clear();
+
+            out.push(CommandInfo::new(
+                strings::commands::scroll(&self.key_config),
+                true,
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::close_popup(&self.key_config),
+                true,
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::repo_switch(&self.key_config),
+                self.selected_repo().is_some()
+                    && !self.selection_is_current(),
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::repo_open_path(&self.key_config),
+                true,
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::repo_remove(&self.key_config),
+                self.selected_repo().is_some()
+                    && !self.selection_is_current(),
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<EventState> {
+        if self.visible {
+            if self.input.is_visible() {
+                if self.input.event(ev)?.is_consumed() {
+                    return Ok(EventState::Consumed);
+                }
+
+                if let Event::Key(e) = ev {
+                    if e == self.key_config.borrow().enter {
+                        let path = self.input.get_text().clone();
+                        self.input.clear();
+                        self.input.hide();
+                        try_or_popup!(
+                            self,
+                            "open repository error:",
+                            self.switch_to(&path)
+                        );
+                    }
+                }
+
+                return Ok(EventState::Consumed);
+            }
+
+            if let Event::Key(e) = ev {
+                if e == self.key_config.borrow().exit_popup {
+                    self.hide();
+                } else if e == self.key_config.borrow().move_down {
+                    self.move_selection(ScrollType::Down);
+                } else if e == self.key_config.borrow().move_up {
+                    self.move_selection(ScrollType::Up);
+                } else if e == self.key_config.borrow().page_down {
+                    self.move_selection(ScrollType::PageDown);
+                } else if e == self.key_config.borrow().page_up {
+                    self.move_selection(ScrollType::PageUp);
+                } else if e == self.key_config.borrow().enter
+                    && !self.selection_is_current()
+                {
+                    if let Some(repo) = self.selected_repo() {
+                        try_or_popup!(
+                            self,
+                            "open repository error:",
+                            self.switch_to(&repo)
+                        );
+                    }
+                } else if e == self.key_config.borrow().repo_open_path
+                {
+                    self.input.show()?;
+                } else if e == self.key_config.borrow().repo_remove
+                    && !self.selection_is_current()
+                {
+                    if let Some(repo) = self.selected_repo() {
+                        self.recent.remove(&repo);
+                        self.selection = self.selection.min(
+                            self.recent
+                                .repos()
+                                .len()
+                                .saturating_sub(1),
+                        );
+                    }
+                }
+            }
+
+            Ok(EventState::Consumed)
+        } else {
+            Ok(EventState::NotConsumed)
+        }
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn hide(&mut self) {
+        self.visible = false
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+
+        Ok(())
+    }
+}
+
+impl RepoSwitcherComponent {
+    /// remembers the current repository in the recent list
+    pub fn new(
+        repo: &RepoPath,
+        queue: Queue,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+    ) -> Self {
+        let mut recent = RecentRepos::new();
+        let current = repo_work_dir(repo).ok();
+        if let Some(current) = &current {
+            recent.add(current);
+        }
+
+        Self {
+            recent,
+            current,
+            visible: false,
+            selection: 0,
+            scroll_top: Cell::new(0),
+            current_height: Cell::new(0),
+            input: TextInputComponent::new(
+                theme.clone(),
+                key_config.clone(),
+                &strings::repo_path_popup_title(&key_config),
+                &strings::repo_path_popup_msg(&key_config),
+                false,
+            )
+            .with_input_type(InputType::Singleline),
+            queue,
+            theme,
+            key_config,
+        }
+    }
+
+    ///
+    pub fn open(&mut self) -> Result<()> {
+        self.selection = 0;
+        self.show()?;
+
+        Ok(())
+    }
+
+    fn selected_repo(&self) -> Option<String> {
+        self.recent.repos().get(self.selection).cloned()
+    }
+
+    fn selection_is_current(&self) -> bool {
+        self.current.is_some() && self.selected_repo() == self.current
+    }
+
+    fn move_selection(&mut self, scroll: ScrollType) {
+        let page = self.current_height.get().saturating_sub(1);
+        let max = self.recent.repos().len().saturating_sub(1);
+
+        let new_selection = match scroll {
+            ScrollType::Up => self.selection.saturating_sub(1),
+            ScrollType::Down => self.selection.saturating_add(1),
+            ScrollType::PageUp => self.selection.saturating_sub(page),
+            ScrollType::PageDown => {
+                self.selection.saturating_add(page)
+            }
+            ScrollType::Home => 0,
+            ScrollType::End => max,
+        };
+
+        self.selection = new_selection.min(max);
+    }
+
+    /// resolves the workdir of the repository `path` is in and asks
+    /// the app to switch over to it
+    fn switch_to(&mut self, path: &str) -> Result<()> {
+        let work_dir = repo_work_dir(&path.into())?;
+
+        self.recent.add(&work_dir);
+        self.hide();
+
+        self.queue
+            .borrow_mut()
+            .push_back(InternalEvent::SwitchRepo(work_dir));
+
+        Ok(())
+    }
+}
@@ -35,13 +35,18 @@ impl DrawableComponent for ResetComponent {
 
             let txt = Text::styled(
                 Cow::from(msg),
-                self.theme.text_danger(),
+                self.theme.borrow().text_danger(),
             );
 
             let area = ui::centered_rect(50, 20, f.size());
             f.render_widget(Clear, area);
             f.render_widget(
-                popup_paragraph(&title, txt, &self.theme, true),
+                popup_paragraph(
+                    &title,
+                    txt,
+                    &self.theme.borrow(),
+                    true,
+                ),
                 area,
             );
         }
@@ -73,9 +78,9 @@ impl Component for ResetComponent {
     fn event(&mut self, ev: Event) -> Result<EventState> {
         if self.visible {
             if let Event::Key(e) = ev {
-                if e == self.key_config.exit_popup {
+                if e == self.key_config.borrow().exit_popup {
                     self.hide();
-                } else if e == self.key_config.enter {
+                } else if e == self.key_config.borrow().enter {
                     self.confirm();
                 }
 
@@ -159,6 +164,10 @@ impl ResetComponent {
                     strings::confirm_title_reset(),
                     strings::confirm_msg_reset_lines(lines.len()),
                 ),
+                Action::ResetPaths(paths) => (
+                    strings::confirm_title_reset(),
+                    strings::confirm_msg_reset_paths(paths.len()),
+                ),
                 Action::DeleteBranch(branch_ref) => (
                     strings::confirm_title_delete_branch(
                         &self.key_config,
@@ -168,6 +177,21 @@ impl ResetComponent {
                         branch_ref,
                     ),
                 ),
+                Action::FlowFinish(kind, name) => (
+                    strings::confirm_title_flow_finish(*kind),
+                    strings::confirm_msg_flow_finish(*kind, name),
+                ),
+                Action::DeleteTags(tags) => (
+                    strings::confirm_title_delete_tags(),
+                    strings::confirm_msg_delete_tags(tags),
+                ),
+                Action::Retag(tag, id) => (
+                    strings::confirm_title_retag(),
+                    strings::confirm_msg_retag(
+                        tag,
+                        &id.get_short_string(),
+                    ),
+                ),
                 Action::ForcePush(branch, _force) => (
                     strings::confirm_title_force_push(
                         &self.key_config,
@@ -177,14 +201,36 @@ impl ResetComponent {
                         branch.rsplit('/').next().expect("There was no / in the head reference which is impossible in git"),
                     ),
                 ),
-                Action::PullMerge{incoming,rebase} => (
+                Action::PullMerge{incoming,rebase,stat} => (
                     strings::confirm_title_merge(&self.key_config,*rebase),
-                    strings::confirm_msg_merge(&self.key_config,*incoming,*rebase),
+                    strings::confirm_msg_merge(&self.key_config,*incoming,*rebase,*stat),
                 ),
                 Action::AbortMerge => (
                     strings::confirm_title_abortmerge(),
                     strings::confirm_msg_abortmerge(),
                 ),
+                Action::AbortRebase => (
+                    strings::confirm_title_abortrebase(),
+                    strings::confirm_msg_abortrebase(),
+                ),
+                Action::DiscardAll => (
+                    strings::confirm_title_discard_all(),
+                    strings::confirm_msg_discard_all(),
+                ),
+                Action::UndoCommit => (
+                    strings::confirm_title_undo_commit(),
+                    strings::confirm_msg_undo_commit(),
+                ),
+                Action::ResetToCommit(id, _) => (
+                    strings::confirm_title_reset_hard(),
+                    strings::confirm_msg_reset_hard(
+                        &id.get_short_string(),
+                    ),
+                ),
+                Action::ForceCheckout(_, name) => (
+                    strings::confirm_title_force_checkout(),
+                    strings::confirm_msg_force_checkout(name),
+                ),
             };
         }
 
@@ -0,0 +1,207 @@
+use super::{
+    popup_paragraph, visibility_blocking, CommandBlocking,
+    CommandInfo, Component, DrawableComponent, EventState,
+};
+use crate::{
+    keys::SharedKeyConfig,
+    queue::{Action, InternalEvent, Queue},
+    strings,
+    ui::{self, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::sync::{CommitId, ResetType};
+use crossterm::event::Event;
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    text::{Span, Spans},
+    widgets::Clear,
+    Frame,
+};
+
+const RESET_TYPES: [ResetType; 3] =
+    [ResetType::Soft, ResetType::Mixed, ResetType::Hard];
+
+/// lets the user choose how to reset the current branch to a commit
+pub struct ResetCommitComponent {
+    commit: Option<CommitId>,
+    selection: usize,
+    visible: bool,
+    queue: Queue,
+    theme: SharedTheme,
+    key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for ResetCommitComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        if self.visible {
+            let mut lines: Vec<Spans> = RESET_TYPES
+                .iter()
+                .enumerate()
+                .map(|(idx, kind)| {
+                    let (name, desc) =
+                        strings::reset_commit_type(*kind);
+                    Spans::from(Span::styled(
+                        format!("{:6} {}", name, desc),
+                        self.theme
+                            .borrow()
+                            .text(true, idx == self.selection),
+                    ))
+                })
+                .collect();
+
+            if self.selected_type() == ResetType::Hard {
+                lines.push(Spans::default());
+                lines.push(Spans::from(Span::styled(
+                    strings::reset_commit_hard_warning(),
+                    self.theme.borrow().text_danger(),
+                )));
+            }
+
+            let title = strings::reset_commit_popup_title(
+                &self
+                    .commit
+                    .map(|id| id.get_short_string())
+                    .unwrap_or_default(),
+            );
+
+            let area = ui::centered_rect_absolute(60, 8, rect);
+            f.render_widget(Clear, area);
+            f.render_widget(
+                popup_paragraph(
+                    &title,
+                    lines,
+                    &self.theme.borrow(),
+                    true,
+                ),
+                area,
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl Component for ResetCommitComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.visible || force_all {
+            out.clear();
+
+            out.push(CommandInfo::new(
+                strings::commands::scroll(&self.key_config),
+                true,
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::close_popup(&self.key_config),
+                true,
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::reset_commit_confirm(
+                    &self.key_config,
+                ),
+                true,
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<EventState> {
+        if self.visible {
+            if let Event::Key(e) = ev {
+                if e == self.key_config.borrow().exit_popup {
+                    self.hide();
+                } else if e == self.key_config.borrow().move_down {
+                    self.selection = self
+                        .selection
+                        .saturating_add(1)
+                        .min(RESET_TYPES.len() - 1);
+                } else if e == self.key_config.borrow().move_up {
+                    self.selection = self.selection.saturating_sub(1);
+                } else if e == self.key_config.borrow().enter {
+                    self.confirm();
+                }
+            }
+
+            return Ok(EventState::Consumed);
+        }
+
+        Ok(EventState::NotConsumed)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+
+        Ok(())
+    }
+}
+
+impl ResetCommitComponent {
+    ///
+    pub fn new(
+        queue: Queue,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+    ) -> Self {
+        Self {
+            commit: None,
+            selection: 0,
+            visible: false,
+            queue,
+            theme,
+            key_config,
+        }
+    }
+
+    ///
+    pub fn open(&mut self, id: CommitId) -> Result<()> {
+        self.commit = Some(id);
+        self.selection = 0;
+        self.show()
+    }
+
+    fn selected_type(&self) -> ResetType {
+        RESET_TYPES[self.selection]
+    }
+
+    /// soft and mixed resets keep all changes and are done right
+    /// away, a hard reset needs another confirmation
+    fn confirm(&mut self) {
+        self.hide();
+
+        if let Some(id) = self.commit.take() {
+            let kind = self.selected_type();
+            let action = Action::ResetToCommit(id, kind);
+
+            // run as an action anyway to be journaled
+            self.queue.borrow_mut().push_back(
+                if kind == ResetType::Hard {
+                    InternalEvent::ConfirmAction(action)
+                } else {
+                    InternalEvent::ConfirmedAction(action)
+                },
+            );
+        }
+    }
+}
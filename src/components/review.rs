@@ -0,0 +1,744 @@
+use super::{
+    textinput::TextInputComponent, visibility_blocking,
+    CommandBlocking, CommandInfo, Component, DiffComponent,
+    DrawableComponent, EventState, FileTreeComponent, InputType,
+    ScrollType,
+};
+use crate::{
+    keys::SharedKeyConfig,
+    options::SharedOptions,
+    queue::{InternalEvent, Queue},
+    review_notes::{ReviewNote, ReviewNotes},
+    strings, try_or_popup,
+    ui::{self, calc_scroll_top, style::SharedTheme},
+};
+use anyhow::Result;
+use asyncgit::{
+    cached,
+    sync::{self, CommitId, RepoPath},
+    AsyncDiff, AsyncNotification, DiffParams, DiffType, StatusItem,
+};
+use crossbeam_channel::Sender;
+use crossterm::event::Event;
+use std::cell::Cell;
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Rect},
+    text::Span,
+    widgets::Clear,
+    Frame,
+};
+
+/// commits compared in a review
+struct ReviewRange {
+    /// `base...branch`, viewed files are persisted under this key
+    name: String,
+    /// merge base of the base ref and the reviewed branch
+    from: CommitId,
+    to: CommitId,
+}
+
+/// lists all files changed on the current branch compared to a base
+/// ref and keeps track of the ones marked as viewed
+pub struct ReviewComponent {
+    repo: RepoPath,
+    input: TextInputComponent,
+    range: Option<ReviewRange>,
+    files: Vec<StatusItem>,
+    selection: usize,
+    scroll_top: Cell<usize>,
+    current_height: Cell<usize>,
+    diff: DiffComponent,
+    git_diff: AsyncDiff,
+    git_branch_name: cached::BranchName,
+    notes: ReviewNotes,
+    note_input: TextInputComponent,
+    /// path, line and side the note being edited is attached to
+    note_line: Option<(String, u32, bool)>,
+    visible: bool,
+    queue: Queue,
+    theme: SharedTheme,
+    key_config: SharedKeyConfig,
+    options: SharedOptions,
+}
+
+impl DrawableComponent for ReviewComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        if self.is_visible() {
+            if let Some(range) = &self.range {
+                let percentages = if self.diff.focused() {
+                    (30, 70)
+                } else {
+                    (50, 50)
+                };
+
+                let chunks = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints(
+                        [
+                            Constraint::Percentage(percentages.0),
+                            Constraint::Percentage(percentages.1),
+                        ]
+                        .as_ref(),
+                    )
+                    .split(rect);
+
+                f.render_widget(Clear, rect);
+
+                let notes = self.selected_file_notes(range);
+                if notes.is_empty() {
+                    self.draw_files(f, chunks[0], range);
+                } else {
+                    let left = Layout::default()
+                        .direction(Direction::Vertical)
+                        .constraints(
+                            [
+                                Constraint::Percentage(70),
+                                Constraint::Percentage(30),
+                            ]
+                            .as_ref(),
+                        )
+                        .split(chunks[0]);
+
+                    self.draw_files(f, left[0], range);
+                    self.draw_notes(f, left[1], &notes);
+                }
+                self.diff.draw(f, chunks[1])?;
+            }
+
+            self.input.draw(f, rect)?;
+            self.note_input.draw(f, rect)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Component for ReviewComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.is_visible() || force_all {
+            if self.input.is_visible() {
+                self.input.commands(out, force_all);
+
+                out.push(CommandInfo::new(
+                    strings::commands::review_base_confirm_msg(
+                        &self.key_config,
+                    ),
+                    true,
+                    true,
+                ));
+
+                return visibility_blocking(self);
+            }
+
+            if self.note_input.is_visible() {
+                self.note_input.commands(out, force_all);
+
+                out.push(CommandInfo::new(
+                    strings::commands::review_note_confirm_msg(
+                        &self.key_config,
+                    ),
+                    true,
+                    true,
+                ));
+
+                return visibility_blocking(self);
+            }
+
+            self.diff.commands(out, force_all);
+
+            out.push(
+                CommandInfo::new(
+                    strings::commands::close_popup(&self.key_config),
+                    true,
+                    true,
+                )
+                .order(1),
+            );
+
+            out.push(CommandInfo::new(
+                strings::commands::diff_focus_right(&self.key_config),
+                self.selected_file().is_some(),
+                !self.diff.focused() || force_all,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::diff_focus_left(&self.key_config),
+                true,
+                self.diff.focused() || force_all,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::review_toggle_viewed(
+                    &self.key_config,
+                ),
+                self.selected_file().is_some(),
+                !self.diff.focused() || force_all,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::review_note(&self.key_config),
+                self.diff.selected_line_position().is_some(),
+                self.diff.focused() || force_all,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::review_export_notes(
+                    &self.key_config,
+                ),
+                self.range.as_ref().map_or(false, |range| {
+                    !self.notes.notes(&range.name).is_empty()
+                }),
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<EventState> {
+        if self.is_visible() {
+            if self.input.is_visible() {
+                if self.input.event(ev)?.is_consumed() {
+                    // closing the input without a review closes us
+                    if !self.input.is_visible()
+                        && self.range.is_none()
+                    {
+                        self.hide();
+                    }
+                    return Ok(EventState::Consumed);
+                }
+
+                if let Event::Key(e) = ev {
+                    if e == self.key_config.borrow().enter {
+                        let base = self.input.get_text().clone();
+                        try_or_popup!(
+                            self,
+                            "review error:",
+                            self.start(base.trim())
+                        );
+                        if self.range.is_none() {
+                            self.hide();
+                        }
+                    }
+                }
+
+                return Ok(EventState::Consumed);
+            }
+
+            if self.note_input.is_visible() {
+                if self.note_input.event(ev)?.is_consumed() {
+                    return Ok(EventState::Consumed);
+                }
+
+                if let Event::Key(e) = ev {
+                    if e == self.key_config.borrow().enter {
+                        self.save_note();
+                    }
+                }
+
+                return Ok(EventState::Consumed);
+            }
+
+            if self.diff.event(ev)?.is_consumed() {
+                return Ok(EventState::Consumed);
+            }
+
+            if let Event::Key(e) = ev {
+                if e == self.key_config.borrow().exit_popup {
+                    self.hide();
+                } else if e == self.key_config.borrow().focus_right
+                    && self.selected_file().is_some()
+                {
+                    self.diff.focus(true);
+                } else if e == self.key_config.borrow().focus_left {
+                    self.diff.focus(false);
+                } else if e
+                    == self.key_config.borrow().review_export_notes
+                {
+                    try_or_popup!(
+                        self,
+                        "copy to clipboard error:",
+                        self.export_notes()
+                    );
+                } else if self.diff.focused() {
+                    if e == self.key_config.borrow().review_note {
+                        self.open_note()?;
+                    }
+                } else {
+                    if e == self.key_config.borrow().move_down {
+                        self.move_selection(ScrollType::Down)?;
+                    } else if e == self.key_config.borrow().move_up {
+                        self.move_selection(ScrollType::Up)?;
+                    } else if e == self.key_config.borrow().page_down
+                    {
+                        self.move_selection(ScrollType::PageDown)?;
+                    } else if e == self.key_config.borrow().page_up {
+                        self.move_selection(ScrollType::PageUp)?;
+                    } else if e == self.key_config.borrow().home
+                        || e == self.key_config.borrow().shift_up
+                    {
+                        self.move_selection(ScrollType::Home)?;
+                    } else if e == self.key_config.borrow().end
+                        || e == self.key_config.borrow().shift_down
+                    {
+                        self.move_selection(ScrollType::End)?;
+                    } else if e
+                        == self
+                            .key_config
+                            .borrow()
+                            .review_toggle_viewed
+                    {
+                        self.toggle_viewed()?;
+                    }
+                }
+
+                return Ok(EventState::Consumed);
+            }
+        }
+
+        Ok(EventState::NotConsumed)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn hide(&mut self) {
+        self.visible = false;
+        self.input.hide();
+        self.note_input.hide();
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+
+        Ok(())
+    }
+}
+
+impl ReviewComponent {
+    ///
+    pub fn new(
+        repo: RepoPath,
+        sender: &Sender<AsyncNotification>,
+        queue: Queue,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+        options: SharedOptions,
+    ) -> Self {
+        Self {
+            input: TextInputComponent::new(
+                theme.clone(),
+                key_config.clone(),
+                &strings::review_base_popup_title(&key_config),
+                &strings::review_base_popup_msg(&key_config),
+                true,
+            )
+            .with_input_type(InputType::Singleline),
+            range: None,
+            files: Vec::new(),
+            selection: 0,
+            scroll_top: Cell::new(0),
+            current_height: Cell::new(0),
+            diff: DiffComponent::new(
+                repo.clone(),
+                queue.clone(),
+                sender,
+                theme.clone(),
+                key_config.clone(),
+                options.clone(),
+                true,
+            ),
+            git_diff: AsyncDiff::new(repo.clone(), sender),
+            git_branch_name: cached::BranchName::new(repo.clone()),
+            notes: ReviewNotes::new(repo.clone()),
+            note_input: TextInputComponent::new(
+                theme.clone(),
+                key_config.clone(),
+                &strings::review_note_popup_title(&key_config),
+                &strings::review_note_popup_msg(&key_config),
+                true,
+            )
+            .with_input_type(InputType::Singleline),
+            note_line: None,
+            visible: false,
+            queue,
+            theme,
+            key_config,
+            options,
+            repo,
+        }
+    }
+
+    /// asks for the base ref, prefilled with the last one used
+    pub fn open(&mut self) -> Result<()> {
+        let base = self
+            .options
+            .borrow()
+            .review_base()
+            .map(ToString::to_string)
+            .unwrap_or_default();
+
+        self.range = None;
+        self.files.clear();
+        self.input.set_text(base);
+        self.input.show()?;
+        self.show()?;
+
+        Ok(())
+    }
+
+    ///
+    pub fn any_work_pending(&self) -> bool {
+        self.git_diff.is_pending() || self.diff.any_work_pending()
+    }
+
+    ///
+    pub fn update_git(
+        &mut self,
+        ev: AsyncNotification,
+    ) -> Result<()> {
+        if self.is_visible() {
+            if let AsyncNotification::Diff = ev {
+                self.update_diff()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// diffs the selected file between the merge base and the
+    /// reviewed commit
+    pub fn update_diff(&mut self) -> Result<()> {
+        if self.is_visible() {
+            let diff_params =
+                match (&self.range, self.selected_file()) {
+                    (Some(range), Some(file)) => Some(DiffParams {
+                        path: file.path.clone(),
+                        diff_type: DiffType::Commits(
+                            range.from, range.to,
+                        ),
+                        options: self.options.borrow().diff_options(),
+                    }),
+                    _ => None,
+                };
+
+            if let Some(diff_params) = diff_params {
+                if let Some((params, last)) = self.git_diff.last()? {
+                    if params == diff_params {
+                        self.diff.update(
+                            diff_params.path,
+                            false,
+                            last,
+                        )?;
+                        return Ok(());
+                    }
+                }
+
+                self.git_diff.request(diff_params)?;
+                self.diff.clear(true)?;
+                return Ok(());
+            }
+
+            self.diff.clear(false)?;
+        }
+
+        Ok(())
+    }
+
+    /// lists the files changed between the merge base of `base` and
+    /// `HEAD`
+    fn start(&mut self, base: &str) -> Result<()> {
+        let to = sync::get_head(&self.repo)?;
+        let from = sync::merge_base(
+            &self.repo,
+            sync::resolve_rev(&self.repo, base)?,
+            to,
+        )?;
+        let branch = self.current_branch(to);
+
+        self.options.borrow_mut().set_review_base(base);
+
+        self.set_range(format!("{}...{}", base, branch), from, to)?;
+        self.update_diff()
+    }
+
+    /// reviews the changes on `branch` (e.g. a colleague's remote
+    /// branch) compared to the current one, nothing gets checked out
+    pub fn open_branch(&mut self, branch: &str) -> Result<()> {
+        let head = sync::get_head(&self.repo)?;
+        let to = sync::resolve_rev(&self.repo, branch)?;
+        let from = sync::merge_base(&self.repo, head, to)?;
+        let current = self.current_branch(head);
+
+        self.set_range(
+            format!("{}...{}", current, branch),
+            from,
+            to,
+        )?;
+        self.show()?;
+        self.update_diff()
+    }
+
+    /// name of the checked out branch, the short `head` if detached
+    fn current_branch(&mut self, head: CommitId) -> String {
+        self.git_branch_name
+            .lookup()
+            .unwrap_or_else(|_| head.get_short_string())
+    }
+
+    fn set_range(
+        &mut self,
+        name: String,
+        from: CommitId,
+        to: CommitId,
+    ) -> Result<()> {
+        self.files = sync::get_compare_files(&self.repo, from, to)?;
+        self.range = Some(ReviewRange { name, from, to });
+        self.selection = 0;
+        self.scroll_top.set(0);
+        self.input.hide();
+        self.diff.focus(false);
+
+        Ok(())
+    }
+
+    /// opens the input for the note on the selected diff line
+    fn open_note(&mut self) -> Result<()> {
+        if let (Some(range), Some(file), Some(pos)) = (
+            &self.range,
+            self.selected_file(),
+            self.diff.selected_line_position(),
+        ) {
+            let (line, old) = match (pos.new_lineno, pos.old_lineno) {
+                (Some(line), _) => (line, false),
+                (None, Some(line)) => (line, true),
+                (None, None) => return Ok(()),
+            };
+
+            let text = self
+                .notes
+                .get(&range.name, &file.path, line, old)
+                .map(|note| note.text.clone())
+                .unwrap_or_default();
+
+            self.note_line = Some((file.path.clone(), line, old));
+            self.note_input.set_text(text);
+            self.note_input.show()?;
+        }
+
+        Ok(())
+    }
+
+    fn save_note(&mut self) {
+        if let (Some(range), Some((path, line, old))) =
+            (&self.range, self.note_line.take())
+        {
+            self.notes.set(
+                &range.name,
+                ReviewNote {
+                    path,
+                    line,
+                    old,
+                    text: self
+                        .note_input
+                        .get_text()
+                        .trim()
+                        .to_string(),
+                },
+            );
+        }
+
+        self.note_input.clear();
+        self.note_input.hide();
+    }
+
+    fn export_notes(&self) -> Result<()> {
+        if let Some(range) = &self.range {
+            crate::clipboard::copy_string(
+                &self.notes.export(&range.name),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn selected_file_notes(
+        &self,
+        range: &ReviewRange,
+    ) -> Vec<&ReviewNote> {
+        self.selected_file().map_or_else(Vec::new, |file| {
+            self.notes
+                .notes(&range.name)
+                .iter()
+                .filter(|note| note.path == file.path)
+                .collect()
+        })
+    }
+
+    fn selected_file(&self) -> Option<&StatusItem> {
+        self.files.get(self.selection)
+    }
+
+    fn is_viewed(&self, path: &str) -> bool {
+        self.range.as_ref().map_or(false, |range| {
+            self.options
+                .borrow()
+                .review_viewed(&range.name)
+                .iter()
+                .any(|viewed| viewed == path)
+        })
+    }
+
+    /// marking a file as viewed moves on to the next unviewed one
+    fn toggle_viewed(&mut self) -> Result<()> {
+        let (name, path) = match (&self.range, self.selected_file()) {
+            (Some(range), Some(file)) => {
+                (range.name.clone(), file.path.clone())
+            }
+            _ => return Ok(()),
+        };
+
+        self.options.borrow_mut().toggle_review_viewed(&name, &path);
+
+        if self.is_viewed(&path) {
+            if let Some(next) = self
+                .files
+                .iter()
+                .enumerate()
+                .skip(self.selection)
+                .chain(self.files.iter().enumerate())
+                .find(|(_, file)| !self.is_viewed(&file.path))
+                .map(|(idx, _)| idx)
+            {
+                self.selection = next;
+                self.update_diff()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn move_selection(&mut self, scroll: ScrollType) -> Result<()> {
+        let page = self.current_height.get().saturating_sub(1);
+        let max = self.files.len().saturating_sub(1);
+
+        let new_selection = match scroll {
+            ScrollType::Up => self.selection.saturating_sub(1),
+            ScrollType::Down => self.selection.saturating_add(1),
+            ScrollType::PageUp => self.selection.saturating_sub(page),
+            ScrollType::PageDown => {
+                self.selection.saturating_add(page)
+            }
+            ScrollType::Home => 0,
+            ScrollType::End => max,
+        };
+
+        let new_selection = new_selection.min(max);
+        if new_selection != self.selection {
+            self.selection = new_selection;
+            self.update_diff()?;
+        }
+
+        Ok(())
+    }
+
+    fn draw_files<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        area: Rect,
+        range: &ReviewRange,
+    ) {
+        let height = usize::from(area.height.saturating_sub(2));
+        self.current_height.set(height);
+
+        self.scroll_top.set(calc_scroll_top(
+            self.scroll_top.get(),
+            height,
+            self.selection,
+        ));
+
+        let viewed = self
+            .files
+            .iter()
+            .filter(|file| self.is_viewed(&file.path))
+            .count();
+
+        let items = self
+            .files
+            .iter()
+            .enumerate()
+            .skip(self.scroll_top.get())
+            .take(height)
+            .map(|(idx, file)| {
+                let selected = idx == self.selection;
+                let is_viewed = self.is_viewed(&file.path);
+                Span::styled(
+                    format!(
+                        "{} {} {}",
+                        if is_viewed { "[x]" } else { "[ ]" },
+                        FileTreeComponent::item_status_char(
+                            file.status
+                        ),
+                        file.path
+                    ),
+                    if is_viewed {
+                        self.theme.borrow().text(false, selected)
+                    } else {
+                        self.theme
+                            .borrow()
+                            .item(file.status, selected)
+                    },
+                )
+            });
+
+        ui::draw_list(
+            f,
+            area,
+            &strings::title_review(
+                &range.name,
+                viewed,
+                self.files.len(),
+            ),
+            items,
+            !self.diff.focused(),
+            &self.theme,
+        );
+    }
+
+    fn draw_notes<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        area: Rect,
+        notes: &[&ReviewNote],
+    ) {
+        let height = usize::from(area.height.saturating_sub(2));
+
+        let items = notes.iter().take(height).map(|note| {
+            Span::styled(
+                strings::review_note_entry(
+                    note.line, note.old, &note.text,
+                ),
+                self.theme.borrow().text(true, false),
+            )
+        });
+
+        ui::draw_list(
+            f,
+            area,
+            &strings::title_review_notes(),
+            items,
+            false,
+            &self.theme,
+        );
+    }
+}
@@ -1,39 +1,85 @@
 use super::{
+    utils::{fuzzy_match, time_to_string},
     visibility_blocking, CommandBlocking, CommandInfo, Component,
-    DrawableComponent, EventState,
+    DrawableComponent, EventState, ScrollType,
 };
 use crate::{
     keys::SharedKeyConfig,
-    queue::Queue,
-    strings,
-    ui::{self, style::SharedTheme},
+    queue::{InternalEvent, Queue},
+    strings, try_or_popup,
+    ui::{
+        self, calc_scroll_top,
+        markdown::{is_markdown, markdown_lines},
+        style::SharedTheme,
+        syntax_text::SyntaxHighlighter,
+        HexView, ImagePreview,
+    },
 };
 use anyhow::Result;
 use asyncgit::{
-    sync::{self, CommitId, TreeFile},
-    AsyncNotification, CWD,
+    sync::{self, CommitId, ContentType, RepoPath, TreeFile},
+    AsyncNotification,
 };
+use bytesize::ByteSize;
 use crossbeam_channel::Sender;
-use crossterm::event::Event;
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use std::{
+    cell::Cell,
+    env, fs,
+    path::{Path, PathBuf},
+};
 use tui::{
-    backend::Backend, layout::Rect, text::Span, widgets::Clear, Frame,
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Rect},
+    text::{Span, Spans, Text},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
 };
 
+/// height of the info lines above an image preview or hex view
+const INFO_HEIGHT: u16 = 3;
+
+/// content of a single file opened from the tree
+struct FileContent {
+    title: String,
+    path: PathBuf,
+    lines: Vec<Spans<'static>>,
+    /// source of a markdown file, shown rendered unless `raw`
+    markdown: Option<String>,
+    raw: bool,
+    preview: Option<ImagePreview>,
+    hex: Option<HexView>,
+    scroll_top: usize,
+    /// hex digits of the offset being typed to jump to
+    offset_editing: Option<String>,
+}
+
 pub struct RevisionFilesComponent {
+    repo: RepoPath,
     title: String,
     theme: SharedTheme,
-    // queue: Queue,
+    queue: Queue,
     files: Vec<TreeFile>,
+    /// indices into `files` matching `filter`
+    filtered: Vec<usize>,
+    filter: String,
+    filter_editing: bool,
+    selection: usize,
+    content: Option<FileContent>,
     revision: Option<CommitId>,
+    /// shows which commit the tree is from above it
+    banner: Vec<Span<'static>>,
     visible: bool,
     key_config: SharedKeyConfig,
-    current_height: std::cell::Cell<usize>,
+    current_height: Cell<usize>,
+    scroll_top: Cell<usize>,
 }
 
 impl RevisionFilesComponent {
     ///
     pub fn new(
-        _queue: &Queue,
+        repo: RepoPath,
+        queue: &Queue,
         _sender: &Sender<AsyncNotification>,
         theme: SharedTheme,
         key_config: SharedKeyConfig,
@@ -42,28 +88,642 @@ impl RevisionFilesComponent {
             title: String::new(),
             theme,
             files: Vec::new(),
+            filtered: Vec::new(),
+            filter: String::new(),
+            filter_editing: false,
+            selection: 0,
+            content: None,
             revision: None,
-            // queue: queue.clone(),
+            banner: Vec::new(),
+            queue: queue.clone(),
             visible: false,
             key_config,
-            current_height: std::cell::Cell::new(0),
+            current_height: Cell::new(0),
+            scroll_top: Cell::new(0),
+            repo,
         }
     }
 
     ///
     pub fn open(&mut self, commit: CommitId) -> Result<()> {
-        self.files = sync::tree_files(CWD, commit)?;
-        self.revision = Some(commit);
-        self.title = format!(
-            "File Tree at {}",
-            self.revision
-                .map(|c| c.get_short_string())
-                .unwrap_or_default()
-        );
+        self.files.clear();
+        self.filter.clear();
+        self.filter_editing = false;
+        self.content = None;
+        self.scroll_top.set(0);
+        self.load(commit)?;
         self.show()?;
 
         Ok(())
     }
+
+    /// shows the tree at `commit`, the selected file stays selected
+    /// (and open) if it exists there
+    fn load(&mut self, commit: CommitId) -> Result<()> {
+        let selected = self
+            .selected_index()
+            .and_then(|idx| self.files.get(idx))
+            .map(|file| file.path.clone());
+        let scroll_top =
+            self.content.take().map(|content| content.scroll_top);
+
+        self.files = sync::tree_files(&self.repo, commit)?;
+        self.revision = Some(commit);
+        self.title =
+            format!("File Tree at {}", commit.get_short_string());
+        self.banner = self.banner_spans(commit)?;
+        self.update_filtered();
+
+        let position = selected.and_then(|selected| {
+            let files = &self.files;
+            self.filtered
+                .iter()
+                .position(|idx| files[*idx].path == selected)
+        });
+
+        if let Some(position) = position {
+            self.selection = position;
+
+            if let Some(scroll_top) = scroll_top {
+                self.open_selected_file()?;
+                if let Some(content) = &mut self.content {
+                    let len = content
+                        .hex
+                        .as_ref()
+                        .map_or(content.lines.len(), HexView::rows);
+                    content.scroll_top =
+                        scroll_top.min(len.saturating_sub(1));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn banner_spans(
+        &self,
+        commit: CommitId,
+    ) -> Result<Vec<Span<'static>>> {
+        let info = sync::get_commit_info(&self.repo, &commit)?;
+
+        Ok(vec![
+            Span::styled(
+                strings::time_machine_banner(),
+                self.theme.borrow().text_danger(),
+            ),
+            Span::raw(" "),
+            Span::styled(
+                commit.get_short_string(),
+                self.theme.borrow().commit_hash(false),
+            ),
+            Span::raw(" "),
+            Span::styled(
+                time_to_string(info.time, false),
+                self.theme.borrow().commit_time(false),
+            ),
+            Span::raw(" "),
+            Span::styled(
+                info.author,
+                self.theme.borrow().commit_author(false),
+            ),
+            Span::raw(" "),
+            Span::styled(
+                info.message,
+                self.theme.borrow().text(true, false),
+            ),
+        ])
+    }
+
+    /// moves to the previous or next commit, of the open file if
+    /// there is one
+    fn step(&mut self, older: bool) -> Result<()> {
+        if let Some(revision) = self.revision {
+            let path = self.content.as_ref().map(|content| {
+                content
+                    .path
+                    .strip_prefix("./")
+                    .unwrap_or(&content.path)
+                    .to_path_buf()
+            });
+
+            let target = if older {
+                sync::older_commit(
+                    &self.repo,
+                    revision,
+                    path.as_deref(),
+                )?
+            } else {
+                sync::newer_commit(
+                    &self.repo,
+                    revision,
+                    path.as_deref(),
+                )?
+            };
+
+            if let Some(target) = target {
+                self.load(target)?;
+            } else {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::ShowErrorMsg(
+                        strings::time_machine_end(
+                            older,
+                            path.is_some(),
+                        ),
+                    ),
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// writes the selected file at this revision to a temp file and
+    /// opens that in the external editor or pager, for files too
+    /// large for the internal view
+    fn open_selected_external(&self, pager: bool) -> Result<()> {
+        if let (Some(file), Some(revision)) = (
+            self.selected_index().and_then(|idx| self.files.get(idx)),
+            self.revision,
+        ) {
+            let bytes = sync::tree_file_bytes(&self.repo, file)?;
+
+            let name = file.path.file_name().map_or_else(
+                || String::from("file"),
+                |name| name.to_string_lossy().to_string(),
+            );
+            let path = env::temp_dir().join(format!(
+                "gitui-{}-{}",
+                revision.get_short_string(),
+                name
+            ));
+            fs::write(&path, bytes)?;
+
+            let path = path.to_string_lossy().to_string();
+            self.queue.borrow_mut().push_back(if pager {
+                InternalEvent::OpenExternalPager(path)
+            } else {
+                InternalEvent::OpenExternalEditor(Some(path))
+            });
+        }
+
+        Ok(())
+    }
+
+    fn update_filtered(&mut self) {
+        let filter = &self.filter;
+        self.filtered = self
+            .files
+            .iter()
+            .enumerate()
+            .filter(|(_, file)| {
+                fuzzy_match(filter, &file.path.to_string_lossy())
+            })
+            .map(|(idx, _)| idx)
+            .collect();
+        self.selection = 0;
+    }
+
+    /// handles keys while the filter is being typed
+    fn filter_event(&mut self, key: KeyEvent) {
+        if key == self.key_config.borrow().exit_popup {
+            self.filter_editing = false;
+            self.filter.clear();
+        } else if key == self.key_config.borrow().enter {
+            self.filter_editing = false;
+            return;
+        } else if key.code == KeyCode::Backspace {
+            self.filter.pop();
+        } else if let KeyCode::Char(c) = key.code {
+            if key.modifiers.contains(KeyModifiers::CONTROL) {
+                return;
+            }
+            self.filter.push(c);
+        } else {
+            return;
+        }
+
+        self.update_filtered();
+    }
+
+    /// index into `files` of the selected entry
+    fn selected_index(&self) -> Option<usize> {
+        self.filtered.get(self.selection).copied()
+    }
+
+    fn copy_selected_path(&self) -> Result<()> {
+        if let Some(file) =
+            self.selected_index().and_then(|idx| self.files.get(idx))
+        {
+            // `tree_files` starts all paths with `./`
+            let path =
+                file.path.strip_prefix("./").unwrap_or(&file.path);
+
+            crate::clipboard::copy_string(&path.to_string_lossy())?;
+        }
+
+        Ok(())
+    }
+
+    fn open_selected_file(&mut self) -> Result<()> {
+        if let Some(file) =
+            self.selected_index().and_then(|idx| self.files.get(idx))
+        {
+            let bytes = sync::tree_file_bytes(&self.repo, file)?;
+
+            let mut markdown = None;
+            let (lines, preview, hex) =
+                match sync::content_type(&bytes) {
+                    ContentType::Text => {
+                        let text = String::from_utf8_lossy(&bytes)
+                            .to_string();
+                        let markdown_file = is_markdown(&file.path);
+                        let lines = self.file_lines(
+                            &file.path,
+                            &text,
+                            markdown_file,
+                        );
+                        if markdown_file {
+                            markdown = Some(text);
+                        }
+                        (lines, None, None)
+                    }
+                    ContentType::Image(image) => {
+                        let preview = ImagePreview::new(&bytes);
+                        let info = strings::image_info(
+                            image,
+                            preview
+                                .as_ref()
+                                .map(ImagePreview::dimensions),
+                        );
+                        (
+                            self.info_lines(info, bytes.len()),
+                            preview,
+                            None,
+                        )
+                    }
+                    ContentType::Binary => (
+                        self.info_lines(
+                            strings::binary_info(),
+                            bytes.len(),
+                        ),
+                        None,
+                        Some(HexView::new(bytes)),
+                    ),
+                };
+
+            self.content = Some(FileContent {
+                title: format!(
+                    "{} -- {}",
+                    self.title,
+                    file.path.to_string_lossy()
+                ),
+                path: file.path.clone(),
+                lines,
+                markdown,
+                raw: false,
+                preview,
+                hex,
+                scroll_top: 0,
+                offset_editing: None,
+            });
+        }
+
+        Ok(())
+    }
+
+    fn info_lines(
+        &self,
+        info: String,
+        size: usize,
+    ) -> Vec<Spans<'static>> {
+        vec![
+            Spans::from(Span::styled(
+                info,
+                self.theme.borrow().text(true, false),
+            )),
+            Spans::from(Span::styled(
+                format!("size: {}", ByteSize::b(size as u64)),
+                self.theme.borrow().text(false, false),
+            )),
+        ]
+    }
+
+    fn file_lines(
+        &self,
+        path: &Path,
+        text: &str,
+        markdown: bool,
+    ) -> Vec<Spans<'static>> {
+        if markdown {
+            markdown_lines(text, None, &self.theme.borrow())
+        } else {
+            self.text_lines(path, text)
+        }
+    }
+
+    fn toggle_markdown_raw(&mut self) {
+        if let Some(mut content) = self.content.take() {
+            if let Some(text) = &content.markdown {
+                content.raw = !content.raw;
+                content.lines = self.file_lines(
+                    &content.path,
+                    text,
+                    !content.raw,
+                );
+                content.scroll_top = 0;
+            }
+            self.content = Some(content);
+        }
+    }
+
+    fn is_markdown_visible(&self) -> bool {
+        self.content
+            .as_ref()
+            .map_or(false, |content| content.markdown.is_some())
+    }
+
+    fn text_lines(
+        &self,
+        path: &Path,
+        content: &str,
+    ) -> Vec<Spans<'static>> {
+        self.theme
+            .borrow()
+            .syntax_theme()
+            .and_then(|theme| SyntaxHighlighter::new(path, theme))
+            .map_or_else(
+                || {
+                    content
+                        .lines()
+                        .map(|line| {
+                            Spans::from(Span::styled(
+                                line.replace("\t", "  "),
+                                self.theme.borrow().text(true, false),
+                            ))
+                        })
+                        .collect()
+                },
+                |highlighter| {
+                    highlighter.highlight_text(content.lines())
+                },
+            )
+    }
+
+    fn move_selection(&mut self, scroll: ScrollType) {
+        let page = self.current_height.get().saturating_sub(2);
+
+        if let Some(content) = &mut self.content {
+            // the info lines stay above the hex rows
+            let (len, page) = content.hex.as_ref().map_or(
+                (content.lines.len(), page),
+                |hex| {
+                    (
+                        hex.rows(),
+                        page.saturating_sub(INFO_HEIGHT.into()),
+                    )
+                },
+            );
+            let max = len.saturating_sub(page);
+            content.scroll_top =
+                Self::scroll(content.scroll_top, scroll, page, max);
+        } else {
+            let max = self.filtered.len().saturating_sub(1);
+            self.selection =
+                Self::scroll(self.selection, scroll, page, max);
+        }
+    }
+
+    /// handles keys while the offset to jump to is being typed
+    fn offset_event(&mut self, key: KeyEvent) {
+        let page = self
+            .current_height
+            .get()
+            .saturating_sub(2 + usize::from(INFO_HEIGHT));
+
+        if let Some(content) = &mut self.content {
+            let input = match &mut content.offset_editing {
+                Some(input) => input,
+                None => return,
+            };
+
+            if key == self.key_config.borrow().exit_popup {
+                content.offset_editing = None;
+            } else if key == self.key_config.borrow().enter {
+                let offset = usize::from_str_radix(
+                    input.trim_start_matches("0x"),
+                    16,
+                );
+                if let (Ok(offset), Some(hex)) =
+                    (offset, &content.hex)
+                {
+                    content.scroll_top = HexView::row_of(offset)
+                        .min(hex.rows().saturating_sub(page));
+                }
+                content.offset_editing = None;
+            } else if key.code == KeyCode::Backspace {
+                input.pop();
+            } else if let KeyCode::Char(c) = key.code {
+                if c.is_ascii_hexdigit() || c == 'x' {
+                    input.push(c);
+                }
+            }
+        }
+    }
+
+    fn hex_visible(&self) -> bool {
+        self.content
+            .as_ref()
+            .map_or(false, |content| content.hex.is_some())
+    }
+
+    fn is_offset_editing(&self) -> bool {
+        self.content
+            .as_ref()
+            .map_or(false, |content| content.offset_editing.is_some())
+    }
+
+    fn scroll(
+        current: usize,
+        scroll: ScrollType,
+        page: usize,
+        max: usize,
+    ) -> usize {
+        let new_pos = match scroll {
+            ScrollType::Up => current.saturating_sub(1),
+            ScrollType::Down => current.saturating_add(1),
+            ScrollType::Home => 0,
+            ScrollType::End => max,
+            ScrollType::PageUp => current.saturating_sub(page),
+            ScrollType::PageDown => current.saturating_add(page),
+        };
+
+        new_pos.min(max)
+    }
+
+    fn draw_content<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        area: Rect,
+        content: &FileContent,
+    ) {
+        if let Some(hex) = &content.hex {
+            self.draw_hex(f, area, content, hex);
+            return;
+        }
+
+        let height = usize::from(area.height.saturating_sub(2));
+
+        let lines: Vec<Spans> = content
+            .lines
+            .iter()
+            .skip(content.scroll_top)
+            .take(height)
+            .cloned()
+            .collect();
+
+        let block = Block::default()
+            .title(Span::styled(
+                content.title.as_str(),
+                self.theme.borrow().title(true),
+            ))
+            .borders(Borders::ALL)
+            .border_style(self.theme.borrow().block(true));
+
+        if let Some(preview) = &content.preview {
+            let inner = block.inner(area);
+            f.render_widget(block, area);
+
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints(
+                    [
+                        Constraint::Length(INFO_HEIGHT),
+                        Constraint::Min(1),
+                    ]
+                    .as_ref(),
+                )
+                .split(inner);
+
+            f.render_widget(
+                Paragraph::new(Text::from(lines)),
+                chunks[0],
+            );
+            preview.draw(f, chunks[1]);
+            return;
+        }
+
+        f.render_widget(
+            Paragraph::new(Text::from(lines)).block(block),
+            area,
+        );
+
+        ui::draw_scrollbar(
+            f,
+            area,
+            &self.theme,
+            content.lines.len().saturating_sub(height),
+            content.scroll_top,
+        );
+    }
+
+    fn draw_hex<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        area: Rect,
+        content: &FileContent,
+        hex: &HexView,
+    ) {
+        let title = format!(
+            "{}{}",
+            content.title,
+            content.offset_editing.as_ref().map_or_else(
+                || strings::hex_offset_info(
+                    HexView::offset_of(content.scroll_top),
+                    hex.size()
+                ),
+                |input| strings::hex_goto_offset_info(input.as_str()),
+            )
+        );
+        let block = Block::default()
+            .title(Span::styled(
+                title,
+                self.theme.borrow().title(true),
+            ))
+            .borders(Borders::ALL)
+            .border_style(self.theme.borrow().block(true));
+
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(
+                [Constraint::Length(INFO_HEIGHT), Constraint::Min(1)]
+                    .as_ref(),
+            )
+            .split(inner);
+
+        f.render_widget(
+            Paragraph::new(Text::from(content.lines.clone())),
+            chunks[0],
+        );
+        hex.draw(f, chunks[1], content.scroll_top, &self.theme);
+
+        ui::draw_scrollbar(
+            f,
+            area,
+            &self.theme,
+            hex.rows().saturating_sub(usize::from(chunks[1].height)),
+            content.scroll_top,
+        );
+    }
+
+    /// commands of the file content shown
+    fn commands_content(&self, out: &mut Vec<CommandInfo>) {
+        out.push(
+            CommandInfo::new(
+                strings::commands::markdown_toggle_raw(
+                    &self.key_config,
+                    self.content
+                        .as_ref()
+                        .map_or(false, |content| content.raw),
+                ),
+                true,
+                self.is_markdown_visible(),
+            )
+            .order(1),
+        );
+        out.push(
+            CommandInfo::new(
+                strings::commands::time_machine_older(
+                    &self.key_config,
+                    self.content.is_some(),
+                ),
+                true,
+                true,
+            )
+            .order(1),
+        );
+        out.push(
+            CommandInfo::new(
+                strings::commands::time_machine_newer(
+                    &self.key_config,
+                    self.content.is_some(),
+                ),
+                true,
+                true,
+            )
+            .order(1),
+        );
+        out.push(
+            CommandInfo::new(
+                strings::commands::hex_goto_offset(&self.key_config),
+                true,
+                self.hex_visible(),
+            )
+            .order(1),
+        );
+    }
 }
 
 impl DrawableComponent for RevisionFilesComponent {
@@ -73,24 +733,69 @@ impl DrawableComponent for RevisionFilesComponent {
         area: Rect,
     ) -> Result<()> {
         if self.is_visible() {
-            let items = self.files.iter().map(|f| {
-                Span::styled(
-                    f.path.to_string_lossy(),
-                    self.theme.text(true, false),
+            f.render_widget(Clear, area);
+
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints(
+                    [Constraint::Length(1), Constraint::Min(1)]
+                        .as_ref(),
                 )
-            });
+                .split(area);
 
-            f.render_widget(Clear, area);
-            ui::draw_list(
-                f,
-                area,
-                &self.title,
-                items,
-                true,
-                &self.theme,
+            f.render_widget(
+                Paragraph::new(Spans::from(self.banner.clone())),
+                chunks[0],
             );
 
+            let area = chunks[1];
+
             self.current_height.set(area.height.into());
+
+            if let Some(content) = &self.content {
+                self.draw_content(f, area, content);
+                return Ok(());
+            }
+
+            let height = usize::from(area.height.saturating_sub(2));
+
+            self.scroll_top.set(calc_scroll_top(
+                self.scroll_top.get(),
+                height,
+                self.selection,
+            ));
+
+            let items = self
+                .filtered
+                .iter()
+                .filter_map(|idx| self.files.get(*idx))
+                .enumerate()
+                .skip(self.scroll_top.get())
+                .take(height)
+                .map(|(idx, f)| {
+                    Span::styled(
+                        f.path.to_string_lossy(),
+                        self.theme
+                            .borrow()
+                            .text(true, idx == self.selection),
+                    )
+                });
+
+            let title =
+                if self.filter.is_empty() && !self.filter_editing {
+                    self.title.clone()
+                } else {
+                    format!(
+                        "{}{}",
+                        self.title,
+                        strings::file_filter_info(
+                            &self.filter,
+                            self.filter_editing
+                        )
+                    )
+                };
+
+            ui::draw_list(f, area, &title, items, true, &self.theme);
         }
 
         Ok(())
@@ -112,6 +817,61 @@ impl Component for RevisionFilesComponent {
                 )
                 .order(1),
             );
+            out.push(
+                CommandInfo::new(
+                    strings::commands::scroll(&self.key_config),
+                    true,
+                    true,
+                )
+                .order(1),
+            );
+            out.push(
+                CommandInfo::new(
+                    strings::commands::show_file_content(
+                        &self.key_config,
+                    ),
+                    self.selected_index().is_some(),
+                    self.content.is_none(),
+                )
+                .order(1),
+            );
+            out.push(
+                CommandInfo::new(
+                    strings::commands::copy_path(&self.key_config),
+                    self.selected_index().is_some(),
+                    self.content.is_none(),
+                )
+                .order(1),
+            );
+            out.push(
+                CommandInfo::new(
+                    strings::commands::edit_tree_file(
+                        &self.key_config,
+                    ),
+                    self.selected_index().is_some(),
+                    true,
+                )
+                .order(1),
+            );
+            out.push(
+                CommandInfo::new(
+                    strings::commands::view_file_pager(
+                        &self.key_config,
+                    ),
+                    self.selected_index().is_some(),
+                    true,
+                )
+                .order(1),
+            );
+            out.push(
+                CommandInfo::new(
+                    strings::commands::file_filter(&self.key_config),
+                    !self.files.is_empty(),
+                    self.content.is_none(),
+                )
+                .order(1),
+            );
+            self.commands_content(out);
         }
 
         visibility_blocking(self)
@@ -123,8 +883,96 @@ impl Component for RevisionFilesComponent {
     ) -> Result<EventState> {
         if self.is_visible() {
             if let Event::Key(key) = event {
-                if key == self.key_config.exit_popup {
-                    self.hide();
+                if self.filter_editing {
+                    self.filter_event(key);
+                } else if self.is_offset_editing() {
+                    self.offset_event(key);
+                } else if key == self.key_config.borrow().exit_popup {
+                    if self.content.is_some() {
+                        self.content = None;
+                    } else if !self.filter.is_empty() {
+                        self.filter.clear();
+                        self.update_filtered();
+                    } else {
+                        self.hide();
+                    }
+                } else if key == self.key_config.borrow().file_filter
+                    && self.content.is_none()
+                {
+                    self.filter_editing = true;
+                } else if key
+                    == self.key_config.borrow().markdown_toggle_raw
+                    && self.is_markdown_visible()
+                {
+                    self.toggle_markdown_raw();
+                } else if key
+                    == self.key_config.borrow().hex_goto_offset
+                    && self.hex_visible()
+                {
+                    if let Some(content) = &mut self.content {
+                        content.offset_editing = Some(String::new());
+                    }
+                } else if key
+                    == self.key_config.borrow().time_machine_older
+                {
+                    try_or_popup!(
+                        self,
+                        "time machine:",
+                        self.step(true)
+                    );
+                } else if key
+                    == self.key_config.borrow().time_machine_newer
+                {
+                    try_or_popup!(
+                        self,
+                        "time machine:",
+                        self.step(false)
+                    );
+                } else if key == self.key_config.borrow().copy
+                    && self.content.is_none()
+                {
+                    try_or_popup!(
+                        self,
+                        "copy to clipboard error:",
+                        self.copy_selected_path()
+                    );
+                } else if key == self.key_config.borrow().edit_file
+                    || key == self.key_config.borrow().view_file_pager
+                {
+                    try_or_popup!(
+                        self,
+                        "open file:",
+                        self.open_selected_external(
+                            key == self
+                                .key_config
+                                .borrow()
+                                .view_file_pager
+                        )
+                    );
+                } else if key == self.key_config.borrow().enter
+                    && self.content.is_none()
+                {
+                    try_or_popup!(
+                        self,
+                        "open file:",
+                        self.open_selected_file()
+                    );
+                } else if key == self.key_config.borrow().move_up {
+                    self.move_selection(ScrollType::Up);
+                } else if key == self.key_config.borrow().move_down {
+                    self.move_selection(ScrollType::Down);
+                } else if key == self.key_config.borrow().home
+                    || key == self.key_config.borrow().shift_up
+                {
+                    self.move_selection(ScrollType::Home);
+                } else if key == self.key_config.borrow().end
+                    || key == self.key_config.borrow().shift_down
+                {
+                    self.move_selection(ScrollType::End);
+                } else if key == self.key_config.borrow().page_up {
+                    self.move_selection(ScrollType::PageUp);
+                } else if key == self.key_config.borrow().page_down {
+                    self.move_selection(ScrollType::PageDown);
                 }
 
                 return Ok(EventState::Consumed);
@@ -11,12 +11,15 @@ use crate::{
     ui::style::SharedTheme,
 };
 use anyhow::Result;
-use asyncgit::{sync, CWD};
+use asyncgit::sync::{self, CommitId, RepoPath};
 use crossterm::event::Event;
 use tui::{backend::Backend, layout::Rect, Frame};
 
 pub struct StashMsgComponent {
+    repo: RepoPath,
     options: StashingOptions,
+    /// gives this stash a new message instead of stashing
+    rename: Option<CommitId>,
     input: TextInputComponent,
     queue: Queue,
     key_config: SharedKeyConfig,
@@ -62,42 +65,8 @@ impl Component for StashMsgComponent {
             }
 
             if let Event::Key(e) = ev {
-                if e == self.key_config.enter {
-                    match sync::stash_save(
-                        CWD,
-                        if self.input.get_text().is_empty() {
-                            None
-                        } else {
-                            Some(self.input.get_text().as_str())
-                        },
-                        self.options.stash_untracked,
-                        self.options.keep_index,
-                    ) {
-                        Ok(_) => {
-                            self.input.clear();
-                            self.hide();
-
-                            self.queue.borrow_mut().push_back(
-                                InternalEvent::Update(
-                                    NeedsUpdate::ALL,
-                                ),
-                            );
-                        }
-                        Err(e) => {
-                            self.hide();
-                            log::error!(
-                                "e: {} (options: {:?})",
-                                e,
-                                self.options
-                            );
-                            self.queue.borrow_mut().push_back(
-                                InternalEvent::ShowErrorMsg(format!(
-                                    "stash error:\n{}\noptions:\n{:?}",
-                                    e, self.options
-                                )),
-                            );
-                        }
-                    }
+                if e == self.key_config.borrow().enter {
+                    self.confirm();
                 }
 
                 // stop key event propagation
@@ -125,12 +94,14 @@ impl Component for StashMsgComponent {
 impl StashMsgComponent {
     ///
     pub fn new(
+        repo: RepoPath,
         queue: Queue,
         theme: SharedTheme,
         key_config: SharedKeyConfig,
     ) -> Self {
         Self {
             options: StashingOptions::default(),
+            rename: None,
             queue,
             input: TextInputComponent::new(
                 theme,
@@ -140,11 +111,80 @@ impl StashMsgComponent {
                 true,
             ),
             key_config,
+            repo,
         }
     }
 
     ///
     pub fn options(&mut self, options: StashingOptions) {
         self.options = options;
+        self.rename = None;
+        self.input
+            .set_title(strings::stash_popup_title(&self.key_config));
+    }
+
+    /// edit the `message` of the stash `id`
+    pub fn open_rename(
+        &mut self,
+        id: CommitId,
+        message: String,
+    ) -> Result<()> {
+        self.rename = Some(id);
+        self.input.set_title(strings::stash_rename_popup_title());
+        self.input.set_text(message);
+        self.show()
+    }
+
+    fn confirm(&mut self) {
+        let msg = if self.input.get_text().is_empty() {
+            None
+        } else {
+            Some(self.input.get_text().as_str())
+        };
+
+        let res = match (self.rename, msg) {
+            (Some(id), Some(msg)) => {
+                sync::stash_rename(&self.repo, id, msg).map(|_| ())
+            }
+            // an empty message keeps the old one
+            (Some(_), None) => Ok(()),
+            (None, _) if self.options.paths.is_empty() => {
+                sync::stash_save(
+                    &self.repo,
+                    msg,
+                    self.options.stash_untracked,
+                    self.options.stash_ignored,
+                    self.options.keep_index,
+                )
+                .map(|_| ())
+            }
+            (None, _) => sync::stash_save_paths(
+                &self.repo,
+                msg,
+                &self.options.paths,
+            )
+            .map(|_| ()),
+        };
+
+        match res {
+            Ok(_) => {
+                self.input.clear();
+                self.hide();
+
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::Update(NeedsUpdate::ALL),
+                );
+            }
+            Err(e) => {
+                self.hide();
+                log::error!("e: {} (options: {:?})", e, self.options);
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::ShowErrorMsg(format!(
+                        "stash error:\n{}\noptions:\n{:?}",
+                        e, self.options
+                    )),
+                );
+            }
+        }
     }
 }
@@ -10,14 +10,12 @@ use crate::{
     ui::style::SharedTheme,
 };
 use anyhow::Result;
-use asyncgit::{
-    sync::{self, CommitId},
-    CWD,
-};
+use asyncgit::sync::{self, CommitId, RepoPath};
 use crossterm::event::Event;
 use tui::{backend::Backend, layout::Rect, Frame};
 
 pub struct TagCommitComponent {
+    repo: RepoPath,
     input: TextInputComponent,
     commit_id: Option<CommitId>,
     queue: Queue,
@@ -64,7 +62,7 @@ impl Component for TagCommitComponent {
             }
 
             if let Event::Key(e) = ev {
-                if e == self.key_config.enter {
+                if e == self.key_config.borrow().enter {
                     self.tag()
                 }
 
@@ -92,6 +90,7 @@ impl Component for TagCommitComponent {
 impl TagCommitComponent {
     ///
     pub fn new(
+        repo: RepoPath,
         queue: Queue,
         theme: SharedTheme,
         key_config: SharedKeyConfig,
@@ -107,6 +106,7 @@ impl TagCommitComponent {
             ),
             commit_id: None,
             key_config,
+            repo,
         }
     }
 
@@ -121,7 +121,11 @@ impl TagCommitComponent {
     ///
     pub fn tag(&mut self) {
         if let Some(commit_id) = self.commit_id {
-            match sync::tag(CWD, &commit_id, self.input.get_text()) {
+            match sync::tag(
+                &self.repo,
+                &commit_id,
+                self.input.get_text(),
+            ) {
                 Ok(_) => {
                     self.input.clear();
                     self.hide();
@@ -0,0 +1,388 @@
+use super::{
+    visibility_blocking, CommandBlocking, CommandInfo, Component,
+    DrawableComponent, EventState, ScrollType,
+};
+use crate::{
+    keys::SharedKeyConfig,
+    queue::{Action, InternalEvent, Queue},
+    strings,
+    ui::{self, calc_scroll_top, style::SharedTheme, Size},
+};
+use anyhow::Result;
+use asyncgit::sync::{self, CommitId, RepoPath, TagInfo};
+use crossterm::event::Event;
+use std::cell::Cell;
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    text::{Span, Spans, Text},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
+    Frame,
+};
+use unicode_truncate::UnicodeTruncateStr;
+
+/// lists all tags to delete or push several of them at once, or to
+/// move one to the commit selected in the log
+pub struct TagListComponent {
+    repo: RepoPath,
+    tags: Vec<TagInfo>,
+    /// names of the tags marked by the user
+    marked: Vec<String>,
+    /// commit selected in the log, tags get moved there
+    target: Option<CommitId>,
+    visible: bool,
+    selection: usize,
+    scroll_top: Cell<usize>,
+    current_height: Cell<usize>,
+    queue: Queue,
+    theme: SharedTheme,
+    key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for TagListComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        if self.visible {
+            const PERCENT_SIZE: Size = Size::new(80, 50);
+            const MIN_SIZE: Size = Size::new(60, 20);
+
+            let area = ui::centered_rect(
+                PERCENT_SIZE.width,
+                PERCENT_SIZE.height,
+                f.size(),
+            );
+            let area =
+                ui::rect_inside(MIN_SIZE, f.size().into(), area);
+            let area = area.intersection(rect);
+
+            f.render_widget(Clear, area);
+
+            let height = usize::from(area.height.saturating_sub(2));
+            self.current_height.set(height);
+
+            self.scroll_top.set(calc_scroll_top(
+                self.scroll_top.get(),
+                height,
+                self.selection,
+            ));
+
+            f.render_widget(
+                Paragraph::new(
+                    self.get_text(
+                        area.width.saturating_sub(2),
+                        height,
+                    ),
+                )
+                .block(
+                    Block::default()
+                        .title(Span::styled(
+                            strings::title_tags(),
+                            self.theme.borrow().title(true),
+                        ))
+                        .border_type(BorderType::Thick)
+                        .borders(Borders::ALL)
+                        .border_style(
+                            self.theme.borrow().block(true),
+                        ),
+                ),
+                area,
+            );
+
+            ui::draw_scrollbar(
+                f,
+                area,
+                &self.theme,
+                self.tags.len().saturating_sub(height),
+                self.scroll_top.get(),
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl Component for TagListComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.visible || force_all {
+            out.clear();
+
+            out.push(CommandInfo::new(
+                strings::commands::scroll(&self.key_config),
+                true,
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::close_popup(&self.key_config),
+                true,
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::tag_list_mark(&self.key_config),
+                !self.tags.is_empty(),
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::tag_list_delete(
+                    &self.key_config,
+                    self.marked.len(),
+                ),
+                !self.tags.is_empty(),
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::tag_list_push(
+                    &self.key_config,
+                    self.marked.len(),
+                ),
+                !self.tags.is_empty(),
+                true,
+            ));
+
+            out.push(CommandInfo::new(
+                strings::commands::tag_list_retag(&self.key_config),
+                self.can_retag(),
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<EventState> {
+        if self.visible {
+            if let Event::Key(e) = ev {
+                if e == self.key_config.borrow().exit_popup {
+                    self.hide();
+                } else if e == self.key_config.borrow().move_down {
+                    self.move_selection(ScrollType::Down);
+                } else if e == self.key_config.borrow().move_up {
+                    self.move_selection(ScrollType::Up);
+                } else if e == self.key_config.borrow().page_down {
+                    self.move_selection(ScrollType::PageDown);
+                } else if e == self.key_config.borrow().page_up {
+                    self.move_selection(ScrollType::PageUp);
+                } else if e == self.key_config.borrow().home {
+                    self.move_selection(ScrollType::Home);
+                } else if e == self.key_config.borrow().end {
+                    self.move_selection(ScrollType::End);
+                } else if e
+                    == self.key_config.borrow().log_mark_commit
+                {
+                    self.toggle_mark();
+                } else if e == self.key_config.borrow().delete_tag {
+                    let tags = self.selected_tags();
+                    if !tags.is_empty() {
+                        self.queue.borrow_mut().push_back(
+                            InternalEvent::ConfirmAction(
+                                Action::DeleteTags(tags),
+                            ),
+                        );
+                    }
+                } else if e == self.key_config.borrow().push {
+                    let tags = self.selected_tags();
+                    if !tags.is_empty() {
+                        self.queue.borrow_mut().push_back(
+                            InternalEvent::PushTags(tags, false),
+                        );
+                    }
+                } else if e == self.key_config.borrow().log_tag_commit
+                    && self.can_retag()
+                {
+                    if let (Some(tag), Some(target)) =
+                        (self.tags.get(self.selection), self.target)
+                    {
+                        self.queue.borrow_mut().push_back(
+                            InternalEvent::ConfirmAction(
+                                Action::Retag(
+                                    tag.name.clone(),
+                                    target,
+                                ),
+                            ),
+                        );
+                    }
+                }
+            }
+
+            Ok(EventState::Consumed)
+        } else {
+            Ok(EventState::NotConsumed)
+        }
+    }
+
+    fn is_visible(&self) -> bool {
+        self.visible
+    }
+
+    fn hide(&mut self) {
+        self.visible = false
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.visible = true;
+
+        Ok(())
+    }
+}
+
+impl TagListComponent {
+    ///
+    pub fn new(
+        repo: RepoPath,
+        queue: Queue,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+    ) -> Self {
+        Self {
+            tags: Vec::new(),
+            marked: Vec::new(),
+            target: None,
+            visible: false,
+            selection: 0,
+            scroll_top: Cell::new(0),
+            current_height: Cell::new(0),
+            queue,
+            theme,
+            key_config,
+            repo,
+        }
+    }
+
+    /// `target` is the commit selected tags can be moved to
+    pub fn open(&mut self, target: CommitId) -> Result<()> {
+        self.target = Some(target);
+        self.marked.clear();
+        self.selection = 0;
+        self.update_tags()?;
+        self.show()
+    }
+
+    /// reloads the tags, marks of deleted ones are dropped
+    pub fn update_tags(&mut self) -> Result<()> {
+        self.tags = sync::get_tag_infos(&self.repo)?;
+
+        let tags = &self.tags;
+        self.marked
+            .retain(|name| tags.iter().any(|tag| &tag.name == name));
+        self.selection =
+            self.selection.min(self.tags.len().saturating_sub(1));
+
+        Ok(())
+    }
+
+    /// the marked tags, the selected one if none is marked
+    fn selected_tags(&self) -> Vec<String> {
+        if self.marked.is_empty() {
+            self.tags
+                .get(self.selection)
+                .map(|tag| vec![tag.name.clone()])
+                .unwrap_or_default()
+        } else {
+            self.marked.clone()
+        }
+    }
+
+    fn can_retag(&self) -> bool {
+        match (self.tags.get(self.selection), self.target) {
+            (Some(tag), Some(target)) => tag.commit != target,
+            _ => false,
+        }
+    }
+
+    fn toggle_mark(&mut self) {
+        if let Some(tag) = self.tags.get(self.selection) {
+            if let Some(idx) =
+                self.marked.iter().position(|name| name == &tag.name)
+            {
+                self.marked.remove(idx);
+            } else {
+                self.marked.push(tag.name.clone());
+            }
+        }
+
+        self.move_selection(ScrollType::Down);
+    }
+
+    fn move_selection(&mut self, scroll: ScrollType) {
+        let page = self.current_height.get().saturating_sub(1);
+        let max = self.tags.len().saturating_sub(1);
+
+        let new_selection = match scroll {
+            ScrollType::Up => self.selection.saturating_sub(1),
+            ScrollType::Down => self.selection.saturating_add(1),
+            ScrollType::PageUp => self.selection.saturating_sub(page),
+            ScrollType::PageDown => {
+                self.selection.saturating_add(page)
+            }
+            ScrollType::Home => 0,
+            ScrollType::End => max,
+        };
+
+        self.selection = new_selection.min(max);
+    }
+
+    fn get_text(&self, width: u16, height: usize) -> Text {
+        const HASH_LENGTH: usize = 8;
+        const MARKER_LENGTH: usize = 2;
+
+        let width = usize::from(width);
+        let name_length = width * 30 / 100;
+        let summary_length = width
+            .saturating_sub(MARKER_LENGTH)
+            .saturating_sub(name_length + 1)
+            .saturating_sub(HASH_LENGTH);
+
+        let lines = self
+            .tags
+            .iter()
+            .enumerate()
+            .skip(self.scroll_top.get())
+            .take(height)
+            .map(|(idx, tag)| {
+                let selected = idx == self.selection;
+                let marked = self.marked.contains(&tag.name);
+                let is_target = Some(tag.commit) == self.target;
+
+                Spans::from(vec![
+                    Span::styled(
+                        if marked { "\u{221a} " } else { "  " }, //√
+                        self.theme.borrow().commit_hash(selected),
+                    ),
+                    Span::styled(
+                        format!(
+                            "{:w$} ",
+                            tag.name.unicode_truncate(name_length).0,
+                            w = name_length
+                        ),
+                        self.theme.borrow().tags(selected),
+                    ),
+                    Span::styled(
+                        format!("{} ", tag.commit.get_short_string()),
+                        self.theme.borrow().commit_hash(selected),
+                    ),
+                    Span::styled(
+                        tag.commit_summary
+                            .unicode_truncate(summary_length)
+                            .0
+                            .to_string(),
+                        self.theme
+                            .borrow()
+                            .text(!is_target, selected),
+                    ),
+                ])
+            })
+            .collect::<Vec<_>>();
+
+        Text::from(lines)
+    }
+}
@@ -128,6 +128,28 @@ impl TextInputComponent {
         }
     }
 
+    /// the word ending at the cursor, it starts after whitespace,
+    /// quotes or an opening parenthesis
+    pub fn word_before_cursor(&self) -> &str {
+        let before = &self.msg[..self.cursor_position];
+        let start = before
+            .char_indices()
+            .rev()
+            .find(|(_, c)| c.is_whitespace() || "`'\"(".contains(*c))
+            .map_or(0, |(idx, c)| idx + c.len_utf8());
+
+        &before[start..]
+    }
+
+    /// replaces `word_before_cursor` by `word`, the cursor moves to
+    /// its end
+    pub fn replace_word_before_cursor(&mut self, word: &str) {
+        let start =
+            self.cursor_position - self.word_before_cursor().len();
+        self.msg.replace_range(start..self.cursor_position, word);
+        self.cursor_position = start + word.len();
+    }
+
     /// Set the `msg`.
     pub fn set_text(&mut self, msg: String) {
         self.msg = msg;
@@ -140,7 +162,7 @@ impl TextInputComponent {
     }
 
     fn get_draw_text(&self) -> Text {
-        let style = self.theme.text(true, false);
+        let style = self.theme.borrow().text(true, false);
 
         let mut txt = Text::default();
         // The portion of the text before the cursor is added
@@ -182,6 +204,7 @@ impl TextInputComponent {
                 Text::styled(
                     substitute.to_owned(),
                     self.theme
+                        .borrow()
                         .text(false, false)
                         .add_modifier(Modifier::UNDERLINED),
                 ),
@@ -273,7 +296,7 @@ impl DrawableComponent for TextInputComponent {
             let txt = if self.msg.is_empty() {
                 Text::styled(
                     self.default_msg.as_str(),
-                    self.theme.text(false, false),
+                    self.theme.borrow().text(false, false),
                 )
             } else {
                 self.get_draw_text()
@@ -296,7 +319,7 @@ impl DrawableComponent for TextInputComponent {
                 popup_paragraph(
                     self.title.as_str(),
                     txt,
-                    &self.theme,
+                    &self.theme.borrow(),
                     true,
                 ),
                 area,
@@ -333,7 +356,7 @@ impl Component for TextInputComponent {
     fn event(&mut self, ev: Event) -> Result<EventState> {
         if self.visible {
             if let Event::Key(e) = ev {
-                if e == self.key_config.exit_popup {
+                if e == self.key_config.borrow().exit_popup {
                     self.hide();
                     return Ok(EventState::Consumed);
                 }
@@ -421,6 +444,29 @@ mod tests {
         assert_eq!(comp.cursor_position, 0);
     }
 
+    #[test]
+    fn test_replace_word_before_cursor() {
+        let mut comp = TextInputComponent::new(
+            SharedTheme::default(),
+            SharedKeyConfig::default(),
+            "",
+            "",
+            false,
+        );
+
+        comp.set_text(String::from("fix `comm and"));
+        for _ in 0..9 {
+            comp.incr_cursor();
+        }
+
+        assert_eq!(comp.word_before_cursor(), "comm");
+
+        comp.replace_word_before_cursor("commit.rs");
+
+        assert_eq!(comp.get_text(), "fix `commit.rs and");
+        assert_eq!(comp.word_before_cursor(), "commit.rs");
+    }
+
     #[test]
     fn text_cursor_initial_position() {
         let mut comp = TextInputComponent::new(
@@ -432,6 +478,7 @@ mod tests {
         );
         let theme = SharedTheme::default();
         let underlined = theme
+            .borrow()
             .text(true, false)
             .add_modifier(Modifier::UNDERLINED);
 
@@ -455,6 +502,7 @@ mod tests {
         );
         let theme = SharedTheme::default();
         let underlined_whitespace = theme
+            .borrow()
             .text(false, false)
             .add_modifier(Modifier::UNDERLINED);
 
@@ -490,6 +538,7 @@ mod tests {
 
         let theme = SharedTheme::default();
         let underlined = theme
+            .borrow()
             .text(false, false)
             .add_modifier(Modifier::UNDERLINED);
 
@@ -520,6 +569,7 @@ mod tests {
 
         let theme = SharedTheme::default();
         let underlined = theme
+            .borrow()
             .text(true, false)
             .add_modifier(Modifier::UNDERLINED);
 
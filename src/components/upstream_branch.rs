@@ -0,0 +1,166 @@
+use super::{
+    textinput::TextInputComponent, visibility_blocking,
+    CommandBlocking, CommandInfo, Component, DrawableComponent,
+    EventState,
+};
+use crate::{
+    keys::SharedKeyConfig,
+    queue::{InternalEvent, NeedsUpdate, Queue},
+    strings,
+    ui::style::SharedTheme,
+};
+use anyhow::Result;
+use asyncgit::sync::{self, RepoPath};
+use crossterm::event::Event;
+use tui::{backend::Backend, layout::Rect, Frame};
+
+/// sets (or clears) the upstream of a local branch
+pub struct UpstreamBranchComponent {
+    repo: RepoPath,
+    input: TextInputComponent,
+    branch: Option<String>,
+    queue: Queue,
+    key_config: SharedKeyConfig,
+}
+
+impl DrawableComponent for UpstreamBranchComponent {
+    fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        self.input.draw(f, rect)?;
+
+        Ok(())
+    }
+}
+
+impl Component for UpstreamBranchComponent {
+    fn commands(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) -> CommandBlocking {
+        if self.is_visible() || force_all {
+            self.input.commands(out, force_all);
+
+            out.push(CommandInfo::new(
+                strings::commands::set_upstream_confirm_msg(
+                    &self.key_config,
+                ),
+                true,
+                true,
+            ));
+        }
+
+        visibility_blocking(self)
+    }
+
+    fn event(&mut self, ev: Event) -> Result<EventState> {
+        if self.is_visible() {
+            if self.input.event(ev)?.is_consumed() {
+                return Ok(EventState::Consumed);
+            }
+
+            if let Event::Key(e) = ev {
+                if e == self.key_config.borrow().enter {
+                    self.set_upstream();
+                }
+
+                return Ok(EventState::Consumed);
+            }
+        }
+        Ok(EventState::NotConsumed)
+    }
+
+    fn is_visible(&self) -> bool {
+        self.input.is_visible()
+    }
+
+    fn hide(&mut self) {
+        self.input.hide()
+    }
+
+    fn show(&mut self) -> Result<()> {
+        self.input.show()?;
+
+        Ok(())
+    }
+}
+
+impl UpstreamBranchComponent {
+    ///
+    pub fn new(
+        repo: RepoPath,
+        queue: Queue,
+        theme: SharedTheme,
+        key_config: SharedKeyConfig,
+    ) -> Self {
+        Self {
+            queue,
+            input: TextInputComponent::new(
+                theme,
+                key_config.clone(),
+                &strings::set_upstream_popup_title(&key_config),
+                &strings::set_upstream_popup_msg(&key_config),
+                true,
+            ),
+            branch: None,
+            key_config,
+            repo,
+        }
+    }
+
+    /// prefilled with the current upstream, or the branch of the
+    /// same name on the default remote if there is none
+    pub fn open(&mut self, branch: String) -> Result<()> {
+        let upstream = sync::get_upstream(&self.repo, &branch)?
+            .or_else(|| {
+                sync::get_default_remote(&self.repo)
+                    .ok()
+                    .map(|remote| format!("{}/{}", remote, branch))
+            })
+            .unwrap_or_default();
+
+        self.branch = Some(branch);
+        self.input.set_text(upstream);
+        self.show()?;
+
+        Ok(())
+    }
+
+    /// empty input removes the upstream
+    pub fn set_upstream(&mut self) {
+        if let Some(branch) = &self.branch {
+            let text = self.input.get_text();
+            let upstream = text.trim();
+            let upstream = if upstream.is_empty() {
+                None
+            } else {
+                Some(upstream)
+            };
+
+            match sync::set_upstream(&self.repo, branch, upstream) {
+                Ok(_) => {
+                    self.queue.borrow_mut().push_back(
+                        InternalEvent::Update(
+                            NeedsUpdate::ALL | NeedsUpdate::BRANCHES,
+                        ),
+                    );
+                    self.hide();
+                }
+                Err(e) => {
+                    log::error!("set upstream: {}", e);
+                    self.queue.borrow_mut().push_back(
+                        InternalEvent::ShowErrorMsg(format!(
+                            "set upstream error:\n{}",
+                            e,
+                        )),
+                    );
+                }
+            }
+        }
+
+        self.input.clear();
+    }
+}
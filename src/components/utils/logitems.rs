@@ -10,6 +10,8 @@ pub struct LogEntry {
     pub msg: String,
     pub hash_short: String,
     pub id: CommitId,
+    /// shown as its replacement
+    pub replaced: bool,
 }
 
 impl From<CommitInfo> for LogEntry {
@@ -25,6 +27,7 @@ impl From<CommitInfo> for LogEntry {
             time,
             hash_short: c.id.get_short_string(),
             id: c.id,
+            replaced: c.replaced,
         }
     }
 }
@@ -36,6 +36,24 @@ pub fn time_to_string(secs: i64, short: bool) -> String {
     .to_string()
 }
 
+/// returns true if all chars of `pattern` appear in `text` in the
+/// same order, matching is case insensitive unless `pattern`
+/// contains uppercase chars
+pub fn fuzzy_match(pattern: &str, text: &str) -> bool {
+    let case_sensitive = pattern.chars().any(char::is_uppercase);
+    let mut text_chars = text.chars();
+
+    pattern.chars().filter(|c| !c.is_whitespace()).all(|p| {
+        text_chars.any(|t| {
+            if case_sensitive {
+                t == p
+            } else {
+                t.to_lowercase().eq(p.to_lowercase())
+            }
+        })
+    })
+}
+
 #[inline]
 pub fn string_width_align(s: &str, width: usize) -> String {
     static POSTFIX: &str = "..";
@@ -58,3 +76,18 @@ pub fn string_width_align(s: &str, width: usize) -> String {
 fn find_truncate_point(s: &str, chars: usize) -> usize {
     s.chars().take(chars).map(char::len_utf8).sum()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::fuzzy_match;
+
+    #[test]
+    fn test_fuzzy_match() {
+        assert!(fuzzy_match("", "src/main.rs"));
+        assert!(fuzzy_match("smr", "src/main.rs"));
+        assert!(fuzzy_match("MAIN", "src/MAIN.rs"));
+        assert!(fuzzy_match("main rs", "src/main.rs"));
+        assert!(!fuzzy_match("Main", "src/main.rs"));
+        assert!(!fuzzy_match("rsm", "src/main.rs"));
+    }
+}
@@ -438,6 +438,9 @@ mod tests {
             .map(|a| StatusItem {
                 path: String::from(*a),
                 status: StatusItemType::Modified,
+                submodule: None,
+                nested_repo: false,
+                summarized: false,
             })
             .collect::<Vec<_>>()
     }
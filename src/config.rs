@@ -0,0 +1,463 @@
+//! loading of the ron config files (key config, theme, options)
+//! entry by entry on top of the defaults and watching them for
+//! changes
+
+use anyhow::{anyhow, Result};
+use ron::ser::{to_string_pretty, PrettyConfig};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+/// single `name: value` entry of the top level struct of a config
+pub struct ConfigEntry {
+    pub name: String,
+    pub value: String,
+    /// line the entry starts in
+    pub line: usize,
+}
+
+/// config read from a file with everything that was wrong with it
+pub struct LoadedConfig<T> {
+    pub config: T,
+    /// human readable errors pointing at file and line
+    pub errors: Vec<String>,
+    /// the file lacked entries (older format), defaults were used
+    pub migrated: bool,
+}
+
+/// splits the top level struct of a ron document into its entries,
+/// comments are dropped, `None` if `data` is no struct
+pub fn entries(data: &str) -> Option<Vec<ConfigEntry>> {
+    let mut res = Vec::new();
+    let mut chars = data.chars().peekable();
+    let mut depth = 0_usize;
+    let mut line = 1;
+    let mut current = String::new();
+    let mut current_line = 0;
+    let mut started = false;
+
+    let mut finish = |current: &mut String, line: usize| {
+        let entry = std::mem::take(current);
+        if entry.trim().is_empty() {
+            return Some(());
+        }
+
+        let mut parts = entry.splitn(2, ':');
+        let name = parts.next()?.trim().to_string();
+        let value = parts.next()?.trim().to_string();
+        res.push(ConfigEntry { name, value, line });
+        Some(())
+    };
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => line += 1,
+            '/' if chars.peek() == Some(&'/') => {
+                for c in &mut chars {
+                    if c == '\n' {
+                        line += 1;
+                        break;
+                    }
+                }
+                continue;
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut last = ' ';
+                for c in &mut chars {
+                    if c == '\n' {
+                        line += 1;
+                    } else if last == '*' && c == '/' {
+                        break;
+                    }
+                    last = c;
+                }
+                continue;
+            }
+            '"' | '\'' if depth > 0 => {
+                current.push(c);
+                let mut escaped = false;
+                for q in &mut chars {
+                    current.push(q);
+                    if escaped {
+                        escaped = false;
+                    } else if q == '\\' {
+                        escaped = true;
+                    } else if q == c {
+                        break;
+                    }
+                }
+                continue;
+            }
+            '(' | '[' | '{' => {
+                depth += 1;
+                if depth == 1 {
+                    if started {
+                        return None;
+                    }
+                    started = true;
+                    continue;
+                }
+            }
+            ')' | ']' | '}' => {
+                depth = depth.checked_sub(1)?;
+                if depth == 0 {
+                    finish(&mut current, current_line)?;
+                    continue;
+                }
+            }
+            ',' if depth == 1 => {
+                finish(&mut current, current_line)?;
+                continue;
+            }
+            _ => (),
+        }
+
+        if depth > 0 {
+            if current.trim().is_empty() && !c.is_whitespace() {
+                current_line = line;
+            }
+            current.push(c);
+        } else if started && !c.is_whitespace() {
+            return None;
+        }
+    }
+
+    if started && depth == 0 {
+        Some(res)
+    } else {
+        None
+    }
+}
+
+fn to_ron<'a>(
+    entries: impl Iterator<Item = (&'a str, &'a str)>,
+) -> String {
+    format!(
+        "({})",
+        entries
+            .map(|(name, value)| format!("{}:{}", name, value))
+            .collect::<Vec<_>>()
+            .join(",")
+    )
+}
+
+/// reads `file` on top of the defaults of `T`: unknown or invalid
+/// entries are reported and replaced by their default, missing
+/// entries (older formats) fall back to the default, entries in
+/// `removed` are dropped silently
+pub fn load<T>(
+    file: &Path,
+    removed: &[&str],
+) -> Result<LoadedConfig<T>>
+where
+    T: Serialize + DeserializeOwned + Default,
+{
+    load_with(file, removed, str::to_string)
+}
+
+/// like `load`, the values of the file are passed through
+/// `map_value` first to allow shorthands ron does not know
+pub fn load_with<T>(
+    file: &Path,
+    removed: &[&str],
+    map_value: fn(&str) -> String,
+) -> Result<LoadedConfig<T>>
+where
+    T: Serialize + DeserializeOwned + Default,
+{
+    let data = fs::read_to_string(file)?;
+    let file_name = file.to_string_lossy();
+
+    let user = entries(&data).ok_or_else(|| {
+        anyhow!(
+            "{}: expected a struct like `( name: value, ... )`",
+            file_name
+        )
+    })?;
+
+    let defaults = entries(&ron::ser::to_string(&T::default())?)
+        .ok_or_else(|| anyhow!("invalid default config"))?;
+    let mut merged: Vec<(String, String)> = defaults
+        .into_iter()
+        .map(|entry| (entry.name, entry.value))
+        .collect();
+
+    let mut errors = Vec::new();
+
+    for entry in &user {
+        if removed.contains(&entry.name.as_str()) {
+            continue;
+        }
+
+        let idx = if let Some(idx) =
+            merged.iter().position(|(name, _)| *name == entry.name)
+        {
+            idx
+        } else {
+            errors.push(format!(
+                "{}:{}: unknown key `{}`",
+                file_name, entry.line, entry.name
+            ));
+            continue;
+        };
+
+        let mut candidate = merged.clone();
+        candidate[idx].1 = map_value(&entry.value);
+
+        let doc = to_ron(
+            candidate.iter().map(|(n, v)| (n.as_str(), v.as_str())),
+        );
+        match ron::de::from_str::<T>(&doc) {
+            Ok(_) => merged = candidate,
+            Err(e) => errors.push(format!(
+                "{}:{}: invalid value for `{}`: {}",
+                file_name, entry.line, entry.name, e.code
+            )),
+        }
+    }
+
+    let migrated = merged.iter().any(|(name, _)| {
+        !user.iter().any(|entry| entry.name == *name)
+    });
+
+    let config = ron::de::from_str(&to_ron(
+        merged.iter().map(|(n, v)| (n.as_str(), v.as_str())),
+    ))?;
+
+    Ok(LoadedConfig {
+        config,
+        errors,
+        migrated,
+    })
+}
+
+/// loads `file` (see `load`), writes the defaults if it does not
+/// exist yet
+pub fn init<T>(file: &Path, removed: &[&str]) -> (T, Vec<String>)
+where
+    T: Serialize + DeserializeOwned + Default,
+{
+    init_with(file, removed, str::to_string)
+}
+
+/// like `init`, see `load_with` for `map_value`
+pub fn init_with<T>(
+    file: &Path,
+    removed: &[&str],
+    map_value: fn(&str) -> String,
+) -> (T, Vec<String>)
+where
+    T: Serialize + DeserializeOwned + Default,
+{
+    if !file.exists() {
+        if let Err(e) = save(&T::default(), file) {
+            log::error!("config save error: {}", e);
+        }
+        return (T::default(), Vec::new());
+    }
+
+    match load_with(file, removed, map_value) {
+        Ok(loaded) => {
+            if loaded.migrated {
+                log::info!(
+                    "config migrated: {}",
+                    file.to_string_lossy()
+                );
+            }
+            (loaded.config, loaded.errors)
+        }
+        Err(e) => (T::default(), vec![e.to_string()]),
+    }
+}
+
+fn save<T: Serialize>(config: &T, file: &Path) -> Result<()> {
+    let data = to_string_pretty(config, PrettyConfig::default())?;
+    fs::write(file, data)?;
+    Ok(())
+}
+
+/// defaults of `T` with every entry commented out so the file is
+/// valid as is and single entries can be enabled by uncommenting
+pub fn commented_default<T>(header: &str) -> Result<String>
+where
+    T: Serialize + Default,
+{
+    let data =
+        to_string_pretty(&T::default(), PrettyConfig::default())?;
+    let lines: Vec<&str> = data.lines().collect();
+
+    let mut res = String::new();
+    for line in header.lines() {
+        res.push_str(&format!("// {}\n", line));
+    }
+
+    for (idx, line) in lines.iter().enumerate() {
+        if idx == 0 || idx + 1 == lines.len() {
+            res.push_str(line);
+        } else {
+            res.push_str(&format!("    // {}", line.trim_start()));
+        }
+        res.push('\n');
+    }
+
+    Ok(res)
+}
+
+/// notices modifications of config files by polling their mtime
+pub struct FileWatcher {
+    files: Vec<(PathBuf, Option<SystemTime>)>,
+}
+
+impl FileWatcher {
+    pub fn new(files: Vec<PathBuf>) -> Self {
+        Self {
+            files: files
+                .into_iter()
+                .map(|file| {
+                    let modified = Self::modified(&file);
+                    (file, modified)
+                })
+                .collect(),
+        }
+    }
+
+    /// `true` if any file was modified, created or removed since
+    /// the last call
+    pub fn changed(&mut self) -> bool {
+        let mut changed = false;
+
+        for (file, last) in &mut self.files {
+            let modified = Self::modified(file);
+            if modified != *last {
+                *last = modified;
+                changed = true;
+            }
+        }
+
+        changed
+    }
+
+    fn modified(file: &Path) -> Option<SystemTime> {
+        fs::metadata(file).and_then(|meta| meta.modified()).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{commented_default, entries, load, FileWatcher};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Test {
+        a: u8,
+        b: String,
+        c: (char, u8),
+    }
+
+    impl Default for Test {
+        fn default() -> Self {
+            Self {
+                a: 1,
+                b: "b".to_string(),
+                c: ('c', 3),
+            }
+        }
+    }
+
+    fn load_str(name: &str, data: &str) -> super::LoadedConfig<Test> {
+        let file = std::env::temp_dir()
+            .join(format!("gitui_config_test_{}.ron", name));
+        std::fs::write(&file, data).unwrap();
+        let res = load::<Test>(&file, &["old"]).unwrap();
+        std::fs::remove_file(file).unwrap();
+        res
+    }
+
+    #[test]
+    fn test_entries() {
+        let data = "(\n  // comment, with comma\n  a: 2,\n  b: \"x, y)\",\n  c: (',', 4),\n)";
+        let res = entries(data).unwrap();
+
+        assert_eq!(res.len(), 3);
+        assert_eq!(res[0].name, "a");
+        assert_eq!(res[0].line, 3);
+        assert_eq!(res[1].value, "\"x, y)\"");
+        assert_eq!(res[2].value, "(',', 4)");
+        assert_eq!(res[2].line, 5);
+
+        assert!(entries("a: 1").is_none());
+        assert!(entries("(a: 1))").is_none());
+    }
+
+    #[test]
+    fn test_load_valid() {
+        let res = load_str("valid", "(a: 2, b: \"x\", c: ('d', 4))");
+
+        assert!(res.errors.is_empty());
+        assert!(!res.migrated);
+        assert_eq!(
+            res.config,
+            Test {
+                a: 2,
+                b: "x".to_string(),
+                c: ('d', 4)
+            }
+        );
+    }
+
+    #[test]
+    fn test_load_errors() {
+        let res = load_str(
+            "errors",
+            "(\n  a: \"x\",\n  b: \"y\",\n  d: 1,\n)",
+        );
+
+        assert_eq!(res.errors.len(), 2);
+        assert!(res.errors[0].contains(":2: invalid value for `a`"));
+        assert!(res.errors[1].contains(":4: unknown key `d`"));
+        assert_eq!(res.config.a, 1);
+        assert_eq!(res.config.b, "y");
+    }
+
+    #[test]
+    fn test_load_migrate() {
+        let res = load_str("migrate", "(a: 5, old: 1)");
+
+        assert!(res.errors.is_empty());
+        assert!(res.migrated);
+        assert_eq!(res.config.a, 5);
+        assert_eq!(res.config.c, ('c', 3));
+    }
+
+    #[test]
+    fn test_commented_default() {
+        let data = commented_default::<Test>("header").unwrap();
+
+        assert!(data.starts_with("// header\n"));
+
+        let res = load_str("commented", &data);
+        assert!(res.errors.is_empty());
+        assert_eq!(res.config, Test::default());
+    }
+
+    #[test]
+    fn test_file_watcher() {
+        let file =
+            std::env::temp_dir().join("gitui_config_test_watch");
+        let _ = std::fs::remove_file(&file);
+
+        let mut watcher = FileWatcher::new(vec![file.clone()]);
+        assert!(!watcher.changed());
+
+        std::fs::write(&file, "()").unwrap();
+        assert!(watcher.changed());
+        assert!(!watcher.changed());
+
+        std::fs::remove_file(&file).unwrap();
+        assert!(watcher.changed());
+    }
+}
@@ -0,0 +1,130 @@
+use crate::{args::get_app_cache_path, version::Version};
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fs,
+    path::PathBuf,
+    sync::{Mutex, MutexGuard, TryLockError},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// amount of recent events kept for the report
+const MAX_EVENTS: usize = 50;
+/// longer events (e.g. error messages) get truncated
+const MAX_EVENT_LEN: usize = 200;
+
+/// what the app was doing, written into the crash report on panic
+#[derive(Default)]
+struct CrashState {
+    summary: BTreeMap<&'static str, String>,
+    events: VecDeque<String>,
+}
+
+static STATE: Lazy<Mutex<CrashState>> = Lazy::new(Mutex::default);
+
+/// a panic while holding the lock must not hide the report
+fn state() -> MutexGuard<'static, CrashState> {
+    STATE
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+/// remembers `value` as the current state of `key` (e.g. the tab)
+pub fn set_state(key: &'static str, value: String) {
+    state().summary.insert(key, value);
+}
+
+/// adds `event` to the recent events
+pub fn record_event(event: String) {
+    let event = if event.chars().count() > MAX_EVENT_LEN {
+        format!(
+            "{}...",
+            event.chars().take(MAX_EVENT_LEN).collect::<String>()
+        )
+    } else {
+        event
+    };
+
+    let mut state = state();
+    if state.events.len() == MAX_EVENTS {
+        state.events.pop_front();
+    }
+    state.events.push_back(event);
+}
+
+/// writes the report into the cache folder and returns its path
+pub fn write(panic: &str, backtrace: &str) -> Result<PathBuf> {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|time| time.as_secs())
+        .unwrap_or_default();
+
+    let file =
+        get_app_cache_path()?.join(format!("crash-{}.log", secs));
+
+    // the panic might have happened while holding the lock
+    let data = match STATE.try_lock() {
+        Ok(state) => report(&state, panic, backtrace),
+        Err(TryLockError::Poisoned(state)) => {
+            report(&state.into_inner(), panic, backtrace)
+        }
+        Err(TryLockError::WouldBlock) => {
+            report(&CrashState::default(), panic, backtrace)
+        }
+    };
+
+    fs::write(&file, data)?;
+
+    Ok(file)
+}
+
+fn report(
+    state: &CrashState,
+    panic: &str,
+    backtrace: &str,
+) -> String {
+    let mut res = format!(
+        "gitui {} crashed\n\npanic: {}\n\n## state\n",
+        Version::new(),
+        panic
+    );
+
+    for (key, value) in &state.summary {
+        res.push_str(&format!("{}: {}\n", key, value));
+    }
+
+    res.push_str("\n## recent events (oldest first)\n");
+    for event in &state.events {
+        res.push_str(&format!("{}\n", event));
+    }
+
+    res.push_str(&format!("\n## backtrace\n{}\n", backtrace));
+
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{report, CrashState, MAX_EVENTS};
+
+    #[test]
+    fn test_report() {
+        let mut state = CrashState::default();
+        state.summary.insert("tab", "1".to_string());
+        for idx in 0..=MAX_EVENTS {
+            if state.events.len() == MAX_EVENTS {
+                state.events.pop_front();
+            }
+            state.events.push_back(format!("event {}", idx));
+        }
+
+        let res = report(&state, "boom", "trace");
+
+        assert!(res.contains("panic: boom"));
+        assert!(res.contains("tab: 1\n"));
+        assert!(!res.contains("event 0\n"));
+        assert!(res.contains(&format!("event {}\n", MAX_EVENTS)));
+        assert!(res.ends_with("## backtrace\ntrace\n"));
+    }
+}
@@ -1,7 +1,7 @@
 use crate::notify_mutex::NotifyableMutex;
 use anyhow::Result;
 use crossbeam_channel::{unbounded, Receiver, Sender};
-use crossterm::event::{self, Event};
+use crossterm::event::{self, Event, MouseEvent, MouseEventKind};
 use std::{
     process,
     sync::{
@@ -29,6 +29,7 @@ pub enum InputEvent {
 }
 
 ///
+#[derive(Clone)]
 pub struct Input {
     desired_state: Arc<NotifyableMutex<bool>>,
     current_state: Arc<AtomicBool>,
@@ -62,6 +63,19 @@ impl Input {
         }
     }
 
+    /// never receives events, used by the ui tests feeding the app
+    /// directly
+    #[cfg(test)]
+    pub fn headless() -> Self {
+        let (_tx, rx) = unbounded();
+
+        Self {
+            receiver: rx,
+            desired_state: Arc::new(NotifyableMutex::new(true)),
+            current_state: Arc::new(AtomicBool::new(true)),
+        }
+    }
+
     ///
     pub fn receiver(&self) -> Receiver<InputEvent> {
         self.receiver.clone()
@@ -90,6 +104,18 @@ impl Input {
         }
     }
 
+    /// the mouse is captured for clicks and the wheel, plain moves
+    /// would only cause needless redraws
+    const fn is_mouse_move(ev: Event) -> bool {
+        matches!(
+            ev,
+            Event::Mouse(MouseEvent {
+                kind: MouseEventKind::Moved,
+                ..
+            })
+        )
+    }
+
     fn input_loop(
         arc_desired: &Arc<NotifyableMutex<bool>>,
         arc_current: &Arc<AtomicBool>,
@@ -105,7 +131,9 @@ impl Input {
                 arc_current.store(true, Ordering::Relaxed);
 
                 if let Some(e) = Self::poll(POLL_DURATION)? {
-                    tx.send(InputEvent::Input(e))?;
+                    if !Self::is_mouse_move(e) {
+                        tx.send(InputEvent::Input(e))?;
+                    }
                 }
             } else {
                 if arc_current.load(Ordering::Relaxed) {
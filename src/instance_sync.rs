@@ -0,0 +1,41 @@
+use asyncgit::sync::{
+    last_instance_change, InstanceChange, RepoPath,
+};
+
+/// notices changes other gitui instances running on the same
+/// repository made. the changes themselves are serialized by the
+/// `InstanceLock` the mutating sync calls take
+pub struct InstanceSync {
+    repo: RepoPath,
+    /// the change we saw last
+    last_change: Option<InstanceChange>,
+}
+
+impl InstanceSync {
+    pub fn new(repo: &RepoPath) -> Self {
+        Self {
+            repo: repo.clone(),
+            last_change: last_instance_change(repo).ok().flatten(),
+        }
+    }
+
+    /// `true` if another instance changed the repository since the
+    /// last call
+    pub fn changed_by_other(&mut self) -> bool {
+        let change = match last_instance_change(&self.repo) {
+            Ok(change) => change,
+            Err(e) => {
+                log::error!("instance sync error: {}", e);
+                return false;
+            }
+        };
+
+        if change == self.last_change {
+            return false;
+        }
+
+        self.last_change = change;
+
+        change.map_or(false, |change| !change.is_own())
+    }
+}
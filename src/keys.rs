@@ -3,21 +3,48 @@
 
 use anyhow::Result;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
-use ron::{
-    self,
-    ser::{to_string_pretty, PrettyConfig},
-};
 use serde::{Deserialize, Serialize};
 use std::{
-    fs::{self, File},
-    io::{Read, Write},
-    path::PathBuf,
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::{Path, PathBuf},
     rc::Rc,
 };
 
-use crate::args::get_app_config_path;
+use crate::{args::get_app_config_path, config};
+
+/// replaced in place when the key config file changes
+pub type SharedKeyConfig = Rc<RefCell<KeyConfig>>;
+
+/// keys of older versions that are ignored when found in a config
+const REMOVED_KEYS: &[&str] = &["tab_toggle_reverse_windows"];
+
+/// key bound to multiple actions in the key config that use
+/// distinct keys in the defaults
+pub struct KeyConflict {
+    pub key: KeyEvent,
+    /// names of the actions and the line of the config file they
+    /// are set in (if found)
+    pub actions: Vec<(String, Option<usize>)>,
+}
+
+/// keys pressed one after another that act like pressing `action`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct KeyChord {
+    pub keys: Vec<KeyEvent>,
+    pub action: KeyEvent,
+}
 
-pub type SharedKeyConfig = Rc<KeyConfig>;
+/// how the keys typed so far relate to the chords
+#[derive(Debug, PartialEq)]
+pub enum ChordMatch {
+    None,
+    /// the start of at least one chord
+    Prefix,
+    /// a whole chord was typed, act like its action was pressed
+    Complete(KeyEvent),
+}
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct KeyConfig {
@@ -49,12 +76,24 @@ pub struct KeyConfig {
     pub shift_down: KeyEvent,
     pub enter: KeyEvent,
     pub blame: KeyEvent,
+    pub file_filter: KeyEvent,
+    pub blame_copy_detection: KeyEvent,
     pub edit_file: KeyEvent,
     pub status_stage_all: KeyEvent,
     pub status_reset_item: KeyEvent,
+    pub status_discard_all: KeyEvent,
+    pub status_open_repo: KeyEvent,
+    pub status_update_submodule: KeyEvent,
     pub status_ignore_file: KeyEvent,
+    pub status_toggle_hidden: KeyEvent,
     pub diff_stage_lines: KeyEvent,
     pub diff_reset_lines: KeyEvent,
+    pub diff_split_toggle: KeyEvent,
+    pub diff_whitespace: KeyEvent,
+    pub diff_context_increase: KeyEvent,
+    pub diff_context_decrease: KeyEvent,
+    pub diff_jump_symbol: KeyEvent,
+    pub diff_toggle_fold: KeyEvent,
     pub stashing_save: KeyEvent,
     pub stashing_toggle_untracked: KeyEvent,
     pub stashing_toggle_index: KeyEvent,
@@ -72,6 +111,71 @@ pub struct KeyConfig {
     pub merge_branch: KeyEvent,
     pub push: KeyEvent,
     pub open_file_tree: KeyEvent,
+    pub open_repo_switcher: KeyEvent,
+    pub repo_open_path: KeyEvent,
+    pub repo_remove: KeyEvent,
+    pub status_diff_base: KeyEvent,
+    pub open_review: KeyEvent,
+    pub review_toggle_viewed: KeyEvent,
+    pub review_note: KeyEvent,
+    pub review_export_notes: KeyEvent,
+    pub undo_commit: KeyEvent,
+    pub open_context_menu: KeyEvent,
+    pub log_reset_commit: KeyEvent,
+    pub log_checkout_commit: KeyEvent,
+    pub log_mark_commit: KeyEvent,
+    pub log_squash_commits: KeyEvent,
+    pub log_fixup_commits: KeyEvent,
+    pub commit_reuse_msg: KeyEvent,
+    pub branch_set_upstream: KeyEvent,
+    pub toggle_perf_hud: KeyEvent,
+    pub file_mark: KeyEvent,
+    pub file_mark_range: KeyEvent,
+    pub log_toggle_replace_refs: KeyEvent,
+    pub status_skip_worktree: KeyEvent,
+    pub hex_goto_offset: KeyEvent,
+    pub markdown_toggle_raw: KeyEvent,
+    pub log_toggle_decorations: KeyEvent,
+    pub open_link: KeyEvent,
+    pub log_goto_commit: KeyEvent,
+    pub quick_amend: KeyEvent,
+    pub commit_stage_tracked: KeyEvent,
+    pub log_filter: KeyEvent,
+    pub log_export_patch: KeyEvent,
+    pub export_patch_copy: KeyEvent,
+    pub status_apply_patch: KeyEvent,
+    pub apply_patch_clipboard: KeyEvent,
+    pub commit_new_branch: KeyEvent,
+    pub inspect_archive: KeyEvent,
+    pub time_machine_older: KeyEvent,
+    pub time_machine_newer: KeyEvent,
+    pub view_file_pager: KeyEvent,
+    pub branch_show_log: KeyEvent,
+    pub log_tags: KeyEvent,
+    pub delete_tag: KeyEvent,
+    pub copy_commit_message: KeyEvent,
+    pub open_hooks: KeyEvent,
+    pub rebase_branch: KeyEvent,
+    pub open_rebase: KeyEvent,
+    pub rebase_skip: KeyEvent,
+    pub split_left: KeyEvent,
+    pub split_right: KeyEvent,
+    pub split_up: KeyEvent,
+    pub split_down: KeyEvent,
+    pub zoom_pane: KeyEvent,
+    pub open_command_palette: KeyEvent,
+    pub undo_operation: KeyEvent,
+    pub redo_operation: KeyEvent,
+    pub commit_complete_path: KeyEvent,
+    pub merge_ff_mode: KeyEvent,
+    pub flow_start: KeyEvent,
+    pub flow_finish: KeyEvent,
+    pub toggle_path_scope: KeyEvent,
+    pub stash_rename: KeyEvent,
+    pub stash_branch: KeyEvent,
+    pub stashing_toggle_ignored: KeyEvent,
+    pub chords: Vec<KeyChord>,
+    pub fetch_options: KeyEvent,
     pub force_push: KeyEvent,
     pub pull: KeyEvent,
     pub abort_merge: KeyEvent,
@@ -79,6 +183,7 @@ pub struct KeyConfig {
 
 #[rustfmt::skip]
 impl Default for KeyConfig {
+    #[allow(clippy::too_many_lines)]
     fn default() -> Self {
         Self {
 			tab_status: KeyEvent { code: KeyCode::Char('1'), modifiers: KeyModifiers::empty()},
@@ -109,12 +214,24 @@ impl Default for KeyConfig {
 			shift_down: KeyEvent { code: KeyCode::Down, modifiers: KeyModifiers::SHIFT},
 			enter: KeyEvent { code: KeyCode::Enter, modifiers: KeyModifiers::empty()},
 			blame: KeyEvent { code: KeyCode::Char('B'), modifiers: KeyModifiers::SHIFT},
+			file_filter: KeyEvent { code: KeyCode::Char('/'), modifiers: KeyModifiers::empty()},
+			blame_copy_detection: KeyEvent { code: KeyCode::Char('c'), modifiers: KeyModifiers::empty()},
 			edit_file: KeyEvent { code: KeyCode::Char('e'), modifiers: KeyModifiers::empty()},
 			status_stage_all: KeyEvent { code: KeyCode::Char('a'), modifiers: KeyModifiers::empty()},
 			status_reset_item: KeyEvent { code: KeyCode::Char('D'), modifiers: KeyModifiers::SHIFT},
+			status_discard_all: KeyEvent { code: KeyCode::Char('X'), modifiers: KeyModifiers::SHIFT},
+			status_open_repo: KeyEvent { code: KeyCode::Char('O'), modifiers: KeyModifiers::SHIFT},
+			status_update_submodule: KeyEvent { code: KeyCode::Char('S'), modifiers: KeyModifiers::SHIFT},
             diff_reset_lines: KeyEvent { code: KeyCode::Char('d'), modifiers: KeyModifiers::empty()},
 			status_ignore_file: KeyEvent { code: KeyCode::Char('i'), modifiers: KeyModifiers::empty()},
+			status_toggle_hidden: KeyEvent { code: KeyCode::Char('H'), modifiers: KeyModifiers::SHIFT},
             diff_stage_lines: KeyEvent { code: KeyCode::Char('s'), modifiers: KeyModifiers::empty()},
+            diff_split_toggle: KeyEvent { code: KeyCode::Char('v'), modifiers: KeyModifiers::empty()},
+            diff_whitespace: KeyEvent { code: KeyCode::Char('W'), modifiers: KeyModifiers::SHIFT},
+            diff_context_increase: KeyEvent { code: KeyCode::Char('+'), modifiers: KeyModifiers::empty()},
+            diff_context_decrease: KeyEvent { code: KeyCode::Char('-'), modifiers: KeyModifiers::empty()},
+            diff_jump_symbol: KeyEvent { code: KeyCode::Char('['), modifiers: KeyModifiers::empty()},
+            diff_toggle_fold: KeyEvent { code: KeyCode::Char('z'), modifiers: KeyModifiers::empty()},
 			stashing_save: KeyEvent { code: KeyCode::Char('s'), modifiers: KeyModifiers::empty()},
 			stashing_toggle_untracked: KeyEvent { code: KeyCode::Char('u'), modifiers: KeyModifiers::empty()},
 			stashing_toggle_index: KeyEvent { code: KeyCode::Char('i'), modifiers: KeyModifiers::empty()},
@@ -135,55 +252,164 @@ impl Default for KeyConfig {
             pull: KeyEvent { code: KeyCode::Char('f'), modifiers: KeyModifiers::empty()},
             abort_merge: KeyEvent { code: KeyCode::Char('M'), modifiers: KeyModifiers::SHIFT},
             open_file_tree: KeyEvent { code: KeyCode::Char('F'), modifiers: KeyModifiers::SHIFT},
+            open_repo_switcher: KeyEvent { code: KeyCode::Char('r'), modifiers: KeyModifiers::CONTROL},
+            repo_open_path: KeyEvent { code: KeyCode::Char('o'), modifiers: KeyModifiers::empty()},
+            repo_remove: KeyEvent { code: KeyCode::Char('D'), modifiers: KeyModifiers::SHIFT},
+            status_diff_base: KeyEvent { code: KeyCode::Char('C'), modifiers: KeyModifiers::SHIFT},
+            open_review: KeyEvent { code: KeyCode::Char('R'), modifiers: KeyModifiers::SHIFT},
+            review_toggle_viewed: KeyEvent { code: KeyCode::Char('x'), modifiers: KeyModifiers::empty()},
+            review_note: KeyEvent { code: KeyCode::Char('n'), modifiers: KeyModifiers::empty()},
+            review_export_notes: KeyEvent { code: KeyCode::Char('E'), modifiers: KeyModifiers::SHIFT},
+            undo_commit: KeyEvent { code: KeyCode::Char('U'), modifiers: KeyModifiers::SHIFT},
+            open_context_menu: KeyEvent { code: KeyCode::Char('A'), modifiers: KeyModifiers::SHIFT},
+            log_reset_commit: KeyEvent { code: KeyCode::Char('R'), modifiers: KeyModifiers::SHIFT},
+            log_checkout_commit: KeyEvent { code: KeyCode::Char('o'), modifiers: KeyModifiers::empty()},
+            log_mark_commit: KeyEvent { code: KeyCode::Char(' '), modifiers: KeyModifiers::empty()},
+            log_squash_commits: KeyEvent { code: KeyCode::Char('s'), modifiers: KeyModifiers::empty()},
+            log_fixup_commits: KeyEvent { code: KeyCode::Char('S'), modifiers: KeyModifiers::SHIFT},
+            commit_reuse_msg: KeyEvent { code: KeyCode::Char('l'), modifiers: KeyModifiers::CONTROL},
+            branch_set_upstream: KeyEvent { code: KeyCode::Char('u'), modifiers: KeyModifiers::empty()},
+            toggle_perf_hud: KeyEvent { code: KeyCode::F(12), modifiers: KeyModifiers::empty()},
+            file_mark: KeyEvent { code: KeyCode::Char(' '), modifiers: KeyModifiers::empty()},
+            file_mark_range: KeyEvent { code: KeyCode::Char('V'), modifiers: KeyModifiers::SHIFT},
+            log_toggle_replace_refs: KeyEvent { code: KeyCode::Char('N'), modifiers: KeyModifiers::SHIFT},
+            status_skip_worktree: KeyEvent { code: KeyCode::Char('L'), modifiers: KeyModifiers::SHIFT},
+            hex_goto_offset: KeyEvent { code: KeyCode::Char('g'), modifiers: KeyModifiers::empty()},
+            markdown_toggle_raw: KeyEvent { code: KeyCode::Char('T'), modifiers: KeyModifiers::SHIFT},
+            log_toggle_decorations: KeyEvent { code: KeyCode::Char('D'), modifiers: KeyModifiers::SHIFT},
+            open_link: KeyEvent { code: KeyCode::Char('o'), modifiers: KeyModifiers::CONTROL},
+            log_goto_commit: KeyEvent { code: KeyCode::Char('g'), modifiers: KeyModifiers::empty()},
+            quick_amend: KeyEvent { code: KeyCode::Char('a'), modifiers: KeyModifiers::CONTROL},
+            commit_stage_tracked: KeyEvent { code: KeyCode::Char('s'), modifiers: KeyModifiers::CONTROL},
+            log_filter: KeyEvent { code: KeyCode::Char('/'), modifiers: KeyModifiers::empty()},
+            log_export_patch: KeyEvent { code: KeyCode::Char('E'), modifiers: KeyModifiers::SHIFT},
+            export_patch_copy: KeyEvent { code: KeyCode::Char('y'), modifiers: KeyModifiers::CONTROL},
+            status_apply_patch: KeyEvent { code: KeyCode::Char('I'), modifiers: KeyModifiers::SHIFT},
+            apply_patch_clipboard: KeyEvent { code: KeyCode::Char('v'), modifiers: KeyModifiers::CONTROL},
+            commit_new_branch: KeyEvent { code: KeyCode::Char('b'), modifiers: KeyModifiers::CONTROL},
+            inspect_archive: KeyEvent { code: KeyCode::Char('Z'), modifiers: KeyModifiers::SHIFT},
+            time_machine_older: KeyEvent { code: KeyCode::Char('['), modifiers: KeyModifiers::empty()},
+            time_machine_newer: KeyEvent { code: KeyCode::Char(']'), modifiers: KeyModifiers::empty()},
+            view_file_pager: KeyEvent { code: KeyCode::Char('v'), modifiers: KeyModifiers::empty()},
+            branch_show_log: KeyEvent { code: KeyCode::Char('L'), modifiers: KeyModifiers::SHIFT},
+            log_tags: KeyEvent { code: KeyCode::Char('T'), modifiers: KeyModifiers::SHIFT},
+            delete_tag: KeyEvent { code: KeyCode::Char('D'), modifiers: KeyModifiers::SHIFT},
+            copy_commit_message: KeyEvent { code: KeyCode::Char('Y'), modifiers: KeyModifiers::SHIFT},
+            open_hooks: KeyEvent { code: KeyCode::Char('k'), modifiers: KeyModifiers::CONTROL},
+            rebase_branch: KeyEvent { code: KeyCode::Char('B'), modifiers: KeyModifiers::SHIFT},
+            open_rebase: KeyEvent { code: KeyCode::Char('B'), modifiers: KeyModifiers::SHIFT},
+            rebase_skip: KeyEvent { code: KeyCode::Char('s'), modifiers: KeyModifiers::empty()},
+            split_left: KeyEvent { code: KeyCode::Char('<'), modifiers: KeyModifiers::empty()},
+            split_right: KeyEvent { code: KeyCode::Char('>'), modifiers: KeyModifiers::empty()},
+            split_up: KeyEvent { code: KeyCode::Char('{'), modifiers: KeyModifiers::empty()},
+            split_down: KeyEvent { code: KeyCode::Char('}'), modifiers: KeyModifiers::empty()},
+            zoom_pane: KeyEvent { code: KeyCode::Char('z'), modifiers: KeyModifiers::CONTROL},
+            open_command_palette: KeyEvent { code: KeyCode::Char('p'), modifiers: KeyModifiers::CONTROL},
+            undo_operation: KeyEvent { code: KeyCode::Char('u'), modifiers: KeyModifiers::CONTROL},
+            redo_operation: KeyEvent { code: KeyCode::Char('y'), modifiers: KeyModifiers::CONTROL},
+            commit_complete_path: KeyEvent { code: KeyCode::Tab, modifiers: KeyModifiers::empty()},
+            merge_ff_mode: KeyEvent { code: KeyCode::Char('M'), modifiers: KeyModifiers::SHIFT},
+            flow_start: KeyEvent { code: KeyCode::Char('w'), modifiers: KeyModifiers::empty()},
+            flow_finish: KeyEvent { code: KeyCode::Char('W'), modifiers: KeyModifiers::SHIFT},
+            toggle_path_scope: KeyEvent { code: KeyCode::Char('g'), modifiers: KeyModifiers::CONTROL},
+            stash_rename: KeyEvent { code: KeyCode::Char('r'), modifiers: KeyModifiers::empty()},
+            stash_branch: KeyEvent { code: KeyCode::Char('b'), modifiers: KeyModifiers::empty()},
+            stashing_toggle_ignored: KeyEvent { code: KeyCode::Char('a'), modifiers: KeyModifiers::empty()},
+            chords: Vec::new(),
+            fetch_options: KeyEvent { code: KeyCode::Char('f'), modifiers: KeyModifiers::CONTROL},
         }
     }
 }
 
 impl KeyConfig {
-    fn save(&self, file: PathBuf) -> Result<()> {
-        let mut file = File::create(file)?;
-        let data = to_string_pretty(self, PrettyConfig::default())?;
-        file.write_all(data.as_bytes())?;
-        Ok(())
-    }
-
     pub fn get_config_file() -> Result<PathBuf> {
         let app_home = get_app_config_path()?;
         Ok(app_home.join("key_config.ron"))
     }
 
-    fn read_file(config_file: PathBuf) -> Result<Self> {
-        let mut f = File::open(config_file)?;
-        let mut buffer = Vec::new();
-        f.read_to_end(&mut buffer)?;
-        Ok(ron::de::from_bytes(&buffer)?)
+    /// loads `file` entry by entry, see `config::init`
+    pub fn init(file: &Path) -> (Self, Vec<String>) {
+        config::init(file, REMOVED_KEYS)
     }
 
-    pub fn init(file: PathBuf) -> Result<Self> {
-        if file.exists() {
-            match Self::read_file(file.clone()) {
-                Err(e) => {
-                    let config_path = file.clone();
-                    let config_path_old =
-                        format!("{}.old", file.to_string_lossy());
-                    fs::rename(
-                        config_path.clone(),
-                        config_path_old.clone(),
-                    )?;
-
-                    Self::default().save(file)?;
-
-                    Err(anyhow::anyhow!("{}\n Old file was renamed to {:?}.\n Defaults loaded and saved as {:?}",
-                        e,config_path_old,config_path.to_string_lossy()))
-                }
-                Ok(res) => Ok(res),
-            }
+    /// finds keys the user config binds to multiple actions that
+    /// use different keys by default, `config_file` is only read to
+    /// point at the offending lines
+    pub fn conflicts(&self, config_file: &Path) -> Vec<KeyConflict> {
+        let defaults: HashMap<String, String> =
+            Self::default().bindings().into_iter().collect();
+
+        let mut by_key: BTreeMap<String, Vec<String>> =
+            BTreeMap::new();
+        for (action, key) in self.bindings() {
+            by_key.entry(key).or_default().push(action);
+        }
+
+        let config_entries = fs::read_to_string(config_file)
+            .ok()
+            .and_then(|data| config::entries(&data))
+            .unwrap_or_default();
+        let line_of = |action: &str| {
+            config_entries
+                .iter()
+                .find(|entry| entry.name == action)
+                .map(|entry| entry.line)
+        };
+
+        by_key
+            .into_iter()
+            .filter(|(_, actions)| {
+                actions.len() > 1
+                    && actions.iter().any(|action| {
+                        defaults.get(action)
+                            != defaults.get(&actions[0])
+                    })
+            })
+            .filter_map(|(key, actions)| {
+                Some(KeyConflict {
+                    key: ron::de::from_str(&key).ok()?,
+                    actions: actions
+                        .into_iter()
+                        .map(|action| {
+                            let line = line_of(&action);
+                            (action, line)
+                        })
+                        .collect(),
+                })
+            })
+            .collect()
+    }
+
+    /// a chord only starts with a key that does nothing on its own
+    /// where it is pressed (see `App::event`)
+    pub fn chord_match(&self, keys: &[KeyEvent]) -> ChordMatch {
+        if let Some(chord) =
+            self.chords.iter().find(|chord| chord.keys == keys)
+        {
+            ChordMatch::Complete(chord.action)
+        } else if self
+            .chords
+            .iter()
+            .any(|chord| chord.keys.starts_with(keys))
+        {
+            ChordMatch::Prefix
         } else {
-            Self::default().save(file)?;
-            Ok(Self::default())
+            ChordMatch::None
         }
     }
 
+    /// all actions with their serialized key, read from the
+    /// serialized config so new fields are covered automatically
+    fn bindings(&self) -> Vec<(String, String)> {
+        ron::ser::to_string(self)
+            .ok()
+            .and_then(|data| config::entries(&data))
+            .unwrap_or_default()
+            .into_iter()
+            .map(|entry| (entry.name, entry.value))
+            .collect()
+    }
+
     //TODO: make this configurable (https://github.com/extrawurst/gitui/issues/465)
     #[allow(clippy::unused_self)]
     const fn get_key_symbol(&self, k: KeyCode) -> &str {
@@ -230,6 +456,12 @@ impl KeyConfig {
                     self.get_key_symbol(ev.code)
                 )
             }
+            KeyCode::Char(' ') => {
+                format!(
+                    "{}\u{2423}", //␣
+                    Self::get_modifier_hint(ev.modifiers)
+                )
+            }
             KeyCode::Char(c) => {
                 format!(
                     "{}{}",
@@ -265,8 +497,11 @@ impl KeyConfig {
 
 #[cfg(test)]
 mod tests {
-    use super::KeyConfig;
+    use super::{ChordMatch, KeyChord, KeyConfig, REMOVED_KEYS};
+    use crate::config;
     use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use std::path::Path;
+    use tempfile::TempDir;
 
     #[test]
     fn test_get_hint() {
@@ -279,11 +514,88 @@ mod tests {
     }
 
     #[test]
-    fn test_load_vim_style_example() {
+    fn test_conflicts() {
+        let mut config = KeyConfig::default();
+        let file = Path::new("does_not_exist.ron");
+
+        assert!(config.conflicts(file).is_empty());
+
+        config.stash_apply = config.blame;
+
+        let conflicts = config.conflicts(file);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].key, config.blame);
+
+        let mut actions: Vec<&str> = conflicts[0]
+            .actions
+            .iter()
+            .map(|(action, _)| action.as_str())
+            .collect();
+        actions.sort_unstable();
+        assert_eq!(actions, vec!["blame", "stash_apply"]);
+    }
+
+    #[test]
+    fn test_conflicts_config_lines() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("key_config.ron");
+        std::fs::write(
+            &file,
+            "(\n    blame: a,\n    stash_apply: b,\n)",
+        )
+        .unwrap();
+
+        let mut config = KeyConfig::default();
+        config.stash_apply = config.blame;
+
+        let mut actions = config.conflicts(&file).remove(0).actions;
+        actions.sort();
         assert_eq!(
-            KeyConfig::read_file("vim_style_key_config.ron".into())
-                .is_ok(),
-            true
+            actions,
+            vec![
+                (String::from("blame"), Some(2)),
+                (String::from("stash_apply"), Some(3))
+            ]
         );
     }
+
+    #[test]
+    fn test_chord_match() {
+        let g = KeyEvent {
+            code: KeyCode::Char('g'),
+            modifiers: KeyModifiers::empty(),
+        };
+        let x = KeyEvent {
+            code: KeyCode::Char('x'),
+            modifiers: KeyModifiers::empty(),
+        };
+
+        let mut config = KeyConfig::default();
+        assert_eq!(config.chord_match(&[g]), ChordMatch::None);
+
+        config.chords.push(KeyChord {
+            keys: vec![g, g],
+            action: config.home,
+        });
+
+        assert_eq!(config.chord_match(&[g]), ChordMatch::Prefix);
+        assert_eq!(
+            config.chord_match(&[g, g]),
+            ChordMatch::Complete(config.home)
+        );
+        assert_eq!(config.chord_match(&[g, x]), ChordMatch::None);
+        assert_eq!(config.chord_match(&[x]), ChordMatch::None);
+    }
+
+    #[test]
+    fn test_load_vim_style_example() {
+        let loaded = config::load::<KeyConfig>(
+            Path::new("vim_style_key_config.ron"),
+            REMOVED_KEYS,
+        )
+        .unwrap();
+
+        assert!(loaded.errors.is_empty());
+        assert!(!loaded.migrated);
+    }
 }
@@ -0,0 +1,163 @@
+use anyhow::{anyhow, Result};
+use std::process::{Command, Stdio};
+
+/// placeholder of the issue id in `issue_url_template`
+const ISSUE_ID: &str = "{id}";
+
+/// a url or an issue reference found in a text
+#[derive(Clone, Debug, PartialEq)]
+pub struct Link {
+    /// as written in the text
+    pub text: String,
+    pub url: String,
+}
+
+/// urls, `#123` and `JIRA-123` references in `text` (without
+/// duplicates), the latter only with a template to make urls of them
+pub fn find_links(
+    text: &str,
+    issue_url_template: Option<&str>,
+) -> Vec<Link> {
+    let mut res: Vec<Link> = Vec::new();
+
+    for word in text.split(|c: char| {
+        c.is_whitespace() || matches!(c, '<' | '>' | '"' | '\'' | '`')
+    }) {
+        let link = find_url(word).or_else(|| {
+            issue_url_template.and_then(|template| {
+                find_issue(word).map(|id| Link {
+                    text: id.to_string(),
+                    url: template.replace(
+                        ISSUE_ID,
+                        id.trim_start_matches('#'),
+                    ),
+                })
+            })
+        });
+
+        if let Some(link) = link {
+            if !res.contains(&link) {
+                res.push(link);
+            }
+        }
+    }
+
+    res
+}
+
+fn find_url(word: &str) -> Option<Link> {
+    let start =
+        word.find("https://").or_else(|| word.find("http://"))?;
+    let url = word[start..].trim_end_matches(|c: char| {
+        matches!(c, '.' | ',' | ';' | ':' | '!' | '?' | ')' | ']')
+    });
+
+    Some(Link {
+        text: url.to_string(),
+        url: url.to_string(),
+    })
+}
+
+/// `#123` or `KEY-123` surrounded by punctuation
+fn find_issue(word: &str) -> Option<&str> {
+    let id = word.trim_matches(|c: char| {
+        !c.is_ascii_alphanumeric() && c != '#'
+    });
+
+    if let Some(number) = id.strip_prefix('#') {
+        return if is_number(number) { Some(id) } else { None };
+    }
+
+    let mut parts = id.splitn(2, '-');
+    let key = parts.next()?;
+    let number = parts.next()?;
+
+    let is_key = key.len() > 1
+        && key.starts_with(|c: char| c.is_ascii_uppercase())
+        && key
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit());
+
+    if is_key && is_number(number) {
+        Some(id)
+    } else {
+        None
+    }
+}
+
+fn is_number(text: &str) -> bool {
+    !text.is_empty() && text.chars().all(|c| c.is_ascii_digit())
+}
+
+/// opens `url` in the browser (`$BROWSER` if set)
+pub fn open_url(url: &str) -> Result<()> {
+    let mut command = std::env::var_os("BROWSER")
+        .map_or_else(open_command, Command::new);
+
+    let status = command
+        .arg(url)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|e| anyhow!("`{:?}`: {}", command, e))?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(anyhow!("`{:?}`: {}", command, status))
+    }
+}
+
+#[cfg(all(target_family = "unix", not(target_os = "macos")))]
+fn open_command() -> Command {
+    Command::new("xdg-open")
+}
+
+#[cfg(target_os = "macos")]
+fn open_command() -> Command {
+    Command::new("open")
+}
+
+#[cfg(windows)]
+fn open_command() -> Command {
+    let mut command = Command::new("cmd");
+    command.args(&["/C", "start", ""]);
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(links: &[Link]) -> Vec<&str> {
+        links.iter().map(|link| link.text.as_str()).collect()
+    }
+
+    #[test]
+    fn test_urls() {
+        let links = find_links(
+            "see https://example.com/a?b=c, (http://x.org/y) and <https://example.com/a?b=c>.",
+            None,
+        );
+
+        assert_eq!(
+            texts(&links),
+            vec!["https://example.com/a?b=c", "http://x.org/y"]
+        );
+    }
+
+    #[test]
+    fn test_issues() {
+        let text =
+            "fixes #12, refs (JIRA-345) but not a#1, UTF8 or x-1";
+
+        assert!(find_links(text, None).is_empty());
+
+        let links =
+            find_links(text, Some("https://tracker/issue/{id}"));
+
+        assert_eq!(texts(&links), vec!["#12", "JIRA-345"]);
+        assert_eq!(links[0].url, "https://tracker/issue/12");
+        assert_eq!(links[1].url, "https://tracker/issue/JIRA-345");
+    }
+}
@@ -20,23 +20,43 @@ mod bug_report;
 mod clipboard;
 mod cmdbar;
 mod components;
+mod config;
+mod crash_report;
 mod input;
+mod instance_sync;
 mod keys;
+mod links;
 mod notify_mutex;
+mod options;
+mod path_patterns;
+mod perf_hud;
 mod profiler;
 mod queue;
+mod recent_repos;
+mod review_notes;
+mod settings_bundle;
 mod spinner;
 mod strings;
 mod tabs;
 mod ui;
+#[cfg(test)]
+mod ui_test;
 mod version;
+mod watcher;
 
-use crate::{app::App, args::process_cmdline};
+use crate::{
+    app::App, args::process_cmdline, config::FileWatcher,
+    watcher::RepoWatcher,
+};
 use anyhow::{bail, Result};
-use asyncgit::AsyncNotification;
+use asyncgit::{sync::RepoPath, AsyncNotification, CWD};
 use backtrace::Backtrace;
-use crossbeam_channel::{tick, unbounded, Receiver, Select};
+use crossbeam_channel::{
+    never, tick, unbounded, Receiver, Select, Sender,
+};
 use crossterm::{
+    cursor::Show,
+    event::{DisableMouseCapture, EnableMouseCapture},
     terminal::{
         disable_raw_mode, enable_raw_mode, EnterAlternateScreen,
         LeaveAlternateScreen,
@@ -44,21 +64,27 @@ use crossterm::{
     ExecutableCommand,
 };
 use input::{Input, InputEvent, InputState};
-use keys::KeyConfig;
+use keys::{KeyConfig, SharedKeyConfig};
 use profiler::Profiler;
 use scopeguard::defer;
 use scopetime::scope_time;
 use spinner::Spinner;
 use std::{
+    cell::RefCell,
+    env,
     io::{self, Write},
-    panic, process,
+    panic,
+    path::{Path, PathBuf},
+    process,
+    rc::Rc,
+    sync::atomic::{AtomicBool, Ordering},
     time::{Duration, Instant},
 };
 use tui::{
     backend::{Backend, CrosstermBackend},
     Terminal,
 };
-use ui::style::Theme;
+use ui::style::{SharedTheme, Theme};
 
 static TICK_INTERVAL: Duration = Duration::from_secs(5);
 static SPINNER_INTERVAL: Duration = Duration::from_millis(80);
@@ -68,6 +94,8 @@ static SPINNER_INTERVAL: Duration = Duration::from_millis(80);
 pub enum QueueEvent {
     Tick,
     SpinnerUpdate,
+    /// files changed on disk
+    Notify,
     GitEvent(AsyncNotification),
     InputEvent(InputEvent),
 }
@@ -77,17 +105,15 @@ fn main() -> Result<()> {
 
     let _profiler = Profiler::new();
 
-    if !valid_path()? {
+    let repo = RepoPath::from_env(CWD);
+
+    if !valid_path(&repo)? {
         eprintln!("invalid path\nplease run gitui inside of a non-bare git repository");
         return Ok(());
     }
 
-    let key_config = KeyConfig::init(KeyConfig::get_config_file()?)
-        .map_err(|e| eprintln!("KeyConfig loading error: {}", e))
-        .unwrap_or_default();
-    let theme = Theme::init(cliargs.theme)
-        .map_err(|e| eprintln!("Theme loading error: {}", e))
-        .unwrap_or_default();
+    let (mut config, config_errors) =
+        ConfigFiles::load(cliargs.theme)?;
 
     setup_terminal()?;
     defer! {
@@ -106,7 +132,10 @@ fn main() -> Result<()> {
     let ticker = tick(TICK_INTERVAL);
     let spinner_ticker = tick(SPINNER_INTERVAL);
 
-    let mut app = App::new(&tx_git, input, theme, key_config);
+    let mut app =
+        config.new_app(repo, &tx_git, &input, cliargs.path_scope);
+    app.show_config_errors(config_errors)?;
+    let mut watcher = start_watcher(&app);
 
     let mut spinner = Spinner::default();
     let mut first_update = true;
@@ -121,6 +150,7 @@ fn main() -> Result<()> {
                 &rx_git,
                 &ticker,
                 &spinner_ticker,
+                watcher.as_ref().map(RepoWatcher::receiver),
             )?
         };
 
@@ -133,6 +163,12 @@ fn main() -> Result<()> {
 
             scope_time!("loop");
 
+            // before handling the event, it might be a key bound
+            // differently now
+            if config.watcher.changed() {
+                config.reload(&mut app)?;
+            }
+
             match event {
                 QueueEvent::InputEvent(ev) => {
                     if let InputEvent::State(InputState::Polling) = ev
@@ -142,7 +178,9 @@ fn main() -> Result<()> {
                     }
                     app.event(ev)?
                 }
-                QueueEvent::Tick => app.update()?,
+                QueueEvent::Tick | QueueEvent::Notify => {
+                    app.update()?
+                }
                 QueueEvent::GitEvent(ev)
                     if ev != AsyncNotification::FinishUnchanged =>
                 {
@@ -152,7 +190,10 @@ fn main() -> Result<()> {
                 QueueEvent::SpinnerUpdate => unreachable!(),
             }
 
+            let draw_start = Instant::now();
             draw(&mut terminal, &app)?;
+            app.set_frame_time(draw_start.elapsed());
+            app.set_backlog(rx_input.len() + rx_git.len());
 
             spinner.set_state(app.any_work_pending());
             spinner.draw(&mut terminal)?;
@@ -160,19 +201,69 @@ fn main() -> Result<()> {
             if app.is_quit() {
                 break;
             }
+
+            if let Some(path) = app.take_repo_switch() {
+                // a fresh app inside of the new repo retargets every
+                // async job, `GIT_DIR`/`GIT_WORK_TREE` only apply to
+                // the repository gitui was started in
+                env::set_current_dir(&path)?;
+                // the scope is a folder of the previous repository
+                app = config.new_app(
+                    RepoPath::Path(path.into()),
+                    &tx_git,
+                    &input,
+                    None,
+                );
+                watcher = start_watcher(&app);
+                first_update = true;
+            }
         }
     }
 
     Ok(())
 }
 
+/// the terminal has to be restored before printing anything,
+/// otherwise the output is lost in the alternate screen
+fn report_panic(msg: &str, backtrace: &Backtrace) {
+    let backtrace = format!("{:?}", backtrace);
+
+    log::error!("panic: {}\ntrace:\n{}", msg, backtrace);
+    eprintln!("panic: {}\ntrace:\n{}", msg, backtrace);
+
+    match crash_report::write(msg, &backtrace) {
+        Ok(file) => {
+            eprintln!("crash report written to: {}", file.display());
+        }
+        Err(e) => eprintln!("writing crash report failed: {}", e),
+    }
+}
+
+/// set while raw mode and the alternate screen are active
+static TERMINAL_ACTIVE: AtomicBool = AtomicBool::new(false);
+
 fn setup_terminal() -> Result<()> {
+    TERMINAL_ACTIVE.store(true, Ordering::SeqCst);
     enable_raw_mode()?;
     io::stdout().execute(EnterAlternateScreen)?;
+    io::stdout().execute(EnableMouseCapture)?;
     Ok(())
 }
 
+/// safe to call multiple times (panic hook and regular shutdown)
 fn shutdown_terminal() {
+    if !TERMINAL_ACTIVE.swap(false, Ordering::SeqCst) {
+        return;
+    }
+
+    if let Err(e) = io::stdout().execute(Show) {
+        eprintln!("show cursor failed:\n{}", e);
+    }
+
+    if let Err(e) = io::stdout().execute(DisableMouseCapture) {
+        eprintln!("disable mouse capture failed:\n{}", e);
+    }
+
     let leave_screen =
         io::stdout().execute(LeaveAlternateScreen).map(|_f| ());
 
@@ -204,9 +295,101 @@ fn draw<B: Backend>(
     Ok(())
 }
 
-fn valid_path() -> Result<bool> {
-    Ok(asyncgit::sync::is_repo(asyncgit::CWD)
-        && !asyncgit::sync::is_bare_repo(asyncgit::CWD)?)
+fn valid_path(repo: &RepoPath) -> Result<bool> {
+    Ok(asyncgit::sync::is_repo(repo)
+        && !asyncgit::sync::is_bare_repo(repo)?)
+}
+
+fn load_config(
+    key_config_file: &Path,
+    theme_file: &Path,
+) -> (KeyConfig, Theme, Vec<String>) {
+    let (key_config, mut errors) = KeyConfig::init(key_config_file);
+    let (theme, theme_errors) = Theme::init(theme_file);
+    errors.extend(theme_errors);
+
+    (key_config, theme, errors)
+}
+
+/// key config and theme, reloaded when their files change
+struct ConfigFiles {
+    key_config_file: PathBuf,
+    theme_file: PathBuf,
+    watcher: FileWatcher,
+    key_config: SharedKeyConfig,
+    theme: SharedTheme,
+}
+
+impl ConfigFiles {
+    /// loads both files, returning the errors found in them
+    fn load(theme_file: PathBuf) -> Result<(Self, Vec<String>)> {
+        let key_config_file = KeyConfig::get_config_file()?;
+        let (key_config, theme, errors) =
+            load_config(&key_config_file, &theme_file);
+        let watcher = FileWatcher::new(vec![
+            key_config_file.clone(),
+            theme_file.clone(),
+        ]);
+
+        Ok((
+            Self {
+                key_config_file,
+                theme_file,
+                watcher,
+                key_config: Rc::new(RefCell::new(key_config)),
+                theme: Rc::new(RefCell::new(theme)),
+            },
+            errors,
+        ))
+    }
+
+    /// loads both files again into the theme and keys shared by all
+    /// components of `app`, which shows the errors found in them
+    fn reload(&mut self, app: &mut App) -> Result<()> {
+        let (key_config, theme, errors) =
+            load_config(&self.key_config_file, &self.theme_file);
+        // loading writes missing files, that is no edit
+        self.watcher.changed();
+
+        *self.key_config.borrow_mut() = key_config;
+        *self.theme.borrow_mut() = theme;
+
+        app.config_reloaded(errors)
+    }
+
+    /// an app sharing the loaded theme and keys
+    fn new_app(
+        &self,
+        repo: RepoPath,
+        tx_git: &Sender<AsyncNotification>,
+        input: &Input,
+        path_scope: Option<String>,
+    ) -> App {
+        App::new(
+            repo,
+            tx_git,
+            input.clone(),
+            self.theme.clone(),
+            self.key_config.clone(),
+            path_scope,
+        )
+    }
+}
+
+/// watches the repository if enabled, falls back to polling if the
+/// watcher fails
+fn start_watcher(app: &App) -> Option<RepoWatcher> {
+    if !app.watch_files() {
+        return None;
+    }
+
+    match RepoWatcher::new(app.repo()) {
+        Ok(watcher) => Some(watcher),
+        Err(e) => {
+            log::error!("file watcher error: {}", e);
+            None
+        }
+    }
 }
 
 fn select_event(
@@ -214,13 +397,18 @@ fn select_event(
     rx_git: &Receiver<AsyncNotification>,
     rx_ticker: &Receiver<Instant>,
     rx_spinner: &Receiver<Instant>,
+    rx_watcher: Option<&Receiver<()>>,
 ) -> Result<QueueEvent> {
+    let rx_never = never();
+    let rx_watcher = rx_watcher.unwrap_or(&rx_never);
+
     let mut sel = Select::new();
 
     sel.recv(rx_input);
     sel.recv(rx_git);
     sel.recv(rx_ticker);
     sel.recv(rx_spinner);
+    sel.recv(rx_watcher);
 
     let oper = sel.select();
     let index = oper.index();
@@ -230,6 +418,7 @@ fn select_event(
         1 => oper.recv(rx_git).map(QueueEvent::GitEvent),
         2 => oper.recv(rx_ticker).map(|_| QueueEvent::Tick),
         3 => oper.recv(rx_spinner).map(|_| QueueEvent::SpinnerUpdate),
+        4 => oper.recv(rx_watcher).map(|_| QueueEvent::Notify),
         _ => bail!("unknown select source"),
     }?;
 
@@ -251,20 +440,23 @@ fn set_panic_handlers() -> Result<()> {
     // regular panic handler
     panic::set_hook(Box::new(|e| {
         let backtrace = Backtrace::new();
-        //TODO: create macro to do both in one
-        log::error!("panic: {:?}\ntrace:\n{:?}", e, backtrace);
-        eprintln!("panic: {:?}\ntrace:\n{:?}", e, backtrace);
         shutdown_terminal();
+        report_panic(&e.to_string(), &backtrace);
     }));
 
     // global threadpool
     rayon_core::ThreadPoolBuilder::new()
         .panic_handler(|e| {
             let backtrace = Backtrace::new();
-            //TODO: create macro to do both in one
-            log::error!("panic: {:?}\ntrace:\n{:?}", e, backtrace);
-            eprintln!("panic: {:?}\ntrace:\n{:?}", e, backtrace);
             shutdown_terminal();
+
+            let msg = e
+                .downcast_ref::<&str>()
+                .map(|msg| (*msg).to_string())
+                .or_else(|| e.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| String::from("unknown panic"));
+            report_panic(&msg, &backtrace);
+
             process::abort();
         })
         .num_threads(4)
@@ -0,0 +1,635 @@
+use anyhow::Result;
+use asyncgit::{
+    sync::{
+        utils::repo_dir, BlameCopyDetection, CommitMsgLintOptions,
+        DiffOptions, FlowBranch, FlowNaming, RepoPath,
+    },
+    NetworkLimits,
+};
+use ron::ser::{to_string_pretty, PrettyConfig};
+use serde::{Deserialize, Serialize};
+use std::{
+    cell::RefCell, collections::BTreeMap, fs::File, io::Write,
+    path::PathBuf, rc::Rc, time::Duration,
+};
+
+use crate::{
+    clipboard::ClipboardBackend,
+    config::{self, FileWatcher},
+    path_patterns::PathPatterns,
+};
+
+/// commits of files matching `paths` (like `crates/asyncgit/**`)
+/// are prefilled with `scope`
+#[derive(Clone, Serialize, Deserialize)]
+struct CommitScope {
+    paths: String,
+    scope: String,
+}
+
+/// preset of the branch list's start and finish branch keys
+#[derive(Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Workflow {
+    None,
+    /// feature, release and hotfix branches
+    GitFlow,
+    /// short-lived branches fast forwarded into the main branch
+    TrunkBased,
+}
+
+impl Default for Workflow {
+    fn default() -> Self {
+        Self::None
+    }
+}
+
+impl Workflow {
+    /// the branches to start and finish
+    pub const fn kinds(self) -> &'static [FlowBranch] {
+        match self {
+            Self::None => &[],
+            Self::GitFlow => &[
+                FlowBranch::Feature,
+                FlowBranch::Release,
+                FlowBranch::Hotfix,
+            ],
+            Self::TrunkBased => &[FlowBranch::Trunk],
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+struct OptionsData {
+    diff_split: bool,
+    diff_ignore_whitespace: bool,
+    diff_ignore_whitespace_eol: bool,
+    diff_context: u32,
+    diff_fold_patterns: Vec<String>,
+    /// 0 off, 1 lines moved within the file (`-M`), 2 also copied
+    /// from files of the same commit (`-C`), 3 from any commit
+    blame_copy_detection: u8,
+    status_hide_patterns: Vec<String>,
+    status_show_hidden: bool,
+    status_watch_files: bool,
+    status_shallow: bool,
+    status_split: Option<u16>,
+    status_lists_split: Option<u16>,
+    revlog_split: u16,
+    commit_subject_max_len: usize,
+    commit_blank_line_after_subject: bool,
+    commit_imperative_mood: bool,
+    commit_msg_history: Vec<String>,
+    commit_scopes: Vec<CommitScope>,
+    commit_scope_format: String,
+    review_base: Option<String>,
+    review_viewed: BTreeMap<String, Vec<String>>,
+    issue_url_template: Option<String>,
+    commit_allow_empty_message: bool,
+    conflicts_auto_continue: bool,
+    autostash: bool,
+    clipboard: ClipboardBackend,
+    network_max_concurrent: usize,
+    network_max_per_remote: usize,
+    network_remote_interval_secs: u64,
+    workflow: Workflow,
+    workflow_main: String,
+    workflow_develop: String,
+    workflow_feature_prefix: String,
+    workflow_release_prefix: String,
+    workflow_hotfix_prefix: String,
+    workflow_trunk_prefix: String,
+    workflow_tag_prefix: String,
+}
+
+impl Default for OptionsData {
+    fn default() -> Self {
+        let diff = DiffOptions::default();
+        let commit_lint = CommitMsgLintOptions::default();
+        let network = NetworkLimits::default();
+        let naming = FlowNaming::default();
+
+        Self {
+            diff_split: false,
+            diff_ignore_whitespace: diff.ignore_whitespace,
+            diff_ignore_whitespace_eol: diff.ignore_whitespace_eol,
+            diff_context: diff.context,
+            diff_fold_patterns: [
+                "Cargo.lock",
+                "package-lock.json",
+                "yarn.lock",
+                "*.min.js",
+                "*.min.css",
+            ]
+            .iter()
+            .map(|pattern| (*pattern).to_string())
+            .collect(),
+            blame_copy_detection: 0,
+            status_hide_patterns: Vec::new(),
+            status_show_hidden: false,
+            status_watch_files: true,
+            status_shallow: false,
+            status_split: None,
+            status_lists_split: None,
+            revlog_split: 60,
+            commit_subject_max_len: commit_lint.subject_max_len,
+            commit_blank_line_after_subject: commit_lint
+                .blank_line_after_subject,
+            commit_imperative_mood: commit_lint.imperative_mood,
+            commit_msg_history: Vec::new(),
+            commit_scopes: Vec::new(),
+            commit_scope_format: "{scope}: ".to_string(),
+            review_base: None,
+            review_viewed: BTreeMap::new(),
+            issue_url_template: None,
+            commit_allow_empty_message: false,
+            conflicts_auto_continue: false,
+            autostash: false,
+            clipboard: ClipboardBackend::default(),
+            network_max_concurrent: network.max_concurrent,
+            network_max_per_remote: network.max_per_remote,
+            network_remote_interval_secs: network
+                .remote_interval
+                .as_secs(),
+            workflow: Workflow::default(),
+            workflow_main: naming.main,
+            workflow_develop: naming.develop,
+            workflow_feature_prefix: naming.feature_prefix,
+            workflow_release_prefix: naming.release_prefix,
+            workflow_hotfix_prefix: naming.hotfix_prefix,
+            workflow_trunk_prefix: naming.trunk_prefix,
+            workflow_tag_prefix: naming.tag_prefix,
+        }
+    }
+}
+
+/// per repository ui options, persisted inside of the `.git` folder
+pub struct Options {
+    repo: RepoPath,
+    data: OptionsData,
+    /// problems found reading the options file
+    load_errors: Vec<String>,
+    /// notices edits of the options file from outside
+    watcher: Option<FileWatcher>,
+    /// folder the status and log are restricted to (`--path-scope`),
+    /// not saved
+    path_scope: Option<String>,
+    path_scope_active: bool,
+}
+
+pub type SharedOptions = Rc<RefCell<Options>>;
+
+impl Options {
+    pub fn new(repo: RepoPath) -> SharedOptions {
+        let (data, load_errors) =
+            Self::read(&repo).unwrap_or_else(|e| {
+                (OptionsData::default(), vec![e.to_string()])
+            });
+
+        let watcher = Self::options_file(&repo)
+            .ok()
+            .map(|file| FileWatcher::new(vec![file]));
+
+        Rc::new(RefCell::new(Self {
+            data,
+            load_errors,
+            watcher,
+            path_scope: None,
+            path_scope_active: true,
+            repo,
+        }))
+    }
+
+    /// reads the options again if the file was edited from outside,
+    /// problems are available via `take_load_errors`
+    pub fn reload_if_changed(&mut self) -> bool {
+        if !self.watcher.as_mut().map_or(false, FileWatcher::changed)
+        {
+            return false;
+        }
+
+        match Self::read(&self.repo) {
+            Ok((data, errors)) => {
+                self.data = data;
+                self.load_errors = errors;
+            }
+            Err(e) => self.load_errors = vec![e.to_string()],
+        }
+
+        true
+    }
+
+    /// errors found while reading the options, only returned once
+    pub fn take_load_errors(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.load_errors)
+    }
+
+    /// diff is shown side by side instead of unified
+    pub const fn diff_split(&self) -> bool {
+        self.data.diff_split
+    }
+
+    pub fn toggle_diff_split(&mut self) {
+        self.data.diff_split = !self.data.diff_split;
+        self.save();
+    }
+
+    pub const fn diff_options(&self) -> DiffOptions {
+        DiffOptions {
+            ignore_whitespace: self.data.diff_ignore_whitespace,
+            ignore_whitespace_eol: self
+                .data
+                .diff_ignore_whitespace_eol,
+            context: self.data.diff_context,
+        }
+    }
+
+    /// diffs of files matching these are folded by default
+    pub fn diff_fold_patterns(&self) -> &[String] {
+        &self.data.diff_fold_patterns
+    }
+
+    /// how hard blame looks for moved or copied lines
+    pub const fn blame_copy_detection(&self) -> BlameCopyDetection {
+        match self.data.blame_copy_detection {
+            0 => BlameCopyDetection::Off,
+            1 => BlameCopyDetection::SameFile,
+            2 => BlameCopyDetection::SameCommit,
+            _ => BlameCopyDetection::AnyCommit,
+        }
+    }
+
+    pub fn set_blame_copy_detection(
+        &mut self,
+        copy_detection: BlameCopyDetection,
+    ) {
+        self.data.blame_copy_detection = match copy_detection {
+            BlameCopyDetection::Off => 0,
+            BlameCopyDetection::SameFile => 1,
+            BlameCopyDetection::SameCommit => 2,
+            BlameCopyDetection::AnyCommit => 3,
+        };
+        self.save();
+    }
+
+    /// tracked files matching these are hidden in the status lists
+    pub fn status_hide_patterns(&self) -> &[String] {
+        &self.data.status_hide_patterns
+    }
+
+    pub const fn status_show_hidden(&self) -> bool {
+        self.data.status_show_hidden
+    }
+
+    pub fn toggle_status_show_hidden(&mut self) {
+        self.data.status_show_hidden = !self.data.status_show_hidden;
+        self.save();
+    }
+
+    /// refresh the status when files change on disk instead of
+    /// only polling
+    pub const fn status_watch_files(&self) -> bool {
+        self.data.status_watch_files
+    }
+
+    /// the unstaged changes only tell which top-level folders have
+    /// changes, for worktrees too big for a full status
+    pub const fn status_shallow(&self) -> bool {
+        self.data.status_shallow
+    }
+
+    /// width of the file lists in percent of the status tab, `None`
+    /// widens the diff while it is focused
+    pub const fn status_split(&self) -> Option<u16> {
+        self.data.status_split
+    }
+
+    pub fn set_status_split(&mut self, split: u16) {
+        self.data.status_split = Some(split);
+        self.save();
+    }
+
+    /// height of the working dir list in percent of both lists,
+    /// `None` makes the one shown in the diff larger
+    pub const fn status_lists_split(&self) -> Option<u16> {
+        self.data.status_lists_split
+    }
+
+    pub fn set_status_lists_split(&mut self, split: u16) {
+        self.data.status_lists_split = Some(split);
+        self.save();
+    }
+
+    /// width of the commit list in percent of the log tab (when the
+    /// details are shown)
+    pub const fn revlog_split(&self) -> u16 {
+        self.data.revlog_split
+    }
+
+    pub fn set_revlog_split(&mut self, split: u16) {
+        self.data.revlog_split = split;
+        self.save();
+    }
+
+    /// checks run on the message in the commit popup
+    pub const fn commit_msg_lint(&self) -> CommitMsgLintOptions {
+        CommitMsgLintOptions {
+            subject_max_len: self.data.commit_subject_max_len,
+            blank_line_after_subject: self
+                .data
+                .commit_blank_line_after_subject,
+            imperative_mood: self.data.commit_imperative_mood,
+        }
+    }
+
+    /// commits may have an empty message
+    /// (`git commit --allow-empty-message`)
+    pub const fn commit_allow_empty_message(&self) -> bool {
+        self.data.commit_allow_empty_message
+    }
+
+    /// a merge or rebase goes on by itself once the last conflict is
+    /// staged (if the commit hooks pass)
+    pub const fn conflicts_auto_continue(&self) -> bool {
+        self.data.conflicts_auto_continue
+    }
+
+    /// local changes get stashed around pulls, merges, rebases and
+    /// checkouts and applied again afterwards
+    pub const fn autostash(&self) -> bool {
+        self.data.autostash
+    }
+
+    /// `Osc52` makes copying work inside ssh and tmux sessions
+    pub const fn clipboard(&self) -> ClipboardBackend {
+        self.data.clipboard
+    }
+
+    /// fetches and pushes beyond these limits wait in a queue
+    pub const fn network_limits(&self) -> NetworkLimits {
+        NetworkLimits {
+            max_concurrent: self.data.network_max_concurrent,
+            max_per_remote: self.data.network_max_per_remote,
+            remote_interval: Duration::from_secs(
+                self.data.network_remote_interval_secs,
+            ),
+        }
+    }
+
+    /// the folder the status and log are restricted to unless it is
+    /// toggled off
+    pub fn path_scope(&self) -> Option<&str> {
+        if self.path_scope_active {
+            self.path_scope.as_deref()
+        } else {
+            None
+        }
+    }
+
+    /// the scope no matter if it is toggled off
+    pub fn path_scope_setting(&self) -> Option<&str> {
+        self.path_scope.as_deref()
+    }
+
+    pub fn set_path_scope(&mut self, scope: Option<String>) {
+        self.path_scope = scope;
+        self.path_scope_active = true;
+    }
+
+    pub fn toggle_path_scope(&mut self) {
+        self.path_scope_active = !self.path_scope_active;
+    }
+
+    pub const fn workflow(&self) -> Workflow {
+        self.data.workflow
+    }
+
+    /// branch names of the `workflow`
+    pub fn flow_naming(&self) -> FlowNaming {
+        FlowNaming {
+            main: self.data.workflow_main.clone(),
+            develop: self.data.workflow_develop.clone(),
+            feature_prefix: self.data.workflow_feature_prefix.clone(),
+            release_prefix: self.data.workflow_release_prefix.clone(),
+            hotfix_prefix: self.data.workflow_hotfix_prefix.clone(),
+            trunk_prefix: self.data.workflow_trunk_prefix.clone(),
+            tag_prefix: self.data.workflow_tag_prefix.clone(),
+        }
+    }
+
+    /// kind and name of the flow branch `branch` of the `workflow`
+    pub fn flow_branch(
+        &self,
+        branch: &str,
+    ) -> Option<(FlowBranch, String)> {
+        self.flow_naming().parse(branch, self.workflow().kinds())
+    }
+
+    /// recently used commit messages, newest first
+    pub fn commit_msg_history(&self) -> &[String] {
+        &self.data.commit_msg_history
+    }
+
+    /// remembers `msg` as the most recent commit message
+    pub fn add_commit_msg(&mut self, msg: &str) {
+        const HISTORY_SIZE: usize = 20;
+
+        let msg = msg.trim();
+        if msg.is_empty()
+            || self
+                .data
+                .commit_msg_history
+                .first()
+                .map(String::as_str)
+                == Some(msg)
+        {
+            return;
+        }
+
+        let history = &mut self.data.commit_msg_history;
+        history.retain(|m| m != msg);
+        history.insert(0, msg.to_string());
+        history.truncate(HISTORY_SIZE);
+
+        self.save();
+    }
+
+    /// `commit_scope_format` with the scopes of the `commit_scopes`
+    /// rules first matching each of the staged `paths` in place of
+    /// `{scope}`, `None` if no rule matches
+    pub fn commit_scope_prefix(
+        &self,
+        paths: &[&str],
+    ) -> Option<String> {
+        let rules = self
+            .data
+            .commit_scopes
+            .iter()
+            .map(|rule| {
+                (
+                    PathPatterns::new(&[&rule.paths]),
+                    rule.scope.as_str(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let mut scopes = Vec::new();
+        for path in paths {
+            if let Some(&(_, scope)) = rules
+                .iter()
+                .find(|(patterns, _)| patterns.matches(path))
+            {
+                if !scopes.contains(&scope) {
+                    scopes.push(scope);
+                }
+            }
+        }
+
+        if scopes.is_empty() {
+            None
+        } else {
+            Some(
+                self.data
+                    .commit_scope_format
+                    .replace("{scope}", &scopes.join(",")),
+            )
+        }
+    }
+
+    /// url of an issue with `{id}` in place of its number (or key and
+    /// number like `JIRA-123`), issue references are no links without
+    pub fn issue_url_template(&self) -> Option<&str> {
+        self.data.issue_url_template.as_deref()
+    }
+
+    /// base ref of the last review
+    pub fn review_base(&self) -> Option<&str> {
+        self.data.review_base.as_deref()
+    }
+
+    pub fn set_review_base(&mut self, base: &str) {
+        self.data.review_base = Some(base.to_string());
+        self.save();
+    }
+
+    /// paths marked as viewed in the review named `review`
+    pub fn review_viewed(&self, review: &str) -> &[String] {
+        self.data
+            .review_viewed
+            .get(review)
+            .map_or(&[], Vec::as_slice)
+    }
+
+    pub fn toggle_review_viewed(&mut self, review: &str, path: &str) {
+        let viewed = self
+            .data
+            .review_viewed
+            .entry(review.to_string())
+            .or_default();
+
+        if let Some(idx) = viewed.iter().position(|p| p == path) {
+            viewed.remove(idx);
+        } else {
+            viewed.push(path.to_string());
+        }
+
+        if viewed.is_empty() {
+            self.data.review_viewed.remove(review);
+        }
+
+        self.save();
+    }
+
+    /// cycles: no ignore -> ignore at eol -> ignore all
+    pub fn diff_cycle_whitespace(&mut self) {
+        let (all, eol) = match (
+            self.data.diff_ignore_whitespace,
+            self.data.diff_ignore_whitespace_eol,
+        ) {
+            (false, false) => (false, true),
+            (false, true) => (true, false),
+            (true, _) => (false, false),
+        };
+
+        self.data.diff_ignore_whitespace = all;
+        self.data.diff_ignore_whitespace_eol = eol;
+        self.save();
+    }
+
+    pub fn diff_context_change(&mut self, increase: bool) {
+        self.data.diff_context = if increase {
+            self.data.diff_context.saturating_add(1)
+        } else {
+            self.data.diff_context.saturating_sub(1)
+        };
+        self.save();
+    }
+
+    fn save(&mut self) {
+        if let Err(e) = self.save_failable() {
+            log::error!("options save error: {}", e);
+        }
+
+        // our own write is no outside edit
+        if let Some(watcher) = &mut self.watcher {
+            watcher.changed();
+        }
+    }
+
+    fn read(repo: &RepoPath) -> Result<(OptionsData, Vec<String>)> {
+        let file = Self::options_file(repo)?;
+        if !file.exists() {
+            return Ok((OptionsData::default(), Vec::new()));
+        }
+
+        let loaded = config::load(&file, &[])?;
+        Ok((loaded.config, loaded.errors))
+    }
+
+    fn save_failable(&self) -> Result<()> {
+        Self::write(&self.repo, &self.data)
+    }
+
+    fn write(repo: &RepoPath, data: &OptionsData) -> Result<()> {
+        let mut file = File::create(Self::options_file(repo)?)?;
+        let data = to_string_pretty(data, PrettyConfig::default())?;
+        file.write_all(data.as_bytes())?;
+
+        Ok(())
+    }
+
+    /// the options of `repo` to share them, what is about this
+    /// repository only (commit messages, reviews) is left out
+    pub fn export(repo: &RepoPath) -> Result<String> {
+        let (data, _) = Self::read(repo)?;
+        let shared = OptionsData {
+            commit_msg_history: Vec::new(),
+            review_base: None,
+            review_viewed: BTreeMap::new(),
+            ..data
+        };
+
+        Ok(to_string_pretty(&shared, PrettyConfig::default())?)
+    }
+
+    /// replaces the options of `repo` by the `exported` ones, keeps
+    /// what is about this repository only (see `export`)
+    pub fn import(repo: &RepoPath, exported: &str) -> Result<()> {
+        let imported: OptionsData = ron::de::from_str(exported)?;
+        let (local, _) = Self::read(repo)?;
+
+        Self::write(
+            repo,
+            &OptionsData {
+                commit_msg_history: local.commit_msg_history,
+                review_base: local.review_base,
+                review_viewed: local.review_viewed,
+                ..imported
+            },
+        )
+    }
+
+    fn options_file(repo: &RepoPath) -> Result<PathBuf> {
+        Ok(repo_dir(repo)?.join("gitui"))
+    }
+}
@@ -0,0 +1,71 @@
+use glob::{MatchOptions, Pattern};
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// glob patterns matched against repository relative paths,
+/// patterns without a `/` only match the file name (like gitignore)
+#[derive(Default)]
+pub struct PathPatterns {
+    patterns: Vec<(Pattern, bool)>,
+}
+
+impl PathPatterns {
+    /// invalid patterns are logged and skipped
+    pub fn new<S: AsRef<str>>(patterns: &[S]) -> Self {
+        let patterns = patterns
+            .iter()
+            .filter_map(|pattern| {
+                let pattern = pattern.as_ref();
+                match Pattern::new(pattern) {
+                    Ok(compiled) => {
+                        Some((compiled, pattern.contains('/')))
+                    }
+                    Err(e) => {
+                        log::error!(
+                            "invalid pattern '{}': {}",
+                            pattern,
+                            e
+                        );
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        Self { patterns }
+    }
+
+    ///
+    pub fn matches(&self, path: &str) -> bool {
+        let file_name = path.rsplit('/').next().unwrap_or(path);
+
+        self.patterns.iter().any(|(pattern, full_path)| {
+            pattern.matches_with(
+                if *full_path { path } else { file_name },
+                MATCH_OPTIONS,
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PathPatterns;
+
+    #[test]
+    fn test_matches() {
+        let patterns =
+            PathPatterns::new(&["Cargo.lock", "*.min.js", "dist/**"]);
+
+        assert!(patterns.matches("Cargo.lock"));
+        assert!(patterns.matches("sub/crate/Cargo.lock"));
+        assert!(patterns.matches("web/app.min.js"));
+        assert!(patterns.matches("dist/a/b.css"));
+        assert!(!patterns.matches("src/dist/b.css"));
+        assert!(!patterns.matches("src/main.rs"));
+    }
+}
@@ -0,0 +1,128 @@
+//! optional overlay with timings of the ui, helps describing slowness
+//! without attaching a profiler. also lists the network operations
+//! running or waiting for their turn
+
+use crate::{strings, ui::style::SharedTheme};
+use asyncgit::AsyncNotification;
+use std::{
+    collections::BTreeMap,
+    convert::TryFrom,
+    time::{Duration, Instant},
+};
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    text::{Span, Spans},
+    widgets::{Block, BorderType, Borders, Clear, Paragraph},
+    Frame,
+};
+
+const WIDTH: u16 = 30;
+
+#[derive(Default)]
+pub struct PerfHud {
+    visible: bool,
+    frame_time: Duration,
+    /// events waiting in the channels when the last one was taken
+    backlog: usize,
+    /// when the async jobs were last asked to refresh
+    refresh_started: Option<Instant>,
+    /// time from the refresh until the job reported back
+    jobs: BTreeMap<String, Duration>,
+    /// jobs that reported since the last refresh
+    reported: Vec<String>,
+}
+
+impl PerfHud {
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+    }
+
+    pub fn set_frame_time(&mut self, time: Duration) {
+        self.frame_time = time;
+    }
+
+    pub fn set_backlog(&mut self, backlog: usize) {
+        self.backlog = backlog;
+    }
+
+    /// the async jobs get asked to refresh now
+    pub fn refresh_started(&mut self) {
+        self.refresh_started = Some(Instant::now());
+        self.reported.clear();
+    }
+
+    /// only the first notification after a refresh counts, later
+    /// ones are progress updates or unrelated
+    pub fn job_finished(&mut self, ev: AsyncNotification) {
+        if let Some(start) = self.refresh_started {
+            let name = format!("{:?}", ev);
+            if !self.reported.contains(&name) {
+                self.jobs.insert(name.clone(), start.elapsed());
+                self.reported.push(name);
+            }
+        }
+    }
+
+    pub fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        theme: &SharedTheme,
+    ) {
+        if !self.visible {
+            return;
+        }
+
+        let mut lines = vec![
+            Self::line("frame", &format_duration(self.frame_time)),
+            Self::line("backlog", &self.backlog.to_string()),
+        ];
+        lines.extend(self.jobs.iter().map(|(name, time)| {
+            Self::line(name, &format_duration(*time))
+        }));
+        lines.extend(asyncgit::network_operations().iter().map(
+            |op| {
+                let name = format!("{} {}", op.kind, op.remote);
+                let state = if op.running { "run" } else { "wait" };
+                Self::line(
+                    &name.chars().take(15).collect::<String>(),
+                    &format!(
+                        "{} {:.0}s",
+                        state,
+                        op.since.elapsed().as_secs_f64()
+                    ),
+                )
+            },
+        ));
+
+        let size = f.size();
+        let height = u16::try_from(lines.len() + 2)
+            .unwrap_or(u16::MAX)
+            .min(size.height);
+        let width = WIDTH.min(size.width);
+        let area = Rect::new(size.width - width, 0, width, height);
+
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Paragraph::new(lines).block(
+                Block::default()
+                    .title(Span::styled(
+                        strings::PERF_HUD_TITLE,
+                        theme.borrow().title(false),
+                    ))
+                    .borders(Borders::ALL)
+                    .border_type(BorderType::Thick)
+                    .border_style(theme.borrow().block(false)),
+            ),
+            area,
+        );
+    }
+
+    fn line<'a>(name: &str, value: &str) -> Spans<'a> {
+        Spans::from(format!("{:<16}{:>10}", name, value))
+    }
+}
+
+fn format_duration(time: Duration) -> String {
+    format!("{:.1}ms", time.as_secs_f64() * 1000.0)
+}
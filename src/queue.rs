@@ -1,6 +1,10 @@
-use crate::tabs::StashingOptions;
-use asyncgit::sync::{diff::DiffLinePosition, CommitId, CommitTags};
+use crate::{components::CommandText, tabs::StashingOptions};
+use asyncgit::sync::{
+    diff::DiffLinePosition, CommitId, CommitTags, DiffStat,
+    FetchDepth, FlowBranch, LogFilter, ResetType,
+};
 use bitflags::bitflags;
+use crossterm::event::KeyEvent;
 use std::{cell::RefCell, collections::VecDeque, rc::Rc};
 
 bitflags! {
@@ -18,6 +22,7 @@ bitflags! {
 }
 
 /// data of item that is supposed to be reset
+#[derive(Debug)]
 pub struct ResetItem {
     /// path to the item (folder/file)
     pub path: String,
@@ -26,19 +31,40 @@ pub struct ResetItem {
 }
 
 ///
+#[derive(Debug)]
 pub enum Action {
     Reset(ResetItem),
     ResetHunk(String, u64),
     ResetLines(String, Vec<DiffLinePosition>),
+    ResetPaths(Vec<String>),
     StashDrop(CommitId),
     StashPop(CommitId),
     DeleteBranch(String),
+    /// merges the workflow branch of that kind and name (see
+    /// `sync::flow_finish`)
+    FlowFinish(FlowBranch, String),
+    DeleteTags(Vec<String>),
+    /// moves the tag to the commit and force pushes it
+    Retag(String, CommitId),
     ForcePush(String, bool),
-    PullMerge { incoming: usize, rebase: bool },
+    PullMerge {
+        incoming: usize,
+        rebase: bool,
+        /// what it changes in the worktree, if that could be told
+        stat: Option<DiffStat>,
+    },
     AbortMerge,
+    AbortRebase,
+    DiscardAll,
+    UndoCommit,
+    /// only asks for confirmation for a hard reset
+    ResetToCommit(CommitId, ResetType),
+    /// checks out the branch (ref, name) discarding local changes
+    ForceCheckout(String, String),
 }
 
 ///
+#[derive(Debug)]
 pub enum InternalEvent {
     ///
     ConfirmAction(Action),
@@ -60,24 +86,102 @@ pub enum InternalEvent {
     InspectCommit(CommitId, Option<CommitTags>),
     ///
     TagCommit(CommitId),
+    /// lets the user choose how to reset the branch to the commit
+    ResetCommit(CommitId),
+    /// lets the user choose what happens to the uncommitted changes
+    /// blocking the checkout of the branch
+    CheckoutStrategy(String),
     ///
     BlameFile(String),
     ///
     CreateBranch,
+    /// create a branch pointing to the commit
+    CreateBranchAt(CommitId),
+    /// start a branch of the configured workflow
+    StartFlowBranch,
+    /// edit the message of the stash
+    RenameStash(CommitId, String),
+    /// `git stash branch`
+    BranchFromStash(CommitId),
     ///
     RenameBranch(String, String),
+    /// choose the upstream of the local branch of that name
+    SetUpstream(String),
     ///
     SelectBranch,
     ///
     OpenExternalEditor(Option<String>),
+    /// shows the file at the (absolute) path in the external pager
+    OpenExternalPager(String),
+    /// runs a nested gitui inside of the submodule or nested
+    /// repository at the path
+    OpenNestedRepo(String),
+    /// full status of the folder summarized by the shallow status,
+    /// by scoping to it
+    ExpandFolderStatus(String),
+    /// replaces the current repository with the one at the path
+    /// (its workdir)
+    SwitchRepo(String),
     ///
     Push(String, bool),
+    /// push the branch to that remote (and track it there)
+    PushTo(String, String, bool),
+    /// choose a pattern ignoring the path (a folder if true)
+    IgnorePath(String, bool),
+    /// choose one of the urls and issue references in the text to
+    /// open in the browser
+    OpenLinks(String),
     ///
     Pull(String),
-    ///
-    PushTags,
+    /// fetch all branches of the default remote
+    FetchRemoteBranches,
+    /// asks for the depth to fetch the branch with
+    OpenFetchOptions(String),
+    /// fetches the branch with this depth without merging it
+    FetchDepth(String, FetchDepth),
+    /// pushes these tags (all missing on the remote if none), forced
+    /// if set
+    PushTags(Vec<String>, bool),
+    /// lists the tags, they can be moved to this commit
+    OpenTags(CommitId),
     ///
     OpenFileTree(CommitId),
+    /// ask where to archive the tree of the commit
+    OpenArchiveCommit(CommitId),
+    /// ask for the commit to select in the log
+    OpenGotoCommit,
+    /// select the commit in the log (once the walk reached it), the
+    /// rev it was resolved from
+    GotoCommit(CommitId, String),
+    /// ask for the patch to apply to the working tree
+    OpenApplyPatch,
+    /// ask where to save the patch of the commit
+    ExportPatch(CommitId),
+    /// ask for the author, path and dates to filter the log by
+    OpenLogFilter,
+    /// show only the commits matching the filter in the log, the
+    /// text it was read from
+    FilterLog(String, LogFilter),
+    /// ask for the ref the status tab diffs the working tree against
+    OpenDiffBase,
+    /// compare the working tree with this ref in the status tab,
+    /// `None` goes back to `HEAD`
+    SetDiffBase(Option<(String, CommitId)>),
+    /// review the changes of the branch against a base ref
+    OpenReview,
+    /// reviews the changes of this branch against the current one
+    ReviewBranch(String),
+    /// lists these commands (and their keys) of the selected item
+    OpenContextMenu(Vec<(CommandText, KeyEvent)>),
+    /// handles the key as if it was pressed
+    SimulateKey(KeyEvent),
+    /// shows the steps of the rebase in progress
+    OpenRebase,
+    /// shows what rebasing onto the local branch would do
+    PreviewRebase(String),
+    /// commits the merge or rebase step whose conflicts were all
+    /// staged
+    ContinueResolved,
 }
 
 ///
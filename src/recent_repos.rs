@@ -0,0 +1,81 @@
+use crate::args::{get_app_cache_path, get_app_config_path};
+use anyhow::Result;
+use ron::{
+    de::from_bytes,
+    ser::{to_string_pretty, PrettyConfig},
+};
+use std::{
+    fs::File,
+    io::{Read, Write},
+    path::PathBuf,
+};
+
+/// upper bound of remembered repositories
+const MAX_RECENT_REPOS: usize = 20;
+
+const REPOS_FILE_NAME: &str = "recent_repos.ron";
+
+/// workdirs of recently opened repositories (most recent first),
+/// persisted in the app cache folder
+pub struct RecentRepos {
+    repos: Vec<String>,
+}
+
+impl RecentRepos {
+    pub fn new() -> Self {
+        Self {
+            repos: Self::read().unwrap_or_default(),
+        }
+    }
+
+    pub fn repos(&self) -> &[String] {
+        &self.repos
+    }
+
+    /// moves `path` to the front of the list, adding it if needed
+    pub fn add(&mut self, path: &str) {
+        self.repos.retain(|repo| repo != path);
+        self.repos.insert(0, path.to_string());
+        self.repos.truncate(MAX_RECENT_REPOS);
+        self.save();
+    }
+
+    pub fn remove(&mut self, path: &str) {
+        self.repos.retain(|repo| repo != path);
+        self.save();
+    }
+
+    fn save(&self) {
+        if let Err(e) = self.save_failable() {
+            log::error!("recent repos save error: {}", e);
+        }
+    }
+
+    fn read() -> Result<Vec<String>> {
+        let file = Self::repos_file()?;
+        // older versions kept the list in the config folder
+        let file = if file.exists() {
+            file
+        } else {
+            get_app_config_path()?.join(REPOS_FILE_NAME)
+        };
+
+        let mut f = File::open(file)?;
+        let mut buffer = Vec::new();
+        f.read_to_end(&mut buffer)?;
+        Ok(from_bytes(&buffer)?)
+    }
+
+    fn save_failable(&self) -> Result<()> {
+        let mut file = File::create(Self::repos_file()?)?;
+        let data =
+            to_string_pretty(&self.repos, PrettyConfig::default())?;
+        file.write_all(data.as_bytes())?;
+
+        Ok(())
+    }
+
+    fn repos_file() -> Result<PathBuf> {
+        Ok(get_app_cache_path()?.join(REPOS_FILE_NAME))
+    }
+}
@@ -0,0 +1,130 @@
+use anyhow::Result;
+use asyncgit::sync::{utils::repo_dir, RepoPath};
+use ron::{
+    de::from_bytes,
+    ser::{to_string_pretty, PrettyConfig},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{Read, Write},
+    path::PathBuf,
+};
+
+/// draft note attached to a single diff line of a review
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ReviewNote {
+    pub path: String,
+    /// line number in the new version of the file or in the old one
+    /// for removed lines
+    pub line: u32,
+    /// `line` refers to a removed line
+    pub old: bool,
+    pub text: String,
+}
+
+impl ReviewNote {
+    fn is_at(&self, path: &str, line: u32, old: bool) -> bool {
+        self.path == path && self.line == line && self.old == old
+    }
+}
+
+/// draft notes by review, persisted inside of the `.git` folder
+pub struct ReviewNotes {
+    repo: RepoPath,
+    notes: BTreeMap<String, Vec<ReviewNote>>,
+}
+
+impl ReviewNotes {
+    pub fn new(repo: RepoPath) -> Self {
+        Self {
+            notes: Self::read(&repo).unwrap_or_default(),
+            repo,
+        }
+    }
+
+    /// notes of `review` ordered by path and line
+    pub fn notes(&self, review: &str) -> &[ReviewNote] {
+        self.notes.get(review).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn get(
+        &self,
+        review: &str,
+        path: &str,
+        line: u32,
+        old: bool,
+    ) -> Option<&ReviewNote> {
+        self.notes(review)
+            .iter()
+            .find(|note| note.is_at(path, line, old))
+    }
+
+    /// replaces the note on the line of `note`, an empty text
+    /// removes it
+    pub fn set(&mut self, review: &str, note: ReviewNote) {
+        let notes = self.notes.entry(review.to_string()).or_default();
+
+        notes.retain(|n| !n.is_at(&note.path, note.line, note.old));
+        if !note.text.trim().is_empty() {
+            notes.push(note);
+            notes.sort_by(|a, b| {
+                (&a.path, a.line, a.old)
+                    .cmp(&(&b.path, b.line, b.old))
+            });
+        }
+
+        if notes.is_empty() {
+            self.notes.remove(review);
+        }
+
+        self.save();
+    }
+
+    /// markdown list of the notes of `review` with `file:line`
+    /// references
+    pub fn export(&self, review: &str) -> String {
+        let mut res = format!("## Review notes: {}\n\n", review);
+
+        for note in self.notes(review) {
+            res.push_str(&format!(
+                "- `{}:{}`{}: {}\n",
+                note.path,
+                note.line,
+                if note.old { " (removed)" } else { "" },
+                note.text
+            ));
+        }
+
+        res
+    }
+
+    fn save(&self) {
+        if let Err(e) = self.save_failable() {
+            log::error!("review notes save error: {}", e);
+        }
+    }
+
+    fn read(
+        repo: &RepoPath,
+    ) -> Result<BTreeMap<String, Vec<ReviewNote>>> {
+        let mut f = File::open(Self::notes_file(repo)?)?;
+        let mut buffer = Vec::new();
+        f.read_to_end(&mut buffer)?;
+        Ok(from_bytes(&buffer)?)
+    }
+
+    fn save_failable(&self) -> Result<()> {
+        let mut file = File::create(Self::notes_file(&self.repo)?)?;
+        let data =
+            to_string_pretty(&self.notes, PrettyConfig::default())?;
+        file.write_all(data.as_bytes())?;
+
+        Ok(())
+    }
+
+    fn notes_file(repo: &RepoPath) -> Result<PathBuf> {
+        Ok(repo_dir(repo)?.join("gitui_review_notes"))
+    }
+}
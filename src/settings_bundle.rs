@@ -0,0 +1,116 @@
+//! a single file with the key config, theme and options to set up
+//! gitui the same way on other machines or for a whole team
+
+use crate::{keys::KeyConfig, options::Options};
+use anyhow::{anyhow, Result};
+use asyncgit::sync::RepoPath;
+use ron::ser::{to_string_pretty, PrettyConfig};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path, process::Command};
+
+/// the content of each config file, missing ones are left untouched
+/// when importing
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct SettingsBundle {
+    key_config: Option<String>,
+    theme: Option<String>,
+    options: Option<String>,
+}
+
+/// writes the key config, the `theme_file` and (inside of a
+/// repository) its options into `file`
+pub fn export(
+    file: &Path,
+    theme_file: &Path,
+    repo: Option<&RepoPath>,
+) -> Result<()> {
+    let bundle = SettingsBundle {
+        key_config: fs::read_to_string(KeyConfig::get_config_file()?)
+            .ok(),
+        theme: fs::read_to_string(theme_file).ok(),
+        options: repo.map(Options::export).transpose()?,
+    };
+
+    fs::write(
+        file,
+        to_string_pretty(&bundle, PrettyConfig::default())?,
+    )?;
+    println!("exported: {}", file.to_string_lossy());
+
+    Ok(())
+}
+
+/// replaces the config files by the ones of the bundle at `source`
+/// (a file or a http(s) url), the replaced ones are kept as `.bak`
+pub fn import(
+    source: &str,
+    theme_file: &Path,
+    repo: Option<&RepoPath>,
+) -> Result<()> {
+    let data = if source.starts_with("http://")
+        || source.starts_with("https://")
+    {
+        download(source)?
+    } else {
+        fs::read_to_string(source)
+            .map_err(|e| anyhow!("{}: {}", source, e))?
+    };
+
+    let bundle: SettingsBundle = ron::de::from_str(&data)
+        .map_err(|e| anyhow!("{}: {}", source, e))?;
+
+    if let Some(key_config) = &bundle.key_config {
+        replace(&KeyConfig::get_config_file()?, key_config)?;
+    }
+
+    if let Some(theme) = &bundle.theme {
+        replace(theme_file, theme)?;
+    }
+
+    match (&bundle.options, repo) {
+        (Some(options), Some(repo)) => {
+            Options::import(repo, options)?;
+            println!("imported: options of the repository");
+        }
+        (Some(_), None) => {
+            println!("skipped: options, not inside of a repository");
+        }
+        _ => (),
+    }
+
+    Ok(())
+}
+
+fn replace(file: &Path, data: &str) -> Result<()> {
+    if file.exists() {
+        let mut backup = file.as_os_str().to_owned();
+        backup.push(".bak");
+        fs::copy(file, backup)?;
+    }
+
+    fs::write(file, data)?;
+    println!("imported: {}", file.to_string_lossy());
+
+    Ok(())
+}
+
+/// there is no http client built in, `curl` is around almost
+/// everywhere (even on windows 10)
+fn download(url: &str) -> Result<String> {
+    let output = Command::new("curl")
+        .args(&["--fail", "--silent", "--show-error", "--location"])
+        .arg(url)
+        .output()
+        .map_err(|e| anyhow!("`curl`: {}", e))?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "{}: {}",
+            url,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(String::from_utf8(output.stdout)?)
+}
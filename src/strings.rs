@@ -1,4 +1,11 @@
 use crate::keys::SharedKeyConfig;
+use asyncgit::sync::{
+    BlameCopyDetection, CheckoutStrategy, CommitId, CommitMsgLint,
+    DiffOptions, DiffStat, FlowBranch, HookInfo, HookSource,
+    ImageType, JournalOp, MergeFastForward, PushCompare,
+    RebaseProgress, RebaseRisk, ResetType, SubmoduleLog,
+    SubmoduleState,
+};
 
 pub mod order {
     pub static NAV: i8 = 2;
@@ -8,11 +15,16 @@ pub mod order {
 pub static PUSH_POPUP_MSG: &str = "Push";
 pub static FORCE_PUSH_POPUP_MSG: &str = "Force Push";
 pub static PULL_POPUP_MSG: &str = "Pull";
+pub static FETCH_POPUP_MSG: &str = "Fetch";
+pub static PERF_HUD_TITLE: &str = "Performance";
+pub static REPLACED_COMMIT_MARKER: &str = "[replaced]";
+pub static AUTOSTASH_PENDING_MARKER: &str = "[autostash pending]";
 pub static PUSH_POPUP_PROGRESS_NONE: &str = "preparing...";
 pub static PUSH_POPUP_STATES_ADDING: &str = "adding objects (1/3)";
 pub static PUSH_POPUP_STATES_DELTAS: &str = "deltas (2/3)";
 pub static PUSH_POPUP_STATES_PUSHING: &str = "pushing (3/3)";
 pub static PUSH_POPUP_STATES_TRANSFER: &str = "transfer";
+pub static PUSH_POPUP_STATES_RESOLVING: &str = "resolving deltas";
 pub static PUSH_POPUP_STATES_DONE: &str = "done";
 
 pub static PUSH_TAGS_POPUP_MSG: &str = "Push Tags";
@@ -23,31 +35,245 @@ pub static PUSH_TAGS_STATES_DONE: &str = "done";
 pub fn title_branches() -> String {
     "Branches".to_string()
 }
+pub fn title_tags() -> String {
+    "Tags".to_string()
+}
+pub fn title_hooks() -> String {
+    "Hooks".to_string()
+}
+pub fn title_rebase(progress: Option<&RebaseProgress>) -> String {
+    match progress.and_then(|p| {
+        p.current.map(|current| (current, p.steps.len()))
+    }) {
+        Some((current, count)) => {
+            format!("Rebase ({}/{})", current + 1, count)
+        }
+        None => "Rebase".to_string(),
+    }
+}
+pub fn title_command_palette(filter: &str) -> String {
+    format!("Commands: {}_", filter)
+}
+pub fn title_rebase_preview(
+    onto: &str,
+    commits: usize,
+    stat: Option<DiffStat>,
+) -> String {
+    let title = format!("Rebase onto {} ({} commits)", onto, commits);
+    match stat {
+        Some(stat) => format!("{} - {}", title, diffstat(stat)),
+        None => title,
+    }
+}
+pub fn diffstat(stat: DiffStat) -> String {
+    format!(
+        "{} files changed, {} insertions(+), {} deletions(-)",
+        stat.files, stat.insertions, stat.deletions
+    )
+}
+pub fn rebase_risk(risk: &RebaseRisk) -> String {
+    match risk {
+        RebaseRisk::Merge => {
+            "merge: dropped, its commits get replayed".to_string()
+        }
+        RebaseRisk::Signed => {
+            "signed: loses the signature".to_string()
+        }
+        RebaseRisk::Published(branch) => {
+            format!("already on {}", branch)
+        }
+    }
+}
+pub const fn merge_fast_forward(
+    mode: MergeFastForward,
+) -> &'static str {
+    match mode {
+        MergeFastForward::Allow => "(ff)",
+        MergeFastForward::Never => "(no-ff)",
+        MergeFastForward::Only => "(ff-only)",
+    }
+}
+pub const fn flow_branch(kind: FlowBranch) -> &'static str {
+    match kind {
+        FlowBranch::Feature => "Feature",
+        FlowBranch::Release => "Release",
+        FlowBranch::Hotfix => "Hotfix",
+        FlowBranch::Trunk => "Branch",
+    }
+}
+pub fn path_scope(scope: &str) -> String {
+    format!("scope: {}/", scope)
+}
+pub fn journal_op(op: &JournalOp) -> String {
+    match op {
+        JournalOp::Discard(paths) if paths.is_empty() => {
+            "Discard All".to_string()
+        }
+        JournalOp::Discard(paths) if paths.len() == 1 => {
+            format!("Discard {}", paths[0])
+        }
+        JournalOp::Discard(paths) => {
+            format!("Discard {} Paths", paths.len())
+        }
+        JournalOp::UndoCommit => "Undo Commit".to_string(),
+        JournalOp::Reset(kind) => format!("Reset {:?}", kind),
+        JournalOp::DeleteBranch(branch_ref) => format!(
+            "Delete {}",
+            branch_ref
+                .trim_start_matches("refs/heads/")
+                .trim_start_matches("refs/remotes/")
+        ),
+        JournalOp::DropStash => "Drop Stash".to_string(),
+    }
+}
+pub fn title_repos() -> String {
+    "Repositories".to_string()
+}
 pub fn title_status(_key_config: &SharedKeyConfig) -> String {
     "Unstaged Changes".to_string()
 }
 pub fn title_diff(_key_config: &SharedKeyConfig) -> String {
     "Diff: ".to_string()
 }
+pub fn diff_options_info(options: DiffOptions) -> String {
+    let mut info = Vec::new();
+    if options.ignore_whitespace {
+        info.push("ignore whitespace".to_string());
+    } else if options.ignore_whitespace_eol {
+        info.push("ignore eol whitespace".to_string());
+    }
+    if options.context != DiffOptions::default().context {
+        info.push(format!("context: {}", options.context));
+    }
+
+    if info.is_empty() {
+        String::new()
+    } else {
+        format!(" ({})", info.join(", "))
+    }
+}
+pub fn diff_folded_info(
+    key_config: &SharedKeyConfig,
+    hunks: usize,
+    added: usize,
+    removed: usize,
+) -> String {
+    format!(
+        "generated file folded: {} hunks, +{} -{} lines (expand [{}])",
+        hunks,
+        added,
+        removed,
+        key_config.borrow().get_hint(key_config.borrow().diff_toggle_fold),
+    )
+}
+pub fn binary_info() -> String {
+    "binary file".to_string()
+}
+pub fn image_info(
+    image: ImageType,
+    dimensions: Option<(u32, u32)>,
+) -> String {
+    dimensions.map_or_else(
+        || format!("{} image", image.name()),
+        |(width, height)| {
+            format!("{} image: {}x{}", image.name(), width, height)
+        },
+    )
+}
+pub fn submodule_log_info(log: &SubmoduleLog) -> String {
+    let short = |id: Option<CommitId>| {
+        id.map_or_else(
+            || "none".to_string(),
+            |id| id.get_short_string(),
+        )
+    };
+    let title = format!(
+        "submodule {} {}..{}",
+        log.path,
+        short(log.old),
+        short(log.new)
+    );
+
+    log.commits.as_ref().map_or_else(
+        || format!("{}: commits not available (submodule not checked out or fetched)", title),
+        |commits| {
+            format!(
+                "{}: {}{} {} commit(s)",
+                title,
+                commits.len(),
+                if log.truncated { "+" } else { "" },
+                if log.rewind { "dropped" } else { "new" }
+            )
+        },
+    )
+}
+pub fn submodule_state_info(state: SubmoduleState) -> String {
+    let states = [
+        (state.uninitialized, "not checked out"),
+        (state.new_commits, "new commits"),
+        (state.modified_content, "modified content"),
+        (state.untracked_content, "untracked content"),
+    ]
+    .iter()
+    .filter(|(set, _)| *set)
+    .map(|(_, name)| *name)
+    .collect::<Vec<_>>();
+
+    if states.is_empty() {
+        String::new()
+    } else {
+        format!(" ({})", states.join(", "))
+    }
+}
+pub fn nested_repo_info() -> String {
+    " (repository)".to_string()
+}
+pub fn summarized_folder_info() -> String {
+    "/ (folder)".to_string()
+}
+pub fn hidden_files_info(hidden: usize) -> String {
+    if hidden == 0 {
+        String::new()
+    } else {
+        format!(" ({} hidden)", hidden)
+    }
+}
+pub fn skipped_files_info(skipped: usize) -> String {
+    if skipped == 0 {
+        String::new()
+    } else {
+        format!(" ({} skipped)", skipped)
+    }
+}
 pub fn title_index(_key_config: &SharedKeyConfig) -> String {
     "Staged Changes".to_string()
 }
 pub fn tab_status(key_config: &SharedKeyConfig) -> String {
-    format!("Status [{}]", key_config.get_hint(key_config.tab_status))
+    format!(
+        "Status [{}]",
+        key_config.borrow().get_hint(key_config.borrow().tab_status)
+    )
 }
 pub fn tab_log(key_config: &SharedKeyConfig) -> String {
-    format!("Log [{}]", key_config.get_hint(key_config.tab_log))
+    format!(
+        "Log [{}]",
+        key_config.borrow().get_hint(key_config.borrow().tab_log)
+    )
 }
 pub fn tab_stashing(key_config: &SharedKeyConfig) -> String {
     format!(
         "Stashing [{}]",
-        key_config.get_hint(key_config.tab_stashing)
+        key_config
+            .borrow()
+            .get_hint(key_config.borrow().tab_stashing)
     )
 }
 pub fn tab_stashes(key_config: &SharedKeyConfig) -> String {
     format!(
         "Stashes [{}]",
-        key_config.get_hint(key_config.tab_stashes)
+        key_config
+            .borrow()
+            .get_hint(key_config.borrow().tab_stashes)
     )
 }
 pub fn tab_divider(_key_config: &SharedKeyConfig) -> String {
@@ -65,27 +291,69 @@ pub fn msg_title_error(_key_config: &SharedKeyConfig) -> String {
 pub fn commit_title() -> String {
     "Commit".to_string()
 }
+pub fn commit_stage_tracked_title(count: usize) -> String {
+    format!("Staged with the commit ({})", count)
+}
+pub fn commit_stage_tracked_empty() -> String {
+    "no modified tracked files".to_string()
+}
+pub fn commit_new_branch_popup_title(
+    _key_config: &SharedKeyConfig,
+) -> String {
+    "Commit to New Branch".to_string()
+}
+pub fn commit_new_branch_popup_msg(
+    _key_config: &SharedKeyConfig,
+) -> String {
+    "type branch name, created at HEAD".to_string()
+}
+pub fn commit_new_branch_name(current: &str, new: &str) -> String {
+    format!("{{{} -> {}}}", current, new)
+}
+pub fn commit_title_empty() -> String {
+    "Commit (nothing staged)".to_string()
+}
 pub fn commit_title_merge() -> String {
     "Commit (Merge)".to_string()
 }
+pub fn commit_title_rebase() -> String {
+    "Commit (Rebase)".to_string()
+}
 pub fn commit_title_amend(_key_config: &SharedKeyConfig) -> String {
     "Commit (Amend)".to_string()
 }
 pub fn commit_msg(_key_config: &SharedKeyConfig) -> String {
     "type commit message..".to_string()
 }
-pub fn commit_first_line_warning(count: usize) -> String {
-    format!("[subject length: {}]", count)
+pub fn commit_lint_warning(lint: &CommitMsgLint) -> String {
+    match lint {
+        CommitMsgLint::SubjectTooLong(count) => {
+            format!("[subject length: {}]", count)
+        }
+        CommitMsgLint::MissingBlankLine => {
+            "[no blank line after subject]".to_string()
+        }
+        CommitMsgLint::NotImperative(word) => {
+            format!("[use imperative mood: '{}']", word)
+        }
+    }
 }
 pub fn commit_editor_msg(_key_config: &SharedKeyConfig) -> String {
     r##"
 # Edit your commit message
-# Lines starting with '#' will be ignored"##
+# Lines starting with '#' will be ignored
+# Saving a non-empty message commits it"##
         .to_string()
 }
 pub fn stash_popup_title(_key_config: &SharedKeyConfig) -> String {
     "Stash".to_string()
 }
+pub fn stash_rename_popup_title() -> String {
+    "Rename Stash".to_string()
+}
+pub fn stash_branch_popup_title() -> String {
+    "Branch from Stash".to_string()
+}
 pub fn stash_popup_msg(_key_config: &SharedKeyConfig) -> String {
     "type name (optional)".to_string()
 }
@@ -116,11 +384,16 @@ pub fn confirm_msg_merge(
     _key_config: &SharedKeyConfig,
     incoming: usize,
     rebase: bool,
+    stat: Option<DiffStat>,
 ) -> String {
-    if rebase {
+    let msg = if rebase {
         format!("Rebase onto {} incoming commits?", incoming)
     } else {
         format!("Merge of {} incoming commits?", incoming)
+    };
+    match stat {
+        Some(stat) => format!("{}\n\n{}", msg, diffstat(stat)),
+        None => msg,
     }
 }
 
@@ -131,9 +404,54 @@ pub fn confirm_msg_abortmerge() -> String {
     "This will revert all uncommitted changes. Are you sure?"
         .to_string()
 }
+pub fn confirm_title_abortrebase() -> String {
+    "Abort rebase?".to_string()
+}
+pub fn confirm_msg_abortrebase() -> String {
+    "This will restore the branch as it was before the rebase and revert all uncommitted changes. Are you sure?"
+        .to_string()
+}
+pub fn confirm_title_discard_all() -> String {
+    "Discard all changes?".to_string()
+}
+pub fn confirm_msg_discard_all() -> String {
+    "This will revert all unstaged changes and delete untracked files. Are you sure?"
+        .to_string()
+}
+pub fn confirm_title_undo_commit() -> String {
+    "Undo last commit?".to_string()
+}
+pub fn confirm_msg_undo_commit() -> String {
+    "This will remove the last commit and keep its changes staged. Are you sure?"
+        .to_string()
+}
+pub fn confirm_title_reset_hard() -> String {
+    "Hard reset?".to_string()
+}
+pub fn confirm_msg_reset_hard(commit: &str) -> String {
+    format!(
+        "This will move the branch to {} and discard all uncommitted changes and all commits after it. This cannot be undone. Are you sure?",
+        commit
+    )
+}
+pub fn confirm_title_force_checkout() -> String {
+    "Force checkout?".to_string()
+}
+pub fn confirm_msg_force_checkout(branch: &str) -> String {
+    format!(
+        "This will check out '{}' and discard all uncommitted changes to tracked files. Are you sure?",
+        branch
+    )
+}
 pub fn confirm_msg_reset() -> String {
     "confirm file reset?".to_string()
 }
+pub fn confirm_msg_reset_paths(paths: usize) -> String {
+    format!(
+        "are you sure you want to discard the changes of {} marked files?",
+        paths
+    )
+}
 pub fn confirm_msg_reset_lines(lines: usize) -> String {
     format!(
         "are you sure you want to discard {} selected lines?",
@@ -165,6 +483,32 @@ pub fn confirm_msg_delete_branch(
 ) -> String {
     format!("Confirm deleting branch: '{}' ?", branch_ref)
 }
+pub fn confirm_title_flow_finish(kind: FlowBranch) -> String {
+    format!("Finish {}", flow_branch(kind))
+}
+pub fn confirm_msg_flow_finish(
+    kind: FlowBranch,
+    name: &str,
+) -> String {
+    let steps = match kind {
+        FlowBranch::Feature => "merge it into develop",
+        FlowBranch::Release | FlowBranch::Hotfix => {
+            "merge it into main and develop and tag main"
+        }
+        FlowBranch::Trunk => "fast forward main to it",
+    };
+
+    format!(
+        "Confirm finishing '{}'?\nThis will {}, delete the branch and check out {}.",
+        name,
+        steps,
+        if kind == FlowBranch::Trunk {
+            "main"
+        } else {
+            "develop"
+        }
+    )
+}
 pub fn confirm_title_force_push(
     _key_config: &SharedKeyConfig,
 ) -> String {
@@ -179,12 +523,36 @@ pub fn confirm_msg_force_push(
         branch_ref
     )
 }
+pub fn confirm_title_delete_tags() -> String {
+    "Delete Tags".to_string()
+}
+pub fn confirm_msg_delete_tags(tags: &[String]) -> String {
+    format!("Confirm deleting tags: {} ?", tags.join(", "))
+}
+pub fn confirm_title_retag() -> String {
+    "Move Tag".to_string()
+}
+pub fn confirm_msg_retag(tag: &str, commit: &str) -> String {
+    format!(
+        "Confirm moving tag '{}' to {} ?  It gets force pushed to the remote.",
+        tag, commit
+    )
+}
 pub fn log_title(_key_config: &SharedKeyConfig) -> String {
     "Commit".to_string()
 }
 pub fn blame_title(_key_config: &SharedKeyConfig) -> String {
     "Blame".to_string()
 }
+pub fn blame_copy_detection(detection: BlameCopyDetection) -> String {
+    match detection {
+        BlameCopyDetection::Off => "renames",
+        BlameCopyDetection::SameFile => "-M",
+        BlameCopyDetection::SameCommit => "-C",
+        BlameCopyDetection::AnyCommit => "-CCC",
+    }
+    .to_string()
+}
 pub fn tag_commit_popup_title(
     _key_config: &SharedKeyConfig,
 ) -> String {
@@ -199,6 +567,29 @@ pub fn stashlist_title(_key_config: &SharedKeyConfig) -> String {
 pub fn help_title(_key_config: &SharedKeyConfig) -> String {
     "Help: all commands".to_string()
 }
+pub fn config_errors_msg(errors: &[String]) -> String {
+    format!(
+        "invalid config entries were replaced by their defaults:\n{}\n\nrun `gitui --default-config` to write commented default config files.",
+        errors.join("\n")
+    )
+}
+pub fn help_key_conflicts_title() -> String {
+    "Key config conflicts".to_string()
+}
+pub fn help_key_conflict(
+    key: &str,
+    actions: &[(String, Option<usize>)],
+) -> String {
+    let actions = actions
+        .iter()
+        .map(|(action, line)| match line {
+            Some(line) => format!("{} (line {})", action, line),
+            None => action.clone(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(" [{}] {}", key, actions)
+}
 pub fn stashing_files_title(_key_config: &SharedKeyConfig) -> String {
     "Files to Stash".to_string()
 }
@@ -207,6 +598,23 @@ pub fn stashing_options_title(
 ) -> String {
     "Options".to_string()
 }
+pub fn file_filter_info(filter: &str, editing: bool) -> String {
+    format!(" [filter: {}{}]", filter, if editing { "_" } else { "" })
+}
+pub fn open_link_popup_title(
+    _key_config: &SharedKeyConfig,
+) -> String {
+    "Open Link".to_string()
+}
+pub fn no_links_msg(_key_config: &SharedKeyConfig) -> String {
+    "no links found (issue references need the `issue_url_template` option)".to_string()
+}
+pub fn hex_offset_info(offset: usize, size: usize) -> String {
+    format!(" [offset: {:08x}/{:08x}]", offset, size)
+}
+pub fn hex_goto_offset_info(input: &str) -> String {
+    format!(" [goto offset: 0x{}_]", input)
+}
 pub fn loading_text(_key_config: &SharedKeyConfig) -> String {
     "Loading ...".to_string()
 }
@@ -215,11 +623,247 @@ pub fn create_branch_popup_title(
 ) -> String {
     "Branch".to_string()
 }
+pub fn create_branch_at_popup_title(commit: &str) -> String {
+    format!("Branch at {}", commit)
+}
+pub fn flow_start_popup_title(
+    kind: FlowBranch,
+    prefix: &str,
+) -> String {
+    format!("Start {}: {}", flow_branch(kind), prefix)
+}
 pub fn create_branch_popup_msg(
     _key_config: &SharedKeyConfig,
 ) -> String {
     "type branch name".to_string()
 }
+pub fn repo_path_popup_title(
+    _key_config: &SharedKeyConfig,
+) -> String {
+    "Open Repository".to_string()
+}
+pub fn repo_path_popup_msg(_key_config: &SharedKeyConfig) -> String {
+    "type path of a repository".to_string()
+}
+pub fn diff_base_popup_title(
+    _key_config: &SharedKeyConfig,
+) -> String {
+    "Diff Base".to_string()
+}
+pub fn diff_base_popup_msg(_key_config: &SharedKeyConfig) -> String {
+    "type a ref to diff against (empty for HEAD)".to_string()
+}
+pub fn log_filter_popup_title(
+    _key_config: &SharedKeyConfig,
+) -> String {
+    "Filter Log".to_string()
+}
+pub fn log_filter_popup_msg(_key_config: &SharedKeyConfig) -> String {
+    "author:name path:src/ since:2021-01-31 until:2021-12-31 rev:origin/main refs:release/*"
+        .to_string()
+}
+pub fn log_title_filtered(
+    _key_config: &SharedKeyConfig,
+    filter: &str,
+) -> String {
+    format!("Commit [{}]", filter)
+}
+pub fn export_patch_popup_title(
+    _key_config: &SharedKeyConfig,
+) -> String {
+    "Export Patch".to_string()
+}
+pub fn export_patch_popup_msg(
+    _key_config: &SharedKeyConfig,
+) -> String {
+    "file to save the patch to".to_string()
+}
+pub fn time_machine_banner() -> String {
+    " TIME MACHINE ".to_string()
+}
+pub fn time_machine_end(older: bool, file: bool) -> String {
+    format!(
+        "no {} commit{}",
+        if older { "older" } else { "newer" },
+        if file { " changing this file" } else { "" }
+    )
+}
+pub fn archive_commit_popup_title(
+    _key_config: &SharedKeyConfig,
+) -> String {
+    "Archive Commit".to_string()
+}
+pub fn archive_commit_popup_msg(
+    _key_config: &SharedKeyConfig,
+) -> String {
+    "archive file (.tar, .tar.gz or .zip)".to_string()
+}
+pub fn archive_commit_unknown_format(file: &str) -> String {
+    format!("'{}' is no .tar, .tar.gz, .tgz or .zip file name", file)
+}
+pub const fn hook_source(source: HookSource) -> &'static str {
+    match source {
+        HookSource::Local => "local",
+        HookSource::HooksPath => "core.hooksPath",
+    }
+}
+pub fn hook_state(hook: &HookInfo) -> String {
+    if !hook.active {
+        "not run: core.hooksPath is set".to_string()
+    } else if !hook.executable {
+        "not run: not executable".to_string()
+    } else if !hook.enabled {
+        "not run by gitui".to_string()
+    } else {
+        "runs".to_string()
+    }
+}
+pub fn hook_output_title(name: &str, success: bool) -> String {
+    format!(
+        "{} {}",
+        name,
+        if success { "succeeded" } else { "failed" }
+    )
+}
+pub fn apply_patch_popup_title(
+    _key_config: &SharedKeyConfig,
+) -> String {
+    "Apply Patch".to_string()
+}
+pub fn apply_patch_popup_msg(
+    _key_config: &SharedKeyConfig,
+) -> String {
+    "patch file to apply to the working tree".to_string()
+}
+pub fn apply_patch_conflicts(paths: &[String]) -> String {
+    format!(
+        "patch did not apply cleanly, resolve the conflicts in:\n{}",
+        paths.join("\n")
+    )
+}
+pub fn goto_commit_popup_title(
+    _key_config: &SharedKeyConfig,
+) -> String {
+    "Go to Commit".to_string()
+}
+pub fn goto_commit_popup_msg(
+    _key_config: &SharedKeyConfig,
+) -> String {
+    "type a sha, branch or tag".to_string()
+}
+pub fn goto_commit_not_in_log(rev: &str) -> String {
+    format!("'{}' is not part of the log", rev)
+}
+pub fn fetch_options_popup_title(
+    _key_config: &SharedKeyConfig,
+    shallow: bool,
+) -> String {
+    if shallow {
+        "Fetch (shallow repository)".to_string()
+    } else {
+        "Fetch".to_string()
+    }
+}
+pub fn fetch_options_popup_msg(
+    _key_config: &SharedKeyConfig,
+) -> String {
+    "depth in commits, empty gets the whole history".to_string()
+}
+pub fn fetch_options_invalid_depth(depth: &str) -> String {
+    format!("'{}' is not a valid depth", depth)
+}
+pub fn title_status_base(base: &str) -> String {
+    format!("Changes against {}", base)
+}
+pub fn review_base_popup_title(
+    _key_config: &SharedKeyConfig,
+) -> String {
+    "Review".to_string()
+}
+pub fn review_base_popup_msg(
+    _key_config: &SharedKeyConfig,
+) -> String {
+    "type the base ref to review against (e.g. origin/main)"
+        .to_string()
+}
+pub fn title_review(
+    name: &str,
+    viewed: usize,
+    total: usize,
+) -> String {
+    format!("Review {} [{}/{} viewed]", name, viewed, total)
+}
+pub fn review_note_popup_title(
+    _key_config: &SharedKeyConfig,
+) -> String {
+    "Note".to_string()
+}
+pub fn review_note_popup_msg(
+    _key_config: &SharedKeyConfig,
+) -> String {
+    "type a note for this line (empty removes it)".to_string()
+}
+pub fn title_review_notes() -> String {
+    "Notes".to_string()
+}
+pub fn review_note_entry(line: u32, old: bool, text: &str) -> String {
+    format!("{}{}: {}", if old { "-" } else { "+" }, line, text)
+}
+pub fn reset_commit_popup_title(commit: &str) -> String {
+    format!("Reset branch to {}", commit)
+}
+pub fn reset_commit_type(
+    kind: ResetType,
+) -> (&'static str, &'static str) {
+    match kind {
+        ResetType::Soft => ("soft", "keep changes staged"),
+        ResetType::Mixed => ("mixed", "keep changes unstaged"),
+        ResetType::Hard => ("hard", "discard all changes"),
+    }
+}
+pub fn reset_commit_hard_warning() -> &'static str {
+    "warning: uncommitted changes and commits after the selected one will be lost!"
+}
+pub fn checkout_strategy_popup_title(branch: &str) -> String {
+    format!("Uncommitted changes block checking out {}", branch)
+}
+pub fn checkout_strategy(
+    strategy: CheckoutStrategy,
+) -> (&'static str, &'static str) {
+    match strategy {
+        CheckoutStrategy::Safe => ("safe", "keep the current branch"),
+        CheckoutStrategy::Stash => {
+            ("stash", "stash the changes and switch")
+        }
+        CheckoutStrategy::Keep => {
+            ("keep", "take the changes along (stashed if they clash)")
+        }
+        CheckoutStrategy::Force => {
+            ("force", "discard the changes and switch")
+        }
+    }
+}
+pub fn checkout_force_warning() -> &'static str {
+    "warning: uncommitted changes to tracked files will be lost!"
+}
+pub fn detached_head() -> String {
+    "detached HEAD".to_string()
+}
+/// ahead/behind against the push remote next to the upstream one
+pub fn push_ahead_behind(push: &PushCompare) -> String {
+    push.compare.as_ref().map_or_else(
+        || format!("{}:new ", push.remote),
+        |state| {
+            format!(
+                "{}:\u{2191}{} \u{2193}{} ",
+                push.remote, state.ahead, state.behind
+            )
+        },
+    )
+}
+pub fn title_context_menu() -> String {
+    "Actions".to_string()
+}
 pub fn username_popup_title(_key_config: &SharedKeyConfig) -> String {
     "Username".to_string()
 }
@@ -243,9 +887,39 @@ pub fn rename_branch_popup_msg(
 ) -> String {
     "new branch name".to_string()
 }
+pub fn set_upstream_popup_title(
+    _key_config: &SharedKeyConfig,
+) -> String {
+    "Upstream Branch".to_string()
+}
+pub fn set_upstream_popup_msg(
+    _key_config: &SharedKeyConfig,
+) -> String {
+    "remote branch (e.g. origin/master), empty to remove".to_string()
+}
+pub fn push_remote_popup_title(branch: &str) -> String {
+    format!("Push '{}' and track on", branch)
+}
+pub fn ignore_path_popup_title(
+    _key_config: &SharedKeyConfig,
+) -> String {
+    "Add to .gitignore".to_string()
+}
+pub fn no_remote_msg(_key_config: &SharedKeyConfig) -> String {
+    "no remote to push to".to_string()
+}
+pub fn no_upstream_msg(key_config: &SharedKeyConfig) -> String {
+    format!(
+        "branch has no upstream, set one in the branch list [{}]",
+        key_config
+            .borrow()
+            .get_hint(key_config.borrow().branch_set_upstream)
+    )
+}
 
 pub mod commit {
     use crate::keys::SharedKeyConfig;
+    use asyncgit::sync::SignatureStatus;
     pub fn details_author(_key_config: &SharedKeyConfig) -> String {
         "Author: ".to_string()
     }
@@ -263,6 +937,21 @@ pub mod commit {
     pub fn details_tags(_key_config: &SharedKeyConfig) -> String {
         "Tags: ".to_string()
     }
+    pub fn details_signature(
+        _key_config: &SharedKeyConfig,
+    ) -> String {
+        "Signature: ".to_string()
+    }
+    pub fn details_signature_status(
+        status: SignatureStatus,
+    ) -> String {
+        match status {
+            SignatureStatus::Verified => "[verified]",
+            SignatureStatus::Unverified => "[unverified]",
+            SignatureStatus::Bad => "[bad]",
+        }
+        .to_string()
+    }
     pub fn details_info_title(
         _key_config: &SharedKeyConfig,
     ) -> String {
@@ -283,6 +972,7 @@ pub mod commit {
 pub mod commands {
     use crate::components::CommandText;
     use crate::keys::SharedKeyConfig;
+    use asyncgit::sync::{JournalOp, MergeFastForward};
 
     static CMD_GROUP_GENERAL: &str = "-- General --";
     static CMD_GROUP_DIFF: &str = "-- Diff --";
@@ -296,7 +986,9 @@ pub mod commands {
         CommandText::new(
             format!(
                 "Next [{}]",
-                key_config.get_hint(key_config.tab_toggle)
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().tab_toggle)
             ),
             "switch to next tab",
             CMD_GROUP_GENERAL,
@@ -308,10 +1000,18 @@ pub mod commands {
         CommandText::new(
             format!(
                 "Tab [{}{}{}{}]",
-                key_config.get_hint(key_config.tab_status),
-                key_config.get_hint(key_config.tab_log),
-                key_config.get_hint(key_config.tab_stashing),
-                key_config.get_hint(key_config.tab_stashes),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().tab_status),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().tab_log),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().tab_stashing),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().tab_stashes),
             ),
             "switch top level tabs directly",
             CMD_GROUP_GENERAL,
@@ -321,7 +1021,9 @@ pub mod commands {
         CommandText::new(
             format!(
                 "Help [{}]",
-                key_config.get_hint(key_config.open_help)
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().open_help)
             ),
             "open this help screen",
             CMD_GROUP_GENERAL,
@@ -333,44 +1035,174 @@ pub mod commands {
         CommandText::new(
             format!(
                 "Nav [{}{}]",
-                key_config.get_hint(key_config.move_up),
-                key_config.get_hint(key_config.move_down)
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().move_up),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().move_down)
             ),
             "navigate commit message",
             CMD_GROUP_GENERAL,
         )
     }
-    pub fn navigate_tree(
+    pub fn time_machine_older(
         key_config: &SharedKeyConfig,
+        file: bool,
     ) -> CommandText {
         CommandText::new(
             format!(
-                "Nav [{}{}{}{}]",
-                key_config.get_hint(key_config.move_up),
-                key_config.get_hint(key_config.move_down),
-                key_config.get_hint(key_config.move_right),
-                key_config.get_hint(key_config.move_left)
-            ),
-            "navigate tree view",
+                "Older [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().time_machine_older),
+            ),
+            if file {
+                "show the previous version of the file"
+            } else {
+                "show the tree of the previous commit"
+            },
             CMD_GROUP_GENERAL,
         )
     }
-    pub fn scroll(key_config: &SharedKeyConfig) -> CommandText {
+    pub fn time_machine_newer(
+        key_config: &SharedKeyConfig,
+        file: bool,
+    ) -> CommandText {
         CommandText::new(
             format!(
-                "Scroll [{}{}]",
-                key_config.get_hint(key_config.focus_above),
-                key_config.get_hint(key_config.focus_below)
-            ),
-            "scroll up or down in focused view",
+                "Newer [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().time_machine_newer),
+            ),
+            if file {
+                "show the next version of the file"
+            } else {
+                "show the tree of the next commit towards HEAD"
+            },
             CMD_GROUP_GENERAL,
         )
     }
-    pub fn copy(key_config: &SharedKeyConfig) -> CommandText {
-        CommandText::new(
+    pub fn edit_tree_file(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Edit [{}]",
+                key_config.borrow().get_hint(key_config.borrow().edit_file),
+            ),
+            "open a copy of the file at this revision in an external editor",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn view_file_pager(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Pager [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().view_file_pager),
+            ),
+            "show the file at this revision in the external pager",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn file_filter(key_config: &SharedKeyConfig) -> CommandText {
+        CommandText::new(
+            format!(
+                "Filter [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().file_filter),
+            ),
+            "fuzzy filter the listed files by path",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn file_mark(key_config: &SharedKeyConfig) -> CommandText {
+        CommandText::new(
+            format!(
+                "Mark [{}]",
+                key_config.borrow().get_hint(key_config.borrow().file_mark),
+            ),
+            "mark/unmark selected file (or folder), actions then apply to all marked files",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn file_mark_range(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Mark range [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().file_mark_range),
+            ),
+            "start marking a range of files, press again to end it",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn help_filter(key_config: &SharedKeyConfig) -> CommandText {
+        CommandText::new(
+            format!(
+                "Filter [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().file_filter),
+            ),
+            "fuzzy filter the commands",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn navigate_tree(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Nav [{}{}{}{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().move_up),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().move_down),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().move_right),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().move_left)
+            ),
+            "navigate tree view",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn scroll(key_config: &SharedKeyConfig) -> CommandText {
+        CommandText::new(
+            format!(
+                "Scroll [{}{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().focus_above),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().focus_below)
+            ),
+            "scroll up or down in focused view",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn copy(key_config: &SharedKeyConfig) -> CommandText {
+        CommandText::new(
             format!(
                 "Copy [{}]",
-                key_config.get_hint(key_config.copy),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().copy),
             ),
             "copy selected lines to clipboard",
             CMD_GROUP_DIFF,
@@ -380,32 +1212,158 @@ pub mod commands {
         CommandText::new(
             format!(
                 "Copy Hash [{}]",
-                key_config.get_hint(key_config.copy),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().copy),
             ),
             "copy selected commit hash to clipboard",
             CMD_GROUP_LOG,
         )
     }
+    pub fn copy_commit_message(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Copy Message [{}]",
+                key_config.borrow().get_hint(
+                    key_config.borrow().copy_commit_message
+                ),
+            ),
+            "copy message of selected commit to clipboard",
+            CMD_GROUP_LOG,
+        )
+    }
+    pub fn copy_path(key_config: &SharedKeyConfig) -> CommandText {
+        CommandText::new(
+            format!(
+                "Copy Path [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().copy),
+            ),
+            "copy path of selected file to clipboard",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn copy_branch_name(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Copy Name [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().copy),
+            ),
+            "copy name of selected branch to clipboard",
+            CMD_GROUP_GENERAL,
+        )
+    }
     pub fn push_tags(key_config: &SharedKeyConfig) -> CommandText {
         CommandText::new(
             format!(
                 "Push Tags [{}]",
-                key_config.get_hint(key_config.push),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().push),
             ),
             "push tags to remote",
             CMD_GROUP_LOG,
         )
     }
+    pub fn log_tags(key_config: &SharedKeyConfig) -> CommandText {
+        CommandText::new(
+            format!(
+                "Tags [{}]",
+                key_config.borrow().get_hint(key_config.borrow().log_tags),
+            ),
+            "list tags to delete or push several or move one to the selected commit",
+            CMD_GROUP_LOG,
+        )
+    }
+    pub fn tag_list_mark(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Mark [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().log_mark_commit),
+            ),
+            "mark/unmark selected tag",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn tag_list_delete(
+        key_config: &SharedKeyConfig,
+        marked: usize,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Delete{} [{}]",
+                if marked > 0 {
+                    format!(" {}", marked)
+                } else {
+                    String::new()
+                },
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().delete_tag),
+            ),
+            "delete the marked tags or the selected one",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn tag_list_push(
+        key_config: &SharedKeyConfig,
+        marked: usize,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Push{} [{}]",
+                if marked > 0 {
+                    format!(" {}", marked)
+                } else {
+                    String::new()
+                },
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().push),
+            ),
+            "push the marked tags or the selected one",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn tag_list_retag(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Move to commit [{}]",
+                key_config.borrow().get_hint(key_config.borrow().log_tag_commit),
+            ),
+            "move the selected tag to the commit selected in the log and force push it",
+            CMD_GROUP_GENERAL,
+        )
+    }
     pub fn diff_home_end(
         key_config: &SharedKeyConfig,
     ) -> CommandText {
         CommandText::new(
             format!(
                 "Jump up/down [{},{},{},{}]",
-                key_config.get_hint(key_config.home),
-                key_config.get_hint(key_config.end),
-                key_config.get_hint(key_config.move_up),
-                key_config.get_hint(key_config.move_down)
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().home),
+                key_config.borrow().get_hint(key_config.borrow().end),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().move_up),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().move_down)
             ),
             "scroll to top or bottom of diff",
             CMD_GROUP_DIFF,
@@ -417,7 +1375,9 @@ pub mod commands {
         CommandText::new(
             format!(
                 "Add hunk [{}]",
-                key_config.get_hint(key_config.enter),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().enter),
             ),
             "adds selected hunk to stage",
             CMD_GROUP_DIFF,
@@ -429,7 +1389,9 @@ pub mod commands {
         CommandText::new(
             format!(
                 "Reset hunk [{}]",
-                key_config.get_hint(key_config.status_reset_item),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().status_reset_item),
             ),
             "reverts selected hunk",
             CMD_GROUP_DIFF,
@@ -441,7 +1403,9 @@ pub mod commands {
         CommandText::new(
             format!(
                 "Reset lines [{}]",
-                key_config.get_hint(key_config.diff_reset_lines),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().diff_reset_lines),
             ),
             "resets selected lines",
             CMD_GROUP_DIFF,
@@ -453,7 +1417,9 @@ pub mod commands {
         CommandText::new(
             format!(
                 "Stage lines [{}]",
-                key_config.get_hint(key_config.diff_stage_lines),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().diff_stage_lines),
             ),
             "stage selected lines",
             CMD_GROUP_DIFF,
@@ -465,19 +1431,94 @@ pub mod commands {
         CommandText::new(
             format!(
                 "Unstage lines [{}]",
-                key_config.get_hint(key_config.diff_stage_lines),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().diff_stage_lines),
             ),
             "unstage selected lines",
             CMD_GROUP_DIFF,
         )
     }
+    pub fn diff_split_toggle(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Split view [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().diff_split_toggle),
+            ),
+            "toggle side by side diff view",
+            CMD_GROUP_DIFF,
+        )
+    }
+    pub fn diff_whitespace(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Whitespace [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().diff_whitespace),
+            ),
+            "cycle ignoring whitespace changes (none, eol, all)",
+            CMD_GROUP_DIFF,
+        )
+    }
+    pub fn diff_context(key_config: &SharedKeyConfig) -> CommandText {
+        CommandText::new(
+            format!(
+                "Context [{}{}]",
+                key_config.borrow().get_hint(
+                    key_config.borrow().diff_context_increase
+                ),
+                key_config.borrow().get_hint(
+                    key_config.borrow().diff_context_decrease
+                ),
+            ),
+            "increase/decrease number of context lines",
+            CMD_GROUP_DIFF,
+        )
+    }
+    pub fn diff_jump_symbol(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Enclosing symbol [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().diff_jump_symbol),
+            ),
+            "jump to the hunk header naming the enclosing function",
+            CMD_GROUP_DIFF,
+        )
+    }
+    pub fn diff_toggle_fold(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Fold [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().diff_toggle_fold),
+            ),
+            "fold/expand the diff into a one line summary",
+            CMD_GROUP_DIFF,
+        )
+    }
     pub fn diff_hunk_remove(
         key_config: &SharedKeyConfig,
     ) -> CommandText {
         CommandText::new(
             format!(
                 "Remove hunk [{}]",
-                key_config.get_hint(key_config.enter),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().enter),
             ),
             "removes selected hunk from stage",
             CMD_GROUP_DIFF,
@@ -487,7 +1528,9 @@ pub mod commands {
         CommandText::new(
             format!(
                 "Close [{}]",
-                key_config.get_hint(key_config.exit_popup),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().exit_popup),
             ),
             "close overlay (e.g commit, help)",
             CMD_GROUP_GENERAL,
@@ -497,7 +1540,9 @@ pub mod commands {
         CommandText::new(
             format!(
                 "Close [{}]",
-                key_config.get_hint(key_config.enter),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().enter),
             ),
             "close msg popup (e.g msg)",
             CMD_GROUP_GENERAL,
@@ -508,7 +1553,9 @@ pub mod commands {
         CommandText::new(
             format!(
                 "Validate [{}]",
-                key_config.get_hint(key_config.enter),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().enter),
             ),
             "validate msg",
             CMD_GROUP_GENERAL,
@@ -521,18 +1568,36 @@ pub mod commands {
         CommandText::new(
             format!(
                 "To files [{},{}]",
-                key_config.get_hint(key_config.tab_status),
-                key_config.get_hint(key_config.tab_log),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().tab_status),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().tab_log),
             ),
             "focus/select file tree of staged or unstaged files",
             CMD_GROUP_GENERAL,
         )
     }
+    pub fn quick_amend(key_config: &SharedKeyConfig) -> CommandText {
+        CommandText::new(
+            format!(
+                "Amend staged [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().quick_amend),
+            ),
+            "amend the staged changes into HEAD keeping its message",
+            CMD_GROUP_COMMIT,
+        )
+    }
     pub fn abort_merge(key_config: &SharedKeyConfig) -> CommandText {
         CommandText::new(
             format!(
                 "Abort merge [{}]",
-                key_config.get_hint(key_config.abort_merge),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().abort_merge),
             ),
             "abort ongoing merge",
             CMD_GROUP_GENERAL,
@@ -544,7 +1609,9 @@ pub mod commands {
         CommandText::new(
             format!(
                 "To stage [{}]",
-                key_config.get_hint(key_config.toggle_workarea),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().toggle_workarea),
             ),
             "focus/select staging area",
             CMD_GROUP_GENERAL,
@@ -556,7 +1623,9 @@ pub mod commands {
         CommandText::new(
             format!(
                 "To unstaged [{}]",
-                key_config.get_hint(key_config.toggle_workarea),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().toggle_workarea),
             ),
             "focus/select unstaged area",
             CMD_GROUP_GENERAL,
@@ -566,7 +1635,9 @@ pub mod commands {
         CommandText::new(
             format!(
                 "Commit [{}]",
-                key_config.get_hint(key_config.open_commit),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().open_commit),
             ),
             "open commit popup (available in non-empty stage)",
             CMD_GROUP_COMMIT,
@@ -578,9 +1649,11 @@ pub mod commands {
         CommandText::new(
             format!(
                 "Open editor [{}]",
-                key_config.get_hint(key_config.open_commit_editor),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().open_commit_editor),
             ),
-            "open commit editor (available in non-empty stage)",
+            "write the message in the external editor and commit it",
             CMD_GROUP_COMMIT,
         )
     }
@@ -588,114 +1661,1164 @@ pub mod commands {
         CommandText::new(
             format!(
                 "Commit [{}]",
-                key_config.get_hint(key_config.enter),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().enter),
+            ),
+            "commit (available when commit message is non-empty)",
+            CMD_GROUP_COMMIT,
+        )
+    }
+    pub fn commit_amend(key_config: &SharedKeyConfig) -> CommandText {
+        CommandText::new(
+            format!(
+                "Amend [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().commit_amend),
+            ),
+            "amend last commit",
+            CMD_GROUP_COMMIT,
+        )
+    }
+    pub fn commit_history(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "History [{}{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().move_up),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().move_down),
+            ),
+            "cycle through recently used commit messages",
+            CMD_GROUP_COMMIT,
+        )
+    }
+    pub fn commit_reuse_msg(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Reuse msg [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().commit_reuse_msg),
+            ),
+            "reuse message and author of last commit",
+            CMD_GROUP_COMMIT,
+        )
+    }
+    pub fn commit_stage_tracked(
+        key_config: &SharedKeyConfig,
+        enabled: bool,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "{} tracked [{}]",
+                if enabled { "Don't stage" } else { "Stage" },
+                key_config.borrow().get_hint(key_config.borrow().commit_stage_tracked),
+            ),
+            "stage all modified and deleted tracked files with the commit (like `git commit -a`)",
+            CMD_GROUP_COMMIT,
+        )
+    }
+    pub fn commit_new_branch(
+        key_config: &SharedKeyConfig,
+        enabled: bool,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "{} [{}]",
+                if enabled {
+                    "Commit to current branch"
+                } else {
+                    "Commit to new branch"
+                },
+                key_config.borrow().get_hint(key_config.borrow().commit_new_branch),
+            ),
+            "create a branch at HEAD and commit there, the current branch stays as it is",
+            CMD_GROUP_COMMIT,
+        )
+    }
+    pub fn commit_complete_path(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Complete path [{}]",
+                key_config.borrow().get_hint(key_config.borrow().commit_complete_path),
+            ),
+            "complete the word at the cursor to a staged file name or path, again for the next one",
+            CMD_GROUP_COMMIT,
+        )
+    }
+    pub fn commit_new_branch_confirm(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Set branch [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().enter),
+            ),
+            "commit to a new branch of this name",
+            CMD_GROUP_COMMIT,
+        )
+    }
+    pub fn edit_item(key_config: &SharedKeyConfig) -> CommandText {
+        CommandText::new(
+            format!(
+                "Edit [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().edit_file),
+            ),
+            "edit the currently selected file in an external editor",
+            CMD_GROUP_CHANGES,
+        )
+    }
+    pub fn stage_item(key_config: &SharedKeyConfig) -> CommandText {
+        CommandText::new(
+            format!(
+                "Stage [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().enter),
+            ),
+            "stage currently selected file or entire path",
+            CMD_GROUP_CHANGES,
+        )
+    }
+    pub fn stage_all(key_config: &SharedKeyConfig) -> CommandText {
+        CommandText::new(
+            format!(
+                "Stage All [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().status_stage_all),
+            ),
+            "stage all changes (in unstaged files)",
+            CMD_GROUP_CHANGES,
+        )
+    }
+    pub fn unstage_item(key_config: &SharedKeyConfig) -> CommandText {
+        CommandText::new(
+            format!(
+                "Unstage [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().enter),
+            ),
+            "unstage currently selected file or entire path",
+            CMD_GROUP_CHANGES,
+        )
+    }
+    pub fn unstage_all(key_config: &SharedKeyConfig) -> CommandText {
+        CommandText::new(
+            format!(
+                "Unstage all [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().status_stage_all),
+            ),
+            "unstage all files (in staged files)",
+            CMD_GROUP_CHANGES,
+        )
+    }
+    pub fn reset_item(key_config: &SharedKeyConfig) -> CommandText {
+        CommandText::new(
+            format!(
+                "Reset [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().status_reset_item),
+            ),
+            "revert changes in selected file or entire path",
+            CMD_GROUP_CHANGES,
+        )
+    }
+    pub fn discard_all(key_config: &SharedKeyConfig) -> CommandText {
+        CommandText::new(
+            format!(
+                "Discard all [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().status_discard_all),
+            ),
+            "revert all unstaged changes and remove untracked files",
+            CMD_GROUP_CHANGES,
+        )
+    }
+    pub fn expand_folder_status(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Expand [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().status_open_repo),
+            ),
+            "full status of the folder, by scoping to it",
+            CMD_GROUP_CHANGES,
+        )
+    }
+    pub fn open_nested_repo(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Open repo [{}]",
+                key_config.borrow().get_hint(key_config.borrow().status_open_repo),
+            ),
+            "run gitui inside of the selected submodule or nested repository",
+            CMD_GROUP_CHANGES,
+        )
+    }
+    pub fn update_submodule(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Update submodule [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().status_update_submodule),
+            ),
+            "check out the commit recorded for the selected submodule",
+            CMD_GROUP_CHANGES,
+        )
+    }
+    pub fn ignore_item(key_config: &SharedKeyConfig) -> CommandText {
+        CommandText::new(
+            format!(
+                "Ignore [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().status_ignore_file),
+            ),
+            "Add file or path to .gitignore",
+            CMD_GROUP_CHANGES,
+        )
+    }
+    pub fn status_toggle_hidden(
+        key_config: &SharedKeyConfig,
+        show_hidden: bool,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "{} hidden [{}]",
+                if show_hidden { "Hide" } else { "Show" },
+                key_config.borrow().get_hint(key_config.borrow().status_toggle_hidden),
+            ),
+            "toggle files matching the configured hide patterns and files with skipped changes",
+            CMD_GROUP_CHANGES,
+        )
+    }
+    pub fn status_skip_worktree(
+        key_config: &SharedKeyConfig,
+        skipped: bool,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "{} changes [{}]",
+                if skipped { "Unskip" } else { "Skip" },
+                key_config.borrow().get_hint(key_config.borrow().status_skip_worktree),
+            ),
+            "hide local changes of the tracked file from the status (skip-worktree)",
+            CMD_GROUP_CHANGES,
+        )
+    }
+    pub fn hex_goto_offset(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Goto offset [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().hex_goto_offset),
+            ),
+            "jump to a hex offset of the binary file",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn open_link(key_config: &SharedKeyConfig) -> CommandText {
+        CommandText::new(
+            format!(
+                "Links [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().open_link),
+            ),
+            "choose a url or issue reference to open in the browser",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn open_link_confirm(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Open [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().enter),
+            ),
+            "open the selected link in the browser",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn markdown_toggle_raw(
+        key_config: &SharedKeyConfig,
+        raw: bool,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "{} [{}]",
+                if raw { "Render markdown" } else { "Raw text" },
+                key_config.borrow().get_hint(
+                    key_config.borrow().markdown_toggle_raw
+                ),
+            ),
+            "toggle between rendered markdown and its raw text",
+            CMD_GROUP_GENERAL,
+        )
+    }
+
+    pub fn diff_focus_left(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Back [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().focus_left),
+            ),
+            "view and select changed files",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn diff_focus_right(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Diff [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().focus_right),
+            ),
+            "inspect file diff",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn open_repo_switcher(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Repos [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().open_repo_switcher),
+            ),
+            "switch to a recently opened repository",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn open_hooks(key_config: &SharedKeyConfig) -> CommandText {
+        CommandText::new(
+            format!(
+                "Hooks [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().open_hooks),
+            ),
+            "list the installed hooks to turn them off or run them",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn hooks_toggle(key_config: &SharedKeyConfig) -> CommandText {
+        CommandText::new(
+            format!(
+                "On/Off [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().file_mark),
+            ),
+            "whether gitui runs the selected hook when committing",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn hooks_run(key_config: &SharedKeyConfig) -> CommandText {
+        CommandText::new(
+            format!(
+                "Run [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().enter),
+            ),
+            "run the selected hook and show its output",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn open_rebase(key_config: &SharedKeyConfig) -> CommandText {
+        CommandText::new(
+            format!(
+                "Rebase [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().open_rebase),
+            ),
+            "show the commits of the rebase in progress",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn rebase_branch(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Rebase onto [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().rebase_branch),
+            ),
+            "rebase the current branch onto the selected one",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn rebase_start(key_config: &SharedKeyConfig) -> CommandText {
+        CommandText::new(
+            format!(
+                "Start [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().enter),
+            ),
+            "rebase the current branch replaying these commits",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn rebase_continue(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Continue [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().enter),
+            ),
+            "commit the resolved conflicts and continue the rebase",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn rebase_edit_message(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Edit message [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().open_commit),
+            ),
+            "continue with a new message for the current commit",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn rebase_skip(key_config: &SharedKeyConfig) -> CommandText {
+        CommandText::new(
+            format!(
+                "Skip [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().rebase_skip),
+            ),
+            "drop the current commit and continue the rebase",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn rebase_abort(key_config: &SharedKeyConfig) -> CommandText {
+        CommandText::new(
+            format!(
+                "Abort [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().abort_merge),
+            ),
+            "restore the branch as it was before the rebase",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn rebase_move_step(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Move [{}{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().shift_up),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().shift_down),
+            ),
+            "reorder the commits not replayed yet",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn resize_panes(
+        key_config: &SharedKeyConfig,
+        vertical: bool,
+    ) -> CommandText {
+        let mut keys = format!(
+            "{}{}",
+            key_config
+                .borrow()
+                .get_hint(key_config.borrow().split_left),
+            key_config
+                .borrow()
+                .get_hint(key_config.borrow().split_right),
+        );
+        if vertical {
+            keys.push_str(
+                &key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().split_up),
+            );
+            keys.push_str(
+                &key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().split_down),
+            );
+        }
+
+        CommandText::new(
+            format!("Resize [{}]", keys),
+            "move the borders between the panes (saved per repository)",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn zoom_pane(
+        key_config: &SharedKeyConfig,
+        zoomed: bool,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "{} [{}]",
+                if zoomed { "Unzoom" } else { "Zoom" },
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().zoom_pane),
+            ),
+            "show the focused pane only",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn open_command_palette(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Command palette [{}]",
+                key_config.borrow().get_hint(
+                    key_config.borrow().open_command_palette
+                ),
+            ),
+            "search all available commands by name and run one",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn command_palette_run(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Run [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().enter),
+            ),
+            "run the selected command",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn toggle_perf_hud(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Perf HUD [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().toggle_perf_hud),
+            ),
+            "show frame time, async job timings and event backlog",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn repo_switch(key_config: &SharedKeyConfig) -> CommandText {
+        CommandText::new(
+            format!(
+                "Switch [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().enter),
+            ),
+            "open selected repository",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn repo_open_path(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Open path [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().repo_open_path),
+            ),
+            "open repository by typing its path",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn repo_remove(key_config: &SharedKeyConfig) -> CommandText {
+        CommandText::new(
+            format!(
+                "Remove [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().repo_remove),
+            ),
+            "remove repository from the recent list",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn repo_path_confirm_msg(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Open [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().enter),
+            ),
+            "open repository",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn diff_base_confirm_msg(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Set [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().enter),
+            ),
+            "diff working tree against this ref",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn status_diff_base(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Diff Base [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().status_diff_base),
+            ),
+            "diff working tree against another ref than HEAD",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn status_apply_patch(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Apply Patch [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().status_apply_patch),
+            ),
+            "apply a patch file or the clipboard to the working tree",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn apply_patch_confirm(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Apply [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().enter),
+            ),
+            "apply the patch in the file",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn apply_patch_clipboard(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "From Clipboard [{}]",
+                key_config.borrow().get_hint(
+                    key_config.borrow().apply_patch_clipboard
+                ),
+            ),
+            "apply the patch in the clipboard instead",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn open_review(key_config: &SharedKeyConfig) -> CommandText {
+        CommandText::new(
+            format!(
+                "Review [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().open_review),
+            ),
+            "review all changes of the branch against a base ref",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn review_base_confirm_msg(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Review [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().enter),
+            ),
+            "list changes against this ref",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn review_toggle_viewed(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Viewed [{}]",
+                key_config.borrow().get_hint(
+                    key_config.borrow().review_toggle_viewed
+                ),
+            ),
+            "mark file as viewed or unviewed",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn review_note(key_config: &SharedKeyConfig) -> CommandText {
+        CommandText::new(
+            format!(
+                "Note [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().review_note),
+            ),
+            "add or edit a draft note on the selected line",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn review_note_confirm_msg(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Save [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().enter),
+            ),
+            "save note",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn review_export_notes(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Export Notes [{}]",
+                key_config.borrow().get_hint(
+                    key_config.borrow().review_export_notes
+                ),
+            ),
+            "copy all notes as markdown to the clipboard",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn undo_operation(
+        key_config: &SharedKeyConfig,
+        op: Option<&JournalOp>,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Undo {}[{}]",
+                op.map(|op| format!("{} ", super::journal_op(op)))
+                    .unwrap_or_default(),
+                key_config.borrow().get_hint(key_config.borrow().undo_operation),
+            ),
+            "restore the state before the last discard, reset, branch delete or stash drop",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn redo_operation(
+        key_config: &SharedKeyConfig,
+        op: Option<&JournalOp>,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Redo {}[{}]",
+                op.map(|op| format!("{} ", super::journal_op(op)))
+                    .unwrap_or_default(),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().redo_operation),
+            ),
+            "run the last undone operation again",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn undo_commit(key_config: &SharedKeyConfig) -> CommandText {
+        CommandText::new(
+            format!(
+                "Undo Commit [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().undo_commit),
+            ),
+            "undo last commit keeping its changes staged",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn log_reset_commit(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Reset [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().log_reset_commit),
+            ),
+            "reset current branch to selected commit",
+            CMD_GROUP_LOG,
+        )
+    }
+    pub fn log_checkout_commit(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Checkout [{}]",
+                key_config.borrow().get_hint(
+                    key_config.borrow().log_checkout_commit
+                ),
+            ),
+            "checkout selected commit (detached HEAD)",
+            CMD_GROUP_LOG,
+        )
+    }
+    pub fn log_mark_commit(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Mark [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().log_mark_commit),
+            ),
+            "mark/unmark selected commit for squashing",
+            CMD_GROUP_LOG,
+        )
+    }
+    pub fn log_squash_commits(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Squash [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().log_squash_commits),
+            ),
+            "squash marked commits into one, keeping all messages",
+            CMD_GROUP_LOG,
+        )
+    }
+    pub fn log_fixup_commits(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Fixup [{}]",
+                key_config.borrow().get_hint(key_config.borrow().log_fixup_commits),
+            ),
+            "squash marked commits into one, keeping the oldest message",
+            CMD_GROUP_LOG,
+        )
+    }
+    pub fn log_toggle_replace_refs(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Replace refs [{}]",
+                key_config.borrow().get_hint(key_config.borrow().log_toggle_replace_refs),
+            ),
+            "toggle showing commits replaced by refs/replace and grafts (like git --no-replace-objects)",
+            CMD_GROUP_LOG,
+        )
+    }
+    pub fn log_toggle_decorations(
+        key_config: &SharedKeyConfig,
+        shown: bool,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "{} refs [{}]",
+                if shown { "Hide" } else { "Show" },
+                key_config.borrow().get_hint(key_config.borrow().log_toggle_decorations),
+            ),
+            "toggle showing branches, remote branches and tags next to their commits",
+            CMD_GROUP_LOG,
+        )
+    }
+    pub fn log_goto_commit(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Go to [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().log_goto_commit),
+            ),
+            "select the commit of a sha, branch or tag",
+            CMD_GROUP_LOG,
+        )
+    }
+    pub fn log_filter(key_config: &SharedKeyConfig) -> CommandText {
+        CommandText::new(
+            format!(
+                "Filter [{}]",
+                key_config.borrow().get_hint(key_config.borrow().log_filter),
+            ),
+            "show only commits by an author, changing a path or in a date range",
+            CMD_GROUP_LOG,
+        )
+    }
+    pub fn log_export_patch(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Export Patch [{}]",
+                key_config.borrow().get_hint(key_config.borrow().log_export_patch),
+            ),
+            "save the selected commit as a patch like git format-patch",
+            CMD_GROUP_LOG,
+        )
+    }
+    pub fn export_patch_confirm(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Save [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().enter),
+            ),
+            "write the patch to the file",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn export_patch_copy(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Copy [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().export_patch_copy),
+            ),
+            "copy the patch to the clipboard instead",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn log_filter_confirm(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Filter [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().enter),
             ),
-            "commit (available when commit message is non-empty)",
-            CMD_GROUP_COMMIT,
+            "filter the log (empty shows all commits)",
+            CMD_GROUP_GENERAL,
         )
     }
-    pub fn commit_amend(key_config: &SharedKeyConfig) -> CommandText {
+    pub fn goto_commit_confirm(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
         CommandText::new(
             format!(
-                "Amend [{}]",
-                key_config.get_hint(key_config.commit_amend),
+                "Go to [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().enter),
             ),
-            "amend last commit",
-            CMD_GROUP_COMMIT,
+            "select the commit in the log",
+            CMD_GROUP_GENERAL,
         )
     }
-    pub fn edit_item(key_config: &SharedKeyConfig) -> CommandText {
+    pub fn fetch_options_confirm(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
         CommandText::new(
             format!(
-                "Edit [{}]",
-                key_config.get_hint(key_config.edit_file),
+                "Fetch [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().enter),
             ),
-            "edit the currently selected file in an external editor",
-            CMD_GROUP_CHANGES,
+            "fetch the branch with this depth, nothing gets merged",
+            CMD_GROUP_GENERAL,
         )
     }
-    pub fn stage_item(key_config: &SharedKeyConfig) -> CommandText {
+    pub fn status_fetch_options(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
         CommandText::new(
             format!(
-                "Stage [{}]",
-                key_config.get_hint(key_config.enter),
+                "Fetch depth [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().fetch_options),
             ),
-            "stage currently selected file or entire path",
-            CMD_GROUP_CHANGES,
+            "fetch the branch limited to a depth or unshallow it",
+            CMD_GROUP_GENERAL,
         )
     }
-    pub fn stage_all(key_config: &SharedKeyConfig) -> CommandText {
+    pub fn log_create_branch(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
         CommandText::new(
             format!(
-                "Stage All [{}]",
-                key_config.get_hint(key_config.status_stage_all),
+                "Branch [{}]",
+                key_config.borrow().get_hint(key_config.borrow().create_branch),
             ),
-            "stage all changes (in unstaged files)",
-            CMD_GROUP_CHANGES,
+            "create branch at selected commit (switches to it if the commit is HEAD)",
+            CMD_GROUP_LOG,
         )
     }
-    pub fn unstage_item(key_config: &SharedKeyConfig) -> CommandText {
+    pub fn checkout_strategy_confirm(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
         CommandText::new(
             format!(
-                "Unstage [{}]",
-                key_config.get_hint(key_config.enter),
+                "Checkout [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().enter),
             ),
-            "unstage currently selected file or entire path",
-            CMD_GROUP_CHANGES,
+            "check out the branch using the selected strategy",
+            CMD_GROUP_GENERAL,
         )
     }
-    pub fn unstage_all(key_config: &SharedKeyConfig) -> CommandText {
+    pub fn reset_commit_confirm(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
         CommandText::new(
             format!(
-                "Unstage all [{}]",
-                key_config.get_hint(key_config.status_stage_all),
+                "Reset [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().enter),
             ),
-            "unstage all files (in staged files)",
-            CMD_GROUP_CHANGES,
+            "reset branch using selected mode",
+            CMD_GROUP_GENERAL,
         )
     }
-    pub fn reset_item(key_config: &SharedKeyConfig) -> CommandText {
+    pub fn open_context_menu(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
         CommandText::new(
             format!(
-                "Reset [{}]",
-                key_config.get_hint(key_config.status_reset_item),
+                "Actions [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().open_context_menu),
             ),
-            "revert changes in selected file or entire path",
-            CMD_GROUP_CHANGES,
+            "list all actions of the selected item",
+            CMD_GROUP_GENERAL,
         )
     }
-    pub fn ignore_item(key_config: &SharedKeyConfig) -> CommandText {
+    pub fn context_menu_confirm(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
         CommandText::new(
             format!(
-                "Ignore [{}]",
-                key_config.get_hint(key_config.status_ignore_file),
+                "Run [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().enter),
             ),
-            "Add file or path to .gitignore",
-            CMD_GROUP_CHANGES,
+            "run selected action",
+            CMD_GROUP_GENERAL,
         )
     }
-
-    pub fn diff_focus_left(
+    pub fn push_remote_confirm(
         key_config: &SharedKeyConfig,
     ) -> CommandText {
         CommandText::new(
             format!(
-                "Back [{}]",
-                key_config.get_hint(key_config.focus_left),
+                "Push [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().enter),
             ),
-            "view and select changed files",
+            "push to the selected remote and track it",
             CMD_GROUP_GENERAL,
         )
     }
-    pub fn diff_focus_right(
+    pub fn ignore_path_confirm(
         key_config: &SharedKeyConfig,
     ) -> CommandText {
         CommandText::new(
             format!(
-                "Diff [{}]",
-                key_config.get_hint(key_config.focus_right),
+                "Ignore [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().enter),
             ),
-            "inspect file diff",
+            "add the selected pattern to .gitignore",
             CMD_GROUP_GENERAL,
         )
     }
@@ -703,7 +2826,9 @@ pub mod commands {
         CommandText::new(
             format!(
                 "Quit [{}]",
-                key_config.get_hint(key_config.exit),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().exit),
             ),
             "quit gitui application",
             CMD_GROUP_GENERAL,
@@ -715,7 +2840,9 @@ pub mod commands {
         CommandText::new(
             format!(
                 "Confirm [{}]",
-                key_config.get_hint(key_config.enter),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().enter),
             ),
             "confirm action",
             CMD_GROUP_GENERAL,
@@ -727,7 +2854,9 @@ pub mod commands {
         CommandText::new(
             format!(
                 "Save [{}]",
-                key_config.get_hint(key_config.stashing_save),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().stashing_save),
             ),
             "opens stash name input popup",
             CMD_GROUP_STASHING,
@@ -739,7 +2868,9 @@ pub mod commands {
         CommandText::new(
             format!(
                 "Toggle Staged [{}]",
-                key_config.get_hint(key_config.stashing_toggle_index),
+                key_config.borrow().get_hint(
+                    key_config.borrow().stashing_toggle_index
+                ),
             ),
             "toggle including staged files into stash",
             CMD_GROUP_STASHING,
@@ -751,20 +2882,37 @@ pub mod commands {
         CommandText::new(
             format!(
                 "Toggle Untracked [{}]",
-                key_config
-                    .get_hint(key_config.stashing_toggle_untracked),
+                key_config.borrow().get_hint(
+                    key_config.borrow().stashing_toggle_untracked
+                ),
             ),
             "toggle including untracked files into stash",
             CMD_GROUP_STASHING,
         )
     }
+    pub fn stashing_toggle_ignored(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Toggle Ignored [{}]",
+                key_config.borrow().get_hint(
+                    key_config.borrow().stashing_toggle_ignored
+                ),
+            ),
+            "toggle including ignored files into stash",
+            CMD_GROUP_STASHING,
+        )
+    }
     pub fn stashing_confirm_msg(
         key_config: &SharedKeyConfig,
     ) -> CommandText {
         CommandText::new(
             format!(
                 "Stash [{}]",
-                key_config.get_hint(key_config.enter),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().enter),
             ),
             "save files to stash",
             CMD_GROUP_STASHING,
@@ -776,7 +2924,9 @@ pub mod commands {
         CommandText::new(
             format!(
                 "Apply [{}]",
-                key_config.get_hint(key_config.stash_apply),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().stash_apply),
             ),
             "apply selected stash",
             CMD_GROUP_STASHES,
@@ -788,7 +2938,9 @@ pub mod commands {
         CommandText::new(
             format!(
                 "Drop [{}]",
-                key_config.get_hint(key_config.stash_drop),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().stash_drop),
             ),
             "drop selected stash",
             CMD_GROUP_STASHES,
@@ -800,19 +2952,49 @@ pub mod commands {
         CommandText::new(
             format!(
                 "Pop [{}]",
-                key_config.get_hint(key_config.enter),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().enter),
             ),
             "pop selected stash",
             CMD_GROUP_STASHES,
         )
     }
+    pub fn stashlist_rename(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Rename [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().stash_rename),
+            ),
+            "change the message of the selected stash",
+            CMD_GROUP_STASHES,
+        )
+    }
+    pub fn stashlist_branch(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Branch [{}]",
+                key_config.borrow().get_hint(key_config.borrow().stash_branch),
+            ),
+            "create a branch on the commit the stash was made on and pop it there",
+            CMD_GROUP_STASHES,
+        )
+    }
     pub fn stashlist_inspect(
         key_config: &SharedKeyConfig,
     ) -> CommandText {
         CommandText::new(
             format!(
                 "Inspect [{}]",
-                key_config.get_hint(key_config.focus_right),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().focus_right),
             ),
             "open stash commit details (allows to diff files)",
             CMD_GROUP_STASHES,
@@ -824,7 +3006,9 @@ pub mod commands {
         CommandText::new(
             format!(
                 "Details [{}]",
-                key_config.get_hint(key_config.enter),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().enter),
             ),
             "open details of selected commit",
             CMD_GROUP_LOG,
@@ -836,7 +3020,9 @@ pub mod commands {
         CommandText::new(
             format!(
                 "Inspect [{}]",
-                key_config.get_hint(key_config.focus_right),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().focus_right),
             ),
             "inspect selected commit in detail",
             CMD_GROUP_LOG,
@@ -846,19 +3032,37 @@ pub mod commands {
         CommandText::new(
             format!(
                 "Blame [{}]",
-                key_config.get_hint(key_config.blame),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().blame),
             ),
             "open blame view of selected file",
             CMD_GROUP_LOG,
         )
     }
+    pub fn blame_copy_detection(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Copy detection [{}]",
+                key_config.borrow().get_hint(
+                    key_config.borrow().blame_copy_detection
+                ),
+            ),
+            "cycle how hard blame looks for moved or copied lines",
+            CMD_GROUP_LOG,
+        )
+    }
     pub fn log_tag_commit(
         key_config: &SharedKeyConfig,
     ) -> CommandText {
         CommandText::new(
             format!(
                 "Tag [{}]",
-                key_config.get_hint(key_config.log_tag_commit),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().log_tag_commit),
             ),
             "tag commit",
             CMD_GROUP_LOG,
@@ -870,19 +3074,63 @@ pub mod commands {
         CommandText::new(
             format!(
                 "Files [{}]",
-                key_config.get_hint(key_config.open_file_tree),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().open_file_tree),
             ),
             "inspect file tree at specific revision",
             CMD_GROUP_LOG,
         )
     }
+    pub fn inspect_archive(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Archive [{}]",
+                key_config.borrow().get_hint(key_config.borrow().inspect_archive),
+            ),
+            "save the files of the commit as tar, tar.gz or zip archive",
+            CMD_GROUP_LOG,
+        )
+    }
+    pub fn archive_commit_confirm(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Save [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().enter),
+            ),
+            "write the archive to the file",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn show_file_content(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Show [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().enter),
+            ),
+            "show content of selected file",
+            CMD_GROUP_LOG,
+        )
+    }
     pub fn tag_commit_confirm_msg(
         key_config: &SharedKeyConfig,
     ) -> CommandText {
         CommandText::new(
             format!(
                 "Tag [{}]",
-                key_config.get_hint(key_config.enter),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().enter),
             ),
             "tag commit",
             CMD_GROUP_LOG,
@@ -894,7 +3142,9 @@ pub mod commands {
         CommandText::new(
             format!(
                 "Create Branch [{}]",
-                key_config.get_hint(key_config.enter),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().enter),
             ),
             "create branch",
             CMD_GROUP_GENERAL,
@@ -906,7 +3156,9 @@ pub mod commands {
         CommandText::new(
             format!(
                 "Create [{}]",
-                key_config.get_hint(key_config.create_branch),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().create_branch),
             ),
             "open create branch popup",
             CMD_GROUP_GENERAL,
@@ -918,7 +3170,9 @@ pub mod commands {
         CommandText::new(
             format!(
                 "Rename Branch [{}]",
-                key_config.get_hint(key_config.enter),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().enter),
             ),
             "rename branch",
             CMD_GROUP_GENERAL,
@@ -930,19 +3184,51 @@ pub mod commands {
         CommandText::new(
             format!(
                 "Rename Branch [{}]",
-                key_config.get_hint(key_config.rename_branch),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().rename_branch),
             ),
             "rename branch",
             CMD_GROUP_GENERAL,
         )
     }
+    pub fn set_upstream_confirm_msg(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Set Upstream [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().enter),
+            ),
+            "set upstream (empty removes it)",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn set_upstream_popup(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Upstream [{}]",
+                key_config.borrow().get_hint(
+                    key_config.borrow().branch_set_upstream
+                ),
+            ),
+            "set or remove the upstream of the branch",
+            CMD_GROUP_GENERAL,
+        )
+    }
     pub fn delete_branch_popup(
         key_config: &SharedKeyConfig,
     ) -> CommandText {
         CommandText::new(
             format!(
                 "Delete [{}]",
-                key_config.get_hint(key_config.delete_branch),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().delete_branch),
             ),
             "delete a branch",
             CMD_GROUP_GENERAL,
@@ -950,13 +3236,79 @@ pub mod commands {
     }
     pub fn merge_branch_popup(
         key_config: &SharedKeyConfig,
+        fast_forward: MergeFastForward,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Merge {} [{}]",
+                super::merge_fast_forward(fast_forward),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().merge_branch),
+            ),
+            "merge the selected branch into the current one",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn toggle_path_scope(
+        key_config: &SharedKeyConfig,
+        active: bool,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Path Scope: {} [{}]",
+                if active { "on" } else { "off" },
+                key_config.borrow().get_hint(key_config.borrow().toggle_path_scope),
+            ),
+            "switch between the --path-scope folder and the whole repository in the status and log",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn flow_start(key_config: &SharedKeyConfig) -> CommandText {
+        CommandText::new(
+            format!(
+                "Start [{}]",
+                key_config.borrow().get_hint(key_config.borrow().flow_start),
+            ),
+            "start a feature, release or hotfix (or short-lived) branch of the configured workflow",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn flow_finish(key_config: &SharedKeyConfig) -> CommandText {
+        CommandText::new(
+            format!(
+                "Finish [{}]",
+                key_config.borrow().get_hint(key_config.borrow().flow_finish),
+            ),
+            "merge the selected workflow branch where it belongs and delete it",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn flow_branch_kind(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Kind [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().tab_toggle),
+            ),
+            "switch between feature, release and hotfix",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn merge_ff_mode(
+        key_config: &SharedKeyConfig,
+        fast_forward: MergeFastForward,
     ) -> CommandText {
         CommandText::new(
             format!(
-                "Merge [{}]",
-                key_config.get_hint(key_config.merge_branch),
+                "Merge mode: {} [{}]",
+                super::merge_fast_forward(fast_forward),
+                key_config.borrow().get_hint(key_config.borrow().merge_ff_mode),
             ),
-            "merge a branch",
+            "cycle between fast forwarding if possible, always creating a merge commit and only fast forwarding",
             CMD_GROUP_GENERAL,
         )
     }
@@ -966,12 +3318,68 @@ pub mod commands {
         CommandText::new(
             format!(
                 "Checkout [{}]",
-                key_config.get_hint(key_config.enter),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().enter),
             ),
             "checkout branch",
             CMD_GROUP_GENERAL,
         )
     }
+    pub fn fetch_remote_branches(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Fetch [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().pull),
+            ),
+            "fetch all branches of the remote",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn branch_show_log(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Log [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().branch_show_log),
+            ),
+            "show the log of the branch without checking it out",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn branch_file_tree(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Files [{}]",
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().open_file_tree),
+            ),
+            "browse the files of the branch without checking it out",
+            CMD_GROUP_GENERAL,
+        )
+    }
+    pub fn branch_review(
+        key_config: &SharedKeyConfig,
+    ) -> CommandText {
+        CommandText::new(
+            format!(
+                "Review [{}]",
+                key_config.borrow().get_hint(key_config.borrow().open_review),
+            ),
+            "review the changes of the branch against the current one",
+            CMD_GROUP_GENERAL,
+        )
+    }
     pub fn toggle_branch_popup(
         key_config: &SharedKeyConfig,
         local: bool,
@@ -980,7 +3388,9 @@ pub mod commands {
             format!(
                 "{} [{}]",
                 if local { "Remote" } else { "Local" },
-                key_config.get_hint(key_config.tab_toggle),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().tab_toggle),
             ),
             "toggle branch type (remote/local)",
             CMD_GROUP_GENERAL,
@@ -992,7 +3402,9 @@ pub mod commands {
         CommandText::new(
             format!(
                 "Branches [{}]",
-                key_config.get_hint(key_config.select_branch),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().select_branch),
             ),
             "open select branch popup",
             CMD_GROUP_GENERAL,
@@ -1003,7 +3415,9 @@ pub mod commands {
         CommandText::new(
             format!(
                 "Push [{}]",
-                key_config.get_hint(key_config.push),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().push),
             ),
             "push to origin",
             CMD_GROUP_GENERAL,
@@ -1015,7 +3429,9 @@ pub mod commands {
         CommandText::new(
             format!(
                 "Force Push [{}]",
-                key_config.get_hint(key_config.force_push),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().force_push),
             ),
             "force push to origin",
             CMD_GROUP_GENERAL,
@@ -1025,7 +3441,9 @@ pub mod commands {
         CommandText::new(
             format!(
                 "Pull [{}]",
-                key_config.get_hint(key_config.pull),
+                key_config
+                    .borrow()
+                    .get_hint(key_config.borrow().pull),
             ),
             "fetch/merge",
             CMD_GROUP_GENERAL,
@@ -1,22 +1,23 @@
 use crate::{
     components::{
-        visibility_blocking, CommandBlocking, CommandInfo,
-        CommitDetailsComponent, CommitList, Component,
+        open_context_menu, visibility_blocking, CommandBlocking,
+        CommandInfo, CommitDetailsComponent, CommitList, Component,
         DrawableComponent, EventState,
     },
     keys::SharedKeyConfig,
-    queue::{InternalEvent, Queue},
-    strings,
-    ui::style::SharedTheme,
+    options::SharedOptions,
+    queue::{Action, InternalEvent, NeedsUpdate, Queue},
+    strings, try_or_popup,
+    ui::{self, style::SharedTheme},
 };
 use anyhow::Result;
 use asyncgit::{
     cached,
-    sync::{self, CommitId},
-    AsyncLog, AsyncNotification, AsyncTags, FetchStatus, CWD,
+    sync::{self, CommitId, LogFilter, RepoPath, SquashType},
+    AsyncLog, AsyncNotification, AsyncTags, FetchStatus,
 };
 use crossbeam_channel::Sender;
-use crossterm::event::Event;
+use crossterm::event::{Event, KeyEvent};
 use std::time::Duration;
 use sync::CommitTags;
 use tui::{
@@ -29,6 +30,7 @@ const SLICE_SIZE: usize = 1200;
 
 ///
 pub struct Revlog {
+    repo: RepoPath,
     commit_details: CommitDetailsComponent,
     list: CommitList,
     git_log: AsyncLog,
@@ -36,20 +38,36 @@ pub struct Revlog {
     queue: Queue,
     visible: bool,
     branch_name: cached::BranchName,
+    head_detached: bool,
+    goto_target: Option<(CommitId, String)>,
+    /// what the user typed to filter the log, empty for all commits
+    filter_query: String,
+    /// what it was read to, without the path scope
+    filter: LogFilter,
     key_config: SharedKeyConfig,
+    options: SharedOptions,
 }
 
 impl Revlog {
     ///
     pub fn new(
+        repo: RepoPath,
         queue: &Queue,
         sender: &Sender<AsyncNotification>,
         theme: SharedTheme,
         key_config: SharedKeyConfig,
+        options: SharedOptions,
     ) -> Self {
+        let mut git_log = AsyncLog::new(repo.clone(), sender);
+        git_log.set_filter(LogFilter {
+            path: options.borrow().path_scope().map(String::from),
+            ..LogFilter::default()
+        });
+
         Self {
             queue: queue.clone(),
             commit_details: CommitDetailsComponent::new(
+                repo.clone(),
                 queue,
                 sender,
                 theme.clone(),
@@ -60,11 +78,17 @@ impl Revlog {
                 theme,
                 key_config.clone(),
             ),
-            git_log: AsyncLog::new(sender),
-            git_tags: AsyncTags::new(sender),
+            git_log,
+            git_tags: AsyncTags::new(repo.clone(), sender),
             visible: false,
-            branch_name: cached::BranchName::new(CWD),
+            branch_name: cached::BranchName::new(repo.clone()),
+            head_detached: false,
+            goto_target: None,
+            filter_query: String::new(),
+            filter: LogFilter::default(),
             key_config,
+            options,
+            repo,
         }
     }
 
@@ -93,9 +117,14 @@ impl Revlog {
 
             self.git_tags.request(Duration::from_secs(3), false)?;
 
-            self.list.set_branch(
-                self.branch_name.lookup().map(Some).unwrap_or(None),
-            );
+            self.head_detached = sync::is_head_detached(&self.repo)
+                .unwrap_or_default();
+
+            self.list.set_branch(if self.head_detached {
+                Some(strings::detached_head())
+            } else {
+                self.branch_name.lookup().map(Some).unwrap_or(None)
+            });
 
             if self.commit_details.is_visible() {
                 let commit = self.selected_commit();
@@ -115,11 +144,19 @@ impl Revlog {
     ) -> Result<()> {
         if self.visible {
             match ev {
-                AsyncNotification::CommitFiles
-                | AsyncNotification::Log => self.update()?,
+                AsyncNotification::CommitFiles => self.update()?,
+                AsyncNotification::Log => {
+                    self.goto_reached()?;
+                    self.update()?;
+                }
                 AsyncNotification::Tags => {
                     if let Some(tags) = self.git_tags.last()? {
                         self.list.set_tags(tags);
+                        if let Some(decorations) =
+                            self.git_tags.last_decorations()?
+                        {
+                            self.list.set_decorations(decorations);
+                        }
                         self.update()?;
                     }
                 }
@@ -130,12 +167,82 @@ impl Revlog {
         Ok(())
     }
 
+    /// selects the commit `rev` resolved to, right away if the log
+    /// walk already got to it, otherwise once it does
+    pub fn goto_commit(
+        &mut self,
+        id: CommitId,
+        rev: String,
+    ) -> Result<()> {
+        self.goto_target = Some((id, rev));
+        self.goto_reached()?;
+        self.update()
+    }
+
+    ///
+    pub fn filter_query(&self) -> &str {
+        &self.filter_query
+    }
+
+    /// walks the log again showing only the commits matching
+    /// `filter`, `query` is what it was read from
+    pub fn set_filter(
+        &mut self,
+        query: String,
+        filter: LogFilter,
+    ) -> Result<()> {
+        self.list.set_title(if filter.is_empty() {
+            strings::log_title(&self.key_config)
+        } else {
+            strings::log_title_filtered(&self.key_config, &query)
+        });
+        self.filter_query = query;
+        self.filter = filter;
+
+        self.path_scope_changed()
+    }
+
+    /// walks the log again restricted to the path scope, a path of
+    /// the filter has precedence
+    pub fn path_scope_changed(&mut self) -> Result<()> {
+        let mut filter = self.filter.clone();
+        if filter.path.is_none() {
+            filter.path =
+                self.options.borrow().path_scope().map(String::from);
+        }
+
+        self.git_log.set_filter(filter);
+        self.list.clear();
+        self.list.select_entry(0);
+
+        self.update()
+    }
+
+    fn goto_reached(&mut self) -> Result<()> {
+        if let Some((id, rev)) = &self.goto_target {
+            if let Some(position) = self.git_log.position(*id)? {
+                self.goto_target = None;
+                self.list.set_count_total(self.git_log.count()?);
+                self.list.select_entry(position);
+            } else if !self.git_log.is_pending() {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::ShowErrorMsg(
+                        strings::goto_commit_not_in_log(rev),
+                    ),
+                );
+                self.goto_target = None;
+            }
+        }
+
+        Ok(())
+    }
+
     fn fetch_commits(&mut self) -> Result<()> {
         let want_min =
             self.list.selection().saturating_sub(SLICE_SIZE / 2);
 
         let commits = sync::get_commits_info(
-            CWD,
+            &self.repo,
             &self.git_log.get_slice(want_min, SLICE_SIZE)?,
             self.list.current_size().0.into(),
         );
@@ -151,11 +258,98 @@ impl Revlog {
         self.list.selected_entry().map(|e| e.id)
     }
 
+    fn checkout_commit(&self, id: CommitId) {
+        try_or_popup!(
+            self,
+            "checkout commit error:",
+            sync::autostash(
+                &self.repo,
+                self.options.borrow().autostash(),
+                || sync::checkout_commit(&self.repo, id)
+            )
+        );
+
+        self.queue
+            .borrow_mut()
+            .push_back(InternalEvent::Update(NeedsUpdate::ALL));
+    }
+
+    fn squash_marked(&mut self, kind: SquashType) {
+        match sync::squash_commits(
+            &self.repo,
+            self.list.marked(),
+            kind,
+        ) {
+            Ok(_) => {
+                self.list.clear_marked();
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::Update(NeedsUpdate::ALL),
+                );
+            }
+            Err(e) => {
+                self.queue.borrow_mut().push_back(
+                    InternalEvent::ShowErrorMsg(format!(
+                        "squash error:\n{}",
+                        e
+                    )),
+                );
+            }
+        }
+    }
+
+    /// events of the keys acting on the selected commit
+    fn selected_commit_event(
+        &self,
+        k: KeyEvent,
+        id: CommitId,
+    ) -> Option<InternalEvent> {
+        if k == self.key_config.borrow().log_tag_commit {
+            Some(InternalEvent::TagCommit(id))
+        } else if k == self.key_config.borrow().create_branch {
+            Some(InternalEvent::CreateBranchAt(id))
+        } else if k == self.key_config.borrow().log_reset_commit {
+            Some(InternalEvent::ResetCommit(id))
+        } else if k == self.key_config.borrow().open_file_tree {
+            Some(InternalEvent::OpenFileTree(id))
+        } else if k == self.key_config.borrow().log_export_patch {
+            Some(InternalEvent::ExportPatch(id))
+        } else if k == self.key_config.borrow().log_tags {
+            Some(InternalEvent::OpenTags(id))
+        } else {
+            None
+        }
+    }
+
+    fn toggle_replace_refs(&mut self) -> Result<()> {
+        let enabled = sync::use_replace_refs(&self.repo)?;
+        sync::set_use_replace_refs(&self.repo, !enabled)?;
+
+        self.git_log.invalidate();
+        self.update()
+    }
+
+    fn can_squash(&self) -> bool {
+        self.list.marked().len() > 1
+    }
+
     fn copy_commit_hash(&self) -> Result<()> {
         self.list.copy_entry_hash()?;
         Ok(())
     }
 
+    fn copy_commit_message(&self) -> Result<()> {
+        if let Some(id) = self.selected_commit() {
+            let message = sync::get_commit_details(&self.repo, id)?
+                .message
+                .map(sync::CommitMessage::combine)
+                .unwrap_or_default();
+
+            crate::clipboard::copy_string(&message)?;
+        }
+
+        Ok(())
+    }
+
     fn selected_commit_tags(
         &self,
         commit: &Option<CommitId>,
@@ -166,6 +360,164 @@ impl Revlog {
             tags.and_then(|tags| tags.get(&commit).cloned())
         })
     }
+
+    /// commands changing HEAD or rewriting the history
+    fn commands_history(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) {
+        out.push(
+            CommandInfo::new(
+                strings::commands::undo_commit(&self.key_config),
+                true,
+                self.visible || force_all,
+            )
+            .key(self.key_config.borrow().undo_commit),
+        );
+
+        out.push(
+            CommandInfo::new(
+                strings::commands::log_reset_commit(&self.key_config),
+                self.selected_commit().is_some(),
+                self.visible || force_all,
+            )
+            .key(self.key_config.borrow().log_reset_commit),
+        );
+
+        out.push(
+            CommandInfo::new(
+                strings::commands::log_checkout_commit(
+                    &self.key_config,
+                ),
+                self.selected_commit().is_some(),
+                self.visible || force_all,
+            )
+            .key(self.key_config.borrow().log_checkout_commit),
+        );
+
+        out.push(
+            CommandInfo::new(
+                strings::commands::log_create_branch(
+                    &self.key_config,
+                ),
+                self.selected_commit().is_some(),
+                self.visible || force_all,
+            )
+            .key(self.key_config.borrow().create_branch),
+        );
+
+        out.push(
+            CommandInfo::new(
+                strings::commands::log_mark_commit(&self.key_config),
+                self.selected_commit().is_some(),
+                self.visible || force_all,
+            )
+            .key(self.key_config.borrow().log_mark_commit),
+        );
+
+        out.push(CommandInfo::new(
+            strings::commands::log_squash_commits(&self.key_config),
+            self.can_squash(),
+            self.visible || force_all,
+        ));
+
+        out.push(CommandInfo::new(
+            strings::commands::log_fixup_commits(&self.key_config),
+            self.can_squash(),
+            self.visible || force_all,
+        ));
+    }
+
+    /// commands on how the list shows commits, and the remaining
+    /// commands on the selected commit
+    fn commands_list(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) {
+        out.push(CommandInfo::new(
+            strings::commands::log_toggle_replace_refs(
+                &self.key_config,
+            ),
+            true,
+            self.visible || force_all,
+        ));
+
+        out.push(CommandInfo::new(
+            strings::commands::log_toggle_decorations(
+                &self.key_config,
+                self.list.decorations_shown(),
+            ),
+            true,
+            self.visible || force_all,
+        ));
+
+        out.push(CommandInfo::new(
+            strings::commands::log_export_patch(&self.key_config),
+            self.selected_commit().is_some(),
+            self.visible || force_all,
+        ));
+
+        out.push(CommandInfo::new(
+            strings::commands::log_filter(&self.key_config),
+            true,
+            self.visible || force_all,
+        ));
+
+        out.push(CommandInfo::new(
+            strings::commands::log_goto_commit(&self.key_config),
+            true,
+            self.visible || force_all,
+        ));
+
+        out.push(CommandInfo::new(
+            strings::commands::open_context_menu(&self.key_config),
+            self.selected_commit().is_some(),
+            self.visible || force_all,
+        ));
+    }
+
+    /// keys marking and squashing commits or changing what the list
+    /// shows, `false` if `k` is none of them
+    fn list_event(&mut self, k: KeyEvent) -> bool {
+        if k == self.key_config.borrow().log_mark_commit {
+            self.list.toggle_mark_selected();
+        } else if k == self.key_config.borrow().log_squash_commits
+            && self.can_squash()
+        {
+            self.squash_marked(SquashType::Squash);
+        } else if k == self.key_config.borrow().log_fixup_commits
+            && self.can_squash()
+        {
+            self.squash_marked(SquashType::Fixup);
+        } else if k
+            == self.key_config.borrow().log_toggle_replace_refs
+        {
+            try_or_popup!(
+                self,
+                "replace refs error:",
+                self.toggle_replace_refs()
+            );
+        } else if k == self.key_config.borrow().log_toggle_decorations
+        {
+            self.list.toggle_decorations();
+        } else if k == self.key_config.borrow().log_filter {
+            self.queue
+                .borrow_mut()
+                .push_back(InternalEvent::OpenLogFilter);
+        } else if k == self.key_config.borrow().log_goto_commit {
+            self.queue
+                .borrow_mut()
+                .push_back(InternalEvent::OpenGotoCommit);
+        } else if k == self.key_config.borrow().open_context_menu {
+            open_context_menu(self, &self.queue);
+        } else {
+            return false;
+        }
+
+        true
+    }
 }
 
 impl DrawableComponent for Revlog {
@@ -174,12 +526,13 @@ impl DrawableComponent for Revlog {
         f: &mut Frame<B>,
         area: Rect,
     ) -> Result<()> {
+        let split = self.options.borrow().revlog_split();
         let chunks = Layout::default()
             .direction(Direction::Horizontal)
             .constraints(
                 [
-                    Constraint::Percentage(60),
-                    Constraint::Percentage(40),
+                    Constraint::Percentage(split),
+                    Constraint::Percentage(100 - split),
                 ]
                 .as_ref(),
             )
@@ -205,29 +558,44 @@ impl Component for Revlog {
                 self.update()?;
                 return Ok(EventState::Consumed);
             } else if let Event::Key(k) = ev {
-                if k == self.key_config.enter {
+                if k == self.key_config.borrow().enter {
                     self.commit_details.toggle_visible()?;
                     self.update()?;
                     return Ok(EventState::Consumed);
-                } else if k == self.key_config.copy {
+                } else if (k == self.key_config.borrow().split_left
+                    || k == self.key_config.borrow().split_right)
+                    && self.commit_details.is_visible()
+                {
+                    let split = ui::resize_split(
+                        self.options.borrow().revlog_split(),
+                        k == self.key_config.borrow().split_right,
+                    );
+                    self.options.borrow_mut().set_revlog_split(split);
+                    return Ok(EventState::Consumed);
+                } else if k == self.key_config.borrow().copy {
                     self.copy_commit_hash()?;
                     return Ok(EventState::Consumed);
-                } else if k == self.key_config.push {
-                    self.queue
-                        .borrow_mut()
-                        .push_back(InternalEvent::PushTags);
+                } else if k
+                    == self.key_config.borrow().copy_commit_message
+                {
+                    try_or_popup!(
+                        self,
+                        "copy to clipboard error:",
+                        self.copy_commit_message()
+                    );
                     return Ok(EventState::Consumed);
-                } else if k == self.key_config.log_tag_commit {
-                    return self.selected_commit().map_or(
-                        Ok(EventState::NotConsumed),
-                        |id| {
-                            self.queue.borrow_mut().push_back(
-                                InternalEvent::TagCommit(id),
-                            );
-                            Ok(EventState::Consumed)
-                        },
+                } else if k == self.key_config.borrow().push {
+                    self.queue.borrow_mut().push_back(
+                        InternalEvent::PushTags(Vec::new(), false),
                     );
-                } else if k == self.key_config.focus_right
+                    return Ok(EventState::Consumed);
+                } else if let Some(ev) = self
+                    .selected_commit()
+                    .and_then(|id| self.selected_commit_event(k, id))
+                {
+                    self.queue.borrow_mut().push_back(ev);
+                    return Ok(EventState::Consumed);
+                } else if k == self.key_config.borrow().focus_right
                     && self.commit_details.is_visible()
                 {
                     return self.selected_commit().map_or(
@@ -244,21 +612,31 @@ impl Component for Revlog {
                             Ok(EventState::Consumed)
                         },
                     );
-                } else if k == self.key_config.select_branch {
-                    self.queue
-                        .borrow_mut()
-                        .push_back(InternalEvent::SelectBranch);
+                } else if k == self.key_config.borrow().undo_commit {
+                    self.queue.borrow_mut().push_back(
+                        InternalEvent::ConfirmAction(
+                            Action::UndoCommit,
+                        ),
+                    );
                     return Ok(EventState::Consumed);
-                } else if k == self.key_config.open_file_tree {
+                } else if k
+                    == self.key_config.borrow().log_checkout_commit
+                {
                     return self.selected_commit().map_or(
                         Ok(EventState::NotConsumed),
                         |id| {
-                            self.queue.borrow_mut().push_back(
-                                InternalEvent::OpenFileTree(id),
-                            );
+                            self.checkout_commit(id);
                             Ok(EventState::Consumed)
                         },
                     );
+                } else if k == self.key_config.borrow().select_branch
+                {
+                    self.queue
+                        .borrow_mut()
+                        .push_back(InternalEvent::SelectBranch);
+                    return Ok(EventState::Consumed);
+                } else if self.list_event(k) {
+                    return Ok(EventState::Consumed);
                 }
             }
         }
@@ -275,24 +653,42 @@ impl Component for Revlog {
             self.list.commands(out, force_all);
         }
 
-        out.push(CommandInfo::new(
-            strings::commands::log_details_toggle(&self.key_config),
-            true,
-            self.visible,
-        ));
+        out.push(
+            CommandInfo::new(
+                strings::commands::log_details_toggle(
+                    &self.key_config,
+                ),
+                true,
+                self.visible,
+            )
+            .key(self.key_config.borrow().enter),
+        );
+
+        out.push(
+            CommandInfo::new(
+                strings::commands::log_details_open(&self.key_config),
+                true,
+                (self.visible && self.commit_details.is_visible())
+                    || force_all,
+            )
+            .key(self.key_config.borrow().focus_right),
+        );
 
         out.push(CommandInfo::new(
-            strings::commands::log_details_open(&self.key_config),
+            strings::commands::resize_panes(&self.key_config, false),
             true,
             (self.visible && self.commit_details.is_visible())
                 || force_all,
         ));
 
-        out.push(CommandInfo::new(
-            strings::commands::log_tag_commit(&self.key_config),
-            self.selected_commit().is_some(),
-            self.visible || force_all,
-        ));
+        out.push(
+            CommandInfo::new(
+                strings::commands::log_tag_commit(&self.key_config),
+                self.selected_commit().is_some(),
+                self.visible || force_all,
+            )
+            .key(self.key_config.borrow().log_tag_commit),
+        );
 
         out.push(CommandInfo::new(
             strings::commands::open_branch_select_popup(
@@ -302,8 +698,17 @@ impl Component for Revlog {
             self.visible || force_all,
         ));
 
+        out.push(
+            CommandInfo::new(
+                strings::commands::copy_hash(&self.key_config),
+                self.selected_commit().is_some(),
+                self.visible || force_all,
+            )
+            .key(self.key_config.borrow().copy),
+        );
+
         out.push(CommandInfo::new(
-            strings::commands::copy_hash(&self.key_config),
+            strings::commands::copy_commit_message(&self.key_config),
             self.selected_commit().is_some(),
             self.visible || force_all,
         ));
@@ -314,11 +719,28 @@ impl Component for Revlog {
             self.visible || force_all,
         ));
 
-        out.push(CommandInfo::new(
-            strings::commands::inspect_file_tree(&self.key_config),
-            self.selected_commit().is_some(),
-            self.visible || force_all,
-        ));
+        out.push(
+            CommandInfo::new(
+                strings::commands::log_tags(&self.key_config),
+                self.selected_commit().is_some(),
+                self.visible || force_all,
+            )
+            .key(self.key_config.borrow().log_tags),
+        );
+
+        out.push(
+            CommandInfo::new(
+                strings::commands::inspect_file_tree(
+                    &self.key_config,
+                ),
+                self.selected_commit().is_some(),
+                self.visible || force_all,
+            )
+            .key(self.key_config.borrow().open_file_tree),
+        );
+
+        self.commands_history(out, force_all);
+        self.commands_list(out, force_all);
 
         visibility_blocking(self)
     }
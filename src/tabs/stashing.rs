@@ -12,8 +12,8 @@ use crate::{
 };
 use anyhow::Result;
 use asyncgit::{
-    sync::status::StatusType, AsyncNotification, AsyncStatus,
-    StatusParams,
+    sync::{status::StatusType, RepoPath},
+    AsyncNotification, AsyncStatus, StatusParams,
 };
 use crossbeam_channel::Sender;
 use crossterm::event::Event;
@@ -24,10 +24,14 @@ use tui::{
     widgets::{Block, Borders, Paragraph},
 };
 
-#[derive(Default, Clone, Copy, Debug)]
+#[derive(Default, Clone, Debug)]
 pub struct StashingOptions {
     pub stash_untracked: bool,
+    /// files ignored by `.gitignore`, like `git stash --all`
+    pub stash_ignored: bool,
     pub keep_index: bool,
+    /// only these paths get stashed, everything if empty
+    pub paths: Vec<String>,
 }
 
 pub struct Stashing {
@@ -45,26 +49,35 @@ impl Stashing {
 
     ///
     pub fn new(
+        repo: &RepoPath,
         sender: &Sender<AsyncNotification>,
         queue: &Queue,
         theme: SharedTheme,
         key_config: SharedKeyConfig,
     ) -> Self {
+        let mut index = FileTreeComponent::new(
+            &strings::stashing_files_title(&key_config),
+            true,
+            Some(queue.clone()),
+            theme.clone(),
+            key_config.clone(),
+        );
+        index.enable_marking();
+
         Self {
-            index: FileTreeComponent::new(
-                &strings::stashing_files_title(&key_config),
-                true,
-                Some(queue.clone()),
-                theme.clone(),
-                key_config.clone(),
-            ),
+            index,
             visible: false,
             options: StashingOptions {
                 keep_index: false,
                 stash_untracked: true,
+                stash_ignored: false,
+                paths: Vec::new(),
             },
             theme,
-            git_status: AsyncStatus::new(sender.clone()),
+            git_status: AsyncStatus::new(
+                repo.clone(),
+                sender.clone(),
+            ),
             queue: queue.clone(),
             key_config,
         }
@@ -105,11 +118,15 @@ impl Stashing {
     fn get_option_text(&self) -> Vec<Spans> {
         let bracket_open = Span::raw(Cow::from("["));
         let bracket_close = Span::raw(Cow::from("]"));
-        let option_on =
-            Span::styled(Cow::from("x"), self.theme.option(true));
+        let option_on = Span::styled(
+            Cow::from("x"),
+            self.theme.borrow().option(true),
+        );
 
-        let option_off =
-            Span::styled(Cow::from("_"), self.theme.option(false));
+        let option_off = Span::styled(
+            Cow::from("_"),
+            self.theme.borrow().option(false),
+        );
 
         vec![
             Spans::from(vec![
@@ -122,6 +139,16 @@ impl Stashing {
                 bracket_close.clone(),
                 Span::raw(Cow::from(" stash untracked")),
             ]),
+            Spans::from(vec![
+                bracket_open.clone(),
+                if self.options.stash_ignored {
+                    option_on.clone()
+                } else {
+                    option_off.clone()
+                },
+                bracket_close.clone(),
+                Span::raw(Cow::from(" stash ignored")),
+            ]),
             Spans::from(vec![
                 bracket_open,
                 if self.options.keep_index {
@@ -152,7 +179,7 @@ impl DrawableComponent for Stashing {
         let right_chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints(
-                [Constraint::Length(4), Constraint::Min(1)].as_ref(),
+                [Constraint::Length(5), Constraint::Min(1)].as_ref(),
             )
             .split(chunks[1]);
 
@@ -203,6 +230,13 @@ impl Component for Stashing {
                 self.visible,
                 self.visible || force_all,
             ));
+            out.push(CommandInfo::new(
+                strings::commands::stashing_toggle_ignored(
+                    &self.key_config,
+                ),
+                self.visible,
+                self.visible || force_all,
+            ));
         }
 
         visibility_blocking(self)
@@ -220,26 +254,45 @@ impl Component for Stashing {
             }
 
             if let Event::Key(k) = ev {
-                return if k == self.key_config.stashing_save
+                return if k == self.key_config.borrow().stashing_save
                     && !self.index.is_empty()
                 {
                     self.queue.borrow_mut().push_back(
-                        InternalEvent::PopupStashing(self.options),
+                        InternalEvent::PopupStashing(
+                            StashingOptions {
+                                paths: self.index.marked(),
+                                ..self.options.clone()
+                            },
+                        ),
                     );
 
                     Ok(EventState::Consumed)
-                } else if k == self.key_config.stashing_toggle_index {
+                } else if k
+                    == self.key_config.borrow().stashing_toggle_index
+                {
                     self.options.keep_index =
                         !self.options.keep_index;
                     self.update()?;
                     Ok(EventState::Consumed)
                 } else if k
-                    == self.key_config.stashing_toggle_untracked
+                    == self
+                        .key_config
+                        .borrow()
+                        .stashing_toggle_untracked
                 {
                     self.options.stash_untracked =
                         !self.options.stash_untracked;
                     self.update()?;
                     Ok(EventState::Consumed)
+                } else if k
+                    == self
+                        .key_config
+                        .borrow()
+                        .stashing_toggle_ignored
+                {
+                    self.options.stash_ignored =
+                        !self.options.stash_ignored;
+                    Ok(EventState::Consumed)
                 } else {
                     Ok(EventState::NotConsumed)
                 };
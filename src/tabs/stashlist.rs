@@ -1,7 +1,8 @@
 use crate::{
     components::{
-        visibility_blocking, CommandBlocking, CommandInfo,
-        CommitList, Component, DrawableComponent, EventState,
+        open_context_menu, visibility_blocking, CommandBlocking,
+        CommandInfo, CommitList, Component, DrawableComponent,
+        EventState,
     },
     keys::SharedKeyConfig,
     queue::{Action, InternalEvent, Queue},
@@ -9,13 +10,11 @@ use crate::{
     ui::style::SharedTheme,
 };
 use anyhow::Result;
-use asyncgit::{
-    sync::{self, CommitId},
-    CWD,
-};
+use asyncgit::sync::{self, CommitId, RepoPath};
 use crossterm::event::Event;
 
 pub struct StashList {
+    repo: RepoPath,
     list: CommitList,
     visible: bool,
     queue: Queue,
@@ -25,6 +24,7 @@ pub struct StashList {
 impl StashList {
     ///
     pub fn new(
+        repo: RepoPath,
         queue: &Queue,
         theme: SharedTheme,
         key_config: SharedKeyConfig,
@@ -38,15 +38,19 @@ impl StashList {
             ),
             queue: queue.clone(),
             key_config,
+            repo,
         }
     }
 
     ///
     pub fn update(&mut self) -> Result<()> {
         if self.is_visible() {
-            let stashes = sync::get_stashes(CWD)?;
-            let commits =
-                sync::get_commits_info(CWD, stashes.as_slice(), 100)?;
+            let stashes = sync::get_stashes(&self.repo)?;
+            let commits = sync::get_commits_info(
+                &self.repo,
+                stashes.as_slice(),
+                100,
+            )?;
 
             self.list.set_count_total(commits.len());
             self.list.items().set_items(0, commits);
@@ -57,7 +61,7 @@ impl StashList {
 
     fn apply_stash(&mut self) {
         if let Some(e) = self.list.selected_entry() {
-            match sync::stash_apply(CWD, e.id, false) {
+            match sync::stash_apply(&self.repo, e.id, false) {
                 Ok(_) => {
                     self.queue
                         .borrow_mut()
@@ -91,6 +95,29 @@ impl StashList {
         }
     }
 
+    fn rename_stash(&mut self) {
+        if let Some(e) = self.list.selected_entry() {
+            // the part after `On <branch>: `
+            let message = e
+                .msg
+                .find(": ")
+                .map_or(e.msg.as_str(), |idx| &e.msg[idx + 2..])
+                .to_string();
+
+            self.queue
+                .borrow_mut()
+                .push_back(InternalEvent::RenameStash(e.id, message));
+        }
+    }
+
+    fn branch_from_stash(&mut self) {
+        if let Some(e) = self.list.selected_entry() {
+            self.queue
+                .borrow_mut()
+                .push_back(InternalEvent::BranchFromStash(e.id));
+        }
+    }
+
     fn inspect(&mut self) {
         if let Some(e) = self.list.selected_entry() {
             self.queue
@@ -102,18 +129,18 @@ impl StashList {
     /// Called when a pending stash action has been confirmed
     pub fn action_confirmed(&self, action: &Action) -> bool {
         match *action {
-            Action::StashDrop(id) => Self::drop(id),
+            Action::StashDrop(id) => self.drop(id),
             Action::StashPop(id) => self.pop(id),
             _ => false,
         }
     }
 
-    fn drop(id: CommitId) -> bool {
-        sync::stash_drop(CWD, id).is_ok()
+    fn drop(&self, id: CommitId) -> bool {
+        sync::stash_drop(&self.repo, id).is_ok()
     }
 
     fn pop(&self, id: CommitId) -> bool {
-        match sync::stash_pop(CWD, id) {
+        match sync::stash_pop(&self.repo, id) {
             Ok(_) => {
                 self.queue
                     .borrow_mut()
@@ -156,23 +183,68 @@ impl Component for StashList {
 
             let selection_valid =
                 self.list.selected_entry().is_some();
+            out.push(
+                CommandInfo::new(
+                    strings::commands::stashlist_pop(
+                        &self.key_config,
+                    ),
+                    selection_valid,
+                    true,
+                )
+                .key(self.key_config.borrow().enter),
+            );
+            out.push(
+                CommandInfo::new(
+                    strings::commands::stashlist_apply(
+                        &self.key_config,
+                    ),
+                    selection_valid,
+                    true,
+                )
+                .key(self.key_config.borrow().stash_apply),
+            );
+            out.push(
+                CommandInfo::new(
+                    strings::commands::stashlist_drop(
+                        &self.key_config,
+                    ),
+                    selection_valid,
+                    true,
+                )
+                .key(self.key_config.borrow().stash_drop),
+            );
+            out.push(
+                CommandInfo::new(
+                    strings::commands::stashlist_inspect(
+                        &self.key_config,
+                    ),
+                    selection_valid,
+                    true,
+                )
+                .key(self.key_config.borrow().stash_open),
+            );
+            out.push(
+                CommandInfo::new(
+                    strings::commands::stashlist_rename(
+                        &self.key_config,
+                    ),
+                    selection_valid,
+                    true,
+                )
+                .key(self.key_config.borrow().stash_rename),
+            );
+            out.push(
+                CommandInfo::new(
+                    strings::commands::stashlist_branch(
+                        &self.key_config,
+                    ),
+                    selection_valid,
+                    true,
+                )
+                .key(self.key_config.borrow().stash_branch),
+            );
             out.push(CommandInfo::new(
-                strings::commands::stashlist_pop(&self.key_config),
-                selection_valid,
-                true,
-            ));
-            out.push(CommandInfo::new(
-                strings::commands::stashlist_apply(&self.key_config),
-                selection_valid,
-                true,
-            ));
-            out.push(CommandInfo::new(
-                strings::commands::stashlist_drop(&self.key_config),
-                selection_valid,
-                true,
-            ));
-            out.push(CommandInfo::new(
-                strings::commands::stashlist_inspect(
+                strings::commands::open_context_menu(
                     &self.key_config,
                 ),
                 selection_valid,
@@ -193,14 +265,22 @@ impl Component for StashList {
             }
 
             if let Event::Key(k) = ev {
-                if k == self.key_config.enter {
+                if k == self.key_config.borrow().enter {
                     self.pop_stash()
-                } else if k == self.key_config.stash_apply {
+                } else if k == self.key_config.borrow().stash_apply {
                     self.apply_stash()
-                } else if k == self.key_config.stash_drop {
+                } else if k == self.key_config.borrow().stash_drop {
                     self.drop_stash()
-                } else if k == self.key_config.stash_open {
+                } else if k == self.key_config.borrow().stash_open {
                     self.inspect()
+                } else if k == self.key_config.borrow().stash_rename {
+                    self.rename_stash()
+                } else if k == self.key_config.borrow().stash_branch {
+                    self.branch_from_stash()
+                } else if k
+                    == self.key_config.borrow().open_context_menu
+                {
+                    open_context_menu(self, &self.queue);
                 } else {
                 }
             }
@@ -7,25 +7,33 @@ use crate::{
         FileTreeItemKind,
     },
     keys::SharedKeyConfig,
-    queue::{Action, InternalEvent, Queue, ResetItem},
+    options::SharedOptions,
+    queue::{Action, InternalEvent, NeedsUpdate, Queue, ResetItem},
     strings, try_or_popup,
-    ui::style::SharedTheme,
+    ui::{self, style::SharedTheme},
 };
 use anyhow::Result;
 use asyncgit::{
     cached,
-    sync::BranchCompare,
-    sync::{self, status::StatusType, RepoState},
+    sync::{
+        self,
+        status::{StatusItemType, StatusType},
+        CommitId, HookResult, RepoPath, RepoState,
+    },
+    sync::{BranchCompare, PushCompare},
     AsyncDiff, AsyncNotification, AsyncStatus, DiffParams, DiffType,
-    StatusParams, CWD,
+    StatusParams,
 };
 use crossbeam_channel::Sender;
-use crossterm::event::Event;
+use crossterm::event::{
+    Event, KeyEvent, MouseButton, MouseEvent, MouseEventKind,
+};
 use itertools::Itertools;
+use std::cell::Cell;
 use std::convert::Into;
 use std::convert::TryFrom;
 use tui::{
-    layout::{Alignment, Constraint, Direction, Layout},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Style},
     widgets::Paragraph,
 };
@@ -57,6 +65,7 @@ enum DiffTarget {
 }
 
 pub struct Status {
+    repo: RepoPath,
     visible: bool,
     focus: Focus,
     diff_target: DiffTarget,
@@ -67,10 +76,26 @@ pub struct Status {
     git_status_workdir: AsyncStatus,
     git_status_stage: AsyncStatus,
     git_branch_state: Option<BranchCompare>,
+    /// only set if pushes go to another remote than the upstream
+    git_push_state: Option<PushCompare>,
     git_branch_name: cached::BranchName,
+    head_detached: bool,
     queue: Queue,
     git_action_executed: bool,
+    /// ref (and the commit it resolved to) the workdir is compared
+    /// against instead of `HEAD`
+    diff_base: Option<(String, CommitId)>,
+    /// width of the file lists in percent while the border to the
+    /// diff is dragged with the mouse, saved once it is dropped
+    dragging_split: Option<u16>,
+    /// only the focused pane is shown
+    zoomed: bool,
+    /// the last status listed conflicted files
+    had_conflicts: bool,
+    /// workdir, stage and diff as drawn last, to map mouse clicks
+    areas: Cell<[Rect; 3]>,
     key_config: SharedKeyConfig,
+    options: SharedOptions,
 }
 
 impl DrawableComponent for Status {
@@ -79,47 +104,42 @@ impl DrawableComponent for Status {
         f: &mut tui::Frame<B>,
         rect: tui::layout::Rect,
     ) -> Result<()> {
+        if self.zoomed {
+            return self.draw_zoomed(f, rect);
+        }
+
+        let split =
+            self.dragging_split.unwrap_or_else(|| self.split());
         let chunks = Layout::default()
             .direction(Direction::Horizontal)
             .constraints(
-                if self.focus == Focus::Diff {
-                    [
-                        Constraint::Percentage(30),
-                        Constraint::Percentage(70),
-                    ]
-                } else {
-                    [
-                        Constraint::Percentage(50),
-                        Constraint::Percentage(50),
-                    ]
-                }
+                [
+                    Constraint::Percentage(split),
+                    Constraint::Percentage(100 - split),
+                ]
                 .as_ref(),
             )
             .split(rect);
 
+        let lists_split = self.lists_split();
         let left_chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints(
-                if self.diff_target == DiffTarget::WorkingDir {
-                    [
-                        Constraint::Percentage(60),
-                        Constraint::Percentage(40),
-                    ]
-                } else {
-                    [
-                        Constraint::Percentage(40),
-                        Constraint::Percentage(60),
-                    ]
-                }
+                [
+                    Constraint::Percentage(lists_split),
+                    Constraint::Percentage(100 - lists_split),
+                ]
                 .as_ref(),
             )
             .split(chunks[0]);
 
+        self.areas.set([left_chunks[0], left_chunks[1], chunks[1]]);
+
         self.index_wd.draw(f, left_chunks[0])?;
         self.index.draw(f, left_chunks[1])?;
         self.diff.draw(f, chunks[1])?;
         self.draw_branch_state(f, &left_chunks);
-        Self::draw_repo_state(f, left_chunks[0])?;
+        self.draw_repo_state(f, left_chunks[0])?;
 
         Ok(())
     }
@@ -130,10 +150,12 @@ impl Status {
 
     ///
     pub fn new(
+        repo: RepoPath,
         queue: &Queue,
         sender: &Sender<AsyncNotification>,
         theme: SharedTheme,
         key_config: SharedKeyConfig,
+        options: SharedOptions,
     ) -> Self {
         Self {
             queue: queue.clone(),
@@ -141,43 +163,142 @@ impl Status {
             focus: Focus::WorkDir,
             diff_target: DiffTarget::WorkingDir,
             index_wd: ChangesComponent::new(
+                repo.clone(),
                 &strings::title_status(&key_config),
                 true,
                 true,
                 queue.clone(),
                 theme.clone(),
                 key_config.clone(),
+                options.clone(),
             ),
             index: ChangesComponent::new(
+                repo.clone(),
                 &strings::title_index(&key_config),
                 false,
                 false,
                 queue.clone(),
                 theme.clone(),
                 key_config.clone(),
+                options.clone(),
             ),
             diff: DiffComponent::new(
+                repo.clone(),
                 queue.clone(),
+                sender,
                 theme,
                 key_config.clone(),
+                options.clone(),
                 false,
             ),
-            git_diff: AsyncDiff::new(sender),
-            git_status_workdir: AsyncStatus::new(sender.clone()),
-            git_status_stage: AsyncStatus::new(sender.clone()),
+            git_diff: AsyncDiff::new(repo.clone(), sender),
+            git_status_workdir: AsyncStatus::new(
+                repo.clone(),
+                sender.clone(),
+            ),
+            git_status_stage: AsyncStatus::new(
+                repo.clone(),
+                sender.clone(),
+            ),
             git_action_executed: false,
+            diff_base: None,
             git_branch_state: None,
-            git_branch_name: cached::BranchName::new(CWD),
+            git_push_state: None,
+            dragging_split: None,
+            zoomed: false,
+            had_conflicts: false,
+            areas: Cell::new([Rect::default(); 3]),
+            git_branch_name: cached::BranchName::new(repo.clone()),
+            head_detached: false,
             key_config,
+            options,
+            repo,
         }
     }
 
+    /// width of the file lists in percent
+    fn split(&self) -> u16 {
+        self.options.borrow().status_split().unwrap_or(
+            if self.focus == Focus::Diff { 30 } else { 50 },
+        )
+    }
+
+    /// height of the working dir list in percent of both lists
+    fn lists_split(&self) -> u16 {
+        self.options.borrow().status_lists_split().unwrap_or(
+            if self.diff_target == DiffTarget::WorkingDir {
+                60
+            } else {
+                40
+            },
+        )
+    }
+
+    /// resizes or zooms the panes, `false` for other keys
+    fn layout_event(&mut self, k: KeyEvent) -> bool {
+        if k == self.key_config.borrow().split_left
+            || k == self.key_config.borrow().split_right
+        {
+            let split = ui::resize_split(
+                self.split(),
+                k == self.key_config.borrow().split_right,
+            );
+            self.options.borrow_mut().set_status_split(split);
+        } else if k == self.key_config.borrow().split_up
+            || k == self.key_config.borrow().split_down
+        {
+            let split = ui::resize_split(
+                self.lists_split(),
+                k == self.key_config.borrow().split_down,
+            );
+            self.options.borrow_mut().set_status_lists_split(split);
+        } else if k == self.key_config.borrow().zoom_pane {
+            self.zoomed = !self.zoomed;
+        } else {
+            return false;
+        }
+
+        true
+    }
+
+    /// the focused pane on the whole tab
+    fn draw_zoomed<B: tui::backend::Backend>(
+        &self,
+        f: &mut tui::Frame<B>,
+        rect: Rect,
+    ) -> Result<()> {
+        let none = Rect::default();
+
+        match self.focus {
+            Focus::WorkDir => {
+                self.areas.set([rect, none, none]);
+                self.index_wd.draw(f, rect)?;
+            }
+            Focus::Stage => {
+                self.areas.set([none, rect, none]);
+                self.index.draw(f, rect)?;
+            }
+            Focus::Diff => {
+                self.areas.set([none, none, rect]);
+                self.diff.draw(f, rect)?;
+            }
+        }
+
+        Ok(())
+    }
+
     fn draw_branch_state<B: tui::backend::Backend>(
         &self,
         f: &mut tui::Frame<B>,
         chunks: &[tui::layout::Rect],
     ) {
-        if let Some(branch_name) = self.git_branch_name.last() {
+        let branch_name = if self.head_detached {
+            Some(strings::detached_head())
+        } else {
+            self.git_branch_name.last()
+        };
+
+        if let Some(branch_name) = branch_name {
             let ahead_behind =
                 if let Some(state) = &self.git_branch_state {
                     format!(
@@ -187,9 +308,14 @@ impl Status {
                 } else {
                     String::new()
                 };
+            let push_ahead_behind = self
+                .git_push_state
+                .as_ref()
+                .map(strings::push_ahead_behind)
+                .unwrap_or_default();
             let w = Paragraph::new(format!(
-                "{}{{{}}}",
-                ahead_behind, branch_name
+                "{}{}{{{}}}",
+                ahead_behind, push_ahead_behind, branch_name
             ))
             .alignment(Alignment::Right);
 
@@ -212,13 +338,14 @@ impl Status {
     }
 
     fn draw_repo_state<B: tui::backend::Backend>(
+        &self,
         f: &mut tui::Frame<B>,
         r: tui::layout::Rect,
     ) -> Result<()> {
-        if let Ok(state) = sync::repo_state(CWD) {
+        if let Ok(state) = sync::repo_state(&self.repo) {
             if state != RepoState::Clean {
-                let ids =
-                    sync::mergehead_ids(CWD).unwrap_or_default();
+                let ids = sync::mergehead_ids(&self.repo)
+                    .unwrap_or_default();
                 let ids = format!(
                     "({})",
                     ids.iter()
@@ -227,7 +354,15 @@ impl Status {
                         ))
                         .join(",")
                 );
-                let txt = format!("{:?} {}", state, ids);
+                let mut txt = format!("{:?} {}", state, ids);
+                // applied once the operation is continued or aborted
+                if matches!(
+                    sync::autostash_pending(&self.repo),
+                    Ok(Some(_))
+                ) {
+                    txt.push(' ');
+                    txt.push_str(strings::AUTOSTASH_PENDING_MARKER);
+                }
                 let txt_len = u16::try_from(txt.len())?;
                 let w = Paragraph::new(txt)
                     .style(Style::default().fg(Color::Red))
@@ -290,6 +425,70 @@ impl Status {
         Ok(false)
     }
 
+    /// a click focuses the pane (and selects the file) under the
+    /// cursor, dragging the border between the file lists and the
+    /// diff resizes them
+    fn mouse_event(
+        &mut self,
+        mouse: MouseEvent,
+    ) -> Result<EventState> {
+        let [work_dir, stage, diff] = self.areas.get();
+        let clicked = Rect::new(mouse.column, mouse.row, 1, 1);
+        // the column of the diff border and the one left of it
+        let on_border =
+            diff.intersects(Rect::new(mouse.column, mouse.row, 2, 1))
+                && mouse.column <= diff.x;
+
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left)
+                if on_border && !self.zoomed =>
+            {
+                self.dragging_split = Some(self.split());
+            }
+            MouseEventKind::Drag(MouseButton::Left)
+                if self.dragging_split.is_some() =>
+            {
+                let area = work_dir.union(diff);
+                let left =
+                    u32::from(mouse.column.saturating_sub(area.x))
+                        * 100
+                        / u32::from(area.width.max(1));
+                let left =
+                    u16::try_from(left).unwrap_or(ui::SPLIT_MAX);
+                self.dragging_split =
+                    Some(left.max(ui::SPLIT_MIN).min(ui::SPLIT_MAX));
+            }
+            MouseEventKind::Up(MouseButton::Left)
+                if self.dragging_split.is_some() =>
+            {
+                if let Some(split) = self.dragging_split.take() {
+                    self.options.borrow_mut().set_status_split(split);
+                }
+            }
+            MouseEventKind::Down(MouseButton::Left)
+                if work_dir.intersects(clicked) =>
+            {
+                self.switch_focus(Focus::WorkDir)?;
+                self.index_wd.select_at_row(mouse.row);
+            }
+            MouseEventKind::Down(MouseButton::Left)
+                if stage.intersects(clicked) =>
+            {
+                self.switch_focus(Focus::Stage)?;
+                self.index.select_at_row(mouse.row);
+            }
+            MouseEventKind::Down(MouseButton::Left)
+                if diff.intersects(clicked)
+                    && self.can_focus_diff() =>
+            {
+                self.switch_focus(Focus::Diff)?;
+            }
+            _ => return Ok(EventState::NotConsumed),
+        }
+
+        Ok(EventState::Consumed)
+    }
+
     fn set_diff_target(&mut self, target: DiffTarget) {
         self.diff_target = target;
         let is_stage = self.diff_target == DiffTarget::Stage;
@@ -306,6 +505,10 @@ impl Status {
 
         if let Some(item) = idx.selection() {
             if let FileTreeItemKind::File(i) = item.kind {
+                // a summarized folder has no diff to show
+                if i.summarized {
+                    return None;
+                }
                 return Some((i.path, is_stage));
             }
         }
@@ -315,15 +518,30 @@ impl Status {
     ///
     pub fn update(&mut self) -> Result<()> {
         self.git_branch_name.lookup().map(Some).unwrap_or(None);
+        self.head_detached =
+            sync::is_head_detached(&self.repo).unwrap_or_default();
 
         if self.is_visible() {
             self.git_diff.refresh()?;
-            self.git_status_workdir.fetch(&StatusParams::new(
-                StatusType::WorkingDir,
-                true,
-            ))?;
-            self.git_status_stage
-                .fetch(&StatusParams::new(StatusType::Stage, true))?;
+            let workdir_type = self
+                .diff_base
+                .as_ref()
+                .map_or(StatusType::WorkingDir, |(_, id)| {
+                    StatusType::Base(*id)
+                });
+            let scope =
+                self.options.borrow().path_scope().map(String::from);
+            self.git_status_workdir.fetch(
+                &StatusParams::new(workdir_type, true)
+                    .with_scope(scope.clone())
+                    .with_shallow(
+                        self.options.borrow().status_shallow(),
+                    ),
+            )?;
+            self.git_status_stage.fetch(
+                &StatusParams::new(StatusType::Stage, true)
+                    .with_scope(scope),
+            )?;
 
             self.branch_compare();
         }
@@ -334,6 +552,7 @@ impl Status {
     ///
     pub fn anything_pending(&self) -> bool {
         self.git_diff.is_pending()
+            || self.diff.any_work_pending()
             || self.git_status_stage.is_pending()
             || self.git_status_workdir.is_pending()
     }
@@ -364,6 +583,21 @@ impl Status {
 
         self.update_diff()?;
 
+        let conflicts = stage_status
+            .items
+            .iter()
+            .chain(workdir_status.items.iter())
+            .any(|item| item.status == StatusItemType::Conflicted);
+        if self.had_conflicts
+            && !conflicts
+            && self.options.borrow().conflicts_auto_continue()
+        {
+            self.queue
+                .borrow_mut()
+                .push_back(InternalEvent::ContinueResolved);
+        }
+        self.had_conflicts = conflicts;
+
         if self.git_action_executed {
             self.git_action_executed = false;
 
@@ -387,13 +621,21 @@ impl Status {
         if let Some((path, is_stage)) = self.selected_path() {
             let diff_type = if is_stage {
                 DiffType::Stage
+            } else if let Some((_, id)) = &self.diff_base {
+                DiffType::Base(*id)
             } else {
                 DiffType::WorkDir
             };
 
+            // hunks of a diff against another commit cannot be
+            // staged or reset
+            self.diff
+                .set_immutable(!is_stage && self.diff_base.is_some());
+
             let diff_params = DiffParams {
                 path: path.clone(),
                 diff_type,
+                options: self.options.borrow().diff_options(),
             };
 
             if self.diff.current() == (path.clone(), is_stage) {
@@ -402,6 +644,9 @@ impl Status {
                 if let Some((params, last)) = self.git_diff.last()? {
                     if params == diff_params {
                         self.diff.update(path, is_stage, last)?;
+                    } else {
+                        // diff options changed
+                        self.git_diff.request(diff_params)?;
                     }
                 }
             } else {
@@ -421,9 +666,31 @@ impl Status {
         Ok(())
     }
 
+    /// name of the ref the workdir is compared against, if any
+    pub fn diff_base_name(&self) -> Option<String> {
+        self.diff_base.as_ref().map(|(name, _)| name.clone())
+    }
+
+    /// switches the workdir list and its diffs over to compare
+    /// against `base`, `None` goes back to `HEAD`
+    pub fn set_diff_base(
+        &mut self,
+        base: Option<(String, CommitId)>,
+    ) -> Result<()> {
+        self.index_wd.set_title(&base.as_ref().map_or_else(
+            || strings::title_status(&self.key_config),
+            |(name, _)| strings::title_status_base(name),
+        ));
+        self.diff_base = base;
+
+        self.update()
+    }
+
     /// called after confirmation
     pub fn reset(&mut self, item: &ResetItem) -> bool {
-        if let Err(e) = sync::reset_workdir(CWD, item.path.as_str()) {
+        if let Err(e) =
+            sync::reset_workdir(&self.repo, item.path.as_str())
+        {
             self.queue.borrow_mut().push_back(
                 InternalEvent::ShowErrorMsg(format!(
                     "reset failed:\n{}",
@@ -463,34 +730,324 @@ impl Status {
     }
 
     fn pull(&self) {
-        if let Some(branch) = self.git_branch_name.last() {
+        if let Some(branch) = self.fetchable_branch() {
             self.queue
                 .borrow_mut()
                 .push_back(InternalEvent::Pull(branch));
         }
     }
 
+    fn fetch_options(&self) {
+        if let Some(branch) = self.fetchable_branch() {
+            self.queue
+                .borrow_mut()
+                .push_back(InternalEvent::OpenFetchOptions(branch));
+        }
+    }
+
+    /// the current branch if it has a remote to fetch from
+    fn fetchable_branch(&self) -> Option<String> {
+        let branch = self.git_branch_name.last()?;
+
+        // fetching needs to know where from
+        if let Ok(None) = sync::get_branch_remote(&self.repo, &branch)
+        {
+            self.queue.borrow_mut().push_back(
+                InternalEvent::ShowErrorMsg(
+                    strings::no_upstream_msg(&self.key_config),
+                ),
+            );
+            return None;
+        }
+
+        Some(branch)
+    }
+
     fn branch_compare(&mut self) {
         self.git_branch_state =
             self.git_branch_name.last().and_then(|branch| {
-                sync::branch_compare_upstream(CWD, branch.as_str())
+                sync::branch_compare_upstream(
+                    &self.repo,
+                    branch.as_str(),
+                )
+                .ok()
+            });
+        self.git_push_state =
+            self.git_branch_name.last().and_then(|branch| {
+                sync::branch_compare_push(&self.repo, branch.as_str())
                     .ok()
+                    .flatten()
             });
     }
 
+    /// against the push remote in triangular workflows, a branch
+    /// not there yet can always be pushed
     fn can_push(&self) -> bool {
+        if let Some(push) = &self.git_push_state {
+            return push
+                .compare
+                .as_ref()
+                .map_or(true, |state| state.ahead > 0);
+        }
+
         self.git_branch_state
             .as_ref()
             .map_or(true, |state| state.ahead > 0)
     }
 
-    fn can_abort_merge() -> bool {
-        sync::repo_state(CWD).unwrap_or(RepoState::Clean)
+    /// amends the staged changes into `HEAD` without asking for a
+    /// message, the commit-msg hook is skipped as the message stays
+    fn quick_amend(&self) -> Result<()> {
+        if let HookResult::NotOk(e) =
+            sync::hooks_pre_commit(&self.repo)?
+        {
+            self.queue.borrow_mut().push_back(
+                InternalEvent::ShowErrorMsg(format!(
+                    "pre-commit hook error:\n{}",
+                    e
+                )),
+            );
+            return Ok(());
+        }
+
+        sync::amend_staged(&self.repo)?;
+
+        if let HookResult::NotOk(e) =
+            sync::hooks_post_commit(&self.repo)?
+        {
+            self.queue.borrow_mut().push_back(
+                InternalEvent::ShowErrorMsg(format!(
+                    "post-commit hook error:\n{}",
+                    e
+                )),
+            );
+        }
+
+        self.queue
+            .borrow_mut()
+            .push_back(InternalEvent::Update(NeedsUpdate::ALL));
+
+        Ok(())
+    }
+
+    fn can_abort_merge(&self) -> bool {
+        sync::repo_state(&self.repo).unwrap_or(RepoState::Clean)
             == RepoState::Merge
     }
 
+    fn is_rebasing(&self) -> bool {
+        sync::repo_state(&self.repo).unwrap_or(RepoState::Clean)
+            == RepoState::Rebase
+    }
+
     pub fn abort_merge(&self) {
-        try_or_popup!(self, "abort merge", sync::abort_merge(CWD))
+        try_or_popup!(
+            self,
+            "abort merge",
+            sync::abort_merge(&self.repo)
+        )
+    }
+
+    /// pushing and fetching, with their key to be found in the
+    /// command palette
+    fn commands_remote(&self, out: &mut Vec<CommandInfo>) {
+        let focus_on_diff = self.is_focus_on_diff();
+        let key_config = &self.key_config;
+
+        out.push(
+            CommandInfo::new(
+                strings::commands::status_push(key_config),
+                self.can_push(),
+                !focus_on_diff,
+            )
+            .key(key_config.borrow().push),
+        );
+        out.push(
+            CommandInfo::new(
+                strings::commands::status_force_push(key_config),
+                true,
+                self.can_push() && !focus_on_diff,
+            )
+            .key(key_config.borrow().force_push),
+        );
+        out.push(
+            CommandInfo::new(
+                strings::commands::status_pull(key_config),
+                true,
+                !focus_on_diff,
+            )
+            .key(key_config.borrow().pull),
+        );
+        out.push(
+            CommandInfo::new(
+                strings::commands::status_fetch_options(key_config),
+                true,
+                !focus_on_diff,
+            )
+            .key(key_config.borrow().fetch_options),
+        );
+    }
+
+    /// keys opening popups or acting on the repository, `false` if
+    /// `k` is none of them
+    fn action_event(&self, k: KeyEvent) -> bool {
+        if k == self.key_config.borrow().status_diff_base
+            && !self.is_focus_on_diff()
+        {
+            self.queue
+                .borrow_mut()
+                .push_back(InternalEvent::OpenDiffBase);
+        } else if k == self.key_config.borrow().status_apply_patch
+            && !self.is_focus_on_diff()
+        {
+            self.queue
+                .borrow_mut()
+                .push_back(InternalEvent::OpenApplyPatch);
+        } else if k == self.key_config.borrow().open_review
+            && !self.is_focus_on_diff()
+        {
+            self.queue
+                .borrow_mut()
+                .push_back(InternalEvent::OpenReview);
+        } else if k == self.key_config.borrow().undo_commit
+            && !self.is_focus_on_diff()
+        {
+            self.queue.borrow_mut().push_back(
+                InternalEvent::ConfirmAction(Action::UndoCommit),
+            );
+        } else if k == self.key_config.borrow().quick_amend
+            && !self.is_focus_on_diff()
+            && !self.index.is_empty()
+        {
+            try_or_popup!(self, "amend error:", self.quick_amend());
+        } else if k == self.key_config.borrow().abort_merge
+            && self.can_abort_merge()
+        {
+            self.queue.borrow_mut().push_back(
+                InternalEvent::ConfirmAction(Action::AbortMerge),
+            );
+        } else if k == self.key_config.borrow().open_rebase
+            && self.is_rebasing()
+        {
+            self.queue
+                .borrow_mut()
+                .push_back(InternalEvent::OpenRebase);
+        } else {
+            return false;
+        }
+
+        true
+    }
+
+    /// the actions of the tab, with their key to be found in the
+    /// command palette
+    fn commands_actions(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) {
+        let focus_on_diff = self.is_focus_on_diff();
+        let key_config = &self.key_config;
+
+        out.push(
+            CommandInfo::new(
+                strings::commands::open_branch_select_popup(
+                    key_config,
+                ),
+                true,
+                !focus_on_diff,
+            )
+            .key(key_config.borrow().select_branch),
+        );
+
+        self.commands_remote(out);
+
+        out.push(
+            CommandInfo::new(
+                strings::commands::status_diff_base(key_config),
+                true,
+                !focus_on_diff,
+            )
+            .key(key_config.borrow().status_diff_base),
+        );
+
+        out.push(
+            CommandInfo::new(
+                strings::commands::status_apply_patch(key_config),
+                true,
+                !focus_on_diff,
+            )
+            .key(key_config.borrow().status_apply_patch),
+        );
+
+        out.push(
+            CommandInfo::new(
+                strings::commands::open_review(key_config),
+                true,
+                !focus_on_diff,
+            )
+            .key(key_config.borrow().open_review),
+        );
+
+        out.push(
+            CommandInfo::new(
+                strings::commands::undo_commit(key_config),
+                true,
+                !focus_on_diff,
+            )
+            .key(key_config.borrow().undo_commit),
+        );
+
+        out.push(
+            CommandInfo::new(
+                strings::commands::quick_amend(key_config),
+                !self.index.is_empty(),
+                !focus_on_diff,
+            )
+            .key(key_config.borrow().quick_amend),
+        );
+
+        out.push(
+            CommandInfo::new(
+                strings::commands::abort_merge(key_config),
+                true,
+                self.can_abort_merge() || force_all,
+            )
+            .key(key_config.borrow().abort_merge),
+        );
+
+        out.push(
+            CommandInfo::new(
+                strings::commands::open_rebase(key_config),
+                true,
+                self.is_rebasing() || force_all,
+            )
+            .key(key_config.borrow().open_rebase),
+        );
+    }
+
+    fn commands_layout(
+        &self,
+        out: &mut Vec<CommandInfo>,
+        force_all: bool,
+    ) {
+        out.push(CommandInfo::new(
+            strings::commands::resize_panes(&self.key_config, true),
+            !self.zoomed,
+            self.visible || force_all,
+        ));
+
+        out.push(
+            CommandInfo::new(
+                strings::commands::zoom_pane(
+                    &self.key_config,
+                    self.zoomed,
+                ),
+                true,
+                self.visible || force_all,
+            )
+            .key(self.key_config.borrow().zoom_pane),
+        );
     }
 
     fn commands_nav(
@@ -555,39 +1112,11 @@ impl Component for Status {
                 self.components().as_slice(),
             );
 
-            out.push(CommandInfo::new(
-                strings::commands::open_branch_select_popup(
-                    &self.key_config,
-                ),
-                true,
-                !focus_on_diff,
-            ));
-
-            out.push(CommandInfo::new(
-                strings::commands::status_push(&self.key_config),
-                self.can_push(),
-                !focus_on_diff,
-            ));
-            out.push(CommandInfo::new(
-                strings::commands::status_force_push(
-                    &self.key_config,
-                ),
-                true,
-                self.can_push() && !focus_on_diff,
-            ));
-            out.push(CommandInfo::new(
-                strings::commands::status_pull(&self.key_config),
-                true,
-                !focus_on_diff,
-            ));
-
-            out.push(CommandInfo::new(
-                strings::commands::abort_merge(&self.key_config),
-                true,
-                Self::can_abort_merge() || force_all,
-            ));
+            self.commands_actions(out, force_all);
         }
 
+        self.commands_layout(out, force_all);
+
         {
             out.push(CommandInfo::new(
                 strings::commands::edit_item(&self.key_config),
@@ -628,8 +1157,16 @@ impl Component for Status {
                 return Ok(EventState::Consumed);
             }
 
+            if let Event::Mouse(mouse) = ev {
+                return self.mouse_event(mouse);
+            }
+
             if let Event::Key(k) = ev {
-                return if k == self.key_config.edit_file
+                if self.layout_event(k) {
+                    return Ok(EventState::Consumed);
+                }
+
+                return if k == self.key_config.borrow().edit_file
                     && (self.can_focus_diff()
                         || self.is_focus_on_diff())
                 {
@@ -641,63 +1178,61 @@ impl Component for Status {
                         );
                     }
                     Ok(EventState::Consumed)
-                } else if k == self.key_config.toggle_workarea
+                } else if k
+                    == self.key_config.borrow().toggle_workarea
                     && !self.is_focus_on_diff()
                 {
                     self.switch_focus(self.focus.toggled_focus())
                         .map(Into::into)
-                } else if k == self.key_config.focus_right
+                } else if k == self.key_config.borrow().focus_right
                     && self.can_focus_diff()
                 {
                     self.switch_focus(Focus::Diff).map(Into::into)
-                } else if k == self.key_config.focus_left {
+                } else if k == self.key_config.borrow().focus_left {
                     self.switch_focus(match self.diff_target {
                         DiffTarget::Stage => Focus::Stage,
                         DiffTarget::WorkingDir => Focus::WorkDir,
                     })
                     .map(Into::into)
-                } else if k == self.key_config.move_down
+                } else if k == self.key_config.borrow().move_down
                     && self.focus == Focus::WorkDir
                     && !self.index.is_empty()
                 {
                     self.switch_focus(Focus::Stage).map(Into::into)
-                } else if k == self.key_config.move_up
+                } else if k == self.key_config.borrow().move_up
                     && self.focus == Focus::Stage
                     && !self.index_wd.is_empty()
                 {
                     self.switch_focus(Focus::WorkDir).map(Into::into)
-                } else if k == self.key_config.select_branch
+                } else if k == self.key_config.borrow().select_branch
                     && !self.is_focus_on_diff()
                 {
                     self.queue
                         .borrow_mut()
                         .push_back(InternalEvent::SelectBranch);
                     Ok(EventState::Consumed)
-                } else if k == self.key_config.force_push
+                } else if k == self.key_config.borrow().force_push
                     && !self.is_focus_on_diff()
                     && self.can_push()
                 {
                     self.push(true);
                     Ok(EventState::Consumed)
-                } else if k == self.key_config.push
+                } else if k == self.key_config.borrow().push
                     && !self.is_focus_on_diff()
                 {
                     self.push(false);
                     Ok(EventState::Consumed)
-                } else if k == self.key_config.pull
+                } else if k == self.key_config.borrow().pull
                     && !self.is_focus_on_diff()
                 {
                     self.pull();
                     Ok(EventState::Consumed)
-                } else if k == self.key_config.abort_merge
-                    && Self::can_abort_merge()
+                } else if k == self.key_config.borrow().fetch_options
+                    && !self.is_focus_on_diff()
                 {
-                    self.queue.borrow_mut().push_back(
-                        InternalEvent::ConfirmAction(
-                            Action::AbortMerge,
-                        ),
-                    );
-
+                    self.fetch_options();
+                    Ok(EventState::Consumed)
+                } else if self.action_event(k) {
                     Ok(EventState::Consumed)
                 } else {
                     Ok(EventState::NotConsumed)
@@ -0,0 +1,143 @@
+use super::style::SharedTheme;
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    text::{Span, Spans},
+    widgets::Paragraph,
+    Frame,
+};
+
+const BYTES_PER_ROW: usize = 16;
+
+/// hex and ascii dump of binary content like `hexdump -C`, only the
+/// visible rows get rendered
+pub struct HexView {
+    bytes: Vec<u8>,
+}
+
+impl HexView {
+    pub fn new(bytes: Vec<u8>) -> Self {
+        Self { bytes }
+    }
+
+    pub fn rows(&self) -> usize {
+        (self.bytes.len() + BYTES_PER_ROW - 1) / BYTES_PER_ROW
+    }
+
+    pub fn size(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// row containing the byte at `offset`
+    pub const fn row_of(offset: usize) -> usize {
+        offset / BYTES_PER_ROW
+    }
+
+    /// offset of the first byte in `row`
+    pub const fn offset_of(row: usize) -> usize {
+        row * BYTES_PER_ROW
+    }
+
+    pub fn draw<B: Backend>(
+        &self,
+        f: &mut Frame<B>,
+        area: Rect,
+        scroll_top: usize,
+        theme: &SharedTheme,
+    ) {
+        let lines: Vec<Spans> = self
+            .bytes
+            .chunks(BYTES_PER_ROW)
+            .enumerate()
+            .skip(scroll_top)
+            .take(area.height.into())
+            .map(|(row, bytes)| {
+                let (offset, hex, ascii) =
+                    Self::row_text(Self::offset_of(row), bytes);
+                Spans::from(vec![
+                    Span::styled(
+                        offset,
+                        theme.borrow().text(false, false),
+                    ),
+                    Span::styled(
+                        hex,
+                        theme.borrow().text(true, false),
+                    ),
+                    Span::styled(
+                        ascii,
+                        theme.borrow().text(false, false),
+                    ),
+                ])
+            })
+            .collect();
+
+        f.render_widget(Paragraph::new(lines), area);
+    }
+
+    /// offset, hex bytes (padded to a full row) and their printable
+    /// ascii characters
+    fn row_text(
+        offset: usize,
+        bytes: &[u8],
+    ) -> (String, String, String) {
+        let hex: String = (0..BYTES_PER_ROW)
+            .map(|idx| {
+                let gap =
+                    if idx == BYTES_PER_ROW / 2 { " " } else { "" };
+                bytes.get(idx).map_or_else(
+                    || format!("{}   ", gap),
+                    |byte| format!("{}{:02x} ", gap, byte),
+                )
+            })
+            .collect();
+
+        let ascii: String = bytes
+            .iter()
+            .map(|byte| {
+                if byte.is_ascii_graphic() || *byte == b' ' {
+                    char::from(*byte)
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+
+        (format!("{:08x}  ", offset), hex, format!(" |{}|", ascii))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_row_text() {
+        let (offset, hex, ascii) =
+            HexView::row_text(0x20, b"Hello\x00\xffworld!\n\ta");
+
+        assert_eq!(offset, "00000020  ");
+        assert_eq!(
+            hex,
+            "48 65 6c 6c 6f 00 ff 77  6f 72 6c 64 21 0a 09 61 "
+        );
+        assert_eq!(ascii, " |Hello..world!..a|");
+    }
+
+    #[test]
+    fn test_partial_row() {
+        let (_, hex, ascii) = HexView::row_text(0, b"ab");
+
+        assert_eq!(hex.len(), BYTES_PER_ROW * 3 + 1);
+        assert!(hex.starts_with("61 62    "));
+        assert_eq!(ascii, " |ab|");
+    }
+
+    #[test]
+    fn test_rows() {
+        assert_eq!(HexView::new(Vec::new()).rows(), 0);
+        assert_eq!(HexView::new(vec![0; 16]).rows(), 1);
+        assert_eq!(HexView::new(vec![0; 17]).rows(), 2);
+        assert_eq!(HexView::row_of(0x1f), 1);
+        assert_eq!(HexView::offset_of(2), 0x20);
+    }
+}
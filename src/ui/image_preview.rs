@@ -0,0 +1,105 @@
+use image::{DynamicImage, GenericImageView, Rgba};
+use std::cell::RefCell;
+use tui::{
+    backend::Backend,
+    layout::Rect,
+    style::{Color, Style},
+    text::{Span, Spans},
+    widgets::Paragraph,
+    Frame,
+};
+
+/// upper half block, foreground colors the top and background the
+/// bottom pixel so one cell shows two (roughly square) pixels
+const HALF_BLOCK: &str = "\u{2580}";
+
+/// low resolution preview of an image using unicode half blocks
+pub struct ImagePreview {
+    image: DynamicImage,
+    cache: RefCell<Option<((u16, u16), Vec<Spans<'static>>)>>,
+}
+
+impl ImagePreview {
+    /// returns `None` if `bytes` cannot be decoded
+    pub fn new(bytes: &[u8]) -> Option<Self> {
+        let image = image::load_from_memory(bytes).ok()?;
+
+        Some(Self {
+            image,
+            cache: RefCell::new(None),
+        })
+    }
+
+    /// width and height in pixels
+    pub fn dimensions(&self) -> (u32, u32) {
+        self.image.dimensions()
+    }
+
+    pub fn draw<B: Backend>(&self, f: &mut Frame<B>, area: Rect) {
+        let size = (area.width, area.height);
+
+        let mut cache = self.cache.borrow_mut();
+        let is_cached = cache
+            .as_ref()
+            .map_or(false, |(cached, _)| *cached == size);
+        if !is_cached {
+            *cache = Some((size, self.render(size)));
+        }
+
+        if let Some((_, lines)) = cache.as_ref() {
+            f.render_widget(Paragraph::new(lines.clone()), area);
+        }
+    }
+
+    fn render(
+        &self,
+        (width, height): (u16, u16),
+    ) -> Vec<Spans<'static>> {
+        if width == 0 || height == 0 {
+            return Vec::new();
+        }
+
+        let thumbnail = self
+            .image
+            .thumbnail(u32::from(width), u32::from(height) * 2)
+            .to_rgba8();
+        let (thumb_width, thumb_height) = thumbnail.dimensions();
+
+        (0..thumb_height)
+            .step_by(2)
+            .map(|y| {
+                Spans::from(
+                    (0..thumb_width)
+                        .map(|x| {
+                            let top =
+                                to_color(*thumbnail.get_pixel(x, y));
+                            let bottom = if y + 1 < thumb_height {
+                                to_color(
+                                    *thumbnail.get_pixel(x, y + 1),
+                                )
+                            } else {
+                                Color::Reset
+                            };
+
+                            Span::styled(
+                                HALF_BLOCK,
+                                Style::default().fg(top).bg(bottom),
+                            )
+                        })
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect()
+    }
+}
+
+fn to_color(pixel: Rgba<u8>) -> Color {
+    let [r, g, b, a] = pixel.0;
+
+    // mostly transparent pixels show the terminal background
+    if a < 128 {
+        Color::Reset
+    } else {
+        Color::Rgb(r, g, b)
+    }
+}
@@ -0,0 +1,355 @@
+use super::{style::Theme, syntax_text::SyntaxHighlighter};
+use std::{borrow::Cow, path::Path};
+use tui::{
+    style::{Modifier, Style},
+    text::{Span, Spans},
+};
+use unicode_width::UnicodeWidthStr;
+
+const CODE_FENCE: &str = "```";
+const QUOTE_MARKER: &str = "│ ";
+const BULLET_MARKER: &str = "• ";
+
+/// files shown rendered unless the raw text is asked for
+pub fn is_markdown(path: &Path) -> bool {
+    path.extension().and_then(|ext| ext.to_str()).map_or(
+        false,
+        |ext| {
+            ["md", "markdown", "mdown", "mkd"]
+                .iter()
+                .any(|md| ext.eq_ignore_ascii_case(md))
+        },
+    )
+}
+
+/// renders the basics of markdown: headings, lists, quotes, code
+/// fences (highlighted if the language is known), inline code,
+/// emphasis and links. lines are wrapped to `width` if given.
+pub fn markdown_lines(
+    text: &str,
+    width: Option<usize>,
+    theme: &Theme,
+) -> Vec<Spans<'static>> {
+    let mut res = Vec::new();
+    let mut lines = text.lines();
+
+    while let Some(line) = lines.next() {
+        if let Some(lang) = line.trim_start().strip_prefix(CODE_FENCE)
+        {
+            let code: Vec<&str> = lines
+                .by_ref()
+                .take_while(|line| {
+                    !line.trim_start().starts_with(CODE_FENCE)
+                })
+                .collect();
+            res.extend(code_lines(lang.trim(), &code, theme));
+        } else {
+            res.extend(block_lines(line, width, theme));
+        }
+    }
+
+    res
+}
+
+fn code_lines(
+    lang: &str,
+    code: &[&str],
+    theme: &Theme,
+) -> Vec<Spans<'static>> {
+    let highlighter = theme
+        .syntax_theme()
+        .filter(|_| !lang.is_empty())
+        .and_then(|name| SyntaxHighlighter::for_token(lang, name));
+
+    highlighter.map_or_else(
+        || {
+            code.iter()
+                .map(|line| {
+                    Spans::from(Span::styled(
+                        line.replace("\t", "  "),
+                        theme.markdown_code(),
+                    ))
+                })
+                .collect()
+        },
+        |highlighter| {
+            highlighter.highlight_text(code.iter().copied())
+        },
+    )
+}
+
+/// a heading, quote, list item or paragraph line
+fn block_lines(
+    line: &str,
+    width: Option<usize>,
+    theme: &Theme,
+) -> Vec<Spans<'static>> {
+    let content = line.trim_start();
+    let indent = " ".repeat(line.len() - content.len());
+
+    let (marker, content, style) =
+        if let Some((level, heading)) = heading(content) {
+            (String::new(), heading, theme.markdown_heading(level))
+        } else if let Some(quote) = content.strip_prefix('>') {
+            (
+                String::from(QUOTE_MARKER),
+                quote.trim_start(),
+                theme.text(false, false),
+            )
+        } else if let Some((marker, item)) = list_item(content) {
+            (marker, item, theme.text(true, false))
+        } else {
+            (String::new(), content, theme.text(true, false))
+        };
+
+    if content.is_empty() && marker.is_empty() {
+        return vec![Spans::default()];
+    }
+
+    let prefix = format!("{}{}", indent, marker);
+    let prefix_width = prefix.width();
+
+    let wrapped = width.map_or_else(
+        || vec![Cow::Borrowed(content)],
+        |width| {
+            textwrap::wrap(
+                content,
+                width.saturating_sub(prefix_width).max(1),
+            )
+        },
+    );
+
+    wrapped
+        .iter()
+        .enumerate()
+        .map(|(idx, part)| {
+            // continued lines line up with the text after the marker
+            let prefix = if idx == 0 {
+                prefix.clone()
+            } else {
+                " ".repeat(prefix_width)
+            };
+
+            let mut spans =
+                vec![Span::styled(prefix, theme.markdown_marker())];
+            spans.extend(inline_spans(part, style, theme));
+            Spans::from(spans)
+        })
+        .collect()
+}
+
+/// `# heading` up to level 6
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    let text = line[level..].strip_prefix(' ')?;
+
+    if (1..=6).contains(&level) {
+        Some((level, text.trim()))
+    } else {
+        None
+    }
+}
+
+/// `- item`, `* item`, `+ item` or `1. item`
+fn list_item(line: &str) -> Option<(String, &str)> {
+    for bullet in &["- ", "* ", "+ "] {
+        if let Some(item) = line.strip_prefix(bullet) {
+            return Some((String::from(BULLET_MARKER), item));
+        }
+    }
+
+    let digits =
+        line.chars().take_while(char::is_ascii_digit).count();
+    let rest = &line[digits..];
+    let item = rest
+        .strip_prefix(". ")
+        .or_else(|| rest.strip_prefix(") "))
+        .filter(|_| digits > 0)?;
+
+    Some((format!("{}. ", &line[..digits]), item))
+}
+
+fn inline_spans(
+    text: &str,
+    style: Style,
+    theme: &Theme,
+) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut plain = String::new();
+    let mut rest = text;
+
+    while let Some(c) = rest.chars().next() {
+        if let Some((element, len)) =
+            inline_element(rest, style, theme)
+        {
+            if !plain.is_empty() {
+                spans.push(Span::styled(
+                    std::mem::take(&mut plain),
+                    style,
+                ));
+            }
+            spans.extend(element);
+            rest = &rest[len..];
+        } else {
+            plain.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+
+    if !plain.is_empty() {
+        spans.push(Span::styled(plain, style));
+    }
+
+    spans
+}
+
+/// inline code, bold, emphasis or a link at the start of `text` and
+/// the length of its markup
+fn inline_element(
+    text: &str,
+    style: Style,
+    theme: &Theme,
+) -> Option<(Vec<Span<'static>>, usize)> {
+    if let Some(code) = text.strip_prefix('`') {
+        let end = code.find('`')?;
+        return Some((
+            vec![Span::styled(
+                code[..end].to_string(),
+                theme.markdown_code(),
+            )],
+            end + 2,
+        ));
+    }
+
+    if let Some(bold) = text.strip_prefix("**") {
+        if let Some(end) = bold.find("**").filter(|end| *end > 0) {
+            return Some((
+                vec![Span::styled(
+                    bold[..end].to_string(),
+                    style.add_modifier(Modifier::BOLD),
+                )],
+                end + 4,
+            ));
+        }
+    }
+
+    if let Some(emphasis) =
+        text.strip_prefix('*').filter(|em| !em.starts_with(' '))
+    {
+        let end = emphasis.find('*').filter(|end| *end > 0)?;
+        return Some((
+            vec![Span::styled(
+                emphasis[..end].to_string(),
+                style.add_modifier(Modifier::ITALIC),
+            )],
+            end + 2,
+        ));
+    }
+
+    if let Some(link) = text.strip_prefix('[') {
+        let text_end = link.find("](")?;
+        let url = &link[text_end + 2..];
+        let url_end = url.find(')')?;
+        return Some((
+            vec![
+                Span::styled(
+                    link[..text_end].to_string(),
+                    theme.markdown_link(),
+                ),
+                Span::styled(
+                    format!(" ({})", &url[..url_end]),
+                    theme.text(false, false),
+                ),
+            ],
+            text_end + url_end + 4,
+        ));
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(text: &str, width: Option<usize>) -> Vec<String> {
+        markdown_lines(text, width, &Theme::default())
+            .iter()
+            .map(|line| {
+                line.0
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_blocks() {
+        let text = "# Title\n\n- one\n  * two\n3. three\n> quote";
+
+        assert_eq!(
+            render(text, None),
+            vec![
+                "Title",
+                "",
+                "• one",
+                "  • two",
+                "3. three",
+                "│ quote"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_code_fence() {
+        let text = "before\n```\nlet a = *b*;\n```\nafter";
+
+        assert_eq!(
+            render(text, None),
+            vec!["before", "let a = *b*;", "after"]
+        );
+    }
+
+    #[test]
+    fn test_inline() {
+        let spans = inline_spans(
+            "a `code` **bold** *em* [link](http://x) 2 * 3",
+            Style::default(),
+            &Theme::default(),
+        );
+        let contents: Vec<&str> =
+            spans.iter().map(|span| span.content.as_ref()).collect();
+
+        assert_eq!(
+            contents,
+            vec![
+                "a ",
+                "code",
+                " ",
+                "bold",
+                " ",
+                "em",
+                " ",
+                "link",
+                " (http://x)",
+                " 2 * 3"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_wrapped_list_item() {
+        assert_eq!(
+            render("- one two three", Some(9)),
+            vec!["• one two", "  three"]
+        );
+    }
+
+    #[test]
+    fn test_is_markdown() {
+        assert!(is_markdown(Path::new("README.md")));
+        assert!(is_markdown(Path::new("docs/NOTES.Markdown")));
+        assert!(!is_markdown(Path::new("main.rs")));
+    }
+}
@@ -1,11 +1,35 @@
+mod hex_view;
+mod image_preview;
+pub mod markdown;
 mod scrollbar;
 mod scrolllist;
 pub mod style;
+pub mod syntax_text;
 
+pub use hex_view::HexView;
+pub use image_preview::ImagePreview;
 pub use scrollbar::draw_scrollbar;
 pub use scrolllist::draw_list;
 use tui::layout::{Constraint, Direction, Layout, Rect};
 
+/// bounds of a split in percent, no pane gets too small to be used
+pub const SPLIT_MIN: u16 = 10;
+pub const SPLIT_MAX: u16 = 90;
+
+/// moves the border of a split (given in percent of the first pane)
+/// by one step
+pub fn resize_split(split: u16, grow: bool) -> u16 {
+    const STEP: u16 = 5;
+
+    let split = if grow {
+        split.saturating_add(STEP)
+    } else {
+        split.saturating_sub(STEP)
+    };
+
+    split.max(SPLIT_MIN).min(SPLIT_MAX)
+}
+
 /// return the scroll position (line) necessary to have the `selection` in view if it is not already
 pub const fn calc_scroll_top(
     current_top: usize,
@@ -77,6 +77,6 @@ pub fn draw_scrollbar<B: Backend>(
     pos: usize,
 ) {
     let mut widget = Scrollbar::new(max, pos);
-    widget.style_pos = theme.scroll_bar_pos();
+    widget.style_pos = theme.borrow().scroll_bar_pos();
     f.render_widget(widget, r)
 }
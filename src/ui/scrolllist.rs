@@ -67,9 +67,12 @@ pub fn draw_list<'b, B: Backend, L>(
 {
     let list = ScrollableList::new(items).block(
         Block::default()
-            .title(Span::styled(title, theme.title(selected)))
+            .title(Span::styled(
+                title,
+                theme.borrow().title(selected),
+            ))
             .borders(Borders::ALL)
-            .border_style(theme.block(selected)),
+            .border_style(theme.borrow().block(selected)),
     );
     f.render_widget(list, r)
 }
@@ -1,22 +1,17 @@
 //TODO: remove once fixed https://github.com/rust-lang/rust-clippy/issues/6818
 #![allow(clippy::use_self)]
 
-use anyhow::Result;
-use asyncgit::{DiffLineType, StatusItemType};
-use ron::{
-    de::from_bytes,
-    ser::{to_string_pretty, PrettyConfig},
+use crate::config;
+use asyncgit::{
+    sync::{DecorationKind, SignatureStatus},
+    DiffLineType, StatusItemType,
 };
 use serde::{Deserialize, Serialize};
-use std::{
-    fs::{self, File},
-    io::{Read, Write},
-    path::PathBuf,
-    rc::Rc,
-};
+use std::{cell::RefCell, path::Path, rc::Rc};
 use tui::style::{Color, Modifier, Style};
 
-pub type SharedTheme = Rc<Theme>;
+/// replaced in place when the theme file changes
+pub type SharedTheme = Rc<RefCell<Theme>>;
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Theme {
@@ -25,6 +20,8 @@ pub struct Theme {
     command_fg: Color,
     #[serde(with = "Color")]
     selection_bg: Color,
+    /// text on the selection, `command_fg` if not set
+    selection_fg: Option<Color>,
     #[serde(with = "Color")]
     cmdbar_extra_lines_bg: Color,
     #[serde(with = "Color")]
@@ -33,6 +30,12 @@ pub struct Theme {
     diff_line_add: Color,
     #[serde(with = "Color")]
     diff_line_delete: Color,
+    diff_line_add_bg: Option<Color>,
+    diff_line_delete_bg: Option<Color>,
+    #[serde(with = "Color")]
+    diff_word_add: Color,
+    #[serde(with = "Color")]
+    diff_word_delete: Color,
     #[serde(with = "Color")]
     diff_file_added: Color,
     #[serde(with = "Color")]
@@ -53,6 +56,15 @@ pub struct Theme {
     push_gauge_bg: Color,
     #[serde(with = "Color")]
     push_gauge_fg: Color,
+    #[serde(with = "Color")]
+    decoration_branch: Color,
+    #[serde(with = "Color")]
+    decoration_remote_branch: Color,
+    #[serde(with = "Color")]
+    decoration_tag: Color,
+    /// name of the syntect theme used for syntax highlighting,
+    /// `None` disables highlighting
+    syntax: Option<String>,
 }
 
 impl Theme {
@@ -116,7 +128,7 @@ impl Theme {
             (false, _) => Style::default().fg(self.disabled_fg),
             (true, false) => Style::default(),
             (true, true) => Style::default()
-                .fg(self.command_fg)
+                .fg(self.selection_fg.unwrap_or(self.command_fg))
                 .bg(self.selection_bg),
         }
     }
@@ -174,12 +186,14 @@ impl Theme {
         selected: bool,
     ) -> Style {
         let style = match typ {
-            DiffLineType::Add => {
-                Style::default().fg(self.diff_line_add)
-            }
-            DiffLineType::Delete => {
-                Style::default().fg(self.diff_line_delete)
-            }
+            DiffLineType::Add => with_bg(
+                Style::default().fg(self.diff_line_add),
+                self.diff_line_add_bg,
+            ),
+            DiffLineType::Delete => with_bg(
+                Style::default().fg(self.diff_line_delete),
+                self.diff_line_delete_bg,
+            ),
             DiffLineType::Header => Style::default()
                 .fg(self.disabled_fg)
                 .add_modifier(Modifier::BOLD),
@@ -193,6 +207,47 @@ impl Theme {
         self.apply_select(style, selected)
     }
 
+    /// style of the intra-line part that changed inside of a diff line
+    pub fn diff_word(
+        &self,
+        typ: DiffLineType,
+        selected: bool,
+    ) -> Style {
+        match typ {
+            DiffLineType::Add => Style::default()
+                .fg(self.diff_word_add)
+                .add_modifier(Modifier::REVERSED),
+            DiffLineType::Delete => Style::default()
+                .fg(self.diff_word_delete)
+                .add_modifier(Modifier::REVERSED),
+            _ => self.diff_line(typ, selected),
+        }
+    }
+
+    /// name of the syntect theme, `None` if highlighting is disabled
+    pub fn syntax_theme(&self) -> Option<&str> {
+        self.syntax.as_deref()
+    }
+
+    /// style of a syntax highlighted part of a diff line, `None` if
+    /// the line keeps its diff colors (added and deleted lines unless
+    /// the theme sets a background for them)
+    pub fn diff_syntax(
+        &self,
+        typ: DiffLineType,
+        style: Style,
+        selected: bool,
+    ) -> Option<Style> {
+        let bg = match typ {
+            DiffLineType::None => None,
+            DiffLineType::Add => Some(self.diff_line_add_bg?),
+            DiffLineType::Delete => Some(self.diff_line_delete_bg?),
+            DiffLineType::Header => return None,
+        };
+
+        Some(self.apply_select(with_bg(style, bg), selected))
+    }
+
     pub fn text_danger(&self) -> Style {
         Style::default().fg(self.danger_fg)
     }
@@ -229,6 +284,30 @@ impl Theme {
         )
     }
 
+    /// annotated tags stand out from lightweight ones
+    pub fn decoration(
+        &self,
+        kind: DecorationKind,
+        selected: bool,
+    ) -> Style {
+        let style = match kind {
+            DecorationKind::LocalBranch => Style::default()
+                .fg(self.decoration_branch)
+                .add_modifier(Modifier::BOLD),
+            DecorationKind::RemoteBranch => {
+                Style::default().fg(self.decoration_remote_branch)
+            }
+            DecorationKind::AnnotatedTag => Style::default()
+                .fg(self.decoration_tag)
+                .add_modifier(Modifier::BOLD),
+            DecorationKind::LightweightTag => {
+                Style::default().fg(self.decoration_tag)
+            }
+        };
+
+        self.apply_select(style, selected)
+    }
+
     pub fn commit_hash_in_blame(
         &self,
         is_blamed_commit: bool,
@@ -242,51 +321,92 @@ impl Theme {
         }
     }
 
+    pub fn commit_signature(&self, status: SignatureStatus) -> Style {
+        Style::default().fg(match status {
+            SignatureStatus::Verified => self.diff_line_add,
+            SignatureStatus::Unverified => self.diff_file_modified,
+            SignatureStatus::Bad => self.danger_fg,
+        })
+    }
+
+    pub fn markdown_heading(&self, level: usize) -> Style {
+        let style =
+            self.text(true, false).add_modifier(Modifier::BOLD);
+
+        if level == 1 {
+            style.add_modifier(Modifier::UNDERLINED)
+        } else {
+            style
+        }
+    }
+
+    pub fn markdown_code(&self) -> Style {
+        Style::default().fg(self.commit_time)
+    }
+
+    pub fn markdown_link(&self) -> Style {
+        Style::default()
+            .fg(self.commit_hash)
+            .add_modifier(Modifier::UNDERLINED)
+    }
+
+    pub fn markdown_marker(&self) -> Style {
+        Style::default().fg(self.disabled_fg)
+    }
+
     pub fn push_gauge(&self) -> Style {
         Style::default()
             .fg(self.push_gauge_fg)
             .bg(self.push_gauge_bg)
     }
 
-    // This will only be called when theme.ron doesn't already exists
-    fn save(&self, theme_file: PathBuf) -> Result<()> {
-        let mut file = File::create(theme_file)?;
-        let data = to_string_pretty(self, PrettyConfig::default())?;
-        file.write_all(data.as_bytes())?;
-        Ok(())
+    /// loads `file` entry by entry, see `config::init`. colors may
+    /// be given as `"#rrggbb"` as well
+    pub fn init(file: &Path) -> (Self, Vec<String>) {
+        config::init_with(file, &[], expand_hex_colors)
     }
+}
 
-    fn read_file(theme_file: PathBuf) -> Result<Self> {
-        let mut f = File::open(theme_file)?;
-        let mut buffer = Vec::new();
-        f.read_to_end(&mut buffer)?;
-        Ok(from_bytes(&buffer)?)
-    }
+fn with_bg(style: Style, bg: Option<Color>) -> Style {
+    bg.map_or(style, |bg| style.bg(bg))
+}
 
-    pub fn init(file: PathBuf) -> Result<Self> {
-        if file.exists() {
-            match Self::read_file(file.clone()) {
-                Err(e) => {
-                    let config_path = file.clone();
-                    let config_path_old =
-                        format!("{}.old", file.to_string_lossy());
-                    fs::rename(
-                        config_path.clone(),
-                        config_path_old.clone(),
-                    )?;
+/// replaces `"#rrggbb"` strings by `Rgb(r, g, b)`, the only way tui
+/// reads truecolors
+fn expand_hex_colors(value: &str) -> String {
+    let mut res = String::with_capacity(value.len());
+    let mut rest = value;
 
-                    Self::default().save(file)?;
+    while let Some(start) = rest.find("\"#") {
+        res.push_str(&rest[..start]);
 
-                    Err(anyhow::anyhow!("{}\n Old file was renamed to {:?}.\n Defaults loaded and saved as {:?}",
-                        e,config_path_old,config_path.to_string_lossy()))
-                }
-                Ok(res) => Ok(res),
+        let closed = rest.get(start + 8..start + 9) == Some("\"");
+        match rest.get(start + 2..start + 8).and_then(parse_hex) {
+            Some((r, g, b)) if closed => {
+                res.push_str(&format!("Rgb({}, {}, {})", r, g, b));
+                rest = &rest[start + 9..];
+            }
+            _ => {
+                res.push_str("\"#");
+                rest = &rest[start + 2..];
             }
-        } else {
-            Self::default().save(file)?;
-            Ok(Self::default())
         }
     }
+
+    res.push_str(rest);
+    res
+}
+
+fn parse_hex(hex: &str) -> Option<(u8, u8, u8)> {
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let channel = |idx: usize| {
+        u8::from_str_radix(hex.get(idx..idx + 2)?, 16).ok()
+    };
+
+    Some((channel(0)?, channel(2)?, channel(4)?))
 }
 
 impl Default for Theme {
@@ -295,10 +415,15 @@ impl Default for Theme {
             selected_tab: Color::Yellow,
             command_fg: Color::White,
             selection_bg: Color::Blue,
+            selection_fg: None,
             cmdbar_extra_lines_bg: Color::Blue,
             disabled_fg: Color::DarkGray,
             diff_line_add: Color::Green,
             diff_line_delete: Color::Red,
+            diff_line_add_bg: None,
+            diff_line_delete_bg: None,
+            diff_word_add: Color::LightGreen,
+            diff_word_delete: Color::LightRed,
             diff_file_added: Color::LightGreen,
             diff_file_removed: Color::LightRed,
             diff_file_moved: Color::LightMagenta,
@@ -309,6 +434,30 @@ impl Default for Theme {
             danger_fg: Color::Red,
             push_gauge_bg: Color::Blue,
             push_gauge_fg: Color::Reset,
+            decoration_branch: Color::LightGreen,
+            decoration_remote_branch: Color::LightRed,
+            decoration_tag: Color::Yellow,
+            syntax: Some("base16-eighties.dark".to_string()),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::expand_hex_colors;
+
+    #[test]
+    fn test_expand_hex_colors() {
+        assert_eq!(
+            expand_hex_colors("\"#ff8000\""),
+            "Rgb(255, 128, 0)"
+        );
+        assert_eq!(
+            expand_hex_colors("Some(\"#0A0b0C\")"),
+            "Some(Rgb(10, 11, 12))"
+        );
+        assert_eq!(expand_hex_colors("\"#ff80\""), "\"#ff80\"");
+        assert_eq!(expand_hex_colors("\"#gg8000\""), "\"#gg8000\"");
+        assert_eq!(expand_hex_colors("Rgb(1, 2, 3)"), "Rgb(1, 2, 3)");
+    }
+}
@@ -0,0 +1,273 @@
+use asyncgit::AsyncNotification;
+use crossbeam_channel::Sender;
+use once_cell::sync::Lazy;
+use std::{
+    ops::Range,
+    path::Path,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+use syntect::{
+    easy::HighlightLines,
+    highlighting::{
+        FontStyle, Style as SyntectStyle, Theme as SyntectTheme,
+        ThemeSet,
+    },
+    parsing::{SyntaxReference, SyntaxSet},
+};
+use tui::{
+    style::{Color, Modifier, Style},
+    text::{Span, Spans},
+};
+
+static SYNTAX_SET: Lazy<SyntaxSet> =
+    Lazy::new(SyntaxSet::load_defaults_nonewlines);
+static THEME_SET: Lazy<ThemeSet> = Lazy::new(ThemeSet::load_defaults);
+
+/// styled byte ranges of a highlighted line, in order and without
+/// gaps
+pub type HighlightedLine = Vec<(Range<usize>, Style)>;
+
+/// lines highlighted in order with one parser state. the result of a
+/// line is kept at its index, lines without one only advance the
+/// state (like the old side of context lines in a diff)
+pub type SyntaxStream = Vec<(Option<usize>, String)>;
+
+/// syntax highlighter for a single file type and syntect theme
+#[derive(Clone, Copy)]
+pub struct SyntaxHighlighter {
+    syntax: &'static SyntaxReference,
+    theme: &'static SyntectTheme,
+}
+
+impl SyntaxHighlighter {
+    /// returns `None` if no syntax is known for the extension of
+    /// `path` or `theme_name` is no known syntect theme
+    pub fn new(path: &Path, theme_name: &str) -> Option<Self> {
+        let extension = path.extension()?.to_str()?;
+        let syntax =
+            SYNTAX_SET.find_syntax_by_extension(extension)?;
+        let theme = THEME_SET.themes.get(theme_name)?;
+
+        Some(Self { syntax, theme })
+    }
+
+    /// returns `None` for unknown languages, `token` is a name or
+    /// extension like the language of a markdown code fence
+    pub fn for_token(token: &str, theme_name: &str) -> Option<Self> {
+        let syntax = SYNTAX_SET.find_syntax_by_token(token)?;
+        let theme = THEME_SET.themes.get(theme_name)?;
+
+        Some(Self { syntax, theme })
+    }
+
+    /// highlights `streams` into `len` lines, lines of no stream stay
+    /// empty
+    pub fn highlight_streams(
+        &self,
+        streams: &[SyntaxStream],
+        len: usize,
+    ) -> Vec<HighlightedLine> {
+        let mut res = vec![HighlightedLine::new(); len];
+
+        for stream in streams {
+            let mut highlighter =
+                HighlightLines::new(self.syntax, self.theme);
+
+            for (idx, line) in stream {
+                let styles = highlighter.highlight(line, &SYNTAX_SET);
+
+                if let Some(target) =
+                    idx.and_then(|idx| res.get_mut(idx))
+                {
+                    let mut start = 0;
+                    *target = styles
+                        .into_iter()
+                        .map(|(style, text)| {
+                            let range = start..start + text.len();
+                            start = range.end;
+                            (range, to_tui_style(style))
+                        })
+                        .collect();
+                }
+            }
+        }
+
+        res
+    }
+
+    /// highlights a whole file, keeping parser state across lines
+    pub fn highlight_text<'a, I>(
+        &self,
+        lines: I,
+    ) -> Vec<Spans<'static>>
+    where
+        I: Iterator<Item = &'a str>,
+    {
+        let mut highlighter =
+            HighlightLines::new(self.syntax, self.theme);
+
+        lines
+            .map(|line| {
+                Spans::from(
+                    highlighter
+                        .highlight(line, &SYNTAX_SET)
+                        .into_iter()
+                        .map(|(style, text)| {
+                            Span::styled(
+                                text.to_string(),
+                                to_tui_style(style),
+                            )
+                        })
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect()
+    }
+}
+
+/// `line` styled by its highlighting, `None` if that does not cover
+/// `line`
+pub fn highlighted_spans(
+    line: &str,
+    ranges: &HighlightedLine,
+) -> Option<Spans<'static>> {
+    if ranges.last().map(|(range, _)| range.end) != Some(line.len()) {
+        return None;
+    }
+
+    ranges
+        .iter()
+        .map(|(range, style)| {
+            Some(Span::styled(
+                line.get(range.clone())?.to_string(),
+                *style,
+            ))
+        })
+        .collect::<Option<Vec<_>>>()
+        .map(Spans::from)
+}
+
+/// result of the latest request of `AsyncSyntaxHighlighting`
+struct Highlighted {
+    key: u64,
+    lines: Option<Arc<Vec<HighlightedLine>>>,
+}
+
+/// highlights in the background and keeps the result of the latest
+/// request, requesting the same key again does not highlight again.
+/// results of older requests are dropped
+pub struct AsyncSyntaxHighlighting {
+    last: Arc<Mutex<Option<Highlighted>>>,
+    sender: Sender<AsyncNotification>,
+    pending: Arc<AtomicUsize>,
+}
+
+impl AsyncSyntaxHighlighting {
+    pub fn new(sender: &Sender<AsyncNotification>) -> Self {
+        Self {
+            last: Arc::new(Mutex::new(None)),
+            sender: sender.clone(),
+            pending: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.pending.load(Ordering::Relaxed) > 0
+    }
+
+    /// the highlighted lines of the latest request, `None` while it
+    /// is pending
+    pub fn get(&self) -> Option<Arc<Vec<HighlightedLine>>> {
+        let last = self.last.lock().ok()?;
+
+        last.as_ref().and_then(|last| last.lines.clone())
+    }
+
+    /// drops the latest request and its result
+    pub fn clear(&mut self) {
+        if let Ok(mut last) = self.last.lock() {
+            *last = None;
+        }
+    }
+
+    /// highlights `len` lines out of `streams` unless `key` was
+    /// requested last (`streams` is only called if not)
+    pub fn request<F>(
+        &mut self,
+        key: u64,
+        highlighter: SyntaxHighlighter,
+        streams: F,
+        len: usize,
+    ) where
+        F: FnOnce() -> Vec<SyntaxStream>,
+    {
+        match self.last.lock() {
+            Ok(mut last) => {
+                if last.as_ref().map(|last| last.key) == Some(key) {
+                    return;
+                }
+
+                *last = Some(Highlighted { key, lines: None });
+            }
+            Err(e) => {
+                log::error!("syntax highlighting error: {}", e);
+                return;
+            }
+        }
+
+        let streams = streams();
+        let arc_last = Arc::clone(&self.last);
+        let arc_pending = Arc::clone(&self.pending);
+        let sender = self.sender.clone();
+
+        self.pending.fetch_add(1, Ordering::Relaxed);
+
+        rayon_core::spawn(move || {
+            let lines = highlighter.highlight_streams(&streams, len);
+
+            let notify = match arc_last.lock() {
+                Ok(mut last) => match last.as_mut() {
+                    Some(last) if last.key == key => {
+                        last.lines = Some(Arc::new(lines));
+                        true
+                    }
+                    _ => false,
+                },
+                Err(e) => {
+                    log::error!("syntax highlighting error: {}", e);
+                    false
+                }
+            };
+
+            arc_pending.fetch_sub(1, Ordering::Relaxed);
+
+            sender
+                .send(if notify {
+                    AsyncNotification::SyntaxHighlighting
+                } else {
+                    AsyncNotification::FinishUnchanged
+                })
+                .expect("error sending notify");
+        });
+    }
+}
+
+fn to_tui_style(style: SyntectStyle) -> Style {
+    let fg = style.foreground;
+    let mut res = Style::default().fg(Color::Rgb(fg.r, fg.g, fg.b));
+
+    if style.font_style.contains(FontStyle::BOLD) {
+        res = res.add_modifier(Modifier::BOLD);
+    }
+    if style.font_style.contains(FontStyle::ITALIC) {
+        res = res.add_modifier(Modifier::ITALIC);
+    }
+    if style.font_style.contains(FontStyle::UNDERLINE) {
+        res = res.add_modifier(Modifier::UNDERLINED);
+    }
+
+    res
+}
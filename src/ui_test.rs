@@ -0,0 +1,376 @@
+//! headless driver for end to end tests of the ui: key scripts are
+//! fed into a real `App` running against a fixture repository and
+//! the rendered buffer is checked afterwards
+
+use crate::{
+    app::App,
+    args::set_test_app_dir,
+    draw,
+    input::{Input, InputEvent},
+    keys::SharedKeyConfig,
+    ui::style::SharedTheme,
+};
+use asyncgit::{sync::RepoPath, AsyncNotification};
+use crossbeam_channel::{unbounded, Receiver};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use std::{
+    fs,
+    path::Path,
+    process::Command,
+    time::{Duration, Instant},
+};
+use tempfile::TempDir;
+use tui::{backend::TestBackend, Terminal};
+
+/// async jobs taking longer than this fail the test
+const SETTLE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// repository in a temp folder, set up using the git cli
+pub struct Fixture {
+    dir: TempDir,
+}
+
+impl Fixture {
+    /// empty repository with a local identity
+    pub fn new() -> Self {
+        let fixture = Self {
+            dir: TempDir::new().unwrap(),
+        };
+
+        fixture.git(&["init", "-q"]);
+        fixture.git(&["config", "user.name", "name"]);
+        fixture.git(&["config", "user.email", "name@example.com"]);
+
+        fixture
+    }
+
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+
+    /// runs git inside of the repository, panics on failure
+    pub fn git(&self, args: &[&str]) {
+        let output = Command::new("git")
+            .args(args)
+            .current_dir(self.path())
+            .output()
+            .unwrap();
+
+        assert!(
+            output.status.success(),
+            "git {:?}: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    /// creates `branch` off HEAD holding a commit of `file`, HEAD
+    /// stays where it was
+    pub fn branch(
+        &self,
+        branch: &str,
+        file: &str,
+        content: &str,
+        msg: &str,
+    ) {
+        self.git(&["checkout", "-q", "-b", branch]);
+        self.commit(file, content, msg);
+        self.git(&["checkout", "-q", "-"]);
+    }
+
+    pub fn write(&self, file: &str, content: &str) {
+        fs::write(self.path().join(file), content).unwrap();
+    }
+
+    /// writes `file` and commits it
+    pub fn commit(&self, file: &str, content: &str, msg: &str) {
+        self.write(file, content);
+        self.git(&["add", file]);
+        self.git(&["commit", "-q", "-m", msg]);
+    }
+}
+
+/// a running app rendering into a `TestBackend`
+pub struct UiTest {
+    app: App,
+    terminal: Terminal<TestBackend>,
+    rx_git: Receiver<AsyncNotification>,
+    /// config and cache folders of this test only
+    _app_dir: TempDir,
+}
+
+impl UiTest {
+    pub fn new(fixture: &Fixture) -> Self {
+        // keep the users config and cache folders untouched
+        let app_dir = TempDir::new().unwrap();
+        set_test_app_dir(Some(app_dir.path().to_path_buf()));
+
+        let (tx_git, rx_git) = unbounded();
+
+        let app = App::new(
+            RepoPath::Path(fixture.path().to_path_buf()),
+            &tx_git,
+            Input::headless(),
+            SharedTheme::default(),
+            SharedKeyConfig::default(),
+            None,
+        );
+
+        let mut res = Self {
+            app,
+            terminal: Terminal::new(TestBackend::new(120, 30))
+                .unwrap(),
+            rx_git,
+            _app_dir: app_dir,
+        };
+
+        res.settle();
+
+        res
+    }
+
+    /// feeds `script` into the app key by key, plain chars are typed
+    /// as is and special keys are written in angle brackets:
+    /// `<enter>`, `<esc>`, `<tab>`, `<backtab>`, `<space>`, `<up>`,
+    /// `<down>`, `<left>`, `<right>`, `<c-x>` (ctrl + x)
+    pub fn keys(&mut self, script: &str) -> &mut Self {
+        let mut chars = script.chars();
+
+        while let Some(c) = chars.next() {
+            let key = if c == '<' {
+                let name: String = chars
+                    .by_ref()
+                    .take_while(|c| *c != '>')
+                    .collect();
+                Self::special_key(&name)
+            } else if c.is_uppercase() {
+                KeyEvent::new(KeyCode::Char(c), KeyModifiers::SHIFT)
+            } else {
+                KeyEvent::new(KeyCode::Char(c), KeyModifiers::empty())
+            };
+
+            self.app
+                .event(InputEvent::Input(Event::Key(key)))
+                .unwrap();
+            self.settle();
+        }
+
+        self
+    }
+
+    /// rendered screen, one line per row with trailing spaces removed
+    pub fn screen(&mut self) -> String {
+        draw(&mut self.terminal, &self.app).unwrap();
+
+        let buffer = self.terminal.backend().buffer();
+        let width = buffer.area.width as usize;
+
+        buffer
+            .content()
+            .chunks(width)
+            .map(|row| {
+                row.iter()
+                    .map(|cell| cell.symbol.as_str())
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// panics with the screen content if `text` is not visible
+    pub fn assert_visible(&mut self, text: &str) -> &mut Self {
+        let screen = self.screen();
+        assert!(
+            screen.contains(text),
+            "`{}` not visible on screen:\n{}",
+            text,
+            screen
+        );
+        self
+    }
+
+    /// panics with the screen content if `text` is visible
+    pub fn assert_hidden(&mut self, text: &str) -> &mut Self {
+        let screen = self.screen();
+        assert!(
+            !screen.contains(text),
+            "`{}` visible on screen:\n{}",
+            text,
+            screen
+        );
+        self
+    }
+
+    /// updates the app (like a tick does) and waits for all async
+    /// jobs to finish, needed after changing the fixture repository
+    pub fn settle(&mut self) -> &mut Self {
+        self.app.update().unwrap();
+
+        let start = Instant::now();
+        loop {
+            while let Ok(ev) = self.rx_git.try_recv() {
+                if ev != AsyncNotification::FinishUnchanged {
+                    self.app.update_git(ev).unwrap();
+                }
+            }
+
+            if !self.app.any_work_pending() {
+                break;
+            }
+
+            assert!(
+                start.elapsed() < SETTLE_TIMEOUT,
+                "async jobs did not finish"
+            );
+
+            if let Ok(ev) =
+                self.rx_git.recv_timeout(Duration::from_millis(50))
+            {
+                if ev != AsyncNotification::FinishUnchanged {
+                    self.app.update_git(ev).unwrap();
+                }
+            }
+        }
+
+        self
+    }
+
+    fn special_key(name: &str) -> KeyEvent {
+        let code = match name {
+            "enter" => KeyCode::Enter,
+            "esc" => KeyCode::Esc,
+            "tab" => KeyCode::Tab,
+            "backtab" => {
+                return KeyEvent::new(
+                    KeyCode::BackTab,
+                    KeyModifiers::SHIFT,
+                )
+            }
+            "space" => KeyCode::Char(' '),
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            _ => {
+                let c = name
+                    .strip_prefix("c-")
+                    .and_then(|c| c.chars().next())
+                    .unwrap_or_else(|| {
+                        panic!("unknown key: <{}>", name)
+                    });
+                return KeyEvent::new(
+                    KeyCode::Char(c),
+                    KeyModifiers::CONTROL,
+                );
+            }
+        };
+
+        KeyEvent::new(code, KeyModifiers::empty())
+    }
+}
+
+impl Drop for UiTest {
+    fn drop(&mut self) {
+        set_test_app_dir(None);
+    }
+}
+
+mod tests {
+    use super::{Fixture, UiTest};
+
+    #[test]
+    fn test_stage_and_commit() {
+        let fixture = Fixture::new();
+        fixture.commit("a.txt", "a", "initial");
+        fixture.write("b.txt", "b");
+
+        let mut ui = UiTest::new(&fixture);
+        ui.assert_visible("b.txt");
+
+        ui.keys("<enter>c")
+            .keys("add b")
+            .keys("<enter>")
+            .assert_hidden("b.txt");
+
+        ui.keys("2")
+            .assert_visible("add b")
+            .assert_visible("initial");
+    }
+
+    #[test]
+    fn test_external_change() {
+        let fixture = Fixture::new();
+        fixture.commit("a.txt", "a", "initial");
+
+        let mut ui = UiTest::new(&fixture);
+        ui.assert_hidden("a.txt");
+
+        fixture.write("a.txt", "changed");
+        ui.settle().assert_visible("a.txt");
+    }
+
+    #[test]
+    fn test_resolve_merge_conflict() {
+        let fixture = Fixture::new();
+        fixture.commit("a.txt", "base\n", "initial");
+        fixture.branch(
+            "topic",
+            "a.txt",
+            "topic\n",
+            "change on topic",
+        );
+        fixture.commit("a.txt", "head\n", "change on head");
+
+        let mut ui = UiTest::new(&fixture);
+
+        // merge the second branch in the list
+        ui.keys("b<down>m").assert_visible("a.txt");
+
+        fixture.write("a.txt", "resolved\n");
+
+        // stage the resolution and commit the merge
+        ui.settle().keys("<enter>c").assert_visible("Merge branch");
+        ui.keys("<enter>").assert_hidden("a.txt");
+
+        ui.keys("2")
+            .assert_visible("Merge branch 'topic'")
+            .assert_visible("change on topic")
+            .assert_visible("change on head");
+    }
+
+    #[test]
+    fn test_resolve_rebase_conflict() {
+        let fixture = Fixture::new();
+        fixture.commit("a.txt", "base\n", "initial");
+        fixture.branch(
+            "topic",
+            "a.txt",
+            "topic\n",
+            "change on topic",
+        );
+        fixture.commit("a.txt", "head\n", "change on head");
+
+        let mut ui = UiTest::new(&fixture);
+
+        // rebase onto the second branch in the list, confirming the
+        // preview stops at the conflict and shows the rebase popup
+        ui.keys("b<down>B")
+            .assert_visible("change on head")
+            .keys("<enter>")
+            .assert_visible("Rebase (1/1)");
+
+        fixture.write("a.txt", "resolved\n");
+
+        // close the popup, stage the resolution and commit the step
+        // with its original message
+        ui.settle().keys("<esc><enter>c");
+        ui.keys("<enter>").assert_hidden("a.txt");
+
+        ui.keys("2")
+            .assert_visible("change on head")
+            .assert_visible("change on topic")
+            .assert_hidden("Merge");
+    }
+}
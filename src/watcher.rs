@@ -0,0 +1,284 @@
+use anyhow::Result;
+use asyncgit::sync::{IgnoreFilter, RepoPath};
+use crossbeam_channel::{bounded, Receiver, Sender, TrySendError};
+use notify::{
+    DebouncedEvent, RecommendedWatcher, RecursiveMode, Watcher,
+};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, RecvTimeoutError},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// changes within this duration are reported as one
+const DEBOUNCE: Duration = Duration::from_millis(500);
+/// the debouncer reports every path on its own, events following
+/// each other closer than this are batched into one notification
+const BATCH_QUIET: Duration = Duration::from_millis(100);
+/// a steady stream of changes (e.g. a script creating hundreds of
+/// refs) still notifies this often
+const BATCH_MAX: Duration = Duration::from_secs(2);
+
+/// watches the working directory (including the `.git` folder) and
+/// sends a notification whenever something changed on disk. ignored
+/// folders (like build output) are not watched at all and changes of
+/// ignored files are dropped
+pub struct RepoWatcher {
+    receiver: Receiver<()>,
+}
+
+impl RepoWatcher {
+    pub fn new(repo: &RepoPath) -> Result<Self> {
+        let (tx, rx) = mpsc::channel();
+        let mut watcher: RecommendedWatcher =
+            Watcher::new(tx, DEBOUNCE)?;
+
+        let filter = IgnoreFilter::new(repo)?;
+        for dir in Self::dirs_to_watch(&filter, filter.work_dir()) {
+            watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+        }
+        watcher.watch(filter.git_dir(), RecursiveMode::Recursive)?;
+
+        // a notification not taken yet covers the new ones as well
+        let (sender, receiver) = bounded(1);
+
+        thread::spawn(move || {
+            // the watcher stops once dropped
+            let mut forward = Forward { watcher, filter };
+            forward.run(&rx, &sender);
+        });
+
+        Ok(Self { receiver })
+    }
+
+    /// dropping the watcher stops it, so this is not handed out
+    pub const fn receiver(&self) -> &Receiver<()> {
+        &self.receiver
+    }
+
+    /// `dir` and the folders below it that are neither ignored nor
+    /// a `.git` folder (ours is watched on its own, others belong to
+    /// nested repositories)
+    fn dirs_to_watch(
+        filter: &IgnoreFilter,
+        dir: &Path,
+    ) -> Vec<PathBuf> {
+        let mut dirs = Vec::new();
+        let mut pending = vec![dir.to_path_buf()];
+
+        while let Some(dir) = pending.pop() {
+            if let Ok(entries) = fs::read_dir(&dir) {
+                for entry in entries.flatten() {
+                    let path = entry.path();
+
+                    // symlinks are not followed
+                    if entry.file_type().map_or(false, |t| t.is_dir())
+                        && entry.file_name() != ".git"
+                        && !filter.is_ignored(&path, true)
+                    {
+                        pending.push(path);
+                    }
+                }
+            }
+
+            dirs.push(dir);
+        }
+
+        dirs
+    }
+
+    /// the path a relevant event is about, `None` for a rescan
+    fn event_path(ev: &DebouncedEvent) -> Option<&Path> {
+        match ev {
+            DebouncedEvent::Create(path)
+            | DebouncedEvent::Write(path)
+            | DebouncedEvent::Chmod(path)
+            | DebouncedEvent::Remove(path)
+            | DebouncedEvent::Rename(_, path) => Some(path),
+            _ => None,
+        }
+    }
+
+    fn is_relevant(ev: &DebouncedEvent) -> bool {
+        let path = match ev {
+            // the debounced event follows
+            DebouncedEvent::NoticeWrite(_)
+            | DebouncedEvent::NoticeRemove(_) => return false,
+            DebouncedEvent::Error(e, path) => {
+                log::error!("watcher error: {} ({:?})", e, path);
+                return false;
+            }
+            DebouncedEvent::Create(path)
+            | DebouncedEvent::Write(path)
+            | DebouncedEvent::Chmod(path)
+            | DebouncedEvent::Remove(path)
+            | DebouncedEvent::Rename(_, path) => path,
+            DebouncedEvent::Rescan => return true,
+        };
+
+        // lock files come and go with every git command, the
+        // change itself is reported once they are renamed into place
+        !path.extension().map_or(false, |ext| ext == "lock")
+            && !path.ends_with("gitui_changed")
+    }
+}
+
+/// the watcher thread: filters the events and extends the watches
+/// to folders created
+struct Forward {
+    watcher: RecommendedWatcher,
+    filter: IgnoreFilter,
+}
+
+impl Forward {
+    fn run(
+        &mut self,
+        rx: &mpsc::Receiver<DebouncedEvent>,
+        sender: &Sender<()>,
+    ) {
+        while let Ok(ev) = rx.recv() {
+            if !self.handle(&ev) {
+                continue;
+            }
+
+            if !self.batch(rx) {
+                break;
+            }
+
+            match sender.try_send(()) {
+                Ok(_) | Err(TrySendError::Full(_)) => (),
+                // receiving app is gone
+                Err(TrySendError::Disconnected(_)) => break,
+            }
+        }
+    }
+
+    /// swallows the events following the first one of a batch,
+    /// `false` if the watcher is gone
+    fn batch(&mut self, rx: &mpsc::Receiver<DebouncedEvent>) -> bool {
+        let deadline = Instant::now() + BATCH_MAX;
+
+        loop {
+            let left =
+                deadline.saturating_duration_since(Instant::now());
+            if left == Duration::from_secs(0) {
+                return true;
+            }
+
+            match rx.recv_timeout(BATCH_QUIET.min(left)) {
+                Ok(ev) => {
+                    self.handle(&ev);
+                }
+                Err(RecvTimeoutError::Timeout) => return true,
+                Err(RecvTimeoutError::Disconnected) => return false,
+            }
+        }
+    }
+
+    /// `true` if the app needs to know about `ev`, starts watching
+    /// folders it created
+    fn handle(&mut self, ev: &DebouncedEvent) -> bool {
+        if !RepoWatcher::is_relevant(ev) {
+            return false;
+        }
+
+        let path = match RepoWatcher::event_path(ev) {
+            Some(path) => path,
+            None => return true,
+        };
+
+        if path.starts_with(self.filter.git_dir()) {
+            return true;
+        }
+
+        let is_folder = path.is_dir();
+        if self.filter.is_ignored(path, is_folder) {
+            return false;
+        }
+
+        if is_folder
+            && matches!(
+                ev,
+                DebouncedEvent::Create(_)
+                    | DebouncedEvent::Rename(..)
+            )
+        {
+            for dir in RepoWatcher::dirs_to_watch(&self.filter, path)
+            {
+                if let Err(e) = self
+                    .watcher
+                    .watch(&dir, RecursiveMode::NonRecursive)
+                {
+                    log::error!("watcher error: {} ({:?})", e, dir);
+                }
+            }
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RepoWatcher;
+    use asyncgit::sync::IgnoreFilter;
+    use notify::DebouncedEvent;
+    use std::{fs, path::PathBuf, process::Command};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_dirs_to_watch() {
+        let td = TempDir::new().unwrap();
+        let status = Command::new("git")
+            .args(&["init", "--quiet"])
+            .arg(td.path())
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        fs::create_dir_all(td.path().join("src/nested")).unwrap();
+        fs::create_dir_all(td.path().join("target/debug")).unwrap();
+        fs::write(td.path().join(".gitignore"), "target/\n").unwrap();
+
+        let filter =
+            IgnoreFilter::new(&td.path().to_str().unwrap().into())
+                .unwrap();
+        let root = filter.work_dir();
+
+        let mut dirs = RepoWatcher::dirs_to_watch(&filter, root);
+        dirs.sort();
+
+        assert_eq!(
+            dirs,
+            vec![
+                root.to_path_buf(),
+                root.join("src"),
+                root.join("src/nested")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_is_relevant() {
+        let path = |p: &str| PathBuf::from(p);
+
+        assert!(RepoWatcher::is_relevant(&DebouncedEvent::Write(
+            path("repo/src/main.rs")
+        )));
+        assert!(RepoWatcher::is_relevant(&DebouncedEvent::Rename(
+            path("repo/.git/index.lock"),
+            path("repo/.git/index")
+        )));
+        assert!(!RepoWatcher::is_relevant(&DebouncedEvent::Create(
+            path("repo/.git/refs/heads/master.lock")
+        )));
+        assert!(!RepoWatcher::is_relevant(&DebouncedEvent::Write(
+            path("repo/.git/gitui_changed")
+        )));
+        assert!(!RepoWatcher::is_relevant(
+            &DebouncedEvent::NoticeWrite(path("repo/a.txt"))
+        ));
+    }
+}